@@ -0,0 +1,661 @@
+# MOSS English strings. Copy this file to the `lang` folder in the MOSS data dir, named after
+# your language code (eg: ru.ftl), and translate the values to add a language.
+language-name = English
+
+close = Close
+cancel = Cancel
+unknown = Unknown
+
+nav-settings = Settings
+nav-refresh = Refresh
+nav-install = Install Mod(s)
+nav-mod-browser = Open Mod Browser
+nav-mod-repo = Open Unofficial Mod Repo
+nav-installed = Installed: { $count }
+nav-active = Active: { $count }
+nav-mod-index = Mod Index
+nav-mods-subforum = Mods Subforum
+nav-modding-subforum = Modding Subforum
+nav-close-browser = Close Mod Browser
+log-title = Log
+log-installing = Installing { $mods }
+log-resuming-install = Resuming install for { $name }
+log-auto-update-started = Begin auto-update of { $name }
+log-token-not-saved = Could not save the token for { $domain } to the system keychain - it will only be used until MOSS is closed
+log-unrecognised-link = Unrecognised link { $link }
+
+tab-launch = Launch
+tab-tools = Tools & Filters
+
+tools-search = Search
+tools-query = Query
+tools-save-query = Save Query
+tools-toggles = Toggles
+tools-enable-all = Enable All
+tools-disable-all = Disable All
+tools-enable-shown = Enable Shown
+tools-disable-shown = Disable Shown
+tools-enabled-count = Enabled { $count } mods
+tools-disabled-count = Disabled { $count } mods
+bulk-enable = Enable
+bulk-disable = Disable
+bulk-enable-shown = Enable Shown
+bulk-enable-all = Enable All
+bulk-disable-shown = Disable Shown
+bulk-disable-all = Disable All
+bulk-enable-prompt = This will enable { $count } mods. Are you sure?
+bulk-disable-prompt = This will disable { $count } mods. Are you sure?
+bulk-understand = I understand this will change my current mod setup
+bulk-type-word = Type { $word } to continue:
+tools-scan-stage = Loading mods
+tools-update-check-progress = Checking for updates: { $checked } / { $total }
+tools-problem-mods = Problem Mods
+tools-problem-mods-hint = These mods could not be loaded. Fix or remove their mod_info.json and refresh.
+tools-open-file = Open File
+tools-archived = Archived Mods
+tools-archived-none = Archive a mod from its right click menu to remove it from the mods folder without losing it.
+tools-restore = Restore
+tools-memory = Memory
+tools-estimate-ram = Estimate RAM for Enabled Mods
+tools-ram-estimate = Recommended -Xmx: { $recommended } ({ $assets } of graphics and sounds, { $jars } of code, { $factions } factions)
+tools-ram-heavy = Includes extra for { $mods }, which need more than their size suggests.
+tools-ram-current = Current -Xmx: { $current }
+tools-ram-unknown = Could not read -Xmx from vmparams.
+tools-ram-too-low = -Xmx is well below the estimate. Expect crashes or long pauses while playing.
+tools-apply-ram = Apply Recommendation
+tools-bisect = Find a Crashing Mod
+tools-bisect-hint = Leaves half of the enabled mods enabled at a time, narrowing down which one crashes the game.
+tools-bisect-start = Start
+tools-bisect-step = Step { $step }: { $testing } of { $suspects } suspects are enabled. Launch the game, then say whether it crashed.
+tools-bisect-launch = Launch
+tools-bisect-crashed = It Crashed
+tools-bisect-worked = It Worked
+tools-bisect-found = The crash is caused by { $mods }.
+tools-bisect-finish = Finish, Leaving It Disabled
+tools-bisect-stop = Stop and Restore Mods
+bisect-interrupted-title = Unfinished search for a crashing mod
+bisect-interrupted = MOSS was closed while finding a crashing mod, so some of your mods may still be disabled. Restore the mods that were enabled before the search started?
+bisect-restore = Restore
+bisect-keep = Keep Current Mods
+safe-mode-title = Starsector Crashed While Starting
+safe-mode-log = The log shows:\n{ $error }
+safe-mode-suspects = Safe mode disables the most recently enabled or installed mods and launches again: { $mods }
+safe-mode-hint = If the game starts, re-enable them a few at a time to find the broken one. If it crashes again, safe mode will narrow it down further. This can be undone with Ctrl+Z.
+safe-mode-relaunch = Disable and Relaunch
+safe-mode-disabled = Safe mode disabled { $count } mod(s)
+enabled-mods-unreadable-title = Unreadable enabled_mods.json
+enabled-mods-unreadable = enabled_mods.json could not be read, so no mods were loaded.
+enabled-mods-restore-prompt = Do you want to restore the backup from { $written }?
+enabled-mods-restore = Restore
+enabled-mods-no-backup = enabled_mods.json could not be read and there is no backup to restore
+enabled-mods-backup-unreadable = Could not read { $path }
+enabled-mods-restore-failed = Failed to restore enabled_mods.json { $err }
+tools-share = Share Mod List
+tools-share-hint = Copies the enabled mods, with their versions and forum links, ready to paste into a support thread.
+tools-share-bbcode = Copy for Forum
+tools-share-markdown = Copy as Markdown
+tools-share-copied = Copied the mod list to the clipboard
+tools-install-history = Install History
+tools-install-history-hint = Every mod MOSS has installed, updated or deleted, with where it came from.
+tools-install-history-view = View History
+install-log-installed = Installed { $name } { $after }
+install-log-updated = Updated { $name } from { $before } to { $after }
+install-log-rolled-back = Rolled back { $name } from { $before } to { $after }
+install-log-deleted = Deleted { $name } { $before }
+install-log-archived = Archived { $name } { $before }
+install-log-restored = Restored { $name } { $after } from the trash
+install-log-source = { $time } - from { $source }
+install-log-download-again = Download Again
+install-log-install-again = Install Again
+install-log-title = Install History
+install-log-empty = MOSS hasn't installed, updated or deleted any mods yet.
+install-log-hint = Downloading a previous version again saves it to the download folder, so it can be checked before installing over the current one.
+install-log-open = Open history file
+roll-back-title = Roll Back { $name }
+roll-back-kept = { $name } { $version } is installed. MOSS kept these versions when updates replaced them:
+roll-back-hint = Rolling back keeps the installed version too, so it can be undone.
+roll-back-to = Roll Back to { $version }
+roll-back-done = Rolled back { $name } to { $version }
+install-stage-checking = Checking the archive
+install-stage-extracting = Extracting
+install-menu-archives = From Archive(s)
+install-menu-archives-filter = Archives
+install-menu-folder = From Folder
+install-menu-download-only = Only Download From the Mod Browser
+install-queue-dismiss = Remove from the list
+install-report-view = View
+install-found-open-path = Open path
+install-found-install = Install
+install-found-title-folder = Found multiple mods in folder
+install-found-title-archive = Found multiple mods in archive
+install-found-prompt = Select which of the following mods to install:
+install-found-mod = { $name } ({ $version }) - ID: { $id }
+install-found-path = At path: { $path }
+install-found-install-selected = Install Selected
+install-found-install-all = Install All
+install-found-ignore-all = Ignore All
+install-queue-title = Installs
+install-report-title = Install Report
+install-report-automatic-title = Automatic Updates
+install-report-summary = Installed: { $installed }, Skipped: { $skipped }, Failed: { $failed }
+install-report-installed = { $source } - installed { $name }
+install-report-skipped = { $source } - skipped: { $reason }
+install-report-failed = { $source } - failed: { $reason }
+install-dir-warning-title = Unrecognised install directory
+install-dir-warning = { $path } does not look like a Starsector installation.
+install-dir-warning-hint = Installing mods here may extract them into an unrelated folder. Installs are disabled until you confirm this is the correct directory.
+install-dir-warning-confirm = Use Anyway
+install-dir-not-set = Cannot install mods until a valid install directory has been set
+overwrite-title = Overwrite?
+overwrite-id-exists = A mod with ID { $id } already exists.
+overwrite-folder-exists = Found a folder at the path { $path } when trying to install { $id }.
+overwrite-replace-mod = Would you like to replace the existing mod?
+overwrite-replace-folder = Would you like to replace the existing folder?
+overwrite-replace = Overwrite
+overwrite-merge = Merge
+overwrite-merge-tooltip = Replace the mod, but keep config files you may have edited, such as data/config/settings.json
+overwrite-all = Overwrite All
+overwrite-cancel-all = Cancel All
+duplicate-ignore = Ignore
+duplicate-keep-delete = Keep, delete the rest
+duplicate-keep-archive = Keep, archive the rest
+duplicate-keep-archive-tooltip = The other copies are zipped into { $dir } before they're deleted
+duplicate-title = Duplicate detected
+duplicate-detected = Detected { $count } installs of mod with ID { $id }, using { $size } in total.
+duplicate-ignore-all = Ignore All
+duplicate-version = Version: { $version }
+duplicate-path = Path: { $path }
+duplicate-size = Size: { $size }
+duplicate-modified = Last modified: { $time }
+duplicate-modified-unknown = Failed to retrieve last modified
+duplicate-created = Created at: { $time }
+duplicate-created-unknown = Failed to retrieve creation time
+delete-title = Delete { $name }
+delete-prompt = Do you want to delete { $name }?
+delete-undo-hint = This can be undone with Ctrl+Z until MOSS is closed.
+delete-confirm = Confirm
+history-undid = Undid { $operation }
+history-redid = Redid { $operation }
+history-set-enabled = enabling or disabling { $count } mod(s)
+history-delete = deleting { $name }
+delete-permanently-title = Delete { $name } Permanently?
+delete-permanently-body = { $name } could not be moved to the trash, so deleting it can't be undone.
+delete-permanently-error = Error: { $error }
+delete-permanently-confirm = Delete Permanently
+download-retry = Retry
+download-dismiss = Remove from the list
+downloads-title = Downloads
+downloads-started-at = Started at: { $time }
+downloads-failed = { $name } failed
+download-only-no-dir = Choose a folder for downloads in the settings first
+corrupt-download-title = Corrupted download
+corrupt-download-body = { $name } is damaged or incomplete, so nothing was installed from it.
+corrupt-download-error = Error: { $error }
+corrupt-download-retry = Retry download
+loose-file-title = Loose File Detected
+loose-file-name = File: { $name }
+loose-file-hint = This isn't an archive, so it can't be installed as a mod of its own. It can be placed into an installed mod's folder instead, replacing the file of the same name if the mod has one.
+loose-file-place = Place into mod...
+loose-file-no-mods = No mods installed
+loose-file-just-download = Just Download
+loose-file-confirm-title = Place File?
+loose-file-confirm-destination = { $name } will be saved to { $path }
+loose-file-confirm-backup = The file already there will be kept as { $backup }
+loose-file-confirm = Place
+loose-file-placed = Placed { $name } at { $path }
+loose-file-placed-backed-up = Placed { $name } at { $path }, the file it replaced was kept as { $backup }
+download-detected-title = Download Detected
+download-detected-file = File: { $name }
+download-detected-size = Size: { $size }
+download-detected-size-unknown = unknown
+download-detected-destination = Installs into: { $path }
+download-detected-no-install = Choose a Starsector install in the settings to install mods.
+download-detected-install = Install
+download-detected-just-download = Just Download
+mod-link-title = Mod Link Copied
+mod-link-copied = You copied a link to { $kind }:
+mod-link-forum = a Fractal Softworks forum thread
+mod-link-nexus = a Nexus Mods page
+mod-link-github = a GitHub release
+mod-link-download = a mod download
+mod-link-open = Open in Mod Browser
+mod-link-install = Download and Install
+mod-link-just-download = Just Download
+mod-link-ignore = Ignore
+saved-query-remove = Remove saved query
+toast-dismiss = Dismiss
+toast-installed = Installed
+toast-error = Error
+popup-error = Error
+popup-critical = Changes Not Saved
+popup-copy-error = Copy error
+popup-progress-hide = Hide
+popup-progress-too-late = Too far along to cancel, it will finish in the background
+popup-select-install-title = Select Starsector Install
+popup-select-install-none = No Starsector install was found in the usual places.
+popup-select-install-found = Found these Starsector installs, pick the one to manage mods for:
+popup-select-install-browse = Browse...
+popup-select-install-later = Later
+tools-developer = Developer
+tools-edit-mod-info = Edit Selected Mod's mod_info.json...
+tools-create-mod = Create Mod Skeleton...
+tools-filters = Filters
+
+mod-list-empty = No mods
+filters-status = Status
+filters-version-checker = Version Checker
+filters-auto-update = Auto Update Support
+filters-smart = Smart Filters
+filters-saved-queries = Saved Queries
+filters-presets = Presets
+filters-presets-none = No saved presets
+filters-preset-name = Preset name
+filters-preset-save = Save Preset
+filters-preset-delete = Delete
+
+status-bar-total = All { $count } mods
+status-bar-enabled = { $enabled }/{ $total } enabled
+status-bar-updates = { $count } updates
+status-bar-errors = { $count } errors
+status-bar-size = { $size } on disk
+filter-enabled = Enabled
+filter-disabled = Disabled
+filter-unimplemented = Unimplemented
+filter-error = Error
+filter-discrepancy = Discrepancy
+filter-up-to-date = Up To Date
+filter-patch = Patch
+filter-minor = Minor
+filter-major = Major
+filter-unchecked = Not checked yet
+filter-auto-update-available = Auto Update Available
+filter-auto-update-unsupported = Auto Update Unsupported
+filter-installed-week = Installed in the last 7 days
+filter-installed-month = Installed in the last 30 days
+filter-updated-week = Updated in the last 7 days
+filter-updated-month = Updated in the last 30 days
+filter-unmatched = No remote version found
+
+launch-starsector-version = Starsector Version:
+launch-unknown = Unknown
+launch-start = Launch Starsector
+launch-check = Check Enabled Mods
+launch-check-title = Problems with enabled mods
+launch-check-none = No problems found.
+launch-check-launch-anyway = Launch Anyway
+launch-check-cancel = Cancel
+launch-check-missing-dependency = { $name } needs { $dependency }, which isn't installed
+launch-check-disabled-dependency = { $name } needs { $dependency }, which isn't enabled
+launch-check-game-version = { $name } was made for Starsector { $version }
+launch-check-incompatible = { $a } doesn't work with { $b }
+launch-check-incompatible-reason = { $a } doesn't work with { $b }: { $reason }
+launch-check-ram = The enabled mods may need around { $estimate } MB of memory, but the game is only allowed { $allowed } MB. Raise the maximum RAM in Settings
+library-install = Install { $library }
+library-missing-title = { $library } isn't installed
+library-missing = Needed by { $mods }. Click to install it.
+library-install-failed-title = Couldn't download the library
+library-install-failed = Opening { $library }'s forum thread so it can be downloaded from there.
+compat-abandoned = Abandoned
+compat-abandoned-successor = Abandoned — use { $successor } instead
+compat-conflict = Conflicts with { $name }
+compat-conflict-reason = Conflicts with { $name }: { $reason }
+game-version-title = Game version
+game-version-from-log = Read from starsector.log, so it may be out of date if the game was updated without being launched. Click to set it in Settings
+game-version-not-found = Couldn't find the Starsector version in your install, so mod compatibility can't be checked. Click to set it in Settings
+
+description-discussion = Discussion:
+description-categories = Categories:
+description-repo-updated = Repo updated:
+description-show-mod-info = Show mod_info description
+description-show-forum-post = Show forum post
+description-forum-post-failed = Could not load the forum post: { $err }
+description-forum-post-missing = Could not find the opening post
+description-tab-description = Description
+description-tab-config-files = Config files
+description-tab-documents = Documents
+description-update-checking = Checking...
+description-update-error = Error: { $err }
+description-update-no-version-file = No version file
+description-update-not-checked = Not checked yet
+description-update-check-now = Check now
+description-name = Name:
+description-id = ID:
+description-authors = Author(s):
+description-enabled = Enabled:
+description-version = Version:
+description-game-version = Game version:
+description-edit-versions = Edit versions...
+description-update-status = Update status:
+description-installed-at = Installed at:
+description-fractal-link = Fractal link:
+description-nexus-link = Nexus link:
+description-open-in-file-manager = Open in file manager...
+description-description = Description:
+description-edit-versions-title = Edit { $name }
+description-edit-versions-hint = Changes are written straight to this mod's mod_info.json.
+description-edit-versions-save = Save
+description-no-mod-selected = No mod selected.
+config-files-finding = Looking for config files...
+config-files-none = This mod has no config files.
+config-files-open = Open
+config-files-edit = Edit
+config-files-edit-title = Edit { $name }
+config-files-save = Save
+config-files-would-not-load = Not saved, the file would not load: { $err }
+config-files-write-failed = Could not write the file: { $err }
+documents-none = This mod has no README, changelog or license.
+documents-read = Read
+documents-open = Open
+documents-read-failed = Could not read { $name }: { $err }
+repo-install = Install
+repo-installed = Installed
+repo-update-available = Installed, update available
+repo-discord-title = Open in Discord?
+repo-discord-prompt = Attempt to open this link in the Discord app?
+repo-discord-open = Open
+repo-title = Mod Repo
+repo-filters = Filters
+repo-categories = Categories
+repo-game-version = Game Version
+repo-sort-by = Sort by
+repo-search = Search:
+repo-name = Name:
+repo-download-only = Download Only
+repo-summary = Summary:
+repo-description = Description:
+repo-expand = Click to expand...
+repo-authors = Authors:
+repo-links = Links:
+repo-updated-at = Updated at:
+repo-created-at = Created at:
+repo-link-direct-download = Raw Url
+repo-link-download-page = Other
+repo-filter-any-version = Any
+repo-filter-search = Search
+repo-sort-name = Name
+repo-sort-created = Created At
+repo-sort-updated = Last Updated
+repo-sort-authors = Author(s)
+repo-offline = Couldn't reach the mod repo, showing the copy from { $date }
+
+mod-info-title = Edit { $name }
+mod-info-hint = Changes are written to this mod's mod_info.json and version file, keeping their comments. The version is written to both.
+mod-info-id = ID:
+mod-info-name = Name:
+mod-info-author = Author:
+mod-info-version = Version:
+mod-info-game-version = Game version:
+mod-info-description = Description:
+mod-info-master-version-file-label = Master version file:
+mod-info-thread-id-label = Forum thread ID:
+mod-info-direct-download-url-label = Direct download URL:
+mod-info-changelog-url-label = Changelog URL:
+mod-info-no-version-file = This mod has no version file.
+mod-info-save = Save
+mod-info-id-invalid = ID must not be empty or contain spaces.
+mod-info-name-empty = Name must not be empty.
+mod-info-version-empty = Version must not be empty.
+mod-info-thread-id-invalid = Forum thread ID must be a number.
+mod-info-master-version-file = Master version file
+mod-info-direct-download-url = Direct download URL
+mod-info-changelog-url = Changelog URL
+mod-info-url-invalid = { $field } must be a http(s) URL.
+mod-info-read-failed = Could not read mod_info.json: { $err }
+mod-info-edit-failed = Could not find where to edit mod_info.json.
+mod-info-would-not-load = mod_info.json would not load: { $err }
+mod-info-version-file-read-failed = Could not read { $path }: { $err }
+mod-info-version-file-edit-failed = Could not find where to edit the version file.
+mod-info-version-file-would-not-load = The version file would not load: { $err }
+mod-info-write-failed = Could not write { $path }: { $err }
+
+skeleton-title = Create Mod Skeleton
+skeleton-hint = A new, empty mod is created in the mods folder, named after its ID.
+skeleton-plugin = Add a mod plugin
+skeleton-version-file = Add a version file
+skeleton-create = Create
+skeleton-id-invalid = ID must only be letters, numbers, - and _.
+skeleton-name-empty = Name must not be empty.
+skeleton-version-invalid = Version must look like 1.2.3 to be used in a version file.
+skeleton-folder-exists = { $path } already exists.
+
+menu-never-notify = Never Notify About Updates
+menu-ignore-update = Ignore Update to { $version }
+
+log-save-metadata-failed = Failed to save metadata for { $path }: { $err }
+
+heading-id = ID
+heading-name = Name
+heading-author = Author(s)
+heading-game-version = Game Version
+heading-enabled = Enabled
+heading-version = Version
+heading-score = score
+heading-auto-update = Auto-Update Supported
+heading-install-date = Install Date
+heading-last-updated = Last Updated
+heading-size = Size
+heading-author-activity = Author Last Active
+
+update-title = Auto-update?
+update-prompt = Would you like to automatically update { $name }?
+update-installed-version = Installed version: { $version }
+update-new-version = New version: { $version }
+update-version-error = Error: failed to retrieve version, this shouldn't be possible.
+update-git-warning = NOTE: A .git directory has been detected in the target directory. Are you sure this isn't being used for development?
+update-save-breaking = This update looks like it will break existing saves
+update-save-breaking-line = From the changelog: "{ $line }"
+update-warning = WARNING:
+update-warning-saves = Save compatibility is not guaranteed when updating a mod. Your save may no longer load if you apply this update.
+update-warning-reports = Bug reports about saves broken by using this feature will be ignored.
+update-warning-warned = YOU HAVE BEEN WARNED
+update-confirm = Update
+update-ignore = Ignore this version
+update-download-failed-title = Download failed
+update-download-failed = Could not download the update for { $name }.
+update-download-failed-error = Error: { $error }
+update-download-failed-mirror = The mod repo lists another source for this mod, which may still work: { $url }
+update-download-from-mirror = Download from mirror
+update-open-download-page = Open download page
+update-check-title = Updates available
+version-check-failed-title = Version check failed
+version-check-failed = The version file for { $name } couldn't be fetched or read:
+version-check-failed-unknown = No error was recorded.
+version-check-retry = Retry
+update-check-summary = { $count } mod(s) have updates.
+update-status-major = Major update available: { $version }
+update-status-minor = Minor update available: { $version }
+update-status-patch = Patch available: { $version }
+update-status-up-to-date = Up to date
+update-status-error = Error
+update-status-discrepancy = Discrepancy
+update-tooltip-error = Error\nThere was an error retrieving or parsing this mod's version information.\nClick to see what went wrong.
+update-tooltip-discrepancy = Discrepancy\nThe installed version of this mod is higher than the version available from the server.\nThis usually means the mod author has forgotten to update their remote version file and is not a cause for alarm.
+update-tooltip-auto-update = { $status }\nClick to update.
+update-tooltip-download-page = { $status }\nClick to open the download page.
+update-tooltip-ignored = { $tooltip }\nUpdate notifications for this mod are ignored.
+update-available = Update available!
+update-none-available = No update available
+update-unsupported = Unsupported
+auto-update-title = Updating Mods
+auto-update-stage = Updating { $name }
+auto-update-skipped = Skipping automatic updates, as the last ones are still being installed
+auto-update-started = Automatically updating { $mods }
+self-update-new-version = New version: { $version }
+self-update-new-beta = New version: { $version } (beta)
+self-update-release-notes = Release notes:
+self-update-title = Update Mod Manager?
+self-update-available = A new version of Starsector Mod Manager is available.
+self-update-current-version = Current version: { $version }
+self-update-prompt = Would you like to update now?
+self-update-prompt-browser = Would you like to open the update in your browser?
+self-update-confirm = Update
+self-update-restart-title = Restart?
+self-update-complete = Update complete.
+self-update-restart-prompt = Would you like to restart?
+self-update-restart = Restart
+self-update-error-title = Error
+self-update-failed = Failed to update Mod Manager.
+self-update-failed-hint = It is recommended that you restart and check that the Manager has not been corrupted.
+self-update-open-releases = Open releases page
+self-update-status-failed = Failed to retrieve Mod Manager update status.
+self-update-status-unknown = There may or may not be an update available.
+
+settings-title = Settings
+settings-language = Language:
+settings-language-tooltip = Takes effect after restarting MOSS.\nMore languages can be added by placing language packs in { $dir }
+settings-theme = Theme:
+settings-ui-scale = UI scale:
+settings-ui-scale-tooltip = Makes text and controls larger or smaller, on top of the scaling set for your monitor.
+settings-game-version = Game version:
+settings-game-version-tooltip = Optional. The Starsector version mods are checked against, eg: 0.97a-RC11. Only needed if the version can't be found in your install, or is found wrong.
+settings-game-version-placeholder = Detected automatically
+settings-compatibility-db = Compatibility list URL:
+settings-compatibility-db-tooltip = A community-maintained list of mods that conflict with each other or have been abandoned, flagged with a warning in the mod list. Leave empty to turn it off. Takes effect on the next refresh.
+settings-compatibility-db-placeholder = Off
+settings-accent = Accent colour:
+settings-accent-tooltip = Optional. A hex colour such as ff9800, used for highlights instead of the theme's own.
+settings-update-check-interval = Hours between update checks:
+settings-update-channel = MOSS updates:
+settings-update-channel-stable = Stable
+settings-update-channel-beta = Beta
+settings-proxy = Proxy:
+settings-proxy-tooltip = Optional. An HTTP, HTTPS or SOCKS5 proxy used for every connection MOSS makes, eg: http://proxy.example.com:8080
+settings-ca-certificate = Extra CA certificate:
+settings-ca-certificate-tooltip = Optional. Path to a PEM certificate to trust, for networks that inspect HTTPS traffic.
+settings-connect-timeout = Connection timeout (seconds, 0 for none):
+settings-request-timeout = Request timeout (seconds, 0 for none):
+settings-mod-library = Keep mods in the mod library
+settings-mod-library-tooltip = New mods are installed into { $dir } and linked into the mods folder while they're enabled, so switching mods on and off never copies them.\nThe library is shared by every Starsector install, so a mod used by several installs is only stored once.
+settings-mod-library-move = Move installed mods into the library
+settings-mod-library-moved = Moved { $count } mods into the mod library
+settings-update-check-interval-tooltip = Mods are always checked for updates on startup. While MOSS is open they are checked again this often, 0 turns this off.
+settings-update-channel-tooltip = Beta releases get new features first, but may have bugs that haven't been found yet. Switching back to stable takes effect from the next stable release.
+graphicslib-open = Open GraphicsLib Presets
+graphicslib-low = Low
+graphicslib-low-description = Lights only
+graphicslib-medium = Medium
+graphicslib-medium-description = No texture maps
+graphicslib-high = High
+graphicslib-high-description = Everything
+graphicslib-current = (CURRENT)
+graphicslib-apply = Apply
+graphicslib-shaders = Shaders (turns off everything below)
+graphicslib-lights = Dynamic lights
+graphicslib-bloom = Bloom
+graphicslib-distortion = Distortion effects
+graphicslib-shield-ripples = Shield ripples
+graphicslib-normal-maps = Normal maps
+graphicslib-material-maps = Material maps
+graphicslib-surface-maps = Surface maps
+graphicslib-64-bit-buffer = 64-bit colour buffer
+graphicslib-restore = Restore Original Options
+graphicslib-restore-failed = Failed to restore GraphicsLib's options: { $err }
+graphicslib-save-failed = Failed to save GraphicsLib's options: { $err }
+graphicslib-hint = Changes are written straight to GRAPHICS_OPTIONS.ini, and take effect the next time the game starts.
+graphicslib-not-found = GraphicsLib's GRAPHICS_OPTIONS.ini was not found in the mods folder.
+
+jre-already-installed = JRE { $jre } already installed!
+jre-upgrade-complete = JRE upgrade complete!
+jre-upgrade-failed = ERROR: Failed to upgrade JRE.
+jre-upgrade-rolled-back = ERROR: Failed to upgrade JRE. The change was rolled back, so the game will still run on the JRE it had before.
+jre-manage = Let MOSS Manage It
+jre-install = Install
+jre-revert-stock = Revert to Vanilla/Stock JRE 7
+jre-swap-failed = Failed to install JRE { $jre }
+jre-revert-failed = Failed to revert to JRE 7
+jre-swap-copy-error = Copy error
+jre-swap-retry = Retry
+jre-phase-downloading-of = Downloading... { $received } of { $total }
+jre-phase-downloading = Downloading... { $received }
+jre-phase-extracting = Extracting...
+jre-phase-installing = Installing...
+jre-describe-java = Java { $version }
+jre-describe-vendor = { $jre } ({ $vendor })
+jre-describe-original = { $jre }, as shipped with the game
+jre-describe-managed = { $jre }, installed by MOSS as { $flavour }
+jre-describe-by-hand = { $jre }, installed by hand
+jre-describe-mikohime = { $jre }, with the Mikohime kit
+
+game-backup-hint = vmparams and GraphicsLib's options are backed up before MOSS first changes them each session, and vmparams before every JRE switch.
+game-backup-open = Game File Backups...
+game-backup-title = Game File Backups
+game-backup-none = MOSS hasn't backed up any game files yet.
+game-backup-restore-hint = Restoring a backup backs up the files it replaces first, so it can be undone.
+game-backup-restore = Restore
+game-backup-restored = Restored the game files backed up { $taken }
+game-backup-restore-failed = Failed to restore game files: { $err }
+game-backup-open-folder = Open backup folder
+game-backup-before-vmparams = Before editing vmparams
+game-backup-before-graphicslib = Before editing GraphicsLib's options
+game-backup-before-jre = Before installing JRE { $jre }
+game-backup-before-jre-7 = Before reverting to JRE 7
+game-backup-before-mikohime = Before installing Mikohime { $version }
+game-backup-before-restoring = Before restoring "{ $reason }"
+settings-view-logs = View logs
+settings-report-problem = Report a problem
+settings-add = Add
+settings-report-problem-hint = Bundles logs, settings and your mod list into a zip to attach to a GitHub issue. Your API key and home folder are left out.
+settings-edit-columns = Edit columns
+settings-nexus-api-key = Nexus Mods API Key:
+settings-nexus-api-key-placeholder = None
+settings-git-warn = Warn when overwriting '.git' folders
+settings-hide-webview-on-conflict = Minimize browser when installation encounters conflict
+settings-forum-links-in-webview = Use bundled browser when opening forum links
+settings-forum-links-in-webview-tooltip = This allows installing mods directly from links in forum posts
+settings-watch-clipboard = Offer to open mod links copied to the clipboard
+settings-place-loose-files = Offer to place downloaded .jar and .json files into a mod
+settings-url-scheme = Open moss:// links with MOSS
+settings-url-scheme-failed = Failed to change what opens moss:// links
+settings-associate-archives = Offer MOSS for opening mod archives
+settings-associate-archives-failed = Failed to change what opens mod archives
+settings-discrepancy-auto-update = Show automatic updates even for mods that have a version discrepancy
+settings-discrepancy-auto-update-hint = (Recommended Off)
+settings-accent-placeholder = Theme default
+settings-bulk-confirmation = Confirm enabling or disabling many mods at once:
+settings-bulk-confirmation-off = Off
+settings-bulk-confirmation-checkbox = Tick a box
+settings-bulk-confirmation-type-word = Type a word
+settings-bulk-confirmation-threshold = Only confirm when more mods than this would change:
+settings-column-editor-title = Column Editor
+settings-add-column = Add new column
+settings-logs-empty = No log file has been written yet.
+settings-logs-title = Logs
+settings-open-log-folder = Open log folder
+settings-vmparams = Enable vmparams editing
+settings-min-ram = Minimum RAM:
+settings-max-ram = Maximum RAM:
+settings-jre-switcher = Open JRE Switcher
+settings-current-jre-unknown = Current JRE: unknown
+settings-jre-swap-starting = Starting...
+settings-jre-managed-mode = Enable 'Managed' mode.
+settings-experimental-launch = Enable experimental direct launch
+settings-horizontal-resolution = Horizontal Resolution:
+settings-vertical-resolution = Vertical Resolution:
+settings-access-tokens = Access tokens:
+settings-access-tokens-token = Token
+settings-external-dirs = External mod folders:
+settings-external-dirs-placeholder = Folder path
+settings-cache-dir = Download & Cache Directory:
+settings-download-dir = Download Only Folder:
+settings-download-dir-not-set = Not set
+settings-current-jre = Current JRE: { $jre }
+settings-logs-balancers = Batched UI updates:\n{ $balancers }
+settings-jre-recommended = (RECOMMENDED)
+settings-jre-unsupported = (UNSUPPORTED)
+settings-jre-research = (RESEARCH)
+settings-jre-experimental = (EXPERIMENTAL)
+settings-jre-mikohime = JRE { $version } + Mikohime kit
+settings-browse = Browse...
+settings-reset = Reset
+settings-install-dir = Starsector Install Directory:
+settings-nexus-api-key-tooltip = Optional. Allows checking Nexus Mods for updates to mods that don't provide a version file.\nYour personal API key can be found in your Nexus Mods account settings.
+settings-watch-clipboard-tooltip = Forum, Nexus Mods and GitHub release links copied from anywhere, such as Discord, can be opened in the bundled browser or downloaded straight away
+settings-place-loose-files-tooltip = Some small mods and patches are shared as a bare file rather than an archive. Downloading one offers to put it in an installed mod's folder, replacing the file of the same name if there is one
+settings-url-scheme-tooltip = Lets "Install with MOSS" links on mod sites start a download, which you'll be asked to confirm
+settings-associate-archives-tooltip = Adds MOSS to the "Open with" choices for .zip, .7z and .rar files. Once picked as the default, double-clicking a downloaded mod installs it
+settings-jre-managed-mode-hint = 'Managed' mode stores JRE updates in a MOSS managed data folder, keeping your Starsector install folder clutter free.\nUnfortunately, if you're on Windows, MOSS must be run with administrator privileges for this mode to work.
+settings-jre-mikohime-hint = Java 17 and 21 also install the newest Mikohime kit for them, which replaces the game's launch scripts and vmparams. The files it replaces are backed up first.
+settings-access-tokens-tooltip = Optional. Sent when downloading from the given domain, for example to fetch releases from a private GitHub repo.\nTokens are stored in your system keychain, not in the settings file.
+settings-external-dirs-tooltip = Optional. Folders of mods kept somewhere other than the game's mods folder, eg: a shared network drive.\nEnabling one of these mods links it into the mods folder, disabling it removes the link. The mod itself is never moved.
+settings-cache-dir-tooltip = Where downloads are stored before they're installed.\nPicking a folder on the same drive as your Starsector install avoids slow copies between drives.
+settings-download-dir-tooltip = Where archives are saved when you choose to download a mod without installing it.
@@ -1,12 +1,11 @@
 use std::{
-  fs::{metadata, File},
-  io::Write,
-  path::PathBuf,
+  fs::metadata,
+  path::{Path, PathBuf},
   rc::Rc,
   sync::Arc,
+  time::Instant,
 };
 
-use base64::{decode, encode};
 use chrono::{DateTime, Local, TimeZone};
 use druid::{
   commands,
@@ -17,25 +16,25 @@ use druid::{
     Axis, Button, Checkbox, Either, Flex, Label, List, Maybe, Scope, SizedBox, Spinner, Tabs,
     TabsPolicy, TextBox, ViewSwitcher,
   },
-  AppDelegate as Delegate, Command, Data, DelegateCtx, Env, Event, EventCtx, Handled, KeyEvent,
-  Lens, LensExt, Selector, SingleUse, Size, Target, Widget, WidgetExt, WidgetId, WindowDesc,
-  WindowHandle, WindowId, WindowLevel,
+  AppDelegate as Delegate, Application, Command, Data, DelegateCtx, Env, Event, EventCtx,
+  ExtEventSink, Handled, KeyEvent, Lens, LensExt, Menu, MenuItem, Selector, SingleUse, Target,
+  Widget, WidgetExt, WidgetId, WindowDesc, WindowHandle, WindowId, WindowLevel,
 };
 use druid_widget_nursery::{
   material_icons::Icon, FutureWidget, ProgressBar, Separator, Stack, StackChildPosition,
   WidgetExt as WidgetExtNursery,
 };
-use rand::random;
 use remove_dir_all::remove_dir_all;
 use reqwest::Url;
 use strum::IntoEnumIterator;
 use tap::{Pipe, Tap};
 use tokio::runtime::Handle;
+use tracing::{debug, error, info};
 use webview_shared::{
   InstallType, UserEvent, FRACTAL_INDEX, FRACTAL_MODDING_SUBFORUM, FRACTAL_MODS_FORUM, PROJECT,
-  WEBVIEW_EVENT, WEBVIEW_INSTALL, WEBVIEW_OFFSET,
+  WEBVIEW_EVENT, WEBVIEW_INSTALL,
 };
-use webview_subsystem::init_webview;
+use webview_subsystem::{init_webview, webview_bounds};
 use wry::WebView;
 
 use crate::{
@@ -47,33 +46,79 @@ use crate::{
 };
 
 use self::{
-  controllers::{AppController, HoverController, InstallController, ModListController},
-  installer::{HybridPath, StringOrPath, DOWNLOAD_PROGRESS, DOWNLOAD_STARTED, INSTALL_ALL},
-  mod_description::ModDescription,
-  mod_entry::{ModEntry, ModMetadata},
-  mod_list::{EnabledMods, Filters, ModList},
+  bisect::{Bisection, Interrupted, Step},
+  controllers::{
+    AppController, ClipboardController, HoverController, InstallController, LayoutController,
+    ModListController, SidePanelSplitController, SidePanelTabController,
+  },
+  history::{History, Operation},
+  i18n::{tr, tr_args},
+  installer::{
+    loose_file, BlobDownload, DownloadInfo, HybridPath, InstallError, InstallOutcome,
+    InstallStatus, OverwriteMode, StringOrPath, DOWNLOAD_PROGRESS, DOWNLOAD_STARTED, INSTALL_ALL,
+    INSTALL_STATUS,
+  },
+  mod_description::{config_files, forum_post, ModDescription},
+  mod_entry::{mod_info_editor::ModInfoForm, thumbnail, ModEntry, ModMetadata},
+  mod_list::{
+    headings::Heading,
+    library, link_farm,
+    share::{self, ShareFormat},
+    EnabledMods, FilterPreset, Filters, ModList, ParseFailure,
+  },
   mod_repo::ModRepo,
+  mod_skeleton::SkeletonForm,
   modal::Modal,
-  settings::{Settings, SettingsCommand},
+  popup::Popup,
+  progress::{self, Progress},
+  ram_estimate::RamEstimate,
+  settings::{vmparams::VMParams, BulkConfirmation, Settings, SettingsCommand},
+  toast::Toast,
   util::{
-    button_painter, get_latest_manager, get_quoted_version, get_starsector_version, h2, h3,
-    icons::*, make_column_pair, Button2, CommandExt, DummyTransfer, IndyToggleState, LabelExt,
-    LensExtExt as _, Release, GET_INSTALLED_STARSECTOR,
+    bold_text, button_painter, format_size, get_quoted_version, get_starsector_version, h2, h3,
+    icons::*, make_column_pair, Button2, Card, CommandExt, DummyTransfer, LabelExt,
+    LensExtExt as _, Release, VersionSource, GET_INSTALLED_STARSECTOR, RED_KEY,
   },
 };
 
+mod archive;
+mod bisect;
+pub mod cli;
+mod clipboard_watch;
+mod compat_db;
 mod controllers;
+mod file_association;
+mod history;
+pub mod i18n;
+mod install_log;
 pub mod installer;
+mod known_libraries;
+mod launch_check;
+pub mod logging;
 mod mod_description;
 mod mod_entry;
 mod mod_list;
 mod mod_repo;
+mod mod_skeleton;
 pub mod modal;
+mod net;
+mod popup;
+mod progress;
+mod ram_estimate;
+mod report;
+mod safe_mode;
+mod secrets;
 mod settings;
+pub mod single_instance;
+mod theme;
+mod toast;
+mod update_check;
 mod updater;
+mod url_scheme;
 #[allow(dead_code)]
 #[path = "./util.rs"]
 pub mod util;
+mod version_source;
 
 const TAG: &str = env!("CARGO_PKG_VERSION");
 
@@ -82,7 +127,7 @@ pub struct App {
   init: bool,
   settings: settings::Settings,
   mod_list: mod_list::ModList,
-  active: Option<String>,
+  active: Option<PathBuf>,
   #[data(ignore)]
   runtime: Handle,
   #[data(ignore)]
@@ -90,11 +135,31 @@ pub struct App {
   #[data(same_fn = "PartialEq::eq")]
   log: Vector<String>,
   overwrite_log: Vector<Rc<(StringOrPath, HybridPath, Arc<ModEntry>)>>,
-  duplicate_log: Vector<(Arc<ModEntry>, Arc<ModEntry>)>,
+  duplicate_log: Vector<Vector<Arc<ModEntry>>>,
   #[data(same_fn = "option_ptr_cmp")]
   webview: Option<Rc<WebView>>,
+  /// How many mods the last Enable All / Disable All changed
+  toggle_summary: Option<String>,
+  /// Mods checked so far and how many there are, while an update check is running
+  update_check_progress: Option<(usize, usize)>,
+  scan_progress: Option<Progress>,
+  #[data(same_fn = "PartialEq::eq")]
+  archived_mods: Vector<PathBuf>,
+  #[data(same_fn = "PartialEq::eq")]
+  problem_mods: Vector<ParseFailure>,
+  ram_estimate: Option<RamEstimate>,
+  bisection: Option<Bisection>,
+  #[data(ignore)]
+  history: History,
   downloads: OrdMap<i64, (i64, String, f64)>,
+  /// Mega downloads that failed part way through: start time, blob URI, error
+  failed_downloads: OrdMap<i64, (i64, String, String)>,
+  install_queue: OrdMap<i64, (i64, String, InstallStatus)>,
   mod_repo: Option<ModRepo>,
+  toasts: Vector<Toast>,
+  /// Save downloads started in the mod browser to the download only folder instead of installing
+  /// them, chosen from the install options
+  download_only: bool,
 }
 
 impl App {
@@ -106,9 +171,14 @@ impl App {
   const REFRESH: Selector<()> = Selector::new("app.mod_list.refresh");
   const DISABLE: Selector<()> = Selector::new("app.disable");
   const UPDATE_AVAILABLE: Selector<Result<Release, String>> = Selector::new("app.update.available");
-  const SELF_UPDATE: Selector<()> = Selector::new("app.update.perform");
+  /// Looks for a MOSS update on the update channel in settings.
+  const CHECK_SELF_UPDATE: Selector = Selector::new("app.update.check");
+  const SELF_UPDATE: Selector<Release> = Selector::new("app.update.perform");
+  const SELF_UPDATE_FINISHED: Selector<Result<updater::Applied, String>> =
+    Selector::new("app.update.finished");
   const RESTART: Selector<PathBuf> = Selector::new("app.update.restart");
   const LOG_SUCCESS: Selector<String> = Selector::new("app.mod.install.success");
+  const SHOW_LOG: Selector = Selector::new("app.install.show_log");
   const CLEAR_LOG: Selector = Selector::new("app.install.clear_log");
   const LOG_ERROR: Selector<(String, String)> = Selector::new("app.mod.install.fail");
   const LOG_MESSAGE: Selector<String> = Selector::new("app.mod.install.start");
@@ -117,16 +187,38 @@ impl App {
   const CLEAR_OVERWRITE_LOG: Selector<bool> = Selector::new("app.install.clear_overwrite_log");
   const REMOVE_OVERWRITE_LOG_ENTRY: Selector<StringOrPath> =
     Selector::new("app.install.overwrite.decline");
-  const DELETE_AND_SUMBIT: Selector<(PathBuf, Arc<ModEntry>)> =
+  /// The copy to keep, the copies to remove, and whether to archive them first.
+  const RESOLVE_DUPLICATES: Selector<(Arc<ModEntry>, Vec<Arc<ModEntry>>, bool)> =
     Selector::new("app.mod.duplicate.resolve");
   const REMOVE_DUPLICATE_LOG_ENTRY: Selector<String> =
     Selector::new("app.mod.duplicate.remove_log");
   const CLEAR_DUPLICATE_LOG: Selector = Selector::new("app.mod.duplicate.ignore_all");
   pub const OPEN_WEBVIEW: Selector<Option<String>> = Selector::new("app.webview.open");
   const CONFIRM_DELETE_MOD: Selector<Arc<ModEntry>> = Selector::new("app.mod_entry.delete");
+  /// Deletes a mod that couldn't be moved to the trash, so can't be restored.
+  const DELETE_MOD_PERMANENTLY: Selector<Arc<ModEntry>> =
+    Selector::new("app.mod_entry.delete_permanently");
+  /// Replaces the install dir's `enabled_mods.json` with the given backup.
+  const RESTORE_ENABLED_MODS: Selector<(PathBuf, PathBuf)> =
+    Selector::new("app.enabled_mods.restore");
+  /// Launches the game, skipping the enabled mods check if true.
+  const LAUNCH: Selector<bool> = Selector::new("app.launch");
+  const CHECK_ENABLED_MODS: Selector = Selector::new("app.launch.check");
   const REMOVE_DOWNLOAD_BAR: Selector<i64> = Selector::new("app.download.bar.remove");
+  const RETRY_DOWNLOAD: Selector<i64> = Selector::new("app.download.retry");
+  const DOWNLOAD_FAILED: Selector<(i64, String, String)> = Selector::new("app.download.failed");
+  const CONFIRM_DOWNLOAD: Selector<DownloadInfo> = Selector::new("app.download.confirm");
+  /// Saves the download at a URL to the download only folder instead of installing it.
+  pub const DOWNLOAD_ONLY: Selector<String> = Selector::new("app.download.only");
+  const REMOVE_INSTALL_QUEUE_ENTRY: Selector<i64> = Selector::new("app.install.queue.remove");
   const FOUND_MULTIPLE: Selector<(HybridPath, Vec<PathBuf>)> =
     Selector::new("app.install.found_multiple");
+  pub const ACTIVATE: Selector<Vec<String>> = Selector::new("app.activate");
+  /// Enabled, only the mods shown, and how many mods would change.
+  const CONFIRM_SET_ALL_ENABLED: Selector<(bool, bool, usize)> =
+    Selector::new("app.mod_list.set_all_enabled.confirm");
+  /// Enabled, and only the mods shown.
+  const SET_ALL_ENABLED: Selector<(bool, bool)> = Selector::new("app.mod_list.set_all_enabled");
 
   pub fn new(runtime: Handle) -> Self {
     let settings = settings::Settings::load()
@@ -142,13 +234,51 @@ impl App {
         settings
       })
       .unwrap_or_else(|_| settings::Settings::new());
+    settings.sync_nexus_api_key();
+    secrets::load(&settings.token_domains);
+    i18n::set_language(&settings.language);
+    update_check::set_interval(settings.update_check_interval_hours);
+    net::configure(&settings.network);
+    library::set(settings.mod_library());
+
+    if let Err(err) = std::fs::create_dir_all(settings.cache_dir()) {
+      error!("Failed to create cache dir {:?}", err)
+    }
+    // deletions can only be undone until MOSS is closed
+    let mod_dirs = settings
+      .install_dir
+      .as_deref()
+      .map(util::mods_dir)
+      .into_iter()
+      .chain(settings.linked_mod_dirs())
+      .collect();
+    runtime.spawn_blocking(move || history::empty_trash(mod_dirs));
+    if settings.register_url_scheme {
+      // again each run, in case MOSS has been moved or updated since
+      runtime.spawn_blocking(|| {
+        if let Err(err) = url_scheme::register() {
+          error!("Failed to register moss:// links {:?}", err)
+        }
+      });
+    }
+    if settings.associate_archives {
+      runtime.spawn_blocking(|| {
+        if let Err(err) = file_association::register() {
+          error!("Failed to register as a program for mod archives {:?}", err)
+        }
+      });
+    }
 
     let headings = settings.headings.clone();
+    let sort = settings.sort.clone();
+    let columns = settings.layout.columns.clone();
+    let mut mod_list = mod_list::ModList::new(headings, sort, columns);
+    mod_list.set_version_override(settings.version_override());
 
     App {
       init: false,
       settings,
-      mod_list: mod_list::ModList::new(headings),
+      mod_list,
       active: None,
       runtime,
       widget_id: WidgetId::reserved(0),
@@ -156,22 +286,329 @@ impl App {
       overwrite_log: Vector::new(),
       duplicate_log: Vector::new(),
       webview: None,
+      toggle_summary: None,
+      update_check_progress: None,
+      scan_progress: None,
+      archived_mods: archive::list(),
+      problem_mods: Vector::new(),
+      ram_estimate: None,
+      bisection: None,
+      history: History::default(),
       downloads: OrdMap::new(),
+      failed_downloads: OrdMap::new(),
+      install_queue: OrdMap::new(),
       mod_repo: None,
+      toasts: Vector::new(),
+      download_only: false,
+    }
+  }
+
+  pub fn save_original_env(&self, env: &mut Env) {
+    theme::save_original_env(
+      env,
+      self.settings.theme,
+      &self.settings.accent_colour,
+      self.settings.ui_scale,
+    )
+  }
+
+  /// Rebuilds `env` for the current theme, so windows update as soon as the theme is changed.
+  fn apply_theme(env: &mut Env, data: &App) {
+    theme::apply(
+      env,
+      data.settings.theme,
+      &data.settings.accent_colour,
+      data.settings.ui_scale,
+    )
+  }
+
+  /// Enables or disables every mod, or only those shown by the current search and filters, first
+  /// asking for confirmation if the user has opted into it for an action this large.
+  fn request_set_all_enabled(&mut self, ctx: &mut EventCtx, enabled: bool, shown_only: bool) {
+    let affected = self
+      .mod_list
+      .mods
+      .values()
+      .filter(|entry| entry.enabled != enabled)
+      .filter(|entry| !enabled || self.mod_list.can_enable(entry))
+      .filter(|entry| !shown_only || self.mod_list.is_shown(entry))
+      .count();
+
+    if self
+      .settings
+      .bulk_confirmation
+      .required(affected, self.settings.bulk_confirmation_threshold)
+    {
+      ctx.submit_command(App::CONFIRM_SET_ALL_ENABLED.with((enabled, shown_only, affected)))
+    } else {
+      if let Err(err) = self.set_all_enabled(enabled, shown_only) {
+        ctx.submit_command(Popup::SHOW.with(Popup::Critical(err)));
+      }
+    }
+  }
+
+  /// Flips every mod, or every shown mod, in a single update to the mod list, so
+  /// `enabled_mods.json` is only written once and the whole change is undone in one step.
+  fn set_all_enabled(&mut self, enabled: bool, shown_only: bool) -> anyhow::Result<()> {
+    if self.settings.install_dir.is_none() {
+      return Ok(());
+    }
+
+    let before = self.enabled_ids();
+    let mut mods = self.mod_list.mods.clone();
+    let mut changed = 0;
+    for (_, entry) in mods.iter_mut().filter(|(_, entry)| {
+      entry.enabled != enabled
+        && (!enabled || self.mod_list.can_enable(entry))
+        && (!shown_only || self.mod_list.is_shown(entry))
+    }) {
+      Arc::make_mut(entry).enabled = enabled;
+      changed += 1;
+    }
+    self.mod_list.mods = mods;
+
+    let key = if enabled {
+      "tools-enabled-count"
+    } else {
+      "tools-disabled-count"
+    };
+    self.toggle_summary = Some(tr_args(key, &[("count", &changed)]));
+
+    self.enabled_changed(before)
+  }
+
+  /// Reloads the compatibility list in the background, or clears it if it's been turned off.
+  fn refresh_compat_db(&mut self, ext_ctx: ExtEventSink) {
+    match self.settings.compat_db_url() {
+      Some(url) => {
+        self.runtime.spawn(compat_db::CompatDb::load(url, ext_ctx));
+      }
+      None => self.mod_list.set_compat(Arc::default()),
+    }
+  }
+
+  /// Points installed mods at their records in the mod repo, after the index has been loaded or
+  /// refreshed.
+  fn mod_repo_changed(&mut self) {
+    let Some(repo) = self.mod_repo.as_mut() else {
+      return;
+    };
+    repo.set_index();
+    repo.mark_installed(self.mod_list.mods.values().map(|entry| entry.as_ref()));
+
+    let mut mods = self.mod_list.mods.clone();
+    for (_, entry) in mods.iter_mut() {
+      let record = ModRepo::record_for(entry);
+      if entry.repo_record != record {
+        Arc::make_mut(entry).repo_record = record
+      }
+    }
+    self.mod_list.mods = mods;
+  }
+
+  /// Records a change to which mods are enabled so it can be undone, then writes
+  /// `enabled_mods.json` and relinks external mods. Does nothing if nothing changed.
+  fn enabled_changed(&mut self, before: Vec<String>) -> anyhow::Result<()> {
+    let after = self.enabled_ids();
+    if self.settings.install_dir.is_none() || after == before {
+      return Ok(());
+    }
+
+    self.history.record(Operation::SetEnabled(before, after));
+    self.write_enabled_mods()
+  }
+
+  fn write_enabled_mods(&self) -> anyhow::Result<()> {
+    let Some(install_dir) = &self.settings.install_dir else {
+      return Ok(());
+    };
+
+    // the links are still synced, so external mods match what's enabled in MOSS either way
+    let saved = EnabledMods::from(self.enabled_ids())
+      .save(install_dir)
+      .map_err(|err| anyhow::anyhow!("Failed to save enabled_mods.json {:?}", err));
+    self.sync_links(install_dir)?;
+
+    saved
+  }
+
+  /// Links exactly the enabled external mods into the mods folder. Mods sharing an ID with the one
+  /// used for it are always disabled, and go first so the used one can take over their link.
+  fn sync_links(&self, install_dir: &Path) -> anyhow::Result<()> {
+    let mods = &self.mod_list;
+    let unused = mods.mods.values().filter(|entry| !mods.can_enable(entry));
+
+    link_farm::sync(install_dir, unused.chain(mods.used()))
+  }
+
+  /// Enables exactly the mods in `ids`, which must be sorted.
+  fn set_enabled_ids(&mut self, ids: &[String]) {
+    let mut mods = self.mod_list.mods.clone();
+    for (_, entry) in mods.iter_mut() {
+      let enabled = self.mod_list.can_enable(entry) && ids.binary_search(&entry.id).is_ok();
+      if entry.enabled != enabled {
+        Arc::make_mut(entry).enabled = enabled
+      }
+    }
+    self.mod_list.mods = mods;
+  }
+
+  /// Undoes or redoes `operation`, returning the operation that repeats it. A deleted mod's
+  /// folder moves each time it goes to the trash.
+  fn apply_history(&mut self, operation: Operation, undo: bool) -> anyhow::Result<Operation> {
+    match operation {
+      Operation::SetEnabled(before, after) => {
+        self.set_enabled_ids(if undo { &before } else { &after });
+        self.write_enabled_mods()?;
+
+        Ok(Operation::SetEnabled(before, after))
+      }
+      Operation::Delete(entry, trashed) if undo => {
+        history::restore(&trashed, &entry.path)?;
+        self.mod_list.add(entry.clone());
+        if let Err(err) = install_log::record(&install_log::Entry::restored(&entry)) {
+          error!("Failed to record restoring {}: {}", entry.id, err)
+        }
+        self.write_enabled_mods()?;
+
+        Ok(Operation::Delete(entry, trashed))
+      }
+      Operation::Delete(entry, _) => {
+        let trashed = App::trash_mod_folder(self.settings.install_dir.as_deref(), &entry)?;
+        self.mod_list.remove(&entry.path);
+        if let Err(err) = install_log::record(&install_log::Entry::deleted(&entry)) {
+          error!("Failed to record deleting {}: {}", entry.id, err)
+        }
+
+        Ok(Operation::Delete(entry, trashed))
+      }
+    }
+  }
+
+  /// Copies the enabled mods to the clipboard, ready to post.
+  fn copy_mod_list(&mut self, format: ShareFormat) {
+    let enabled = self.mod_list.mods.values().filter(|entry| entry.enabled);
+    Application::global()
+      .clipboard()
+      .put_string(share::format(format, enabled.map(Arc::as_ref)));
+    self.log_message(&tr("tools-share-copied"));
+  }
+
+  /// Saves the mod list's filters, search and sort as a preset under the name typed in, replacing
+  /// any preset of the same name.
+  fn save_filter_preset(&mut self) {
+    let name = self.mod_list.preset_name.trim().to_string();
+    let preset = self.mod_list.preset(name.clone());
+    self
+      .settings
+      .filter_presets
+      .retain(|existing| existing.name != name);
+    self.settings.filter_presets.push_back(preset);
+    self.mod_list.preset_name.clear();
+    if let Err(err) = self.settings.save() {
+      error!("Failed to save settings {:?}", err)
+    }
+  }
+
+  /// Lists the saved presets to apply, and under them the same again to delete.
+  fn filter_presets_menu(presets: &Vector<FilterPreset>) -> Menu<App> {
+    if presets.is_empty() {
+      return Menu::empty().entry(MenuItem::new(tr("filters-presets-none")).enabled(false));
+    }
+
+    let mut delete = Menu::new(tr("filters-preset-delete"));
+    let mut menu = Menu::empty();
+    for preset in presets.iter().cloned() {
+      let name = preset.name.clone();
+      delete = delete.entry(MenuItem::new(name.clone()).on_activate(
+        move |_, data: &mut App, _| {
+          data
+            .settings
+            .filter_presets
+            .retain(|existing| existing.name != name);
+          if let Err(err) = data.settings.save() {
+            error!("Failed to save settings {:?}", err)
+          }
+        },
+      ));
+      menu = menu.entry(
+        MenuItem::new(preset.name.clone()).on_activate(move |ctx, _, _| {
+          ctx.submit_command(ModList::APPLY_PRESET.with(preset.clone()))
+        }),
+      );
     }
+
+    menu.separator().entry(delete)
+  }
+
+  /// Asks which installed mod a downloaded `.jar` or `.json` should be placed into.
+  fn loose_file_modal(info: &DownloadInfo) -> impl Widget<App> {
+    let (uri, file_name) = (info.uri.clone(), info.file_name.clone());
+
+    Modal::<App>::new(&tr("loose-file-title"))
+      .with_content(tr_args("loose-file-name", &[("name", &info.file_name)]))
+      .with_content(tr("loose-file-hint"))
+      .with_content(
+        Button2::from_label(tr("loose-file-place"))
+          .on_click2(move |ctx, mouse, data: &mut App, _| {
+            let window = ctx.window_id();
+            let mut mods: Vec<&Arc<ModEntry>> = data.mod_list.mods.values().collect();
+            mods.sort_by_cached_key(|entry| entry.name.to_lowercase());
+
+            let mut menu = Menu::empty();
+            for entry in mods {
+              let payload = (uri.clone(), file_name.clone(), entry.path.clone());
+              menu = menu.entry(
+                MenuItem::new(entry.name.clone()).on_activate(move |ctx, _, _| {
+                  ctx.submit_command(loose_file::PLACE.with(payload.clone()));
+                  ctx.submit_command(commands::CLOSE_WINDOW.to(window));
+                }),
+              );
+            }
+            if data.mod_list.mods.is_empty() {
+              menu = menu.entry(MenuItem::new(tr("loose-file-no-mods")).enabled(false));
+            }
+            ctx.show_context_menu::<App>(menu, ctx.to_window(mouse.pos))
+          })
+          .boxed(),
+      )
+      .with_button(
+        &tr("loose-file-just-download"),
+        App::DOWNLOAD_ONLY.with(info.uri.clone()),
+      )
+      .with_close_label(&tr("cancel"))
+      .build()
+  }
+
+  fn enabled_ids(&self) -> Vec<String> {
+    let mut ids: Vec<String> = self
+      .mod_list
+      .mods
+      .values()
+      .filter(|entry| entry.enabled)
+      .map(|entry| entry.id.clone())
+      .collect();
+    ids.sort();
+
+    ids
+  }
+
+  /// Opens the main window at the size and position it was last closed at.
+  pub fn restore_layout(&self, window: WindowDesc<Self>) -> WindowDesc<Self> {
+    self.settings.layout.apply(window)
   }
 
   pub fn ui_builder() -> impl Widget<Self> {
     let settings = Flex::row()
       .with_child(
         Flex::row()
-          .with_child(Label::new("Settings").with_text_size(18.))
+          .with_child(Label::new(tr("nav-settings")).with_text_size(theme::NAV_TEXT_SIZE))
           .with_spacer(5.)
           .with_child(Icon::new(SETTINGS))
           .padding((8., 4.))
           .background(button_painter())
           .controller(HoverController)
-          .on_click(|event_ctx, _, _| {
+          .on_activate(|event_ctx, _, _| {
             event_ctx.submit_command(App::SELECTOR.with(AppCommands::OpenSettings))
           }),
       )
@@ -179,46 +616,47 @@ impl App {
     let refresh = Flex::row()
       .with_child(
         Flex::row()
-          .with_child(Label::new("Refresh").with_text_size(18.))
+          .with_child(Label::new(tr("nav-refresh")).with_text_size(theme::NAV_TEXT_SIZE))
           .with_spacer(5.)
           .with_child(Icon::new(SYNC))
           .padding((8., 4.))
           .background(button_painter())
           .controller(HoverController)
-          .on_click(|event_ctx, _, _| event_ctx.submit_command(App::REFRESH)),
+          .on_activate(|event_ctx, _, _| event_ctx.submit_command(App::REFRESH)),
       )
       .expand_width();
     let install_dir_browser =
       Settings::install_dir_browser_builder(Axis::Vertical).lens(App::settings);
     let install_mod_button = Flex::row()
-      .with_child(Label::new("Install Mod(s)").with_text_size(18.))
+      .with_child(Label::new(tr("nav-install")).with_text_size(theme::NAV_TEXT_SIZE))
       .with_spacer(5.)
       .with_child(Icon::new(INSTALL_DESKTOP))
       .padding((8., 4.))
       .background(button_painter())
       .controller(HoverController)
-      .on_click(|_, _, _| {})
-      .controller(InstallController)
+      .on_click2(|ctx, mouse, _, _| {
+        ctx.show_context_menu::<App>(InstallController::menu(ctx), ctx.to_window(mouse.pos))
+      })
       .on_command(App::OPEN_FILE, |ctx, payload, data| {
         if let Some(targets) = payload {
           if !targets.is_empty() {
-            ctx.submit_command(App::LOG_MESSAGE.with(format!("Installing {}",
-                targets
-                  .iter()
-                  .map(|t| {
-                    t.file_name().map_or_else(
-                      || String::from("unknown"),
-                      |f| f.to_string_lossy().into_owned(),
-                    )
-                  })
-                  .collect::<Vec<String>>()
-                  .join(", "),
-              )));
+            let names = targets
+              .iter()
+              .map(|t| {
+                t.file_name()
+                  .map_or_else(|| tr("unknown"), |f| f.to_string_lossy().into_owned())
+              })
+              .collect::<Vec<String>>()
+              .join(", ");
+            ctx.submit_command(
+              App::LOG_MESSAGE.with(tr_args("log-installing", &[("mods", &names)])),
+            );
             data.runtime.spawn(
               installer::Payload::Initial(targets.iter().map(|f| f.to_path_buf()).collect())
                 .install(
                   ctx.get_external_handle(),
                   data.settings.install_dir.clone().unwrap(),
+                  data.settings.cache_dir(),
                   data.mod_list.mods.values().map(|v| v.id.clone()).collect(),
                 ),
             );
@@ -227,53 +665,56 @@ impl App {
       })
       .on_command(App::OPEN_FOLDER, |ctx, payload, data| {
         if let Some(target) = payload {
-          ctx.submit_command(App::LOG_MESSAGE.with(format!(
-            "Installing {}",
-            target.file_name().map_or_else(
-              || String::from("unknown"),
-              |f| f.to_string_lossy().into_owned(),
-            )
-          )));
+          let name = target
+            .file_name()
+            .map_or_else(|| tr("unknown"), |f| f.to_string_lossy().into_owned());
+          ctx.submit_command(App::LOG_MESSAGE.with(tr_args("log-installing", &[("mods", &name)])));
           data
             .runtime
             .spawn(installer::Payload::Initial(vec![target.clone()]).install(
               ctx.get_external_handle(),
               data.settings.install_dir.clone().unwrap(),
+              data.settings.cache_dir(),
               data.mod_list.mods.values().map(|v| v.id.clone()).collect(),
             ));
         }
       })
-      .disabled_if(|data, _| data.settings.install_dir.is_none());
+      .disabled_if(|data, _| !data.settings.install_dir_usable());
     let browse_index_button = Flex::row()
-      .with_child(Label::new("Open Mod Browser").with_text_size(18.))
+      .with_child(Label::new(tr("nav-mod-browser")).with_text_size(theme::NAV_TEXT_SIZE))
       .with_spacer(5.)
       .with_child(Icon::new(OPEN_BROWSER))
       .padding((8., 4.))
       .background(button_painter())
       .controller(HoverController)
-      .on_click(|event_ctx, _, _| event_ctx.submit_command(App::OPEN_WEBVIEW.with(None)))
+      .on_activate(|event_ctx, _, _| event_ctx.submit_command(App::OPEN_WEBVIEW.with(None)))
       .expand_width()
       .disabled_if(|data: &App, _| data.settings.install_dir.is_none());
     let mod_repo = FutureWidget::new(
-      |_, _| ModRepo::get_mod_repo(),
+      |_, _| ModRepo::load_cache(),
       Flex::row()
-        .with_child(Label::new("Open Unofficial Mod Repo").with_text_size(18.))
+        .with_child(Label::new(tr("nav-mod-repo")).with_text_size(theme::NAV_TEXT_SIZE))
         .with_spacer(5.)
         .with_child(Icon::new(EXTENSION))
         .padding((8., 4.))
         .background(button_painter()),
       |value, data: &mut App, _| {
-        data.mod_repo = value.inspect_err(|err| eprintln!("{:?}", err)).ok();
+        // the refresh started at launch may have already finished
+        if data.mod_repo.is_none() {
+          data.mod_repo = value.inspect(|_| info!("Showing cached mod repo until it's refreshed"));
+          data.mod_repo_changed();
+        }
 
         Flex::row()
-          .with_child(Label::new("Open Unofficial Mod Repo").with_text_size(18.))
+          .with_child(Label::new(tr("nav-mod-repo")).with_text_size(theme::NAV_TEXT_SIZE))
           .with_spacer(5.)
           .with_child(Icon::new(EXTENSION))
           .padding((8., 4.))
           .background(button_painter())
           .controller(HoverController)
-          .on_click(|ctx, data: &mut App, _| {
-            if data.mod_repo.is_some() {
+          .on_activate(|ctx, data: &mut App, _| {
+            if let Some(repo) = data.mod_repo.as_mut() {
+              repo.mark_installed(data.mod_list.mods.values().map(|entry| entry.as_ref()));
               let modal = Stack::new()
                 .with_child(
                   ModRepo::ui_builder().disabled_if(|data: &ModRepo, _| data.modal_open()),
@@ -281,9 +722,9 @@ impl App {
                 .with_positioned_child(
                   Either::new(
                     |modal: &Option<String>, _| modal.is_some(),
-                    Modal::new("Open in Discord?")
-                      .with_content("Attempt to open this link in the Discord app?")
-                      .with_button("Open", ModRepo::OPEN_IN_DISCORD)
+                    Modal::new(&tr("repo-discord-title"))
+                      .with_content(tr("repo-discord-prompt"))
+                      .with_button(&tr("repo-discord-open"), ModRepo::OPEN_IN_DISCORD)
                       .with_close()
                       .with_on_close_override(|ctx, _| {
                         ctx.submit_command_global(ModRepo::CLEAR_MODAL)
@@ -303,7 +744,8 @@ impl App {
                   |orig, new| {
                     orig.replace(new);
                   },
-                ));
+                ))
+                .env_scope(App::apply_theme);
 
               let window = WindowDesc::new(modal.boxed())
                 .window_size((1000., 400.))
@@ -322,18 +764,40 @@ impl App {
       |_, _, _| mod_list::ModList::ui_builder().boxed(),
     )
     .lens(App::mod_list)
-    .on_change(|_ctx, _old, data, _env| {
-      if let Some(install_dir) = &data.settings.install_dir {
-        let enabled: Vec<Arc<ModEntry>> = data
-          .mod_list
-          .mods
-          .iter()
-          .filter_map(|(_, v)| v.enabled.then(|| v.clone()))
-          .collect();
-
-        if let Err(err) = EnabledMods::from(enabled).save(install_dir) {
-          eprintln!("{:?}", err)
-        };
+    .on_change(|ctx, old, data, _env| {
+      // most changes to the mod list (searching, sorting, resizing columns) don't touch which
+      // mods are enabled, enabled_changed only writes the file when they do. Mods being loaded by
+      // a scan, or cleared for one, aren't the user's doing, so they're neither written nor undone
+      if data.scan_progress.is_none()
+        && old.mod_list.has_same_mods(&data.mod_list)
+        && let Err(err) = data.enabled_changed(old.enabled_ids())
+      {
+        ctx.submit_command(Popup::SHOW.with(Popup::Critical(err)));
+      }
+      // enabling a fork changes which of the mods sharing its ID is used, but not which IDs are
+      // enabled, so enabled_changed leaves the links alone. A scan only loads the mods as they
+      // are, so doesn't need them relinked.
+      if !old.mod_list.uses_same(&data.mod_list)
+        && data.scan_progress.is_none()
+        && let Some(install_dir) = &data.settings.install_dir
+        && let Err(err) = data.sync_links(install_dir)
+      {
+        ctx.submit_command(Popup::SHOW.with(Popup::Critical(err)));
+      }
+      // waits for the scan to finish, so libraries that just haven't been loaded yet aren't
+      // reported as missing
+      if !old.mod_list.mods.same(&data.mod_list.mods) && data.scan_progress.is_none() {
+        for offer in known_libraries::offers(data.mod_list.mods.values()) {
+          Toast::push(&mut data.toasts, offer)
+        }
+      }
+      // sorting by search score only lasts as long as the search
+      let sort = data.mod_list.header.sort();
+      if sort != data.settings.sort && sort[0].0 != Heading::Score {
+        data.settings.sort = sort;
+        if let Err(err) = data.settings.save() {
+          error!("Failed to save the mod list's sort order {:?}", err)
+        }
       }
     })
     .expand()
@@ -344,12 +808,13 @@ impl App {
           data.active.clone(),
           data.mod_list.mods.clone(),
           data.webview.is_some(),
+          data.settings.install_dir.clone(),
         )
       },
-      |(active, mods, enabled), _, _| {
-        if let Some(entry) = active.as_ref().and_then(|active| mods.get(active)) {
+      |(active, mods, enabled, install_dir), _, _| {
+        if let Some(entry) = active.as_ref().and_then(|path| mods.get(path)) {
           let enabled = *enabled;
-          ModDescription::ui_builder()
+          ModDescription::ui_builder(install_dir.clone())
             .lens(lens::Constant(entry.clone()))
             .disabled_if(move |_, _| enabled)
             .boxed()
@@ -360,7 +825,7 @@ impl App {
     );
     let tool_panel = Flex::column()
       .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
-      .with_child(h2("Search"))
+      .with_child(h2(&tr("tools-search")))
       .with_child(
         TextBox::new()
           .on_change(|ctx, _, _, _| {
@@ -370,137 +835,614 @@ impl App {
           .expand_width(),
       )
       .with_default_spacer()
-      .with_child(h2("Toggles"))
+      .with_child(h2(&tr("tools-query")))
       .with_child(
-        Button::new("Enable All")
-          .controller(HoverController)
-          .on_click(|_, data: &mut App, _| {
-            if let Some(install_dir) = data.settings.install_dir.as_ref().cloned() {
-              let ids: Vec<String> = data.mod_list.mods.keys().cloned().collect();
-
-              for id in ids.iter() {
-                if let Some(mut entry) = data.mod_list.mods.remove(id) {
-                  (Arc::make_mut(&mut entry)).enabled = true;
-                  data.mod_list.mods.insert(id.clone(), entry);
-                }
-              }
-              if let Err(err) = EnabledMods::from(ids).save(&install_dir) {
-                eprintln!("{:?}", err)
-              }
-            }
+        TextBox::new()
+          .with_placeholder("enabled && status == outdated")
+          .on_change(|ctx, _, _, _| {
+            ctx.submit_command(ModList::QUERY_UPDATE);
           })
-          .disabled_if(|data: &App, _| data.mod_list.mods.values().all(|e| e.enabled))
+          .lens(App::mod_list.then(ModList::query_text))
           .expand_width(),
       )
+      .with_child(
+        Maybe::or_empty(|| Label::wrapped_func(|err: &String, _| err.clone()))
+          .lens(App::mod_list.then(ModList::query_error)),
+      )
       .with_spacer(5.)
       .with_child(
-        Button::new("Disable All")
+        Button::new(tr("tools-save-query"))
           .controller(HoverController)
-          .on_click(|_, data: &mut App, _| {
-            if let Some(install_dir) = data.settings.install_dir.as_ref() {
-              let ids: Vec<String> = data.mod_list.mods.keys().cloned().collect();
-
-              for id in ids.iter() {
-                if let Some(mut entry) = data.mod_list.mods.remove(id) {
-                  (Arc::make_mut(&mut entry)).enabled = false;
-                  data.mod_list.mods.insert(id.clone(), entry);
-                }
-              }
-              if let Err(err) = EnabledMods::empty().save(install_dir) {
-                eprintln!("{:?}", err)
+          .on_activate(|_, data: &mut App, _| {
+            let query = data.mod_list.query_text.trim().to_string();
+            if !data.settings.saved_queries.contains(&query) {
+              data.settings.saved_queries.push_back(query);
+              if let Err(err) = data.settings.save() {
+                error!("Failed to save settings {:?}", err)
               }
             }
           })
-          .disabled_if(|data: &App, _| data.mod_list.mods.values().all(|e| !e.enabled))
+          .disabled_if(|data: &App, _| {
+            data.mod_list.query_text.trim().is_empty() || data.mod_list.query_error.is_some()
+          })
           .expand_width(),
       )
       .with_default_spacer()
-      .with_child(h2("Filters"))
-      .tap_mut(|panel| {
-        for filter in Filters::iter() {
-          match filter {
-            Filters::Enabled => panel.add_child(h3("Status")),
-            Filters::Unimplemented => panel.add_child(h3("Version Checker")),
-            Filters::AutoUpdateAvailable => panel.add_child(h3("Auto Update Support")),
-            _ => {}
-          };
-          panel.add_child(
-            Scope::from_function(
-              |state: bool| state,
-              IndyToggleState::default(),
-              Checkbox::from_label(Label::wrapped(filter.to_string())).on_change(
-                move |ctx, _, new, _| {
-                  ctx.submit_command(ModList::FILTER_UPDATE.with((filter, !*new)))
-                },
-              ),
-            )
-            .lens(lens::Constant(true)),
+      .with_child(h2(&tr("tools-toggles")))
+      .with_child(
+        Button::new(tr("tools-enable-all"))
+          .controller(HoverController)
+          .on_activate(|ctx, data: &mut App, _| data.request_set_all_enabled(ctx, true, false))
+          .disabled_if(|data: &App, _| {
+            data
+              .mod_list
+              .mods
+              .values()
+              .all(|e| e.enabled || !data.mod_list.can_enable(e))
+          })
+          .expand_width(),
+      )
+      .with_spacer(5.)
+      .with_child(
+        Button::new(tr("tools-disable-all"))
+          .controller(HoverController)
+          .on_activate(|ctx, data: &mut App, _| data.request_set_all_enabled(ctx, false, false))
+          .disabled_if(|data: &App, _| data.mod_list.mods.values().all(|e| !e.enabled))
+          .expand_width(),
+      )
+      .with_child(Either::new(
+        |data: &App, _| data.mod_list.is_filtered(),
+        Flex::column()
+          .with_spacer(5.)
+          .with_child(
+            Button::new(tr("tools-enable-shown"))
+              .controller(HoverController)
+              .on_activate(|ctx, data: &mut App, _| data.request_set_all_enabled(ctx, true, true))
+              .disabled_if(|data: &App, _| {
+                data
+                  .mod_list
+                  .mods
+                  .values()
+                  .all(|e| e.enabled || !data.mod_list.can_enable(e) || !data.mod_list.is_shown(e))
+              })
+              .expand_width(),
           )
-        }
-      })
-      .padding(20.);
-    let launch_panel = Flex::column()
-      .with_child(make_column_pair(
-        h2("Starsector Version:"),
-        Maybe::new(
-          || Label::wrapped_func(|v: &String, _| v.clone()),
-          || Label::new("Unknown"),
-        )
-        .lens(
-          App::mod_list
-            .then(ModList::starsector_version)
-            .map(|v| v.as_ref().and_then(get_quoted_version), |_, _| {}),
-        ),
+          .with_spacer(5.)
+          .with_child(
+            Button::new(tr("tools-disable-shown"))
+              .controller(HoverController)
+              .on_activate(|ctx, data: &mut App, _| data.request_set_all_enabled(ctx, false, true))
+              .disabled_if(|data: &App, _| {
+                data
+                  .mod_list
+                  .mods
+                  .values()
+                  .all(|e| !e.enabled || !data.mod_list.is_shown(e))
+              })
+              .expand_width(),
+          ),
+        SizedBox::empty(),
       ))
+      .with_child(
+        Maybe::or_empty(|| Label::wrapped_func(|summary: &String, _| summary.clone()))
+          .lens(App::toggle_summary),
+      )
+      .with_child(
+        ViewSwitcher::new(
+          |progress: &Option<Progress>, _| progress.clone(),
+          |progress, _, _| match progress {
+            Some(progress) => progress.inline_view().padding((0., 5., 0., 0.)).boxed(),
+            None => SizedBox::empty().boxed(),
+          },
+        )
+        .lens(App::scan_progress),
+      )
+      .with_child(
+        Maybe::or_empty(|| {
+          Flex::column()
+            .with_child(Label::wrapped_func(
+              |(checked, total): &(usize, usize), _| {
+                tr_args(
+                  "tools-update-check-progress",
+                  &[("checked", checked), ("total", total)],
+                )
+              },
+            ))
+            .with_child(
+              ProgressBar::new()
+                .lens(lens::Map::new(
+                  |(checked, total): &(usize, usize)| *checked as f64 / *total as f64,
+                  |_, _| {},
+                ))
+                .expand_width(),
+            )
+            .padding((0., 5., 0., 0.))
+        })
+        .lens(App::update_check_progress),
+      )
+      .with_child(
+        ViewSwitcher::new(
+          |failures: &Vector<ParseFailure>, _| failures.clone(),
+          |_, failures, _| {
+            if failures.is_empty() {
+              return SizedBox::empty().boxed();
+            }
+
+            Flex::column()
+              .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+              .with_default_spacer()
+              .with_child(h2(&tr("tools-problem-mods")))
+              .with_child(Label::wrapped(tr("tools-problem-mods-hint")))
+              .tap_mut(|column| {
+                for failure in failures.iter().cloned() {
+                  let error = failure.error.clone();
+                  column.add_spacer(5.);
+                  column.add_child(
+                    Flex::row()
+                      .with_flex_child(
+                        Label::wrapped(
+                          failure
+                            .folder
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        )
+                        .expand_width(),
+                        1.,
+                      )
+                      .with_child(
+                        Button::new(tr("tools-open-file"))
+                          .controller(HoverController)
+                          .on_activate(move |ctx, _, _| {
+                            if let Err(err) = opener::open(&failure.file) {
+                              ctx.submit_command(
+                                Popup::SHOW.with(Popup::Error(anyhow::Error::new(err))),
+                              )
+                            }
+                          }),
+                      ),
+                  );
+                  column.add_child(Label::wrapped(error).with_text_size(12.));
+                }
+              })
+              .boxed()
+          },
+        )
+        .lens(App::problem_mods),
+      )
       .with_default_spacer()
-      .with_child(install_dir_browser)
+      .with_child(h2(&tr("tools-archived")))
+      .with_child(
+        ViewSwitcher::new(
+          |archives: &Vector<PathBuf>, _| archives.clone(),
+          |_, archives, _| {
+            if archives.is_empty() {
+              return Label::wrapped(tr("tools-archived-none")).boxed();
+            }
+
+            Flex::column()
+              .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+              .tap_mut(|column| {
+                for archive in archives.iter().cloned() {
+                  column.add_child(
+                    Flex::row()
+                      .with_flex_child(
+                        Label::wrapped(
+                          archive
+                            .file_stem()
+                            .map(|stem| stem.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        )
+                        .expand_width(),
+                        1.,
+                      )
+                      .with_child(
+                        Button::new(tr("tools-restore"))
+                          .controller(HoverController)
+                          .on_activate(move |ctx, _, _| {
+                            ctx.submit_command(archive::RESTORE.with(archive.clone()))
+                          }),
+                      ),
+                  )
+                }
+              })
+              .boxed()
+          },
+        )
+        .lens(App::archived_mods),
+      )
       .with_default_spacer()
-      .with_child(ViewSwitcher::new(
-        |data: &App, _| data.settings.install_dir.is_some(),
-        move |has_dir, _, _| {
-          if *has_dir {
-            Box::new(
-              Flex::row()
-                .with_flex_child(h2("Launch Starsector").expand_width(), 2.)
-                .with_flex_child(Icon::new(PLAY_ARROW).expand_width(), 1.)
-                .padding((8., 4.))
-                .background(button_painter())
+      .with_child(h2(&tr("tools-memory")))
+      .with_child(
+        Button::new(tr("tools-estimate-ram"))
+          .controller(HoverController)
+          .on_activate(|ctx, data: &mut App, _| {
+            let Some(install_dir) = data.settings.install_dir.clone() else {
+              return;
+            };
+            let mods: Vec<(String, PathBuf)> = data
+              .mod_list
+              .mods
+              .values()
+              .filter(|entry| entry.enabled)
+              .map(|entry| (entry.id.clone(), entry.path.clone()))
+              .collect();
+            let ext_ctx = ctx.get_external_handle();
+            data.runtime.spawn_blocking(move || {
+              let mut estimate =
+                RamEstimate::new(mods.iter().map(|(id, path)| (id.as_str(), path.as_path())));
+              estimate.current_mb = VMParams::load(&install_dir)
+                .ok()
+                .map(|vmparams: VMParams| vmparams.heap_max.megabytes());
+              let _ = ext_ctx.submit_command(ram_estimate::ESTIMATED, estimate, Target::Auto);
+            });
+          })
+          .disabled_if(|data: &App, _| data.settings.install_dir.is_none())
+          .expand_width(),
+      )
+      .with_child(
+        Maybe::or_empty(|| {
+          Flex::column()
+            .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+            .with_child(Label::wrapped_func(|estimate: &RamEstimate, _| {
+              tr_args(
+                "tools-ram-estimate",
+                &[
+                  ("recommended", &estimate.recommended_value()),
+                  ("assets", &format_size(estimate.assets_mb * 1024 * 1024)),
+                  ("jars", &format_size(estimate.jars_mb * 1024 * 1024)),
+                  ("factions", &estimate.factions),
+                ],
+              )
+            }))
+            .with_child(Label::wrapped_func(|estimate: &RamEstimate, _| {
+              if estimate.heavy_mods.is_empty() {
+                String::new()
+              } else {
+                let mods = estimate
+                  .heavy_mods
+                  .iter()
+                  .cloned()
+                  .collect::<Vec<_>>()
+                  .join(", ");
+                tr_args("tools-ram-heavy", &[("mods", &mods)])
+              }
+            }))
+            .with_child(Label::wrapped_func(
+              |estimate: &RamEstimate, _| match estimate.current_mb {
+                Some(current) => tr_args(
+                  "tools-ram-current",
+                  &[("current", &format_size(current * 1024 * 1024))],
+                ),
+                None => tr("tools-ram-unknown"),
+              },
+            ))
+            .with_child(Either::new(
+              |estimate: &RamEstimate, _| estimate.too_low(),
+              Label::wrapped(tr("tools-ram-too-low")).with_text_color(RED_KEY),
+              SizedBox::empty(),
+            ))
+            .with_child(
+              Button::new(tr("tools-apply-ram"))
                 .controller(HoverController)
-                .on_click(|ctx, data: &mut App, _| {
-                  if let Some(install_dir) = data.settings.install_dir.clone() {
-                    ctx.submit_command(App::DISABLE);
-                    let ext_ctx = ctx.get_external_handle();
-                    let experimental_launch = data.settings.experimental_launch;
-                    let resolution = data.settings.experimental_resolution;
-                    data.runtime.spawn(async move {
-                      if let Err(err) =
-                        App::launch_starsector(install_dir, experimental_launch, resolution).await
-                      {
-                        dbg!(err);
-                      };
-                      ext_ctx.submit_command(App::ENABLE, (), Target::Auto)
-                    });
-                  }
+                .on_activate(|ctx, _, _| ctx.submit_command(ram_estimate::APPLY))
+                .disabled_if(|estimate: &RamEstimate, _| {
+                  estimate.current_mb.is_none()
+                    || estimate.current_mb == Some(estimate.recommended_mb)
                 })
                 .expand_width(),
             )
-          } else {
-            Box::new(SizedBox::empty())
-          }
-        },
-      ))
-      .main_axis_alignment(druid::widget::MainAxisAlignment::Start)
-      .expand()
-      .padding(20.);
-    let side_panel = Tabs::for_policy(
-      StaticTabsForked::build(vec![
-        InitialTab::new("Launch", launch_panel),
-        InitialTab::new("Tools & Filters", tool_panel),
-      ])
+        })
+        .lens(App::ram_estimate),
+      )
+      .with_default_spacer()
+      .with_child(h2(&tr("tools-bisect")))
+      .with_child(Either::new(
+        |data: &App, _| data.bisection.is_none(),
+        Flex::column()
+          .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+          .with_child(Label::wrapped(tr("tools-bisect-hint")))
+          .with_child(
+            Button::new(tr("tools-bisect-start"))
+              .controller(HoverController)
+              .on_activate(|ctx, _, _| ctx.submit_command(bisect::STEP.with(Step::Start)))
+              .disabled_if(|data: &App, _| data.settings.install_dir.is_none())
+              .expand_width(),
+          ),
+        Flex::column()
+          .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+          .with_child(Label::wrapped_func(|data: &App, _| {
+            let Some(bisection) = &data.bisection else {
+              return String::new();
+            };
+            if bisection.done() {
+              let names = bisection
+                .suspects
+                .iter()
+                .map(|id| {
+                  data
+                    .mod_list
+                    .get(id)
+                    .map_or(id, |entry| &entry.name)
+                    .clone()
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+              tr_args("tools-bisect-found", &[("mods", &names)])
+            } else {
+              tr_args(
+                "tools-bisect-step",
+                &[
+                  ("step", &bisection.step),
+                  ("testing", &bisection.testing.len()),
+                  ("suspects", &bisection.suspects.len()),
+                ],
+              )
+            }
+          }))
+          .with_child(Either::new(
+            |data: &App, _| data.bisection.as_ref().is_some_and(Bisection::done),
+            Button::new(tr("tools-bisect-finish"))
+              .controller(HoverController)
+              .on_activate(|ctx, _, _| ctx.submit_command(bisect::STEP.with(Step::Finish)))
+              .expand_width(),
+            Flex::row()
+              .with_flex_child(
+                Button::new(tr("tools-bisect-launch"))
+                  .controller(HoverController)
+                  .on_activate(|ctx, _, _| ctx.submit_command(App::LAUNCH.with(false)))
+                  .expand_width(),
+                1.,
+              )
+              .with_flex_child(
+                Button::new(tr("tools-bisect-crashed"))
+                  .controller(HoverController)
+                  .on_activate(|ctx, _, _| ctx.submit_command(bisect::STEP.with(Step::Crashed)))
+                  .expand_width(),
+                1.,
+              )
+              .with_flex_child(
+                Button::new(tr("tools-bisect-worked"))
+                  .controller(HoverController)
+                  .on_activate(|ctx, _, _| ctx.submit_command(bisect::STEP.with(Step::Worked)))
+                  .expand_width(),
+                1.,
+              ),
+          ))
+          .with_child(
+            Button::new(tr("tools-bisect-stop"))
+              .controller(HoverController)
+              .on_activate(|ctx, _, _| ctx.submit_command(bisect::STEP.with(Step::Stop)))
+              .expand_width(),
+          ),
+      ))
+      .with_default_spacer()
+      .with_child(h2(&tr("tools-share")))
+      .with_child(Label::wrapped(tr("tools-share-hint")))
+      .with_child(
+        Flex::row()
+          .with_flex_child(
+            Button::new(tr("tools-share-bbcode"))
+              .controller(HoverController)
+              .on_activate(|_, data: &mut App, _| data.copy_mod_list(ShareFormat::BBCode))
+              .expand_width(),
+            1.,
+          )
+          .with_flex_child(
+            Button::new(tr("tools-share-markdown"))
+              .controller(HoverController)
+              .on_activate(|_, data: &mut App, _| data.copy_mod_list(ShareFormat::Markdown))
+              .expand_width(),
+            1.,
+          ),
+      )
+      .with_default_spacer()
+      .with_child(h2(&tr("tools-install-history")))
+      .with_child(Label::wrapped(tr("tools-install-history-hint")))
+      .with_child(
+        Button::new(tr("tools-install-history-view"))
+          .controller(HoverController)
+          .on_activate(|ctx, _, env| {
+            Self::install_history_modal().show_with_size(ctx, env, &(), (600., 500.));
+          })
+          .expand_width(),
+      )
+      .with_default_spacer()
+      .with_child(h2(&tr("tools-developer")))
+      .with_child(
+        Button::new(tr("tools-edit-mod-info"))
+          .controller(HoverController)
+          .on_activate(|ctx, data: &mut App, env| {
+            if let Some(entry) = data
+              .active
+              .as_ref()
+              .and_then(|path| data.mod_list.mods.get(path))
+            {
+              ModInfoForm::modal(entry).show_with_size(
+                ctx,
+                env,
+                &ModInfoForm::new(entry.clone()),
+                (500., 600.),
+              );
+            }
+          })
+          .disabled_if(|data: &App, _| data.active.is_none())
+          .expand_width(),
+      )
+      .with_spacer(5.)
+      .with_child(
+        Button::new(tr("tools-create-mod"))
+          .controller(HoverController)
+          .on_activate(|ctx, data: &mut App, env| {
+            if let Some(install_dir) = &data.settings.install_dir {
+              let game_version = ModList::starsector_version
+                .get(&data.mod_list)
+                .as_ref()
+                .and_then(get_quoted_version);
+              SkeletonForm::modal().show_with_size(
+                ctx,
+                env,
+                &SkeletonForm::new(util::mods_dir(install_dir), game_version),
+                (500., 450.),
+              );
+            }
+          })
+          .disabled_if(|data: &App, _| data.settings.install_dir.is_none())
+          .expand_width(),
+      )
+      .with_default_spacer()
+      .with_child(h2(&tr("tools-filters")))
+      .with_child(
+        Flex::row()
+          .with_child(Button2::from_label(tr("filters-presets")).on_click2(
+            |ctx, mouse, data: &mut App, _| {
+              let menu = Self::filter_presets_menu(&data.settings.filter_presets);
+              ctx.show_context_menu::<App>(menu, ctx.to_window(mouse.pos))
+            },
+          ))
+          .with_default_spacer()
+          .with_flex_child(
+            TextBox::new()
+              .with_placeholder(tr("filters-preset-name"))
+              .lens(App::mod_list.then(ModList::preset_name))
+              .expand_width(),
+            1.,
+          )
+          .with_default_spacer()
+          .with_child(
+            Button::new(tr("filters-preset-save"))
+              .controller(HoverController)
+              .on_activate(|_, data: &mut App, _| data.save_filter_preset())
+              .disabled_if(|data: &App, _| data.mod_list.preset_name.trim().is_empty()),
+          ),
+      )
+      .tap_mut(|panel| {
+        for filter in Filters::iter() {
+          match filter {
+            Filters::Enabled => panel.add_child(h3(&tr("filters-status"))),
+            Filters::Unimplemented => panel.add_child(h3(&tr("filters-version-checker"))),
+            Filters::AutoUpdateAvailable => panel.add_child(h3(&tr("filters-auto-update"))),
+            Filters::InstalledWeek => panel.add_child(h3(&tr("filters-smart"))),
+            _ => {}
+          };
+          let smart = filter.is_smart();
+          // driven by the mod list's filters rather than kept by the checkbox, so applying a
+          // preset ticks the right boxes
+          panel.add_child(
+            Checkbox::from_label(Label::wrapped(filter.label()))
+              .on_change(move |ctx, _, new, _| {
+                ctx.submit_command(ModList::FILTER_UPDATE.with((filter, *new == smart)))
+              })
+              .lens(App::mod_list.then(filter.ticked())),
+          )
+        }
+      })
+      .with_child(h3(&tr("filters-saved-queries")))
+      .with_child(
+        ViewSwitcher::new(
+          |app: &App, _| app.settings.saved_queries.clone(),
+          |queries, _, _| {
+            Flex::column()
+              .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+              .tap_mut(|column| {
+                for query in queries.iter().cloned() {
+                  let toggled = query.clone();
+                  column.add_child(
+                    Flex::row()
+                      .with_flex_child(
+                        Checkbox::from_label(Label::wrapped(query.clone()))
+                          .on_change(move |ctx, _, new, _| {
+                            ctx.submit_command(
+                              ModList::SAVED_QUERY_UPDATE.with((toggled.clone(), *new)),
+                            )
+                          })
+                          .lens(App::mod_list.then(ModList::query_active(query.clone())))
+                          .expand_width(),
+                        1.,
+                      )
+                      .with_child(
+                        Icon::new(CLOSE)
+                          .controller(HoverController)
+                          .on_activate(move |ctx, data: &mut Vector<String>, _| {
+                            data.retain(|existing| existing != &query);
+                            ctx.submit_command(
+                              ModList::SAVED_QUERY_UPDATE.with((query.clone(), false)),
+                            )
+                          })
+                          .labelled(tr("saved-query-remove"))
+                          .lens(App::settings.then(Settings::saved_queries)),
+                      ),
+                  )
+                }
+              })
+              .boxed()
+          },
+        )
+        .on_change(|_, old, data: &mut App, _| {
+          if old.settings.saved_queries != data.settings.saved_queries
+            && let Err(err) = data.settings.save()
+          {
+            error!("Failed to save settings {:?}", err)
+          }
+        }),
+      )
+      .padding(20.);
+    let launch_panel = Flex::column()
+      .with_child(make_column_pair(
+        h2(&tr("launch-starsector-version")),
+        Maybe::new(
+          || Label::wrapped_func(|v: &String, _| v.clone()),
+          || Label::new(tr("launch-unknown")),
+        )
+        .lens(
+          App::mod_list
+            .then(ModList::starsector_version)
+            .map(|v| v.as_ref().and_then(get_quoted_version), |_, _| {}),
+        ),
+      ))
+      .with_default_spacer()
+      .with_child(install_dir_browser)
+      .with_default_spacer()
+      .with_child(ViewSwitcher::new(
+        |data: &App, _| data.settings.install_dir.is_some(),
+        move |has_dir, _, _| {
+          if *has_dir {
+            Box::new(
+              Flex::row()
+                .with_flex_child(h2(&tr("launch-start")).expand_width(), 2.)
+                .with_flex_child(Icon::new(PLAY_ARROW).expand_width(), 1.)
+                .padding((8., 4.))
+                .background(button_painter())
+                .controller(HoverController)
+                .on_activate(|ctx, _, _| ctx.submit_command(App::LAUNCH.with(false)))
+                .expand_width(),
+            )
+          } else {
+            Box::new(SizedBox::empty())
+          }
+        },
+      ))
+      .with_default_spacer()
+      .with_child(
+        Button::new(tr("launch-check"))
+          .controller(HoverController)
+          .on_activate(|ctx, _, _| ctx.submit_command(App::CHECK_ENABLED_MODS))
+          .disabled_if(|data: &App, _| data.settings.install_dir.is_none())
+          .expand_width(),
+      )
+      .main_axis_alignment(druid::widget::MainAxisAlignment::Start)
+      .expand()
+      .padding(20.);
+    let side_panel = Tabs::for_policy(
+      StaticTabsForked::build(vec![
+        InitialTab::new(tr("tab-launch"), launch_panel),
+        InitialTab::new(tr("tab-tools"), tool_panel),
+      ])
       .set_label_height(40.0),
-    );
+    )
+    .controller(SidePanelTabController);
 
-    Flex::column()
+    let main = Flex::column()
       .with_child(Either::new(
         |app: &App, _| app.webview.is_none(),
         Flex::row()
@@ -517,7 +1459,7 @@ impl App {
           .with_child(
             ViewSwitcher::new(
               |len: &usize, _| *len,
-              |len, _, _| Box::new(h3(&format!("Installed: {}", len))),
+              |len, _, _| Box::new(h3(&tr_args("nav-installed", &[("count", len)]))),
             )
             .lens(App::mod_list.then(ModList::mods).compute(|data| data.len())),
           )
@@ -525,7 +1467,7 @@ impl App {
           .with_child(
             ViewSwitcher::new(
               |len: &usize, _| *len,
-              |len, _, _| Box::new(h3(&format!("Active: {}", len))),
+              |len, _, _| Box::new(h3(&tr_args("nav-active", &[("count", len)]))),
             )
             .lens(
               App::mod_list
@@ -538,13 +1480,13 @@ impl App {
         Flex::row()
           .with_child(
             Flex::row()
-              .with_child(Label::new("Mod Index").with_text_size(18.))
+              .with_child(Label::new(tr("nav-mod-index")).with_text_size(theme::NAV_TEXT_SIZE))
               .with_spacer(5.)
               .with_child(Icon::new(NAVIGATE_NEXT))
               .padding((8., 4.))
               .background(button_painter())
               .controller(HoverController)
-              .on_click(|_, data: &mut App, _| {
+              .on_activate(|_, data: &mut App, _| {
                 if let Some(webview) = &data.webview {
                   if webview.url().as_str() != FRACTAL_INDEX {
                     webview.load_url(FRACTAL_INDEX)
@@ -555,13 +1497,13 @@ impl App {
           .with_spacer(10.)
           .with_child(
             Flex::row()
-              .with_child(Label::new("Mods Subforum").with_text_size(18.))
+              .with_child(Label::new(tr("nav-mods-subforum")).with_text_size(theme::NAV_TEXT_SIZE))
               .with_spacer(5.)
               .with_child(Icon::new(NAVIGATE_NEXT))
               .padding((8., 4.))
               .background(button_painter())
               .controller(HoverController)
-              .on_click(|_, data: &mut App, _| {
+              .on_activate(|_, data: &mut App, _| {
                 if let Some(webview) = &data.webview {
                   if webview.url().as_str() != FRACTAL_MODS_FORUM {
                     webview.load_url(FRACTAL_MODS_FORUM)
@@ -572,13 +1514,15 @@ impl App {
           .with_spacer(10.)
           .with_child(
             Flex::row()
-              .with_child(Label::new("Modding Subforum").with_text_size(18.))
+              .with_child(
+                Label::new(tr("nav-modding-subforum")).with_text_size(theme::NAV_TEXT_SIZE),
+              )
               .with_spacer(5.)
               .with_child(Icon::new(NAVIGATE_NEXT))
               .padding((8., 4.))
               .background(button_painter())
               .controller(HoverController)
-              .on_click(|_, data: &mut App, _| {
+              .on_activate(|_, data: &mut App, _| {
                 if let Some(webview) = &data.webview {
                   if webview.url().as_str() != FRACTAL_MODDING_SUBFORUM {
                     webview.load_url(FRACTAL_MODDING_SUBFORUM)
@@ -589,13 +1533,13 @@ impl App {
           .with_flex_spacer(1.0)
           .with_child(
             Flex::row()
-              .with_child(Label::new("Close Mod Browser").with_text_size(18.))
+              .with_child(Label::new(tr("nav-close-browser")).with_text_size(theme::NAV_TEXT_SIZE))
               .with_spacer(5.)
               .with_child(Icon::new(CLOSE))
               .padding((8., 4.))
               .background(button_painter())
               .controller(HoverController)
-              .on_click(|ctx, data: &mut App, _| {
+              .on_activate(|ctx, data: &mut App, _| {
                 data
                   .webview
                   .as_mut()
@@ -610,6 +1554,7 @@ impl App {
         Split::columns(mod_list, side_panel)
           .split_point(0.8)
           .draggable(true)
+          .controller(SidePanelSplitController)
           .expand_height()
           .on_event(|ctx, event, _| {
             if let Event::Command(cmd) = event {
@@ -626,7 +1571,18 @@ impl App {
       .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
       .with_flex_child(mod_description, 1.0)
       .must_fill_main_axis(true)
+      .expand();
+
+    Stack::new()
+      .with_child(main)
+      .with_positioned_child(
+        Toast::stack().lens(App::toasts),
+        StackChildPosition::new().bottom(Some(20.)).right(Some(20.)),
+      )
+      .env_scope(App::apply_theme)
       .controller(AppController)
+      .controller(ClipboardController::default())
+      .controller(LayoutController)
       .with_id(WidgetId::reserved(0))
   }
 
@@ -666,7 +1622,9 @@ impl App {
       let executable = install_dir.join("jre_linux/bin/java");
 
       #[cfg(target_os = "windows")]
-      let current_dir = install_dir.join("starsector-core");
+      let current_dir = util::find_game_file(install_dir, "starfarer_obf.jar")
+        .and_then(|jar| jar.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| install_dir.join("starsector-core"));
       #[cfg(target_os = "linux")]
       let current_dir = install_dir.clone();
 
@@ -726,6 +1684,70 @@ impl App {
     })
   }
 
+  /// Problems with the enabled mods, see [`launch_check`].
+  fn launch_problems(&self) -> Vec<launch_check::Problem> {
+    let vmparams = self.settings.vmparams.clone().or_else(|| {
+      self
+        .settings
+        .install_dir
+        .as_ref()
+        .and_then(|install_dir| settings::vmparams::VMParams::load(install_dir).ok())
+    });
+    let ram = vmparams.map(|vmparams| {
+      let mut estimate = RamEstimate::new(
+        self
+          .mod_list
+          .mods
+          .values()
+          .filter(|entry| entry.enabled)
+          .map(|entry| (entry.id.as_str(), entry.path.as_path())),
+      );
+      estimate.current_mb = Some(vmparams.heap_max.megabytes());
+      estimate
+    });
+
+    launch_check::check(
+      self.mod_list.used(),
+      ModList::starsector_version.get(&self.mod_list).as_ref(),
+      self.mod_list.compat(),
+      ram.as_ref(),
+    )
+  }
+
+  fn launch_check_window(
+    &self,
+    problems: &[launch_check::Problem],
+    launch: bool,
+  ) -> WindowDesc<App> {
+    let modal = Modal::<App>::new(&tr("launch-check-title")).pipe(|mut modal| {
+      if problems.is_empty() {
+        modal = modal.with_content(tr("launch-check-none"))
+      }
+      for problem in problems {
+        modal = modal.with_content(format!("• {}", problem.describe()))
+      }
+      for (library, _) in known_libraries::missing(self.mod_list.mods.values()) {
+        modal = modal.with_button(
+          &tr_args("library-install", &[("library", &library.name)]),
+          known_libraries::INSTALL.with(library),
+        )
+      }
+      modal
+    });
+    let modal = if launch {
+      modal
+        .with_button(&tr("launch-check-launch-anyway"), App::LAUNCH.with(true))
+        .with_close_label(&tr("launch-check-cancel"))
+    } else {
+      modal.with_close()
+    };
+
+    WindowDesc::new(modal.build())
+      .window_size((500., 300.))
+      .show_titlebar(false)
+      .set_level(WindowLevel::AppWindow)
+  }
+
   fn log_message(&mut self, message: &str) {
     self
       .log
@@ -738,14 +1760,94 @@ impl App {
     }
   }
 
-  fn push_duplicate(&mut self, duplicates: &(Arc<ModEntry>, Arc<ModEntry>)) {
-    self.duplicate_log.push_back(duplicates.clone())
+  fn external_mod_error(entry: &ModEntry) -> anyhow::Error {
+    anyhow::anyhow!(
+      "{} is in the external mod folder {}, remove it from there instead",
+      entry.name,
+      entry.path.parent().unwrap_or(&entry.path).to_string_lossy()
+    )
+  }
+
+  /// Fails for external mods outside of the mod library, which have to be removed from their own
+  /// folder instead.
+  fn removable(entry: &ModEntry) -> anyhow::Result<()> {
+    if entry.external && !library::contains(&entry.path) {
+      return Err(App::external_mod_error(entry));
+    }
+
+    Ok(())
+  }
+
+  /// Deletes a mod the user asked to remove through [`App::remove_mod_folder`], refusing if it
+  /// isn't [`App::removable`] and recording it in the install log.
+  fn delete_mod_folder(install_dir: Option<&Path>, entry: &ModEntry) -> anyhow::Result<()> {
+    App::removable(entry)?;
+    App::remove_mod_folder(install_dir, entry)?;
+    if let Err(err) = install_log::record(&install_log::Entry::deleted(entry)) {
+      error!("Failed to record deleting {}: {}", entry.id, err)
+    }
+
+    Ok(())
+  }
+
+  /// Deletes a mod's folder, unlinking it from the mods folder first if it's an external mod.
+  fn remove_mod_folder(install_dir: Option<&Path>, entry: &ModEntry) -> anyhow::Result<()> {
+    App::unlink_external(install_dir, entry)?;
+
+    remove_dir_all(&entry.path)
+      .map_err(|err| anyhow::anyhow!("Failed to delete {}: {}", entry.path.to_string_lossy(), err))
+  }
+
+  /// Like [`App::remove_mod_folder`], but moves the folder into the trash so deleting it can be
+  /// undone, returning where it went. Nothing is deleted if it can't be moved.
+  fn trash_mod_folder(install_dir: Option<&Path>, entry: &ModEntry) -> anyhow::Result<PathBuf> {
+    App::unlink_external(install_dir, entry)?;
+
+    history::trash(&entry.path).map_err(|err| {
+      anyhow::anyhow!(
+        "Could not move {} to the trash, so it was not deleted: {}",
+        entry.path.to_string_lossy(),
+        err
+      )
+    })
+  }
+
+  fn unlink_external(install_dir: Option<&Path>, entry: &ModEntry) -> anyhow::Result<()> {
+    if let Some(install_dir) = install_dir
+      && entry.external
+    {
+      link_farm::sync(
+        install_dir,
+        [Arc::new(entry.clone().tap_mut(|entry| entry.enabled = false))].iter(),
+      )?;
+    }
+
+    Ok(())
+  }
+
+  /// Adds a duplicate to the log, grouping every copy of the same mod together.
+  fn push_duplicate(&mut self, (existing, duplicate): &(Arc<ModEntry>, Arc<ModEntry>)) {
+    if let Some(copies) = self
+      .duplicate_log
+      .iter_mut()
+      .find(|copies| copies.front().is_some_and(|first| first.id == existing.id))
+    {
+      for entry in [existing, duplicate] {
+        if !copies.iter().any(|copy| copy.path == entry.path) {
+          copies.push_back(entry.clone())
+        }
+      }
+    } else {
+      self
+        .duplicate_log
+        .push_back(Vector::from(vec![existing.clone(), duplicate.clone()]))
+    }
   }
 }
 
 enum AppCommands {
   OpenSettings,
-  UpdateModDescription(String),
+  UpdateModDescription(PathBuf),
 }
 
 #[derive(Default)]
@@ -757,21 +1859,32 @@ pub struct AppDelegate {
   overwrite_window: Option<WindowId>,
   duplicate_window: Option<WindowId>,
   download_window: Option<WindowId>,
-  mega_file: Option<(File, PathBuf)>,
+  install_window: Option<WindowId>,
+  blob_download: Option<BlobDownload>,
+  update_sweep: Option<update_check::Sweep>,
+  /// Whether mods are being updated automatically, so a sweep finishing meanwhile doesn't start
+  /// updating them again.
+  auto_updating: bool,
+  /// Open [`Popup::Critical`] windows, raised again whenever any other window opens, be it a
+  /// popup, a subwindow or a modal.
+  critical_popups: Vec<WindowId>,
 }
 
 impl Delegate<App> for AppDelegate {
   fn window_added(
     &mut self,
-    _id: WindowId,
+    id: WindowId,
     handle: druid::WindowHandle,
     _data: &mut App,
     _env: &Env,
-    _ctx: &mut DelegateCtx,
+    ctx: &mut DelegateCtx,
   ) {
     if self.root_window.is_none() {
       self.root_window = Some(handle);
     }
+    if !self.critical_popups.contains(&id) {
+      self.raise_critical_popups(ctx);
+    }
   }
 
   fn command(
@@ -796,7 +1909,17 @@ impl Delegate<App> for AppDelegate {
             );
 
           let settings_window =
-            WindowDesc::new(settings::Settings::ui_builder().lens(App::settings))
+            WindowDesc::new(
+              settings::Settings::ui_builder()
+                .lens(App::settings)
+                .on_change(|_, old, data: &mut App, _| {
+                  if old.settings.game_version_override != data.settings.game_version_override {
+                    let overridden = data.settings.version_override();
+                    data.mod_list.set_version_override(overridden)
+                  }
+                })
+                .env_scope(App::apply_theme),
+            )
               .window_size((800., 400.))
               .show_titlebar(false);
 
@@ -805,8 +1928,8 @@ impl Delegate<App> for AppDelegate {
           ctx.new_window(settings_window);
           return Handled::Yes;
         }
-        AppCommands::UpdateModDescription(desc) => {
-          data.active = Some(desc.clone());
+        AppCommands::UpdateModDescription(path) => {
+          data.active = Some(path.clone());
 
           return Handled::Yes;
         }
@@ -818,12 +1941,17 @@ impl Delegate<App> for AppDelegate {
         data.settings.dirty = false;
         data.settings.install_dir_buf = new_install_dir.to_string_lossy().to_string();
         data.settings.install_dir = Some(new_install_dir.clone());
+        data.settings.install_dir_confirmed = false;
+
+        if !Settings::is_starsector_install(new_install_dir) {
+          Self::show_install_dir_warning(ctx, new_install_dir);
+        }
 
         if data.settings.save().is_err() {
-          eprintln!("Failed to save settings")
+          error!("Failed to save settings")
         };
 
-        data.mod_list.mods.clear();
+        data.mod_list.clear();
         data.runtime.spawn(get_starsector_version(
           ctx.get_external_handle(),
           new_install_dir.clone(),
@@ -831,109 +1959,526 @@ impl Delegate<App> for AppDelegate {
         data.runtime.spawn(ModList::parse_mod_folder(
           ctx.get_external_handle(),
           Some(new_install_dir.clone()),
+          data.settings.linked_mod_dirs(),
         ));
       }
       return Handled::Yes;
-    } else if let Some(entry) = cmd.get(ModList::AUTO_UPDATE) {
-      ctx.submit_command(App::LOG_MESSAGE.with(format!("Begin auto-update of {}", entry.name)));
-      data
-        .runtime
-        .spawn(installer::Payload::Download(entry.clone()).install(
-          ctx.get_external_handle(),
-          data.settings.install_dir.clone().unwrap(),
-          data.mod_list.mods.values().map(|v| v.id.clone()).collect(),
-        ));
-    } else if let Some(()) = cmd.get(App::REFRESH) {
-      if let Some(install_dir) = data.settings.install_dir.as_ref() {
-        data.mod_list.mods.clear();
-        data.runtime.spawn(ModList::parse_mod_folder(
-          ctx.get_external_handle(),
-          Some(install_dir.clone()),
-        ));
-      }
-    } else if let Some(res) = cmd.get(GET_INSTALLED_STARSECTOR) {
-      App::mod_list
-        .then(ModList::starsector_version)
-        .put(data, res.as_ref().ok().cloned());
-    } else if let Some(name) = cmd.get(App::LOG_SUCCESS) {
-      data.log_message(&format!("Successfully installed {}", name));
-      self.display_if_closed(ctx, SubwindowType::Log);
+    } else if let Some(SettingsCommand::UpdatePickerHistory(picker, dir)) =
+      cmd.get(settings::Settings::SELECTOR)
+    {
+      data.settings.last_browsed = Some(dir.clone());
+      data.settings.picker_history.insert(*picker, dir.clone());
+      if data.settings.save().is_err() {
+        error!("Failed to save settings")
+      };
 
       return Handled::Yes;
-    } else if let Some(()) = cmd.get(App::CLEAR_LOG) {
-      data.log.clear();
+    } else if let Some(SettingsCommand::AddToken(domain, token)) =
+      cmd.get(settings::Settings::SELECTOR)
+    {
+      let domain = secrets::normalise_domain(domain);
+      if domain.is_empty() || token.trim().is_empty() {
+        return Handled::Yes;
+      }
+      if !secrets::set(&domain, token.trim()) {
+        ctx.submit_command(App::LOG_MESSAGE.with(tr_args(
+          "log-token-not-saved",
+          &[("domain", &domain)],
+        )));
+      }
+      if !data.settings.token_domains.contains(&domain) {
+        data.settings.token_domains.push_back(domain);
+      }
+      if data.settings.save().is_err() {
+        error!("Failed to save settings")
+      };
 
       return Handled::Yes;
-    } else if let Some((name, err)) = cmd.get(App::LOG_ERROR) {
-      data.log_message(&format!("Failed to install {}. Error: {}", name, err));
-      self.display_if_closed(ctx, SubwindowType::Log);
+    } else if let Some(SettingsCommand::RemoveToken(domain)) = cmd.get(settings::Settings::SELECTOR)
+    {
+      secrets::remove(domain);
+      data.settings.token_domains.retain(|existing| existing != domain);
+      if data.settings.save().is_err() {
+        error!("Failed to save settings")
+      };
 
       return Handled::Yes;
-    } else if let Some(message) = cmd.get(App::LOG_MESSAGE) {
-      data.log_message(message);
-      self.display_if_closed(ctx, SubwindowType::Log);
+    } else if let Some(SettingsCommand::AddExternalModDir(dir)) =
+      cmd.get(settings::Settings::SELECTOR)
+    {
+      if !data.settings.external_mod_dirs.contains(dir) {
+        data.settings.external_mod_dirs.push_back(dir.clone());
+        if data.settings.save().is_err() {
+          error!("Failed to save settings")
+        };
+        ctx.submit_command(App::REFRESH);
+      }
 
       return Handled::Yes;
-    } else if let Some(message) = cmd.get(App::LOG_OVERWRITE) {
-      data.push_overwrite(message.clone());
-      self.display_if_closed(ctx, SubwindowType::Overwrite);
+    } else if let Some(SettingsCommand::RemoveExternalModDir(dir)) =
+      cmd.get(settings::Settings::SELECTOR)
+    {
+      // unlink that folder's mods before they drop out of the mod list
+      if let Some(install_dir) = &data.settings.install_dir {
+        let unlinked: Vec<Arc<ModEntry>> = data
+          .mod_list
+          .mods
+          .values()
+          .filter(|entry| entry.external && entry.path.starts_with(dir))
+          .map(|entry| Arc::new((**entry).clone().tap_mut(|entry| entry.enabled = false)))
+          .collect();
+        if let Err(err) = link_farm::sync(install_dir, unlinked.iter()) {
+          ctx.submit_command(Popup::SHOW.with(Popup::Error(err)));
+        }
+      }
+      data.settings.external_mod_dirs.retain(|existing| existing != dir);
+      if data.settings.save().is_err() {
+        error!("Failed to save settings")
+      };
+      ctx.submit_command(App::REFRESH);
 
       return Handled::Yes;
-    } else if let Some(ovewrite_all) = cmd.get(App::CLEAR_OVERWRITE_LOG) {
-      if *ovewrite_all {
-        for val in &data.overwrite_log {
-          let (conflict, to_install, entry) = val.as_ref();
-          ctx.submit_command(ModList::OVERWRITE.with((
-            match conflict {
-              StringOrPath::String(id) => data.mod_list.mods.get(id).unwrap().path.clone(),
-              StringOrPath::Path(path) => path.clone(),
-            },
-            to_install.clone(),
-            entry.clone(),
-          )))
+    } else if let Some(SettingsCommand::MoveModsToLibrary) = cmd.get(settings::Settings::SELECTOR) {
+      if let Some(install_dir) = &data.settings.install_dir
+        && let Some(library) = data.settings.mod_library()
+      {
+        match library::move_into(install_dir, &library, data.mod_list.mods.values()) {
+          Ok(count) => data.log_message(&tr_args(
+            "settings-mod-library-moved",
+            &[("count", &count.to_string())],
+          )),
+          Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Error(err))),
         }
+        ctx.submit_command(App::REFRESH);
       }
-      data.overwrite_log.clear();
 
       return Handled::Yes;
-    } else if let Some(overwrite_entry) = cmd.get(App::REMOVE_OVERWRITE_LOG_ENTRY) {
-      data.overwrite_log.retain(|val| val.0 != *overwrite_entry);
-      if data.overwrite_log.is_empty() {
-        if let Some(id) = self.overwrite_window.take() {
-          ctx.submit_command(commands::CLOSE_WINDOW.to(id))
+    } else if let Some(SettingsCommand::ReportProblem) = cmd.get(settings::Settings::SELECTOR) {
+      match report::build(&data.settings, data.mod_list.mods.values()) {
+        Ok(bundle) => {
+          info!("Wrote problem report to {}", bundle.to_string_lossy());
+          let _ = opener::open(report::report_dir());
+          if opener::open(report::issue_url(&bundle)).is_err() {
+            error!("Failed to open GitHub");
+          }
         }
+        Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Error(
+          err.context("Failed to write the problem report"),
+        ))),
       }
 
       return Handled::Yes;
-    } else if let Some(duplicates) = cmd.get(ModList::DUPLICATE) {
-      data.push_duplicate(duplicates);
-      self.display_if_closed(ctx, SubwindowType::Duplicate);
+    } else if let Some(SettingsCommand::ConfirmInstallDir) = cmd.get(settings::Settings::SELECTOR) {
+      data.settings.install_dir_confirmed = true;
+      if data.settings.save().is_err() {
+        error!("Failed to save settings")
+      };
 
       return Handled::Yes;
-    } else if let Some((delete_path, keep_entry)) = cmd.get(App::DELETE_AND_SUMBIT) {
-      let ext_ctx = ctx.get_external_handle();
-      let delete_path = delete_path.clone();
-      let keep_entry = keep_entry.clone();
+    } else if let Some(SettingsCommand::UpdateCacheDir(new_cache_dir)) =
+      cmd.get(settings::Settings::SELECTOR)
+    {
+      if &data.settings.cache_dir != new_cache_dir {
+        data.settings.cache_dir = new_cache_dir.clone();
+        if let Err(err) = std::fs::create_dir_all(data.settings.cache_dir()) {
+          error!("Failed to create cache dir {:?}", err)
+        }
+        if data.settings.save().is_err() {
+          error!("Failed to save settings")
+        };
+      }
+      return Handled::Yes;
+    } else if let Some(SettingsCommand::UpdateDownloadDir(new_download_dir)) =
+      cmd.get(settings::Settings::SELECTOR)
+    {
+      if &data.settings.download_dir != new_download_dir {
+        data.settings.download_dir = new_download_dir.clone();
+        if data.settings.save().is_err() {
+          error!("Failed to save settings")
+        };
+      }
+
+      return Handled::Yes;
+    } else if let Some(entry) = cmd.get(ModList::AUTO_UPDATE) {
+      if !data.settings.install_dir_usable() {
+        if let Some(install_dir) = &data.settings.install_dir {
+          Self::show_install_dir_warning(ctx, install_dir);
+        }
+        return Handled::Yes;
+      }
+      ctx.submit_command(App::LOG_MESSAGE.with(tr_args(
+        "log-auto-update-started",
+        &[("name", &entry.name)],
+      )));
+      data
+        .runtime
+        .spawn(installer::Payload::Download(entry.clone()).install(
+          ctx.get_external_handle(),
+          data.settings.install_dir.clone().unwrap(),
+          data.settings.cache_dir(),
+          data.mod_list.mods.values().map(|v| v.id.clone()).collect(),
+        ));
+    } else if let Some((path, metadata)) = cmd.get(ModMetadata::SAVE_MOD_METADATA) {
+      if let Some(mut entry) = data.mod_list.mods.get(path).cloned() {
+        ModEntry::manager_metadata
+          .in_arc()
+          .put(&mut entry, metadata.clone());
+        let ext_ctx = ctx.get_external_handle();
+        let metadata = metadata.clone();
+        let path = entry.path.clone();
+        data.runtime.spawn(async move {
+          if let Err(err) = metadata.save(&path).await {
+            let _ = ext_ctx.submit_command(
+              App::LOG_MESSAGE,
+              tr_args(
+                "log-save-metadata-failed",
+                &[("path", &path.to_string_lossy()), ("err", &err)],
+              ),
+              Target::Auto,
+            );
+          }
+        });
+        data.mod_list.mods.insert(path.clone(), entry);
+      }
+
+      return Handled::Yes;
+    } else if let Some(entry) = cmd.get(ModEntry::CHECK_FOR_UPDATE) {
+      if let Some(version_checker) = entry.version_checker.clone() {
+        if let Some(existing) = data.mod_list.mods.get_mut(&entry.path) {
+          Arc::make_mut(existing).checking_update = true;
+        }
+        data.runtime.spawn(util::get_master_version(
+          ctx.get_external_handle(),
+          version_checker,
+        ));
+      }
+
+      return Handled::Yes;
+    } else if let Some(estimate) = cmd.get(ram_estimate::ESTIMATED) {
+      data.ram_estimate = Some(estimate.clone());
+
+      return Handled::Yes;
+    } else if cmd.is(ram_estimate::APPLY) {
+      if let Some(install_dir) = data.settings.install_dir.clone()
+        && let Some(estimate) = data.ram_estimate.as_mut()
+      {
+        let res = data
+          .settings
+          .vmparams
+          .clone()
+          .map_or_else(|| VMParams::load(&install_dir), Ok)
+          .map_err(|err| anyhow::anyhow!("Failed to read vmparams: {:?}", err))
+          .and_then(|mut vmparams| {
+            estimate.apply(&mut vmparams);
+            vmparams
+              .save(&install_dir)
+              .map_err(|err| anyhow::anyhow!("Failed to save vmparams: {:?}", err))?;
+            Ok(vmparams)
+          });
+        match res {
+          Ok(vmparams) => {
+            estimate.current_mb = Some(vmparams.heap_max.megabytes());
+            data.log_message(&format!("Set -Xmx to {}", vmparams.heap_max));
+            if data.settings.vmparams.is_some() {
+              data.settings.vmparams = Some(vmparams)
+            }
+          }
+          Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Error(err))),
+        }
+      }
+
+      return Handled::Yes;
+    } else if let Some(failures) = cmd.get(ModList::PARSE_FAILURES) {
+      data.problem_mods = failures.clone();
+
+      return Handled::Yes;
+    } else if let Some(progress) = cmd.get(ModList::SCAN_STARTED) {
+      data.scan_progress = Some(progress.clone());
+
+      return Handled::Yes;
+    } else if let Some(()) = cmd.get(ModList::SCAN_FINISHED) {
+      data.scan_progress = None;
+
+      return Handled::Yes;
+    } else if let Some(count) = cmd.get(update_check::SWEEP_STARTED) {
+      self.update_sweep = update_check::Sweep::new(*count);
+      data.update_check_progress = self.update_sweep.as_ref().map(update_check::Sweep::progress);
+
+      return Handled::Yes;
+    } else if let Some(results) = cmd.get(update_check::SWEEP_RESULTS) {
+      let with_updates: Vec<String> = results
+        .iter()
+        .filter(|(id, result)| data.mod_list.apply_remote_version(id, result))
+        .map(|(id, _)| id.clone())
+        .collect();
+      if let Some(sweep) = self.update_sweep.as_mut()
+        && let Some(updates) = sweep.record(results.len(), with_updates.into_iter())
+      {
+        self.update_sweep = None;
+        data.update_check_progress = None;
+        let automatic: Vec<Arc<ModEntry>> = data
+          .mod_list
+          .mods
+          .values()
+          .filter(|entry| entry.auto_update_due())
+          .cloned()
+          .collect();
+        let updates = if !automatic.is_empty() && self.auto_updating {
+          ctx.submit_command(App::LOG_MESSAGE.with(tr("auto-update-skipped")));
+
+          updates
+        } else if !automatic.is_empty() && data.settings.install_dir_usable() {
+          self.auto_updating = true;
+          let names: Vec<&str> = automatic.iter().map(|entry| entry.name.as_str()).collect();
+          ctx.submit_command(App::LOG_MESSAGE.with(tr_args(
+            "auto-update-started",
+            &[("mods", &names.join(", "))],
+          )));
+          let updates = updates.saturating_sub(automatic.len());
+          let progress = Progress::new(progress::Unit::Mods);
+          progress.set_total(automatic.len() as u64);
+          ctx.submit_command(
+            Popup::SHOW.with(Popup::Progress(tr("auto-update-title"), progress.clone())),
+          );
+          let ext_ctx = ctx.get_external_handle();
+          let cache_dir = data.settings.cache_dir();
+          data.runtime.spawn(async move {
+            let outcomes =
+              installer::auto_update(ext_ctx.clone(), automatic, cache_dir, &progress).await;
+            let _ = ext_ctx.submit_command(update_check::AUTO_UPDATED, outcomes, Target::Auto);
+          });
+
+          updates
+        } else {
+          updates
+        };
+        if updates > 0 {
+          Toast::push(
+            &mut data.toasts,
+            Toast::new(
+              tr("update-check-title"),
+              tr_args("update-check-summary", &[("count", &updates)]),
+            ),
+          );
+        }
+      } else if let Some(sweep) = &self.update_sweep {
+        data.update_check_progress = Some(sweep.progress());
+      }
+
+      return Handled::Yes;
+    } else if let Some(()) = cmd.get(update_check::SWEEP_DUE) {
+      let version_checkers = data
+        .mod_list
+        .mods
+        .values()
+        .filter_map(|entry| entry.version_checker.clone())
+        .collect();
+      data.runtime.spawn(update_check::sweep(
+        ctx.get_external_handle(),
+        version_checkers,
+      ));
+
+      return Handled::Yes;
+    } else if let Some(()) = cmd.get(App::REFRESH) {
+      data.archived_mods = archive::list();
+      thumbnail::clear_cache();
+      data.refresh_compat_db(ctx.get_external_handle());
+      if let Some(install_dir) = data.settings.install_dir.as_ref() {
+        data.mod_list.clear();
+        data.runtime.spawn(ModList::parse_mod_folder(
+          ctx.get_external_handle(),
+          Some(install_dir.clone()),
+          data.settings.linked_mod_dirs(),
+        ));
+      }
+    } else if let Some(res) = cmd.get(GET_INSTALLED_STARSECTOR) {
+      let overridden = data.settings.version_override();
+      if overridden.is_none() {
+        let notice = match res {
+          Ok((_, VersionSource::Jar)) => None,
+          Ok((_, VersionSource::Log)) => Some(tr("game-version-from-log")),
+          Err(_) => Some(tr("game-version-not-found")),
+        };
+        if let Some(notice) = notice {
+          Toast::push(
+            &mut data.toasts,
+            Toast::new(tr("game-version-title"), notice)
+              .with_action(App::SELECTOR.with(AppCommands::OpenSettings)),
+          );
+        }
+      }
+      let detected = res.as_ref().ok().map(|(version, _)| version.clone());
+      data.mod_list.set_detected_version(detected, overridden);
+    } else if let Some(name) = cmd.get(App::LOG_SUCCESS) {
+      data.log_message(&format!("Successfully installed {}", name));
+      Toast::push(
+        &mut data.toasts,
+        Toast::new(tr("toast-installed"), name.clone()).with_action(App::SHOW_LOG.into()),
+      );
+
+      return Handled::Yes;
+    } else if let Some(()) = cmd.get(App::SHOW_LOG) {
+      self.display_if_closed(ctx, SubwindowType::Log);
+
+      return Handled::Yes;
+    } else if let Some(()) = cmd.get(App::CLEAR_LOG) {
+      data.log.clear();
+
+      return Handled::Yes;
+    } else if let Some((name, err)) = cmd.get(App::LOG_ERROR) {
+      data.log_message(&format!("Failed to install {}. Error: {}", name, err));
+      self.display_if_closed(ctx, SubwindowType::Log);
+
+      return Handled::Yes;
+    } else if let Some(popup) = cmd.get(Popup::SHOW) {
+      let id = popup.show(ctx);
+      if popup.is_critical() {
+        self.critical_popups.push(id);
+      }
+
+      return Handled::Yes;
+    } else if let Some(db) = cmd.get(compat_db::LOADED) {
+      data.mod_list.set_compat(db.clone());
+
+      return Handled::Yes;
+    } else if let Some(toast) = cmd.get(Toast::SHOW) {
+      Toast::push(&mut data.toasts, toast.clone());
+
+      return Handled::Yes;
+    } else if let Some(library) = cmd.get(known_libraries::INSTALL) {
+      data.runtime.spawn(library.install(ctx.get_external_handle()));
+
+      return Handled::Yes;
+    } else if let Some((flavour, err)) = cmd.get(settings::jre::SWAP_FAILED) {
+      let flavour = *flavour;
+      let body = err.clone();
+      let modal = Modal::<App>::new(&match flavour {
+        Some(flavour) => tr_args("jre-swap-failed", &[("jre", &flavour)]),
+        None => tr("jre-revert-failed"),
+      })
+      .with_content(err.clone())
+      .with_button(&tr("jre-swap-copy-error"), move |_: &mut EventCtx, _: &mut App| {
+        Application::global().clipboard().put_string(&body)
+      })
+      .with_button(&tr("jre-swap-retry"), move |ctx: &mut EventCtx, data: &mut App| {
+        data.settings.start_jre_swap(ctx.get_external_handle(), flavour)
+      })
+      .with_close()
+      .build();
+
+      ctx.new_window(
+        WindowDesc::new(modal)
+          .window_size((500., 300.))
+          .show_titlebar(false)
+          .set_level(WindowLevel::AppWindow),
+      );
+
+      return Handled::Yes;
+    } else if let Some(message) = cmd.get(App::LOG_MESSAGE) {
+      data.log_message(message);
+      self.display_if_closed(ctx, SubwindowType::Log);
+
+      return Handled::Yes;
+    } else if let Some(message) = cmd.get(App::LOG_OVERWRITE) {
+      data.push_overwrite(message.clone());
+      self.display_if_closed(ctx, SubwindowType::Overwrite);
+
+      return Handled::Yes;
+    } else if let Some(ovewrite_all) = cmd.get(App::CLEAR_OVERWRITE_LOG) {
+      if *ovewrite_all {
+        for val in &data.overwrite_log {
+          let (conflict, to_install, entry) = val.as_ref();
+          ctx.submit_command(ModList::OVERWRITE.with((
+            match conflict {
+              StringOrPath::String(id) => data.mod_list.get(id).unwrap().path.clone(),
+              StringOrPath::Path(path) => path.clone(),
+            },
+            to_install.clone(),
+            entry.clone(),
+            OverwriteMode::Replace,
+          )))
+        }
+      }
+      data.overwrite_log.clear();
+
+      return Handled::Yes;
+    } else if let Some(overwrite_entry) = cmd.get(App::REMOVE_OVERWRITE_LOG_ENTRY) {
+      data.overwrite_log.retain(|val| val.0 != *overwrite_entry);
+      if data.overwrite_log.is_empty() {
+        if let Some(id) = self.overwrite_window.take() {
+          ctx.submit_command(commands::CLOSE_WINDOW.to(id))
+        }
+      }
+
+      return Handled::Yes;
+    } else if let Some(duplicates) = cmd.get(ModList::DUPLICATE) {
+      data.push_duplicate(duplicates);
+      self.display_if_closed(ctx, SubwindowType::Duplicate);
+
+      return Handled::Yes;
+    } else if let Some((keep_entry, others, archive)) = cmd.get(App::RESOLVE_DUPLICATES) {
+      let ext_ctx = ctx.get_external_handle();
+      let keep_entry = keep_entry.clone();
+      let others = others.clone();
+      let archive = *archive;
+      let install_dir = data.settings.install_dir.clone();
       data.runtime.spawn(async move {
-        if remove_dir_all(delete_path).is_ok() {
+        let removal_ctx = ext_ctx.clone();
+        let removed = tokio::task::spawn_blocking(move || {
+          let mut removed = true;
+          for other in &others {
+            let res = App::removable(other)
+              .and_then(|_| {
+                if archive {
+                  archive::archive(other).map(|_| ())
+                } else {
+                  Ok(())
+                }
+              })
+              .and_then(|_| App::delete_mod_folder(install_dir.as_deref(), other));
+
+            if let Err(err) = res {
+              removed = false;
+              error!("Failed to remove duplicate at {:?}: {:?}", other.path, err);
+              let _ = removal_ctx.submit_command(
+                App::LOG_MESSAGE,
+                format!(
+                  "Failed to remove the copy of {} at {}: {:#}",
+                  other.name,
+                  other.path.to_string_lossy(),
+                  err
+                ),
+                Target::Auto,
+              );
+            }
+          }
+
+          removed
+        })
+        .await
+        .unwrap_or(false);
+
+        if removed {
           let remote_version = keep_entry.version_checker.clone();
           if ext_ctx
             .submit_command(ModEntry::REPLACE, keep_entry, Target::Auto)
             .is_err()
           {
-            eprintln!("Failed to submit new entry")
+            error!("Failed to submit new entry")
           };
           if let Some(version_meta) = remote_version {
             util::get_master_version(ext_ctx, version_meta).await;
           }
         } else {
-          eprintln!("Failed to delete duplicate mod");
+          error!("Failed to delete duplicate mod");
         }
       });
 
       return Handled::Yes;
     } else if let Some(id) = cmd.get(App::REMOVE_DUPLICATE_LOG_ENTRY) {
-      data.duplicate_log.retain(|entry| entry.0.id != *id);
+      data
+        .duplicate_log
+        .retain(|copies| copies.front().is_some_and(|first| first.id != *id));
       if data.duplicate_log.is_empty() {
         if let Some(id) = self.duplicate_window.take() {
           ctx.submit_command(commands::CLOSE_WINDOW.to(id))
@@ -947,12 +2492,168 @@ impl Delegate<App> for AppDelegate {
         ctx.submit_command(commands::CLOSE_WINDOW.to(id))
       }
 
+      return Handled::Yes;
+    } else if let Some(info) = cmd.get(App::CONFIRM_DOWNLOAD) {
+      if data.settings.place_loose_files && loose_file::is_loose_file(&info.file_name) {
+        ctx.new_window(
+          WindowDesc::new(App::loose_file_modal(info))
+            .window_size((500., 220.))
+            .show_titlebar(false)
+            .set_level(WindowLevel::AppWindow),
+        );
+
+        return Handled::Yes;
+      }
+      let size = info
+        .size
+        .map_or_else(|| tr("download-detected-size-unknown"), format_size);
+      let modal = Modal::<App>::new(&tr("download-detected-title"))
+        .with_content(tr_args(
+          "download-detected-file",
+          &[("name", &info.file_name)],
+        ))
+        .with_content(tr_args("download-detected-size", &[("size", &size)]))
+        .with_content(match &data.settings.install_dir {
+          Some(install_dir) => tr_args(
+            "download-detected-destination",
+            &[("path", &library::install_dir_for(install_dir).display())],
+          ),
+          None => tr("download-detected-no-install"),
+        })
+        .with_button(
+          &tr("download-detected-install"),
+          WEBVIEW_INSTALL.with(InstallType::Uri(info.uri.clone())),
+        )
+        .with_button(
+          &tr("download-detected-just-download"),
+          App::DOWNLOAD_ONLY.with(info.uri.clone()),
+        )
+        .with_close_label(&tr("cancel"))
+        .build();
+
+      ctx.new_window(
+        WindowDesc::new(modal)
+          .window_size((500., 220.))
+          .show_titlebar(false)
+          .set_level(WindowLevel::AppWindow),
+      );
+
+      return Handled::Yes;
+    } else if let Some(uri) = cmd.get(App::DOWNLOAD_ONLY) {
+      let Some(dir) = data.settings.download_dir() else {
+        let err = anyhow::anyhow!(tr("download-only-no-dir"));
+        ctx.submit_command(Popup::SHOW.with(Popup::Error(err)));
+        return Handled::Yes;
+      };
+
+      let uri = uri.clone();
+      let ext_ctx = ctx.get_external_handle();
+      data.runtime.spawn(async move {
+        let info = DownloadInfo::probe(uri).await;
+        let name = info.file_name.clone();
+        match installer::download_to(info, ext_ctx.clone(), &dir).await {
+          Ok(path) => {
+            let _ = ext_ctx.submit_command(
+              App::LOG_MESSAGE,
+              format!("Downloaded {} to {}", name, path.display()),
+              Target::Auto,
+            );
+          }
+          Err(InstallError::Cancelled) => {
+            let _ = ext_ctx.submit_command(
+              App::LOG_MESSAGE,
+              format!("Cancelled download of {}", name),
+              Target::Auto,
+            );
+          }
+          Err(err) => Toast::submit_error(
+            &ext_ctx,
+            anyhow::Error::new(err).context(format!("Failed to download {}", name)),
+          ),
+        }
+      });
+
+      return Handled::Yes;
+    } else if let Some((uri, file_name, mod_dir)) = cmd.get(loose_file::PLACE) {
+      let path = loose_file::destination(mod_dir, file_name);
+      let modal = Modal::<App>::new(&tr("loose-file-confirm-title"))
+        .with_content(tr_args(
+          "loose-file-confirm-destination",
+          &[("name", file_name), ("path", &path.to_string_lossy())],
+        ))
+        .pipe(|modal| {
+          if path.exists() {
+            modal.with_content(tr_args(
+              "loose-file-confirm-backup",
+              &[("backup", &loose_file::backup_path(&path).to_string_lossy())],
+            ))
+          } else {
+            modal
+          }
+        })
+        .with_button(
+          &tr("loose-file-confirm"),
+          loose_file::PLACE_CONFIRMED.with((uri.clone(), file_name.clone(), path.clone())),
+        )
+        .with_close_label(&tr("cancel"))
+        .build();
+
+      ctx.new_window(
+        WindowDesc::new(modal)
+          .window_size((500., 220.))
+          .show_titlebar(false)
+          .set_level(WindowLevel::AppWindow),
+      );
+
+      return Handled::Yes;
+    } else if let Some((uri, file_name, path)) = cmd.get(loose_file::PLACE_CONFIRMED) {
+      let (uri, file_name, path) = (uri.clone(), file_name.clone(), path.clone());
+      let cache_dir = data.settings.cache_dir();
+      let ext_ctx = ctx.get_external_handle();
+      data.runtime.spawn(async move {
+        match loose_file::place(uri, &path, ext_ctx.clone(), &cache_dir).await {
+          Ok(backup) => {
+            let (name, path) = (file_name.as_str(), path.to_string_lossy());
+            let message = match backup {
+              Some(backup) => tr_args(
+                "loose-file-placed-backed-up",
+                &[
+                  ("name", &name),
+                  ("path", &path),
+                  ("backup", &backup.to_string_lossy()),
+                ],
+              ),
+              None => tr_args("loose-file-placed", &[("name", &name), ("path", &path)]),
+            };
+            let _ = ext_ctx.submit_command(App::LOG_MESSAGE, message, Target::Auto);
+          }
+          Err(InstallError::Cancelled) => {
+            let _ = ext_ctx.submit_command(
+              App::LOG_MESSAGE,
+              format!("Cancelled download of {}", file_name),
+              Target::Auto,
+            );
+          }
+          Err(err) => Toast::submit_error(
+            &ext_ctx,
+            anyhow::Error::new(err).context(format!("Failed to place {}", file_name)),
+          ),
+        }
+      });
+
       return Handled::Yes;
     } else if let Some(install) = cmd.get(WEBVIEW_INSTALL) {
+      if !data.settings.install_dir_usable() {
+        if let Some(install_dir) = &data.settings.install_dir {
+          Self::show_install_dir_warning(ctx, install_dir);
+        }
+        return Handled::Yes;
+      }
       let runtime = data.runtime.clone();
       let install = install.clone();
       let ext_ctx = ctx.get_external_handle();
       let install_dir = data.settings.install_dir.clone().unwrap();
+      let cache_dir = data.settings.cache_dir();
       let ids = data.mod_list.mods.values().map(|v| v.id.clone()).collect();
       data.runtime.spawn_blocking(move || {
         runtime.block_on(async move {
@@ -968,29 +2669,43 @@ impl Delegate<App> for AppDelegate {
                 })
                 .unwrap_or_else(|| uri.clone())
                 .to_string();
-              ext_ctx
-                .submit_command(
-                  App::LOG_MESSAGE,
-                  format!("Installing {}", &file_name),
-                  Target::Auto,
-                )
-                .expect("Send install start");
-              let download = installer::download(uri, ext_ctx.clone())
+              let _ = ext_ctx.submit_command(
+                App::LOG_MESSAGE,
+                format!("Installing {}", &file_name),
+                Target::Auto,
+              );
+              let download = match installer::download(uri.clone(), ext_ctx.clone(), &cache_dir)
                 .await
-                .expect("Download archive");
-              let download_dir = PROJECT.cache_dir().to_path_buf();
-              let mut persist_path = download_dir.join(&file_name);
-              if persist_path.exists() {
-                persist_path = download_dir.join(format!("{}({})", file_name, random::<u8>()))
-              }
-              if let Err(err) = download.persist(&persist_path) {
-                if err.error.kind() == std::io::ErrorKind::CrossesDevices {
-                  std::fs::copy(err.file.path(), &persist_path)
-                    .expect("Copy download across devices");
-                } else {
-                  panic!("{}", err)
+              {
+                Ok(download) => download,
+                Err(InstallError::Cancelled) => {
+                  let _ = ext_ctx.submit_command(
+                    App::LOG_MESSAGE,
+                    format!("Cancelled download of {}", file_name),
+                    Target::Auto,
+                  );
+                  return;
                 }
+                Err(err) => {
+                  Toast::submit_error(
+                    &ext_ctx,
+                    anyhow::anyhow!("Failed to download {}: {}", file_name, err),
+                  );
+                  return;
+                }
+              };
+              let persist_path = installer::save_path(&cache_dir, &file_name);
+              if let Err(err) = installer::persist(download, &persist_path) {
+                Toast::submit_error(
+                  &ext_ctx,
+                  anyhow::Error::new(err).context(format!(
+                    "Failed to save download to {}",
+                    persist_path.to_string_lossy()
+                  )),
+                );
+                return;
               }
+              installer::remember_source(&persist_path, &uri);
 
               persist_path
             }
@@ -1000,40 +2715,277 @@ impl Delegate<App> for AppDelegate {
                 .unwrap_or(path.as_os_str())
                 .to_string_lossy()
                 .to_string();
-              ext_ctx
-                .submit_command(
-                  App::LOG_MESSAGE,
-                  format!("Installing {}", &file_name),
-                  Target::Auto,
-                )
-                .expect("Send install start");
+              let _ = ext_ctx.submit_command(
+                App::LOG_MESSAGE,
+                format!("Installing {}", &file_name),
+                Target::Auto,
+              );
 
               path
             }
           };
           installer::Payload::Initial(vec![path])
-            .install(ext_ctx, install_dir, ids)
+            .install(ext_ctx, install_dir, cache_dir, ids)
             .await;
         });
       });
+      return Handled::Yes;
+    } else if let Some(args) = cmd.get(App::ACTIVATE) {
+      if let Some(root_id) = self.root_id {
+        ctx.submit_command(commands::SHOW_WINDOW.to(root_id));
+      }
+
+      let (links, args): (Vec<&String>, Vec<&String>) =
+        args.iter().partition(|arg| url_scheme::is_link(arg));
+      for link in links {
+        match url_scheme::parse(link) {
+          Some(url_scheme::DeepLink::Install(uri)) => {
+            let ext_ctx = ctx.get_external_handle();
+            let uri = uri.clone();
+            data.runtime.spawn(async move {
+              let info = DownloadInfo::probe(uri).await;
+              let _ = ext_ctx.submit_command(App::CONFIRM_DOWNLOAD, info, Target::Auto);
+            });
+          }
+          Some(url_scheme::DeepLink::Open(url)) => {
+            ctx.submit_command(App::OPEN_WEBVIEW.with(Some(url)))
+          }
+          None => ctx.submit_command(
+            App::LOG_MESSAGE.with(tr_args("log-unrecognised-link", &[("link", link)])),
+          ),
+        }
+      }
+
+      let (paths, others): (Vec<&String>, Vec<&String>) =
+        args.into_iter().partition(|arg| Path::new(arg).exists());
+      if !paths.is_empty() {
+        if data.settings.install_dir_usable() {
+          ctx.submit_command(
+            App::OPEN_FILE.with(Some(paths.into_iter().map(PathBuf::from).collect())),
+          );
+        } else {
+          ctx.submit_command(App::LOG_MESSAGE.with(tr("install-dir-not-set")));
+        }
+      }
+      if let Some(url) = others.into_iter().find(|arg| {
+        Url::parse(arg).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+      }) {
+        ctx.submit_command(App::OPEN_WEBVIEW.with(Some(url.clone())));
+      }
+
       return Handled::Yes;
     } else if let Some(url) = cmd.get(App::OPEN_WEBVIEW) && let Some(window) = self.root_window.as_ref() {
       ctx.submit_command(App::DISABLE);
-      let webview = init_webview(url.clone(), window, ctx.get_external_handle()).expect("Initialize webview");
+      let webview = init_webview(
+        url.clone(),
+        window,
+        ctx.get_external_handle(),
+        data.settings.cache_dir().join("webview"),
+        data.settings.ui_scale,
+      )
+      .expect("Initialize webview");
 
       data.webview = Some(Rc::new(webview))
+    } else if let Some((id, topic_id)) = cmd.get(forum_post::FETCH) {
+      let ext_ctx = ctx.get_external_handle();
+      let (id, topic_id) = (id.clone(), topic_id.clone());
+      data.runtime.spawn(async move {
+        let post = forum_post::fetch(&topic_id).await;
+        let _ = ext_ctx.submit_command(forum_post::SET, (id, Some(post)), Target::Auto);
+      });
+
+      return Handled::Yes;
+    } else if let Some((id, post)) = cmd.get(forum_post::SET) {
+      if let Some(mut entry) = data.mod_list.get(id).cloned() {
+        Arc::make_mut(&mut entry).forum_post = post.clone();
+        data.mod_list.mods.insert(entry.path.clone(), entry);
+      }
+
+      return Handled::Yes;
+    } else if let Some((id, mod_dir)) = cmd.get(config_files::FIND) {
+      let ext_ctx = ctx.get_external_handle();
+      let (id, mod_dir) = (id.clone(), mod_dir.clone());
+      let install_dir = data.settings.install_dir.clone();
+      data.runtime.spawn_blocking(move || {
+        let files = config_files::find(&mod_dir, &id, install_dir.as_deref());
+        let _ = ext_ctx.submit_command(config_files::SET, (id, files.into()), Target::Auto);
+      });
+
+      return Handled::Yes;
+    } else if let Some((id, files)) = cmd.get(config_files::SET) {
+      if let Some(mut entry) = data.mod_list.get(id).cloned() {
+        Arc::make_mut(&mut entry).config_files = Some(files.clone());
+        data.mod_list.mods.insert(entry.path.clone(), entry);
+      }
+
+      return Handled::Yes;
     } else if let Some(url) = cmd.get(mod_description::OPEN_IN_BROWSER) {
       if data.settings.open_forum_link_in_webview {
         ctx.submit_command(App::OPEN_WEBVIEW.with(Some(url.clone())));
       } else {
         let _ = opener::open(url);
       }
+    } else if let Some(skip_check) = cmd.get(App::LAUNCH) {
+      let problems = if *skip_check {
+        Vec::new()
+      } else {
+        data.launch_problems()
+      };
+      if !problems.is_empty() {
+        ctx.new_window(data.launch_check_window(&problems, true));
+      } else if let Some(install_dir) = data.settings.install_dir.clone() {
+        ctx.submit_command(App::DISABLE);
+        let ext_ctx = ctx.get_external_handle();
+        let experimental_launch = data.settings.experimental_launch;
+        let resolution = data.settings.experimental_resolution;
+        let log_len = safe_mode::log_len(&install_dir);
+        data.runtime.spawn(async move {
+          let launched = Instant::now();
+          if let Err(err) =
+            App::launch_starsector(install_dir.clone(), experimental_launch, resolution).await
+          {
+            error!("{:?}", err);
+          } else if let Some(error) =
+            safe_mode::check(&install_dir, log_len, launched.elapsed())
+          {
+            let _ = ext_ctx.submit_command(safe_mode::CRASHED, error, Target::Auto);
+          };
+          ext_ctx.submit_command(App::ENABLE, (), Target::Auto)
+        });
+      }
+
+      return Handled::Yes;
+    } else if let Some(()) = cmd.get(App::CHECK_ENABLED_MODS) {
+      ctx.new_window(data.launch_check_window(&data.launch_problems(), false));
+
+      return Handled::Yes;
+    } else if let Some(step) = cmd.get(bisect::STEP) {
+      match step {
+        Step::Start => {
+          let dependencies = data
+            .mod_list
+            .used()
+            .map(|entry| {
+              let dependencies = entry.dependencies.iter().map(|dep| dep.id.clone()).collect();
+              (entry.id.clone(), dependencies)
+            })
+            .collect();
+          data.bisection = Bisection::new(data.enabled_ids(), dependencies);
+          if let Some(bisection) = &data.bisection
+            && let Some(install_dir) = &data.settings.install_dir
+          {
+            Interrupted::save(install_dir.clone(), bisection)
+          }
+        }
+        Step::Crashed => data.bisection.iter_mut().for_each(Bisection::crashed),
+        Step::Worked => data.bisection.iter_mut().for_each(Bisection::worked),
+        Step::Stop | Step::Finish => {}
+      }
+      if matches!(step, Step::Stop | Step::Finish) {
+        Interrupted::clear()
+      }
+      let enabled = match step {
+        Step::Stop => data.bisection.take().map(|bisection| bisection.original().to_vec()),
+        Step::Finish => data.bisection.take().map(|bisection| bisection.enabled()),
+        _ => data.bisection.as_ref().map(Bisection::enabled),
+      };
+      if let Some(enabled) = enabled {
+        data.set_enabled_ids(&enabled);
+        if let Err(err) = data.write_enabled_mods() {
+          ctx.submit_command(Popup::SHOW.with(Popup::Critical(err)))
+        }
+      }
+
+      return Handled::Yes;
+    } else if let Some(interrupted) = cmd.get(bisect::INTERRUPTED) {
+      let modal = Modal::<App>::new(&tr("bisect-interrupted-title"))
+        .with_content(tr("bisect-interrupted"))
+        .with_button(&tr("bisect-restore"), bisect::RESTORE.with(interrupted.clone()))
+        .with_button(&tr("bisect-keep"), bisect::DISCARD)
+        .with_close_label(&tr("cancel"))
+        .build();
+
+      let window = WindowDesc::new(modal)
+        .window_size((400., 150.))
+        .show_titlebar(false)
+        .set_level(WindowLevel::AppWindow);
+
+      ctx.new_window(window);
+
+      return Handled::Yes;
+    } else if let Some(interrupted) = cmd.get(bisect::RESTORE) {
+      match EnabledMods::from(interrupted.original.clone()).save(&interrupted.install_dir) {
+        Ok(_) => {
+          Interrupted::clear();
+          ctx.submit_command(App::REFRESH)
+        }
+        Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::anyhow!(
+          "Failed to restore enabled_mods.json {:?}",
+          err
+        )))),
+      }
+
+      return Handled::Yes;
+    } else if cmd.is(bisect::DISCARD) {
+      Interrupted::clear();
+
+      return Handled::Yes;
+    } else if let Some(error) = cmd.get(safe_mode::CRASHED) {
+      // a running bisection asks the player whether the game crashed instead
+      if data.bisection.is_some() {
+        return Handled::Yes;
+      }
+      let suspects =
+        safe_mode::suspects(data.mod_list.mods.values(), &data.history.recently_enabled());
+      if suspects.is_empty() {
+        return Handled::Yes;
+      }
+      let names = suspects
+        .iter()
+        .map(|entry| entry.name.clone())
+        .collect::<Vec<_>>()
+        .join(", ");
+      let ids = suspects.iter().map(|entry| entry.id.clone()).collect();
+      let modal = Modal::<App>::new(&tr("safe-mode-title"))
+        .with_content(tr_args("safe-mode-log", &[("error", error)]))
+        .with_content(tr_args("safe-mode-suspects", &[("mods", &names)]))
+        .with_content(tr("safe-mode-hint"))
+        .with_button(&tr("safe-mode-relaunch"), safe_mode::ENTER.with(ids))
+        .with_close_label(&tr("cancel"))
+        .build();
+
+      ctx.new_window(
+        WindowDesc::new(modal)
+          .window_size((500., 300.))
+          .show_titlebar(false)
+          .set_level(WindowLevel::AppWindow),
+      );
+
+      return Handled::Yes;
+    } else if let Some(ids) = cmd.get(safe_mode::ENTER) {
+      let before = data.enabled_ids();
+      let mut mods = data.mod_list.mods.clone();
+      for (_, entry) in mods.iter_mut() {
+        if entry.enabled && ids.contains(&entry.id) {
+          Arc::make_mut(entry).enabled = false
+        }
+      }
+      data.mod_list.mods = mods;
+      match data.enabled_changed(before) {
+        Ok(()) => {
+          data.log_message(&tr_args("safe-mode-disabled", &[("count", &ids.len())]));
+          ctx.submit_command(App::LAUNCH.with(false))
+        }
+        Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Critical(err))),
+      }
+
+      return Handled::Yes;
     } else if let Some(entry) = cmd.get(ModEntry::ASK_DELETE_MOD) {
-      let modal = Modal::<App>::new(&format!("Delete {}", entry.name))
-        .with_content(format!("Do you want to PERMANENTLY delete {}?", entry.name))
-        .with_content("This operation cannot be undone.")
-        .with_button("Confirm", App::CONFIRM_DELETE_MOD.with(entry.clone()))
-        .with_close_label("Cancel")
+      let modal = Modal::<App>::new(&tr_args("delete-title", &[("name", &entry.name)]))
+        .with_content(tr_args("delete-prompt", &[("name", &entry.name)]))
+        .with_content(tr("delete-undo-hint"))
+        .with_button(&tr("delete-confirm"), App::CONFIRM_DELETE_MOD.with(entry.clone()))
+        .with_close_label(&tr("cancel"))
         .build();
 
       let window = WindowDesc::new(modal)
@@ -1042,12 +2994,345 @@ impl Delegate<App> for AppDelegate {
         .set_level(WindowLevel::AppWindow);
 
       ctx.new_window(window)
+    } else if let Some((enabled, shown_only, affected)) = cmd.get(App::CONFIRM_SET_ALL_ENABLED) {
+      let window = WindowDesc::new(AppDelegate::build_bulk_confirmation(
+        data.settings.bulk_confirmation,
+        *enabled,
+        *shown_only,
+        *affected,
+      ))
+      .window_size((400., 200.))
+      .show_titlebar(false)
+      .set_level(WindowLevel::AppWindow);
+
+      ctx.new_window(window)
+    } else if let Some((enabled, shown_only)) = cmd.get(App::SET_ALL_ENABLED) {
+      if let Err(err) = data.set_all_enabled(*enabled, *shown_only) {
+        ctx.submit_command(Popup::SHOW.with(Popup::Critical(err)));
+      }
+
+      return Handled::Yes;
     } else if let Some(entry) = cmd.get(App::CONFIRM_DELETE_MOD) {
-      if remove_dir_all(&entry.path).is_ok() {
-        data.mod_list.mods.remove(&entry.id);
+      if entry.external && !library::contains(&entry.path) {
+        ctx.submit_command(Popup::SHOW.with(Popup::Error(App::external_mod_error(entry))));
+      } else {
+        match App::trash_mod_folder(data.settings.install_dir.as_deref(), entry) {
+          Ok(trashed) => {
+            data.mod_list.remove(&entry.path);
+            if let Err(err) = install_log::record(&install_log::Entry::deleted(entry)) {
+              error!("Failed to record deleting {}: {}", entry.id, err)
+            }
+            data.history.record(Operation::Delete(entry.clone(), trashed));
+          }
+          Err(err) => {
+            error!("Failed to move mod to the trash {:?}", err);
+            let modal = Modal::<App>::new(&tr_args(
+              "delete-permanently-title",
+              &[("name", &entry.name)],
+            ))
+            .with_content(tr_args("delete-permanently-body", &[("name", &entry.name)]))
+            .with_content(tr_args("delete-permanently-error", &[("error", &err)]))
+            .with_button(
+              &tr("delete-permanently-confirm"),
+              App::DELETE_MOD_PERMANENTLY.with(entry.clone()),
+            )
+            .with_close_label(&tr("cancel"))
+            .build();
+
+            ctx.new_window(
+              WindowDesc::new(modal)
+                .window_size((500., 220.))
+                .show_titlebar(false)
+                .set_level(WindowLevel::AppWindow),
+            );
+          }
+        }
+      }
+    } else if let Some(entry) = cmd.get(App::DELETE_MOD_PERMANENTLY) {
+      match App::delete_mod_folder(data.settings.install_dir.as_deref(), entry) {
+        Ok(()) => {
+          data.mod_list.remove(&entry.path);
+        }
+        Err(err) => {
+          error!("Failed to delete mod {:?}", err);
+          ctx.submit_command(Popup::SHOW.with(Popup::Error(err)));
+        }
+      }
+
+      return Handled::Yes;
+    } else if cmd.is(history::UNDO) || cmd.is(history::REDO) {
+      let undo = cmd.is(history::UNDO);
+      let operation = if undo {
+        data.history.take_undo()
+      } else {
+        data.history.take_redo()
+      };
+      let Some(operation) = operation else {
+        return Handled::Yes;
+      };
+
+      let description = operation.describe();
+      match data.apply_history(operation, undo) {
+        Ok(operation) if undo => {
+          data.history.undone(operation);
+          data.log_message(&tr_args("history-undid", &[("operation", &description)]));
+        }
+        Ok(operation) => {
+          data.history.redone(operation);
+          data.log_message(&tr_args("history-redid", &[("operation", &description)]));
+        }
+        Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Error(err))),
+      }
+
+      return Handled::Yes;
+    } else if let Some(install_dir) = cmd.get(ModList::ENABLED_MODS_UNREADABLE) {
+      let Some(backup) = EnabledMods::backups(install_dir).into_iter().next() else {
+        ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::anyhow!(tr(
+          "enabled-mods-no-backup"
+        )))));
+        return Handled::Yes;
+      };
+      let written = backup
+        .metadata()
+        .and_then(|meta| meta.modified())
+        .map(|modified| DateTime::<Local>::from(modified).format("%F %R").to_string())
+        .unwrap_or_default();
+
+      let modal = Modal::<App>::new(&tr("enabled-mods-unreadable-title"))
+        .with_content(tr("enabled-mods-unreadable"))
+        .with_content(tr_args(
+          "enabled-mods-restore-prompt",
+          &[("written", &written)],
+        ))
+        .with_button(
+          &tr("enabled-mods-restore"),
+          App::RESTORE_ENABLED_MODS.with((install_dir.clone(), backup)),
+        )
+        .with_close_label(&tr("cancel"))
+        .build();
+
+      let window = WindowDesc::new(modal)
+        .window_size((400., 150.))
+        .show_titlebar(false)
+        .set_level(WindowLevel::AppWindow);
+
+      ctx.new_window(window);
+
+      return Handled::Yes;
+    } else if let Some((install_dir, backup)) = cmd.get(App::RESTORE_ENABLED_MODS) {
+      let res = EnabledMods::load_backup(backup)
+        .ok_or_else(|| {
+          anyhow::anyhow!(tr_args(
+            "enabled-mods-backup-unreadable",
+            &[("path", &backup.to_string_lossy())]
+          ))
+        })
+        .and_then(|enabled| {
+          enabled.save(install_dir).map_err(|err| {
+            anyhow::anyhow!(tr_args(
+              "enabled-mods-restore-failed",
+              &[("err", &format!("{:?}", err))]
+            ))
+          })
+        });
+      match res {
+        Ok(_) => ctx.submit_command(App::REFRESH),
+        Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Critical(err))),
+      }
+
+      return Handled::Yes;
+    } else if let Some(entry) = cmd.get(archive::ARCHIVE) {
+      if entry.external && !library::contains(&entry.path) {
+        ctx.submit_command(Popup::SHOW.with(Popup::Error(App::external_mod_error(entry))));
+      } else {
+        let ext_ctx = ctx.get_external_handle();
+        let entry = entry.clone();
+        let install_dir = data.settings.install_dir.clone();
+        data.runtime.spawn_blocking(move || {
+          let res = archive::archive(&entry).and_then(|path| {
+            App::remove_mod_folder(install_dir.as_deref(), &entry)?;
+            if let Err(err) = install_log::record(&install_log::Entry::archived(&entry, &path)) {
+              error!("Failed to record archiving {}: {}", entry.id, err)
+            }
+            Ok(path)
+          });
+          let _ = ext_ctx.submit_command(
+            archive::ARCHIVED,
+            (entry.clone(), res.map_err(|err| format!("{:#}", err))),
+            Target::Auto,
+          );
+        });
+      }
+
+      return Handled::Yes;
+    } else if let Some(entry) = cmd.get(archive::EXPORT) {
+      let ext_ctx = ctx.get_external_handle();
+      let entry = entry.clone();
+      data.runtime.spawn_blocking(move || {
+        let file_name = archive::release_name(&entry);
+        #[cfg(not(target_os = "linux"))]
+        let res = rfd::FileDialog::new()
+          .add_filter("Zip archive", &["zip"])
+          .set_file_name(&file_name)
+          .save_file();
+        #[cfg(target_os = "linux")]
+        let res = native_dialog::FileDialog::new()
+          .add_filter("Zip archive", &["zip"])
+          .set_filename(&file_name)
+          .show_save_single_file()
+          .ok()
+          .flatten();
+
+        let Some(destination) = res else {
+          return;
+        };
+        match archive::export(&entry, &destination) {
+          Ok(()) => {
+            let _ = ext_ctx.submit_command(
+              App::LOG_MESSAGE,
+              format!("Exported {} to {}", entry.name, destination.to_string_lossy()),
+              Target::Auto,
+            );
+          }
+          Err(err) => Toast::submit_error(&ext_ctx, err),
+        }
+      });
+
+      return Handled::Yes;
+    } else if let Some((entry, res)) = cmd.get(archive::ARCHIVED) {
+      match res {
+        Ok(path) => {
+          data.mod_list.remove(&entry.path);
+          data.log_message(&format!("Archived {} to {}", entry.id, path.to_string_lossy()));
+        }
+        Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::anyhow!(
+          "Failed to archive {}: {}",
+          entry.id,
+          err
+        )))),
+      }
+      data.archived_mods = archive::list();
+
+      return Handled::Yes;
+    } else if let Some(path) = cmd.get(archive::RESTORE) {
+      if let Some(install_dir) = &data.settings.install_dir {
+        let ext_ctx = ctx.get_external_handle();
+        let path = path.clone();
+        let mods_dir = library::install_dir_for(install_dir);
+        data.runtime.spawn_blocking(move || {
+          let message = match archive::restore(&path, &mods_dir) {
+            Ok(restored) => format!("Restored {}", restored.to_string_lossy()),
+            Err(err) => format!("{:#}", err),
+          };
+          let _ = ext_ctx.submit_command(App::LOG_MESSAGE, message, Target::Auto);
+          let _ = ext_ctx.submit_command(App::REFRESH, (), Target::Auto);
+        });
+      }
+
+      return Handled::Yes;
+    } else if let Some(link) = cmd.get(clipboard_watch::FOUND) {
+      let modal = Modal::<App>::new(&tr("mod-link-title"))
+        .with_content(tr_args("mod-link-copied", &[("kind", &link.describe())]))
+        .with_content(link.url.clone())
+        .with_button(
+          &tr("mod-link-open"),
+          App::OPEN_WEBVIEW.with(Some(link.url.clone())),
+        )
+        .pipe(|modal| {
+          if link.kind == clipboard_watch::LinkKind::Download {
+            modal
+              .with_button(
+                &tr("mod-link-install"),
+                WEBVIEW_INSTALL.with(InstallType::Uri(link.url.clone())),
+              )
+              .with_button(
+                &tr("mod-link-just-download"),
+                App::DOWNLOAD_ONLY.with(link.url.clone()),
+              )
+          } else {
+            modal
+          }
+        })
+        .with_close_label(&tr("mod-link-ignore"))
+        .build();
+
+      let window = WindowDesc::new(modal)
+        .window_size((450., 180.))
+        .show_titlebar(false)
+        .set_level(WindowLevel::AppWindow);
+
+      ctx.new_window(window);
+
+      return Handled::Yes;
+    } else if let Some(entry) = cmd.get(archive::ASK_ROLL_BACK) {
+      let kept = archive::replaced_versions(&entry.id);
+      let modal = Modal::<App>::new(&tr_args("roll-back-title", &[("name", &entry.name)]))
+        .with_content(tr_args(
+          "roll-back-kept",
+          &[("name", &entry.name), ("version", &entry.version)],
+        ))
+        .with_content(tr("roll-back-hint"))
+        .pipe(|mut modal| {
+          let mut versions = Vec::new();
+          // newest first, so a version kept more than once is rolled back to its latest copy
+          for kept in kept {
+            let version = archive::replaced_version(&kept);
+            if !versions.contains(&version) {
+              modal = modal.with_button(
+                &tr_args("roll-back-to", &[("version", &version)]),
+                archive::ROLL_BACK.with((entry.clone(), kept)),
+              );
+              versions.push(version);
+            }
+          }
+
+          modal
+        })
+        .with_close_label(&tr("cancel"))
+        .build();
+
+      let window = WindowDesc::new(modal)
+        .window_size((450., 220.))
+        .show_titlebar(false)
+        .set_level(WindowLevel::AppWindow);
+
+      ctx.new_window(window);
+
+      return Handled::Yes;
+    } else if let Some((entry, kept)) = cmd.get(archive::ROLL_BACK) {
+      if entry.external && !library::contains(&entry.path) {
+        ctx.submit_command(Popup::SHOW.with(Popup::Error(App::external_mod_error(entry))));
       } else {
-        eprintln!("Failed to delete mod")
+        let ext_ctx = ctx.get_external_handle();
+        let entry = entry.clone();
+        let kept = kept.clone();
+        data.runtime.spawn_blocking(move || {
+          let version = archive::replaced_version(&kept);
+          match archive::roll_back(&entry, &kept) {
+            Ok(()) => {
+              let change = install_log::Entry::rolled_back(&entry, version.clone());
+              if let Err(err) = install_log::record(&change) {
+                error!("Failed to record rolling back {}: {}", entry.id, err)
+              }
+              let _ = ext_ctx.submit_command(
+                App::LOG_MESSAGE,
+                tr_args(
+                  "roll-back-done",
+                  &[("name", &entry.name), ("version", &version)],
+                ),
+                Target::Auto,
+              );
+              let _ = ext_ctx.submit_command(App::REFRESH, (), Target::Auto);
+            }
+            Err(err) => Toast::submit_error(
+              &ext_ctx,
+              err.context(format!("Failed to roll back {} to {}", entry.name, version)),
+            ),
+          }
+        });
       }
+
+      return Handled::Yes;
     } else if let Some((timestamp, url)) = cmd.get(DOWNLOAD_STARTED) {
       data
         .downloads
@@ -1066,13 +3351,75 @@ impl Delegate<App> for AppDelegate {
       return Handled::Yes;
     } else if let Some(timestamp) = cmd.get(App::REMOVE_DOWNLOAD_BAR) {
       data.downloads.remove(timestamp);
+      data.failed_downloads.remove(timestamp);
 
-      if data.downloads.is_empty() {
+      if data.downloads.is_empty() && data.failed_downloads.is_empty() {
         if let Some(id) = self.download_window.take() {
           ctx.submit_command(commands::CLOSE_WINDOW.to(id))
         }
       }
 
+      return Handled::Yes;
+    } else if let Some(failed) = cmd.get(App::DOWNLOAD_FAILED) {
+      data.downloads.remove(&failed.0);
+      data.failed_downloads.insert(failed.0, failed.clone());
+
+      self.display_if_closed(ctx, SubwindowType::Download);
+
+      return Handled::Yes;
+    } else if let Some(refreshed) = cmd.get(ModRepo::REFRESHED) {
+      ModRepo::apply_refresh(&mut data.mod_repo, refreshed);
+      data.mod_repo_changed();
+
+      return Handled::Yes;
+    } else if let Some(timestamp) = cmd.get(App::RETRY_DOWNLOAD) {
+      if let Some((_, uri, _)) = data.failed_downloads.remove(timestamp) {
+        ctx.submit_command(WEBVIEW_EVENT.with(UserEvent::BlobReceived(uri)))
+      }
+
+      return Handled::Yes;
+    } else if let Some((id, name, status)) = cmd.get(INSTALL_STATUS) {
+      let cancelled = data
+        .install_queue
+        .get(id)
+        .is_some_and(|(_, _, existing)| existing == &InstallStatus::Cancelled);
+      if !cancelled {
+        data
+          .install_queue
+          .insert(*id, (*id, name.clone(), status.clone()));
+      }
+
+      self.display_if_closed(ctx, SubwindowType::Install);
+
+      return Handled::Yes;
+    } else if let Some(id) = cmd.get(App::REMOVE_INSTALL_QUEUE_ENTRY) {
+      data.install_queue.remove(id);
+
+      return Handled::Yes;
+    } else if let Some(report) = cmd.get(update_check::AUTO_UPDATED) {
+      self.auto_updating = false;
+      let window = WindowDesc::new(Self::build_install_report(
+        &tr("install-report-automatic-title"),
+        report,
+      ))
+      .window_size((500., 400.))
+      .show_titlebar(false)
+      .set_level(WindowLevel::AppWindow);
+
+      ctx.new_window(window);
+
+      return Handled::Yes;
+    } else if let Some(report) = cmd.get(installer::INSTALL_REPORT) {
+      let window = WindowDesc::new(Self::build_install_report(
+        &tr("install-report-title"),
+        report,
+      ))
+      .window_size((500., 400.))
+      .show_titlebar(false)
+      .set_level(WindowLevel::AppWindow);
+
+      ctx.new_window(window);
+
       return Handled::Yes;
     } else if let Some((source, found_paths)) = cmd.get(App::FOUND_MULTIPLE) {
       let modal = Self::build_found_multiple(source.clone(), found_paths.clone());
@@ -1090,10 +3437,11 @@ impl Delegate<App> for AppDelegate {
     {
       let ext_ctx = ctx.get_external_handle();
       let install_dir = data.settings.install_dir.as_ref().unwrap().clone();
+      let cache_dir = data.settings.cache_dir();
       let ids = data.mod_list.mods.values().map(|v| v.id.clone()).collect();
       data.runtime.spawn(async move {
         installer::Payload::Initial(to_install.into_iter().collect())
-          .install(ext_ctx, install_dir, ids)
+          .install(ext_ctx, install_dir, cache_dir, ids)
           .await;
 
         drop(source);
@@ -1103,47 +3451,78 @@ impl Delegate<App> for AppDelegate {
     } else if let Some(user_event) = cmd.get(WEBVIEW_EVENT) && let Some(webview) = &data.webview {
       match user_event {
         UserEvent::Navigation(uri) => {
-          println!("Navigation: {}", uri);
+          debug!("Navigation: {}", uri);
           if uri.starts_with("https://www.mediafire.com/file") {
             let _ = webview.evaluate_script(r#"window.alert("You appear to be on a Mediafire site.\nIn order to correctly trigger a Mediafire download, attempt to open the dowload link in a new window.\nThis can be done through the right click context menu, or using a platform shortcut.")"#);
           }
         },
         UserEvent::AskDownload(uri) => {
-          #[cfg(not(target_os = "macos"))]
-          let _ = webview.evaluate_script(&format!(r"
-          let res = window.confirm('Detected an attempted download.\nDo you want to try and install a mod using this download?')
-          window.ipc.postMessage(`confirm_download:${{res}},uri:{}`)
-          ", encode(uri)));
-          #[cfg(target_os = "macos")]
-          let _ = webview.evaluate_script(&format!(r"
-          let dialog = new Dialog();
-          let res = dialog.confirm('Detected an attempted download.\nDo you want to try and install a mod using this download?', {{}})
-            .then(res => window.ipc.postMessage(`confirm_download:${{res}},uri:{}`))
-          ", encode(uri)));
+          let ext_ctx = ctx.get_external_handle();
+          let uri = uri.clone();
+          data.runtime.spawn(async move {
+            let info = DownloadInfo::probe(uri).await;
+            let _ = ext_ctx.submit_command(App::CONFIRM_DOWNLOAD, info, Target::Auto);
+          });
         },
         UserEvent::Download(uri) => {
           let _ = webview.evaluate_script("location.reload();");
-          ctx.submit_command(WEBVIEW_INSTALL.with(InstallType::Uri(uri.clone())))
+          if data.download_only {
+            ctx.submit_command(App::DOWNLOAD_ONLY.with(uri.clone()))
+          } else {
+            ctx.submit_command(WEBVIEW_INSTALL.with(InstallType::Uri(uri.clone())))
+          }
         },
         UserEvent::CancelDownload => {},
+        UserEvent::IpcError(err) => {
+          ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::anyhow!("Received malformed message from webview: {}", err))));
+        },
         UserEvent::NewWindow(uri) => {
-          webview.evaluate_script(&format!("window.location.assign('{}')", uri)).expect("Navigate webview");
+          if let Err(err) = webview.evaluate_script(&format!("window.location.assign('{}')", uri)) {
+            error!("Failed to navigate webview: {:?}", err)
+          }
         },
         UserEvent::BlobReceived(uri) => {
-          let path = PROJECT.cache_dir().join(format!("{}", random::<u16>()));
-          self.mega_file = Some((File::create(&path).expect("Create file"), path));
-          webview.evaluate_script(&format!(r#"
+          if let Some(download) = self.blob_download.take() {
+            data.downloads.remove(&download.started);
+            download.discard();
+          }
+          let download = match BlobDownload::new(uri.clone(), &data.settings.cache_dir()) {
+            Ok(download) => download,
+            Err(err) => {
+              ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::Error::new(err))));
+              return Handled::Yes;
+            }
+          };
+          ctx.submit_command(
+            DOWNLOAD_STARTED.with((download.started, BlobDownload::NAME.to_string())),
+          );
+          self.blob_download = Some(download);
+
+          let res = webview.evaluate_script(&format!(r#"
           (() => {{
             /**
             * @type Blob
             */
             let blob = URL.getObjectURLDict()['{}']
               || Object.values(URL.getObjectURLDict())[0]
+            if (!blob) {{
+              window.ipc.postMessage('#ERROR:Could not find the downloaded file');
+              return;
+            }}
+            window.blobDownloadCancelled = false;
+            window.ipc.postMessage(`#SIZE:${{blob.size}}`);
 
             var increment = 1024;
             var index = 0;
             var reader = new FileReader();
             let func = function() {{
+              if (window.blobDownloadCancelled) {{
+                return;
+              }}
+              if (reader.error) {{
+                window.ipc.postMessage(`#ERROR:${{reader.error}}`);
+                return;
+              }}
               let res = reader.result;
               window.ipc.postMessage(`${{res}}`);
               index += increment;
@@ -1159,33 +3538,49 @@ impl Delegate<App> for AppDelegate {
             reader.onloadend = func;
             reader.readAsDataURL(blob.slice(index, increment))
           }})();
-          "#, uri)).expect("Eval script");
+          "#, uri));
+          if let Err(err) = res {
+            self.fail_blob_download(ctx, format!("Failed to start download: {:?}", err));
+          }
         },
-        UserEvent::BlobChunk(chunk) => {
-          if let Some((file, path)) = self.mega_file.as_mut() {
-            match chunk {
-              Some(chunk) => {
-                let split = chunk.split(',').nth(1);
-                println!("{:?}", chunk.split(',').next());
-                if let Some(split) = split {
-                  if let Ok(decoded) = decode(split) {
-                    if file.write(&decoded).is_err() {
-                      eprintln!("Failed to write bytes to temp file")
-                    }
-                  }
+        UserEvent::BlobSize(size) => {
+          if let Some(download) = self.blob_download.as_mut() {
+            download.set_size(*size)
+          }
+        },
+        UserEvent::BlobChunk(Some(chunk)) => {
+          if let Some(download) = self.blob_download.as_mut() {
+            let before = download.progress();
+            match download.write_chunk(chunk) {
+              Ok(()) => {
+                // chunks are small, only redraw the bar when it moves a whole percent
+                let after = download.progress();
+                if (before * 100.) as u32 != (after * 100.) as u32 {
+                  data.downloads.insert(
+                    download.started,
+                    (download.started, BlobDownload::NAME.to_string(), after),
+                  );
                 }
               },
-              None => {
-                ctx
-                .submit_command(
-                  WEBVIEW_INSTALL.with(
-                  InstallType::Path(path.clone()))
-                );
-                self.mega_file = None;
+              Err(err) => {
+                let _ = webview.evaluate_script("window.blobDownloadCancelled = true;");
+                self.fail_blob_download(ctx, err.to_string());
               }
             }
           }
         },
+        UserEvent::BlobChunk(None) => {
+          if let Some(download) = self.blob_download.take() {
+            data.downloads.insert(
+              download.started,
+              (download.started, BlobDownload::NAME.to_string(), 1.0),
+            );
+            ctx.submit_command(WEBVIEW_INSTALL.with(InstallType::Path(download.path.clone())));
+          }
+        },
+        UserEvent::BlobError(err) => {
+          self.fail_blob_download(ctx, err.clone());
+        },
       }
     }
 
@@ -1194,6 +3589,7 @@ impl Delegate<App> for AppDelegate {
 
   #[allow(unused_variables)]
   fn window_removed(&mut self, id: WindowId, data: &mut App, _env: &Env, ctx: &mut DelegateCtx) {
+    self.critical_popups.retain(|popup| *popup != id);
     match Some(id) {
       a if a == self.settings_id => self.settings_id = None,
       a if a == self.log_window => self.log_window = None,
@@ -1204,14 +3600,31 @@ impl Delegate<App> for AppDelegate {
       a if a == self.duplicate_window => self.duplicate_window = None,
       a if a == self.download_window => {
         data.downloads.clear();
+        data.failed_downloads.clear();
         self.download_window = None;
       }
+      a if a == self.install_window => {
+        let finished: Vec<i64> = data
+          .install_queue
+          .values()
+          .filter(|(_, _, status)| !status.is_active())
+          .map(|(id, _, _)| *id)
+          .collect();
+        for id in finished {
+          data.install_queue.remove(&id);
+        }
+        self.install_window = None;
+      }
       a if a == self.root_id => {
-        println!("quitting");
+        info!("Quitting");
+        webview_shared::shutdown();
         if let Some(child) = &data.webview {
           data.webview = None;
         }
         let _ = std::fs::remove_dir_all(PROJECT.cache_dir());
+        if data.settings.cache_dir.is_some() {
+          let _ = std::fs::remove_dir_all(data.settings.cache_dir());
+        }
         #[cfg(not(target_os = "macos"))]
         ctx.submit_command(commands::QUIT_APP);
         #[cfg(target_os = "macos")]
@@ -1238,11 +3651,30 @@ impl Delegate<App> for AppDelegate {
               data.settings.install_dir.clone().unwrap_or_default(),
             )));
           }
+          if data.settings.install_dir.is_none() {
+            let ext_ctx = ctx.get_external_handle();
+            data.runtime.spawn_blocking(move || {
+              let installs = settings::discovery::find_installs();
+              let _ = ext_ctx.submit_command(
+                Popup::SHOW,
+                Popup::SelectInstall(installs),
+                Target::Auto,
+              );
+            });
+          }
+          ctx.submit_command(App::CHECK_SELF_UPDATE);
+          if let Some(interrupted) = Interrupted::load() {
+            ctx.submit_command(bisect::INTERRUPTED.with(interrupted));
+          }
           let ext_ctx = ctx.get_external_handle();
           data.runtime.spawn(async move {
-            let release = get_latest_manager().await;
-            ext_ctx.submit_command(App::UPDATE_AVAILABLE, release, Target::Auto)
+            let repo = ModRepo::get_mod_repo().await.map_err(|err| format!("{:?}", err));
+            ext_ctx.submit_command(ModRepo::REFRESHED, repo, Target::Auto)
           });
+          data
+            .runtime
+            .spawn(update_check::schedule(ctx.get_external_handle()));
+          data.refresh_compat_db(ctx.get_external_handle());
         }
       }
       Event::KeyDown(KeyEvent {
@@ -1251,14 +3683,14 @@ impl Delegate<App> for AppDelegate {
         ctx.submit_command(App::DUMB_UNIVERSAL_ESCAPE);
         return None;
       }
-      Event::WindowSize(Size { width, height }) => {
-        if Some(window_id) == self.root_id && let Some(webview) = &data.webview {
-          webview.set_bounds(wry::Rect {
-            x: 0,
-            y: WEBVIEW_OFFSET.into(),
-            width: width as u32,
-            height: height as u32,
-          })
+      // moving to a monitor with a different scale doesn't resize the window in display points,
+      // but does change where the webview goes on Windows
+      Event::WindowSize(_) | Event::WindowScale(_) => {
+        if Some(window_id) == self.root_id
+          && let Some(webview) = &data.webview
+          && let Some(window) = &self.root_window
+        {
+          webview.set_bounds(webview_bounds(window, data.settings.ui_scale))
         }
       }
       _ => {}
@@ -1270,13 +3702,25 @@ impl Delegate<App> for AppDelegate {
 
 impl AppDelegate {
   fn build_log_window() -> impl Widget<App> {
-    let modal = Modal::new("Log").with_content("").with_content(
+    let modal = Modal::new(&tr("log-title")).with_content("").with_content(
       List::new(|| Label::wrapped_func(|val: &String, _| val.clone()))
         .lens(App::log)
         .boxed(),
     );
 
-    modal.with_button("Close", App::CLEAR_LOG).build().boxed()
+    modal
+      .with_button(&tr("close"), App::CLEAR_LOG)
+      .build()
+      .boxed()
+  }
+
+  /// Abandons the current Mega download, keeping it in the downloads window so it can be retried.
+  fn fail_blob_download(&mut self, ctx: &mut DelegateCtx, err: String) {
+    if let Some(download) = self.blob_download.take() {
+      error!("Mega download failed: {}", err);
+      ctx.submit_command(App::DOWNLOAD_FAILED.with((download.started, download.uri.clone(), err)));
+      download.discard();
+    }
   }
 
   fn display_if_closed(&mut self, ctx: &mut DelegateCtx, window_type: SubwindowType) {
@@ -1285,16 +3729,20 @@ impl AppDelegate {
       SubwindowType::Overwrite => &mut self.overwrite_window,
       SubwindowType::Duplicate => &mut self.duplicate_window,
       SubwindowType::Download => &mut self.download_window,
+      SubwindowType::Install => &mut self.install_window,
     };
 
     if let Some(id) = window_id {
-      ctx.submit_command(commands::SHOW_WINDOW.to(*id))
+      ctx.submit_command(commands::SHOW_WINDOW.to(*id));
+      // a new window raises them from window_added, but one that's only brought forward doesn't
+      self.raise_critical_popups(ctx);
     } else {
       let modal = match window_type {
         SubwindowType::Log => AppDelegate::build_log_window().boxed(),
         SubwindowType::Overwrite => AppDelegate::build_overwrite_window().boxed(),
         SubwindowType::Duplicate => AppDelegate::build_duplicate_window().boxed(),
         SubwindowType::Download => AppDelegate::build_progress_bars().boxed(),
+        SubwindowType::Install => AppDelegate::build_install_queue().boxed(),
       };
 
       let window = WindowDesc::new(modal)
@@ -1308,87 +3756,95 @@ impl AppDelegate {
     }
   }
 
+  fn raise_critical_popups(&self, ctx: &mut DelegateCtx) {
+    for id in &self.critical_popups {
+      ctx.submit_command(commands::SHOW_WINDOW.to(*id))
+    }
+  }
+
   fn build_overwrite_window() -> impl Widget<App> {
     ViewSwitcher::new(
       |data: &App, _| data.overwrite_log.len(),
       |_, data: &App, _| {
-        let mut modal = Modal::new("Overwrite?");
+        let mut modal = Modal::new(&tr("overwrite-title"));
 
         for val in data.overwrite_log.iter() {
           let (conflict, to_install, entry) = val.as_ref();
           modal = modal
             .with_content(match conflict {
-              StringOrPath::String(id) => {
-                format!("A mod with ID {} alread exists.", id)
-              }
-              StringOrPath::Path(path) => format!(
-                "Found a folder at the path {} when trying to install {}.",
-                path.to_string_lossy(),
-                entry.id
+              StringOrPath::String(id) => tr_args("overwrite-id-exists", &[("id", id)]),
+              StringOrPath::Path(path) => tr_args(
+                "overwrite-folder-exists",
+                &[("path", &path.to_string_lossy()), ("id", &entry.id)],
               ),
             })
             .with_content(
-              Maybe::or_empty(|| {
-                Label::wrapped(
-                  "\
-              NOTE: A .git directory has been detected in the target directory. \
-              Are you sure this isn't being used for development?\
-            ",
-                )
-              })
-              .lens(lens::Constant(
-                data
-                  .settings
-                  .git_warn
-                  .then(|| {
-                    if entry.path.join(".git").exists() {
-                      Some(())
-                    } else {
-                      None
-                    }
-                  })
-                  .flatten(),
-              ))
-              .boxed(),
+              Maybe::or_empty(|| Label::wrapped(tr("update-git-warning")))
+                .lens(lens::Constant(
+                  data
+                    .settings
+                    .git_warn
+                    .then(|| {
+                      if entry.path.join(".git").exists() {
+                        Some(())
+                      } else {
+                        None
+                      }
+                    })
+                    .flatten(),
+                ))
+                .boxed(),
             )
-            .with_content(format!(
-              "Would you like to replace the existing {}?",
-              if let StringOrPath::String(_) = conflict {
-                "mod"
-              } else {
-                "folder"
-              }
-            ))
+            .with_content(tr(if let StringOrPath::String(_) = conflict {
+              "overwrite-replace-mod"
+            } else {
+              "overwrite-replace-folder"
+            }))
             .with_content(
               Flex::row()
                 .with_flex_spacer(1.)
-                .with_child(Button::new("Overwrite").on_click({
-                  let conflict = conflict.clone();
-                  let to_install = to_install.clone();
-                  let entry = entry.clone();
-                  move |ctx: &mut EventCtx, data: &mut App, _| {
-                    ctx.submit_command(
-                      App::REMOVE_OVERWRITE_LOG_ENTRY
-                        .with(conflict.clone())
-                        .to(Target::Global),
-                    );
-                    ctx.submit_command(
-                      ModList::OVERWRITE
-                        .with((
-                          match &conflict {
-                            StringOrPath::String(id) => {
-                              data.mod_list.mods.get(id).unwrap().path.clone()
-                            }
-                            StringOrPath::Path(path) => path.clone(),
-                          },
-                          to_install.clone(),
-                          entry.clone(),
-                        ))
-                        .to(Target::Global),
-                    );
+                .pipe(|mut row| {
+                  for (label, mode) in [
+                    (tr("overwrite-replace"), OverwriteMode::Replace),
+                    (tr("overwrite-merge"), OverwriteMode::Merge),
+                  ] {
+                    let button = Button::new(label).on_activate({
+                      let conflict = conflict.clone();
+                      let to_install = to_install.clone();
+                      let entry = entry.clone();
+                      move |ctx: &mut EventCtx, data: &mut App, _| {
+                        ctx.submit_command(
+                          App::REMOVE_OVERWRITE_LOG_ENTRY
+                            .with(conflict.clone())
+                            .to(Target::Global),
+                        );
+                        ctx.submit_command(
+                          ModList::OVERWRITE
+                            .with((
+                              match &conflict {
+                                StringOrPath::String(id) => {
+                                  data.mod_list.get(id).unwrap().path.clone()
+                                }
+                                StringOrPath::Path(path) => path.clone(),
+                              },
+                              to_install.clone(),
+                              entry.clone(),
+                              mode,
+                            ))
+                            .to(Target::Global),
+                        );
+                      }
+                    });
+                    row.add_child(if mode == OverwriteMode::Merge {
+                      button.stack_tooltip(tr("overwrite-merge-tooltip")).boxed()
+                    } else {
+                      button.boxed()
+                    });
                   }
-                }))
-                .with_child(Button::new("Cancel").on_click({
+
+                  row
+                })
+                .with_child(Button::new(tr("cancel")).on_activate({
                   let conflict = conflict.clone();
                   move |ctx, _, _| {
                     ctx.submit_command(App::REMOVE_OVERWRITE_LOG_ENTRY.with(conflict.clone()));
@@ -1407,10 +3863,13 @@ impl AppDelegate {
 
         if data.overwrite_log.len() > 1 {
           modal
-            .with_button("Overwrite All", App::CLEAR_OVERWRITE_LOG.with(true))
-            .with_button("Cancel All", App::CLEAR_OVERWRITE_LOG.with(false))
+            .with_button(&tr("overwrite-all"), App::CLEAR_OVERWRITE_LOG.with(true))
+            .with_button(
+              &tr("overwrite-cancel-all"),
+              App::CLEAR_OVERWRITE_LOG.with(false),
+            )
         } else {
-          modal.with_button("Close", App::CLEAR_OVERWRITE_LOG.with(false))
+          modal.with_button(&tr("close"), App::CLEAR_OVERWRITE_LOG.with(false))
         }
         .build()
         .boxed()
@@ -1420,27 +3879,39 @@ impl AppDelegate {
 
   fn build_duplicate_window() -> impl Widget<App> {
     ViewSwitcher::new(
-      |app: &App, _| app.duplicate_log.len(),
+      |app: &App, _| app.duplicate_log.clone(),
       |_, app, _| {
-        Modal::new("Duplicate detected")
+        Modal::new(&tr("duplicate-title"))
           .pipe(|mut modal| {
-            for (dupe_a, dupe_b) in &app.duplicate_log {
+            for copies in &app.duplicate_log {
+              let Some(first) = copies.front() else {
+                continue;
+              };
+              let total: u64 = copies.iter().map(|copy| copy.size).sum();
               modal = modal
-                .with_content(format!(
-                  "Detected duplicate installs of mod with ID {}.",
-                  dupe_a.id
+                .with_content(tr_args(
+                  "duplicate-detected",
+                  &[
+                    ("count", &copies.len()),
+                    ("id", &first.id),
+                    ("size", &format_size(total)),
+                  ],
                 ))
                 .with_content(
                   Flex::row()
-                    .with_flex_child(Self::make_dupe_col(dupe_a, dupe_b), 1.)
-                    .with_flex_child(Self::make_dupe_col(dupe_b, dupe_a), 1.)
+                    .tap_mut(|row| {
+                      for copy in copies {
+                        row.add_flex_child(Self::make_dupe_col(copy, copies), 1.)
+                      }
+                    })
+                    .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
                     .boxed(),
                 )
                 .with_content(
                   Flex::row()
                     .with_flex_spacer(1.)
-                    .with_child(Button::new("Ignore").on_click({
-                      let id = dupe_a.id.clone();
+                    .with_child(Button::new(tr("duplicate-ignore")).on_activate({
+                      let id = first.id.clone();
                       move |ctx, _, _| {
                         ctx.submit_command(
                           App::REMOVE_DUPLICATE_LOG_ENTRY
@@ -1455,59 +3926,86 @@ impl AppDelegate {
             }
             modal
           })
-          .with_button("Ignore All", App::CLEAR_DUPLICATE_LOG)
+          .with_button(&tr("duplicate-ignore-all"), App::CLEAR_DUPLICATE_LOG)
           .build()
           .boxed()
       },
     )
   }
 
-  fn make_dupe_col(dupe_a: &Arc<ModEntry>, dupe_b: &Arc<ModEntry>) -> Flex<App> {
-    let meta = metadata(&dupe_a.path);
+  fn make_dupe_col(keep: &Arc<ModEntry>, copies: &Vector<Arc<ModEntry>>) -> Flex<App> {
+    let meta = metadata(&keep.path);
+    let others: Vec<Arc<ModEntry>> = copies
+      .iter()
+      .filter(|copy| copy.path != keep.path)
+      .cloned()
+      .collect();
+    let resolve = move |archive: bool| {
+      let keep = keep.clone();
+      let others = others.clone();
+      move |ctx: &mut EventCtx, _: &mut App, _: &Env| {
+        ctx.submit_command(
+          App::REMOVE_DUPLICATE_LOG_ENTRY
+            .with(keep.id.clone())
+            .to(Target::Global),
+        );
+        ctx.submit_command(
+          App::RESOLVE_DUPLICATES
+            .with((keep.clone(), others.clone(), archive))
+            .to(Target::Global),
+        )
+      }
+    };
+
     Flex::column()
-      .with_child(Label::wrapped(format!("Version: {}", dupe_a.version)))
-      .with_child(Label::wrapped(format!(
-        "Path: {}",
-        dupe_a.path.to_string_lossy()
+      .with_child(Label::wrapped(tr_args(
+        "duplicate-version",
+        &[("version", &keep.version)],
       )))
-      .with_child(Label::wrapped(format!(
-        "Last modified: {}",
-        if let Ok(Ok(time)) = meta.as_ref().map(|meta| meta.modified()) {
-          DateTime::<Local>::from(time).format("%F:%R").to_string()
-        } else {
-          "Failed to retrieve last modified".to_string()
-        }
+      .with_child(Label::wrapped(tr_args(
+        "duplicate-path",
+        &[("path", &keep.path.to_string_lossy())],
       )))
-      .with_child(Label::wrapped(format!(
-        "Created at: {}",
-        meta.and_then(|meta| meta.created()).map_or_else(
-          |_| "Failed to retrieve creation time".to_string(),
-          |time| { DateTime::<Local>::from(time).format("%F:%R").to_string() }
-        )
+      .with_child(Label::wrapped(tr_args(
+        "duplicate-size",
+        &[("size", &format_size(keep.size))],
       )))
-      .with_child(Button::new("Keep").on_click({
-        let id = dupe_a.id.clone();
-        let path = dupe_b.path.clone();
-        let dupe_a = dupe_a.clone();
-        move |ctx, _, _| {
-          ctx.submit_command(
-            App::REMOVE_DUPLICATE_LOG_ENTRY
-              .with(id.clone())
-              .to(Target::Global),
-          );
-          ctx.submit_command(
-            App::DELETE_AND_SUMBIT
-              .with((path.clone(), dupe_a.clone()))
-              .to(Target::Global),
-          )
-        }
-      }))
+      .with_child(Label::wrapped(tr_args(
+        "duplicate-modified",
+        &[(
+          "time",
+          &if let Ok(Ok(time)) = meta.as_ref().map(|meta| meta.modified()) {
+            DateTime::<Local>::from(time).format("%F:%R").to_string()
+          } else {
+            tr("duplicate-modified-unknown")
+          },
+        )],
+      )))
+      .with_child(Label::wrapped(tr_args(
+        "duplicate-created",
+        &[(
+          "time",
+          &meta.and_then(|meta| meta.created()).map_or_else(
+            |_| tr("duplicate-created-unknown"),
+            |time| DateTime::<Local>::from(time).format("%F:%R").to_string(),
+          ),
+        )],
+      )))
+      .with_child(Button::new(tr("duplicate-keep-delete")).on_activate(resolve(false)))
+      .with_child(
+        Button::new(tr("duplicate-keep-archive"))
+          .on_activate(resolve(true))
+          .stack_tooltip(tr_args(
+            "duplicate-keep-archive-tooltip",
+            &[("dir", &archive::archive_dir().to_string_lossy())],
+          )),
+      )
       .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
       .main_axis_alignment(druid::widget::MainAxisAlignment::Start)
   }
 
   fn build_progress_bars() -> impl Widget<App> {
-    Modal::new("Downloads")
+    Modal::new(&tr("downloads-title"))
       .with_content(
         List::new(|| {
           Flex::column()
@@ -1516,7 +4014,7 @@ impl AppDelegate {
               Label::wrapped_func(|data, _| {
                 let start_time = Local.timestamp_opt(*data, 0).unwrap().format("%I:%M%p");
 
-                format!("Started at: {}", start_time)
+                tr_args("downloads-started-at", &[("time", &start_time)])
               })
               .lens(lens!((i64, String, f64), 0)),
             )
@@ -1538,78 +4036,347 @@ impl AppDelegate {
                   )
                   .lens(lens!((i64, String, f64), 2)),
                 )
-                .with_child(
-                  Either::new(
-                    |fraction, _| *fraction < 1.0,
-                    Icon::new(CLOSE).with_color(druid::Color::GRAY),
-                    Icon::new(CLOSE),
-                  )
-                  .lens(lens!((i64, String, f64), 2))
-                  .controller(HoverController)
-                  .on_click(|ctx, data, _| {
-                    ctx.submit_command(App::REMOVE_DOWNLOAD_BAR.with(data.0))
-                  })
-                  .disabled_if(|data, _| data.2 < 1.0),
-                ),
+                .with_child(Either::new(
+                  |data: &(i64, String, f64), _| data.2 < 1.0,
+                  Button2::new(Label::new(tr("cancel")).with_text_size(14.)).on_activate(
+                    |ctx, (timestamp, _, _): &mut (i64, String, f64), _| {
+                      // downloads from Mega are streamed in by the webview and can't be stopped
+                      if installer::cancel_download(*timestamp) {
+                        ctx.submit_command(App::REMOVE_DOWNLOAD_BAR.with(*timestamp))
+                      }
+                    },
+                  ),
+                  Icon::new(CLOSE)
+                    .controller(HoverController)
+                    .on_activate(|ctx, data: &mut (i64, String, f64), _| {
+                      ctx.submit_command(App::REMOVE_DOWNLOAD_BAR.with(data.0))
+                    })
+                    .labelled(tr("download-dismiss")),
+                )),
             )
             .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
         })
         .lens(App::downloads)
         .boxed(),
       )
+      .with_content(
+        List::new(|| {
+          Flex::row()
+            .with_flex_child(
+              Flex::column()
+                .with_child(Label::new(tr_args(
+                  "downloads-failed",
+                  &[("name", &BlobDownload::NAME)],
+                )))
+                .with_child(Label::wrapped_lens(lens!((i64, String, String), 2)))
+                .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+                .expand_width(),
+              1.,
+            )
+            .with_child(Icon::new(REPORT))
+            .with_child(
+              Button2::new(Label::new(tr("download-retry")).with_text_size(14.)).on_activate(
+                |ctx, (timestamp, _, _): &mut (i64, String, String), _| {
+                  ctx.submit_command(App::RETRY_DOWNLOAD.with(*timestamp))
+                },
+              ),
+            )
+            .with_child(Icon::new(CLOSE).controller(HoverController).on_click(
+              |ctx, (timestamp, _, _): &mut (i64, String, String), _| {
+                ctx.submit_command(App::REMOVE_DOWNLOAD_BAR.with(*timestamp))
+              },
+            ))
+        })
+        .lens(App::failed_downloads)
+        .boxed(),
+      )
       .with_close()
       .build()
   }
 
-  fn build_found_multiple(source: HybridPath, found_paths: Vec<PathBuf>) -> impl Widget<App> {
-    let title = format!(
-      "Found multiple mods in {}",
-      match source {
-        HybridPath::PathBuf(_) => "folder",
-        HybridPath::Temp(_, _, _) => "archive",
+  fn show_install_dir_warning(ctx: &mut DelegateCtx, install_dir: &Path) {
+    let modal = Modal::<App>::new(&tr("install-dir-warning-title"))
+      .with_content(tr_args(
+        "install-dir-warning",
+        &[("path", &install_dir.to_string_lossy())],
+      ))
+      .with_content(tr("install-dir-warning-hint"))
+      .with_button(
+        &tr("install-dir-warning-confirm"),
+        Settings::SELECTOR.with(SettingsCommand::ConfirmInstallDir),
+      )
+      .with_close_label(&tr("cancel"))
+      .build();
+
+    let window = WindowDesc::new(modal)
+      .window_size((450., 200.))
+      .show_titlebar(false)
+      .set_level(WindowLevel::AppWindow);
+
+    ctx.new_window(window)
+  }
+
+  fn build_install_queue() -> impl Widget<App> {
+    Modal::new(&tr("install-queue-title"))
+      .with_content(
+        List::new(|| {
+          Flex::row()
+            .with_flex_child(
+              Flex::column()
+                .with_child(Label::wrapped_lens(lens!((i64, String, InstallStatus), 1)))
+                .with_child(
+                  Label::wrapped_func(|status: &InstallStatus, _| status.to_string())
+                    .lens(lens!((i64, String, InstallStatus), 2)),
+                )
+                .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+                .expand_width(),
+              1.,
+            )
+            .with_child(
+              ViewSwitcher::new(
+                |status: &InstallStatus, _| status.clone(),
+                |status, _, _| match status {
+                  InstallStatus::Queued => Spinner::new().boxed(),
+                  InstallStatus::Extracting(progress) => {
+                    progress.inline_view().fix_width(160.).boxed()
+                  }
+                  InstallStatus::AwaitingDecision(_) => Icon::new(HELP).boxed(),
+                  InstallStatus::Done => Icon::new(VERIFIED).boxed(),
+                  InstallStatus::Failed(_) | InstallStatus::Cancelled => Icon::new(REPORT).boxed(),
+                },
+              )
+              .lens(lens!((i64, String, InstallStatus), 2)),
+            )
+            .with_child(Either::new(
+              |(_, _, status): &(i64, String, InstallStatus), _| status.is_active(),
+              Button2::new(Label::new(tr("cancel")).with_text_size(14.)).on_activate(
+                |_, (id, _, status): &mut (i64, String, InstallStatus), _| {
+                  if installer::cancel(*id) {
+                    *status = InstallStatus::Cancelled
+                  }
+                },
+              ),
+              Icon::new(CLOSE)
+                .controller(HoverController)
+                .on_activate(|ctx, (id, _, _): &mut (i64, String, InstallStatus), _| {
+                  ctx.submit_command(App::REMOVE_INSTALL_QUEUE_ENTRY.with(*id))
+                })
+                .labelled(tr("install-queue-dismiss")),
+            ))
+        })
+        .lens(App::install_queue)
+        .boxed(),
+      )
+      .with_close()
+      .build()
+  }
+
+  fn install_history_modal<'a>() -> Modal<'a, ()> {
+    let entries = install_log::read();
+    let mut list = Flex::column();
+    for entry in &entries {
+      let mut row = Flex::row().with_flex_child(
+        Flex::column()
+          .with_child(bold_text(
+            &entry.describe(),
+            druid::theme::TEXT_SIZE_NORMAL,
+            druid::FontWeight::SEMI_BOLD,
+            druid::theme::TEXT_COLOR,
+          ))
+          .with_child(Label::wrapped(match &entry.source {
+            Some(source) => tr_args(
+              "install-log-source",
+              &[("time", &entry.time.format("%F %R")), ("source", source)],
+            ),
+            None => entry.time.format("%F %R").to_string(),
+          }))
+          .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+          .expand_width(),
+        1.,
+      );
+      if let Some(url) = entry.url() {
+        let url = url.to_string();
+        row.add_child(
+          Button2::new(Label::new(tr("install-log-download-again")).padding((10., 0.)))
+            .on_activate(move |ctx, _, _| ctx.submit_command(App::DOWNLOAD_ONLY.with(url.clone()))),
+        );
+      } else if let Some(path) = entry.local_source() {
+        row.add_child(
+          Button2::new(Label::new(tr("install-log-install-again")).padding((10., 0.))).on_activate(
+            move |ctx, _, _| {
+              ctx.submit_command(WEBVIEW_INSTALL.with(InstallType::Path(path.clone())))
+            },
+          ),
+        );
       }
-    );
+      list.add_child(Card::new(row).expand_width());
+    }
+
+    Modal::new(&tr("install-log-title"))
+      .pipe(|modal| {
+        if entries.is_empty() {
+          modal.with_content(tr("install-log-empty"))
+        } else {
+          modal
+            .with_content(tr("install-log-hint"))
+            .with_content(list.scroll().vertical().boxed())
+        }
+      })
+      .with_button(&tr("install-log-open"), |_: &mut EventCtx, _: &mut ()| {
+        let _ = opener::open(install_log::path());
+      })
+      .with_close()
+  }
+
+  fn build_install_report(title: &str, report: &[InstallOutcome]) -> impl Widget<App> {
+    let installed = report
+      .iter()
+      .filter(|outcome| matches!(outcome, InstallOutcome::Installed(..)))
+      .count();
+    let skipped = report
+      .iter()
+      .filter(|outcome| matches!(outcome, InstallOutcome::Skipped(..)))
+      .count();
+    let failed = report.len() - installed - skipped;
+
+    Modal::new(title)
+      .with_content(tr_args(
+        "install-report-summary",
+        &[
+          ("installed", &installed),
+          ("skipped", &skipped),
+          ("failed", &failed),
+        ],
+      ))
+      .pipe(|mut modal| {
+        for outcome in report {
+          modal = match outcome {
+            InstallOutcome::Installed(source, id, name) => modal.with_content(
+              Flex::row()
+                .with_child(Icon::new(VERIFIED))
+                .with_flex_child(
+                  Label::wrapped(tr_args(
+                    "install-report-installed",
+                    &[("source", source), ("name", name)],
+                  ))
+                  .expand_width(),
+                  1.,
+                )
+                .with_child(
+                  Button2::new(Label::new(tr("install-report-view")).with_text_size(14.))
+                    .on_activate({
+                      let id = id.clone();
+                      move |ctx, data: &mut App, _| {
+                        if let Some(entry) = data.mod_list.get(&id) {
+                          ctx.submit_command_global(
+                            App::SELECTOR
+                              .with(AppCommands::UpdateModDescription(entry.path.clone())),
+                          )
+                        }
+                      }
+                    }),
+                )
+                .boxed(),
+            ),
+            InstallOutcome::Skipped(source, reason) => modal.with_content(
+              Flex::row()
+                .with_child(Icon::new(INFO))
+                .with_flex_child(
+                  Label::wrapped(tr_args(
+                    "install-report-skipped",
+                    &[("source", source), ("reason", reason)],
+                  ))
+                  .expand_width(),
+                  1.,
+                )
+                .boxed(),
+            ),
+            InstallOutcome::Failed(source, reason) => modal.with_content(
+              Flex::row()
+                .with_child(Icon::new(REPORT))
+                .with_flex_child(
+                  Label::wrapped(tr_args(
+                    "install-report-failed",
+                    &[("source", source), ("reason", reason)],
+                  ))
+                  .expand_width(),
+                  1.,
+                )
+                .boxed(),
+            ),
+          }
+        }
+
+        modal
+      })
+      .with_close()
+      .build()
+  }
+
+  fn build_found_multiple(source: HybridPath, found_paths: Vec<PathBuf>) -> impl Widget<App> {
+    let title = tr(match source {
+      HybridPath::PathBuf(_) => "install-found-title-folder",
+      HybridPath::Temp(_, _, _) => "install-found-title-archive",
+    });
 
     let mods = found_paths
       .iter()
       .filter_map(|path| ModEntry::from_file(path, ModMetadata::default()).ok())
-      .map(|entry| (true, entry))
+      .map(|entry| (true, true, entry))
       .collect::<Vector<_>>();
 
     let modal = Modal::new(&title)
+      .with_content(tr("install-found-prompt"))
       .pipe(|mut modal| {
-        for (idx, (_, mod_)) in mods.iter().enumerate() {
+        for (idx, (_, _, mod_)) in mods.iter().enumerate() {
           modal = modal
             .with_content(
-              Label::wrapped(format!("Found mod with ID: {}", mod_.id))
-                .or_empty(|(data, _): &(bool, ModEntry), _| *data)
+              Flex::row()
+                .with_child(Checkbox::new("").lens(lens!((bool, bool, ModEntry), 1)))
+                .with_flex_child(
+                  Label::wrapped(tr_args(
+                    "install-found-mod",
+                    &[
+                      ("name", &mod_.name),
+                      ("version", &mod_.version),
+                      ("id", &mod_.id),
+                    ],
+                  ))
+                  .expand_width(),
+                  1.,
+                )
+                .or_empty(|(data, _, _): &(bool, bool, ModEntry), _| *data)
                 .lens(lens::Index::new(idx))
                 .boxed(),
             )
             .with_content(
               Flex::row()
                 .with_flex_child(
-                  Label::wrapped(format!("At path: {}", mod_.path.to_string_lossy()))
-                    .expand_width(),
+                  Label::wrapped(tr_args(
+                    "install-found-path",
+                    &[("path", &mod_.path.to_string_lossy())],
+                  ))
+                  .expand_width(),
                   1.,
                 )
                 .with_child(
-                  Button2::new(Label::new("Open path").with_text_size(14.)).on_click({
-                    let path = mod_.path.clone();
-                    move |_, _, _| {
-                      let _ = opener::open(path.clone());
-                    }
-                  }),
+                  Button2::new(Label::new(tr("install-found-open-path")).with_text_size(14.))
+                    .on_activate({
+                      let path = mod_.path.clone();
+                      move |_, _, _| {
+                        let _ = opener::open(path.clone());
+                      }
+                    }),
                 )
-                .or_empty(|(data, _): &(bool, ModEntry), _| *data)
+                .or_empty(|(data, _, _): &(bool, bool, ModEntry), _| *data)
                 .lens(lens::Index::new(idx))
                 .boxed(),
             )
             .with_content(
-              Button2::from_label("Install")
-                .on_click({
+              Button2::from_label(tr("install-found-install"))
+                .on_activate({
                   let source = source.clone();
-                  move |ctx, (show, entry): &mut (bool, ModEntry), _| {
+                  move |ctx, (show, _, entry): &mut (bool, bool, ModEntry), _| {
                     *show = false;
 
                     let mut vec = Vector::new();
@@ -1619,7 +4386,7 @@ impl AppDelegate {
                     )
                   }
                 })
-                .or_empty(|(data, _): &(bool, ModEntry), _| *data)
+                .or_empty(|(data, _, _): &(bool, bool, ModEntry), _| *data)
                 .lens(lens::Index::new(idx))
                 .boxed(),
             )
@@ -1627,25 +4394,99 @@ impl AppDelegate {
 
         modal
       })
-      .with_button("Install All", {
+      .with_button(&tr("install-found-install-selected"), {
+        let source = source.clone();
+        move |ctx: &mut EventCtx, data: &mut Vector<(bool, bool, ModEntry)>| {
+          ctx.submit_command_global(
+            INSTALL_ALL.with(SingleUse::new((
+              data
+                .iter()
+                .filter_map(|(install, selected, entry)| {
+                  (*install && *selected).then(|| entry.path.clone())
+                })
+                .collect(),
+              source,
+            ))),
+          )
+        }
+      })
+      .with_button(&tr("install-found-install-all"), {
         let source = source.clone();
-        move |ctx: &mut EventCtx, data: &mut Vector<(bool, ModEntry)>| {
+        move |ctx: &mut EventCtx, data: &mut Vector<(bool, bool, ModEntry)>| {
           ctx.submit_command_global(
             INSTALL_ALL.with(SingleUse::new((
               data
                 .iter()
-                .filter_map(|(install, entry)| install.then(|| entry.path.clone()))
+                .filter_map(|(install, _, entry)| install.then(|| entry.path.clone()))
                 .collect(),
               source,
             ))),
           )
         }
       })
-      .with_close_label("Ignore All")
+      .with_close_label(&tr("install-found-ignore-all"))
       .build();
 
     Scope::from_function(move |_| mods, DummyTransfer::default(), modal)
   }
+
+  fn build_bulk_confirmation(
+    confirmation: BulkConfirmation,
+    enabled: bool,
+    shown_only: bool,
+    affected: usize,
+  ) -> impl Widget<App> {
+    let title = tr(match (enabled, shown_only) {
+      (true, true) => "bulk-enable-shown",
+      (true, false) => "bulk-enable-all",
+      (false, true) => "bulk-disable-shown",
+      (false, false) => "bulk-disable-all",
+    });
+    let (action, prompt) = if enabled {
+      ("bulk-enable", "bulk-enable-prompt")
+    } else {
+      ("bulk-disable", "bulk-disable-prompt")
+    };
+
+    let modal = Modal::<(String, bool)>::new(&title)
+      .with_content(tr_args(prompt, &[("count", &affected)]))
+      .pipe(|modal| match confirmation {
+        BulkConfirmation::Never => modal,
+        BulkConfirmation::Checkbox => modal.with_content(
+          Checkbox::new(tr("bulk-understand"))
+            .lens(lens!((String, bool), 1))
+            .boxed(),
+        ),
+        BulkConfirmation::TypeWord => modal
+          .with_content(tr_args(
+            "bulk-type-word",
+            &[("word", &BulkConfirmation::WORD)],
+          ))
+          .with_content(
+            TextBox::new()
+              .with_placeholder(BulkConfirmation::WORD)
+              .lens(lens!((String, bool), 0))
+              .expand_width()
+              .boxed(),
+          ),
+      })
+      .with_content(
+        Button::new(tr(action))
+          .controller(HoverController)
+          .on_activate(move |ctx, _, _| {
+            ctx.submit_command_global(App::SET_ALL_ENABLED.with((enabled, shown_only)));
+            ctx.submit_command(commands::CLOSE_WINDOW)
+          })
+          .disabled_if(move |(typed, ticked): &(String, bool), _| {
+            !confirmation.satisfied(typed, *ticked)
+          })
+          .boxed(),
+      )
+      .with_close_label(&tr("cancel"))
+      .build();
+
+    Scope::from_function(|_| (String::new(), false), DummyTransfer::default(), modal)
+  }
 }
 
 enum SubwindowType {
@@ -1653,4 +4494,5 @@ enum SubwindowType {
   Overwrite,
   Duplicate,
   Download,
+  Install,
 }
@@ -0,0 +1,489 @@
+//! Zipped copies of mod folders, kept in MOSS's data folder so a mod can be removed from the mods
+//! folder without losing it entirely.
+//!
+//! Archiving a mod zips it up and deletes its folder, restoring it unzips it back into the mods
+//! folder (or the mod library, if one is in use) and deletes the archive.
+//!
+//! The same zipping is used to export a mod as a release for its author to upload, minus the files
+//! that only matter while working on it, and to keep the last few versions of a mod that updates
+//! replaced, so an update that breaks a save can be rolled back.
+
+use std::{
+  ffi::OsStr,
+  fs::File,
+  io::{self, Write},
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use chrono::Local;
+use druid::{im::Vector, Selector};
+use webview_shared::PROJECT;
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+use super::{
+  installer::copy_dir_recursive,
+  mod_entry::{ModEntry, ModMetadata},
+};
+
+/// Archives a mod and removes it from the mod list.
+pub const ARCHIVE: Selector<Arc<ModEntry>> = Selector::new("archive.mod");
+/// The archived mod, and the new archive or why archiving failed.
+pub const ARCHIVED: Selector<(Arc<ModEntry>, Result<PathBuf, String>)> =
+  Selector::new("archive.mod.done");
+/// Restores the given archive.
+pub const RESTORE: Selector<PathBuf> = Selector::new("archive.restore");
+/// Asks where to save a release zip of a mod, then exports it there.
+pub const EXPORT: Selector<Arc<ModEntry>> = Selector::new("archive.export");
+/// Asks which replaced version to roll a mod back to.
+pub const ASK_ROLL_BACK: Selector<Arc<ModEntry>> = Selector::new("archive.roll_back.ask");
+/// Rolls a mod back to the given replaced version.
+pub const ROLL_BACK: Selector<(Arc<ModEntry>, PathBuf)> = Selector::new("archive.roll_back");
+
+/// How many versions replaced by updates are kept of each mod.
+const KEPT_VERSIONS: usize = 3;
+
+/// Left out of release zips: version control, editor settings, MOSS's own metadata and files
+/// operating systems leave lying around.
+const EXPORT_EXCLUDED_NAMES: [&str; 12] = [
+  ".git",
+  ".gitignore",
+  ".gitattributes",
+  ".github",
+  ".idea",
+  ".vscode",
+  ".DS_Store",
+  "Thumbs.db",
+  "desktop.ini",
+  "__MACOSX",
+  "nbproject",
+  ModMetadata::FILE_NAME,
+];
+/// Left out of release zips: compiled classes (which belong in the mod's jar) and editor backups.
+const EXPORT_EXCLUDED_EXTENSIONS: [&str; 5] = ["class", "iml", "bak", "tmp", "swp"];
+
+pub fn archive_dir() -> PathBuf {
+  PROJECT.data_dir().join("archive")
+}
+
+/// Zips `entry`'s folder into the archive dir, returning the archive's path. The folder itself is
+/// left alone. Archives keep the folder's name as their root, so unzipping one into the mods
+/// folder restores the mod.
+pub fn archive(entry: &ModEntry) -> anyhow::Result<PathBuf> {
+  let folder_name = entry
+    .path
+    .file_name()
+    .ok_or_else(|| anyhow::anyhow!("{} has no folder name", entry.path.to_string_lossy()))?;
+
+  std::fs::create_dir_all(archive_dir())?;
+  let path = archive_dir().join(format!(
+    "{}-{}-{}.zip",
+    sanitise(&entry.id),
+    sanitise(&entry.version.to_string()),
+    Local::now().format("%Y%m%d-%H%M%S")
+  ));
+
+  zip_folder(&entry.path, Path::new(folder_name), &path)
+    .map_err(|err| anyhow::Error::new(err).context(format!("Failed to archive {}", entry.name)))?;
+
+  Ok(path)
+}
+
+/// Zips `folder` into `destination` under `root`, removing the zip if that fails part way.
+fn zip_folder(folder: &Path, root: &Path, destination: &Path) -> io::Result<()> {
+  let mut zip = ZipWriter::new(File::create(destination)?);
+  let res = add_dir(&mut zip, folder, root).and_then(|_| {
+    zip.finish()?;
+    Ok(())
+  });
+  if res.is_err() {
+    let _ = std::fs::remove_file(destination);
+  }
+
+  res
+}
+
+pub fn replaced_dir(id: &str) -> PathBuf {
+  PROJECT.data_dir().join("replaced").join(sanitise(id))
+}
+
+/// Zips `entry`'s folder before an update replaces it, then forgets all but the newest
+/// [`KEPT_VERSIONS`] of the mod.
+pub fn keep_replaced(entry: &ModEntry) -> anyhow::Result<PathBuf> {
+  let dir = replaced_dir(&entry.id);
+  let path = keep_in(&dir, entry)?;
+  prune(&dir)?;
+
+  Ok(path)
+}
+
+fn keep_in(dir: &Path, entry: &ModEntry) -> anyhow::Result<PathBuf> {
+  std::fs::create_dir_all(dir)?;
+  // named so they sort by when they were replaced, with the version after the first underscore
+  let taken = Local::now().format("%Y%m%d-%H%M%S%3f").to_string();
+  let version = sanitise(&entry.version.to_string());
+  let mut count = 0;
+  let mut path = dir.join(format!("{}-{}_{}.zip", taken, count, version));
+  while path.exists() {
+    count += 1;
+    path = dir.join(format!("{}-{}_{}.zip", taken, count, version));
+  }
+
+  zip_folder(&entry.path, Path::new("mod"), &path).map_err(|err| {
+    anyhow::Error::new(err).context(format!(
+      "Failed to keep a copy of {} {}",
+      entry.name, version
+    ))
+  })?;
+
+  Ok(path)
+}
+
+fn prune(dir: &Path) -> io::Result<()> {
+  for old in replaced_in(dir).into_iter().skip(KEPT_VERSIONS) {
+    std::fs::remove_file(old)?;
+  }
+
+  Ok(())
+}
+
+/// The kept versions of mod `id`, newest first.
+pub fn replaced_versions(id: &str) -> Vec<PathBuf> {
+  replaced_in(&replaced_dir(id))
+}
+
+fn replaced_in(dir: &Path) -> Vec<PathBuf> {
+  let mut kept: Vec<PathBuf> = dir
+    .read_dir()
+    .into_iter()
+    .flatten()
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| path.extension().is_some_and(|ext| ext == "zip"))
+    .collect();
+  kept.sort();
+  kept.reverse();
+
+  kept
+}
+
+/// The version of the mod a kept copy from [`keep_replaced`] holds.
+pub fn replaced_version(kept: &Path) -> String {
+  kept
+    .file_stem()
+    .map(|stem| stem.to_string_lossy())
+    .and_then(|stem| stem.split_once('_').map(|(_, version)| version.to_string()))
+    .unwrap_or_default()
+}
+
+/// Replaces `entry`'s folder with the kept copy `kept`, which is then forgotten. The version
+/// being replaced is kept in turn, so rolling back can itself be undone.
+pub fn roll_back(entry: &ModEntry, kept: &Path) -> anyhow::Result<()> {
+  let parent = entry
+    .path
+    .parent()
+    .ok_or_else(|| anyhow::anyhow!("{} has no parent folder", entry.path.to_string_lossy()))?;
+  let dir = kept.parent().unwrap_or(parent);
+
+  // unzipped next to the kept copies, so a failure can't leave a stray folder in the mods folder
+  let temp = tempfile::tempdir_in(dir)?;
+  ZipArchive::new(File::open(kept)?)?.extract(temp.path())?;
+  let unzipped = temp.path().join("mod");
+  if !unzipped.is_dir() {
+    anyhow::bail!("{} does not hold a mod", kept.to_string_lossy())
+  }
+
+  keep_in(dir, entry)?;
+  // the installed version is only deleted once the kept one is in its place
+  let folder_name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+  let aside = parent.join(format!(".{}.rolling-back", folder_name));
+  if aside.exists() {
+    // left by a roll back that was interrupted after the kept version was in place
+    remove_dir_all::remove_dir_all(&aside)?;
+  }
+  std::fs::rename(&entry.path, &aside)?;
+  let moved = std::fs::rename(&unzipped, &entry.path).or_else(|_| {
+    // the data folder may be on another drive
+    copy_dir_recursive(&entry.path, &unzipped)
+  });
+  if let Err(err) = moved {
+    if entry.path.exists() {
+      remove_dir_all::remove_dir_all(&entry.path)?;
+    }
+    std::fs::rename(&aside, &entry.path)?;
+    return Err(anyhow::Error::new(err).context("Failed to move the kept version into place"));
+  }
+
+  remove_dir_all::remove_dir_all(&aside)?;
+  std::fs::remove_file(kept)?;
+  prune(dir)?;
+
+  Ok(())
+}
+
+/// The file name a release zip of `entry` is offered under, such as `My_Mod-1.2.0.zip`.
+pub fn release_name(entry: &ModEntry) -> String {
+  format!(
+    "{}-{}.zip",
+    sanitise(&entry.name),
+    sanitise(&entry.version.to_string())
+  )
+}
+
+/// Zips `entry`'s folder into `destination` as a release, leaving out the files in
+/// [`EXPORT_EXCLUDED_NAMES`] and [`EXPORT_EXCLUDED_EXTENSIONS`].
+pub fn export(entry: &ModEntry, destination: &Path) -> anyhow::Result<()> {
+  let folder_name = entry
+    .path
+    .file_name()
+    .ok_or_else(|| anyhow::anyhow!("{} has no folder name", entry.path.to_string_lossy()))?;
+
+  let mut zip = ZipWriter::new(File::create(destination)?);
+  let res = add_dir_except(
+    &mut zip,
+    &entry.path,
+    Path::new(folder_name),
+    &excluded_from_release,
+  )
+  .and_then(|_| {
+    zip.finish()?;
+    Ok(())
+  });
+  if let Err(err) = res {
+    let _ = std::fs::remove_file(destination);
+    return Err(anyhow::Error::new(err).context(format!("Failed to export {}", entry.name)));
+  }
+
+  Ok(())
+}
+
+fn excluded_from_release(name: &OsStr) -> bool {
+  let name = name.to_string_lossy();
+
+  EXPORT_EXCLUDED_NAMES
+    .iter()
+    .any(|excluded| name.eq_ignore_ascii_case(excluded))
+    || Path::new(name.as_ref()).extension().is_some_and(|ext| {
+      EXPORT_EXCLUDED_EXTENSIONS
+        .iter()
+        .any(|excluded| ext == *excluded)
+    })
+}
+
+/// Every archive in the archive dir, oldest first.
+pub fn list() -> Vector<PathBuf> {
+  let mut archives: Vec<PathBuf> = archive_dir()
+    .read_dir()
+    .into_iter()
+    .flatten()
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| path.extension().is_some_and(|ext| ext == "zip"))
+    .collect();
+  archives.sort_by_key(|path| path.metadata().and_then(|meta| meta.modified()).ok());
+
+  archives.into()
+}
+
+/// Unzips `archive` into `mods_dir` and deletes it, returning the restored mod folder. Fails
+/// rather than overwriting if a folder of the same name already exists.
+pub fn restore(archive: &Path, mods_dir: &Path) -> anyhow::Result<PathBuf> {
+  let mut zip = ZipArchive::new(File::open(archive)?)?;
+  let root = (0..zip.len())
+    .find_map(|idx| {
+      zip
+        .by_index(idx)
+        .ok()?
+        .enclosed_name()?
+        .components()
+        .next()
+        .map(|root| PathBuf::from(root.as_os_str()))
+    })
+    .ok_or_else(|| anyhow::anyhow!("{} is empty", archive.to_string_lossy()))?;
+
+  let destination = mods_dir.join(root);
+  if destination.exists() {
+    anyhow::bail!(
+      "Could not restore {}, {} already exists",
+      archive.to_string_lossy(),
+      destination.to_string_lossy()
+    )
+  }
+
+  zip.extract(mods_dir)?;
+  std::fs::remove_file(archive)?;
+
+  Ok(destination)
+}
+
+fn add_dir(zip: &mut ZipWriter<File>, dir: &Path, prefix: &Path) -> io::Result<()> {
+  add_dir_except(zip, dir, prefix, &|_| false)
+}
+
+/// Like [`add_dir`], but skips any file or folder whose name `excluded` matches.
+fn add_dir_except(
+  zip: &mut ZipWriter<File>,
+  dir: &Path,
+  prefix: &Path,
+  excluded: &dyn Fn(&OsStr) -> bool,
+) -> io::Result<()> {
+  let options = FileOptions::default();
+  zip.add_directory(format!("{}/", zip_name(prefix)), options)?;
+
+  for entry in dir.read_dir()? {
+    let entry = entry?;
+    if excluded(&entry.file_name()) {
+      continue;
+    }
+    let name = prefix.join(entry.file_name());
+    if entry.file_type()?.is_dir() {
+      add_dir_except(zip, &entry.path(), &name, excluded)?;
+    } else {
+      zip.start_file(zip_name(&name), options)?;
+      zip.write_all(&std::fs::read(entry.path())?)?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Zip entries always use forward slashes.
+fn zip_name(path: &Path) -> String {
+  path
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+fn sanitise(text: &str) -> String {
+  text
+    .chars()
+    .map(|c| {
+      if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::Path;
+
+  use tempfile::tempdir;
+
+  use super::{
+    add_dir, export, keep_in, prune, replaced_in, replaced_version, restore, roll_back, sanitise,
+    zip_name, KEPT_VERSIONS,
+  };
+  use crate::app::mod_entry::{ModEntry, VersionUnion};
+
+  #[test]
+  fn names_are_portable() {
+    assert_eq!(sanitise("1.2.3 (RC/2)"), "1.2.3__RC_2_");
+    assert_eq!(
+      zip_name(&Path::new("my_mod").join("data").join("a.csv")),
+      "my_mod/data/a.csv"
+    );
+  }
+
+  #[test]
+  fn restores_archived_folder() {
+    let source = tempdir().expect("Create source dir");
+    let mod_dir = source.path().join("my_mod");
+    std::fs::create_dir_all(mod_dir.join("data")).expect("Create mod dir");
+    std::fs::write(mod_dir.join("data").join("a.csv"), "a,b").expect("Write mod file");
+
+    let archive = source.path().join("my_mod.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).expect("Create archive"));
+    add_dir(&mut zip, &mod_dir, Path::new("my_mod")).expect("Zip mod");
+    zip.finish().expect("Finish archive");
+
+    let mods = tempdir().expect("Create mods dir");
+    let restored = restore(&archive, mods.path()).expect("Restore archive");
+    assert_eq!(restored, mods.path().join("my_mod"));
+    assert_eq!(
+      std::fs::read_to_string(restored.join("data").join("a.csv")).expect("Read mod file"),
+      "a,b"
+    );
+    assert!(!archive.exists());
+
+    std::fs::write(&archive, []).ok();
+    assert!(restore(&archive, mods.path()).is_err());
+  }
+
+  #[test]
+  fn exports_without_junk() {
+    let source = tempdir().expect("Create source dir");
+    let mod_dir = source.path().join("my_mod");
+    for dir in ["data", ".git", "src"] {
+      std::fs::create_dir_all(mod_dir.join(dir)).expect("Create mod dir");
+    }
+    for file in [
+      "data/a.csv",
+      ".git/HEAD",
+      "src/Plugin.class",
+      "Thumbs.db",
+      "mod_info.json",
+    ] {
+      std::fs::write(mod_dir.join(file), "").expect("Write mod file");
+    }
+
+    let mut entry = ModEntry::default();
+    entry.path = mod_dir;
+    let release = source.path().join("release.zip");
+    export(&entry, &release).expect("Export mod");
+
+    let zip = zip::ZipArchive::new(std::fs::File::open(&release).expect("Open release"))
+      .expect("Read release");
+    let mut names: Vec<&str> = zip.file_names().collect();
+    names.sort();
+    assert_eq!(
+      names,
+      [
+        "my_mod/",
+        "my_mod/data/",
+        "my_mod/data/a.csv",
+        "my_mod/mod_info.json",
+        "my_mod/src/"
+      ]
+    );
+  }
+
+  #[test]
+  fn rolls_back_replaced_version() {
+    let mods = tempdir().expect("Create mods dir");
+    let kept = tempdir().expect("Create kept dir");
+    let mut entry = ModEntry::default();
+    entry.path = mods.path().join("my_mod");
+    std::fs::create_dir_all(&entry.path).expect("Create mod dir");
+    for version in ["1.0", "1.1", "1.2", "1.3"] {
+      std::fs::write(entry.path.join("version.txt"), version).expect("Write mod file");
+      entry.version = VersionUnion::String(version.to_string());
+      keep_in(kept.path(), &entry).expect("Keep version");
+      prune(kept.path()).expect("Prune kept versions");
+    }
+    std::fs::write(entry.path.join("version.txt"), "2.0").expect("Update mod");
+    entry.version = VersionUnion::String(String::from("2.0"));
+
+    let versions = replaced_in(kept.path());
+    assert_eq!(versions.len(), KEPT_VERSIONS);
+    assert_eq!(replaced_version(&versions[0]), "1.3");
+    assert_eq!(replaced_version(&versions[2]), "1.1");
+
+    roll_back(&entry, &versions[1]).expect("Roll back");
+    assert_eq!(
+      std::fs::read_to_string(entry.path.join("version.txt")).expect("Read mod file"),
+      "1.2"
+    );
+    let versions: Vec<String> = replaced_in(kept.path())
+      .into_iter()
+      .map(|kept| replaced_version(&kept))
+      .collect();
+    assert_eq!(versions, ["2.0", "1.3", "1.1"]);
+    assert_eq!(mods.path().read_dir().expect("Read mods dir").count(), 1);
+  }
+}
@@ -0,0 +1,260 @@
+//! A guided binary search for the mod that crashes the game.
+//!
+//! Starting from every enabled mod as a suspect, half of the suspects are left enabled and the
+//! player launches the game. If it crashes the culprit is in that half, otherwise it's in the
+//! other, and the search carries on with whichever half it was until one mod is left. Mods that
+//! have been cleared stay enabled, and a suspect's dependencies are always tested alongside it.
+//!
+//! The mods enabled when a search starts are saved until it's stopped or finished, so they can be
+//! put back if MOSS is closed part way through.
+
+use std::{
+  collections::{HashMap, HashSet},
+  path::PathBuf,
+};
+
+use druid::{Data, Lens, Selector};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use webview_shared::PROJECT;
+
+pub const STEP: Selector<Step> = Selector::new("bisect.step");
+/// A search was left running when MOSS last closed - offers to restore the mods from before it.
+pub const INTERRUPTED: Selector<Interrupted> = Selector::new("bisect.interrupted");
+/// Enables the mods from before an interrupted search again.
+pub const RESTORE: Selector<Interrupted> = Selector::new("bisect.interrupted.restore");
+/// Forgets an interrupted search, leaving the mods as they are.
+pub const DISCARD: Selector = Selector::new("bisect.interrupted.discard");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+  Start,
+  Crashed,
+  Worked,
+  /// Gives up, enabling the mods that were enabled at the start.
+  Stop,
+  /// Enables the mods that were enabled at the start, apart from the culprit.
+  Finish,
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Bisection {
+  /// Enabled mods when the search started, sorted.
+  #[data(same_fn = "PartialEq::eq")]
+  #[lens(ignore)]
+  original: Vec<String>,
+  /// Mods that might be the culprit.
+  #[data(same_fn = "PartialEq::eq")]
+  pub suspects: Vec<String>,
+  /// The suspects enabled in this step.
+  #[data(same_fn = "PartialEq::eq")]
+  pub testing: Vec<String>,
+  pub step: usize,
+  /// IDs of the mods each mod depends on.
+  #[data(ignore)]
+  dependencies: HashMap<String, Vec<String>>,
+}
+
+impl Bisection {
+  /// Starts a search of `enabled`, or `None` if there's nothing to search.
+  pub fn new(enabled: Vec<String>, dependencies: HashMap<String, Vec<String>>) -> Option<Self> {
+    if enabled.is_empty() {
+      return None;
+    }
+
+    let mut bisection = Self {
+      suspects: enabled.clone(),
+      original: enabled,
+      testing: Vec::new(),
+      step: 0,
+      dependencies,
+    };
+    bisection.next();
+
+    Some(bisection)
+  }
+
+  /// Whether the culprit is known - the last suspect, or the last few if they can't be tested
+  /// separately because they depend on each other.
+  pub fn done(&self) -> bool {
+    self.testing.is_empty()
+  }
+
+  pub fn crashed(&mut self) {
+    self.suspects = std::mem::take(&mut self.testing);
+    self.next()
+  }
+
+  pub fn worked(&mut self) {
+    let testing = std::mem::take(&mut self.testing);
+    self.suspects.retain(|id| !testing.contains(id));
+    self.next()
+  }
+
+  /// The mods to enable for this step, sorted. A mod that depends on one of the original mods
+  /// that isn't enabled, directly or not, is left disabled too, as it would only fail to load.
+  pub fn enabled(&self) -> Vec<String> {
+    let mut enabled: Vec<String> = self
+      .original
+      .iter()
+      .filter(|id| !self.suspects.contains(id) || self.testing.contains(id))
+      .cloned()
+      .collect();
+    if self.done() {
+      enabled.retain(|id| !self.suspects.contains(id))
+    }
+
+    // disabling a mod can leave another without its dependency, so repeat until nothing changes
+    loop {
+      let loadable: Vec<String> = enabled
+        .iter()
+        .filter(|id| {
+          self
+            .dependencies
+            .get(*id)
+            .into_iter()
+            .flatten()
+            .all(|dependency| !self.original.contains(dependency) || enabled.contains(dependency))
+        })
+        .cloned()
+        .collect();
+      if loadable.len() == enabled.len() {
+        return enabled;
+      }
+      enabled = loadable;
+    }
+  }
+
+  pub fn original(&self) -> &[String] {
+    &self.original
+  }
+
+  /// Picks the half of the suspects to test next, leaving it empty when they can't be split.
+  fn next(&mut self) {
+    self.testing.clear();
+    if self.suspects.len() < 2 {
+      return;
+    }
+
+    let (first, second) = self.suspects.split_at(self.suspects.len() / 2);
+    for half in [first, second] {
+      let testing = self.with_dependencies(half);
+      if testing.len() < self.suspects.len() {
+        self.testing = testing;
+        self.step += 1;
+        return;
+      }
+    }
+  }
+
+  /// `ids` and the suspects they depend on, directly or not, in the order of the suspects.
+  fn with_dependencies(&self, ids: &[String]) -> Vec<String> {
+    let mut included: HashSet<&String> = ids.iter().collect();
+    let mut queue: Vec<&String> = ids.iter().collect();
+    while let Some(id) = queue.pop() {
+      for dependency in self.dependencies.get(id).into_iter().flatten() {
+        if let Some(suspect) = self.suspects.iter().find(|suspect| *suspect == dependency)
+          && included.insert(suspect)
+        {
+          queue.push(suspect)
+        }
+      }
+    }
+
+    self
+      .suspects
+      .iter()
+      .filter(|id| included.contains(id))
+      .cloned()
+      .collect()
+  }
+}
+
+/// The mods enabled before a search, saved while it's running.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interrupted {
+  pub install_dir: PathBuf,
+  /// Sorted, as in [`Bisection::original`].
+  pub original: Vec<String>,
+}
+
+impl Interrupted {
+  fn path() -> PathBuf {
+    PROJECT.data_dir().join("bisection.json")
+  }
+
+  /// Saves the mods enabled as `bisection` starts in `install_dir`.
+  pub fn save(install_dir: PathBuf, bisection: &Bisection) {
+    let interrupted = Self {
+      install_dir,
+      original: bisection.original.clone(),
+    };
+    let res = serde_json::to_vec(&interrupted)
+      .map_err(std::io::Error::from)
+      .and_then(|json| {
+        std::fs::create_dir_all(PROJECT.data_dir())?;
+        std::fs::write(Self::path(), json)
+      });
+    if let Err(err) = res {
+      warn!("Failed to save the mods enabled before bisecting {:?}", err)
+    }
+  }
+
+  /// The mods from before a search that was never stopped or finished, if there was one.
+  pub fn load() -> Option<Self> {
+    std::fs::read(Self::path())
+      .ok()
+      .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+  }
+
+  /// Forgets the saved mods, once the search is over.
+  pub fn clear() {
+    let _ = std::fs::remove_file(Self::path());
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::collections::HashMap;
+
+  use super::Bisection;
+
+  fn ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+  }
+
+  #[test]
+  fn finds_culprit() {
+    let dependencies = HashMap::from([(String::from("e"), ids(&["a"]))]);
+    let mut bisection =
+      Bisection::new(ids(&["a", "b", "c", "d", "e"]), dependencies).expect("Start bisection");
+    assert_eq!(bisection.testing, ids(&["a", "b"]));
+
+    // the culprit is "e", which can only be tested with "a"
+    bisection.worked();
+    assert_eq!(bisection.testing, ids(&["c"]));
+    assert_eq!(bisection.enabled(), ids(&["a", "b", "c"]));
+    bisection.worked();
+    assert_eq!(bisection.testing, ids(&["d"]));
+    bisection.worked();
+    assert!(bisection.done());
+    assert_eq!(bisection.suspects, ids(&["e"]));
+    assert_eq!(bisection.enabled(), ids(&["a", "b", "c", "d"]));
+  }
+
+  #[test]
+  fn leaves_out_mods_missing_dependencies() {
+    let dependencies = HashMap::from([
+      (String::from("c"), ids(&["b"])),
+      (String::from("d"), ids(&["c"])),
+    ]);
+    let mut bisection =
+      Bisection::new(ids(&["a", "b", "c", "d", "e"]), dependencies).expect("Start bisection");
+    assert_eq!(bisection.testing, ids(&["a", "b"]));
+
+    // "c" and "d" are cleared, but can't load while "b" isn't being tested
+    bisection.crashed();
+    assert_eq!(bisection.testing, ids(&["a"]));
+    assert_eq!(bisection.enabled(), ids(&["a", "e"]));
+  }
+}
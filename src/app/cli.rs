@@ -0,0 +1,357 @@
+//! Headless commands for managing mods without launching the GUI, e.g. on a server or when
+//! building a modpack in CI.
+
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+
+use clap::Subcommand;
+use serde::Serialize;
+
+use super::{
+  installer::{self, InstallOutcome, OverwriteMode},
+  mod_entry::{ModEntry, ModMetadata, UpdateStatus},
+  mod_list::{
+    query::Query,
+    share::{self, ShareFormat},
+    EnabledMods,
+  },
+  progress::{Progress, Unit},
+  secrets,
+  settings::Settings,
+  util::mods_dir,
+  version_source,
+};
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Install mods from archives or folders
+  Install {
+    #[clap(required = true)]
+    paths: Vec<PathBuf>,
+    /// Replace mods that are already installed
+    #[clap(long)]
+    overwrite: bool,
+    /// Replace mods that are already installed, keeping their config files
+    #[clap(long, conflicts_with = "overwrite")]
+    merge: bool,
+  },
+  /// Enable mods by ID
+  Enable {
+    #[clap(required = true)]
+    ids: Vec<String>,
+  },
+  /// Disable mods by ID
+  Disable {
+    #[clap(required = true)]
+    ids: Vec<String>,
+  },
+  /// List installed mods
+  List {
+    /// Print the list as JSON
+    #[clap(long)]
+    json: bool,
+    /// Print the list ready to post on the forum (bbcode) or elsewhere (markdown)
+    #[clap(long, arg_enum, conflicts_with = "json")]
+    share: Option<ShareFormat>,
+    /// Only list mods matching a query, eg: "enabled && status == outdated && size > 500MB"
+    #[clap(long, short)]
+    query: Option<String>,
+  },
+  /// Update mods that support Version Checker and provide a direct download
+  Update {
+    /// Update every mod that has an update available
+    #[clap(long, required_unless_present = "ids")]
+    all: bool,
+    ids: Vec<String>,
+  },
+}
+
+#[derive(Serialize)]
+struct ModSummary {
+  id: String,
+  name: String,
+  version: String,
+  game_version: String,
+  enabled: bool,
+  path: PathBuf,
+}
+
+/// Runs a command to completion, returning the process exit code.
+pub async fn run(command: Command, install_dir: Option<PathBuf>) -> i32 {
+  let settings = Settings::load().unwrap_or_else(|_| Settings::new());
+  settings.sync_nexus_api_key();
+  secrets::load(&settings.token_domains);
+
+  let Some(install_dir) = install_dir.or_else(|| settings.install_dir.clone()) else {
+    eprintln!("No install directory set - pass one with --install-dir or set it in the GUI");
+    return 1;
+  };
+  if !Settings::is_starsector_install(&install_dir) {
+    eprintln!(
+      "{} does not look like a Starsector installation",
+      install_dir.to_string_lossy()
+    );
+    return 1;
+  }
+
+  let res = match command {
+    Command::Install {
+      paths,
+      overwrite,
+      merge,
+    } => {
+      let mode = if merge {
+        Some(OverwriteMode::Merge)
+      } else {
+        overwrite.then_some(OverwriteMode::Replace)
+      };
+      install(&install_dir, &settings.cache_dir(), paths, mode).await
+    }
+    Command::Enable { ids } => set_enabled(&install_dir, ids, true),
+    Command::Disable { ids } => set_enabled(&install_dir, ids, false),
+    Command::List { json, share, query } => list(&install_dir, json, share, query).await,
+    Command::Update { all, ids } => {
+      update(&install_dir, &settings.cache_dir(), (!all).then_some(ids)).await
+    }
+  };
+
+  match res {
+    Ok(()) => 0,
+    Err(err) => {
+      eprintln!("{}", err);
+      1
+    }
+  }
+}
+
+fn read_mods(install_dir: &Path) -> Result<Vec<ModEntry>, String> {
+  let enabled = EnabledMods::load(install_dir).ok_or("Could not read enabled_mods.json")?;
+
+  let mut mods: Vec<ModEntry> = std::fs::read_dir(mods_dir(install_dir))
+    .map_err(|err| format!("Could not read mods folder: {}", err))?
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+    .filter_map(|entry| ModEntry::from_file(&entry.path(), ModMetadata::default()).ok())
+    .map(|mut entry| {
+      entry.set_enabled(enabled.contains(&entry.id));
+      entry
+    })
+    .collect();
+  mods.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+  Ok(mods)
+}
+
+fn installed(install_dir: &Path) -> Result<HashMap<String, PathBuf>, String> {
+  Ok(
+    read_mods(install_dir)?
+      .into_iter()
+      .map(|entry| (entry.id, entry.path))
+      .collect(),
+  )
+}
+
+fn report(outcomes: &[InstallOutcome]) -> bool {
+  let mut ok = true;
+  for outcome in outcomes {
+    match outcome {
+      InstallOutcome::Installed(source, id, name) => {
+        println!("Installed {} ({}) from {}", name, id, source)
+      }
+      InstallOutcome::Skipped(source, reason) => {
+        println!(
+          "Skipped {}: {} - use --overwrite or --merge to replace it",
+          source, reason
+        )
+      }
+      InstallOutcome::Failed(source, reason) => {
+        ok = false;
+        eprintln!("Failed to install {}: {}", source, reason)
+      }
+    }
+  }
+
+  ok
+}
+
+async fn install(
+  install_dir: &Path,
+  cache_dir: &Path,
+  paths: Vec<PathBuf>,
+  mode: Option<OverwriteMode>,
+) -> Result<(), String> {
+  let mods_dir = mods_dir(install_dir);
+  let mut installed = installed(install_dir)?;
+
+  let mut ok = true;
+  for path in paths {
+    let outcomes = installer::install_headless(path, &mods_dir, cache_dir, &installed, mode).await;
+    for outcome in &outcomes {
+      if let InstallOutcome::Installed(_, id, _) = outcome {
+        installed
+          .entry(id.clone())
+          .or_insert_with(|| mods_dir.join(id));
+      }
+    }
+    ok &= report(&outcomes);
+  }
+
+  ok.then_some(())
+    .ok_or_else(|| String::from("Some mods failed to install"))
+}
+
+fn set_enabled(install_dir: &Path, ids: Vec<String>, enabled: bool) -> Result<(), String> {
+  let mods = read_mods(install_dir)?;
+  if let Some(unknown) = ids
+    .iter()
+    .find(|id| !mods.iter().any(|entry| &entry.id == *id))
+  {
+    return Err(format!("No mod with ID {} is installed", unknown));
+  }
+
+  let mut enabled_mods = EnabledMods::load(install_dir)
+    .ok_or("Could not read enabled_mods.json")?
+    .ids()
+    .to_vec();
+  enabled_mods.retain(|id| !ids.contains(id));
+  if enabled {
+    enabled_mods.extend(ids.iter().cloned());
+  }
+
+  EnabledMods::from(enabled_mods)
+    .save(install_dir)
+    .map_err(|err| format!("Failed to save enabled_mods.json: {:?}", err))?;
+  for id in ids {
+    println!("{} {}", if enabled { "Enabled" } else { "Disabled" }, id);
+  }
+
+  Ok(())
+}
+
+async fn list(
+  install_dir: &Path,
+  json: bool,
+  share: Option<ShareFormat>,
+  query: Option<String>,
+) -> Result<(), String> {
+  let mut mods = read_mods(install_dir)?;
+
+  if let Some(query) = query {
+    let query = Query::parse(&query).map_err(|err| format!("Invalid query: {}", err))?;
+    if query.needs_remote() {
+      for entry in mods.iter_mut() {
+        if let Some(local) = &entry.version_checker {
+          let remote = version_source::check(local).await.ok();
+          entry.update_status = Some(UpdateStatus::from((local, &remote)));
+          entry.remote_version = remote;
+        }
+      }
+    }
+    mods.retain(|entry| query.matches(entry));
+  }
+
+  if let Some(format) = share {
+    println!("{}", share::format(format, &mods));
+  } else if json {
+    let summaries: Vec<ModSummary> = mods
+      .into_iter()
+      .map(|entry| ModSummary {
+        version: entry.version.to_string(),
+        game_version: entry.raw_game_version,
+        enabled: entry.enabled,
+        id: entry.id,
+        name: entry.name,
+        path: entry.path,
+      })
+      .collect();
+    let json = serde_json::to_string_pretty(&summaries).map_err(|err| err.to_string())?;
+    println!("{}", json);
+  } else {
+    for entry in mods {
+      println!(
+        "[{}] {} ({}) v{}",
+        if entry.enabled { "x" } else { " " },
+        entry.name,
+        entry.id,
+        entry.version
+      );
+    }
+  }
+
+  Ok(())
+}
+
+async fn update(
+  install_dir: &Path,
+  cache_dir: &Path,
+  only: Option<Vec<String>>,
+) -> Result<(), String> {
+  let mods_dir = mods_dir(install_dir);
+  let mods = read_mods(install_dir)?;
+  let installed: HashMap<String, PathBuf> = mods
+    .iter()
+    .map(|entry| (entry.id.clone(), entry.path.clone()))
+    .collect();
+
+  let mut ok = true;
+  for entry in mods
+    .iter()
+    .filter(|entry| only.as_ref().map_or(true, |only| only.contains(&entry.id)))
+  {
+    let Some(local) = &entry.version_checker else {
+      if only.is_some() {
+        println!("{} does not support update checking", entry.name);
+      }
+      continue;
+    };
+
+    let remote = match version_source::check(local).await {
+      Ok(remote) if remote > *local => remote,
+      Ok(_) => {
+        println!("{} is up to date", entry.name);
+        continue;
+      }
+      Err(err) => {
+        ok = false;
+        eprintln!("Failed to check {} for updates:\n{}", entry.name, err);
+        continue;
+      }
+    };
+    let Some(url) = remote.direct_download_url.clone() else {
+      println!(
+        "{} {} is available, but must be downloaded manually",
+        entry.name, remote.version
+      );
+      continue;
+    };
+
+    println!(
+      "Updating {} from {} to {}",
+      entry.name, local.version, remote.version
+    );
+    let progress = Progress::new(Unit::Bytes);
+    let file =
+      match installer::download_with_progress(url, cache_dir, &progress, |_| {}, |_, _| {}).await {
+        Ok(file) => file,
+        Err(err) => {
+          ok = false;
+          eprintln!("Failed to download {}: {}", entry.name, err);
+          continue;
+        }
+      };
+    let outcomes = installer::install_headless(
+      file.path().to_path_buf(),
+      &mods_dir,
+      cache_dir,
+      &installed,
+      Some(OverwriteMode::Replace),
+    )
+    .await;
+    ok &= report(&outcomes);
+  }
+
+  ok.then_some(())
+    .ok_or_else(|| String::from("Some mods failed to update"))
+}
@@ -0,0 +1,103 @@
+//! Noticing mod links copied to the clipboard, from Discord say, and offering to open them in the
+//! mod browser or download them straight away. Off unless turned on in the settings.
+//!
+//! The clipboard can only be read on the UI thread, so
+//! [`ClipboardController`](super::controllers::ClipboardController) polls it on a timer. Only a
+//! clipboard holding nothing but a link counts, so copying a message that mentions one doesn't.
+
+use druid::Selector;
+use reqwest::Url;
+
+use super::i18n::tr;
+
+/// A mod link has been copied.
+pub const FOUND: Selector<ModLink> = Selector::new("clipboard_watch.found");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+  Forum,
+  Nexus,
+  GitHub,
+  /// A file attached to a GitHub release, which can be downloaded without the browser.
+  Download,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModLink {
+  pub url: String,
+  pub kind: LinkKind,
+}
+
+impl ModLink {
+  pub fn describe(&self) -> String {
+    tr(match self.kind {
+      LinkKind::Forum => "mod-link-forum",
+      LinkKind::Nexus => "mod-link-nexus",
+      LinkKind::GitHub => "mod-link-github",
+      LinkKind::Download => "mod-link-download",
+    })
+  }
+}
+
+/// The mod link `text` consists of, if it is one.
+pub fn mod_link(text: &str) -> Option<ModLink> {
+  let text = text.trim();
+  if text.contains(char::is_whitespace) {
+    return None;
+  }
+  let url = Url::parse(text).ok()?;
+  if !matches!(url.scheme(), "http" | "https") {
+    return None;
+  }
+
+  let host = url.host_str()?.trim_start_matches("www.");
+  let path = url.path();
+  let kind = match host {
+    "fractalsoftworks.com" if path.starts_with("/forum") && url.query()?.contains("topic=") => {
+      LinkKind::Forum
+    }
+    "nexusmods.com" if path.starts_with("/starsector/mods/") => LinkKind::Nexus,
+    "github.com" if path.contains("/releases/download/") => LinkKind::Download,
+    "github.com" if path.contains("/releases") => LinkKind::GitHub,
+    _ => return None,
+  };
+
+  Some(ModLink {
+    url: url.to_string(),
+    kind,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::{mod_link, LinkKind};
+
+  #[test]
+  fn recognises_mod_links() {
+    let kind = |text: &str| mod_link(text).map(|link| link.kind);
+
+    assert_eq!(
+      kind(" https://fractalsoftworks.com/forum/index.php?topic=5444.0\n"),
+      Some(LinkKind::Forum)
+    );
+    assert_eq!(
+      kind("https://www.nexusmods.com/starsector/mods/12"),
+      Some(LinkKind::Nexus)
+    );
+    assert_eq!(
+      kind("https://github.com/someone/mod/releases/tag/v1.0"),
+      Some(LinkKind::GitHub)
+    );
+    assert_eq!(
+      kind("https://github.com/someone/mod/releases/download/v1.0/Mod.zip"),
+      Some(LinkKind::Download)
+    );
+    assert_eq!(kind("https://github.com/someone/mod"), None);
+    assert_eq!(kind("https://fractalsoftworks.com/forum/index.php"), None);
+    assert_eq!(
+      kind("get LazyLib https://fractalsoftworks.com/forum/index.php?topic=5444"),
+      None
+    );
+    assert_eq!(kind("not a link"), None);
+  }
+}
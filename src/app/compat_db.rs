@@ -0,0 +1,307 @@
+//! A community-maintained list of what's known about mods that their own files don't say: which
+//! mods conflict with each other, which have been abandoned, and what replaced them. It's fetched
+//! from the URL in the settings, by default `compatibility.json` in MOSS's repository, and saved
+//! so the warnings it raises still show while offline. The repository's copy starts out empty,
+//! entries are added to it as conflicts and abandoned mods are reported and confirmed.
+//!
+//! The file maps mod IDs to entries, all of whose fields are optional:
+//!
+//! ```json
+//! {
+//!   "mods": {
+//!     "old_mod": {
+//!       "abandoned": true,
+//!       "successor": { "id": "new_mod", "name": "New Mod" },
+//!       "conflicts": [{ "id": "other_mod", "reason": "Both replace the same hullmods" }],
+//!       "note": "Breaks saves made with 1.x"
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use anyhow::Context;
+use druid::{ExtEventSink, Selector, Target};
+use serde::Deserialize;
+use tracing::warn;
+use webview_shared::PROJECT;
+
+use super::{
+  i18n::{tr, tr_args},
+  net,
+};
+
+pub const DEFAULT_URL: &str = concat!(
+  "https://raw.githubusercontent.com/atlanticaccent/starsector-mod-manager-rust/main/",
+  "compatibility.json"
+);
+
+pub const LOADED: Selector<Arc<CompatDb>> = Selector::new("compat_db.loaded");
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct CompatDb {
+  /// Keyed by lowercase mod ID, since IDs are matched ignoring case.
+  #[serde(default)]
+  mods: HashMap<String, CompatEntry>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct CompatEntry {
+  #[serde(default)]
+  pub abandoned: bool,
+  /// The mod to use instead, usually of one that's been abandoned.
+  pub successor: Option<Successor>,
+  /// Mods this one doesn't work alongside. Conflicts go both ways, so only one of the two mods
+  /// needs to list the other.
+  #[serde(default)]
+  pub conflicts: Vec<Conflict>,
+  pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Successor {
+  pub id: String,
+  #[serde(default)]
+  pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Conflict {
+  pub id: String,
+  pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+  Abandoned {
+    successor: Option<String>,
+  },
+  /// Another installed mod, by name, and why they don't work together if that's known.
+  Conflict {
+    name: String,
+    reason: Option<String>,
+  },
+  Note(String),
+}
+
+impl Warning {
+  pub fn text(&self) -> String {
+    match self {
+      Warning::Abandoned { successor: None } => tr("compat-abandoned"),
+      Warning::Abandoned {
+        successor: Some(successor),
+      } => tr_args("compat-abandoned-successor", &[("successor", successor)]),
+      Warning::Conflict { name, reason: None } => tr_args("compat-conflict", &[("name", name)]),
+      Warning::Conflict {
+        name,
+        reason: Some(reason),
+      } => tr_args(
+        "compat-conflict-reason",
+        &[("name", name), ("reason", reason)],
+      ),
+      Warning::Note(note) => note.clone(),
+    }
+  }
+}
+
+impl CompatDb {
+  /// Kept with MOSS's data rather than in the cache, which the OS may clear, so it's there the
+  /// next time MOSS starts offline.
+  fn cache_path() -> PathBuf {
+    PROJECT.data_dir().join("compatibility.json")
+  }
+
+  /// Fetches the list from `url`, saving it to disk so it can be used while offline.
+  pub async fn fetch(url: &str) -> anyhow::Result<Self> {
+    let text = net::api_client_builder()
+      .build()?
+      .get(url)
+      .send()
+      .await
+      .and_then(|res| res.error_for_status())?
+      .text()
+      .await?;
+    let db = Self::parse(&text).context("Parse the compatibility list")?;
+
+    let path = Self::cache_path();
+    if let Err(err) = tokio::fs::create_dir_all(PROJECT.data_dir()).await {
+      warn!(
+        "Failed to create data dir for the compatibility list: {}",
+        err
+      )
+    } else if let Err(err) = tokio::fs::write(&path, text).await {
+      warn!(
+        "Failed to cache the compatibility list at {:?}: {}",
+        path, err
+      )
+    }
+
+    Ok(db)
+  }
+
+  /// The list saved by the last successful fetch, if there is one.
+  pub async fn load_cache() -> Option<Self> {
+    let text = tokio::fs::read_to_string(Self::cache_path()).await.ok()?;
+
+    Self::parse(&text)
+      .inspect_err(|err| warn!("Failed to parse cached compatibility list: {}", err))
+      .ok()
+  }
+
+  /// The list from `url`, or the cached copy if it can't be fetched.
+  pub async fn get(url: &str) -> Option<Self> {
+    match Self::fetch(url).await {
+      Ok(db) => Some(db),
+      Err(err) => {
+        warn!("Failed to fetch the compatibility list: {:?}", err);
+        Self::load_cache().await
+      }
+    }
+  }
+
+  /// Fetches the list from `url`, or loads the cached copy, and sends it to the mod list.
+  pub async fn load(url: String, ext_ctx: ExtEventSink) {
+    if let Some(db) = Self::get(&url).await {
+      let _ = ext_ctx.submit_command(LOADED, Arc::new(db), Target::Auto);
+    }
+  }
+
+  fn parse(text: &str) -> serde_json::Result<Self> {
+    let db = serde_json::from_str::<Self>(text)?;
+
+    Ok(Self {
+      mods: db
+        .mods
+        .into_iter()
+        .map(|(id, entry)| (id.to_lowercase(), entry))
+        .collect(),
+    })
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.mods.is_empty()
+  }
+
+  /// Everything known to be wrong with the mod `id`. Conflicts are only raised with mods that are
+  /// installed, which `installed` looks up by lowercase ID, returning their name.
+  pub fn warnings(&self, id: &str, installed: impl Fn(&str) -> Option<String>) -> Vec<Warning> {
+    let id = id.to_lowercase();
+    let mut warnings = Vec::new();
+
+    if let Some(entry) = self.mods.get(&id) {
+      if entry.abandoned {
+        warnings.push(Warning::Abandoned {
+          successor: entry.successor.as_ref().map(|successor| {
+            if successor.name.is_empty() {
+              successor.id.clone()
+            } else {
+              successor.name.clone()
+            }
+          }),
+        })
+      }
+      if let Some(note) = entry.note.as_ref().filter(|note| !note.trim().is_empty()) {
+        warnings.push(Warning::Note(note.clone()))
+      }
+    }
+
+    let listed_here = self.mods.get(&id).into_iter().flat_map(|entry| {
+      entry
+        .conflicts
+        .iter()
+        .map(|conflict| (conflict.id.to_lowercase(), conflict.reason.clone()))
+    });
+    let listed_there = self.mods.iter().flat_map(|(other, entry)| {
+      entry
+        .conflicts
+        .iter()
+        .filter(|conflict| conflict.id.eq_ignore_ascii_case(&id))
+        .map(|conflict| (other.clone(), conflict.reason.clone()))
+    });
+    let mut conflicts: Vec<(String, Option<String>)> = Vec::new();
+    for (other, reason) in listed_here.chain(listed_there) {
+      if other != id && !conflicts.iter().any(|(existing, _)| existing == &other) {
+        conflicts.push((other, reason))
+      }
+    }
+    conflicts.sort();
+    warnings.extend(conflicts.into_iter().filter_map(|(other, reason)| {
+      installed(&other).map(|name| Warning::Conflict { name, reason })
+    }));
+
+    warnings
+  }
+
+  /// Every warning for the mod `id` as one line each, or `None` if there aren't any.
+  pub fn warning_text(
+    &self,
+    id: &str,
+    installed: impl Fn(&str) -> Option<String>,
+  ) -> Option<String> {
+    let warnings = self.warnings(id, installed);
+
+    (!warnings.is_empty()).then(|| {
+      warnings
+        .iter()
+        .map(Warning::text)
+        .collect::<Vec<_>>()
+        .join("\n")
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{CompatDb, Warning};
+
+  #[test]
+  fn warns_about_abandoned_and_conflicting_mods() {
+    let db = CompatDb::parse(
+      r#"{
+        "mods": {
+          "Old_Mod": {
+            "abandoned": true,
+            "successor": { "id": "new_mod", "name": "New Mod" },
+            "conflicts": [{ "id": "uninstalled_mod" }]
+          },
+          "new_mod": {
+            "conflicts": [{ "id": "OTHER_MOD", "reason": "Both replace the same hullmods" }]
+          }
+        }
+      }"#,
+    )
+    .unwrap();
+    let installed = |id: &str| {
+      ["old_mod", "new_mod", "other_mod"]
+        .contains(&id)
+        .then(|| id.replace('_', " "))
+    };
+
+    assert_eq!(
+      db.warnings("old_mod", installed),
+      vec![Warning::Abandoned {
+        successor: Some(String::from("New Mod"))
+      }]
+    );
+    // conflicts are raised on both mods, though only one lists the other
+    let conflict = |name: &str| Warning::Conflict {
+      name: name.to_string(),
+      reason: Some(String::from("Both replace the same hullmods")),
+    };
+    assert_eq!(
+      db.warnings("new_mod", installed),
+      vec![conflict("other mod")]
+    );
+    assert_eq!(
+      db.warnings("other_mod", installed),
+      vec![conflict("new mod")]
+    );
+    assert!(db.warnings("unlisted_mod", installed).is_empty());
+  }
+
+  #[test]
+  fn published_list_parses() {
+    CompatDb::parse(include_str!("../../compatibility.json")).expect("Parse compatibility.json");
+  }
+}
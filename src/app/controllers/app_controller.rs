@@ -1,12 +1,19 @@
 use std::process;
 
-use druid::{commands, widget::Controller, Env, Event, EventCtx, Target, Widget};
+use druid::{
+  commands, widget::Controller, Env, Event, EventCtx, HotKey, SysMods, Target, Widget, WidgetExt,
+};
 use self_update::version::bump_is_greater;
+use tap::Pipe;
+use tracing::error;
 
 use crate::app::{
+  history,
+  i18n::{tr, tr_args},
   modal::Modal,
-  settings::{self, Settings, SettingsCommand},
-  updater::{open_in_browser, self_update, support_self_update},
+  settings::{self, Picker, Settings, SettingsCommand},
+  updater::{self, open_in_browser, support_self_update, Applied},
+  util::{bold_text, get_latest_manager},
   App, TAG,
 };
 
@@ -17,21 +24,36 @@ impl<W: Widget<App>> Controller<App, W> for AppController {
     if let Event::Command(cmd) = event {
       if let Some(settings::SettingsCommand::SelectInstallDir) = cmd.get(Settings::SELECTOR) {
         let ext_ctx = ctx.get_external_handle();
+        let start = data.settings.picker_start(Picker::InstallDir);
         ctx.set_disabled(true);
         data.runtime.spawn_blocking(move || {
           #[cfg(target_os = "macos")]
           let res = rfd::FileDialog::new()
             .add_filter("*.app", &["app"])
+            .pipe(|dialog| match &start {
+              Some(start) => dialog.set_directory(start),
+              None => dialog,
+            })
             .pick_file();
           #[cfg(target_os = "windows")]
-          let res = rfd::FileDialog::new().pick_folder();
+          let res = rfd::FileDialog::new()
+            .pipe(|dialog| match &start {
+              Some(start) => dialog.set_directory(start),
+              None => dialog,
+            })
+            .pick_folder();
           #[cfg(target_os = "linux")]
           let res = native_dialog::FileDialog::new()
+            .pipe(|dialog| match &start {
+              Some(start) => dialog.set_location(start),
+              None => dialog,
+            })
             .show_open_single_dir()
             .ok()
             .flatten();
 
           if let Some(handle) = res {
+            Settings::record_pick(&ext_ctx, Picker::InstallDir, &handle);
             ext_ctx.submit_command(
               Settings::SELECTOR,
               SettingsCommand::UpdateInstallDir(handle),
@@ -41,34 +63,118 @@ impl<W: Widget<App>> Controller<App, W> for AppController {
             ext_ctx.submit_command(App::ENABLE, (), Target::Auto)
           }
         });
+      } else if let Some(settings::SettingsCommand::SelectCacheDir) = cmd.get(Settings::SELECTOR) {
+        let ext_ctx = ctx.get_external_handle();
+        let start = data.settings.picker_start(Picker::CacheDir);
+        data.runtime.spawn_blocking(move || {
+          #[cfg(not(target_os = "linux"))]
+          let res = rfd::FileDialog::new()
+            .pipe(|dialog| match &start {
+              Some(start) => dialog.set_directory(start),
+              None => dialog,
+            })
+            .pick_folder();
+          #[cfg(target_os = "linux")]
+          let res = native_dialog::FileDialog::new()
+            .pipe(|dialog| match &start {
+              Some(start) => dialog.set_location(start),
+              None => dialog,
+            })
+            .show_open_single_dir()
+            .ok()
+            .flatten();
+
+          if let Some(handle) = res {
+            Settings::record_pick(&ext_ctx, Picker::CacheDir, &handle);
+            let _ = ext_ctx.submit_command(
+              Settings::SELECTOR,
+              SettingsCommand::UpdateCacheDir(Some(handle)),
+              Target::Auto,
+            );
+          }
+        });
+      } else if let Some(settings::SettingsCommand::SelectDownloadDir) =
+        cmd.get(Settings::SELECTOR)
+      {
+        let ext_ctx = ctx.get_external_handle();
+        let start = data.settings.picker_start(Picker::DownloadDir);
+        data.runtime.spawn_blocking(move || {
+          #[cfg(not(target_os = "linux"))]
+          let res = rfd::FileDialog::new()
+            .pipe(|dialog| match &start {
+              Some(start) => dialog.set_directory(start),
+              None => dialog,
+            })
+            .pick_folder();
+          #[cfg(target_os = "linux")]
+          let res = native_dialog::FileDialog::new()
+            .pipe(|dialog| match &start {
+              Some(start) => dialog.set_location(start),
+              None => dialog,
+            })
+            .show_open_single_dir()
+            .ok()
+            .flatten();
+
+          if let Some(handle) = res {
+            Settings::record_pick(&ext_ctx, Picker::DownloadDir, &handle);
+            let _ = ext_ctx.submit_command(
+              Settings::SELECTOR,
+              SettingsCommand::UpdateDownloadDir(Some(handle)),
+              Target::Auto,
+            );
+          }
+        });
       } else if let Some(()) = cmd.get(App::DUMB_UNIVERSAL_ESCAPE) {
         ctx.set_focus(data.widget_id);
         ctx.resign_focus();
-      } else if let Some(()) = cmd.get(App::SELF_UPDATE) {
-        let original_exe = std::env::current_exe();
-        if dbg!(support_self_update()) && original_exe.is_ok() {
-          let widget = if self_update().is_ok() {
-            Modal::new("Restart?")
-              .with_content("Update complete.")
-              .with_content("Would you like to restart?")
-              .with_button(
-                "Restart",
-                App::RESTART
-                  .with(original_exe.as_ref().unwrap().clone())
-                  .to(Target::Global),
-              )
-              .with_close_label("Cancel")
-          } else {
-            Modal::new("Error")
-              .with_content("Failed to update Mod Manager.")
-              .with_content("It is recommended that you restart and check that the Manager has not been corrupted.")
-              .with_close()
-          };
-
-          widget.show(ctx, env, &());
+      } else if let Some(()) = cmd.get(App::CHECK_SELF_UPDATE) {
+        let ext_ctx = ctx.get_external_handle();
+        let channel = data.settings.update_channel;
+        data.runtime.spawn(async move {
+          let release = get_latest_manager(channel).await;
+          ext_ctx.submit_command(App::UPDATE_AVAILABLE, release, Target::Auto)
+        });
+      } else if let Some(release) = cmd.get(App::SELF_UPDATE) {
+        if support_self_update() {
+          let ext_ctx = ctx.get_external_handle();
+          let release = release.clone();
+          let cache_dir = data.settings.cache_dir();
+          data.runtime.spawn(async move {
+            let res = updater::self_update(release, ext_ctx.clone(), cache_dir).await;
+            let _ = ext_ctx.submit_command(App::SELF_UPDATE_FINISHED, res, Target::Auto);
+          });
         } else {
           open_in_browser();
         }
+      } else if let Some(res) = cmd.get(App::SELF_UPDATE_FINISHED) {
+        let widget = match res {
+          Ok(Applied::Replaced(original_exe)) => Modal::new(&tr("self-update-restart-title"))
+            .with_content(tr("self-update-complete"))
+            .with_content(tr("self-update-restart-prompt"))
+            .with_button(
+              &tr("self-update-restart"),
+              App::RESTART.with(original_exe.clone()).to(Target::Global),
+            )
+            .with_close_label(&tr("cancel")),
+          Ok(Applied::InstallerLaunched) => {
+            ctx.submit_command(commands::QUIT_APP);
+            return;
+          }
+          Err(err) => {
+            error!("Failed to update: {}", err);
+            Modal::new(&tr("self-update-error-title"))
+              .with_content(tr("self-update-failed"))
+              .with_content(err.clone())
+              .with_content(tr("self-update-failed-hint"))
+              .with_button(&tr("self-update-open-releases"), |_: &mut EventCtx, _: &mut ()| {
+                open_in_browser()
+              })
+              .with_close()
+          }
+        };
+
+        widget.show(ctx, env, &());
       } else if let Some(payload) = cmd.get(App::UPDATE_AVAILABLE) {
         let widget = if let Ok(release) = payload {
           let local_tag = TAG.strip_prefix('v').unwrap_or(TAG);
@@ -77,42 +183,76 @@ impl<W: Widget<App>> Controller<App, W> for AppController {
             .strip_prefix('v')
             .unwrap_or(&release.tag_name);
           if let Ok(true) = bump_is_greater(local_tag, release_tag) {
-            Modal::new("Update Mod Manager?")
-              .with_content("A new version of Starsector Mod Manager is available.")
-              .with_content(format!("Current version: {}", TAG))
-              .with_content(format!("New version: {}", release.tag_name))
+            Modal::new(&tr("self-update-title"))
+              .with_content(tr("self-update-available"))
+              .with_content(tr_args("self-update-current-version", &[("version", &TAG)]))
+              .with_content(tr_args(
+                if release.prerelease {
+                  "self-update-new-beta"
+                } else {
+                  "self-update-new-version"
+                },
+                &[("version", &release.tag_name)],
+              ))
               .with_content({
                 #[cfg(not(target_os = "macos"))]
-                let label = "Would you like to update now?";
+                let label = tr("self-update-prompt");
                 #[cfg(target_os = "macos")]
-                let label = "Would you like to open the update in your browser?";
+                let label = tr("self-update-prompt-browser");
 
                 label
               })
-              .with_button("Update", App::SELF_UPDATE)
-              .with_close_label("Cancel")
+              .pipe(|modal| match release.body.as_deref().map(str::trim) {
+                Some(notes) if !notes.is_empty() => modal
+                  .with_content(
+                    bold_text(
+                      &tr("self-update-release-notes"),
+                      druid::theme::TEXT_SIZE_NORMAL,
+                      druid::FontWeight::BOLD,
+                      druid::theme::TEXT_COLOR,
+                    )
+                    .boxed(),
+                  )
+                  .with_content(notes.to_string()),
+                _ => modal,
+              })
+              .with_button(&tr("self-update-confirm"), App::SELF_UPDATE.with(release.clone()))
+              .with_close_label(&tr("cancel"))
           } else {
             return;
           }
         } else {
-          Modal::new("Error")
-            .with_content("Failed to retrieve Mod Manager update status.")
-            .with_content("There may or may not be an update available.")
+          Modal::new(&tr("self-update-error-title"))
+            .with_content(tr("self-update-status-failed"))
+            .with_content(tr("self-update-status-unknown"))
             .with_close()
         };
 
-        widget.show(ctx, env, &());
+        widget.show_with_size(ctx, env, &(), (500., 400.));
       } else if let Some(original_exe) = cmd.get(App::RESTART) {
         if process::Command::new(original_exe).spawn().is_ok() {
           ctx.submit_command(commands::QUIT_APP)
         } else {
-          eprintln!("Failed to restart")
+          error!("Failed to restart")
         };
       }
     } else if let Event::MouseDown(_) = event {
       if ctx.is_disabled() {
         ctx.set_handled();
       }
+    } else if let Event::KeyDown(key_event) = event
+      && !ctx.has_focus()
+    {
+      // a focused widget, such as a text box, handles its own undo and redo
+      if HotKey::new(SysMods::Cmd, "z").matches(key_event) {
+        ctx.submit_command(history::UNDO);
+        ctx.set_handled();
+      } else if HotKey::new(SysMods::CmdShift, "Z").matches(key_event)
+        || HotKey::new(SysMods::Cmd, "y").matches(key_event)
+      {
+        ctx.submit_command(history::REDO);
+        ctx.set_handled();
+      }
     }
 
     child.event(ctx, event, data, env)
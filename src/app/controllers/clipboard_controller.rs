@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use druid::{widget::Controller, Application, Env, Event, EventCtx, TimerToken, Widget};
+
+use crate::app::{clipboard_watch, App};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Polls the clipboard for mod links while watching it is turned on in the settings.
+#[derive(Default)]
+pub struct ClipboardController {
+  timer: TimerToken,
+  /// What the clipboard held when last polled, or `None` if it hasn't been since watching was
+  /// turned on - whatever was copied before then isn't offered.
+  last: Option<Option<String>>,
+}
+
+impl<W: Widget<App>> Controller<App, W> for ClipboardController {
+  fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut App, env: &Env) {
+    match event {
+      Event::WindowConnected => self.timer = ctx.request_timer(POLL_INTERVAL),
+      Event::Timer(token) if *token == self.timer => {
+        self.timer = ctx.request_timer(POLL_INTERVAL);
+        if !data.settings.watch_clipboard {
+          self.last = None;
+          return;
+        }
+
+        let text = Application::global().clipboard().get_string();
+        if self.last.as_ref() != Some(&text) {
+          if self.last.is_some()
+            && let Some(link) = text.as_deref().and_then(clipboard_watch::mod_link)
+          {
+            ctx.submit_command(clipboard_watch::FOUND.with(link));
+          }
+          self.last = Some(text);
+        }
+
+        return;
+      }
+      _ => {}
+    }
+
+    child.event(ctx, event, data, env)
+  }
+}
@@ -1,83 +1,92 @@
-use druid::{widget::Controller, Event, EventCtx, Menu, MenuItem, Target, Widget};
+use druid::{EventCtx, Menu, MenuItem, Target};
+use tap::Pipe;
 
-use crate::app::App;
+use crate::app::{
+  i18n::tr,
+  settings::{Picker, Settings},
+  App,
+};
 
 pub struct InstallController;
 
-impl<W: Widget<App>> Controller<App, W> for InstallController {
-  fn event(
-    &mut self,
-    child: &mut W,
-    ctx: &mut EventCtx,
-    event: &Event,
-    data: &mut App,
-    env: &druid::Env,
-  ) {
-    match event {
-      Event::MouseDown(mouse_event) => {
-        if mouse_event.button == druid::MouseButton::Left {
-          ctx.set_active(true);
-          ctx.request_paint();
-        }
-      }
-      Event::MouseUp(mouse_event) => {
-        if ctx.is_active() && mouse_event.button == druid::MouseButton::Left {
-          ctx.set_active(false);
-          if ctx.is_hot() {
-            let ext_ctx = ctx.get_external_handle();
-            let menu: Menu<App> = Menu::empty()
-              .entry(MenuItem::new("From Archive(s)").on_activate(
-                move |_ctx, data: &mut App, _| {
-                  let ext_ctx = ext_ctx.clone();
-                  data.runtime.spawn_blocking(move || {
-                    #[cfg(not(target_os = "linux"))]
-                    let res = rfd::FileDialog::new()
-                      .add_filter(
-                        "Archives",
-                        &["zip", "7z", "7zip", "rar", "rar4", "rar5", "tar"],
-                      )
-                      .pick_files();
-                    #[cfg(target_os = "linux")]
-                    let res = native_dialog::FileDialog::new()
-                      .add_filter(
-                        "Archives",
-                        &["zip", "7z", "7zip", "rar", "rar4", "rar5", "tar"],
-                      )
-                      .show_open_multiple_file()
-                      .ok();
-
-                    ext_ctx.submit_command(App::OPEN_FILE, res, Target::Auto)
-                  });
-                },
-              ))
-              .entry(MenuItem::new("From Folder").on_activate({
-                let ext_ctx = ctx.get_external_handle();
-                move |_ctx, data: &mut App, _| {
-                  data.runtime.spawn_blocking({
-                    let ext_ctx = ext_ctx.clone();
-                    move || {
-                      #[cfg(not(target_os = "linux"))]
-                      let res = rfd::FileDialog::new().pick_folder();
-                      #[cfg(target_os = "linux")]
-                      let res = native_dialog::FileDialog::new()
-                        .show_open_single_dir()
-                        .ok()
-                        .flatten();
+impl InstallController {
+  /// The ways of picking mods to install, shown when the install button is clicked.
+  pub fn menu(ctx: &mut EventCtx) -> Menu<App> {
+    let ext_ctx = ctx.get_external_handle();
+    Menu::empty()
+      .entry(MenuItem::new(tr("install-menu-archives")).on_activate(
+        move |_ctx, data: &mut App, _| {
+          let ext_ctx = ext_ctx.clone();
+          let start = data.settings.picker_start(Picker::Archive);
+          data.runtime.spawn_blocking(move || {
+            #[cfg(not(target_os = "linux"))]
+            let res = rfd::FileDialog::new()
+              .add_filter(
+                &tr("install-menu-archives-filter"),
+                &["zip", "7z", "7zip", "rar", "rar4", "rar5", "tar"],
+              )
+              .pipe(|dialog| match &start {
+                Some(start) => dialog.set_directory(start),
+                None => dialog,
+              })
+              .pick_files();
+            #[cfg(target_os = "linux")]
+            let res = native_dialog::FileDialog::new()
+              .add_filter(
+                &tr("install-menu-archives-filter"),
+                &["zip", "7z", "7zip", "rar", "rar4", "rar5", "tar"],
+              )
+              .pipe(|dialog| match &start {
+                Some(start) => dialog.set_location(start),
+                None => dialog,
+              })
+              .show_open_multiple_file()
+              .ok();
 
-                      ext_ctx.submit_command(App::OPEN_FOLDER, res, Target::Auto)
-                    }
-                  });
-                }
-              }));
+            if let Some(first) = res.as_ref().and_then(|files| files.first()) {
+              Settings::record_pick(&ext_ctx, Picker::Archive, first);
+            }
+            ext_ctx.submit_command(App::OPEN_FILE, res, Target::Auto)
+          });
+        },
+      ))
+      .entry(MenuItem::new(tr("install-menu-folder")).on_activate({
+        let ext_ctx = ctx.get_external_handle();
+        move |_ctx, data: &mut App, _| {
+          let start = data.settings.picker_start(Picker::Folder);
+          data.runtime.spawn_blocking({
+            let ext_ctx = ext_ctx.clone();
+            move || {
+              #[cfg(not(target_os = "linux"))]
+              let res = rfd::FileDialog::new()
+                .pipe(|dialog| match &start {
+                  Some(start) => dialog.set_directory(start),
+                  None => dialog,
+                })
+                .pick_folder();
+              #[cfg(target_os = "linux")]
+              let res = native_dialog::FileDialog::new()
+                .pipe(|dialog| match &start {
+                  Some(start) => dialog.set_location(start),
+                  None => dialog,
+                })
+                .show_open_single_dir()
+                .ok()
+                .flatten();
 
-            ctx.show_context_menu::<App>(menu, ctx.to_window(mouse_event.pos))
-          }
-          ctx.request_paint();
+              if let Some(folder) = &res {
+                Settings::record_pick(&ext_ctx, Picker::Folder, folder);
+              }
+              ext_ctx.submit_command(App::OPEN_FOLDER, res, Target::Auto)
+            }
+          });
         }
-      }
-      _ => {}
-    }
-
-    child.event(ctx, event, data, env);
+      }))
+      .separator()
+      .entry(
+        MenuItem::new(tr("install-menu-download-only"))
+          .selected_if(|data: &App, _| data.download_only)
+          .on_activate(|_ctx, data: &mut App, _| data.download_only = !data.download_only),
+      )
   }
 }
@@ -0,0 +1,113 @@
+use druid::{
+  widget::{Controller, Tabs},
+  Env, Event, EventCtx, LifeCycle, LifeCycleCtx, Point, Screen, Widget,
+};
+use tracing::error;
+
+use crate::{
+  app::App,
+  patch::{split::Split, tabs_policy::StaticTabsForked},
+};
+
+/// Keeps the main window's layout in the settings, saving it when the window closes.
+pub struct LayoutController;
+
+impl<W: Widget<App>> Controller<App, W> for LayoutController {
+  fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut App, env: &Env) {
+    match event {
+      Event::WindowConnected => {
+        // a monitor that was unplugged since could leave the window somewhere it can't be seen
+        let position = ctx.window().get_position();
+        let monitors = Screen::get_monitors();
+        if !monitors.is_empty()
+          && !monitors
+            .iter()
+            .any(|monitor| monitor.virtual_work_rect().contains(position))
+        {
+          ctx.window().set_position(Point::ORIGIN);
+        }
+      }
+      Event::WindowCloseRequested => {
+        let window = ctx.window();
+        data.settings.layout.record_window(
+          window.get_size(),
+          window.get_position(),
+          window.get_window_state(),
+        );
+        data.settings.layout.columns = Some(data.mod_list.header.ratios.clone());
+        if let Err(err) = data.settings.save() {
+          error!("Failed to save the window layout {:?}", err)
+        }
+      }
+      _ => {}
+    }
+
+    child.event(ctx, event, data, env)
+  }
+}
+
+const SIDE_PANEL_TABS: usize = 2;
+
+/// Opens the side panel on the tab it was last left on.
+pub struct SidePanelTabController;
+
+impl Controller<App, Tabs<StaticTabsForked<App>>> for SidePanelTabController {
+  fn event(
+    &mut self,
+    child: &mut Tabs<StaticTabsForked<App>>,
+    ctx: &mut EventCtx,
+    event: &Event,
+    data: &mut App,
+    env: &Env,
+  ) {
+    child.event(ctx, event, data, env);
+    data.settings.layout.side_panel_tab = child.tab_index();
+  }
+
+  fn lifecycle(
+    &mut self,
+    child: &mut Tabs<StaticTabsForked<App>>,
+    ctx: &mut LifeCycleCtx,
+    event: &LifeCycle,
+    data: &App,
+    env: &Env,
+  ) {
+    if let LifeCycle::WidgetAdded = event {
+      child.set_tab_index(data.settings.layout.side_panel_tab.min(SIDE_PANEL_TABS - 1));
+    }
+
+    child.lifecycle(ctx, event, data, env)
+  }
+}
+
+/// Puts the divider between the mod list and the side panel where it was last left.
+pub struct SidePanelSplitController;
+
+impl Controller<App, Split<App>> for SidePanelSplitController {
+  fn event(
+    &mut self,
+    child: &mut Split<App>,
+    ctx: &mut EventCtx,
+    event: &Event,
+    data: &mut App,
+    env: &Env,
+  ) {
+    child.event(ctx, event, data, env);
+    data.settings.layout.side_panel_split = child.split_point_chosen();
+  }
+
+  fn lifecycle(
+    &mut self,
+    child: &mut Split<App>,
+    ctx: &mut LifeCycleCtx,
+    event: &LifeCycle,
+    data: &App,
+    env: &Env,
+  ) {
+    if let LifeCycle::WidgetAdded = event {
+      child.set_split_point_chosen(data.settings.layout.side_panel_split);
+    }
+
+    child.lifecycle(ctx, event, data, env)
+  }
+}
@@ -1,14 +1,18 @@
 mod app_controller;
+mod clipboard_controller;
 mod hover_controller;
 mod install_controller;
+mod layout_controller;
 mod mod_entry_click_controller;
 mod mod_list_controller;
 mod on_event;
 mod on_notif;
 
 pub use app_controller::AppController;
+pub use clipboard_controller::ClipboardController;
 pub use hover_controller::HoverController;
 pub use install_controller::InstallController;
+pub use layout_controller::{LayoutController, SidePanelSplitController, SidePanelTabController};
 pub use mod_entry_click_controller::ModEntryClickController;
 pub use mod_list_controller::ModListController;
 pub use on_event::OnEvent;
@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
-use druid::{widget::Controller, Event, EventCtx, Menu, MenuItem, Widget};
+use druid::{widget::Controller, Application, Event, EventCtx, Menu, MenuItem, Widget};
 use tap::Pipe;
+use tracing::error;
 
 use crate::app::{
+  archive,
+  i18n::{tr, tr_args},
   mod_description::{ModDescription, OPEN_IN_BROWSER},
-  mod_entry::ModEntry,
+  mod_entry::{ModEntry, ModMetadata},
   App,
 };
 
@@ -36,10 +39,22 @@ impl<W: Widget<Arc<ModEntry>>> Controller<Arc<ModEntry>, W> for ModEntryClickCon
                 let entry = data.clone();
                 move |_, _, _| {
                   if let Err(err) = opener::open(entry.path.clone()) {
-                    eprintln!("{}", err)
+                    error!("{}", err)
                   }
                 }
               }))
+              .pipe(|menu| {
+                let Some(version_file) = data.version_file() else {
+                  return menu;
+                };
+                menu.entry(
+                  MenuItem::new("Open Version File").on_activate(move |_, _, _| {
+                    if let Err(err) = opener::open(&version_file) {
+                      error!("{}", err)
+                    }
+                  }),
+                )
+              })
               .pipe(|mut menu| {
                 if let Some(fractal_id) =
                   data.version_checker.as_ref().map(|v| v.fractal_id.clone())
@@ -74,6 +89,87 @@ impl<W: Widget<Arc<ModEntry>>> Controller<Arc<ModEntry>, W> for ModEntryClickCon
 
                 menu
               })
+              .separator()
+              .entry(MenuItem::new("Copy Mod ID").on_activate({
+                let id = data.id.clone();
+                move |_, _, _| Application::global().clipboard().put_string(&id)
+              }))
+              .entry(
+                MenuItem::new("Check for Update")
+                  .enabled(data.version_checker.is_some() && !data.checking_update)
+                  .on_activate({
+                    let entry = data.clone();
+                    move |ctx, _, _| {
+                      ctx.submit_command(ModEntry::CHECK_FOR_UPDATE.with(entry.clone()))
+                    }
+                  }),
+              )
+              .entry(
+                MenuItem::new(tr("menu-never-notify"))
+                  .selected(data.manager_metadata.pinned)
+                  .on_activate({
+                    let entry = data.clone();
+                    move |ctx, _, _| {
+                      let mut metadata = entry.manager_metadata.clone();
+                      metadata.pinned = !metadata.pinned;
+                      ctx.submit_command(
+                        ModMetadata::SAVE_MOD_METADATA.with((entry.path.clone(), metadata)),
+                      )
+                    }
+                  }),
+              )
+              .entry(
+                MenuItem::new("Update Automatically")
+                  .selected(data.manager_metadata.auto_update)
+                  .enabled(data.manager_metadata.auto_update || data.auto_updatable())
+                  .on_activate({
+                    let entry = data.clone();
+                    move |ctx, _, _| {
+                      let mut metadata = entry.manager_metadata.clone();
+                      metadata.auto_update = !metadata.auto_update;
+                      ctx.submit_command(
+                        ModMetadata::SAVE_MOD_METADATA.with((entry.path.clone(), metadata)),
+                      )
+                    }
+                  }),
+              )
+              .pipe(|menu| {
+                let Some(version) = data.available_update() else {
+                  return menu;
+                };
+                let ignored = data.manager_metadata.ignored_version.as_ref() == Some(&version);
+                menu.entry(
+                  MenuItem::new(tr_args("menu-ignore-update", &[("version", &version)]))
+                    .selected(ignored)
+                    .on_activate({
+                      let entry = data.clone();
+                      move |ctx, _, _| {
+                        let mut metadata = entry.manager_metadata.clone();
+                        metadata.ignored_version = (!ignored).then(|| version.clone());
+                        ctx.submit_command(
+                          ModMetadata::SAVE_MOD_METADATA.with((entry.path.clone(), metadata)),
+                        )
+                      }
+                    }),
+                )
+              })
+              .separator()
+              .entry(MenuItem::new("Export Release Zip...").on_activate({
+                let entry = data.clone();
+                move |ctx, _, _| ctx.submit_command(archive::EXPORT.with(entry.clone()))
+              }))
+              .entry(
+                MenuItem::new("Roll Back to a Previous Version...")
+                  .enabled(!archive::replaced_versions(&data.id).is_empty())
+                  .on_activate({
+                    let entry = data.clone();
+                    move |ctx, _, _| ctx.submit_command(archive::ASK_ROLL_BACK.with(entry.clone()))
+                  }),
+              )
+              .entry(MenuItem::new("Archive").on_activate({
+                let entry = data.clone();
+                move |ctx, _, _| ctx.submit_command(archive::ARCHIVE.with(entry.clone()))
+              }))
               .entry(MenuItem::new("Delete").on_activate({
                 let entry = data.clone();
                 move |ctx, _, _| ctx.submit_command(ModEntry::ASK_DELETE_MOD.with(entry.clone()))
@@ -2,16 +2,23 @@ use std::sync::Arc;
 
 use druid::{
   lens,
-  widget::{Controller, Label, Maybe},
+  widget::{Controller, Flex, Label, Maybe},
   Env, Event, EventCtx, Widget, WidgetExt,
 };
+use tap::Pipe;
+use tracing::error;
+use webview_shared::{InstallType, WEBVIEW_INSTALL};
 
 use crate::app::{
-  installer::{self, ChannelMessage},
-  mod_entry::{ModEntry, UpdateStatus},
+  i18n::{tr, tr_args},
+  install_log,
+  installer::{self, ChannelMessage, Retry},
+  mod_description::OPEN_IN_BROWSER,
+  mod_entry::{ModEntry, ModMetadata, UpdateStatus},
   mod_list::ModList,
+  mod_repo::UrlSource,
   modal::Modal,
-  util::{get_master_version, LabelExt},
+  util::{bold_text, get_master_version, LabelExt, ON_RED_KEY, RED_KEY},
   App,
 };
 
@@ -20,14 +27,17 @@ pub struct ModListController;
 impl<W: Widget<App>> Controller<App, W> for ModListController {
   fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut App, env: &Env) {
     if let Event::Command(cmd) = event {
-      if let Some((conflict, install_to, entry)) = cmd.get(ModList::OVERWRITE) {
+      if let Some((conflict, install_to, entry, mode)) = cmd.get(ModList::OVERWRITE) {
         if let Some(install_dir) = &data.settings.install_dir {
-          ctx.submit_command(App::LOG_MESSAGE.with(format!("Resuming install for {}", entry.name)));
+          ctx.submit_command(
+            App::LOG_MESSAGE.with(tr_args("log-resuming-install", &[("name", &entry.name)])),
+          );
           data.runtime.spawn(
-            installer::Payload::Resumed(entry.clone(), install_to.clone(), conflict.clone())
+            installer::Payload::Resumed(entry.clone(), install_to.clone(), conflict.clone(), *mode)
               .install(
                 ctx.get_external_handle(),
                 install_dir.clone(),
+                data.settings.cache_dir(),
                 data.mod_list.mods.values().map(|v| v.id.clone()).collect(),
               ),
           );
@@ -37,7 +47,8 @@ impl<W: Widget<App>> Controller<App, W> for ModListController {
         match payload {
           ChannelMessage::Success(entry) => {
             let mut entry = entry.clone();
-            if let Some(existing) = data.mod_list.mods.get(&entry.id) {
+            // an update is installed over the folder of the version it replaces
+            if let Some(existing) = data.mod_list.mods.get(&entry.path) {
               let mut mut_entry = Arc::make_mut(&mut entry);
               mut_entry.enabled = existing.enabled;
               if let Some(remote_version_checker) = existing.remote_version.clone() {
@@ -53,8 +64,13 @@ impl<W: Widget<App>> Controller<App, W> for ModListController {
                 version_checker,
               ));
             }
+            let previous = data.mod_list.mods.get(&entry.path).map(Arc::as_ref);
+            let change = install_log::Entry::installed(&entry, previous);
+            if let Err(err) = install_log::record(&change) {
+              error!("Failed to record install of {}: {}", entry.id, err)
+            }
             ctx.submit_command(App::LOG_SUCCESS.with(entry.name.clone()));
-            data.mod_list.mods.insert(entry.id.clone(), entry);
+            data.mod_list.replace(entry);
             ctx.children_changed();
           }
           ChannelMessage::Duplicate(conflict, to_install, entry) => ctx.submit_command(
@@ -65,48 +81,147 @@ impl<W: Widget<App>> Controller<App, W> for ModListController {
           }
           ChannelMessage::Error(name, err) => {
             ctx.submit_command(App::LOG_ERROR.with((name.clone(), err.clone())));
-            eprintln!("Failed to install {}", err);
+            error!("Failed to install {}", err);
+          }
+          ChannelMessage::DownloadFailed(entry, err, source, url) => {
+            ctx.submit_command(App::LOG_ERROR.with((entry.name.clone(), err.clone())));
+            error!("Failed to download {}: {}", entry.name, err);
+
+            let modal = Modal::new(&tr("update-download-failed-title"))
+              .with_content(tr_args("update-download-failed", &[("name", &entry.name)]))
+              .with_content(tr_args("update-download-failed-error", &[("error", err)]))
+              .with_content(tr_args("update-download-failed-mirror", &[("url", url)]));
+            let modal = if *source == UrlSource::DirectDownload {
+              let mut mirrored = (**entry).clone();
+              if let Some(remote) = mirrored.remote_version.as_mut() {
+                remote.direct_download_url = Some(url.clone());
+              }
+              modal.with_button(
+                &tr("update-download-from-mirror"),
+                ModList::AUTO_UPDATE.with(Arc::new(mirrored)),
+              )
+            } else {
+              modal.with_button(
+                &tr("update-open-download-page"),
+                OPEN_IN_BROWSER.with(url.clone()),
+              )
+            };
+            modal
+              .with_close_label(&tr("cancel"))
+              .show_with_size(ctx, env, &(), (500., 250.));
+          }
+          ChannelMessage::Corrupt(name, err, retry) => {
+            ctx.submit_command(App::LOG_ERROR.with((name.clone(), err.clone())));
+            error!("Corrupted download {}: {}", name, err);
+
+            let modal = Modal::new(&tr("corrupt-download-title"))
+              .with_content(tr_args("corrupt-download-body", &[("name", name)]))
+              .with_content(tr_args("corrupt-download-error", &[("error", err)]));
+            let modal = match retry {
+              Some(Retry::Download(url)) => modal
+                .with_button(
+                  &tr("corrupt-download-retry"),
+                  WEBVIEW_INSTALL.with(InstallType::Uri(url.clone())),
+                )
+                .with_close_label(&tr("cancel")),
+              Some(Retry::Update(entry)) => modal
+                .with_button(
+                  &tr("corrupt-download-retry"),
+                  ModList::AUTO_UPDATE.with(entry.clone()),
+                )
+                .with_close_label(&tr("cancel")),
+              None => modal.with_close_label(&tr("close")),
+            };
+            modal.show_with_size(ctx, env, &(), (500., 200.));
           }
         }
       }
     } else if let Event::Notification(notif) = event {
       if let Some(entry) = notif.get(ModEntry::AUTO_UPDATE) {
-        Modal::new("Auto-update?")
-          .with_content(format!("Would you like to automatically update {}?", entry.name))
-          .with_content(format!("Installed version: {}", entry.version))
-          .with_content(format!(
-            "New version: {}",
-            entry
-              .remote_version
-              .as_ref()
-              .map(|v| v.version.to_string())
-              .unwrap_or_else(|| String::from(
-                "Error: failed to retrieve version, this shouldn't be possible."
-              ))
+        Modal::new(&tr("update-title"))
+          .with_content(tr_args("update-prompt", &[("name", &entry.name)]))
+          .with_content(tr_args(
+            "update-installed-version",
+            &[("version", &entry.version)],
+          ))
+          .with_content(tr_args(
+            "update-new-version",
+            &[(
+              "version",
+              &entry
+                .remote_version
+                .as_ref()
+                .map(|v| v.version.to_string())
+                .unwrap_or_else(|| tr("update-version-error")),
+            )],
           ))
           .with_content(
-            Maybe::or_empty(|| Label::wrapped("\
-              NOTE: A .git directory has been detected in the target directory. \
-              Are you sure this isn't being used for development?\
-            "))
-            .lens(
-              lens::Constant(data.settings.git_warn.then(|| {
-                if entry.path.join(".git").exists() {
-                  Some(())
-                } else {
-                  None
-                }
-              }).flatten())
-            )
-            .boxed()
+            Maybe::or_empty(|| Label::wrapped(tr("update-git-warning")))
+              .lens(lens::Constant(
+                data
+                  .settings
+                  .git_warn
+                  .then(|| {
+                    if entry.path.join(".git").exists() {
+                      Some(())
+                    } else {
+                      None
+                    }
+                  })
+                  .flatten(),
+              ))
+              .boxed(),
           )
-          .with_content("WARNING:")
-          .with_content("Save compatibility is not guaranteed when updating a mod. Your save may no longer load if you apply this update.")
-          .with_content("Bug reports about saves broken by using this feature will be ignored.")
-          .with_content("YOU HAVE BEEN WARNED")
-          .with_button("Update", ModList::AUTO_UPDATE.with(entry.clone()))
-          .with_close_label("Cancel")
-          .show_with_size(ctx, env, &(), (600., 300.));
+          .with_content(
+            Maybe::or_empty(|| {
+              Flex::column()
+                .with_child(bold_text(
+                  &tr("update-save-breaking"),
+                  druid::theme::TEXT_SIZE_NORMAL,
+                  druid::FontWeight::BOLD,
+                  ON_RED_KEY,
+                ))
+                .with_child(
+                  Label::wrapped_func(|line: &String, _| {
+                    tr_args("update-save-breaking-line", &[("line", line)])
+                  })
+                  .with_text_color(ON_RED_KEY),
+                )
+                .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+                .padding(5.)
+                .background(RED_KEY)
+                .rounded(3.)
+                .expand_width()
+            })
+            .lens(lens::Constant(
+              entry
+                .remote_version
+                .as_ref()
+                .and_then(|remote| remote.save_warning.clone()),
+            ))
+            .boxed(),
+          )
+          .with_content(tr("update-warning"))
+          .with_content(tr("update-warning-saves"))
+          .with_content(tr("update-warning-reports"))
+          .with_content(tr("update-warning-warned"))
+          .with_button(
+            &tr("update-confirm"),
+            ModList::AUTO_UPDATE.with(entry.clone()),
+          )
+          .pipe(|modal| {
+            let Some(version) = entry.available_update() else {
+              return modal;
+            };
+            let mut metadata = entry.manager_metadata.clone();
+            metadata.ignored_version = Some(version);
+            modal.with_button(
+              &tr("update-ignore"),
+              ModMetadata::SAVE_MOD_METADATA.with((entry.path.clone(), metadata)),
+            )
+          })
+          .with_close_label(&tr("cancel"))
+          .show_with_size(ctx, env, &(), (600., 360.));
       }
     }
 
@@ -0,0 +1,130 @@
+//! Offering MOSS as a program to open mod archives with, so a downloaded mod can be installed by
+//! double-clicking it.
+//!
+//! The OS starts MOSS with the archive as an argument, which goes through the same path as
+//! archives dropped on the executable, and if MOSS is already running the single instance lock
+//! hands it over. Registering only adds MOSS to the "Open with" choices for the current user -
+//! the OS asks before making it the default, so archive managers aren't silently replaced.
+
+/// Archive types MOSS can install from.
+pub const EXTENSIONS: [&str; 3] = ["zip", "7z", "rar"];
+
+#[cfg(target_os = "windows")]
+const PROG_ID: &str = "MOSS.ModArchive";
+
+/// Offers this executable for opening mod archives.
+#[cfg(target_os = "windows")]
+pub fn register() -> anyhow::Result<()> {
+  use super::url_scheme::reg;
+
+  let exe = std::env::current_exe()?;
+  let command = format!("\"{}\" \"%1\"", exe.to_string_lossy());
+  let key = format!(r"HKCU\Software\Classes\{}", PROG_ID);
+
+  reg(&["add", &key, "/ve", "/d", "Starsector mod archive", "/f"])?;
+  reg(&[
+    "add",
+    &format!(r"{}\shell\open\command", key),
+    "/ve",
+    "/d",
+    &command,
+    "/f",
+  ])?;
+  for extension in EXTENSIONS {
+    let key = format!(r"HKCU\Software\Classes\.{}\OpenWithProgids", extension);
+    reg(&["add", &key, "/v", PROG_ID, "/d", "", "/f"])?;
+  }
+
+  Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn unregister() -> anyhow::Result<()> {
+  use super::url_scheme::reg;
+
+  for extension in EXTENSIONS {
+    // the value is missing if registering failed part way
+    let key = format!(r"HKCU\Software\Classes\.{}\OpenWithProgids", extension);
+    let _ = reg(&["delete", &key, "/v", PROG_ID, "/f"]);
+  }
+
+  reg(&[
+    "delete",
+    &format!(r"HKCU\Software\Classes\{}", PROG_ID),
+    "/f",
+  ])
+}
+
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "moss-mod-archive.desktop";
+
+#[cfg(target_os = "linux")]
+const MIME_TYPES: [&str; 4] = [
+  "application/zip",
+  "application/x-7z-compressed",
+  "application/vnd.rar",
+  "application/x-rar",
+];
+
+#[cfg(target_os = "linux")]
+fn applications_dir() -> anyhow::Result<std::path::PathBuf> {
+  let dirs = directories::BaseDirs::new().ok_or_else(|| anyhow::anyhow!("No home folder"))?;
+
+  Ok(dirs.data_dir().join("applications"))
+}
+
+/// Offers this executable for opening mod archives.
+#[cfg(target_os = "linux")]
+pub fn register() -> anyhow::Result<()> {
+  let exe = std::env::current_exe()?;
+  let dir = applications_dir()?;
+  std::fs::create_dir_all(&dir)?;
+  std::fs::write(
+    dir.join(DESKTOP_FILE),
+    format!(
+      "[Desktop Entry]\n\
+       Type=Application\n\
+       Name=MOSS\n\
+       Comment=Install Starsector mods\n\
+       Exec=\"{}\" %F\n\
+       NoDisplay=true\n\
+       MimeType={};\n",
+      exe.to_string_lossy(),
+      MIME_TYPES.join(";")
+    ),
+  )?;
+  update_desktop_database(&dir);
+
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn unregister() -> anyhow::Result<()> {
+  let dir = applications_dir()?;
+  match std::fs::remove_file(dir.join(DESKTOP_FILE)) {
+    Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+    _ => {}
+  }
+  update_desktop_database(&dir);
+
+  Ok(())
+}
+
+/// Refreshes the cache desktop environments read "Open with" choices from. Not every distro
+/// ships the tool, and those without it read the desktop files directly.
+#[cfg(target_os = "linux")]
+fn update_desktop_database(dir: &std::path::Path) {
+  let _ = std::process::Command::new("update-desktop-database")
+    .arg(dir)
+    .status();
+}
+
+#[cfg(target_os = "macos")]
+pub fn register() -> anyhow::Result<()> {
+  anyhow::bail!("Opening archives with MOSS isn't supported on macOS yet")
+}
+
+#[cfg(target_os = "macos")]
+pub fn unregister() -> anyhow::Result<()> {
+  Ok(())
+}
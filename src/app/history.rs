@@ -0,0 +1,204 @@
+//! Undo and redo for enabling, disabling and deleting mods.
+//!
+//! Deleted mods are moved into a trash folder next to them rather than being removed outright,
+//! so that deleting can be undone. Keeping the trash in the same folder as the mod means it's
+//! always on the same drive, so trashing a mod never needs it copied. The trash is emptied each
+//! time MOSS starts.
+
+use std::{
+  io,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use chrono::Local;
+use druid::{im::Vector, Selector};
+use tracing::error;
+
+use super::{i18n::tr_args, mod_entry::ModEntry};
+
+pub const UNDO: Selector = Selector::new("history.undo");
+pub const REDO: Selector = Selector::new("history.redo");
+
+/// How many operations are remembered.
+const LIMIT: usize = 50;
+
+#[derive(Clone, Debug)]
+pub enum Operation {
+  /// IDs of the enabled mods before and after, sorted.
+  SetEnabled(Vec<String>, Vec<String>),
+  /// A deleted mod, and where its folder is in the trash.
+  Delete(Arc<ModEntry>, PathBuf),
+}
+
+impl Operation {
+  pub fn describe(&self) -> String {
+    match self {
+      Operation::SetEnabled(before, after) => {
+        let changed = before
+          .iter()
+          .filter(|id| after.binary_search(id).is_err())
+          .chain(after.iter().filter(|id| before.binary_search(id).is_err()))
+          .count();
+        tr_args("history-set-enabled", &[("count", &changed)])
+      }
+      Operation::Delete(entry, _) => tr_args("history-delete", &[("name", &entry.name)]),
+    }
+  }
+}
+
+#[derive(Clone, Default)]
+pub struct History {
+  undo: Vector<Operation>,
+  redo: Vector<Operation>,
+}
+
+impl History {
+  /// Remembers a new operation, forgetting anything that was undone before it.
+  pub fn record(&mut self, operation: Operation) {
+    self.push_undo(operation);
+    self.redo.clear();
+  }
+
+  /// The last operation, to be undone. Pass it to [`History::undone`] once it has been.
+  pub fn take_undo(&mut self) -> Option<Operation> {
+    self.undo.pop_back()
+  }
+
+  /// The last undone operation, to be redone. Pass it to [`History::redone`] once it has been.
+  pub fn take_redo(&mut self) -> Option<Operation> {
+    self.redo.pop_back()
+  }
+
+  pub fn undone(&mut self, operation: Operation) {
+    self.redo.push_back(operation)
+  }
+
+  pub fn redone(&mut self, operation: Operation) {
+    self.push_undo(operation)
+  }
+
+  /// IDs of the mods enabled by the remembered operations, most recently enabled first.
+  pub fn recently_enabled(&self) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for operation in self.undo.iter().rev() {
+      if let Operation::SetEnabled(before, after) = operation {
+        for id in after.iter().filter(|id| before.binary_search(id).is_err()) {
+          if !ids.contains(id) {
+            ids.push(id.clone())
+          }
+        }
+      }
+    }
+
+    ids
+  }
+
+  fn push_undo(&mut self, operation: Operation) {
+    self.undo.push_back(operation);
+    while self.undo.len() > LIMIT {
+      self.undo.pop_front();
+    }
+  }
+}
+
+/// Name of the trash folder kept in the mods folder, and in each external mod folder. It has no
+/// `mod_info.json`, so neither MOSS nor the game mistake it for a mod.
+pub const TRASH: &str = ".moss-trash";
+
+/// Moves `path` into the trash in the folder it's in, returning where it ended up.
+pub fn trash(path: &Path) -> io::Result<PathBuf> {
+  let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+    return Err(io::Error::new(io::ErrorKind::InvalidInput, "Folder has no name"));
+  };
+
+  let trash_dir = parent.join(TRASH);
+  std::fs::create_dir_all(&trash_dir)?;
+  let destination = trash_dir.join(format!(
+    "{}-{}",
+    Local::now().format("%Y%m%d-%H%M%S%3f"),
+    name.to_string_lossy()
+  ));
+  std::fs::rename(path, &destination)?;
+
+  Ok(destination)
+}
+
+/// Moves a trashed folder back to `original`, unless something has taken its place since.
+pub fn restore(trashed: &Path, original: &Path) -> io::Result<()> {
+  if original.exists() {
+    return Err(io::Error::new(
+      io::ErrorKind::AlreadyExists,
+      format!("{} already exists", original.to_string_lossy()),
+    ));
+  }
+
+  std::fs::rename(trashed, original)
+}
+
+/// Empties the trash in each of `dirs`, the folders mods are kept in.
+pub fn empty_trash(dirs: Vec<PathBuf>) {
+  for dir in dirs {
+    if let Err(err) = remove_dir_all::remove_dir_all(dir.join(TRASH))
+      && err.kind() != io::ErrorKind::NotFound
+    {
+      error!("Failed to empty the trash in {:?} {:?}", dir, err)
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::fs;
+
+  use tempfile::tempdir;
+
+  use super::{empty_trash, restore, trash, History, Operation, TRASH};
+
+  fn set_enabled(before: &[&str], after: &[&str]) -> Operation {
+    let ids = |ids: &[&str]| -> Vec<String> { ids.iter().map(|id| id.to_string()).collect() };
+    Operation::SetEnabled(ids(before), ids(after))
+  }
+
+  #[test]
+  fn recording_forgets_undone_operations() {
+    let mut history = History::default();
+    history.record(set_enabled(&[], &["a"]));
+    history.record(set_enabled(&["a"], &["a", "b"]));
+
+    let undone = history.take_undo().expect("Operation to undo");
+    assert_eq!(undone.describe(), "enabling or disabling 1 mod(s)");
+    history.undone(undone);
+    let redone = history.take_redo().expect("Operation to redo");
+    history.redone(redone);
+    assert!(history.take_redo().is_none());
+
+    if let Some(undone) = history.take_undo() {
+      history.undone(undone)
+    }
+    history.record(set_enabled(&["a"], &[]));
+    assert!(history.take_redo().is_none());
+    assert!(history.take_undo().is_some());
+    assert!(history.take_undo().is_some());
+    assert!(history.take_undo().is_none());
+  }
+
+  #[test]
+  fn trashes_next_to_the_mod() {
+    let mods = tempdir().expect("Create mods dir");
+    let path = mods.path().join("some_mod");
+    fs::create_dir(&path).expect("Create mod");
+    fs::write(path.join("mod_info.json"), "{}").expect("Write mod_info.json");
+
+    let trashed = trash(&path).expect("Trash mod");
+    assert!(!path.exists());
+    assert!(trashed.starts_with(mods.path().join(TRASH)));
+
+    restore(&trashed, &path).expect("Restore mod");
+    assert!(path.join("mod_info.json").is_file());
+
+    trash(&path).expect("Trash mod again");
+    empty_trash(vec![mods.path().to_path_buf()]);
+    assert!(!mods.path().join(TRASH).exists());
+  }
+}
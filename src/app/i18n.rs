@@ -0,0 +1,130 @@
+//! Translated UI strings.
+//!
+//! Language packs are files in a small subset of the Fluent format - one `key = value` per
+//! line, `#` comments, and `{ $name }` placeholders - named after their language code, eg:
+//! `ru.ftl`. English is built in, any other packs are read from the `lang` folder in the data
+//! dir. Keys missing from a pack fall back to English.
+
+use std::{
+  collections::HashMap,
+  fmt::Display,
+  path::PathBuf,
+  sync::{LazyLock, RwLock},
+};
+
+use tracing::warn;
+use webview_shared::PROJECT;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+static ENGLISH: LazyLock<HashMap<String, String>> =
+  LazyLock::new(|| parse(include_str!("../../assets/lang/en.ftl")));
+
+static ACTIVE: LazyLock<RwLock<HashMap<String, String>>> = LazyLock::new(Default::default);
+
+pub fn lang_dir() -> PathBuf {
+  PROJECT.data_dir().join("lang")
+}
+
+/// Switches to the pack for `code`, falling back to English if it can't be read.
+pub fn set_language(code: &str) {
+  let pack = if code == DEFAULT_LANGUAGE {
+    HashMap::new()
+  } else {
+    match std::fs::read_to_string(lang_dir().join(code).with_extension("ftl")) {
+      Ok(text) => parse(&text),
+      Err(err) => {
+        warn!("Failed to read language pack {}: {}", code, err);
+        HashMap::new()
+      }
+    }
+  };
+
+  if let Ok(mut active) = ACTIVE.write() {
+    *active = pack
+  }
+}
+
+/// The language code and display name of every available pack, English first.
+pub fn available() -> Vec<(String, String)> {
+  let mut packs: Vec<(String, String)> = std::fs::read_dir(lang_dir())
+    .map(|entries| {
+      entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ftl"))
+        .filter_map(|path| {
+          let code = path.file_stem()?.to_string_lossy().to_string();
+          let name = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| parse(&text).remove("language-name"))
+            .unwrap_or_else(|| code.clone());
+
+          (code != DEFAULT_LANGUAGE).then_some((code, name))
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  packs.sort();
+  packs.insert(0, (DEFAULT_LANGUAGE.to_string(), tr("language-name")));
+
+  packs
+}
+
+/// Looks up `key` in the active language pack.
+pub fn tr(key: &str) -> String {
+  lookup(key, |value| value.to_string())
+}
+
+/// Looks up `key` in the active language pack, filling in its `{ $name }` placeholders.
+pub fn tr_args(key: &str, args: &[(&str, &dyn Display)]) -> String {
+  lookup(key, |value| {
+    args.iter().fold(value.to_string(), |value, (name, arg)| {
+      value.replace(&format!("{{ ${} }}", name), &arg.to_string())
+    })
+  })
+}
+
+fn lookup(key: &str, format: impl Fn(&str) -> String) -> String {
+  if let Ok(active) = ACTIVE.read() && let Some(value) = active.get(key) {
+    return format(value);
+  }
+
+  ENGLISH.get(key).map_or_else(
+    || {
+      warn!("Missing translation for {}", key);
+      key.to_string()
+    },
+    |value| format(value),
+  )
+}
+
+fn parse(text: &str) -> HashMap<String, String> {
+  text
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .filter_map(|line| line.split_once('='))
+    .map(|(key, value)| (key.trim().to_string(), value.trim().replace("\\n", "\n")))
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::{parse, ENGLISH};
+
+  #[test]
+  fn parses_packs() {
+    let pack = parse("# comment\nlanguage-name = Русский\n\nbroken line\nkey = a = b\\nc");
+
+    assert_eq!(pack.len(), 2);
+    assert_eq!(pack["language-name"], "Русский");
+    assert_eq!(pack["key"], "a = b\nc");
+  }
+
+  #[test]
+  fn english_is_complete() {
+    assert!(ENGLISH.contains_key("language-name"));
+    assert!(ENGLISH.values().all(|value| !value.is_empty()));
+  }
+}
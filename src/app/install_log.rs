@@ -0,0 +1,232 @@
+//! A permanent record of every mod MOSS installs, updates, rolls back, archives or deletes, for
+//! working out what changed before a save stopped loading.
+//!
+//! Unlike the undo history this survives restarts. Each change is a line of JSON appended to a
+//! file in MOSS's data folder, so recording one never rewrites what's already there.
+
+use std::{
+  io::{self, Write},
+  path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use webview_shared::PROJECT;
+
+use super::{i18n::tr_args, mod_entry::ModEntry};
+
+/// How many changes the history view shows.
+pub const SHOWN: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Change {
+  Installed,
+  Updated,
+  RolledBack,
+  Deleted,
+  /// Zipped into the mod archive and then removed.
+  Archived,
+  /// Put back from the trash by undoing a delete.
+  Restored,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+  pub change: Change,
+  pub id: String,
+  pub name: String,
+  pub version_before: Option<String>,
+  pub version_after: Option<String>,
+  /// The URL or archive the mod was installed from, if known.
+  pub source: Option<String>,
+  pub time: DateTime<Local>,
+}
+
+impl Entry {
+  /// An install of `entry`, or an update if it replaced `previous`.
+  pub fn installed(entry: &ModEntry, previous: Option<&ModEntry>) -> Self {
+    Self {
+      change: if previous.is_some() {
+        Change::Updated
+      } else {
+        Change::Installed
+      },
+      id: entry.id.clone(),
+      name: entry.name.clone(),
+      version_before: previous.map(|previous| previous.version.to_string()),
+      version_after: Some(entry.version.to_string()),
+      source: entry.manager_metadata.source.clone(),
+      time: Local::now(),
+    }
+  }
+
+  /// A roll back of `entry` to a kept copy of `version`.
+  pub fn rolled_back(entry: &ModEntry, version: String) -> Self {
+    Self {
+      change: Change::RolledBack,
+      version_before: Some(entry.version.to_string()),
+      version_after: Some(version),
+      source: None,
+      ..Self::deleted(entry)
+    }
+  }
+
+  /// `entry` zipped into `archive` and removed from the mods folder.
+  pub fn archived(entry: &ModEntry, archive: &Path) -> Self {
+    Self {
+      change: Change::Archived,
+      source: Some(archive.to_string_lossy().into_owned()),
+      ..Self::deleted(entry)
+    }
+  }
+
+  /// A delete of `entry` that was undone.
+  pub fn restored(entry: &ModEntry) -> Self {
+    Self {
+      change: Change::Restored,
+      version_before: None,
+      version_after: Some(entry.version.to_string()),
+      ..Self::deleted(entry)
+    }
+  }
+
+  pub fn deleted(entry: &ModEntry) -> Self {
+    Self {
+      change: Change::Deleted,
+      id: entry.id.clone(),
+      name: entry.name.clone(),
+      version_before: Some(entry.version.to_string()),
+      version_after: None,
+      source: entry.manager_metadata.source.clone(),
+      time: Local::now(),
+    }
+  }
+
+  pub fn describe(&self) -> String {
+    let version = |version: &Option<String>| version.as_deref().unwrap_or("unknown").to_string();
+    let key = match self.change {
+      Change::Installed => "install-log-installed",
+      Change::Updated => "install-log-updated",
+      Change::RolledBack => "install-log-rolled-back",
+      Change::Deleted => "install-log-deleted",
+      Change::Archived => "install-log-archived",
+      Change::Restored => "install-log-restored",
+    };
+
+    tr_args(
+      key,
+      &[
+        ("name", &self.name),
+        ("before", &version(&self.version_before)),
+        ("after", &version(&self.version_after)),
+      ],
+    )
+  }
+
+  /// The source, if it can be downloaded again.
+  pub fn url(&self) -> Option<&str> {
+    self
+      .source
+      .as_deref()
+      .filter(|source| source.starts_with("https://") || source.starts_with("http://"))
+  }
+
+  /// The source, if it's an archive or folder that's still there.
+  pub fn local_source(&self) -> Option<PathBuf> {
+    self
+      .source
+      .as_ref()
+      .filter(|_| self.url().is_none())
+      .map(PathBuf::from)
+      .filter(|path| path.exists())
+  }
+}
+
+pub fn path() -> PathBuf {
+  PROJECT.data_dir().join("install_history.jsonl")
+}
+
+pub fn record(entry: &Entry) -> io::Result<()> {
+  record_in(&path(), entry)
+}
+
+fn record_in(path: &Path, entry: &Entry) -> io::Result<()> {
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  let mut line = serde_json::to_vec(entry)?;
+  line.push(b'\n');
+
+  std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)?
+    .write_all(&line)
+}
+
+/// The most recent [`SHOWN`] changes, newest first.
+pub fn read() -> Vec<Entry> {
+  read_from(&path())
+}
+
+fn read_from(path: &Path) -> Vec<Entry> {
+  let text = std::fs::read_to_string(path).unwrap_or_default();
+
+  // a line cut short by a crash mid-write is skipped rather than losing the whole log
+  text
+    .lines()
+    .rev()
+    .filter_map(|line| serde_json::from_str(line).ok())
+    .take(SHOWN)
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use chrono::Local;
+  use tempfile::tempdir;
+
+  use super::{read_from, record_in, Change, Entry};
+
+  fn entry(change: Change, before: Option<&str>, after: Option<&str>) -> Entry {
+    Entry {
+      change,
+      id: String::from("lw_lazylib"),
+      name: String::from("LazyLib"),
+      version_before: before.map(String::from),
+      version_after: after.map(String::from),
+      source: Some(String::from("https://example.com/LazyLib.zip")),
+      time: Local::now(),
+    }
+  }
+
+  #[test]
+  fn reads_newest_first() {
+    let dir = tempdir().expect("Create data dir");
+    let path = dir.path().join("install_history.jsonl");
+    record_in(&path, &entry(Change::Installed, None, Some("2.7"))).expect("Record install");
+    std::fs::OpenOptions::new()
+      .append(true)
+      .open(&path)
+      .and_then(|mut file| std::io::Write::write_all(&mut file, b"{\"change\":\"Upd\n"))
+      .expect("Write torn line");
+    record_in(&path, &entry(Change::Updated, Some("2.7"), Some("2.8"))).expect("Record update");
+
+    let entries = read_from(&path);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].describe(), "Updated LazyLib from 2.7 to 2.8");
+    assert_eq!(entries[1].describe(), "Installed LazyLib 2.7");
+    assert_eq!(entries[0].url(), Some("https://example.com/LazyLib.zip"));
+    assert!(entries[0].local_source().is_none());
+
+    let archive = dir.path().join("LazyLib-2.8.zip");
+    std::fs::write(&archive, "").expect("Write archive");
+    let archived = Entry {
+      change: Change::Archived,
+      source: Some(archive.to_string_lossy().into_owned()),
+      ..entry(Change::Deleted, Some("2.8"), None)
+    };
+    assert_eq!(archived.describe(), "Archived LazyLib 2.8");
+    assert_eq!(archived.local_source(), Some(archive));
+  }
+}
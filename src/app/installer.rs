@@ -4,30 +4,48 @@ use std::{
   io::{self, Write},
   iter::FusedIterator,
   path::{Path, PathBuf},
-  sync::Arc,
+  sync::{
+    atomic::{AtomicI64, Ordering},
+    Arc, LazyLock, Mutex,
+  },
 };
 
 use chrono::Local;
 use druid::im::Vector;
-use druid::{ExtEventSink, Selector, SingleUse, Target};
+use druid::{Data, ExtEventSink, Selector, SingleUse, Target};
 use remove_dir_all::remove_dir_all;
 use reqwest::Url;
 use snafu::{OptionExt, ResultExt, Snafu};
-use tempfile::{tempdir, TempDir};
+use tempfile::{tempdir_in, NamedTempFile, TempDir};
 use tokio::{
   fs::rename,
-  task::{self, JoinSet},
+  sync::{mpsc::error::TrySendError, Semaphore},
+  task::{self, AbortHandle, JoinSet},
   time::timeout,
 };
+use tracing::error;
 
 use crate::app::{mod_entry::ModEntry, util::LoadBalancer};
 
-use super::mod_entry::ModMetadata;
+use super::{
+  archive,
+  i18n::{tr, tr_args},
+  mod_entry::ModMetadata,
+  mod_list::library,
+  mod_repo::{ModRepo, UrlSource},
+  net,
+  progress::{self, Progress},
+  secrets,
+};
+
+mod extract;
+pub mod loose_file;
+mod quarantine;
 
 #[derive(Clone)]
 pub enum Payload {
   Initial(Vec<PathBuf>),
-  Resumed(Arc<ModEntry>, HybridPath, PathBuf),
+  Resumed(Arc<ModEntry>, HybridPath, PathBuf, OverwriteMode),
   Download(Arc<ModEntry>),
 }
 
@@ -37,29 +55,162 @@ pub const DOWNLOAD_PROGRESS: Selector<Vec<(i64, String, f64)>> =
   Selector::new("install.download.progress");
 pub const INSTALL_ALL: Selector<SingleUse<(Vector<PathBuf>, HybridPath)>> =
   Selector::new("install.found_multiple.install_all");
+pub const INSTALL_REPORT: Selector<Vec<InstallOutcome>> = Selector::new("install.batch.report");
+pub const INSTALL_STATUS: Selector<(i64, String, InstallStatus)> =
+  Selector::new("install.queue.status");
+
+/// Maximum number of archives extracted and installed at the same time.
+const MAX_CONCURRENT_INSTALLS: usize = 3;
+
+static INSTALL_PERMITS: LazyLock<Semaphore> =
+  LazyLock::new(|| Semaphore::new(MAX_CONCURRENT_INSTALLS));
+static INSTALL_QUEUE: LazyLock<Mutex<HashMap<i64, (AbortHandle, Progress)>>> =
+  LazyLock::new(Default::default);
+static NEXT_QUEUE_ID: AtomicI64 = AtomicI64::new(0);
+/// Downloads in progress, by the time they started at.
+static DOWNLOADS: LazyLock<Mutex<HashMap<i64, Progress>>> = LazyLock::new(Default::default);
+/// Where files queued for install came from, when that isn't the file itself - the URL of a
+/// download, or the archive a mod folder was found in.
+static SOURCES: LazyLock<Mutex<HashMap<PathBuf, String>>> = LazyLock::new(Default::default);
+
+/// Cancels a queued or in progress install. Returns false if the install has already finished.
+///
+/// An archive that is mid-extraction stops at its next read and what was extracted so far is
+/// removed. Rar archives are read by unrar itself, so they finish extracting in the background
+/// before being removed.
+pub fn cancel(id: i64) -> bool {
+  INSTALL_QUEUE
+    .lock()
+    .ok()
+    .and_then(|mut queue| queue.remove(&id))
+    .map(|(handle, progress)| {
+      progress.cancel();
+      handle.abort()
+    })
+    .is_some()
+}
+
+/// Cancels the download that started at `started`, removing what was downloaded so far. Returns
+/// false if it has already finished.
+pub fn cancel_download(started: i64) -> bool {
+  DOWNLOADS
+    .lock()
+    .ok()
+    .and_then(|mut downloads| downloads.remove(&started))
+    .map(|progress| progress.cancel())
+    .is_some()
+}
+
+/// Records that `path` came from `source`, so installing it records `source` as where the mod
+/// came from.
+pub fn remember_source(path: &Path, source: &str) {
+  if let Ok(mut sources) = SOURCES.lock() {
+    sources.insert(path.to_path_buf(), source.to_string());
+  }
+}
+
+fn source_of(path: &Path) -> String {
+  SOURCES
+    .lock()
+    .ok()
+    .and_then(|mut sources| sources.remove(path))
+    .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
 
 impl Payload {
-  pub async fn install(self, ext_ctx: ExtEventSink, install_dir: PathBuf, installed: Vec<String>) {
-    let mods_dir = install_dir.join("mods");
+  pub async fn install(
+    self,
+    ext_ctx: ExtEventSink,
+    install_dir: PathBuf,
+    cache_dir: PathBuf,
+    installed: Vec<String>,
+  ) {
+    let mods_dir = library::install_dir_for(&install_dir);
     let mut handles = JoinSet::new();
     match self {
       Payload::Initial(targets) => {
+        let batch = targets.len() > 1;
         let mods_dir = Arc::new(mods_dir);
+        let cache_dir = Arc::new(cache_dir);
         let installed = Arc::new(installed);
+        let mut paths = JoinSet::new();
+        let mut pending = HashMap::new();
         for target in targets {
-          handles.spawn(handle_path(
-            ext_ctx.clone(),
-            target,
-            mods_dir.clone(),
-            installed.clone(),
-          ));
+          let id = NEXT_QUEUE_ID.fetch_add(1, Ordering::Relaxed);
+          let name = target
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("unknown"));
+          let status = {
+            let ext_ctx = ext_ctx.clone();
+            let name = name.clone();
+            move |status| {
+              let _ =
+                ext_ctx.submit_command(INSTALL_STATUS, (id, name.clone(), status), Target::Auto);
+            }
+          };
+          status(InstallStatus::Queued);
+
+          let progress = Progress::new(progress::Unit::Bytes);
+          let handle = paths.spawn({
+            let ext_ctx = ext_ctx.clone();
+            let mods_dir = mods_dir.clone();
+            let cache_dir = cache_dir.clone();
+            let installed = installed.clone();
+            let progress = progress.clone();
+            async move {
+              let _permit = INSTALL_PERMITS.acquire().await;
+              status(InstallStatus::Extracting(progress.clone()));
+
+              let outcome =
+                handle_path(ext_ctx, target, mods_dir, cache_dir, installed, progress).await;
+              status(match &outcome {
+                InstallOutcome::Installed(..) => InstallStatus::Done,
+                InstallOutcome::Skipped(_, reason) => {
+                  InstallStatus::AwaitingDecision(reason.clone())
+                }
+                InstallOutcome::Failed(_, reason) => InstallStatus::Failed(reason.clone()),
+              });
+
+              (id, outcome)
+            }
+          });
+          if let Ok(mut queue) = INSTALL_QUEUE.lock() {
+            queue.insert(id, (handle, progress));
+          }
+          pending.insert(id, name);
+        }
+
+        let mut report = Vec::new();
+        while let Some(res) = paths.join_next().await {
+          match res {
+            Ok((id, outcome)) => {
+              if let Ok(mut queue) = INSTALL_QUEUE.lock() {
+                queue.remove(&id);
+              }
+              pending.remove(&id);
+              report.push(outcome)
+            }
+            Err(err) if err.is_cancelled() => {}
+            Err(err) => error!("Install task failed {:?}", err),
+          }
+        }
+        for (_, name) in pending {
+          report.push(InstallOutcome::Skipped(name, String::from("Cancelled")))
+        }
+        if batch {
+          let _ = ext_ctx.submit_command(INSTALL_REPORT, report, Target::Auto);
         }
       }
-      Payload::Resumed(entry, path, existing) => {
-        handles.spawn(async move { handle_delete(ext_ctx.clone(), entry, path, existing).await });
+      Payload::Resumed(entry, path, existing, mode) => {
+        handles.spawn(async move {
+          let _ = handle_delete(ext_ctx.clone(), entry, path, existing, mode).await;
+        });
       }
       Payload::Download(entry) => {
-        handles.spawn(handle_auto(ext_ctx, entry));
+        handles.spawn(async move {
+          handle_auto(ext_ctx, entry, cache_dir).await;
+        });
       }
     }
     while handles.join_next().await.is_some() {}
@@ -70,30 +221,44 @@ async fn handle_path(
   ext_ctx: ExtEventSink,
   path: PathBuf,
   mods_dir: Arc<PathBuf>,
+  cache_dir: Arc<PathBuf>,
   installed: Arc<Vec<String>>,
-) {
+  progress: Progress,
+) -> InstallOutcome {
   let file_name = path
     .file_name()
     .map(|f| f.to_string_lossy().to_string())
     .unwrap_or_else(|| String::from("unknown"));
+  let source = source_of(&path);
 
   let mod_folder = if path.is_file() {
-    let decompress = task::spawn_blocking(move || decompress(path))
-      .await
-      .expect("Run decompression");
+    let decompress = task::spawn_blocking(move || {
+      let temp = decompress(path, &cache_dir, &progress)?;
+      // some mods are distributed as an archive wrapped in another archive
+      match find_nested_archive(temp.path()) {
+        Some(nested) => decompress(nested, &cache_dir, &progress),
+        None => Ok(temp),
+      }
+    })
+    .await
+    .context(Join {})
+    .and_then(|res| res);
     match decompress {
       Ok(temp) => HybridPath::Temp(Arc::new(temp), file_name.clone(), None),
       Err(err) => {
-        println!("{:?}", err);
-        ext_ctx
-          .submit_command(
-            INSTALL,
-            ChannelMessage::Error(file_name, err.to_string()),
-            Target::Auto,
-          )
-          .expect("Send error over async channel");
-
-        return;
+        error!("{:?}", err);
+        let message = match &err {
+          InstallError::Corrupt { detail } => {
+            let retry = Url::parse(&source)
+              .is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+              .then(|| Retry::Download(source.clone()));
+            ChannelMessage::Corrupt(file_name.clone(), detail.clone(), retry)
+          }
+          _ => ChannelMessage::Error(file_name.clone(), err.to_string()),
+        };
+        let _ = ext_ctx.submit_command(INSTALL, message, Target::Auto);
+
+        return InstallOutcome::Failed(file_name, err.to_string());
       }
     }
   } else {
@@ -116,13 +281,18 @@ async fn handle_path(
   {
     Ok(mod_paths) => {
       if mod_paths.len() > 1 {
+        for mod_path in &mod_paths {
+          remember_source(mod_path, &source);
+        }
         let _ = ext_ctx.submit_command(
           INSTALL,
           ChannelMessage::FoundMultiple(mod_folder, mod_paths),
           Target::Auto,
         );
+
+        InstallOutcome::Skipped(file_name, String::from("Contains multiple mods, awaiting selection"))
       } else if let Some(mod_path) = mod_paths.get(0)
-          && let mod_metadata = ModMetadata::new()
+          && let mod_metadata = ModMetadata::with_source(source)
           && mod_metadata.save(mod_path).await.is_ok()
           && let Ok(mut mod_info) = ModEntry::from_file(mod_path, mod_metadata)
         {
@@ -137,71 +307,90 @@ async fn handle_path(
             // instead, just submit the new entry if it doesn't conflict with an existing path, _then_ detect the conflict
             // that way there's less chance an existing ID gets missed due to the ID list effectively getting cached when
             // this function starts
-            ext_ctx.submit_command(INSTALL, ChannelMessage::Duplicate(id.clone().into(), rewrite(), Arc::new(mod_info)), Target::Auto).expect("Send query over async channel");
+            let _ = ext_ctx.submit_command(INSTALL, ChannelMessage::Duplicate(id.clone().into(), rewrite(), Arc::new(mod_info)), Target::Auto);
+
+            InstallOutcome::Skipped(file_name, String::from("Already installed, awaiting overwrite decision"))
           } else if mods_dir.join(mod_info.id.clone()).exists() {
             let mod_folder = rewrite();
-            ext_ctx.submit_command(INSTALL, ChannelMessage::Duplicate(mods_dir.join(mod_info.id.clone()).into(), mod_folder, Arc::new(mod_info)), Target::Auto).expect("Send query over async channel");
+            let _ = ext_ctx.submit_command(INSTALL, ChannelMessage::Duplicate(mods_dir.join(mod_info.id.clone()).into(), mod_folder, Arc::new(mod_info)), Target::Auto);
+
+            InstallOutcome::Skipped(file_name, String::from("Target folder already exists, awaiting overwrite decision"))
           } else {
-            move_or_copy(mod_path.clone(), mods_dir.join(&mod_info.id)).await;
+            if let Err(err) = move_or_copy(mod_path.clone(), mods_dir.join(&mod_info.id)).await {
+              let err = format!("{:#}", err);
+              let _ = ext_ctx.submit_command(INSTALL, ChannelMessage::Error(file_name.clone(), err.clone()), Target::Auto);
+
+              return InstallOutcome::Failed(file_name, err);
+            }
 
             mod_info.set_path(mods_dir.join(&mod_info.id));
-            ext_ctx.submit_command(INSTALL, ChannelMessage::Success(Arc::new(mod_info)), Target::Auto).expect("Send success over async channel");
+            mod_info.external = library::contains(&mod_info.path);
+            let outcome = InstallOutcome::Installed(file_name, mod_info.id.clone(), mod_info.name.clone());
+            let _ = ext_ctx.submit_command(INSTALL, ChannelMessage::Success(Arc::new(mod_info)), Target::Auto);
+
+            outcome
           }
+        } else if let Some(mod_path) = mod_paths.get(0)
+          && let Err(err) = ModEntry::from_file(mod_path, ModMetadata::default())
+        {
+          let err = err.to_string();
+          let _ = ext_ctx.submit_command(INSTALL, ChannelMessage::Error(file_name.clone(), err.clone()), Target::Auto);
+
+          InstallOutcome::Failed(file_name, err)
         } else {
-          ext_ctx.submit_command(INSTALL, ChannelMessage::Error(file_name, "Could not find mod folder or parse mod_info file.".to_string()), Target::Auto).expect("Send error over async channel");
+          let err = String::from("Could not find mod folder or parse mod_info file.");
+          let _ = ext_ctx.submit_command(INSTALL, ChannelMessage::Error(file_name.clone(), err.clone()), Target::Auto);
+
+          InstallOutcome::Failed(file_name, err)
         }
     }
     Err(err) => {
-      ext_ctx
-        .submit_command(
-          INSTALL,
-          ChannelMessage::Error(file_name, format!("Failed to find mod, err: {}", err)),
-          Target::Auto,
-        )
-        .expect("Send error over async channel");
+      let err = format!("Failed to find mod, err: {}", err);
+      let _ = ext_ctx.submit_command(
+        INSTALL,
+        ChannelMessage::Error(file_name.clone(), err.clone()),
+        Target::Auto,
+      );
+
+      InstallOutcome::Failed(file_name, err)
     }
   }
 }
 
-pub fn decompress(path: PathBuf) -> Result<TempDir, InstallError> {
-  let source = std::fs::File::open(&path).context(Io {
-    detail: "Failed to open source archive",
+/// Extracts the archive at `path` into a temporary folder in `cache_dir`. If extraction fails
+/// part way whatever was extracted is [quarantined](quarantine::quarantine), if `progress` is
+/// cancelled the folder is removed.
+///
+/// The archive is read twice, once to check it and once to extract it, and `progress` counts
+/// each read from the start.
+pub fn decompress(
+  path: PathBuf,
+  cache_dir: &Path,
+  progress: &Progress,
+) -> Result<TempDir, InstallError> {
+  create_dir_all(cache_dir).context(Io {
+    detail: "Failed to create cache dir",
   })?;
-  let temp_dir = tempdir().context(Io {
+  let temp_dir = tempdir_in(cache_dir).context(Io {
     detail: "Failed to open a temp dir",
   })?;
-  let mime_type = infer::get_from_path(&path)
-    .context(Io {
-      detail: "Failed to open archive for archive type inference",
-    })?
-    .context(Mime {
-      detail: "Failed to get mime type",
-    })?
-    .mime_type();
-
-  match mime_type {
-    "application/vnd.rar" | "application/x-rar-compressed" => {
-      #[cfg(not(target_env = "musl"))]
-      unrar::Archive::new(path.to_string_lossy().to_string())
-        .extract_to(temp_dir.path().to_string_lossy().to_string())
-        .ok()
-        .context(Unrar {
-          detail: "Opaque Unrar error. Assume there's been an error unpacking your rar archive.",
-        })?
-        .process()
-        .ok()
-        .context(Unrar {
-          detail: "Opaque Unrar error. Assume there's been an error unpacking your rar archive.",
-        })?;
-      // trust me I tried to de-dupe this and it's buggered
-      #[cfg(target_env = "musl")]
-      compress_tools::uncompress_archive(source, temp_dir.path(), compress_tools::Ownership::Ignore)
-        .context(CompressTools {})?
-    }
-    _ => {
-      compress_tools::uncompress_archive(source, temp_dir.path(), compress_tools::Ownership::Ignore)
-        .context(CompressTools {})?
+
+  progress.set_total(path.metadata().map_or(0, |metadata| metadata.len()));
+  progress.set_done(0);
+  progress.set_stage(tr("install-stage-checking"));
+  extract::verify(&path, progress)?;
+  progress.set_done(0);
+  progress.set_stage(tr("install-stage-extracting"));
+  if let Err(err) = extract::extract(&path, temp_dir.path(), progress) {
+    if !progress.is_cancelled() {
+      let name = path.file_name().unwrap_or_default().to_string_lossy();
+      let detail = format!("{:?}", err);
+      quarantine::quarantine(temp_dir.path(), &name, "Extracting the archive", &detail);
     }
+    return Err(err);
+  }
+  if progress.is_cancelled() {
+    return Cancelled.fail();
   }
 
   Ok(temp_dir)
@@ -222,7 +411,7 @@ impl Iterator for ModSearch {
         let res: std::io::Result<()> = try {
           for entry in path.read_dir()? {
             let entry = entry?;
-            if entry.file_type()?.is_dir() {
+            if entry.file_type()?.is_dir() && !ModSearch::is_ignored(&entry.file_name()) {
               self.paths.push_back(entry.path());
             }
           }
@@ -239,6 +428,9 @@ impl Iterator for ModSearch {
 }
 
 impl ModSearch {
+  /// Folders that archivers add alongside the actual content, which never contain a real mod.
+  const IGNORED_DIRS: [&'static str; 1] = ["__MACOSX"];
+
   pub fn new(path: impl AsRef<Path>) -> Self {
     let mut paths = VecDeque::new();
     paths.push_front(path.as_ref().to_path_buf());
@@ -253,23 +445,103 @@ impl ModSearch {
   pub fn exhaustive(&mut self) -> std::io::Result<Vec<PathBuf>> {
     self.collect()
   }
+
+  fn is_ignored(name: &std::ffi::OsStr) -> bool {
+    Self::IGNORED_DIRS.iter().any(|ignored| name == *ignored)
+  }
 }
 
-impl FusedIterator for ModSearch {}
+/// If `root` contains no mods but does contain exactly one archive, returns that archive.
+fn find_nested_archive(root: &Path) -> Option<PathBuf> {
+  const ARCHIVE_EXTENSIONS: [&str; 3] = ["zip", "7z", "rar"];
+
+  if !matches!(ModSearch::new(root).first(), Ok(None)) {
+    return None;
+  }
+
+  let mut archives = Vec::new();
+  let mut paths = VecDeque::from([root.to_path_buf()]);
+  while let Some(path) = paths.pop_front() {
+    for entry in path.read_dir().ok()?.flatten() {
+      let Ok(file_type) = entry.file_type() else {
+        continue
+      };
+      if file_type.is_dir() && !ModSearch::is_ignored(&entry.file_name()) {
+        paths.push_back(entry.path());
+      } else if file_type.is_file()
+        && entry.path().extension().is_some_and(|ext| {
+          ARCHIVE_EXTENSIONS
+            .iter()
+            .any(|archive| ext.eq_ignore_ascii_case(archive))
+        })
+      {
+        archives.push(entry.path());
+      }
+    }
+  }
 
-async fn move_or_copy(from: PathBuf, to: PathBuf) {
-  // let mount_from = find_mountpoint(&from).expect("Find origin mount point");
-  // let mount_to = find_mountpoint(&to).expect("Find destination mount point");
+  if archives.len() == 1 {
+    archives.pop()
+  } else {
+    None
+  }
+}
+
+impl FusedIterator for ModSearch {}
 
+/// Moves the folder at `from` to `to`, copying it across if it can't be renamed - such as when the
+/// cache and the mods folder are on different filesystems. The copy is made next to `to` and only
+/// renamed into place once complete, so a failed copy never leaves half a mod in the mods folder -
+/// what was copied is [quarantined](quarantine::quarantine) instead.
+async fn move_or_copy(from: PathBuf, to: PathBuf) -> anyhow::Result<()> {
   if rename(from.clone(), to.clone()).await.is_err() {
-    task::spawn_blocking(move || copy_dir_recursive(&to, &from))
-      .await
-      .expect("Run blocking dir copy")
-      .expect("Copy dir to new destination");
+    let destination = to.to_string_lossy().into_owned();
+    task::spawn_blocking(move || -> anyhow::Result<()> {
+      let staging = tempdir_in(to.parent().unwrap_or(Path::new(".")))
+        .map_err(|err| anyhow::anyhow!("Failed to copy mod to {}: {}", destination, err))?;
+      let staged = staging.path().join("mod");
+      let res = copy_dir_recursive(&staged, &from).and_then(|_| std::fs::rename(&staged, &to));
+      if let Err(err) = res {
+        let name = to.file_name().unwrap_or_default().to_string_lossy();
+        let kept = quarantine::quarantine(
+          &staged,
+          &name,
+          "Copying into the mods folder",
+          &err.to_string(),
+        );
+        anyhow::bail!(
+          "Failed to copy mod to {}: {}{}",
+          destination,
+          err,
+          quarantine::kept_note(kept)
+        )
+      }
+
+      Ok(())
+    })
+    .await??;
   }
+
+  Ok(())
 }
 
-fn copy_dir_recursive(to: &Path, from: &Path) -> io::Result<()> {
+/// Moves `file` to `path`, copying it across when they're on different filesystems, where it
+/// can't simply be renamed. Like [`move_or_copy`] the copy is made next to `path` first, so
+/// `path` is either the whole file or untouched.
+pub fn persist(file: NamedTempFile, path: &Path) -> io::Result<()> {
+  match file.persist(path) {
+    Ok(_) => Ok(()),
+    Err(err) if err.error.kind() == io::ErrorKind::CrossesDevices => {
+      let mut staged = NamedTempFile::new_in(path.parent().unwrap_or(Path::new(".")))?;
+      io::copy(&mut err.file.reopen()?, &mut staged)?;
+
+      staged.persist(path).map(|_| ()).map_err(|err| err.error)
+    }
+    Err(err) => Err(err.error),
+  }
+}
+
+pub fn copy_dir_recursive(to: &Path, from: &Path) -> io::Result<()> {
   if !to.exists() {
     create_dir_all(to)?;
   }
@@ -286,82 +558,374 @@ fn copy_dir_recursive(to: &Path, from: &Path) -> io::Result<()> {
   Ok(())
 }
 
+/// How an existing mod folder is treated when a new version is installed over it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverwriteMode {
+  /// Delete the existing folder entirely.
+  Replace,
+  /// Replace the existing folder, but carry over config files users commonly edit by hand.
+  ///
+  /// Note that LunaLib keeps saved settings in `saves/common`, outside of the mod folder, so they
+  /// survive either mode.
+  Merge,
+}
+
+impl OverwriteMode {
+  /// Paths, relative to the mod folder, that are kept when merging.
+  const PRESERVED_FILES: [&'static str; 4] = [
+    "data/config/settings.json",
+    "data/config/modSettings.json",
+    "data/config/exerelin_config.json",
+    "data/config/LunaSettings.csv",
+  ];
+
+  /// Reads the files that should survive the overwrite. Any `.ini` file in the root of the mod
+  /// folder (such as GraphicsLib's `GRAPHICS_OPTIONS.ini`) is treated as user config as well.
+  fn collect_preserved(self, mod_folder: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+    if self == OverwriteMode::Replace {
+      return Vec::new();
+    }
+
+    let root_ini_files = mod_folder
+      .read_dir()
+      .into_iter()
+      .flatten()
+      .flatten()
+      .map(|entry| PathBuf::from(entry.file_name()))
+      .filter(|name| {
+        name
+          .extension()
+          .is_some_and(|ext| ext.eq_ignore_ascii_case("ini"))
+      });
+
+    Self::PRESERVED_FILES
+      .iter()
+      .map(PathBuf::from)
+      .chain(root_ini_files)
+      .filter_map(|relative| {
+        std::fs::read(mod_folder.join(&relative))
+          .ok()
+          .map(|contents| (relative, contents))
+      })
+      .collect()
+  }
+}
+
 async fn handle_delete(
   ext_ctx: ExtEventSink,
   mut entry: Arc<ModEntry>,
   new_path: HybridPath,
   old_path: PathBuf,
-) {
-  let destination = old_path.canonicalize().expect("Canonicalize destination");
-  remove_dir_all(destination).expect("Remove old mod");
-
-  let origin = new_path.get_path_copy();
-  move_or_copy(origin, old_path.clone()).await;
-  (*Arc::make_mut(&mut entry)).set_path(old_path);
-
-  ext_ctx
-    .submit_command(INSTALL, ChannelMessage::Success(entry), Target::Auto)
-    .expect("Send success over async channel");
-}
-
-async fn handle_auto(ext_ctx: ExtEventSink, entry: Arc<ModEntry>) {
-  let url = entry
-    .remote_version
-    .as_ref()
-    .unwrap()
-    .direct_download_url
-    .as_ref()
-    .unwrap();
-  let target_version = &entry.remote_version.as_ref().unwrap().version;
-  match download(url.clone(), ext_ctx.clone()).await {
+  mode: OverwriteMode,
+) -> Result<(), String> {
+  let previous = ModMetadata::parse(&old_path).await.ok();
+  let (message, res) = match replace_mod_folder(new_path.get_path_copy(), &old_path, mode).await {
+    Ok(()) => {
+      let mut_entry = Arc::make_mut(&mut entry);
+      if let Some(previous) = previous {
+        mut_entry.manager_metadata.keep_preferences(&previous);
+        if let Err(err) = mut_entry.manager_metadata.save(&old_path).await {
+          error!("Failed to save metadata for {}: {}", mut_entry.id, err)
+        }
+      }
+      mut_entry.external = library::contains(&old_path);
+      mut_entry.set_path(old_path);
+      (ChannelMessage::Success(entry), Ok(()))
+    }
+    Err(err) => {
+      let err = format!("{:#}", err);
+      (
+        ChannelMessage::Error(entry.name.clone(), err.clone()),
+        Err(err),
+      )
+    }
+  };
+
+  let _ = ext_ctx.submit_command(INSTALL, message, Target::Auto);
+
+  res
+}
+
+async fn replace_mod_folder(
+  origin: PathBuf,
+  old_path: &Path,
+  mode: OverwriteMode,
+) -> anyhow::Result<()> {
+  let preserved = mode.collect_preserved(old_path);
+
+  // keep the version being replaced, so an update that breaks something can be rolled back
+  if let Ok(replaced) = ModEntry::from_file(old_path, ModMetadata::default()) {
+    task::spawn_blocking(move || archive::keep_replaced(&replaced))
+      .await?
+      .map_err(|err| anyhow::anyhow!("Could not keep the version being replaced: {:#}", err))?;
+  }
+
+  let destination = old_path
+    .canonicalize()
+    .map_err(|err| anyhow::anyhow!("Could not find installed mod: {}", err))?;
+  if let Err(err) = remove_dir_all(&destination) {
+    // don't leave a half deleted mod behind in the mods folder
+    let name = destination
+      .file_name()
+      .unwrap_or_default()
+      .to_string_lossy();
+    let kept = quarantine::quarantine(
+      &destination,
+      &name,
+      "Removing the old version",
+      &err.to_string(),
+    );
+    anyhow::bail!(
+      "Failed to remove old version of mod: {}{}",
+      err,
+      quarantine::kept_note(kept)
+    )
+  }
+
+  move_or_copy(origin, old_path.to_path_buf()).await?;
+  for (relative, contents) in preserved {
+    let path = old_path.join(relative);
+    let res: io::Result<()> = try {
+      if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+      }
+      std::fs::write(&path, contents)?;
+    };
+    if let Err(err) = res {
+      error!("Failed to restore {}: {}", path.to_string_lossy(), err)
+    }
+  }
+
+  Ok(())
+}
+
+/// Installs every mod found at `path` without involving the GUI - used by the command line
+/// interface.
+///
+/// `installed` maps the IDs of installed mods to their folders. Mods that are already installed
+/// are skipped unless an `overwrite` mode is given.
+pub async fn install_headless(
+  path: PathBuf,
+  mods_dir: &Path,
+  cache_dir: &Path,
+  installed: &HashMap<String, PathBuf>,
+  overwrite: Option<OverwriteMode>,
+) -> Vec<InstallOutcome> {
+  let file_name = path
+    .file_name()
+    .map(|f| f.to_string_lossy().to_string())
+    .unwrap_or_else(|| String::from("unknown"));
+  let source = path.to_string_lossy().to_string();
+
+  let (_temp, root) = if path.is_file() {
+    let cache_dir = cache_dir.to_path_buf();
+    let decompress = task::spawn_blocking(move || {
+      let progress = Progress::new(progress::Unit::Bytes);
+      let temp = decompress(path, &cache_dir, &progress)?;
+      match find_nested_archive(temp.path()) {
+        Some(nested) => decompress(nested, &cache_dir, &progress),
+        None => Ok(temp),
+      }
+    })
+    .await
+    .context(Join {})
+    .and_then(|res| res);
+    match decompress {
+      Ok(temp) => {
+        let root = temp.path().to_path_buf();
+        (Some(temp), root)
+      }
+      Err(err) => return vec![InstallOutcome::Failed(file_name, err.to_string())],
+    }
+  } else {
+    (None, path)
+  };
+
+  let mod_paths = match ModSearch::new(&root).exhaustive() {
+    Ok(mod_paths) if !mod_paths.is_empty() => mod_paths,
+    Ok(_) => {
+      return vec![InstallOutcome::Failed(
+        file_name,
+        String::from("Could not find mod folder"),
+      )]
+    }
+    Err(err) => {
+      return vec![InstallOutcome::Failed(
+        file_name,
+        format!("Failed to find mod, err: {}", err),
+      )]
+    }
+  };
+
+  let mut outcomes = Vec::new();
+  for mod_path in mod_paths {
+    let mod_metadata = ModMetadata::with_source(&source);
+    let mod_info = match ModEntry::from_file(&mod_path, mod_metadata.clone()) {
+      Ok(mod_info) => mod_info,
+      Err(err) => {
+        outcomes.push(InstallOutcome::Failed(file_name.clone(), err.to_string()));
+        continue;
+      }
+    };
+    if let Err(err) = mod_metadata.save(&mod_path).await {
+      outcomes.push(InstallOutcome::Failed(
+        file_name.clone(),
+        format!("Failed to write manager metadata: {}", err),
+      ));
+      continue;
+    }
+
+    let destination = mods_dir.join(&mod_info.id);
+    let existing = installed
+      .get(&mod_info.id)
+      .cloned()
+      .or_else(|| destination.exists().then(|| destination.clone()));
+    match (existing, overwrite) {
+      (Some(_), None) => outcomes.push(InstallOutcome::Skipped(
+        file_name.clone(),
+        format!("{} is already installed", mod_info.name),
+      )),
+      (Some(existing), Some(mode)) => {
+        outcomes.push(match replace_mod_folder(mod_path, &existing, mode).await {
+          Ok(()) => InstallOutcome::Installed(file_name.clone(), mod_info.id, mod_info.name),
+          Err(err) => InstallOutcome::Failed(file_name.clone(), format!("{:#}", err)),
+        })
+      }
+      (None, _) => outcomes.push(match move_or_copy(mod_path, destination).await {
+        Ok(()) => InstallOutcome::Installed(file_name.clone(), mod_info.id, mod_info.name),
+        Err(err) => InstallOutcome::Failed(file_name.clone(), format!("{:#}", err)),
+      }),
+    }
+  }
+
+  outcomes
+}
+
+/// Updates each of `entries` in turn without asking, returning how each went. Cancelling
+/// `progress` skips the mods that haven't been started yet.
+pub async fn auto_update(
+  ext_ctx: ExtEventSink,
+  entries: Vec<Arc<ModEntry>>,
+  cache_dir: PathBuf,
+  progress: &Progress,
+) -> Vec<InstallOutcome> {
+  let mut outcomes = Vec::new();
+  for entry in entries {
+    if progress.is_cancelled() {
+      outcomes.push(InstallOutcome::Skipped(
+        entry.name.clone(),
+        String::from("Cancelled"),
+      ));
+      continue;
+    }
+    progress.set_stage(tr_args("auto-update-stage", &[("name", &entry.name)]));
+    outcomes.push(handle_auto(ext_ctx.clone(), entry, cache_dir.clone()).await);
+    progress.advance(1);
+  }
+  progress.finish();
+
+  outcomes
+}
+
+async fn handle_auto(
+  ext_ctx: ExtEventSink,
+  entry: Arc<ModEntry>,
+  cache_dir: PathBuf,
+) -> InstallOutcome {
+  let Some((url, target_version)) = entry.remote_version.as_ref().and_then(|remote| {
+    remote
+      .direct_download_url
+      .as_ref()
+      .map(|url| (url, &remote.version))
+  }) else {
+    let err = String::from("The mod has no direct download link");
+    let _ = ext_ctx.submit_command(
+      INSTALL,
+      ChannelMessage::Error(entry.id.clone(), err.clone()),
+      Target::Auto,
+    );
+
+    return InstallOutcome::Failed(entry.name.clone(), err);
+  };
+  match download(url.clone(), ext_ctx.clone(), &cache_dir).await {
     Ok(file) => {
       let path = file.path().to_path_buf();
-      let decompress = task::spawn_blocking(move || decompress(path))
-        .await
-        .expect("Run decompression");
+      let decompress = task::spawn_blocking(move || {
+        let progress = Progress::new(progress::Unit::Bytes);
+        decompress(path, &cache_dir, &progress)
+      })
+      .await
+      .context(Join {})
+      .and_then(|res| res);
       match decompress {
         Ok(temp) => {
           let temp = Arc::new(temp);
           let path = temp.path().to_owned();
           let source = url.clone();
-          let mod_metadata = ModMetadata::new();
+          let mod_metadata = ModMetadata::with_source(url);
           if let Ok(Some(path)) = task::spawn_blocking(move || ModSearch::new(path).first())
             .await
-            .expect("Run blocking search")
+            .map_err(io::Error::from)
+            .and_then(|res| res)
             .context(Io { detail: "File IO error when searching for mod" })
             && mod_metadata.save(&path).await.is_ok()
             && let Ok(mod_info) = ModEntry::from_file(&path, mod_metadata)
           {
             let hybrid = HybridPath::Temp(temp, source, Some(path));
-            if &mod_info.version_checker.as_ref().unwrap().version != target_version {
-              ext_ctx.submit_command(INSTALL, ChannelMessage::Error(mod_info.name.clone(), "Downloaded version does not match expected version".to_string()), Target::Auto).expect("Send error over async channel");
+            if mod_info.version_checker.as_ref().map(|meta| &meta.version) != Some(target_version) {
+              let err = String::from("Downloaded version does not match expected version");
+              let _ = ext_ctx.submit_command(INSTALL, ChannelMessage::Error(mod_info.name.clone(), err.clone()), Target::Auto);
+
+              InstallOutcome::Failed(url.clone(), err)
             } else {
-              handle_delete(ext_ctx, Arc::new(mod_info), hybrid, entry.path.clone()).await;
+              let (id, name) = (mod_info.id.clone(), mod_info.name.clone());
+              match handle_delete(ext_ctx, Arc::new(mod_info), hybrid, entry.path.clone(), OverwriteMode::Replace).await {
+                Ok(()) => InstallOutcome::Installed(url.clone(), id, name),
+                Err(err) => InstallOutcome::Failed(url.clone(), err),
+              }
             }
           } else {
-            ext_ctx.submit_command(INSTALL, ChannelMessage::Error(entry.id.clone(), "Some kind of unpack error".to_string()), Target::Auto).expect("Send error over async channel");
+            let err = String::from("Some kind of unpack error");
+            let _ = ext_ctx.submit_command(INSTALL, ChannelMessage::Error(entry.id.clone(), err.clone()), Target::Auto);
+
+            InstallOutcome::Failed(url.clone(), err)
           }
         }
         Err(err) => {
-          println!("{:?}", err);
-          ext_ctx
-            .submit_command(
-              INSTALL,
-              ChannelMessage::Error(entry.id.clone(), err.to_string()),
-              Target::Auto,
-            )
-            .expect("Send error over async channel");
+          error!("{:?}", err);
+          let message = match &err {
+            InstallError::Corrupt { detail } => ChannelMessage::Corrupt(
+              entry.name.clone(),
+              detail.clone(),
+              Some(Retry::Update(entry.clone())),
+            ),
+            _ => ChannelMessage::Error(entry.id.clone(), err.to_string()),
+          };
+          let _ = ext_ctx.submit_command(INSTALL, message, Target::Auto);
+
+          InstallOutcome::Failed(url.clone(), err.to_string())
         }
-      };
+      }
     }
     Err(err) => {
-      ext_ctx
-        .submit_command(
-          INSTALL,
-          ChannelMessage::Error(entry.id.clone(), err.to_string()),
-          Target::Auto,
-        )
-        .expect("Send error over async channel");
+      // the author may have moved hosts - see if the mod repo knows where the mod went
+      let alternate = if err.is_dead_link()
+        && let Some(meta) = entry.version_checker.as_ref()
+        && let Some(repo) = ModRepo::cached().await
+      {
+        repo.find_alternate_download(&meta.fractal_id, &meta.nexus_id, url)
+      } else {
+        None
+      };
+      let message = match alternate {
+        Some((source, alternate)) => {
+          ChannelMessage::DownloadFailed(entry.clone(), err.to_string(), source, alternate)
+        }
+        None => ChannelMessage::Error(entry.id.clone(), err.to_string()),
+      };
+      let _ = ext_ctx.submit_command(INSTALL, message, Target::Auto);
+
+      InstallOutcome::Failed(url.clone(), err.to_string())
     }
   }
 }
@@ -369,34 +933,63 @@ async fn handle_auto(ext_ctx: ExtEventSink, entry: Arc<ModEntry>) {
 pub async fn download(
   url: String,
   ext_ctx: ExtEventSink,
+  cache_dir: &Path,
 ) -> Result<tempfile::NamedTempFile, InstallError> {
-  static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
-
   static UPDATE_BALANCER: LoadBalancer<
     (i64, String, f64),
     Vec<(i64, String, f64)>,
     HashMap<i64, (i64, String, f64)>,
-  > = LoadBalancer::new(DOWNLOAD_PROGRESS);
+  > = LoadBalancer::new("download progress", DOWNLOAD_PROGRESS);
 
-  let mut file = tempfile::NamedTempFile::new().context(Io {
-    detail: String::from("Failed to create named temp file to write to"),
-  })?;
-  let client = reqwest::ClientBuilder::default()
+  let tx = UPDATE_BALANCER.sender(ext_ctx.clone());
+  let start = Local::now().timestamp();
+  let job = Progress::new(progress::Unit::Bytes);
+  if let Ok(mut downloads) = DOWNLOADS.lock() {
+    downloads.insert(start, job.clone());
+  }
+
+  let res = download_with_progress(
+    url,
+    cache_dir,
+    &job,
+    |name| {
+      let _ = ext_ctx.submit_command(DOWNLOAD_STARTED, (start, name.to_string()), Target::Auto);
+    },
+    |name, progress| {
+      // a full balancer just means this update is skipped, the next one will replace it anyway
+      if let Err(TrySendError::Closed(_)) = tx.try_send((start, name.to_string(), progress)) {
+        error!("Failed to send download progress, balancer closed");
+      }
+    },
+  )
+  .await;
+  if let Ok(mut downloads) = DOWNLOADS.lock() {
+    downloads.remove(&start);
+  }
+
+  res
+}
+
+static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
+
+fn download_client() -> Result<reqwest::Client, InstallError> {
+  net::client_builder()
     .redirect(reqwest::redirect::Policy::limited(200))
     .user_agent(APP_USER_AGENT)
     .build()
-    .context(Network {})?;
-
-  let mut res = client.get(&url).send().await.context(Network {})?;
+    .context(Network {})
+}
 
-  let name = res
-    .headers()
+/// The name of the file a response is for, from its `Content-Disposition` header or else the
+/// last segment of `url`.
+fn file_name(headers: &reqwest::header::HeaderMap, url: &str) -> String {
+  headers
     .get(reqwest::header::CONTENT_DISPOSITION)
     .and_then(|v| v.to_str().ok())
     .and_then(|v| v.rsplit_once("filename="))
-    .map(|(_, filename)| filename.to_string())
+    .map(|(_, filename)| filename.trim_matches(|c| c == '"' || c == ';').to_string())
     .unwrap_or_else(|| {
-      Url::parse(&url)
+      Url::parse(url)
         .ok()
         .and_then(|url| {
           url
@@ -404,33 +997,218 @@ pub async fn download(
             .and_then(|segments| segments.last())
             .map(|s| s.to_string())
         })
-        .unwrap_or(url)
-    });
+        .unwrap_or_else(|| url.to_string())
+    })
+}
 
-  let tx = UPDATE_BALANCER.sender(ext_ctx.clone());
+/// What a download link points to, shown when confirming a download from the browser.
+#[derive(Debug, Clone)]
+pub struct DownloadInfo {
+  pub uri: String,
+  pub file_name: String,
+  pub size: Option<u64>,
+}
 
-  let start = Local::now().timestamp();
-  let _ = ext_ctx.submit_command(DOWNLOAD_STARTED, (start, name.clone()), Target::Auto);
+impl DownloadInfo {
+  /// Asks for the file's name and size with a HEAD request. Servers that don't answer one leave
+  /// the size unknown and the name taken from the URL.
+  pub async fn probe(uri: String) -> Self {
+    let res = match download_client() {
+      Ok(client) => {
+        let mut request = client.head(&uri);
+        if let Some(token) = secrets::token_for(&uri) {
+          request = request.bearer_auth(token);
+        }
+        request
+          .send()
+          .await
+          .and_then(reqwest::Response::error_for_status)
+          .ok()
+      }
+      Err(_) => None,
+    };
+
+    Self {
+      file_name: res.as_ref().map_or_else(
+        || file_name(&reqwest::header::HeaderMap::new(), &uri),
+        |res| file_name(res.headers(), &uri),
+      ),
+      // a HEAD response has no body, so the length has to come from the header itself
+      size: res
+        .as_ref()
+        .and_then(|res| res.headers().get(reqwest::header::CONTENT_LENGTH))
+        .and_then(|len| len.to_str().ok()?.parse().ok())
+        .filter(|len| *len > 0),
+      uri,
+    }
+  }
+}
+
+/// Downloads `info` into `dir` without installing it, returning where it was saved.
+pub async fn download_to(
+  info: DownloadInfo,
+  ext_ctx: ExtEventSink,
+  dir: &Path,
+) -> Result<PathBuf, InstallError> {
+  let file = download(info.uri, ext_ctx, dir).await?;
+  let path = save_path(dir, &info.file_name);
+  persist(file, &path).context(Io {
+    detail: String::from("Failed to save download"),
+  })?;
+
+  Ok(path)
+}
+
+/// Where to save a download named `file_name` in `dir`. The name comes from the server, so only
+/// its last component is kept, and a number is added before the extension if it's taken.
+pub fn save_path(dir: &Path, file_name: &str) -> PathBuf {
+  let name = Path::new(file_name)
+    .file_name()
+    .map_or_else(|| PathBuf::from("download"), PathBuf::from);
+  let stem = name
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  let extension = name
+    .extension()
+    .map(|extension| format!(".{}", extension.to_string_lossy()))
+    .unwrap_or_default();
+
+  let mut path = dir.join(&name);
+  let mut count = 0;
+  while path.exists() {
+    count += 1;
+    path = dir.join(format!("{} ({}){}", stem, count, extension));
+  }
+
+  path
+}
+
+/// Downloads `url` to a temporary file in `cache_dir`. `on_start` is called with the name of
+/// the file once it is known, and `on_progress` with the fraction downloaded so far whenever the
+/// total size is known, plus once on completion. The bytes downloaded are also counted by
+/// `progress`, and cancelling it stops the download and removes the temporary file.
+pub async fn download_with_progress(
+  url: String,
+  cache_dir: &Path,
+  progress: &Progress,
+  on_start: impl FnOnce(&str),
+  mut on_progress: impl FnMut(&str, f64),
+) -> Result<tempfile::NamedTempFile, InstallError> {
+  create_dir_all(cache_dir).context(Io {
+    detail: String::from("Failed to create cache dir"),
+  })?;
+  let mut file = tempfile::NamedTempFile::new_in(cache_dir).context(Io {
+    detail: String::from("Failed to create named temp file to write to"),
+  })?;
+  let client = download_client()?;
+
+  let mut request = client.get(&url);
+  if let Some(token) = secrets::token_for(&url) {
+    request = request.bearer_auth(token);
+  }
+  let mut res = request
+    .send()
+    .await
+    .and_then(reqwest::Response::error_for_status)
+    .context(Network {})?;
+
+  let name = file_name(res.headers(), &url);
+
+  on_start(&name);
 
   let total = res.content_length();
+  progress.set_total(total.unwrap_or(0));
   let mut current_total = 0.0;
   while let Some(chunk) = res.chunk().await.context(Network {})? {
+    if progress.is_cancelled() {
+      return Cancelled.fail();
+    }
     file.write(&chunk).context(Io {
       detail: String::from("Failed to write downloaded chunk to temp file"),
     })?;
+    progress.advance(chunk.len() as u64);
     if let Some(total) = total {
       current_total += chunk.len() as f64;
-      let _ = tx.send((start, name.clone(), (current_total / total as f64)));
+      on_progress(&name, current_total / total as f64);
     }
   }
 
-  let _ = tx.send((start, name, 1.0)).inspect_err(|e| {
-    eprintln!("err: {:?}", e);
-  });
+  on_progress(&name, 1.0);
 
   Ok(file)
 }
 
+/// A download from a Mega blob, streamed in from the webview as base64 data URL chunks.
+pub struct BlobDownload {
+  pub uri: String,
+  pub started: i64,
+  pub path: PathBuf,
+  file: std::fs::File,
+  size: Option<u64>,
+  written: u64,
+}
+
+impl BlobDownload {
+  pub const NAME: &'static str = "Mega download";
+
+  pub fn new(uri: String, cache_dir: &Path) -> Result<Self, InstallError> {
+    create_dir_all(cache_dir).context(Io {
+      detail: String::from("Failed to create cache dir"),
+    })?;
+    let path = cache_dir.join(format!("{}", rand::random::<u16>()));
+    let file = std::fs::File::create(&path).context(Io {
+      detail: format!("Failed to create {} for download", path.to_string_lossy()),
+    })?;
+
+    Ok(Self {
+      uri,
+      started: Local::now().timestamp(),
+      path,
+      file,
+      size: None,
+      written: 0,
+    })
+  }
+
+  /// Sets the size of the blob as reported by the webview, used to estimate progress.
+  pub fn set_size(&mut self, size: u64) {
+    self.size = Some(size)
+  }
+
+  /// Decodes and writes a `data:<mime>;base64,<data>` chunk.
+  pub fn write_chunk(&mut self, chunk: &str) -> Result<(), InstallError> {
+    let (_, data) = chunk.split_once(',').context(Any {
+      detail: String::from("Received a malformed chunk from the webview"),
+    })?;
+    let decoded = base64::decode(data).map_err(|err| InstallError::Any {
+      detail: format!("Failed to decode chunk: {}", err),
+    })?;
+    self.file.write_all(&decoded).context(Io {
+      detail: String::from("Failed to write downloaded chunk to temp file"),
+    })?;
+    self.written += decoded.len() as u64;
+
+    Ok(())
+  }
+
+  /// The fraction downloaded so far, held just short of done until the last chunk arrives.
+  pub fn progress(&self) -> f64 {
+    self
+      .size
+      .filter(|size| *size > 0)
+      .map_or(0.0, |size| (self.written as f64 / size as f64).min(0.99))
+  }
+
+  /// Deletes whatever was written so far.
+  pub fn discard(self) {
+    drop(self.file);
+    if let Err(err) = std::fs::remove_file(&self.path) {
+      error!("Failed to remove partial download {:?}: {}", self.path, err)
+    }
+  }
+}
+
 #[derive(Debug, Clone)]
 pub enum HybridPath {
   PathBuf(PathBuf),
@@ -456,12 +1234,16 @@ pub enum InstallError {
   Mime {
     detail: String,
   },
+  #[cfg(feature = "libarchive")]
   CompressTools {
     source: compress_tools::Error,
   },
   Unrar {
     detail: String,
   },
+  Unsupported {
+    detail: String,
+  },
   Network {
     source: reqwest::Error,
   },
@@ -474,6 +1256,30 @@ pub enum InstallError {
   Any {
     detail: String,
   },
+  /// The archive failed its integrity check, usually because the download was cut short.
+  Corrupt {
+    detail: String,
+  },
+  Cancelled,
+}
+
+impl InstallError {
+  /// Whether the download failed because the file is gone or its host is unreachable, rather
+  /// than for some transient reason.
+  pub fn is_dead_link(&self) -> bool {
+    match self {
+      InstallError::Network { source } => {
+        source.is_connect()
+          || source.status().is_some_and(|status| {
+            matches!(
+              status,
+              reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE
+            )
+          })
+      }
+      _ => false,
+    }
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -484,6 +1290,61 @@ pub enum ChannelMessage {
   Duplicate(StringOrPath, HybridPath, Arc<ModEntry>),
   FoundMultiple(HybridPath, Vec<PathBuf>),
   Error(String, String),
+  /// Mod entry, error, and an alternate source for the download found in the mod repo
+  DownloadFailed(Arc<ModEntry>, String, UrlSource, String),
+  /// Name of the archive, error, and how to download it again if it was downloaded
+  Corrupt(String, String, Option<Retry>),
+}
+
+/// How to get a fresh copy of an archive that failed its integrity check.
+#[derive(Debug, Clone)]
+pub enum Retry {
+  /// Download the URL again and install it like any other download
+  Download(String),
+  /// Retry the update of the mod
+  Update(Arc<ModEntry>),
+}
+
+/// The state of a single archive or folder in the install queue.
+#[derive(Debug, Clone, PartialEq, Data)]
+pub enum InstallStatus {
+  Queued,
+  /// Checking and extracting an archive, or looking for mods in a folder, which reports nothing.
+  Extracting(Progress),
+  AwaitingDecision(String),
+  Done,
+  Failed(String),
+  Cancelled,
+}
+
+impl InstallStatus {
+  pub fn is_active(&self) -> bool {
+    matches!(self, InstallStatus::Queued | InstallStatus::Extracting(_))
+  }
+}
+
+impl std::fmt::Display for InstallStatus {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      InstallStatus::Queued => write!(f, "Queued"),
+      InstallStatus::Extracting(_) => write!(f, "Extracting"),
+      InstallStatus::AwaitingDecision(reason) => write!(f, "Awaiting decision: {}", reason),
+      InstallStatus::Done => write!(f, "Done"),
+      InstallStatus::Failed(reason) => write!(f, "Failed: {}", reason),
+      InstallStatus::Cancelled => write!(f, "Cancelled"),
+    }
+  }
+}
+
+/// The result of installing a single archive or folder, collected for the batch install report.
+#[derive(Debug, Clone)]
+pub enum InstallOutcome {
+  /// Source name, Mod ID, Mod name
+  Installed(String, String, String),
+  /// Source name, Reason
+  Skipped(String, String),
+  /// Source name, Reason
+  Failed(String, String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -506,12 +1367,20 @@ impl From<PathBuf> for StringOrPath {
 
 #[cfg(test)]
 mod test {
-  use std::{collections::HashSet, fs};
+  use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::Write,
+  };
 
   use self_update::TempDir;
   use tempfile::tempdir;
 
-  use super::ModSearch;
+  use super::{
+    extract, find_nested_archive, install_headless, save_path, BlobDownload, InstallError,
+    InstallOutcome, ModSearch,
+  };
+  use crate::app::progress::{Progress, Unit};
 
   fn create_folder_with_n_mods<const N: usize>() -> TempDir {
     let temp_dir = tempdir().expect("Create temp dir");
@@ -525,6 +1394,23 @@ mod test {
     temp_dir
   }
 
+  #[test]
+  fn save_path_stays_in_dir() {
+    let dir = tempdir().expect("Create dir");
+    assert_eq!(
+      save_path(dir.path(), "../../evil.zip"),
+      dir.path().join("evil.zip")
+    );
+    assert_eq!(save_path(dir.path(), ".."), dir.path().join("download"));
+
+    fs::write(dir.path().join("mod.zip"), "").expect("Write mod.zip");
+    fs::write(dir.path().join("mod (1).zip"), "").expect("Write mod (1).zip");
+    assert_eq!(
+      save_path(dir.path(), "mod.zip"),
+      dir.path().join("mod (2).zip")
+    );
+  }
+
   #[test]
   fn find_first_valid_mod() {
     let mods_dir = create_folder_with_n_mods::<1>();
@@ -568,4 +1454,164 @@ mod test {
     assert!(iter.next().is_none());
     assert_eq!(path_set.len(), 5)
   }
+
+  #[test]
+  fn skip_macos_metadata() {
+    let mods_dir = create_folder_with_n_mods::<1>();
+    let junk = mods_dir.path().join("__MACOSX").join("0");
+    fs::create_dir_all(&junk).expect("Create fake metadata dir");
+    fs::File::create(junk.join("mod_info.json")).expect("Create fake mod_info.json");
+
+    let found = ModSearch::new(mods_dir.path())
+      .exhaustive()
+      .expect("Search for mods");
+
+    assert_eq!(found, vec![mods_dir.path().join("0")]);
+  }
+
+  #[test]
+  fn find_single_nested_archive() {
+    let temp_dir = tempdir().expect("Create temp dir");
+    let nested = temp_dir.path().join("junk");
+    fs::create_dir(&nested).expect("Create junk dir");
+    fs::File::create(nested.join("Mod.ZIP")).expect("Create fake archive");
+
+    assert_eq!(
+      find_nested_archive(temp_dir.path()),
+      Some(nested.join("Mod.ZIP"))
+    );
+
+    fs::File::create(temp_dir.path().join("Other.7z")).expect("Create fake archive");
+
+    assert!(find_nested_archive(temp_dir.path()).is_none());
+  }
+
+  #[test]
+  fn ignore_nested_archive_beside_mod() {
+    let mods_dir = create_folder_with_n_mods::<1>();
+    fs::File::create(mods_dir.path().join("extra.zip")).expect("Create fake archive");
+
+    assert!(find_nested_archive(mods_dir.path()).is_none());
+  }
+
+  #[tokio::test]
+  async fn install_headless_skips_installed() {
+    let source = tempdir().expect("Create temp dir");
+    let mod_folder = source.path().join("Test Mod");
+    fs::create_dir(&mod_folder).expect("Create fake mod dir");
+    fs::write(
+      mod_folder.join("mod_info.json"),
+      r#"{ "id": "test_mod", "name": "Test Mod", "description": "", "version": "1.0", "gameVersion": "0.96a" }"#,
+    )
+    .expect("Write fake mod_info.json");
+    let install = tempdir().expect("Create temp dir");
+    let mods_dir = install.path().join("mods");
+    fs::create_dir(&mods_dir).expect("Create mods dir");
+
+    let outcomes = install_headless(
+      mod_folder.clone(),
+      &mods_dir,
+      install.path(),
+      &HashMap::new(),
+      None,
+    )
+    .await;
+
+    assert!(matches!(&outcomes[..], [InstallOutcome::Installed(_, id, _)] if id == "test_mod"));
+    assert!(mods_dir.join("test_mod").join("mod_info.json").is_file());
+
+    let installed = HashMap::from([(String::from("test_mod"), mods_dir.join("test_mod"))]);
+    let outcomes = install_headless(
+      mods_dir.join("test_mod"),
+      &mods_dir,
+      install.path(),
+      &installed,
+      None,
+    )
+    .await;
+
+    assert!(matches!(&outcomes[..], [InstallOutcome::Skipped(..)]));
+  }
+
+  #[test]
+  fn verify_catches_corrupted_zip() {
+    let dir = tempdir().expect("Create temp dir");
+    let path = dir.path().join("mod.zip");
+    let mut zip = zip::ZipWriter::new(fs::File::create(&path).expect("Create zip"));
+    let options =
+      zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    zip
+      .start_file("mod/mod_info.json", options)
+      .expect("Start file");
+    zip
+      .write_all(b"{\"id\": \"some_mod\"}")
+      .expect("Write file");
+    zip.finish().expect("Finish zip");
+
+    let progress = Progress::new(Unit::Bytes);
+    extract::verify(&path, &progress).expect("Intact zip passes");
+
+    let mut bytes = fs::read(&path).expect("Read zip");
+    let offset = bytes
+      .windows(9)
+      .position(|window| window == b"some_mod\"")
+      .expect("Find file contents");
+    bytes[offset] = b'S';
+    fs::write(&path, bytes).expect("Write corrupted zip");
+
+    assert!(matches!(
+      extract::verify(&path, &progress),
+      Err(InstallError::Corrupt { .. })
+    ));
+  }
+
+  #[test]
+  #[cfg(any(feature = "libarchive", feature = "pure-rust-extraction"))]
+  fn verify_catches_truncated_7z() {
+    let dir = tempdir().expect("Create temp dir");
+    let path = dir.path().join("mod.7z");
+    let bytes = include_bytes!("../../tests/assets/mod.7z");
+    fs::write(&path, bytes).expect("Write 7z");
+
+    let progress = Progress::new(Unit::Bytes);
+    extract::verify(&path, &progress).expect("Intact 7z passes");
+
+    // a download cut short loses the header at the end of the archive
+    fs::write(&path, &bytes[..bytes.len() / 2]).expect("Write truncated 7z");
+    assert!(matches!(
+      extract::verify(&path, &progress),
+      Err(InstallError::Corrupt { .. })
+    ));
+  }
+
+  #[test]
+  fn blob_download_decodes_chunks() {
+    let cache = tempdir().expect("Create temp dir");
+    let mut download = BlobDownload::new(String::from("blob:https://mega.nz/test"), cache.path())
+      .expect("Create download");
+    download.set_size(10);
+
+    download
+      .write_chunk("data:application/octet-stream;base64,aGVsbG8=")
+      .expect("Write chunk");
+    assert_eq!(download.progress(), 0.5);
+
+    assert!(download
+      .write_chunk("data:application/octet-stream;base64,!!!")
+      .is_err());
+    assert!(download.write_chunk("not a data url").is_err());
+
+    download
+      .write_chunk("data:application/octet-stream;base64,d29ybGQ=")
+      .expect("Write chunk");
+    assert_eq!(download.progress(), 0.99);
+    assert_eq!(
+      fs::read(&download.path).expect("Read download"),
+      b"helloworld"
+    );
+
+    let path = download.path.clone();
+    download.discard();
+    assert!(!path.exists());
+  }
 }
@@ -0,0 +1,321 @@
+use std::{
+  fs::File,
+  io::{self, BufReader, Read, Seek, SeekFrom},
+  path::Path,
+};
+
+use flate2::read::GzDecoder;
+use snafu::{OptionExt, ResultExt};
+use tracing::warn;
+
+use super::{Cancelled, InstallError, Io, Mime};
+use crate::app::progress::Progress;
+
+fn mime_type(path: &Path) -> Result<&'static str, InstallError> {
+  Ok(
+    infer::get_from_path(path)
+      .context(Io {
+        detail: "Failed to open archive for archive type inference",
+      })?
+      .context(Mime {
+        detail: "Failed to get mime type",
+      })?
+      .mime_type(),
+  )
+}
+
+/// Reads every file in the archive at `path` without writing anything out, so a truncated or
+/// corrupted download is caught before extraction starts rather than part way through it. How
+/// much of the archive has been read is reported to `progress`, except for rar archives, which
+/// unrar reads itself, and 7z archives checked by the `pure-rust-extraction` feature, which reads
+/// them by entry rather than as a stream. Otherwise 7z archives are read through libarchive.
+///
+/// Formats that can't be checked without extracting them are assumed to be fine.
+pub fn verify(path: &Path, progress: &Progress) -> Result<(), InstallError> {
+  let mime_type = mime_type(path)?;
+  let open = || {
+    File::open(path)
+      .map(|file| BufReader::new(Cancellable::new(file, progress).counting()))
+      .context(Io {
+        detail: "Failed to open archive to check it",
+      })
+  };
+
+  match mime_type {
+    #[cfg(not(target_env = "musl"))]
+    "application/vnd.rar" | "application/x-rar-compressed" => verify_rar(path),
+    #[cfg(feature = "pure-rust-extraction")]
+    "application/x-7z-compressed" => pure::verify_7z(path, progress),
+    #[cfg(all(feature = "libarchive", not(feature = "pure-rust-extraction")))]
+    "application/x-7z-compressed" => verify_libarchive(path, progress),
+    "application/zip" => {
+      let mut zip = zip::ZipArchive::new(open()?).map_err(corrupt)?;
+      for i in 0..zip.len() {
+        if progress.is_cancelled() {
+          return Cancelled.fail();
+        }
+        // the checksum of each file is checked once it's been read to the end
+        io::copy(&mut zip.by_index(i).map_err(corrupt)?, &mut io::sink()).map_err(corrupt)?;
+      }
+      Ok(())
+    }
+    "application/gzip" => verify_tar(tar::Archive::new(GzDecoder::new(open()?)), progress),
+    "application/x-tar" => verify_tar(tar::Archive::new(open()?), progress),
+    _ => Ok(()),
+  }
+}
+
+fn corrupt(err: impl std::fmt::Display) -> InstallError {
+  InstallError::Corrupt {
+    detail: err.to_string(),
+  }
+}
+
+fn verify_tar<R: Read>(
+  mut archive: tar::Archive<R>,
+  progress: &Progress,
+) -> Result<(), InstallError> {
+  for entry in archive.entries().map_err(corrupt)? {
+    if progress.is_cancelled() {
+      return Cancelled.fail();
+    }
+    io::copy(&mut entry.map_err(corrupt)?, &mut io::sink()).map_err(corrupt)?;
+  }
+
+  Ok(())
+}
+
+#[cfg(not(target_env = "musl"))]
+fn verify_rar(path: &Path) -> Result<(), InstallError> {
+  unrar::Archive::new(path.to_string_lossy().to_string())
+    .test()
+    .ok()
+    .context(super::Corrupt {
+      detail: "Could not open the rar archive to test it",
+    })?
+    .process()
+    .ok()
+    .context(super::Corrupt {
+      detail: "The rar archive failed its integrity test",
+    })?;
+
+  Ok(())
+}
+
+/// Reads every entry of an archive libarchive can open, throwing the contents away.
+#[cfg(all(feature = "libarchive", not(feature = "pure-rust-extraction")))]
+fn verify_libarchive(path: &Path, progress: &Progress) -> Result<(), InstallError> {
+  let source = File::open(path).context(Io {
+    detail: "Failed to open archive to check it",
+  })?;
+
+  let contents =
+    compress_tools::ArchiveIterator::from_read(Cancellable::new(source, progress).counting())
+      .map_err(corrupt)?;
+  for content in contents {
+    if progress.is_cancelled() {
+      return Cancelled.fail();
+    }
+    if let compress_tools::ArchiveContents::Err(err) = content {
+      return Err(corrupt(err));
+    }
+  }
+
+  Ok(())
+}
+
+/// Extracts the archive at `path` into `dest`, stopping at the next read from the archive once
+/// `progress` is cancelled. Like [`verify`], how much has been read is reported to `progress`.
+///
+/// With the `pure-rust-extraction` feature enabled zip, 7z, rar and tarballs are handled without
+/// linking against libarchive - if that fails, or the format isn't one of the above, extraction
+/// falls back to `compress_tools` when the `libarchive` feature is enabled.
+pub fn extract(path: &Path, dest: &Path, progress: &Progress) -> Result<(), InstallError> {
+  let mime_type = mime_type(path)?;
+
+  match mime_type {
+    #[cfg(not(target_env = "musl"))]
+    "application/vnd.rar" | "application/x-rar-compressed" => extract_rar(path, dest),
+    #[cfg(feature = "pure-rust-extraction")]
+    "application/zip"
+    | "application/x-7z-compressed"
+    | "application/gzip"
+    | "application/x-tar" => {
+      pure::extract(path, dest, mime_type, progress).or_else(|err| {
+        if cfg!(feature = "libarchive") && !progress.is_cancelled() {
+          warn!(
+            "Pure Rust extraction failed, falling back to libarchive: {}",
+            err
+          );
+          // start the count over, rather than adding to what the failed attempt read
+          progress.set_done(0);
+          libarchive(path, dest, progress)
+        } else {
+          Err(err)
+        }
+      })
+    }
+    _ => libarchive(path, dest, progress),
+  }
+}
+
+/// Fails reads once `progress` is cancelled, so extraction libraries that read from a stream stop
+/// part way through. Can also count what's read into `progress`, when that's the archive itself
+/// rather than what's unpacked from it.
+struct Cancellable<R> {
+  inner: R,
+  progress: Progress,
+  counting: bool,
+}
+
+impl<R> Cancellable<R> {
+  fn new(inner: R, progress: &Progress) -> Self {
+    Self {
+      inner,
+      progress: progress.clone(),
+      counting: false,
+    }
+  }
+
+  fn counting(mut self) -> Self {
+    self.counting = true;
+    self
+  }
+}
+
+impl<R: Read> Read for Cancellable<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    if self.progress.is_cancelled() {
+      return Err(io::Error::new(io::ErrorKind::Other, "Extraction cancelled"));
+    }
+
+    let read = self.inner.read(buf)?;
+    if self.counting {
+      self.progress.advance(read as u64)
+    }
+
+    Ok(read)
+  }
+}
+
+impl<R: Seek> Seek for Cancellable<R> {
+  fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+    self.inner.seek(pos)
+  }
+}
+
+#[cfg(not(target_env = "musl"))]
+fn extract_rar(path: &Path, dest: &Path) -> Result<(), InstallError> {
+  unrar::Archive::new(path.to_string_lossy().to_string())
+    .extract_to(dest.to_string_lossy().to_string())
+    .ok()
+    .context(super::Unrar {
+      detail: "Opaque Unrar error. Assume there's been an error unpacking your rar archive.",
+    })?
+    .process()
+    .ok()
+    .context(super::Unrar {
+      detail: "Opaque Unrar error. Assume there's been an error unpacking your rar archive.",
+    })?;
+
+  Ok(())
+}
+
+#[cfg(feature = "libarchive")]
+fn libarchive(path: &Path, dest: &Path, progress: &Progress) -> Result<(), InstallError> {
+  let source = File::open(path).context(Io {
+    detail: "Failed to open source archive",
+  })?;
+
+  compress_tools::uncompress_archive(
+    Cancellable::new(source, progress).counting(),
+    dest,
+    compress_tools::Ownership::Ignore,
+  )
+  .context(super::CompressTools {})
+}
+
+#[cfg(not(feature = "libarchive"))]
+fn libarchive(path: &Path, _dest: &Path, _progress: &Progress) -> Result<(), InstallError> {
+  super::Unsupported {
+    detail: format!(
+      "Unsupported archive format: {}",
+      path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_default()
+    ),
+  }
+  .fail()
+}
+
+#[cfg(feature = "pure-rust-extraction")]
+mod pure {
+  use std::{fs::File, io, io::BufReader, path::Path};
+
+  use flate2::read::GzDecoder;
+  use snafu::ResultExt;
+
+  use super::{
+    super::{InstallError, Io, Unsupported},
+    corrupt, Cancellable, Progress,
+  };
+
+  pub fn verify_7z(path: &Path, progress: &Progress) -> Result<(), InstallError> {
+    sevenz_rust::SevenZReader::open(path, sevenz_rust::Password::empty())
+      .map_err(corrupt)?
+      .for_each_entries(|_, reader| {
+        io::copy(&mut Cancellable::new(reader, progress), &mut io::sink())?;
+        Ok(true)
+      })
+      .map_err(|err| {
+        if progress.is_cancelled() {
+          InstallError::Cancelled
+        } else {
+          corrupt(err)
+        }
+      })
+  }
+
+  pub fn extract(
+    path: &Path,
+    dest: &Path,
+    mime_type: &str,
+    progress: &Progress,
+  ) -> Result<(), InstallError> {
+    let source = Cancellable::new(
+      File::open(path).context(Io {
+        detail: "Failed to open source archive",
+      })?,
+      progress,
+    )
+    .counting();
+
+    match mime_type {
+      "application/zip" => zip::ZipArchive::new(BufReader::new(source))
+        .and_then(|mut zip| zip.extract(dest))
+        .map_err(|err| InstallError::Any {
+          detail: format!("Failed to unpack zip: {}", err),
+        }),
+      "application/x-7z-compressed" => {
+        sevenz_rust::decompress(source, dest).map_err(|err| InstallError::Any {
+          detail: format!("Failed to unpack 7z: {}", err),
+        })
+      }
+      "application/gzip" => tar::Archive::new(GzDecoder::new(BufReader::new(source)))
+        .unpack(dest)
+        .context(Io {
+          detail: "Failed to unpack tarball",
+        }),
+      "application/x-tar" => tar::Archive::new(BufReader::new(source))
+        .unpack(dest)
+        .context(Io {
+          detail: "Failed to unpack tarball",
+        }),
+      _ => Unsupported {
+        detail: format!("Unsupported archive format: {}", mime_type),
+      }
+      .fail(),
+    }
+  }
+}
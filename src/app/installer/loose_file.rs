@@ -0,0 +1,182 @@
+//! Small mods and patches distributed as a bare `.jar` or `.json` rather than an archive. These
+//! can't be installed as a mod of their own, so when turned on in the settings, downloading one
+//! offers to place it into an installed mod's folder instead. Where it will go is shown for the
+//! user to confirm first, and any file it replaces is kept next to it with a `.bak` extension.
+
+use std::{
+  ffi::OsString,
+  fs::rename,
+  path::{Path, PathBuf},
+};
+
+use druid::{ExtEventSink, Selector};
+use snafu::ResultExt;
+use tracing::error;
+
+use super::{download, persist, InstallError, Io};
+
+const EXTENSIONS: [&str; 2] = ["jar", "json"];
+/// How deep into a mod folder a file of the same name is looked for.
+const MAX_DEPTH: usize = 4;
+
+/// Download URI, file name and the folder of the mod to place the file in. Asks the user to
+/// confirm the file's [`destination`] before anything is downloaded.
+pub const PLACE: Selector<(String, String, PathBuf)> = Selector::new("installer.loose_file.place");
+/// Download URI, file name and the confirmed destination.
+pub const PLACE_CONFIRMED: Selector<(String, String, PathBuf)> =
+  Selector::new("installer.loose_file.place.confirmed");
+
+pub fn is_loose_file(file_name: &str) -> bool {
+  Path::new(file_name)
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| {
+      EXTENSIONS
+        .iter()
+        .any(|loose| ext.eq_ignore_ascii_case(loose))
+    })
+}
+
+/// Where `file_name` goes in `mod_dir`: over a file of the same name if the mod already has one,
+/// which is the usual case for an updated jar, otherwise in the mod's `jars` folder for a jar if
+/// it has one, or else the top of the mod folder.
+pub fn destination(mod_dir: &Path, file_name: &str) -> PathBuf {
+  // the name can come from a server's headers, so only its last component is trusted
+  let file_name = Path::new(file_name)
+    .file_name()
+    .map_or_else(|| file_name.into(), |name| name.to_os_string());
+
+  if let Some(existing) = find_named(mod_dir, &file_name, MAX_DEPTH) {
+    return existing;
+  }
+  let jars = mod_dir.join("jars");
+  if Path::new(&file_name)
+    .extension()
+    .is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
+    && jars.is_dir()
+  {
+    jars.join(file_name)
+  } else {
+    mod_dir.join(file_name)
+  }
+}
+
+fn find_named(dir: &Path, name: &std::ffi::OsStr, depth: usize) -> Option<PathBuf> {
+  let mut dirs = Vec::new();
+  for path in dir.read_dir().ok()?.flatten().map(|entry| entry.path()) {
+    if path.is_dir() {
+      dirs.push(path)
+    } else if path
+      .file_name()
+      .is_some_and(|file| file.eq_ignore_ascii_case(name))
+    {
+      return Some(path);
+    }
+  }
+  dirs.sort();
+
+  (depth > 0)
+    .then(|| dirs.iter().find_map(|dir| find_named(dir, name, depth - 1)))
+    .flatten()
+}
+
+/// Where a file at `path` is kept when it's replaced: next to it with `.bak` on the end, numbered
+/// if there's already a backup.
+pub fn backup_path(path: &Path) -> PathBuf {
+  let name = path.file_name().unwrap_or_default();
+  let with_suffix = |suffix: String| {
+    let mut name = OsString::from(name);
+    name.push(suffix);
+    path.with_file_name(name)
+  };
+
+  let mut backup = with_suffix(String::from(".bak"));
+  for i in 2.. {
+    if !backup.exists() {
+      break;
+    }
+    backup = with_suffix(format!(".bak{}", i));
+  }
+
+  backup
+}
+
+/// Downloads `uri` and saves it to `path`, moving any file already there to its [`backup_path`].
+/// Returns where the replaced file was kept, if there was one.
+pub async fn place(
+  uri: String,
+  path: &Path,
+  ext_ctx: ExtEventSink,
+  cache_dir: &Path,
+) -> Result<Option<PathBuf>, InstallError> {
+  let file = download(uri, ext_ctx, cache_dir).await?;
+  let detail = || format!("Failed to place {}", path.display());
+
+  let backup = if path.exists() {
+    let backup = backup_path(path);
+    rename(path, &backup).context(Io { detail: detail() })?;
+    Some(backup)
+  } else {
+    None
+  };
+  if let Err(err) = persist(file, path) {
+    // put back what was there rather than leave the mod without it
+    if let Some(backup) = &backup
+      && let Err(err) = rename(backup, path)
+    {
+      error!("Failed to restore {:?} from its backup: {}", path, err)
+    }
+    return Err(err).context(Io { detail: detail() });
+  }
+
+  Ok(backup)
+}
+
+#[cfg(test)]
+mod test {
+  use std::fs;
+
+  use super::{backup_path, destination, is_loose_file};
+
+  #[test]
+  fn recognises_loose_files() {
+    assert!(is_loose_file("SomeMod.jar"));
+    assert!(is_loose_file("settings.JSON"));
+    assert!(!is_loose_file("SomeMod.zip"));
+    assert!(!is_loose_file("jar"));
+  }
+
+  #[test]
+  fn places_over_existing_files_then_in_jars() {
+    let mod_dir = tempfile::tempdir().unwrap();
+    let mod_dir = mod_dir.path();
+    fs::create_dir_all(mod_dir.join("data/config")).unwrap();
+    fs::write(mod_dir.join("data/config/settings.json"), "").unwrap();
+
+    assert_eq!(
+      destination(mod_dir, "settings.json"),
+      mod_dir.join("data/config/settings.json")
+    );
+    assert_eq!(destination(mod_dir, "Mod.jar"), mod_dir.join("Mod.jar"));
+
+    fs::create_dir(mod_dir.join("jars")).unwrap();
+    assert_eq!(
+      destination(mod_dir, "Mod.jar"),
+      mod_dir.join("jars/Mod.jar")
+    );
+    assert_eq!(
+      destination(mod_dir, "../../Mod.jar"),
+      mod_dir.join("jars/Mod.jar")
+    );
+  }
+
+  #[test]
+  fn backups_are_numbered() {
+    let mod_dir = tempfile::tempdir().unwrap();
+    let jar = mod_dir.path().join("Mod.jar");
+
+    assert_eq!(backup_path(&jar), mod_dir.path().join("Mod.jar.bak"));
+    fs::write(mod_dir.path().join("Mod.jar.bak"), "").unwrap();
+    assert_eq!(backup_path(&jar), mod_dir.path().join("Mod.jar.bak2"));
+  }
+}
@@ -0,0 +1,225 @@
+//! What's left of an install that failed part way, be it a half extracted archive, a half copied
+//! mod folder or the remains of an old version that couldn't be fully removed. Rather than being
+//! deleted, or worse left in the mods folder where it would show up as a broken mod, it's moved
+//! into `failed_installs` in the data dir along with a note of what went wrong, so the failure
+//! can be looked into.
+//!
+//! This isn't kept under the cache dir, which would be the natural home for it, because the cache
+//! dir is deleted whenever MOSS quits - the kept files would be gone before anyone had the chance
+//! to look at them.
+
+use std::{
+  fs::{create_dir_all, read_dir, rename},
+  io,
+  path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use remove_dir_all::remove_dir_all;
+use tracing::{error, warn};
+use webview_shared::PROJECT;
+
+use super::copy_dir_recursive;
+
+const DIR: &str = "failed_installs";
+/// How many failed installs are kept, older ones are deleted as new ones are added.
+const KEPT: usize = 10;
+const DIAGNOSTIC: &str = "diagnostic.txt";
+
+/// Where failed installs are kept. In the data dir rather than the cache dir, as the cache dir
+/// doesn't outlive the session.
+pub fn failed_installs_dir() -> PathBuf {
+  PROJECT.data_dir().join(DIR)
+}
+
+/// Moves `partial` into its own folder under [`failed_installs_dir`], next to a file describing
+/// which `stage` of installing `name` failed and why. `partial` is removed even if it can't be
+/// kept.
+///
+/// Returns where the files were kept, or `None` if there weren't any or they couldn't be moved.
+pub fn quarantine(partial: &Path, name: &str, stage: &str, err: &str) -> Option<PathBuf> {
+  quarantine_in(&failed_installs_dir(), partial, name, stage, err)
+}
+
+fn quarantine_in(
+  dir: &Path,
+  partial: &Path,
+  name: &str,
+  stage: &str,
+  err: &str,
+) -> Option<PathBuf> {
+  let files = count_files(partial);
+  if files == 0 {
+    if partial.exists()
+      && let Err(err) = remove_dir_all(partial)
+    {
+      error!("Failed to remove empty partial install at {:?}: {}", partial, err)
+    }
+    return None;
+  }
+
+  prune(dir);
+  let stamp = Local::now().format("%Y%m%d-%H%M%S%.6f");
+  let mut target = dir.join(format!("{}_{}", stamp, sanitise(name)));
+  for i in 2.. {
+    if !target.exists() {
+      break;
+    }
+    target = dir.join(format!("{}_{}_{}", stamp, sanitise(name), i));
+  }
+  let kept_in = target.join("files");
+  let moved: io::Result<()> = try {
+    create_dir_all(&target)?;
+    if rename(partial, &kept_in).is_err() {
+      // the data dir may be on another drive
+      copy_dir_recursive(&kept_in, partial)?;
+    }
+  };
+
+  let diagnostic = format!(
+    "MOSS {} failed to install {}\nTime: {}\nStage: {}\nError: {}\nFiles left behind: {}\n{}",
+    env!("CARGO_PKG_VERSION"),
+    name,
+    Local::now().to_rfc3339(),
+    stage,
+    err,
+    files,
+    match &moved {
+      Ok(()) => String::from("They have been kept in the files folder next to this one.\n"),
+      Err(err) => format!("They could not be kept: {}\n", err),
+    }
+  );
+  if let Err(err) =
+    create_dir_all(&target).and_then(|_| std::fs::write(target.join(DIAGNOSTIC), diagnostic))
+  {
+    warn!(
+      "Failed to write diagnostic for failed install of {}: {}",
+      name, err
+    )
+  }
+
+  if partial.exists()
+    && let Err(err) = remove_dir_all(partial)
+  {
+    error!("Failed to remove partial install at {:?}: {}", partial, err)
+  }
+
+  match moved {
+    Ok(()) => Some(target),
+    Err(err) => {
+      warn!("Failed to keep partial install of {}: {}", name, err);
+      None
+    }
+  }
+}
+
+/// A note to add to an error message saying where the partial install went, if anywhere.
+pub fn kept_note(kept: Option<PathBuf>) -> String {
+  kept
+    .map(|path| {
+      format!(
+        ". What was installed has been moved to {}",
+        path.to_string_lossy()
+      )
+    })
+    .unwrap_or_default()
+}
+
+fn count_files(path: &Path) -> usize {
+  read_dir(path)
+    .into_iter()
+    .flatten()
+    .flatten()
+    .map(|entry| {
+      if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+        count_files(&entry.path())
+      } else {
+        1
+      }
+    })
+    .sum()
+}
+
+/// Deletes the oldest failed installs, making room for one more.
+fn prune(dir: &Path) {
+  let mut kept: Vec<PathBuf> = read_dir(dir)
+    .into_iter()
+    .flatten()
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| path.is_dir())
+    .collect();
+  // folder names start with when the install failed, so sort oldest first
+  kept.sort();
+
+  let excess = kept.len().saturating_sub(KEPT - 1);
+  for path in kept.into_iter().take(excess) {
+    if let Err(err) = remove_dir_all(&path) {
+      warn!("Failed to remove old failed install at {:?}: {}", path, err)
+    }
+  }
+}
+
+fn sanitise(name: &str) -> String {
+  name
+    .chars()
+    .map(|c| {
+      if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use std::fs;
+
+  use tempfile::tempdir;
+
+  use super::{quarantine_in, DIAGNOSTIC, KEPT};
+
+  #[test]
+  fn moves_partial_installs_and_prunes_old_ones() {
+    let failed = tempdir().expect("Create failed installs dir");
+    let mods = tempdir().expect("Create mods dir");
+    let partial = mods.path().join("half_mod");
+    fs::create_dir_all(partial.join("data")).expect("Create partial mod");
+    fs::write(partial.join("mod_info.json"), "{").expect("Write partial mod_info.json");
+
+    let kept = quarantine_in(
+      failed.path(),
+      &partial,
+      "Half Mod.zip",
+      "copying",
+      "Disk full",
+    )
+    .expect("Keep partial install");
+    assert!(!partial.exists());
+    assert!(kept.join("files/mod_info.json").is_file());
+    let diagnostic = fs::read_to_string(kept.join(DIAGNOSTIC)).expect("Read diagnostic");
+    assert!(diagnostic.contains("Half Mod.zip") && diagnostic.contains("Disk full"));
+
+    // empty folders aren't worth keeping
+    fs::create_dir(&partial).expect("Create empty partial mod");
+    assert!(quarantine_in(failed.path(), &partial, "Empty", "extracting", "").is_none());
+    assert!(!partial.exists());
+
+    for i in 0..KEPT {
+      fs::create_dir(&partial).expect("Create partial mod");
+      fs::write(partial.join("mod_info.json"), "{").expect("Write partial mod_info.json");
+      quarantine_in(failed.path(), &partial, &i.to_string(), "copying", "").expect("Keep");
+    }
+    assert_eq!(
+      failed
+        .path()
+        .read_dir()
+        .expect("Read failed installs")
+        .count(),
+      KEPT
+    );
+    assert!(!kept.exists());
+  }
+}
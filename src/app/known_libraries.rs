@@ -0,0 +1,192 @@
+//! The library mods most others are built on. When an installed mod depends on one that isn't
+//! installed, MOSS offers to install it in one click, downloading the release named in the
+//! library's own version file and installing it like any other download.
+//!
+//! If the version file can't be fetched or has no direct download, the library's forum thread is
+//! opened instead so it can be downloaded by hand.
+
+use std::{
+  collections::HashSet,
+  sync::{Arc, LazyLock, Mutex},
+};
+
+use druid::{ExtEventSink, Selector, Target};
+use tracing::warn;
+use webview_shared::{InstallType, WEBVIEW_INSTALL};
+
+use super::{
+  i18n::{tr, tr_args},
+  mod_description::{ModDescription, OPEN_IN_BROWSER},
+  mod_entry::{ModEntry, ModVersionMeta},
+  net,
+  toast::Toast,
+};
+
+pub const INSTALL: Selector<&'static KnownLibrary> = Selector::new("known_libraries.install");
+
+#[derive(Debug)]
+pub struct KnownLibrary {
+  /// The library's mod ID, as other mods list it in their dependencies.
+  pub id: &'static str,
+  pub name: &'static str,
+  version_file: &'static str,
+  forum_topic: &'static str,
+}
+
+pub static LIBRARIES: [KnownLibrary; 3] = [
+  KnownLibrary {
+    id: "lw_lazylib",
+    name: "LazyLib",
+    version_file: "https://raw.githubusercontent.com/LazyWizard/lazylib/master/lazylib.version",
+    forum_topic: "5444",
+  },
+  KnownLibrary {
+    id: "MagicLib",
+    name: "MagicLib",
+    version_file:
+      "https://raw.githubusercontent.com/MagicLibStarsector/MagicLib/master/magiclib.version",
+    forum_topic: "25868",
+  },
+  KnownLibrary {
+    id: "shaderLib",
+    name: "GraphicsLib",
+    version_file:
+      "https://raw.githubusercontent.com/DarkRevenant/GraphicsLib/master/GraphicsLib.version",
+    forum_topic: "10982",
+  },
+];
+
+/// Libraries already offered this session, so closing the offer doesn't bring it straight back.
+static OFFERED: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Default::default);
+
+impl KnownLibrary {
+  fn forum_url(&self) -> String {
+    format!("{}{}", ModDescription::FRACTAL_URL, self.forum_topic)
+  }
+
+  /// Where the latest release can be downloaded from, according to the library's version file.
+  async fn download_url(&self) -> Result<String, String> {
+    let text = net::api_client()?
+      .get(self.version_file)
+      .send()
+      .await
+      .and_then(|res| res.error_for_status())
+      .map_err(|err| err.to_string())?
+      .text()
+      .await
+      .map_err(|err| err.to_string())?;
+
+    ModVersionMeta::parse(&text)?
+      .direct_download_url
+      .filter(|url| !url.trim().is_empty())
+      .ok_or_else(|| String::from("The version file has no direct download"))
+  }
+
+  /// Downloads and installs the library, or opens its forum thread if that isn't possible.
+  pub async fn install(&'static self, ext_ctx: ExtEventSink) {
+    let _ = match self.download_url().await {
+      Ok(url) => ext_ctx.submit_command(WEBVIEW_INSTALL, InstallType::Uri(url), Target::Auto),
+      Err(err) => {
+        warn!("No download found for {}: {}", self.name, err);
+        let _ = ext_ctx.submit_command(
+          Toast::SHOW,
+          Toast::new(
+            tr("library-install-failed-title"),
+            tr_args("library-install-failed", &[("library", &self.name)]),
+          ),
+          Target::Auto,
+        );
+        ext_ctx.submit_command(OPEN_IN_BROWSER, self.forum_url(), Target::Auto)
+      }
+    };
+  }
+}
+
+/// Known libraries that some of `mods` depend on but aren't installed, with the names of the mods
+/// that need each.
+pub fn missing<'a>(
+  mods: impl Iterator<Item = &'a Arc<ModEntry>>,
+) -> Vec<(&'static KnownLibrary, Vec<String>)> {
+  let mods: Vec<&Arc<ModEntry>> = mods.collect();
+  let installed = |id: &str| mods.iter().any(|entry| entry.id.eq_ignore_ascii_case(id));
+
+  LIBRARIES
+    .iter()
+    .filter(|library| !installed(library.id))
+    .filter_map(|library| {
+      let mut needed_by: Vec<String> = mods
+        .iter()
+        .filter(|entry| {
+          entry
+            .dependencies
+            .iter()
+            .any(|dependency| dependency.id.eq_ignore_ascii_case(library.id))
+        })
+        .map(|entry| entry.name.clone())
+        .collect();
+      needed_by.sort();
+
+      (!needed_by.is_empty()).then_some((library, needed_by))
+    })
+    .collect()
+}
+
+/// An offer to install each missing library that hasn't been offered yet this session.
+pub fn offers<'a>(mods: impl Iterator<Item = &'a Arc<ModEntry>>) -> Vec<Toast> {
+  let mut offered = OFFERED.lock().unwrap();
+
+  missing(mods)
+    .into_iter()
+    .filter(|(library, _)| offered.insert(library.id))
+    .map(|(library, needed_by)| {
+      Toast::new(
+        tr_args("library-missing-title", &[("library", &library.name)]),
+        tr_args(
+          "library-missing",
+          &[("library", &library.name), ("mods", &needed_by.join(", "))],
+        ),
+      )
+      .with_action(INSTALL.with(library))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use std::sync::Arc;
+
+  use super::missing;
+  use crate::app::mod_entry::{Dependency, ModEntry};
+
+  fn entry(id: &str, dependencies: &[&str]) -> Arc<ModEntry> {
+    Arc::new(ModEntry {
+      id: id.to_string(),
+      name: id.to_string(),
+      dependencies: dependencies
+        .iter()
+        .map(|id| Dependency {
+          id: id.to_string(),
+          name: String::new(),
+        })
+        .collect(),
+      ..Default::default()
+    })
+  }
+
+  #[test]
+  fn finds_missing_libraries() {
+    let mods = [
+      entry("ships", &["lw_lazylib", "magiclib"]),
+      entry("weapons", &["MagicLib", "some_other_lib"]),
+      entry("lw_lazylib", &[]),
+    ];
+
+    let missing = missing(mods.iter());
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].0.name, "MagicLib");
+    assert_eq!(
+      missing[0].1,
+      vec![String::from("ships"), String::from("weapons")]
+    );
+  }
+}
@@ -0,0 +1,188 @@
+//! Checks run over the enabled mods before launching the game.
+//!
+//! Nothing here stops the game from being launched, problems are shown so the user can decide
+//! whether to go ahead anyway.
+
+use std::{collections::HashMap, sync::Arc};
+
+use super::{
+  compat_db::{CompatDb, Warning},
+  i18n::tr_args,
+  mod_entry::{GameVersion, ModEntry},
+  ram_estimate::RamEstimate,
+  util::StarsectorVersionDiff,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+  /// Mod name, dependency name
+  MissingDependency(String, String),
+  /// Mod name, dependency name
+  DisabledDependency(String, String),
+  /// Mod name, the game version it was made for
+  WrongGameVersion(String, String),
+  /// Mod names, and the reason if the compatibility list gives one
+  Incompatible(String, String, Option<String>),
+  /// Recommended MB, MB allowed by vmparams
+  NotEnoughRam(u64, u64),
+}
+
+impl Problem {
+  pub fn describe(&self) -> String {
+    match self {
+      Problem::MissingDependency(name, dependency) => tr_args(
+        "launch-check-missing-dependency",
+        &[("name", name), ("dependency", dependency)],
+      ),
+      Problem::DisabledDependency(name, dependency) => tr_args(
+        "launch-check-disabled-dependency",
+        &[("name", name), ("dependency", dependency)],
+      ),
+      Problem::WrongGameVersion(name, version) => tr_args(
+        "launch-check-game-version",
+        &[("name", name), ("version", version)],
+      ),
+      Problem::Incompatible(a, b, None) => {
+        tr_args("launch-check-incompatible", &[("a", a), ("b", b)])
+      }
+      Problem::Incompatible(a, b, Some(reason)) => tr_args(
+        "launch-check-incompatible-reason",
+        &[("a", a), ("b", b), ("reason", reason)],
+      ),
+      Problem::NotEnoughRam(estimate, allowed) => tr_args(
+        "launch-check-ram",
+        &[("estimate", estimate), ("allowed", allowed)],
+      ),
+    }
+  }
+}
+
+/// Everything that looks wrong with the enabled mods in `mods`. Conflicts come from the
+/// compatibility list in `compat`, memory from a [`RamEstimate`] of the enabled mods that has
+/// `-Xmx` filled in.
+pub fn check<'a>(
+  mods: impl Iterator<Item = &'a Arc<ModEntry>>,
+  game_version: Option<&GameVersion>,
+  compat: &CompatDb,
+  ram: Option<&RamEstimate>,
+) -> Vec<Problem> {
+  let mods: HashMap<&str, &Arc<ModEntry>> = mods.map(|entry| (entry.id.as_str(), entry)).collect();
+  let mut enabled: Vec<&Arc<ModEntry>> = mods
+    .values()
+    .filter(|entry| entry.enabled)
+    .copied()
+    .collect();
+  enabled.sort_by(|a, b| a.name.cmp(&b.name));
+
+  let mut problems = Vec::new();
+  for entry in &enabled {
+    for dependency in &entry.dependencies {
+      match mods.get(dependency.id.as_str()) {
+        None => problems.push(Problem::MissingDependency(
+          entry.name.clone(),
+          dependency.display_name().to_string(),
+        )),
+        Some(installed) if !installed.enabled => problems.push(Problem::DisabledDependency(
+          entry.name.clone(),
+          installed.name.clone(),
+        )),
+        Some(_) => {}
+      }
+    }
+
+    // Starsector versions are all 0.x, so a change in the second number is a major release
+    if let Some(game_version) = game_version
+      && entry.game_version != Default::default()
+      && let StarsectorVersionDiff::Major | StarsectorVersionDiff::Minor =
+        StarsectorVersionDiff::from((&entry.game_version, game_version))
+    {
+      problems.push(Problem::WrongGameVersion(
+        entry.name.clone(),
+        entry.raw_game_version.clone(),
+      ))
+    }
+  }
+
+  let enabled_name = |id: &str| {
+    enabled
+      .iter()
+      .find(|entry| entry.id.eq_ignore_ascii_case(id))
+      .map(|entry| entry.name.clone())
+  };
+  for entry in &enabled {
+    for warning in compat.warnings(&entry.id, enabled_name) {
+      // conflicts are listed from both sides, so only keep each pair once
+      if let Warning::Conflict { name, reason } = warning
+        && entry.name < name
+      {
+        problems.push(Problem::Incompatible(entry.name.clone(), name, reason))
+      }
+    }
+  }
+
+  if let Some(ram) = ram
+    && let Some(current) = ram.current_mb
+    && ram.too_low()
+  {
+    problems.push(Problem::NotEnoughRam(ram.recommended_mb, current))
+  }
+
+  problems
+}
+
+#[cfg(test)]
+mod test {
+  use std::sync::Arc;
+
+  use super::{check, Problem};
+  use crate::app::{
+    compat_db::CompatDb,
+    mod_entry::{Dependency, ModEntry},
+    util::parse_game_version,
+  };
+
+  fn entry(id: &str, enabled: bool, game_version: &str) -> ModEntry {
+    let mut entry = ModEntry::default();
+    entry.id = String::from(id);
+    entry.name = String::from(id);
+    entry.enabled = enabled;
+    entry.raw_game_version = String::from(game_version);
+    entry.game_version = parse_game_version(game_version);
+    entry
+  }
+
+  #[test]
+  fn finds_dependency_and_version_problems() {
+    let mut needs_libs = entry("needs_libs", true, "0.97a-RC11");
+    needs_libs.dependencies = vec![
+      Dependency {
+        id: String::from("lw_lazylib"),
+        name: String::from("LazyLib"),
+      },
+      Dependency {
+        id: String::from("MagicLib"),
+        name: String::new(),
+      },
+    ];
+    let mods = [
+      Arc::new(needs_libs),
+      Arc::new(entry("MagicLib", false, "0.97a-RC11")),
+      Arc::new(entry("old_mod", true, "0.8.1a")),
+    ];
+
+    let problems = check(
+      mods.iter(),
+      Some(&parse_game_version("0.97a-RC11")),
+      &CompatDb::default(),
+      None,
+    );
+    assert_eq!(
+      problems,
+      vec![
+        Problem::MissingDependency(String::from("needs_libs"), String::from("LazyLib")),
+        Problem::DisabledDependency(String::from("needs_libs"), String::from("MagicLib")),
+        Problem::WrongGameVersion(String::from("old_mod"), String::from("0.8.1a")),
+      ]
+    );
+  }
+}
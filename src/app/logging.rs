@@ -0,0 +1,126 @@
+use std::{
+  collections::VecDeque,
+  io::{BufRead, BufReader},
+  path::PathBuf,
+};
+
+use tracing_appender::{
+  non_blocking::WorkerGuard,
+  rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use webview_shared::PROJECT;
+
+const LOG_PREFIX: &str = "moss";
+const LOG_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+
+pub fn log_dir() -> PathBuf {
+  PROJECT.data_dir().join("logs")
+}
+
+/// Where the most recent panic is written, with a backtrace, for problem reports.
+pub fn crash_report_path() -> PathBuf {
+  PROJECT.data_dir().join("last_crash.txt")
+}
+
+/// Sets up logging to stderr and to a daily rotating file in the data dir, keeping the last
+/// week of logs. Panics are logged as well, so they end up in the file, and the latest one is
+/// also written to [`crash_report_path`].
+///
+/// The returned guard flushes the file on drop and must be held until the app exits.
+pub fn init() -> Option<WorkerGuard> {
+  let filter = || EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+  let appender = RollingFileAppender::builder()
+    .rotation(Rotation::DAILY)
+    .filename_prefix(LOG_PREFIX)
+    .filename_suffix(LOG_SUFFIX)
+    .max_log_files(MAX_LOG_FILES)
+    .build(log_dir());
+
+  let guard = match appender {
+    Ok(appender) => {
+      let (writer, guard) = tracing_appender::non_blocking(appender);
+      tracing_subscriber::registry()
+        .with(
+          fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(filter()),
+        )
+        .with(
+          fmt::layer()
+            .with_ansi(false)
+            .with_writer(writer)
+            .with_filter(filter()),
+        )
+        .init();
+
+      Some(guard)
+    }
+    Err(err) => {
+      tracing_subscriber::registry()
+        .with(
+          fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(filter()),
+        )
+        .init();
+      tracing::error!("Failed to open log file, logging to stderr only: {}", err);
+
+      None
+    }
+  };
+
+  let default_hook = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    tracing::error!("{}", info);
+    let _ = std::fs::write(
+      crash_report_path(),
+      format!(
+        "{}\n{}\n\n{}",
+        chrono::Local::now().to_rfc3339(),
+        info,
+        std::backtrace::Backtrace::force_capture()
+      ),
+    );
+    default_hook(info)
+  }));
+
+  guard
+}
+
+/// Every log file still kept.
+pub fn log_files() -> Vec<PathBuf> {
+  std::fs::read_dir(log_dir())
+    .map(|entries| {
+      entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(LOG_PREFIX))
+        .map(|entry| entry.path())
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// The most recently written log file.
+pub fn latest_log() -> Option<PathBuf> {
+  log_files()
+    .into_iter()
+    .max_by_key(|path| path.metadata().and_then(|meta| meta.modified()).ok())
+}
+
+/// Reads the last `lines` lines of the most recent log file.
+pub fn tail(lines: usize) -> Option<String> {
+  let file = std::fs::File::open(latest_log()?).ok()?;
+
+  let mut tail = VecDeque::with_capacity(lines);
+  for line in BufReader::new(file).lines().map_while(Result::ok) {
+    if tail.len() == lines {
+      tail.pop_front();
+    }
+    tail.push_back(line);
+  }
+
+  Some(Vec::from(tail).join("\n"))
+}
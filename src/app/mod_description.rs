@@ -1,17 +1,29 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use chrono::{DateTime, Local};
 use druid::{
-  widget::{Button, Flex, Label, Maybe, Scroll},
-  LensExt, Selector, Widget, WidgetExt,
+  lens,
+  widget::{
+    Button, Either, Flex, Label, Maybe, RawLabel, Scroll, Spinner, Tabs, TextBox, ViewSwitcher,
+  },
+  EventCtx, LensExt, Selector, Widget, WidgetExt,
 };
+use tracing::error;
 
 use super::{
-  mod_entry::{ModMetadata, ModVersionMeta},
+  i18n::{tr, tr_args},
+  mod_entry::{thumbnail::Thumbnail, ModMetadata, ModVersionMeta},
+  mod_repo::RepoRecord,
+  modal::Modal,
   ModEntry,
 };
 
-use super::util::{make_flex_description_row, LabelExt};
+use super::util::{make_flex_description_row, CommandExt, LabelExt, WidgetExtEx};
+use crate::patch::tabs_policy::{InitialTab, StaticTabsForked};
+
+pub mod config_files;
+pub mod documents;
+pub mod forum_post;
 
 pub const OPEN_IN_BROWSER: Selector<String> =
   Selector::new("mod_description.forum.open_in_webview");
@@ -23,43 +35,65 @@ impl ModDescription {
   pub const FRACTAL_URL: &'static str = "https://fractalsoftworks.com/forum/index.php?topic=";
   pub const NEXUS_URL: &'static str = "https://www.nexusmods.com/starsector/mods/";
 
-  pub fn ui_builder() -> impl Widget<Arc<ModEntry>> {
+  pub fn ui_builder(install_dir: Option<PathBuf>) -> impl Widget<Arc<ModEntry>> {
     Flex::column()
       .with_flex_child(
         Flex::row()
           .with_flex_child(
             Scroll::new(
               Flex::column()
+                .with_child(Thumbnail::banner(120.))
                 .with_child(make_flex_description_row(
-                  Label::wrapped("Name:"),
+                  Label::wrapped(tr("description-name")),
                   Label::wrapped_lens(ModEntry::name.in_arc()),
                 ))
                 .with_child(make_flex_description_row(
-                  Label::wrapped("ID:"),
+                  Label::wrapped(tr("description-id")),
                   Label::wrapped_lens(ModEntry::id.in_arc()),
                 ))
                 .with_child(make_flex_description_row(
-                  Label::wrapped("Author(s):"),
+                  Label::wrapped(tr("description-authors")),
                   Label::wrapped_lens(ModEntry::author.in_arc()),
                 ))
                 .with_child(make_flex_description_row(
-                  Label::wrapped("Enabled:"),
+                  Label::wrapped(tr("description-enabled")),
                   Label::wrapped_lens(ModEntry::enabled.in_arc().map(|e| e.to_string(), |_, _| {})),
                 ))
                 .with_child(make_flex_description_row(
-                  Label::wrapped("Version:"),
+                  Label::wrapped(tr("description-version")),
                   Label::wrapped_lens(ModEntry::version.in_arc().map(|v| v.to_string(), |_, _| {})),
                 ))
+                .with_child(make_flex_description_row(
+                  Label::wrapped(tr("description-game-version")),
+                  Label::wrapped_lens(ModEntry::raw_game_version.in_arc()),
+                ))
+                .with_child(
+                  Button::new(tr("description-edit-versions"))
+                    .on_activate(|ctx, data: &mut Arc<ModEntry>, env| {
+                      ModDescription::edit_versions_modal(data.clone()).show_with_size(
+                        ctx,
+                        env,
+                        &(data.version.to_string(), data.raw_game_version.clone()),
+                        (400., 200.),
+                      );
+                    })
+                    .align_right()
+                    .expand_width(),
+                )
+                .with_child(make_flex_description_row(
+                  Label::wrapped(tr("description-update-status")),
+                  ModDescription::update_check_builder(),
+                ))
                 .with_child(
                   make_flex_description_row(
-                    Label::wrapped("Installed at:"),
+                    Label::wrapped(tr("description-installed-at")),
                     Label::wrapped_func(|data: &ModMetadata, _| {
                       if let Some(date) = data.install_date {
                         DateTime::<Local>::from(date)
                           .format("%v %I:%M%p")
                           .to_string()
                       } else {
-                        String::from("Unknown")
+                        tr("unknown")
                       }
                     }),
                   )
@@ -69,7 +103,7 @@ impl ModDescription {
                   Maybe::or_empty(|| {
                     Maybe::or_empty(|| {
                       make_flex_description_row(
-                        Label::wrapped("Fractal link:"),
+                        Label::wrapped(tr("description-fractal-link")),
                         Button::from_label(Label::wrapped_func(|data: &String, _: &druid::Env| {
                           format!("{}{}", ModDescription::FRACTAL_URL, data.clone())
                         }))
@@ -99,7 +133,7 @@ impl ModDescription {
                   Maybe::or_empty(|| {
                     Maybe::or_empty(|| {
                       make_flex_description_row(
-                        Label::wrapped("Nexus link:"),
+                        Label::wrapped(tr("description-nexus-link")),
                         Button::from_label(Label::wrapped_func(|data: &String, _: &druid::Env| {
                           format!("{}{}", ModDescription::NEXUS_URL, data.clone())
                         }))
@@ -124,6 +158,59 @@ impl ModDescription {
                     ))
                   })
                   .lens(ModEntry::version_checker.in_arc()),
+                )
+                .with_child(
+                  Maybe::or_empty(|| {
+                    Flex::column()
+                      .with_child(
+                        Maybe::or_empty(|| {
+                          make_flex_description_row(
+                            Label::wrapped(tr("description-discussion")),
+                            Button::from_label(Label::wrapped_func(
+                              |data: &String, _: &druid::Env| data.clone(),
+                            ))
+                            .on_click(|ctx, data: &mut String, _| {
+                              ctx.submit_command(OPEN_IN_BROWSER.with(data.clone()))
+                            }),
+                          )
+                        })
+                        .lens(RepoRecord::discussion),
+                      )
+                      .with_child(
+                        Maybe::or_empty(|| {
+                          make_flex_description_row(
+                            Label::wrapped(tr("description-categories")),
+                            Label::wrapped_func(|data: &String, _| data.clone()),
+                          )
+                        })
+                        .lens(RepoRecord::categories.map(
+                          |categories| {
+                            (!categories.is_empty())
+                              .then(|| categories.iter().cloned().collect::<Vec<_>>().join(", "))
+                          },
+                          |_, _| {},
+                        )),
+                      )
+                      .with_child(
+                        Maybe::or_empty(|| {
+                          make_flex_description_row(
+                            Label::wrapped(tr("description-repo-updated")),
+                            Label::wrapped_func(|data: &String, _| data.clone()),
+                          )
+                        })
+                        .lens(RepoRecord::updated.map(
+                          |date| {
+                            date.map(|date| {
+                              DateTime::<Local>::from(date)
+                                .format("%v %I:%M%p")
+                                .to_string()
+                            })
+                          },
+                          |_, _| {},
+                        )),
+                      )
+                  })
+                  .lens(ModEntry::repo_record.in_arc()),
                 ),
             )
             .vertical()
@@ -131,31 +218,26 @@ impl ModDescription {
             1.,
           )
           .with_flex_child(
-            Flex::column()
-              .with_child(
-                Label::new("Description:")
-                  .with_text_alignment(druid::TextAlignment::Start)
-                  .expand_width(),
-              )
-              .with_flex_child(
-                Scroll::new(
-                  Label::dynamic(|t: &String, _| t.to_string())
-                    .with_line_break_mode(druid::widget::LineBreaking::WordWrap)
-                    .lens(ModEntry::description.in_arc()),
-                )
-                .vertical()
-                .expand(),
-                1.,
+            Tabs::for_policy(StaticTabsForked::build(vec![
+              InitialTab::new(
+                tr("description-tab-description"),
+                ModDescription::description_builder(),
+              ),
+              InitialTab::new(
+                tr("description-tab-config-files"),
+                config_files::ui_builder(install_dir),
               ),
+              InitialTab::new(tr("description-tab-documents"), documents::ui_builder()),
+            ])),
             1.,
           ),
         1.,
       )
       .with_child(
-        Button::new("Open in file manager...")
-          .on_click(|_, data: &mut Arc<ModEntry>, _| {
+        Button::new(tr("description-open-in-file-manager"))
+          .on_activate(|_, data: &mut Arc<ModEntry>, _| {
             if let Err(err) = opener::open(data.path.clone()) {
-              eprintln!("{}", err)
+              error!("{}", err)
             }
           })
           .align_right()
@@ -164,7 +246,135 @@ impl ModDescription {
       .padding(5.)
   }
 
+  /// The result of the mod's last version check, with a button to check again straight away.
+  fn update_check_builder() -> impl Widget<Arc<ModEntry>> {
+    Flex::row()
+      .with_flex_child(
+        Either::new(
+          |entry: &Arc<ModEntry>, _| entry.checking_update,
+          Flex::row()
+            .with_child(Spinner::new())
+            .with_default_spacer()
+            .with_child(Label::new(tr("description-update-checking"))),
+          Label::wrapped_func(|entry: &Arc<ModEntry>, _| {
+            match (&entry.update_error, &entry.update_status) {
+              (Some(err), _) => tr_args("description-update-error", &[("err", err)]),
+              (None, Some(status)) => status.to_string(),
+              (None, None) if entry.version_checker.is_none() => {
+                tr("description-update-no-version-file")
+              }
+              (None, None) => tr("description-update-not-checked"),
+            }
+          }),
+        ),
+        1.,
+      )
+      .with_child(
+        Button::new(tr("description-update-check-now"))
+          .on_click(|ctx, entry: &mut Arc<ModEntry>, _| {
+            ctx.submit_command(ModEntry::CHECK_FOR_UPDATE.with(entry.clone()))
+          })
+          .disabled_if(|entry: &Arc<ModEntry>, _| {
+            entry.version_checker.is_none() || entry.checking_update
+          }),
+      )
+  }
+
+  /// The mod's `mod_info.json` description, or its forum post.
+  fn description_builder() -> impl Widget<Arc<ModEntry>> {
+    Flex::column()
+      .with_child(
+        Flex::row()
+          .with_child(
+            Label::new(tr("description-description"))
+              .with_text_alignment(druid::TextAlignment::Start),
+          )
+          .with_flex_spacer(1.)
+          .with_child(Either::new(
+            |entry: &Arc<ModEntry>, _| entry.forum_post.is_some(),
+            Button::new(tr("description-show-mod-info")).on_activate(
+              |ctx, data: &mut Arc<ModEntry>, _| {
+                ctx.submit_command(forum_post::SET.with((data.id.clone(), None)))
+              },
+            ),
+            Button::new(tr("description-show-forum-post"))
+              .on_activate(|ctx, data: &mut Arc<ModEntry>, _| {
+                if let Some(topic_id) = ModDescription::topic_id(data) {
+                  ctx.submit_command(forum_post::FETCH.with((data.id.clone(), topic_id)))
+                }
+              })
+              .disabled_if(|data, _| ModDescription::topic_id(data).is_none()),
+          ))
+          .expand_width(),
+      )
+      .with_flex_child(
+        Scroll::new(ViewSwitcher::new(
+          |entry: &Arc<ModEntry>, _| entry.forum_post.clone(),
+          |post, _, _| match post {
+            Some(Ok(spans)) => RawLabel::new()
+              .with_line_break_mode(druid::widget::LineBreaking::WordWrap)
+              .lens(lens::Constant(forum_post::rich_text(spans)))
+              .boxed(),
+            Some(Err(err)) => {
+              Label::wrapped(tr_args("description-forum-post-failed", &[("err", err)])).boxed()
+            }
+            None => Label::dynamic(|t: &String, _| t.to_string())
+              .with_line_break_mode(druid::widget::LineBreaking::WordWrap)
+              .lens(ModEntry::description.in_arc())
+              .boxed(),
+          },
+        ))
+        .vertical()
+        .expand(),
+        1.,
+      )
+  }
+
+  fn edit_versions_modal<'a>(entry: Arc<ModEntry>) -> Modal<'a, (String, String)> {
+    let title = tr_args("description-edit-versions-title", &[("name", &entry.name)]);
+
+    Modal::new(&title)
+      .with_content(tr("description-edit-versions-hint"))
+      .with_content(
+        make_flex_description_row(
+          Label::wrapped(tr("description-version")),
+          TextBox::new()
+            .lens(lens!((String, String), 0))
+            .expand_width(),
+        )
+        .boxed(),
+      )
+      .with_content(
+        make_flex_description_row(
+          Label::wrapped(tr("description-game-version")),
+          TextBox::new()
+            .lens(lens!((String, String), 1))
+            .expand_width(),
+        )
+        .boxed(),
+      )
+      .with_button(
+        &tr("description-edit-versions-save"),
+        move |ctx: &mut EventCtx, (version, game_version): &mut (String, String)| match entry
+          .write_versions(version, game_version)
+        {
+          Ok(updated) => ctx.submit_command_global(ModEntry::REPLACE.with(Arc::new(updated))),
+          Err(err) => error!("Failed to update mod_info.json for {}: {}", entry.id, err),
+        },
+      )
+      .with_close_label(&tr("cancel"))
+  }
+
+  /// The mod's forum topic, if its version file names one.
+  fn topic_id(entry: &ModEntry) -> Option<String> {
+    entry
+      .version_checker
+      .as_ref()
+      .map(|version_checker| version_checker.fractal_id.clone())
+      .filter(|id| !id.is_empty())
+  }
+
   pub fn empty_builder() -> impl Widget<()> {
-    Label::new("No mod selected.")
+    Label::new(tr("description-no-mod-selected"))
   }
 }
@@ -0,0 +1,320 @@
+//! Files players commonly edit to configure a mod - anything under its `data/config`, `.ini`
+//! files in its root (such as GraphicsLib's `GRAPHICS_OPTIONS.ini`), and the settings LunaLib
+//! saves for it in the game's `saves/common`.
+//!
+//! Files open in the system's default editor. JSON files can also be edited here, in which case
+//! nothing is written unless the text still parses.
+
+use std::{
+  io::Read,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use druid::{
+  widget::{
+    Button, Controller, CrossAxisAlignment, Flex, Label, Maybe, Scroll, TextBox, ViewSwitcher,
+  },
+  Data, Env, EventCtx, Lens, LifeCycle, LifeCycleCtx, Selector, Widget, WidgetExt,
+};
+use json_comments::strip_comments;
+use tracing::error;
+
+use crate::app::{
+  i18n::{tr, tr_args},
+  mod_entry::{config_text, describe_json5_error, ModEntry},
+  modal::Modal,
+  util::{LabelExt, WidgetExtEx},
+};
+
+const EXTENSIONS: [&str; 4] = ["json", "ini", "csv", "txt"];
+/// How deep `data/config` is searched.
+const MAX_DEPTH: usize = 4;
+/// Where LunaLib saves each mod's settings, relative to the game folder.
+const LUNA_SETTINGS: &str = "saves/common/LunaSettings";
+
+/// Mod ID and folder - looks for the mod's config files in the background.
+pub const FIND: Selector<(String, PathBuf)> = Selector::new("mod_description.config_files.find");
+/// Mod ID and the config files found for it.
+pub const SET: Selector<(String, Arc<[PathBuf]>)> =
+  Selector::new("mod_description.config_files.set");
+
+/// Config files of the mod with `id` in `mod_dir`. Version files are left out, as they're edited
+/// along with `mod_info.json`.
+pub fn find(mod_dir: &Path, id: &str, install_dir: Option<&Path>) -> Vec<PathBuf> {
+  fn find_in(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    let mut entries: Vec<PathBuf> = dir
+      .read_dir()
+      .into_iter()
+      .flatten()
+      .flatten()
+      .map(|entry| entry.path())
+      .collect();
+    entries.sort();
+    for path in entries {
+      if path.is_dir() {
+        if depth < MAX_DEPTH && !path.ends_with("version") {
+          find_in(&path, depth + 1, found)
+        }
+      } else if has_config_extension(&path) {
+        found.push(path)
+      }
+    }
+  }
+
+  let mut found = Vec::new();
+  find_in(&mod_dir.join("data").join("config"), 0, &mut found);
+
+  let mut root_ini_files: Vec<PathBuf> = mod_dir
+    .read_dir()
+    .into_iter()
+    .flatten()
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| {
+      path.is_file()
+        && path
+          .extension()
+          .is_some_and(|ext| ext.eq_ignore_ascii_case("ini"))
+    })
+    .collect();
+  root_ini_files.sort();
+  found.extend(root_ini_files);
+
+  if let Some(install_dir) = install_dir {
+    let mut luna_files: Vec<PathBuf> = install_dir
+      .join(LUNA_SETTINGS)
+      .read_dir()
+      .into_iter()
+      .flatten()
+      .flatten()
+      .map(|entry| entry.path())
+      .filter(|path| {
+        path
+          .file_name()
+          .and_then(|name| name.to_str()?.split('.').next())
+          .is_some_and(|name| name == id)
+      })
+      .collect();
+    luna_files.sort();
+    found.extend(luna_files);
+  }
+
+  found
+}
+
+fn has_config_extension(path: &Path) -> bool {
+  path.extension().is_some_and(|ext| {
+    EXTENSIONS
+      .iter()
+      .any(|config| ext.eq_ignore_ascii_case(config))
+  })
+}
+
+/// JSON files, including LunaLib's `.json.data` saves.
+fn is_json(path: &Path) -> bool {
+  path.file_name().is_some_and(|name| {
+    let name = name.to_string_lossy().to_lowercase();
+    name.ends_with(".json") || name.ends_with(".json.data")
+  })
+}
+
+/// Checks that `text` parses the way the game parses JSON, comments included.
+fn check_json(text: &str) -> Result<(), String> {
+  let mut stripped = String::new();
+  strip_comments(text.as_bytes())
+    .read_to_string(&mut stripped)
+    .map_err(|err| err.to_string())?;
+
+  json5::from_str::<serde_json::Value>(&stripped)
+    .map(|_| ())
+    .map_err(describe_json5_error)
+}
+
+/// A row per config file, for the mod details panel. The files are looked for in the background
+/// the first time they're shown, as searching a large mod can take a moment.
+pub fn ui_builder(install_dir: Option<PathBuf>) -> impl Widget<Arc<ModEntry>> {
+  ViewSwitcher::new(
+    |entry: &Arc<ModEntry>, _| (entry.path.clone(), entry.config_files.clone()),
+    move |(_, files), entry: &Arc<ModEntry>, _| {
+      let Some(files) = files else {
+        return Label::wrapped(tr("config-files-finding"))
+          .controller(FindOnAdded)
+          .boxed();
+      };
+      if files.is_empty() {
+        return Label::wrapped(tr("config-files-none")).boxed();
+      }
+
+      let mut column = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+      for path in files.iter().cloned() {
+        // LunaLib's files are outside the mod, so they're shown relative to the game folder
+        let name = path
+          .strip_prefix(&entry.path)
+          .ok()
+          .or_else(|| path.strip_prefix(install_dir.as_deref()?).ok())
+          .unwrap_or(&path)
+          .to_string_lossy()
+          .to_string();
+        column.add_child(file_row(name, path));
+      }
+
+      Scroll::new(column).vertical().expand().boxed()
+    },
+  )
+}
+
+/// Asks for the mod's config files to be found once the tab is shown.
+struct FindOnAdded;
+
+impl<W: Widget<Arc<ModEntry>>> Controller<Arc<ModEntry>, W> for FindOnAdded {
+  fn lifecycle(
+    &mut self,
+    child: &mut W,
+    ctx: &mut LifeCycleCtx,
+    event: &LifeCycle,
+    data: &Arc<ModEntry>,
+    env: &Env,
+  ) {
+    if let LifeCycle::WidgetAdded = event {
+      ctx.submit_command(FIND.with((data.id.clone(), data.path.clone())))
+    }
+
+    child.lifecycle(ctx, event, data, env)
+  }
+}
+
+fn file_row(name: String, path: PathBuf) -> impl Widget<Arc<ModEntry>> {
+  let mut row = Flex::row()
+    .with_flex_child(Label::wrapped(&name).expand_width(), 1.)
+    .with_child(Button::new(tr("config-files-open")).on_activate({
+      let path = path.clone();
+      move |_, _, _| {
+        if let Err(err) = opener::open(&path) {
+          error!("{}", err)
+        }
+      }
+    }));
+
+  if is_json(&path) {
+    row.add_spacer(5.);
+    row.add_child(
+      Button::new(tr("config-files-edit")).on_activate(move |ctx, _, env| {
+        match JsonEditor::new(path.clone()) {
+          Ok(editor) => {
+            JsonEditor::modal(&name).show_with_size(ctx, env, &editor, (700., 600.));
+          }
+          Err(err) => error!("Failed to read {}: {}", path.to_string_lossy(), err),
+        }
+      }),
+    );
+  }
+
+  row.padding((0., 2.)).expand_width()
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct JsonEditor {
+  #[data(same_fn = "PartialEq::eq")]
+  path: PathBuf,
+  text: String,
+  error: Option<String>,
+}
+
+impl JsonEditor {
+  pub fn new(path: PathBuf) -> Result<Self, String> {
+    let text = config_text::read(&path)?;
+
+    Ok(Self {
+      path,
+      text,
+      error: None,
+    })
+  }
+
+  fn save(&mut self, ctx: &mut EventCtx) {
+    let res = check_json(&self.text)
+      .map_err(|err| tr_args("config-files-would-not-load", &[("err", &err)]))
+      .and_then(|_| {
+        std::fs::write(&self.path, &self.text)
+          .map_err(|err| tr_args("config-files-write-failed", &[("err", &err)]))
+      });
+
+    match res {
+      Ok(_) => ctx.submit_command(druid::commands::CLOSE_WINDOW),
+      Err(err) => self.error = Some(err),
+    }
+  }
+
+  pub fn modal<'a>(name: &str) -> Modal<'a, Self> {
+    Modal::new(&tr_args("config-files-edit-title", &[("name", &name)]))
+      .with_content(
+        Scroll::new(TextBox::multiline().lens(JsonEditor::text).expand_width())
+          .vertical()
+          .fix_height(450.)
+          .boxed(),
+      )
+      .with_content(
+        Maybe::or_empty(|| Label::wrapped_func(|error: &String, _| error.clone()))
+          .lens(JsonEditor::error)
+          .boxed(),
+      )
+      .with_content(
+        Button::new(tr("config-files-save"))
+          .on_activate(|ctx, editor: &mut JsonEditor, _| editor.save(ctx))
+          .align_right()
+          .expand_width()
+          .boxed(),
+      )
+      .with_close_label(&tr("close"))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use tempfile::tempdir;
+
+  use super::{check_json, find};
+
+  #[test]
+  fn finds_config_files() {
+    let install = tempdir().expect("Create install dir");
+    let mod_dir = install.path().join("mods/my_mod");
+    for file in [
+      "data/config/settings.json",
+      "data/config/sub/options.ini",
+      "data/config/version/version_files.csv",
+      "data/hulls/ship.json",
+      "GRAPHICS_OPTIONS.ini",
+    ] {
+      let path = mod_dir.join(file);
+      std::fs::create_dir_all(path.parent().expect("Parent dir")).expect("Create dir");
+      std::fs::write(path, "{}").expect("Write file");
+    }
+    let luna = install.path().join("saves/common/LunaSettings");
+    std::fs::create_dir_all(&luna).expect("Create LunaSettings");
+    std::fs::write(luna.join("my_mod.json.data"), "{}").expect("Write settings");
+    std::fs::write(luna.join("my_mod_extra.json.data"), "{}").expect("Write settings");
+
+    let found = find(&mod_dir, "my_mod", Some(install.path()));
+    assert_eq!(
+      found,
+      vec![
+        mod_dir.join("data/config/settings.json"),
+        mod_dir.join("data/config/sub/options.ini"),
+        mod_dir.join("GRAPHICS_OPTIONS.ini"),
+        luna.join("my_mod.json.data"),
+      ]
+    );
+    assert_eq!(find(&mod_dir, "my_mod", None).len(), 3);
+    assert!(find(&PathBuf::from("missing"), "my_mod", None).is_empty());
+  }
+
+  #[test]
+  fn checks_json_like_the_game() {
+    assert!(check_json("{\n  # comment\n  \"a\": 1,\n}").is_ok());
+    assert!(check_json("{\n  \"a\": \n}").is_err());
+  }
+}
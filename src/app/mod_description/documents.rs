@@ -0,0 +1,331 @@
+//! READMEs, changelogs and licenses shipped in a mod's folder, readable in the mod details panel
+//! without going looking for them in a file manager.
+//!
+//! Markdown files are reduced to the same bold, italic and link [`Span`]s as forum posts, anything
+//! else is shown as plain text.
+
+use std::{
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use druid::{
+  lens,
+  widget::{Button, CrossAxisAlignment, Flex, Label, LineBreaking, RawLabel, Scroll, ViewSwitcher},
+  Widget, WidgetExt,
+};
+use tracing::error;
+
+use super::forum_post::{self, Span};
+use crate::app::{
+  i18n::{tr, tr_args},
+  mod_entry::ModEntry,
+  modal::Modal,
+  popup::Popup,
+  util::{LabelExt, WidgetExtEx},
+};
+
+/// Names documents start with, compared ignoring case and extension.
+const NAMES: [&str; 6] = [
+  "readme",
+  "changelog",
+  "change_log",
+  "changes",
+  "license",
+  "licence",
+];
+const EXTENSIONS: [&str; 2] = ["md", "txt"];
+
+/// Documents in the root of `mod_dir`, in name order.
+pub fn find(mod_dir: &Path) -> Vec<PathBuf> {
+  let mut found: Vec<PathBuf> = mod_dir
+    .read_dir()
+    .into_iter()
+    .flatten()
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| path.is_file() && is_document(path))
+    .collect();
+  found.sort();
+
+  found
+}
+
+fn is_document(path: &Path) -> bool {
+  let Some(name) = path.file_name().map(|name| name.to_string_lossy().to_lowercase()) else {
+    return false;
+  };
+  let (stem, extension) = match name.split_once('.') {
+    Some((stem, extension)) => (stem, Some(extension)),
+    None => (name.as_str(), None),
+  };
+
+  NAMES.contains(&stem) && extension.map_or(true, |extension| EXTENSIONS.contains(&extension))
+}
+
+fn is_markdown(path: &Path) -> bool {
+  path
+    .extension()
+    .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+}
+
+/// The document at `path`, formatted if it's markdown.
+fn read(path: &Path) -> Result<Vec<Span>, String> {
+  let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+  let text = String::from_utf8_lossy(&bytes);
+
+  Ok(if is_markdown(path) {
+    parse_markdown(&text)
+  } else {
+    vec![Span {
+      text: text.into_owned(),
+      ..Default::default()
+    }]
+  })
+}
+
+/// Reduces markdown to bold, italics and links. Headings are bolded, list bullets become `•` and
+/// code is shown as it is, everything else that isn't text (images, rules) is dropped.
+fn parse_markdown(text: &str) -> Vec<Span> {
+  let mut spans = Spans::default();
+  let mut in_code_block = false;
+  let mut started = false;
+  for line in text.lines() {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") {
+      in_code_block = !in_code_block;
+      continue;
+    }
+    if started {
+      spans.push("\n", None);
+    }
+    started = true;
+    if in_code_block {
+      spans.push(line, None);
+      continue;
+    }
+
+    let indent = &line[..line.len() - trimmed.len()];
+    if trimmed.len() >= 3 && trimmed.chars().all(|c| matches!(c, '-' | '*' | '_' | ' ')) {
+      // a horizontal rule
+    } else if let Some(heading) = trimmed.strip_prefix('#') {
+      spans.bold = true;
+      spans.inline(heading.trim_start_matches('#').trim());
+      spans.bold = false;
+    } else if let Some(item) = ["- ", "* ", "+ "]
+      .iter()
+      .find_map(|bullet| trimmed.strip_prefix(bullet))
+    {
+      spans.push(indent, None);
+      spans.push("• ", None);
+      spans.inline(item);
+    } else {
+      spans.inline(line);
+    }
+    // formatting doesn't carry over into the next line
+    spans.bold = false;
+    spans.italic = false;
+  }
+
+  spans.spans
+}
+
+#[derive(Default)]
+struct Spans {
+  spans: Vec<Span>,
+  bold: bool,
+  italic: bool,
+}
+
+impl Spans {
+  fn push(&mut self, text: &str, link: Option<&str>) {
+    if text.is_empty() {
+      return;
+    }
+    if link.is_none()
+      && let Some(last) = self.spans.last_mut()
+      && last.bold == self.bold
+      && last.italic == self.italic
+      && last.link.is_none()
+    {
+      last.text.push_str(text);
+    } else {
+      self.spans.push(Span {
+        text: text.to_string(),
+        bold: self.bold,
+        italic: self.italic,
+        link: link.map(String::from),
+      })
+    }
+  }
+
+  fn inline(&mut self, mut rest: &str) {
+    let mut text = String::new();
+    while let Some(c) = rest.chars().next() {
+      let next = rest[c.len_utf8()..].chars().next();
+      if rest.starts_with("**") {
+        self.push(&std::mem::take(&mut text), None);
+        self.bold = !self.bold;
+        rest = &rest[2..];
+      } else if c == '*' && (self.italic || next.is_some_and(|next| !next.is_whitespace())) {
+        self.push(&std::mem::take(&mut text), None);
+        self.italic = !self.italic;
+        rest = &rest[1..];
+      } else if c == '`' {
+        rest = &rest[1..];
+      } else if let Some((label, url, len)) = link(rest) {
+        self.push(&std::mem::take(&mut text), None);
+        // images are dropped
+        if !rest.starts_with('!') {
+          self.push(label, Some(url));
+        }
+        rest = &rest[len..];
+      } else {
+        text.push(c);
+        rest = &rest[c.len_utf8()..];
+      }
+    }
+    self.push(&text, None);
+  }
+}
+
+/// The label and URL of a `[label](url)` or `![alt](url)` at the start of `text`, and how long it
+/// is.
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+  let start = if text.starts_with("![") {
+    2
+  } else if text.starts_with('[') {
+    1
+  } else {
+    return None;
+  };
+  let label_end = start + text[start..].find("](")?;
+  let url_end = label_end + 2 + text[label_end + 2..].find(')')?;
+
+  Some((
+    &text[start..label_end],
+    &text[label_end + 2..url_end],
+    url_end + 1,
+  ))
+}
+
+/// A row per document, for the mod details panel.
+pub fn ui_builder() -> impl Widget<Arc<ModEntry>> {
+  ViewSwitcher::new(
+    |entry: &Arc<ModEntry>, _| entry.path.clone(),
+    |_, entry: &Arc<ModEntry>, _| {
+      let files = find(&entry.path);
+      if files.is_empty() {
+        return Label::wrapped(tr("documents-none")).boxed();
+      }
+
+      let mut column = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+      for path in files {
+        column.add_child(file_row(path));
+      }
+
+      Scroll::new(column).vertical().expand().boxed()
+    },
+  )
+}
+
+fn file_row(path: PathBuf) -> impl Widget<Arc<ModEntry>> {
+  let name = path
+    .file_name()
+    .unwrap_or_default()
+    .to_string_lossy()
+    .to_string();
+
+  Flex::row()
+    .with_flex_child(Label::wrapped(&name).expand_width(), 1.)
+    .with_child(Button::new(tr("documents-read")).on_activate({
+      let path = path.clone();
+      move |ctx, _, env| match read(&path) {
+        Ok(spans) => {
+          reader(&name, &spans).show_with_size(ctx, env, &(), (700., 600.));
+        }
+        Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::anyhow!(tr_args(
+          "documents-read-failed",
+          &[("name", &name), ("err", &err)],
+        ))))),
+      }
+    }))
+    .with_spacer(5.)
+    .with_child(
+      Button::new(tr("documents-open")).on_activate(move |_, _, _| {
+        if let Err(err) = opener::open(&path) {
+          error!("{}", err)
+        }
+      }),
+    )
+    .padding((0., 2.))
+    .expand_width()
+}
+
+fn reader<'a>(name: &str, spans: &[Span]) -> Modal<'a, ()> {
+  Modal::new(name)
+    .with_content(
+      Scroll::new(
+        RawLabel::new()
+          .with_line_break_mode(LineBreaking::WordWrap)
+          .lens(lens::Constant(forum_post::rich_text(spans)))
+          .expand_width(),
+      )
+      .vertical()
+      .fix_height(480.)
+      .boxed(),
+    )
+    .with_close_label(&tr("close"))
+}
+
+#[cfg(test)]
+mod test {
+  use tempfile::tempdir;
+
+  use super::{find, parse_markdown};
+
+  #[test]
+  fn finds_documents() {
+    let mod_dir = tempdir().expect("Create mod dir");
+    for file in [
+      "README.md",
+      "changelog.txt",
+      "LICENSE",
+      "readme.json",
+      "mod_info.json",
+    ] {
+      std::fs::write(mod_dir.path().join(file), "").expect("Write file");
+    }
+
+    assert_eq!(
+      find(mod_dir.path()),
+      vec![
+        mod_dir.path().join("LICENSE"),
+        mod_dir.path().join("README.md"),
+        mod_dir.path().join("changelog.txt"),
+      ]
+    );
+  }
+
+  #[test]
+  fn reduces_markdown() {
+    let spans = parse_markdown(
+      "# Example Mod\n\nAdds **new** *ships*, see [the thread](https://example.com).\n\
+       ![banner](banner.png)\n- One\n```\n*not italic*\n```\n---\n2 * 3 = `six`",
+    );
+
+    let plain: String = spans.iter().map(|span| span.text.as_str()).collect();
+    assert_eq!(
+      plain,
+      "Example Mod\n\nAdds new ships, see the thread.\n\n• One\n*not italic*\n\n2 * 3 = six"
+    );
+    assert!(spans[0].bold && spans[0].text == "Example Mod");
+    assert!(spans.iter().any(|span| span.bold && span.text == "new"));
+    assert!(spans.iter().any(|span| span.italic && span.text == "ships"));
+    assert!(
+      spans.iter().any(
+        |span| span.text == "the thread" && span.link.as_deref() == Some("https://example.com")
+      )
+    );
+  }
+}
@@ -0,0 +1,421 @@
+//! The opening post of a mod's forum thread, shown in place of its `mod_info.json` description.
+//!
+//! Posts are reduced to text with bold, italics and links - images, colours, sizes and layout are
+//! dropped. The same reduction handles BBCode, so descriptions copied straight from the forum's
+//! editor come out the same way.
+
+use std::sync::Arc;
+
+use druid::{
+  text::{RichText, RichTextBuilder},
+  FontStyle, FontWeight, Selector,
+};
+
+use super::{ModDescription, OPEN_IN_BROWSER};
+use crate::app::{i18n::tr, net, util::BLUE_KEY};
+
+/// Mod ID and forum topic ID - fetches the topic's opening post for the mod.
+pub const FETCH: Selector<(String, String)> = Selector::new("mod_description.forum_post.fetch");
+/// Mod ID, and the post to show in its description or `None` to show `mod_info.json`'s again.
+pub const SET: Selector<(String, Option<ForumPost>)> =
+  Selector::new("mod_description.forum_post.set");
+
+/// A formatted post, or why it couldn't be fetched.
+pub type ForumPost = Result<Arc<[Span]>, String>;
+
+/// A run of text with the same formatting.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Span {
+  pub text: String,
+  pub bold: bool,
+  pub italic: bool,
+  pub link: Option<String>,
+}
+
+/// Tags that start a new line when opened or closed.
+const BLOCK_TAGS: [&str; 14] = [
+  "p",
+  "div",
+  "tr",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "blockquote",
+  "quote",
+  "list",
+  "ul",
+  "ol",
+];
+/// Tags whose contents are dropped along with them.
+const SKIPPED_TAGS: [&str; 3] = ["img", "script", "style"];
+/// BBCode tags recognised as tags rather than bracketed text.
+const BBCODE_TAGS: [&str; 18] = [
+  "b", "i", "u", "s", "url", "img", "list", "*", "li", "color", "size", "font", "quote", "code",
+  "center", "left", "right", "spoiler",
+];
+
+pub async fn fetch(topic_id: &str) -> ForumPost {
+  let page = net::api_client()?
+    .get(format!("{}{}", ModDescription::FRACTAL_URL, topic_id))
+    .send()
+    .await
+    .and_then(|res| res.error_for_status())
+    .map_err(|err| err.to_string())?
+    .text()
+    .await
+    .map_err(|err| err.to_string())?;
+
+  let post = first_post(&page).ok_or_else(|| tr("description-forum-post-missing"))?;
+
+  Ok(parse(post).into())
+}
+
+/// The markup of the first post on a forum page.
+fn first_post(page: &str) -> Option<&str> {
+  let start = page.find("<div class=\"inner\"")?;
+  let start = start + page[start..].find('>')? + 1;
+
+  let mut depth = 1;
+  let mut idx = start;
+  loop {
+    let open = page[idx..].find("<div").map(|offset| (offset, 1));
+    let close = page[idx..].find("</div").map(|offset| (offset, -1));
+    let (offset, change) = match (open, close) {
+      (Some(open), Some(close)) => open.min(close),
+      (None, Some(close)) => close,
+      _ => return None,
+    };
+    depth += change;
+    if depth == 0 {
+      return Some(&page[start..idx + offset]);
+    }
+    idx += offset + 4;
+  }
+}
+
+/// Reduces HTML or BBCode to formatted runs of text.
+pub fn parse(markup: &str) -> Vec<Span> {
+  let html = markup.contains("</") || markup.contains("<br");
+  let mut parser = Parser::default();
+
+  let mut rest = markup;
+  while !rest.is_empty() {
+    let tag = match rest.chars().next() {
+      Some('<') => rest.find('>').map(|end| (&rest[1..end], end)),
+      Some('[') => rest
+        .find(']')
+        .map(|end| (&rest[1..end], end))
+        .filter(|(tag, _)| BBCODE_TAGS.contains(&tag_name(tag).as_str())),
+      _ => None,
+    };
+
+    if let Some((tag, end)) = tag {
+      parser.tag(tag);
+      rest = &rest[end + 1..];
+    } else {
+      let end = rest
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| matches!(c, '<' | '['))
+        .map_or(rest.len(), |(idx, _)| idx);
+      let text = decode_entities(&rest[..end]);
+      if html {
+        parser.text(&collapse_whitespace(&text))
+      } else {
+        parser.text(&text)
+      }
+      rest = &rest[end..];
+    }
+  }
+
+  parser.finish()
+}
+
+/// Collapses runs of whitespace into single spaces, as browsers do.
+fn collapse_whitespace(text: &str) -> String {
+  let mut collapsed = String::with_capacity(text.len());
+  for c in text.chars() {
+    if !c.is_whitespace() {
+      collapsed.push(c)
+    } else if !collapsed.ends_with(' ') {
+      collapsed.push(' ')
+    }
+  }
+
+  collapsed
+}
+
+fn tag_name(tag: &str) -> String {
+  tag
+    .trim_start_matches('/')
+    .split(|c: char| c.is_whitespace() || c == '=' || c == '/')
+    .next()
+    .unwrap_or_default()
+    .to_lowercase()
+}
+
+/// The value of `name` in an HTML tag, or the `[tag=value]` value of a BBCode one.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+  if let Some((_, value)) = tag.split_once('=')
+    && !tag.contains(char::is_whitespace)
+  {
+    return Some(value.trim_matches(['"', '\'']).to_string());
+  }
+
+  let start = tag.find(&format!("{}=", name))? + name.len() + 1;
+  let value = &tag[start..];
+  let value = match value.chars().next()? {
+    quote @ ('"' | '\'') => value[1..].split(quote).next()?,
+    _ => value.split(char::is_whitespace).next()?,
+  };
+
+  Some(decode_entities(value))
+}
+
+fn decode_entities(text: &str) -> String {
+  let mut decoded = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find('&') {
+    decoded.push_str(&rest[..start]);
+    rest = &rest[start..];
+
+    let entity = rest
+      .find(';')
+      .filter(|end| *end <= 10)
+      .map(|end| (&rest[1..end], end));
+    let character = entity.and_then(|(entity, _)| match entity {
+      "amp" => Some('&'),
+      "lt" => Some('<'),
+      "gt" => Some('>'),
+      "quot" => Some('"'),
+      "apos" => Some('\''),
+      "nbsp" => Some(' '),
+      _ => entity
+        .strip_prefix("#x")
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .or_else(|| entity.strip_prefix('#')?.parse().ok())
+        .and_then(char::from_u32),
+    });
+
+    match (character, entity) {
+      (Some(character), Some((_, end))) => {
+        decoded.push(character);
+        rest = &rest[end + 1..];
+      }
+      _ => {
+        decoded.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  decoded.push_str(rest);
+
+  decoded
+}
+
+#[derive(Default)]
+struct Parser {
+  spans: Vec<Span>,
+  bold: usize,
+  italic: usize,
+  skip: usize,
+  link: Option<String>,
+  /// Set by a BBCode `[url]` with no value, whose text is also its address.
+  link_is_text: bool,
+}
+
+impl Parser {
+  fn tag(&mut self, tag: &str) {
+    let closing = tag.starts_with('/');
+    let name = tag_name(tag);
+    let change = |count: &mut usize| {
+      *count = if closing {
+        count.saturating_sub(1)
+      } else {
+        *count + 1
+      }
+    };
+
+    match name.as_str() {
+      _ if SKIPPED_TAGS.contains(&name.as_str()) => {
+        // html images have no closing tag
+        if name != "img" || !tag.contains("src=") {
+          change(&mut self.skip)
+        }
+      }
+      _ if self.skip > 0 => {}
+      "b" | "strong" => change(&mut self.bold),
+      "i" | "em" => change(&mut self.italic),
+      "a" | "url" if closing => {
+        self.link = None;
+        self.link_is_text = false;
+      }
+      "a" | "url" => {
+        self.link = attribute(tag, "href");
+        self.link_is_text = self.link.is_none() && name == "url";
+      }
+      "br" => self.text("\n"),
+      "li" | "*" if !closing => {
+        self.line_break();
+        self.text("• ")
+      }
+      _ if BLOCK_TAGS.contains(&name.as_str()) => self.line_break(),
+      _ => {}
+    }
+  }
+
+  /// Starts a new line, unless already at the start of one.
+  fn line_break(&mut self) {
+    if self
+      .spans
+      .last()
+      .is_some_and(|span| !span.text.trim_end_matches(' ').ends_with('\n'))
+    {
+      self.text("\n")
+    }
+  }
+
+  fn text(&mut self, text: &str) {
+    if self.skip > 0 || text.is_empty() {
+      return;
+    }
+    if self.link_is_text {
+      self.link = Some(text.trim().to_string());
+      self.link_is_text = false;
+    }
+
+    let span = Span {
+      text: text.to_string(),
+      bold: self.bold > 0,
+      italic: self.italic > 0,
+      link: self.link.clone(),
+    };
+    match self.spans.last_mut() {
+      Some(last)
+        if (&last.link, last.bold, last.italic) == (&span.link, span.bold, span.italic) =>
+      {
+        last.text.push_str(&span.text)
+      }
+      _ => self.spans.push(span),
+    }
+  }
+
+  /// Trims whitespace at line ends and runs of blank lines left over from layout tags.
+  fn finish(self) -> Vec<Span> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut newlines = 2;
+    for mut span in self.spans {
+      let mut text = String::new();
+      for c in span.text.chars() {
+        match c {
+          '\n' if newlines < 2 => {
+            while text.ends_with(' ') {
+              text.pop();
+            }
+            newlines += 1;
+            text.push('\n')
+          }
+          '\n' => {}
+          ' ' if newlines > 0 => {}
+          c => {
+            newlines = 0;
+            text.push(c)
+          }
+        }
+      }
+      if !text.is_empty() {
+        span.text = text;
+        spans.push(span)
+      }
+    }
+    if let Some(last) = spans.last_mut() {
+      last.text.truncate(last.text.trim_end().len())
+    }
+
+    spans
+  }
+}
+
+/// Formats `spans` for a label, with links opening in the browser when clicked.
+pub fn rich_text(spans: &[Span]) -> RichText {
+  let mut builder = RichTextBuilder::new();
+  for span in spans {
+    let mut attributes = builder.push(&span.text);
+    if span.bold {
+      attributes.weight(FontWeight::BOLD);
+    }
+    if span.italic {
+      attributes.style(FontStyle::Italic);
+    }
+    if let Some(link) = &span.link {
+      attributes
+        .underline(true)
+        .text_color(BLUE_KEY)
+        .link(OPEN_IN_BROWSER.with(link.clone()));
+    }
+  }
+
+  builder.build()
+}
+
+#[cfg(test)]
+mod test {
+  use super::{first_post, parse, Span};
+
+  fn plain(spans: &[Span]) -> String {
+    spans.iter().map(|span| span.text.as_str()).collect()
+  }
+
+  #[test]
+  fn reduces_html() {
+    let spans = parse(
+      r#"<b>Example Mod</b> adds <i>ships</i>.<br /><br /><br />
+      <img src="https://example.com/banner.png" alt="" />
+      Download <a href="https://example.com/mod.zip" class="bbc_link">here</a> &amp; enjoy<br>
+      <ul class="bbc_list"><li>One</li><li>Two</li></ul>"#,
+    );
+
+    assert_eq!(
+      plain(&spans),
+      "Example Mod adds ships.\n\nDownload here & enjoy\n• One\n• Two"
+    );
+    assert!(spans[0].bold);
+    assert!(spans.iter().any(|span| span.italic && span.text == "ships"));
+    assert!(spans.iter().any(
+      |span| span.text == "here" && span.link.as_deref() == Some("https://example.com/mod.zip")
+    ));
+  }
+
+  #[test]
+  fn reduces_bbcode() {
+    let spans = parse(
+      "[center][size=5][b]Example[/b][/size][/center]\n[img]https://example.com/a.png[/img]\
+       [url=https://example.com]Site[/url] and [url]https://example.com/b[/url] [not a tag]",
+    );
+
+    assert_eq!(
+      plain(&spans),
+      "Example\nSite and https://example.com/b [not a tag]"
+    );
+    assert!(spans
+      .iter()
+      .any(|span| span.text == "Site" && span.link.as_deref() == Some("https://example.com")));
+    assert!(spans.iter().any(|span| span.text == "https://example.com/b"
+      && span.link.as_deref() == Some("https://example.com/b")));
+  }
+
+  #[test]
+  fn finds_opening_post() {
+    let page = r#"<div id="forumposts"><div class="post_wrapper"><div class="post">
+      <div class="inner" data-msgid="1" id="msg_1">First <div class="quote">nested</div> post</div>
+      </div></div><div class="post"><div class="inner" id="msg_2">Reply</div></div></div>"#;
+
+    assert_eq!(
+      first_post(page),
+      Some(r#"First <div class="quote">nested</div> post"#)
+    );
+  }
+}
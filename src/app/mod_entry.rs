@@ -11,8 +11,8 @@ use chrono::{DateTime, Local, Utc};
 use druid::{
   im::Vector,
   lens,
-  widget::{Button, Checkbox, Controller, Either, Flex, Label, ViewSwitcher},
-  Color, Data, ExtEventSink, KeyOrValue, Lens, LensExt, Selector, Widget, WidgetExt,
+  widget::{Button, Checkbox, Controller, Either, Flex, Label, SizedBox, Spinner, ViewSwitcher},
+  Color, Data, Env, EventCtx, ExtEventSink, KeyOrValue, Lens, LensExt, Selector, Widget, WidgetExt,
 };
 use druid_widget_nursery::{material_icons::Icon, WidgetExt as WidgetExtNursery};
 use json_comments::strip_comments;
@@ -20,24 +20,37 @@ use serde::{Deserialize, Serialize};
 
 use serde_aux::prelude::*;
 use tap::Tap;
+use tracing::warn;
 
 use crate::{
   app::{
-    controllers::ModEntryClickController,
-    util::{default_true, parse_game_version, LabelExt},
+    controllers::{HoverController, ModEntryClickController},
+    i18n::{tr, tr_args},
+    modal::Modal,
+    util::{default_true, dir_size, format_size, parse_game_version, time_since, LabelExt},
     App, AppCommands,
   },
   patch::split::Split,
 };
 
+use self::{loose_version::LooseVersion, parse_cache::ParseCache, thumbnail::Thumbnail};
 use super::{
+  mod_description::{forum_post::ForumPost, ModDescription, OPEN_IN_BROWSER},
   mod_list::headings::{self, Heading},
+  mod_repo::{ModRepo, RepoRecord},
   util::{
     self, icons::*, BLUE_KEY, GREEN_KEY, ON_BLUE_KEY, ON_GREEN_KEY, ON_ORANGE_KEY, ON_RED_KEY,
     ON_YELLOW_KEY, ORANGE_KEY, RED_KEY, YELLOW_KEY,
   },
 };
 
+pub mod config_text;
+mod loose_version;
+pub mod mod_info_edit;
+pub mod mod_info_editor;
+pub mod parse_cache;
+pub mod thumbnail;
+
 pub type GameVersion = (
   Option<String>,
   Option<String>,
@@ -54,7 +67,7 @@ pub struct ModEntry {
   pub version: VersionUnion,
   description: String,
   #[serde(alias = "gameVersion")]
-  raw_game_version: String,
+  pub raw_game_version: String,
   #[serde(skip)]
   pub game_version: GameVersion,
   #[serde(skip)]
@@ -63,6 +76,10 @@ pub struct ModEntry {
   highlighted: bool,
   #[serde(skip)]
   pub version_checker: Option<ModVersionMeta>,
+  /// The file `version_checker` was read from, relative to the mod folder.
+  #[serde(skip)]
+  #[data(same_fn = "PartialEq::eq")]
+  version_file: Option<PathBuf>,
   #[serde(skip)]
   pub remote_version: Option<ModVersionMeta>,
   #[serde(skip)]
@@ -75,6 +92,56 @@ pub struct ModEntry {
   display: bool,
   #[serde(skip)]
   pub manager_metadata: ModMetadata,
+  /// Total size of the mod folder in bytes.
+  #[serde(skip)]
+  pub size: u64,
+  /// This mod's entry in the mod repo, if it has one.
+  #[serde(skip)]
+  pub repo_record: Option<RepoRecord>,
+  /// Whether this mod lives in an external mod folder rather than the game's mods folder.
+  #[serde(skip)]
+  pub external: bool,
+  /// Mods that must be enabled for this one to work.
+  #[serde(default)]
+  #[data(same_fn = "PartialEq::eq")]
+  pub dependencies: Vec<Dependency>,
+  /// The opening post of this mod's forum thread, when shown in place of its description.
+  #[serde(skip)]
+  #[data(same_fn = "PartialEq::eq")]
+  pub forum_post: Option<ForumPost>,
+  /// The mod's config files, once they've been looked for.
+  #[serde(skip)]
+  #[data(same_fn = "PartialEq::eq")]
+  pub config_files: Option<Arc<[PathBuf]>>,
+  /// Whether another mod in the list has the same ID, of which only one can be enabled.
+  #[serde(skip)]
+  pub id_collision: bool,
+  /// Why the last check of the mod's remote version file failed, if it did.
+  #[serde(skip)]
+  pub update_error: Option<String>,
+  /// Set while a version check asked for from this mod's menu or details is running.
+  #[serde(skip)]
+  pub checking_update: bool,
+  /// Why the compatibility list advises against this mod, if it does.
+  #[serde(skip)]
+  pub compat_warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Default)]
+pub struct Dependency {
+  pub id: String,
+  #[serde(default)]
+  pub name: String,
+}
+
+impl Dependency {
+  pub fn display_name(&self) -> &str {
+    if self.name.is_empty() {
+      &self.id
+    } else {
+      &self.name
+    }
+  }
 }
 
 impl ModEntry {
@@ -82,41 +149,193 @@ impl ModEntry {
   pub const REPLACE: Selector<Arc<ModEntry>> = Selector::new("MOD_ENTRY_REPLACE");
   pub const AUTO_UPDATE: Selector<Arc<ModEntry>> = Selector::new("mod_list.update.auto");
   pub const ASK_DELETE_MOD: Selector<Arc<ModEntry>> = Selector::new("mod_entry.delete");
+  /// Checks the mod's remote version file again.
+  pub const CHECK_FOR_UPDATE: Selector<Arc<ModEntry>> = Selector::new("mod_entry.update.check");
 
   pub fn from_file(path: &Path, manager_metadata: ModMetadata) -> Result<ModEntry, ModEntryError> {
-    if let Ok(mod_info_file) = std::fs::read_to_string(path.join("mod_info.json")) {
+    ModEntry::from_file_cached(path, manager_metadata, &ParseCache::default())
+  }
+
+  /// Like [`ModEntry::from_file`], but reuses what `cache` has for a mod that hasn't changed, and
+  /// adds it to `cache` otherwise.
+  pub fn from_file_cached(
+    path: &Path,
+    manager_metadata: ModMetadata,
+    cache: &ParseCache,
+  ) -> Result<ModEntry, ModEntryError> {
+    let mod_info_path = path.join("mod_info.json");
+    let with_path = |err: &dyn Display| format!("{}: {}", mod_info_path.to_string_lossy(), err);
+
+    let stamp = parse_cache::stamp(path);
+    let (raw, size, version_files) = if let Some(cached) = cache.get(path, stamp) {
+      (cached.mod_info, cached.size, cached.version_files)
+    } else {
+      let mod_info_file = config_text::read(&mod_info_path)
+        .map_err(|err| ModEntryError::FileError(with_path(&err)))?;
+
       let mut stripped = String::new();
-      if strip_comments(mod_info_file.as_bytes()).read_to_string(&mut stripped).is_ok()
-        && let Ok(mut mod_info) = json5::from_str::<ModEntry>(&stripped)
-      {
-        mod_info.version_checker = ModEntry::parse_version_checker(path, &mod_info.id);
-        mod_info.path = path.to_path_buf();
-        mod_info.game_version = parse_game_version(&mod_info.raw_game_version);
-        mod_info.manager_metadata = manager_metadata;
-        Ok(mod_info)
-      } else {
-        Err(ModEntryError::ParseError)
+      strip_comments(mod_info_file.as_bytes())
+        .read_to_string(&mut stripped)
+        .map_err(|err| ModEntryError::ParseError(with_path(&err)))?;
+      let raw = json5::from_str::<serde_json::Value>(&stripped)
+        .map_err(|err| ModEntryError::ParseError(with_path(&describe_json5_error(err))))?;
+      let size = dir_size(path);
+      let version_files = ModEntry::version_file_paths(path);
+      cache.insert(path, stamp, raw.clone(), size, version_files.clone());
+
+      (raw, size, version_files)
+    };
+    let mut mod_info = serde_json::from_value::<ModEntry>(raw)
+      .map_err(|err| ModEntryError::ParseError(with_path(&err)))?;
+
+    if let Some((version_file, version_checker)) =
+      ModEntry::parse_version_checker(version_files, &mod_info.id)
+    {
+      mod_info.version_file = version_file.strip_prefix(path).ok().map(Path::to_path_buf);
+      mod_info.version_checker = Some(version_checker);
+    }
+    mod_info.path = path.to_path_buf();
+    mod_info.game_version = parse_game_version(&mod_info.raw_game_version);
+    mod_info.manager_metadata = manager_metadata;
+    mod_info.size = size;
+    mod_info.repo_record = ModRepo::record_for(&mod_info);
+    Ok(mod_info)
+  }
+
+  /// The mod's version files, up to the first that can be read - those named in its
+  /// `version_files.csv` first, then any other `.version` file in the mod folder, for mods that
+  /// put theirs somewhere unusual. The folder is only searched until a readable one is found.
+  fn version_file_paths(path: &Path) -> Vec<PathBuf> {
+    const MAX_DEPTH: usize = 4;
+
+    /// Returns whether a readable version file has been found.
+    fn find_version_files(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) -> bool {
+      for entry in dir.read_dir().into_iter().flatten().flatten() {
+        let entry_path = entry.path();
+        if entry_path.is_dir() {
+          if depth < MAX_DEPTH && find_version_files(&entry_path, depth + 1, found) {
+            return true;
+          }
+        } else if entry_path
+          .extension()
+          .is_some_and(|ext| ext.eq_ignore_ascii_case("version"))
+          && !found.contains(&entry_path)
+        {
+          let readable = ModEntry::read_version_file(&entry_path).is_ok();
+          found.push(entry_path);
+          if readable {
+            return true;
+          }
+        }
       }
-    } else {
-      Err(ModEntryError::FileError)
+
+      false
     }
+
+    let listed: Vec<PathBuf> = File::open(
+      path
+        .join("data")
+        .join("config")
+        .join("version")
+        .join("version_files.csv"),
+    )
+    .map(|file| {
+      BufReader::new(file)
+        .lines()
+        .skip(1)
+        .map_while(Result::ok)
+        .filter_map(|line| {
+          let file_name = line.split(',').next()?.trim().to_string();
+          (!file_name.is_empty()).then(|| path.join(file_name))
+        })
+        .filter(|version_path| version_path.is_file())
+        .collect()
+    })
+    .unwrap_or_default();
+    let mut paths = Vec::new();
+    for version_path in listed {
+      let readable = ModEntry::read_version_file(&version_path).is_ok();
+      paths.push(version_path);
+      if readable {
+        return paths;
+      }
+    }
+    find_version_files(path, 0, &mut paths);
+
+    paths
   }
 
-  fn parse_version_checker(path: &Path, id: &str) -> Option<ModVersionMeta> {
-    let mut no_comments = String::new();
-    if let Ok(version_loc_file) = File::open(path.join("data").join("config").join("version").join("version_files.csv"))
-      && let Some(Ok(version_filename)) = BufReader::new(version_loc_file).lines().nth(1)
-      && let Some(version_filename) = version_filename.split(',').next()
-      && let Ok(version_data) = std::fs::read_to_string(path.join(version_filename))
-      && strip_comments(version_data.as_bytes()).read_to_string(&mut no_comments).is_ok()
-      && let Ok(normalized) = handwritten_json::normalize(&no_comments)
-      && let Ok(mut version) = json5::from_str::<ModVersionMeta>(&normalized)
-    {
-      version.id = id.to_string();
-      Some(version)
-    } else {
-      None
+  /// The version file the mod's version checker was read from, if it has one.
+  pub fn version_file(&self) -> Option<PathBuf> {
+    self.version_file.as_ref().map(|file| self.path.join(file))
+  }
+
+  fn read_version_file(version_path: &Path) -> Result<ModVersionMeta, String> {
+    ModVersionMeta::parse(&config_text::read(version_path)?)
+  }
+
+  /// The first of `version_paths` that can be read, and what's in it.
+  fn parse_version_checker(
+    version_paths: Vec<PathBuf>,
+    id: &str,
+  ) -> Option<(PathBuf, ModVersionMeta)> {
+    let mut first_err = None;
+    for version_path in version_paths {
+      match ModEntry::read_version_file(&version_path) {
+        Ok(mut version) => {
+          version.id = id.to_string();
+          return Some((version_path, version));
+        }
+        Err(err) => {
+          first_err.get_or_insert((version_path, err));
+        }
+      }
+    }
+
+    if let Some((version_path, err)) = first_err {
+      warn!(
+        "Failed to read version file {}: {}",
+        version_path.to_string_lossy(),
+        err
+      );
     }
+    None
+  }
+
+  /// Rewrites the version and game version in this mod's `mod_info.json` without disturbing the
+  /// rest of the file, then re-reads the entry from disk.
+  pub fn write_versions(
+    &self,
+    version: &str,
+    game_version: &str,
+  ) -> Result<ModEntry, ModEntryError> {
+    let mod_info_path = self.path.join("mod_info.json");
+    let with_path = |err: &dyn Display| format!("{}: {}", mod_info_path.to_string_lossy(), err);
+    let mod_info_file =
+      config_text::read(&mod_info_path).map_err(|err| ModEntryError::FileError(with_path(&err)))?;
+
+    let edited = mod_info_edit::set_version(&mod_info_file, version)
+      .and_then(|edited| mod_info_edit::set_string(&edited, "gameVersion", game_version))
+      .ok_or_else(|| ModEntryError::ParseError(with_path(&"could not find version fields")))?;
+    std::fs::write(&mod_info_path, edited)
+      .map_err(|err| ModEntryError::FileError(with_path(&err)))?;
+
+    self.reload()
+  }
+
+  /// Re-reads the entry from disk after its files have been edited, keeping what was known about
+  /// it that isn't in them.
+  pub fn reload(&self) -> Result<ModEntry, ModEntryError> {
+    let mut entry = ModEntry::from_file(&self.path, self.manager_metadata.clone())?;
+    entry.enabled = self.enabled;
+    entry.remote_version = self.remote_version.clone();
+    entry.update_status = entry
+      .version_checker
+      .as_ref()
+      .filter(|_| entry.remote_version.is_some())
+      .map(|local| UpdateStatus::from((local, &entry.remote_version)));
+
+    Ok(entry)
   }
 
   pub fn set_enabled(&mut self, enabled: bool) {
@@ -156,17 +375,45 @@ impl ModEntry {
             header @ Heading::ID | header @ Heading::Name | header @ Heading::Author => {
               let label = Label::wrapped_func(|text: &String, _| text.to_string());
               match header {
-                Heading::ID => label.lens(ModEntry::id.in_arc()).padding(5.).expand_width(),
-                Heading::Name => label
-                  .lens(ModEntry::name.in_arc())
+                Heading::ID => label
+                  .lens(ModEntry::id.in_arc())
                   .padding(5.)
-                  .expand_width(),
+                  .expand_width()
+                  .boxed(),
+                Heading::Name => Flex::row()
+                  .with_child(Thumbnail::icon(20.))
+                  .with_flex_child(label.lens(ModEntry::name.in_arc()), 1.)
+                  .with_child(Either::new(
+                    |entry: &Arc<ModEntry>, _| entry.id_collision,
+                    Icon::new(REPORT)
+                      .stack_tooltip(
+                        "Another mod has the same ID\n\
+                        Only one of them can be enabled at a time, enabling this one disables \
+                        the other.",
+                      )
+                      .with_crosshair(true),
+                    SizedBox::empty(),
+                  ))
+                  .with_child(ViewSwitcher::new(
+                    |entry: &Arc<ModEntry>, _| entry.compat_warning.clone(),
+                    |warning, _, _| match warning {
+                      Some(warning) => Icon::new(WARNING)
+                        .stack_tooltip(warning.clone())
+                        .with_crosshair(true)
+                        .boxed(),
+                      None => SizedBox::empty().boxed(),
+                    },
+                  ))
+                  .padding(5.)
+                  .expand_width()
+                  .boxed(),
                 Heading::Author => label
                   .lens(ModEntry::author.in_arc())
                   .padding(5.)
-                  .expand_width(),
+                  .expand_width()
+                  .boxed(),
                 _ => unreachable!(),
-              }.boxed()
+              }
             }
             Heading::GameVersion => Label::wrapped_func(|version: &GameVersion, _| {
               util::get_quoted_version(version).unwrap_or_default()
@@ -176,7 +423,13 @@ impl ModEntry {
             .expand_width()
             .boxed(),
             Heading::Version => ViewSwitcher::new(
-              |entry: &Arc<ModEntry>, _| entry.update_status.clone(),
+              |entry: &Arc<ModEntry>, _| {
+                (
+                  entry.update_status.clone(),
+                  entry.update_ignored(),
+                  entry.checking_update,
+                )
+              },
               |_, data, env| {
                 let color = data
                   .update_status
@@ -193,6 +446,10 @@ impl ModEntry {
                     )
                     .with_flex_spacer(1.)
                     .tap_mut(|row| {
+                      if data.checking_update {
+                        row.add_child(Spinner::new());
+                        return;
+                      }
                       let mut icon_row = Flex::row();
                       let mut iter = 0;
 
@@ -212,24 +469,40 @@ impl ModEntry {
 
                       if let Some(update_status) = &data.update_status {
                         let tooltip = match update_status {
-                          UpdateStatus::Error => "Error\nThere was an error retrieving or parsing this mod's version information.".to_string(),
+                          UpdateStatus::Error => tr("update-tooltip-error"),
                           UpdateStatus::UpToDate => update_status.to_string(),
-                          UpdateStatus::Discrepancy(_) => "\
-                            Discrepancy\n\
-                            The installed version of this mod is higher than the version available from the server.\n\
-                            This usually means the mod author has forgotten to update their remote version file and is not a cause for alarm.\
-                          ".to_string(),
-                          _ => update_status.to_string()
+                          UpdateStatus::Discrepancy(_) => tr("update-tooltip-discrepancy"),
+                          _ if data.auto_updatable() => {
+                            tr_args("update-tooltip-auto-update", &[("status", update_status)])
+                          }
+                          _ => {
+                            tr_args("update-tooltip-download-page", &[("status", update_status)])
+                          }
+                        };
+                        let tooltip = if data.update_ignored() {
+                          tr_args("update-tooltip-ignored", &[("tooltip", &tooltip)])
+                        } else {
+                          tooltip
                         };
                         let text_color = color.clone();
                         let background_color =
                           <KeyOrValue<Color>>::from(update_status).resolve(env);
-                        row.add_child(
-                          icon_row.stack_tooltip(tooltip)
-                            .with_text_attribute(druid::text::Attribute::TextColor(text_color))
-                            .with_background_color(background_color)
-                            .with_crosshair(true)
-                        )
+                        let cell = icon_row
+                          .stack_tooltip(tooltip)
+                          .with_text_attribute(druid::text::Attribute::TextColor(text_color))
+                          .with_background_color(background_color)
+                          .with_crosshair(true);
+                        let actionable = update_status == &UpdateStatus::Error
+                          || data.available_update().is_some();
+                        if actionable {
+                          row.add_child(cell.controller(HoverController).on_activate(
+                            |ctx, data: &mut Arc<ModEntry>, env| {
+                              ModEntry::status_clicked(ctx, data, env)
+                            },
+                          ))
+                        } else {
+                          row.add_child(cell)
+                        }
                       } else {
                         row.add_child(icon_row)
                       }
@@ -241,35 +514,83 @@ impl ModEntry {
             .expand_width()
             .boxed(),
             Heading::AutoUpdateSupport => Either::new(
-              |entry: &Arc<ModEntry>, _| entry.remote_version
-                .as_ref()
-                .and_then(|r| r.direct_download_url.as_ref())
-                .is_some(),
+              |entry: &Arc<ModEntry>, _| {
+                entry
+                  .remote_version
+                  .as_ref()
+                  .and_then(|r| r.direct_download_url.as_ref())
+                  .is_some()
+              },
               Either::new(
-                |entry: &Arc<ModEntry>, _| entry.update_status.as_ref().is_some_and(|status| status != &UpdateStatus::Error),
+                |entry: &Arc<ModEntry>, _| {
+                  entry
+                    .update_status
+                    .as_ref()
+                    .is_some_and(|status| status != &UpdateStatus::Error)
+                },
                 Either::new(
-                  |entry: &Arc<ModEntry>, _| entry.update_status.as_ref().is_some_and(|status| !matches!(status, &UpdateStatus::UpToDate | &UpdateStatus::Discrepancy(_))),
-                  Button::from_label(Label::wrapped("Update available!")).on_click(
+                  |entry: &Arc<ModEntry>, _| {
+                    entry.update_status.as_ref().is_some_and(|status| {
+                      !matches!(
+                        status,
+                        &UpdateStatus::UpToDate | &UpdateStatus::Discrepancy(_)
+                      )
+                    })
+                  },
+                  Button::from_label(Label::wrapped(tr("update-available"))).on_click(
                     |ctx: &mut druid::EventCtx, data: &mut Arc<ModEntry>, _| {
                       ctx.submit_notification(ModEntry::AUTO_UPDATE.with(data.clone()))
                     },
                   ),
-                  Label::wrapped("No update available")),
-                Label::wrapped("Unsupported")),
-              Label::wrapped("Unsupported"),
+                  Label::wrapped(tr("update-none-available")),
+                ),
+                Label::wrapped(tr("update-unsupported")),
+              ),
+              Label::wrapped(tr("update-unsupported")),
             )
             .padding(5.)
             .expand_width()
             .boxed(),
-            Heading::InstallDate => Label::wrapped_func(|data: &ModMetadata, _| if let Some(date) = data.install_date {
-                DateTime::<Local>::from(date).format("%v %I:%M%p").to_string()
+            Heading::InstallDate => Label::wrapped_func(|data: &ModMetadata, _| {
+              if let Some(date) = data.install_date {
+                DateTime::<Local>::from(date)
+                  .format("%v %I:%M%p")
+                  .to_string()
               } else {
-                String::from("Unknown")
-              })
-              .lens(ModEntry::manager_metadata.in_arc())
+                tr("unknown")
+              }
+            })
+            .lens(ModEntry::manager_metadata.in_arc())
+            .padding(5.)
+            .expand_width()
+            .boxed(),
+            Heading::LastUpdated => Label::wrapped_func(|data: &Arc<ModEntry>, _| {
+              data
+                .repo_record
+                .as_ref()
+                .and_then(|record| record.updated)
+                .map_or_else(
+                  || tr("unknown"),
+                  |date| DateTime::<Local>::from(date).format("%v").to_string(),
+                )
+            })
+            .padding(5.)
+            .expand_width()
+            .boxed(),
+            Heading::Size => Label::wrapped_func(|data: &Arc<ModEntry>, _| format_size(data.size))
               .padding(5.)
               .expand_width()
               .boxed(),
+            Heading::AuthorActivity => Label::wrapped_func(|data: &Arc<ModEntry>, _| {
+              data
+                .repo_record
+                .as_ref()
+                .and_then(|record| record.author_activity)
+                .map_or_else(|| tr("unknown"), time_since)
+            })
+            .padding(5.)
+            .expand_width()
+            .boxed(),
             Heading::Enabled | Heading::Score => continue,
           };
 
@@ -299,7 +620,7 @@ impl ModEntry {
         .on_click(
           |ctx: &mut druid::EventCtx, data: &mut Arc<ModEntry>, _env: &druid::Env| {
             ctx.submit_command(
-              App::SELECTOR.with(AppCommands::UpdateModDescription(data.id.clone())),
+              App::SELECTOR.with(AppCommands::UpdateModDescription(data.path.clone())),
             )
           },
         )
@@ -314,6 +635,97 @@ impl ModEntry {
   pub fn set_path(&mut self, path: PathBuf) {
     self.path = path;
   }
+
+  /// The available update's version, if there is one.
+  pub fn available_update(&self) -> Option<String> {
+    matches!(
+      self.update_status,
+      Some(UpdateStatus::Major(_) | UpdateStatus::Minor(_) | UpdateStatus::Patch(_))
+    )
+    .then(|| {
+      self
+        .remote_version
+        .as_ref()
+        .map(|remote| remote.version.to_string())
+    })
+    .flatten()
+  }
+
+  /// The forum thread or Nexus Mods page the mod can be downloaded from, if it has one.
+  pub fn download_page(&self) -> Option<String> {
+    let meta = self
+      .remote_version
+      .as_ref()
+      .or(self.version_checker.as_ref())?;
+    if !meta.fractal_id.is_empty() {
+      Some(format!(
+        "{}{}",
+        ModDescription::FRACTAL_URL,
+        meta.fractal_id
+      ))
+    } else if !meta.nexus_id.is_empty() {
+      Some(format!("{}{}", ModDescription::NEXUS_URL, meta.nexus_id))
+    } else {
+      None
+    }
+  }
+
+  /// Acts on the mod's update status: offers to install an available update, or opens its
+  /// download page if it can't be installed directly, and explains a failed version check with
+  /// the option to try again.
+  fn status_clicked(ctx: &mut EventCtx, entry: &Arc<ModEntry>, env: &Env) {
+    match entry.update_status {
+      Some(UpdateStatus::Major(_) | UpdateStatus::Minor(_) | UpdateStatus::Patch(_)) => {
+        if entry.auto_updatable() {
+          ctx.submit_notification(ModEntry::AUTO_UPDATE.with(entry.clone()))
+        } else if let Some(url) = entry.download_page() {
+          ctx.submit_command(OPEN_IN_BROWSER.with(url))
+        }
+      }
+      Some(UpdateStatus::Error) => {
+        Modal::new(&tr("version-check-failed-title"))
+          .with_content(tr_args("version-check-failed", &[("name", &entry.name)]))
+          .with_content(
+            entry
+              .update_error
+              .clone()
+              .unwrap_or_else(|| tr("version-check-failed-unknown")),
+          )
+          .with_button(
+            &tr("version-check-retry"),
+            ModEntry::CHECK_FOR_UPDATE.with(entry.clone()),
+          )
+          .with_close_label(&tr("close"))
+          .show_with_size(ctx, env, &(), (500., 250.));
+      }
+      _ => {}
+    }
+  }
+
+  /// Whether the mod has a direct download the mod manager can update it from.
+  pub fn auto_updatable(&self) -> bool {
+    self
+      .remote_version
+      .as_ref()
+      .is_some_and(|remote| remote.direct_download_url.is_some())
+  }
+
+  /// Whether the mod has an update available that the user has asked to be installed without
+  /// asking.
+  pub fn auto_update_due(&self) -> bool {
+    self.manager_metadata.auto_update
+      && self.auto_updatable()
+      && self.available_update().is_some()
+      && !self.update_ignored()
+  }
+
+  /// Whether the user has asked not to be told about this mod's available update, either by
+  /// pinning the mod or by ignoring that particular version.
+  pub fn update_ignored(&self) -> bool {
+    self.manager_metadata.pinned
+      || (self.manager_metadata.ignored_version.is_some()
+        && self.available_update() == self.manager_metadata.ignored_version)
+  }
 }
 
 struct RowController {
@@ -348,7 +760,7 @@ impl Controller<Arc<ModEntry>, Split<Arc<ModEntry>>> for RowController {
   }
 }
 
-#[derive(Debug, Clone, Deserialize, Data, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Deserialize, Data, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum VersionUnion {
   String(String),
@@ -366,6 +778,13 @@ impl Display for VersionUnion {
   }
 }
 
+impl VersionUnion {
+  /// This version, for comparing with others.
+  pub fn loose(&self) -> LooseVersion {
+    LooseVersion::parse(&self.to_string())
+  }
+}
+
 impl From<VersionUnion> for String {
   fn from(version_union: VersionUnion) -> Self {
     version_union.to_string()
@@ -378,9 +797,33 @@ impl Default for VersionUnion {
   }
 }
 
+#[derive(Debug)]
 pub enum ModEntryError {
-  ParseError,
-  FileError,
+  ParseError(String),
+  FileError(String),
+}
+
+/// json5's errors only say where they happened in their location, not their message.
+pub fn describe_json5_error(err: json5::Error) -> String {
+  match err {
+    json5::Error::Message {
+      msg,
+      location: Some(location),
+    } => format!(
+      "line {}, column {}: {}",
+      location.line, location.column, msg
+    ),
+    json5::Error::Message { msg, .. } => msg,
+  }
+}
+
+impl Display for ModEntryError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ModEntryError::ParseError(detail) => write!(f, "Failed to parse {}", detail),
+      ModEntryError::FileError(detail) => write!(f, "Failed to read {}", detail),
+    }
+  }
 }
 
 #[derive(Debug, Clone, Deserialize, Eq, Data, Lens)]
@@ -402,6 +845,28 @@ pub struct ModVersionMeta {
   pub nexus_id: String,
   #[serde(alias = "modVersion")]
   pub version: Version,
+  #[serde(alias = "changelogURL")]
+  #[serde(default)]
+  pub changelog_url: Option<String>,
+  /// The line of the changelog that suggests this version isn't save compatible, if any.
+  #[serde(skip)]
+  pub save_warning: Option<String>,
+}
+
+impl ModVersionMeta {
+  /// Parses the text of a version file. Files are normalised first to cope with the odd things
+  /// people write by hand, falling back to plain JSON5 (comments, trailing commas) if that fails.
+  pub fn parse(text: &str) -> Result<Self, String> {
+    let mut no_comments = String::new();
+    strip_comments(text.as_bytes())
+      .read_to_string(&mut no_comments)
+      .map_err(|err| err.to_string())?;
+
+    handwritten_json::normalize(&no_comments)
+      .map_err(|err| err.to_string())
+      .and_then(|normalized| json5::from_str::<Self>(&normalized).map_err(|err| err.to_string()))
+      .or_else(|err| json5::from_str::<Self>(&no_comments).map_err(|_| err))
+  }
 }
 
 impl PartialEq for ModVersionMeta {
@@ -422,17 +887,40 @@ impl Ord for ModVersionMeta {
   }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq, PartialOrd, Ord, Data, Lens)]
+#[derive(Debug, Clone, Deserialize, Data, Lens)]
 pub struct Version {
+  #[serde(alias = "majorVersion")]
   #[serde(deserialize_with = "deserialize_number_from_string")]
   pub major: i32,
+  #[serde(alias = "minorVersion")]
   #[serde(deserialize_with = "deserialize_number_from_string")]
   pub minor: i32,
+  #[serde(alias = "patchVersion")]
   #[serde(default)]
   #[serde(deserialize_with = "deserialize_string_from_number")]
   pub patch: String,
 }
 
+impl Ord for Version {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    LooseVersion::parse(&self.to_string()).cmp(&LooseVersion::parse(&other.to_string()))
+  }
+}
+
+impl PartialOrd for Version {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl PartialEq for Version {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == std::cmp::Ordering::Equal
+  }
+}
+
+impl Eq for Version {}
+
 impl Display for Version {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
     if !self.patch.is_empty() {
@@ -455,14 +943,16 @@ pub enum UpdateStatus {
 
 impl Display for UpdateStatus {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-    match self {
-      UpdateStatus::Major(remote) => write!(f, "Major update available: {}", remote),
-      UpdateStatus::Minor(remote) => write!(f, "Minor update available: {}", remote),
-      UpdateStatus::Patch(remote) => write!(f, "Patch available: {}", remote),
-      UpdateStatus::UpToDate => write!(f, "Up to date"),
-      UpdateStatus::Error => write!(f, "Error"),
-      UpdateStatus::Discrepancy(_) => write!(f, "Discrepancy"),
-    }
+    let text = match self {
+      UpdateStatus::Major(remote) => tr_args("update-status-major", &[("version", remote)]),
+      UpdateStatus::Minor(remote) => tr_args("update-status-minor", &[("version", remote)]),
+      UpdateStatus::Patch(remote) => tr_args("update-status-patch", &[("version", remote)]),
+      UpdateStatus::UpToDate => tr("update-status-up-to-date"),
+      UpdateStatus::Error => tr("update-status-error"),
+      UpdateStatus::Discrepancy(_) => tr("update-status-discrepancy"),
+    };
+
+    f.write_str(&text)
   }
 }
 
@@ -517,22 +1007,83 @@ impl UpdateStatus {
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Data, Lens, Default)]
 pub struct ModMetadata {
+  /// When the installed version of the mod was installed
   #[data(same_fn = "PartialEq::eq")]
   pub install_date: Option<DateTime<Utc>>,
+  /// When the mod was first installed, before any updates
+  #[serde(default)]
+  #[data(same_fn = "PartialEq::eq")]
+  pub first_install_date: Option<DateTime<Utc>>,
+  /// Never notify about updates to this mod
+  #[serde(default)]
+  pub pinned: bool,
+  /// Don't notify about this remote version, later versions are still notified about
+  #[serde(default)]
+  pub ignored_version: Option<String>,
+  /// The URL or archive the mod was installed from
+  #[serde(default)]
+  pub source: Option<String>,
+  /// Install updates found by update checks without asking, for mods with a direct download
+  #[serde(default)]
+  pub auto_update: bool,
 }
 
 impl ModMetadata {
-  const FILE_NAME: &'static str = ".moss";
+  pub const FILE_NAME: &'static str = ".moss";
 
-  pub const SUBMIT_MOD_METADATA: Selector<(String, ModMetadata)> =
+  pub const SUBMIT_MOD_METADATA: Selector<(PathBuf, ModMetadata)> =
     Selector::new("mod_metadata.submit");
+  /// Replaces the metadata of the mod at the given path and writes it to the mod's folder.
+  pub const SAVE_MOD_METADATA: Selector<(PathBuf, ModMetadata)> =
+    Selector::new("mod_metadata.save");
 
   pub fn new() -> Self {
+    let now = Some(Utc::now());
+
     Self {
-      install_date: Some(Utc::now()),
+      install_date: now,
+      first_install_date: now,
+      ..Default::default()
     }
   }
 
+  pub fn with_source(source: impl Into<String>) -> Self {
+    Self {
+      source: Some(source.into()),
+      ..Self::new()
+    }
+  }
+
+  /// Carries the user's update notification choices over from a previous install of the mod,
+  /// along with when it was first installed.
+  pub fn keep_preferences(&mut self, previous: &ModMetadata) {
+    self.first_install_date = previous.first_installed();
+    self.pinned = previous.pinned;
+    self.ignored_version = previous.ignored_version.clone();
+    self.auto_update = previous.auto_update;
+  }
+
+  /// Metadata written before the first install date was recorded only has the latest one.
+  pub fn first_installed(&self) -> Option<DateTime<Utc>> {
+    self.first_install_date.or(self.install_date)
+  }
+
+  /// Whether the mod was first installed in the last `days` days.
+  pub fn installed_within(&self, days: i64) -> bool {
+    self
+      .first_installed()
+      .is_some_and(|date| Utc::now() - date <= chrono::Duration::days(days))
+  }
+
+  /// Whether the mod was updated, rather than installed for the first time, in the last `days`
+  /// days.
+  pub fn updated_within(&self, days: i64) -> bool {
+    self.first_installed() != self.install_date
+      && self
+        .install_date
+        .is_some_and(|date| Utc::now() - date <= chrono::Duration::days(days))
+  }
+
   pub fn path(parent: impl AsRef<Path>) -> PathBuf {
     parent.as_ref().join(Self::FILE_NAME)
   }
@@ -547,11 +1098,15 @@ impl ModMetadata {
     Ok(metadata)
   }
 
-  pub async fn parse_and_send(id: String, mod_folder: impl AsRef<Path>, ext_ctx: ExtEventSink) {
+  pub async fn parse_and_send(mod_folder: PathBuf, ext_ctx: ExtEventSink) {
     use druid::Target;
 
-    if let Ok(mod_metadata) = Self::parse(mod_folder).await {
-      let _ = ext_ctx.submit_command(Self::SUBMIT_MOD_METADATA, (id, mod_metadata), Target::Auto);
+    if let Ok(mod_metadata) = Self::parse(&mod_folder).await {
+      let _ = ext_ctx.submit_command(
+        Self::SUBMIT_MOD_METADATA,
+        (mod_folder, mod_metadata),
+        Target::Auto,
+      );
     }
   }
 
@@ -567,3 +1122,69 @@ impl ModMetadata {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod test {
+  use chrono::{Duration, Utc};
+
+  use super::{ModEntry, ModMetadata, ModVersionMeta, UpdateStatus};
+
+  #[test]
+  fn tells_updates_from_installs() {
+    let mut metadata = ModMetadata::new();
+    assert!(metadata.installed_within(7));
+    assert!(!metadata.updated_within(7));
+
+    let previous = ModMetadata {
+      install_date: Some(Utc::now() - Duration::days(40)),
+      ..Default::default()
+    };
+    metadata.keep_preferences(&previous);
+    assert!(!metadata.installed_within(30));
+    assert!(metadata.updated_within(7));
+  }
+
+  #[test]
+  fn parses_nonstandard_version_files() {
+    let version = ModVersionMeta::parse(
+      r#"{
+        # a comment
+        "masterVersionFile": "https://example.com/mod.version",
+        "modName": "Example",
+        "modThreadId": 12345,
+        "modVersion": { "majorVersion": 1, "minorVersion": "2", "patchVersion": 3, },
+      }"#,
+    )
+    .expect("Parse version file");
+
+    assert_eq!(version.remote_url, "https://example.com/mod.version");
+    assert_eq!(version.fractal_id, "12345");
+    assert_eq!(version.version.to_string(), "1.2.3");
+  }
+
+  #[test]
+  fn ignores_pinned_and_ignored_updates() {
+    let remote = ModVersionMeta::parse(
+      r#"{
+        "masterVersionFile": "",
+        "modName": "Example",
+        "modVersion": { "major": 1, "minor": 1, "patch": 0 },
+      }"#,
+    )
+    .expect("Parse version file");
+    let mut entry = ModEntry::default();
+    entry.update_status = Some(UpdateStatus::Minor(remote.version.clone()));
+    entry.remote_version = Some(remote);
+    assert_eq!(entry.available_update().as_deref(), Some("1.1.0"));
+    assert!(!entry.update_ignored());
+
+    entry.manager_metadata.ignored_version = Some(String::from("1.0.5"));
+    assert!(!entry.update_ignored());
+    entry.manager_metadata.ignored_version = Some(String::from("1.1.0"));
+    assert!(entry.update_ignored());
+
+    entry.manager_metadata.ignored_version = None;
+    entry.manager_metadata.pinned = true;
+    assert!(entry.update_ignored());
+  }
+}
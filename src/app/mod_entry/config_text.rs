@@ -0,0 +1,93 @@
+//! Decoding of mod config files (`mod_info.json`, `.version` files) before they are parsed.
+//!
+//! Mods are packaged by hand on every platform, so these files turn up with UTF-8 or UTF-16 byte
+//! order marks and Windows or classic Mac line endings, none of which the JSON5 parser accepts.
+//! Anything that still can't be decoded is reported with the exact decode error.
+
+use std::path::Path;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Decodes `bytes` as UTF-8 or, if it starts with a byte order mark, UTF-16, stripping the mark
+/// and normalising line endings to `\n`.
+pub fn decode(bytes: &[u8]) -> Result<String, String> {
+  let text = if let Some(bytes) = bytes.strip_prefix(UTF8_BOM) {
+    std::str::from_utf8(bytes)
+      .map_err(|err| err.to_string())?
+      .to_string()
+  } else if let Some(bytes) = bytes.strip_prefix(UTF16_LE_BOM) {
+    decode_utf16(bytes, u16::from_le_bytes)?
+  } else if let Some(bytes) = bytes.strip_prefix(UTF16_BE_BOM) {
+    decode_utf16(bytes, u16::from_be_bytes)?
+  } else {
+    std::str::from_utf8(bytes)
+      .map_err(|err| err.to_string())?
+      .to_string()
+  };
+
+  Ok(text.replace("\r\n", "\n").replace('\r', "\n"))
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<String, String> {
+  if bytes.len() % 2 != 0 {
+    return Err(String::from("odd number of bytes in UTF-16 text"));
+  }
+
+  let units: Vec<u16> = bytes
+    .chunks_exact(2)
+    .map(|pair| from_bytes([pair[0], pair[1]]))
+    .collect();
+
+  String::from_utf16(&units).map_err(|err| err.to_string())
+}
+
+/// Reads and decodes the file at `path`.
+pub fn read(path: &Path) -> Result<String, String> {
+  let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+
+  decode(&bytes)
+}
+
+#[cfg(test)]
+mod test {
+  use super::decode;
+
+  #[test]
+  fn strips_utf8_bom() {
+    assert_eq!(
+      decode(b"\xEF\xBB\xBF{ \"id\": \"a\" }").as_deref(),
+      Ok("{ \"id\": \"a\" }")
+    );
+  }
+
+  #[test]
+  fn decodes_utf16() {
+    let le: Vec<u8> = IntoIterator::into_iter([0xFF, 0xFE])
+      .chain("{}\r\n".encode_utf16().flat_map(u16::to_le_bytes))
+      .collect();
+    let be: Vec<u8> = IntoIterator::into_iter([0xFE, 0xFF])
+      .chain("{}\r\n".encode_utf16().flat_map(u16::to_be_bytes))
+      .collect();
+
+    assert_eq!(decode(&le).as_deref(), Ok("{}\n"));
+    assert_eq!(decode(&be).as_deref(), Ok("{}\n"));
+  }
+
+  #[test]
+  fn normalises_line_endings() {
+    assert_eq!(decode(b"a\r\nb\rc\n").as_deref(), Ok("a\nb\nc\n"));
+  }
+
+  #[test]
+  fn reports_decode_errors() {
+    assert_eq!(
+      decode(b"{ \"name\": \"\xE9\" }"),
+      Err(String::from(
+        "invalid utf-8 sequence of 1 bytes from index 11"
+      ))
+    );
+    assert!(decode(&[0xFF, 0xFE, 0x00]).is_err());
+  }
+}
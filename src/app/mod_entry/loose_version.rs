@@ -0,0 +1,278 @@
+//! Version numbers compared the way mod authors mean them rather than as text.
+//!
+//! Roughly follows semver precedence, loosened to cope with what turns up in mod_info and version
+//! files:
+//! - a leading `v` is ignored, and missing components count as zero, so `v1.2` equals `1.2.0`
+//! - anything after a `-`, or a known tag such as `rc` or `beta`, marks a pre-release, which sorts
+//!   before the release itself: `0.10.5-rc2` < `0.10.5`
+//! - any other letter suffix is a revision, which sorts after the release: `1.2` < `1.2a` < `1.2b`
+//! - build metadata after a `+` is ignored
+//! - numbers compare as numbers: `1.9` < `1.10`
+//!
+//! Versions without any numbers in them fall back to comparing their text, and sort before any
+//! that have numbers so that the order stays consistent.
+
+use std::cmp::Ordering;
+
+/// Tags that mark a pre-release, in the order they come in.
+const PRE_RELEASE_TAGS: [&str; 7] = ["dev", "snapshot", "alpha", "beta", "pre", "preview", "rc"];
+
+#[derive(Debug, Clone)]
+pub struct LooseVersion {
+  raw: String,
+  release: Vec<Part>,
+  pre_release: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+  Number(u64),
+  Text(String),
+}
+
+impl LooseVersion {
+  pub fn parse(text: &str) -> Self {
+    let raw = text.trim().to_lowercase();
+    let version = raw
+      .strip_prefix('v')
+      .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+      .unwrap_or(&raw);
+    let version = version.split('+').next().unwrap_or_default();
+
+    let (release, pre_release) = match version.split_once('-') {
+      Some((release, pre_release)) => (release, pre_release),
+      None => PRE_RELEASE_TAGS
+        .iter()
+        .filter_map(|tag| Self::find_tag(version, tag))
+        .min()
+        .map_or((version, ""), |idx| version.split_at(idx)),
+    };
+
+    Self {
+      release: Self::parts(release),
+      pre_release: Self::parts(pre_release),
+      raw,
+    }
+  }
+
+  /// Where `tag` starts in `version`, if it directly follows a number or separator.
+  fn find_tag(version: &str, tag: &str) -> Option<usize> {
+    version.match_indices(tag).map(|(idx, _)| idx).find(|idx| {
+      *idx > 0
+        && version[..*idx]
+          .chars()
+          .next_back()
+          .is_some_and(|c| c.is_ascii_digit() || matches!(c, '.' | '_' | ' ' | '(' | '['))
+    })
+  }
+
+  /// Splits on separators and wherever digits meet letters, so `2a` is `2` then `a`.
+  fn parts(text: &str) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut flush = |current: &mut String| {
+      if !current.is_empty() {
+        parts.push(match current.parse() {
+          Ok(number) => Part::Number(number),
+          Err(_) => Part::Text(std::mem::take(current)),
+        });
+        current.clear();
+      }
+    };
+
+    for c in text.chars() {
+      if !c.is_alphanumeric() {
+        flush(&mut current);
+      } else {
+        if current
+          .chars()
+          .last()
+          .is_some_and(|last| last.is_ascii_digit() != c.is_ascii_digit())
+        {
+          flush(&mut current);
+        }
+        current.push(c);
+      }
+    }
+    flush(&mut current);
+
+    parts
+  }
+
+  fn has_numbers(&self) -> bool {
+    self
+      .release
+      .iter()
+      .any(|part| matches!(part, Part::Number(_)))
+  }
+
+  /// Release components, where a missing component equals zero and sorts before any suffix.
+  ///
+  /// Trailing zeros are dropped before comparing, so that `1.2`, `1.2.0` and `1.2a` compare the
+  /// same way against each other as against anything else.
+  fn cmp_release(a: &[Part], b: &[Part]) -> Ordering {
+    fn trim_zeros(parts: &[Part]) -> &[Part] {
+      let len = parts
+        .iter()
+        .rposition(|part| part != &Part::Number(0))
+        .map_or(0, |idx| idx + 1);
+      &parts[..len]
+    }
+
+    let (a, b) = (trim_zeros(a), trim_zeros(b));
+    for (a, b) in a.iter().zip(b) {
+      let ord = match (a, b) {
+        (Part::Number(a), Part::Number(b)) => a.cmp(b),
+        (Part::Text(a), Part::Text(b)) => a.cmp(b),
+        (Part::Number(_), Part::Text(_)) => Ordering::Greater,
+        (Part::Text(_), Part::Number(_)) => Ordering::Less,
+      };
+      if ord != Ordering::Equal {
+        return ord;
+      }
+    }
+
+    a.len().cmp(&b.len())
+  }
+
+  /// Pre-release identifiers, as in semver, except known tags are ranked by how close to a
+  /// release they are rather than alphabetically, and come before any other text.
+  fn cmp_pre_release(a: &[Part], b: &[Part]) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+      (true, true) => return Ordering::Equal,
+      (true, false) => return Ordering::Greater,
+      (false, true) => return Ordering::Less,
+      (false, false) => {}
+    }
+
+    let rank = |text: &str| PRE_RELEASE_TAGS.iter().position(|tag| *tag == text);
+    for (a, b) in a.iter().zip(b) {
+      let ord = match (a, b) {
+        (Part::Number(a), Part::Number(b)) => a.cmp(b),
+        (Part::Number(_), Part::Text(_)) => Ordering::Less,
+        (Part::Text(_), Part::Number(_)) => Ordering::Greater,
+        (Part::Text(a), Part::Text(b)) => match (rank(a), rank(b)) {
+          (Some(a), Some(b)) => a.cmp(&b),
+          (Some(_), None) => Ordering::Less,
+          (None, Some(_)) => Ordering::Greater,
+          (None, None) => a.cmp(b),
+        },
+      };
+      if ord != Ordering::Equal {
+        return ord;
+      }
+    }
+
+    a.len().cmp(&b.len())
+  }
+}
+
+impl Ord for LooseVersion {
+  fn cmp(&self, other: &Self) -> Ordering {
+    match (self.has_numbers(), other.has_numbers()) {
+      (false, false) => return self.raw.cmp(&other.raw),
+      (false, true) => return Ordering::Less,
+      (true, false) => return Ordering::Greater,
+      (true, true) => {}
+    }
+
+    Self::cmp_release(&self.release, &other.release)
+      .then_with(|| Self::cmp_pre_release(&self.pre_release, &other.pre_release))
+  }
+}
+
+impl PartialOrd for LooseVersion {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl PartialEq for LooseVersion {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for LooseVersion {}
+
+#[cfg(test)]
+mod test {
+  use std::cmp::Ordering;
+
+  use super::LooseVersion;
+
+  fn cmp(a: &str, b: &str) -> Ordering {
+    LooseVersion::parse(a).cmp(&LooseVersion::parse(b))
+  }
+
+  #[test]
+  fn compares_numbers_numerically() {
+    assert_eq!(cmp("1.9", "1.10"), Ordering::Less);
+    assert_eq!(cmp("2.0.0", "10.0.0"), Ordering::Less);
+    assert_eq!(cmp("1.2.3", "1.2.3"), Ordering::Equal);
+  }
+
+  #[test]
+  fn pads_missing_components() {
+    assert_eq!(cmp("1.2", "1.2.0"), Ordering::Equal);
+    assert_eq!(cmp("v1.2", "1.2.0.0"), Ordering::Equal);
+    assert_eq!(cmp("1.2", "1.2.1"), Ordering::Less);
+  }
+
+  #[test]
+  fn pre_releases_come_first() {
+    assert_eq!(cmp("0.10.5-rc2", "0.10.5"), Ordering::Less);
+    assert_eq!(cmp("0.10.5rc2", "0.10.5"), Ordering::Less);
+    assert_eq!(cmp("1.0.0-rc.1", "1.0.0-rc.2"), Ordering::Less);
+    assert_eq!(cmp("1.0.0-rc9", "1.0.0-rc10"), Ordering::Less);
+    assert_eq!(cmp("1.0-alpha", "1.0-beta"), Ordering::Less);
+    assert_eq!(cmp("1.0 beta", "1.0 rc1"), Ordering::Less);
+    assert_eq!(cmp("1.0-dev", "1.0-alpha"), Ordering::Less);
+    assert_eq!(cmp("1.0.0-alpha", "1.0.0-alpha.1"), Ordering::Less);
+    assert_eq!(cmp("1.0.0-rc1", "0.9.9"), Ordering::Greater);
+  }
+
+  #[test]
+  fn letter_suffixes_are_revisions() {
+    assert_eq!(cmp("1.2", "1.2a"), Ordering::Less);
+    assert_eq!(cmp("1.2a", "1.2b"), Ordering::Less);
+    assert_eq!(cmp("1.2b", "1.3"), Ordering::Less);
+    assert_eq!(cmp("0.95.1a", "0.95.1a"), Ordering::Equal);
+  }
+
+  #[test]
+  fn ignores_build_metadata_and_case() {
+    assert_eq!(cmp("1.0.0+build.5", "1.0.0+build.9"), Ordering::Equal);
+    assert_eq!(cmp("1.0.0-RC1", "1.0.0-rc1"), Ordering::Equal);
+    assert_eq!(cmp("V2.1", "2.1"), Ordering::Equal);
+  }
+
+  #[test]
+  fn falls_back_to_text() {
+    assert_eq!(cmp("alpha", "beta"), Ordering::Less);
+    assert_eq!(cmp("", "1.0"), Ordering::Less);
+    assert_eq!(cmp("unknown", "unknown"), Ordering::Equal);
+    assert_eq!(cmp("u", "v1.2"), Ordering::Less);
+    assert_eq!(cmp("u", "1.3"), Ordering::Less);
+  }
+
+  #[test]
+  fn orders_consistently() {
+    let versions = [
+      "", "u", "1.3", "v1.2", "1.2.0", "1.2a", "1.2.1", "1.2-dev", "1.2-beta", "1.2-c", "1.2.0a",
+      "1.2b", "0.9", "1.2-rc1", "2",
+    ]
+    .map(LooseVersion::parse);
+
+    for a in &versions {
+      for b in &versions {
+        assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{:?} {:?}", a, b);
+        for c in &versions {
+          if a <= b && b <= c {
+            assert!(a <= c, "{:?} <= {:?} <= {:?}", a, b, c);
+          }
+        }
+      }
+    }
+  }
+}
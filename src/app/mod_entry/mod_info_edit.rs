@@ -0,0 +1,348 @@
+//! Minimal in-place editing of `mod_info.json`, version files, and other loosely formatted JSON
+//! config such as GraphicsLib's `GRAPHICS_OPTIONS.ini`.
+//!
+//! Mod info files are loosely formatted JSON5 and frequently contain comments, so rather than
+//! round-tripping them through serde, values are located by scanning the raw text and only the
+//! value itself is replaced. Everything else in the file is left exactly as it was.
+
+use std::ops::Range;
+
+use crate::app::version_source::parse_version;
+
+/// Replaces the value of a top level key with the given string.
+pub fn set_string(text: &str, key: &str, value: &str) -> Option<String> {
+  let range = find_value(text, key)?;
+
+  Some(replace(text, range, &quote(value)))
+}
+
+/// The raw text of a top level key's value, such as `true` or `"text"` with its quotes.
+pub fn get_raw<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+  find_value(text, key).map(|range| &text[range])
+}
+
+/// Replaces the value of a top level key with `raw` as written, for booleans and numbers.
+pub fn set_raw(text: &str, key: &str, raw: &str) -> Option<String> {
+  let range = find_value(text, key)?;
+
+  Some(replace(text, range, raw))
+}
+
+/// Like [`set_string`], but adds the key to the top of the outermost object if it's missing.
+pub fn set_or_insert_string(text: &str, key: &str, value: &str) -> Option<String> {
+  if let Some(edited) = set_string(text, key, value) {
+    return Some(edited);
+  }
+
+  let open = skip_trivia(text.as_bytes(), 0);
+  if text.as_bytes().get(open) != Some(&b'{') {
+    return None;
+  }
+  let mut text = text.to_string();
+  text.insert_str(open + 1, &format!("\n  {}: {},", quote(key), quote(value)));
+
+  Some(text)
+}
+
+/// Replaces the mod's version, preserving the object form (`{ "major": ... }`) when the file
+/// already uses it and the new version can be represented that way.
+pub fn set_version(text: &str, version: &str) -> Option<String> {
+  set_version_at(text, "version", version)
+}
+
+/// [`set_version`] for any key, such as a version file's `modVersion`.
+pub fn set_version_at(text: &str, key: &str, version: &str) -> Option<String> {
+  let range = find_value(text, key)?;
+  let existing = &text[range.clone()];
+
+  let replacement = match parse_version(version) {
+    Some(parsed) if existing.starts_with('{') && parsed.to_string() == version.trim() => {
+      let mut object = existing.to_string();
+      for (key, value) in [
+        ("major", parsed.major.to_string()),
+        ("minor", parsed.minor.to_string()),
+      ] {
+        let field = find_value(&object, key)?;
+        let value = if is_quoted(&object[field.clone()]) {
+          quote(&value)
+        } else {
+          value
+        };
+        object.replace_range(field, &value);
+      }
+      match find_value(&object, "patch") {
+        Some(field) => object.replace_range(field, &quote(&parsed.patch)),
+        None if !parsed.patch.is_empty() => {
+          let minor = find_value(&object, "minor")?;
+          object.insert_str(minor.end, &format!(", \"patch\": {}", quote(&parsed.patch)));
+        }
+        None => {}
+      }
+
+      object
+    }
+    _ => quote(version),
+  };
+
+  Some(replace(text, range, &replacement))
+}
+
+fn replace(text: &str, range: Range<usize>, with: &str) -> String {
+  let mut text = text.to_string();
+  text.replace_range(range, with);
+
+  text
+}
+
+fn quote(value: &str) -> String {
+  serde_json::to_string(value).unwrap_or_else(|_| format!("\"{}\"", value))
+}
+
+fn is_quoted(value: &str) -> bool {
+  value.starts_with(['"', '\''])
+}
+
+/// Finds the byte range of the value belonging to `key` in the outermost object of `text`.
+fn find_value(text: &str, key: &str) -> Option<Range<usize>> {
+  let bytes = text.as_bytes();
+  let mut depth = 0;
+  let mut expect_key = false;
+  let mut idx = 0;
+
+  while idx < bytes.len() {
+    if let Some(end) = skip_comment(bytes, idx) {
+      idx = end;
+      continue;
+    }
+
+    match bytes[idx] {
+      open @ (b'{' | b'[') => {
+        depth += 1;
+        expect_key = depth == 1 && open == b'{';
+        idx += 1;
+      }
+      b'}' | b']' => {
+        depth -= 1;
+        idx += 1;
+      }
+      b',' => {
+        expect_key = depth == 1;
+        idx += 1;
+      }
+      b'"' | b'\'' => {
+        let end = skip_string(bytes, idx)?;
+        if depth == 1 && expect_key {
+          expect_key = false;
+          if &text[idx + 1..end - 1] == key {
+            return value_after_colon(bytes, end);
+          }
+        }
+        idx = end;
+      }
+      c if depth == 1 && expect_key && (c.is_ascii_alphabetic() || c == b'_' || c == b'$') => {
+        let end = idx
+          + bytes[idx..]
+            .iter()
+            .take_while(|c| c.is_ascii_alphanumeric() || **c == b'_' || **c == b'$')
+            .count();
+        expect_key = false;
+        if &text[idx..end] == key {
+          return value_after_colon(bytes, end);
+        }
+        idx = end;
+      }
+      _ => idx += 1,
+    }
+  }
+
+  None
+}
+
+fn value_after_colon(bytes: &[u8], idx: usize) -> Option<Range<usize>> {
+  let idx = skip_trivia(bytes, idx);
+  if bytes.get(idx) != Some(&b':') {
+    return None;
+  }
+  let start = skip_trivia(bytes, idx + 1);
+
+  let end = match bytes.get(start)? {
+    b'"' | b'\'' => skip_string(bytes, start)?,
+    b'{' | b'[' => {
+      let mut depth = 0;
+      let mut idx = start;
+      loop {
+        if let Some(end) = skip_comment(bytes, idx) {
+          idx = end;
+          continue;
+        }
+        match bytes.get(idx)? {
+          b'"' | b'\'' => idx = skip_string(bytes, idx)?,
+          b'{' | b'[' => {
+            depth += 1;
+            idx += 1;
+          }
+          b'}' | b']' => {
+            depth -= 1;
+            idx += 1;
+            if depth == 0 {
+              break idx;
+            }
+          }
+          _ => idx += 1,
+        }
+      }
+    }
+    _ => {
+      let mut end = start;
+      while end < bytes.len()
+        && !matches!(bytes[end], b',' | b'}' | b']' | b'\n' | b'\r')
+        && skip_comment(bytes, end).is_none()
+      {
+        end += 1;
+      }
+      while end > start && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+      }
+      end
+    }
+  };
+
+  Some(start..end)
+}
+
+fn skip_trivia(bytes: &[u8], mut idx: usize) -> usize {
+  loop {
+    if let Some(end) = skip_comment(bytes, idx) {
+      idx = end;
+    } else if bytes.get(idx).is_some_and(u8::is_ascii_whitespace) {
+      idx += 1;
+    } else {
+      return idx;
+    }
+  }
+}
+
+/// Returns the index just past the comment starting at `idx`, if there is one.
+fn skip_comment(bytes: &[u8], idx: usize) -> Option<usize> {
+  match (bytes.get(idx)?, bytes.get(idx + 1)) {
+    (b'#', _) | (b'/', Some(b'/')) => Some(
+      bytes[idx..]
+        .iter()
+        .position(|c| *c == b'\n')
+        .map_or(bytes.len(), |pos| idx + pos),
+    ),
+    (b'/', Some(b'*')) => Some(
+      bytes[idx + 2..]
+        .windows(2)
+        .position(|window| window == b"*/")
+        .map_or(bytes.len(), |pos| idx + 2 + pos + 2),
+    ),
+    _ => None,
+  }
+}
+
+/// Returns the index just past the closing quote of the string starting at `idx`.
+fn skip_string(bytes: &[u8], idx: usize) -> Option<usize> {
+  let quote = bytes[idx];
+  let mut idx = idx + 1;
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b'\\' => idx += 2,
+      c if c == quote => return Some(idx + 1),
+      _ => idx += 1,
+    }
+  }
+
+  None
+}
+
+#[cfg(test)]
+mod test {
+  use super::{get_raw, set_or_insert_string, set_raw, set_string, set_version, set_version_at};
+
+  const STRING_VERSION: &str = r#"{
+  # A comment with "version": "0.0.0" in it
+  "id": "test_mod",
+  "name": "Test Mod",
+  "version": "1.0.0", // trailing comment
+  "gameVersion": "0.95.1a-RC6",
+  "dependencies": [{ "id": "lw_lazylib", "version": "2.8" }],
+}"#;
+
+  const OBJECT_VERSION: &str = r#"{
+  id: "test_mod",
+  /* block comment */
+  version: { major: 1, minor: "2", patch: 3 },
+  gameVersion: "0.95.1a-RC6",
+}"#;
+
+  #[test]
+  fn replaces_string_version() {
+    let edited = set_version(STRING_VERSION, "1.1.0b").expect("Edit version");
+
+    assert!(edited.contains(r#""version": "1.1.0b", // trailing comment"#));
+    assert!(edited.contains(r#"# A comment with "version": "0.0.0" in it"#));
+    assert!(edited.contains(r#"{ "id": "lw_lazylib", "version": "2.8" }"#));
+  }
+
+  #[test]
+  fn preserves_object_version() {
+    let edited = set_version(OBJECT_VERSION, "2.5.1").expect("Edit version");
+
+    assert!(edited.contains(r#"version: { major: 2, minor: "5", patch: "1" },"#));
+    assert!(edited.contains("/* block comment */"));
+  }
+
+  #[test]
+  fn object_version_falls_back_to_string() {
+    let edited = set_version(OBJECT_VERSION, "2.5-beta").expect("Edit version");
+
+    assert!(edited.contains(r#"version: "2.5-beta","#));
+  }
+
+  #[test]
+  fn replaces_game_version() {
+    let edited =
+      set_string(STRING_VERSION, "gameVersion", "0.96a-RC10").expect("Edit game version");
+
+    assert!(edited.contains(r#""gameVersion": "0.96a-RC10","#));
+    assert!(edited.contains(r#""version": "1.0.0""#));
+  }
+
+  #[test]
+  fn missing_key() {
+    assert!(set_string("{ \"id\": \"test\" }", "gameVersion", "0.96a").is_none());
+  }
+
+  #[test]
+  fn inserts_missing_key() {
+    let edited = set_or_insert_string(OBJECT_VERSION, "author", "Someone").expect("Insert author");
+
+    assert!(edited.starts_with("{\n  \"author\": \"Someone\",\n  id: \"test_mod\","));
+    assert_eq!(
+      set_or_insert_string(&edited, "author", "Else"),
+      set_string(&edited, "author", "Else")
+    );
+  }
+
+  #[test]
+  fn replaces_version_file_version() {
+    let version_file = r#"{
+  "masterVersionFile": "https://example.com/mod.version",
+  "modVersion": { "major": 1, "minor": 2, "patch": 0 }
+}"#;
+    let edited = set_version_at(version_file, "modVersion", "1.3.0").expect("Edit version");
+
+    assert!(edited.contains(r#""modVersion": { "major": 1, "minor": 3, "patch": "0" }"#));
+  }
+
+  #[test]
+  fn replaces_raw_values() {
+    let options = "{\n\t# Bloom\n\t\"enableBloom\":true,\n\t\"maximumLights\":100\n}";
+    let edited = set_raw(options, "enableBloom", "false").expect("Edit bloom");
+
+    assert_eq!(get_raw(&edited, "enableBloom"), Some("false"));
+    assert_eq!(get_raw(&edited, "maximumLights"), Some("100"));
+    assert!(edited.contains("# Bloom"));
+  }
+}
@@ -0,0 +1,301 @@
+//! A form for editing a mod's `mod_info.json` and version file, for mod authors working on a mod
+//! in place.
+//!
+//! Edits go through [`mod_info_edit`], so comments and formatting in the files survive. Both files
+//! are checked after editing and nothing is written unless they'd still load.
+
+use std::{
+  io::Read,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use druid::{
+  widget::{Button, Either, Flex, Label, Maybe, TextBox},
+  Data, EventCtx, Lens, Widget, WidgetExt,
+};
+use json_comments::strip_comments;
+
+use super::{config_text, mod_info_edit, ModEntry, ModVersionMeta};
+use crate::app::{
+  i18n::{tr, tr_args},
+  modal::Modal,
+  util::{make_flex_description_row, LabelExt, WidgetExtEx},
+};
+
+#[derive(Clone, Data, Lens)]
+pub struct ModInfoForm {
+  #[data(ignore)]
+  entry: Arc<ModEntry>,
+  #[data(same_fn = "PartialEq::eq")]
+  version_file: Option<PathBuf>,
+  id: String,
+  name: String,
+  author: String,
+  version: String,
+  game_version: String,
+  description: String,
+  master_version_file: String,
+  thread_id: String,
+  direct_download_url: String,
+  changelog_url: String,
+  errors: Option<String>,
+}
+
+impl ModInfoForm {
+  pub fn new(entry: Arc<ModEntry>) -> Self {
+    let checker = entry.version_checker.as_ref();
+    let checker_field =
+      |field: fn(&ModVersionMeta) -> Option<String>| checker.and_then(field).unwrap_or_default();
+
+    Self {
+      version_file: entry.version_file(),
+      id: entry.id.clone(),
+      name: entry.name.clone(),
+      author: entry.author.clone(),
+      version: entry.version.to_string(),
+      game_version: entry.raw_game_version.clone(),
+      description: entry.description.clone(),
+      master_version_file: checker_field(|checker| Some(checker.remote_url.clone())),
+      thread_id: checker_field(|checker| Some(checker.fractal_id.clone())),
+      direct_download_url: checker_field(|checker| checker.direct_download_url.clone()),
+      changelog_url: checker_field(|checker| checker.changelog_url.clone()),
+      errors: None,
+      entry,
+    }
+  }
+
+  /// Problems with the form itself, before anything is edited.
+  fn validate(&self) -> Vec<String> {
+    let mut errors = Vec::new();
+    if self.id.trim().is_empty() || self.id.contains(char::is_whitespace) {
+      errors.push(tr("mod-info-id-invalid"))
+    }
+    if self.name.trim().is_empty() {
+      errors.push(tr("mod-info-name-empty"))
+    }
+    if self.version.trim().is_empty() {
+      errors.push(tr("mod-info-version-empty"))
+    }
+    if self.version_file.is_some() {
+      if !self.thread_id.is_empty() && !self.thread_id.chars().all(|c| c.is_ascii_digit()) {
+        errors.push(tr("mod-info-thread-id-invalid"))
+      }
+      for (field, url) in [
+        ("mod-info-master-version-file", &self.master_version_file),
+        ("mod-info-direct-download-url", &self.direct_download_url),
+        ("mod-info-changelog-url", &self.changelog_url),
+      ] {
+        if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+          errors.push(tr_args("mod-info-url-invalid", &[("field", &tr(field))]))
+        }
+      }
+    }
+
+    errors
+  }
+
+  /// Both files with the form's values written into them, or everything that's wrong.
+  fn edit(&self) -> Result<(String, Option<String>), Vec<String>> {
+    let errors = self.validate();
+    if !errors.is_empty() {
+      return Err(errors);
+    }
+
+    let mod_info = config_text::read(&self.entry.path.join("mod_info.json"))
+      .map_err(|err| vec![tr_args("mod-info-read-failed", &[("err", &err)])])?;
+    let mod_info = IntoIterator::into_iter([
+      ("id", &self.id),
+      ("name", &self.name),
+      ("author", &self.author),
+      ("gameVersion", &self.game_version),
+      ("description", &self.description),
+    ])
+    .try_fold(mod_info, |text, (key, value)| {
+      mod_info_edit::set_or_insert_string(&text, key, value)
+    })
+    .and_then(|text| mod_info_edit::set_version(&text, &self.version))
+    .ok_or_else(|| vec![tr("mod-info-edit-failed")])?;
+
+    let mut errors = Vec::new();
+    let mut stripped = String::new();
+    let loads = strip_comments(mod_info.as_bytes())
+      .read_to_string(&mut stripped)
+      .map_err(|err| err.to_string())
+      .and_then(|_| json5::from_str::<ModEntry>(&stripped).map_err(|err| err.to_string()));
+    if let Err(err) = loads {
+      errors.push(tr_args("mod-info-would-not-load", &[("err", &err)]))
+    }
+
+    let version_file = if let Some(path) = &self.version_file {
+      let text = config_text::read(path).map_err(|err| {
+        vec![tr_args(
+          "mod-info-version-file-read-failed",
+          &[("path", &path.to_string_lossy()), ("err", &err)],
+        )]
+      })?;
+      let mut fields = vec![
+        ("masterVersionFile", &self.master_version_file),
+        ("modThreadId", &self.thread_id),
+      ];
+      // optional, so only added when filled in
+      let checker = self.entry.version_checker.as_ref();
+      for (key, value, had_value) in [
+        (
+          "directDownloadURL",
+          &self.direct_download_url,
+          checker.is_some_and(|checker| checker.direct_download_url.is_some()),
+        ),
+        (
+          "changelogURL",
+          &self.changelog_url,
+          checker.is_some_and(|checker| checker.changelog_url.is_some()),
+        ),
+      ] {
+        if !value.is_empty() || had_value {
+          fields.push((key, value))
+        }
+      }
+      let text = fields
+        .into_iter()
+        .try_fold(text, |text, (key, value)| {
+          mod_info_edit::set_or_insert_string(&text, key, value)
+        })
+        .and_then(|text| mod_info_edit::set_version_at(&text, "modVersion", &self.version))
+        .ok_or_else(|| vec![tr("mod-info-version-file-edit-failed")])?;
+
+      if let Err(err) = ModVersionMeta::parse(&text) {
+        errors.push(tr_args(
+          "mod-info-version-file-would-not-load",
+          &[("err", &err)],
+        ))
+      }
+      Some(text)
+    } else {
+      None
+    };
+
+    if errors.is_empty() {
+      Ok((mod_info, version_file))
+    } else {
+      Err(errors)
+    }
+  }
+
+  /// Writes both files and swaps the reloaded mod into the mod list.
+  ///
+  /// Both are written to temporary files first and only moved into place once both have been
+  /// written, so a failed save never leaves either file half written.
+  fn save(&mut self, ctx: &mut EventCtx) {
+    let res = self.edit().and_then(|(mod_info, version_file)| {
+      let files: Vec<(PathBuf, String)> =
+        std::iter::once((self.entry.path.join("mod_info.json"), mod_info))
+          .chain(self.version_file.clone().zip(version_file))
+          .collect();
+      let write_failed = |path: &Path, err: std::io::Error| {
+        vec![tr_args(
+          "mod-info-write-failed",
+          &[("path", &path.to_string_lossy()), ("err", &err)],
+        )]
+      };
+
+      let mut written = Vec::new();
+      for (path, text) in files {
+        let temp = temp_path(&path);
+        if let Err(err) = std::fs::write(&temp, text) {
+          let _ = std::fs::remove_file(&temp);
+          for (temp, _) in written {
+            let _ = std::fs::remove_file(temp);
+          }
+          return Err(write_failed(&path, err));
+        }
+        written.push((temp, path));
+      }
+      for (temp, path) in written {
+        std::fs::rename(&temp, &path).map_err(|err| write_failed(&path, err))?;
+      }
+
+      self.entry.reload().map_err(|err| vec![err.to_string()])
+    });
+
+    match res {
+      Ok(entry) => {
+        ctx.submit_command_global(ModEntry::REPLACE.with(Arc::new(entry)));
+        ctx.submit_command(druid::commands::CLOSE_WINDOW);
+      }
+      Err(errors) => self.errors = Some(errors.join("\n")),
+    }
+  }
+
+  pub fn modal<'a>(entry: &ModEntry) -> Modal<'a, Self> {
+    fn row(
+      label: &str,
+      lens: impl Lens<ModInfoForm, String> + 'static,
+    ) -> Box<dyn Widget<ModInfoForm>> {
+      make_flex_description_row(
+        Label::wrapped(label),
+        TextBox::new().lens(lens).expand_width(),
+      )
+      .boxed()
+    }
+
+    Modal::new(&tr_args("mod-info-title", &[("name", &entry.name)]))
+      .with_content(tr("mod-info-hint"))
+      .with_content(row(&tr("mod-info-id"), ModInfoForm::id))
+      .with_content(row(&tr("mod-info-name"), ModInfoForm::name))
+      .with_content(row(&tr("mod-info-author"), ModInfoForm::author))
+      .with_content(row(&tr("mod-info-version"), ModInfoForm::version))
+      .with_content(row(&tr("mod-info-game-version"), ModInfoForm::game_version))
+      .with_content(
+        make_flex_description_row(
+          Label::wrapped(tr("mod-info-description")),
+          TextBox::multiline()
+            .lens(ModInfoForm::description)
+            .expand_width(),
+        )
+        .boxed(),
+      )
+      .with_content(
+        Either::new(
+          |form: &ModInfoForm, _| form.version_file.is_some(),
+          Flex::column()
+            .with_child(row(
+              &tr("mod-info-master-version-file-label"),
+              ModInfoForm::master_version_file,
+            ))
+            .with_child(row(&tr("mod-info-thread-id-label"), ModInfoForm::thread_id))
+            .with_child(row(
+              &tr("mod-info-direct-download-url-label"),
+              ModInfoForm::direct_download_url,
+            ))
+            .with_child(row(
+              &tr("mod-info-changelog-url-label"),
+              ModInfoForm::changelog_url,
+            )),
+          Label::wrapped(tr("mod-info-no-version-file")),
+        )
+        .boxed(),
+      )
+      .with_content(
+        Maybe::or_empty(|| Label::wrapped_func(|errors: &String, _| errors.clone()))
+          .lens(ModInfoForm::errors)
+          .boxed(),
+      )
+      .with_content(
+        Button::new(tr("mod-info-save"))
+          .on_activate(|ctx, form: &mut ModInfoForm, _| form.save(ctx))
+          .align_right()
+          .expand_width()
+          .boxed(),
+      )
+      .with_close_label(&tr("close"))
+  }
+}
+
+/// Where `path` is written before being moved into place.
+fn temp_path(path: &Path) -> PathBuf {
+  let mut temp = path.as_os_str().to_owned();
+  temp.push(".tmp");
+
+  PathBuf::from(temp)
+}
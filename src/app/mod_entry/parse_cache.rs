@@ -0,0 +1,184 @@
+//! Parsed `mod_info.json`s kept between runs, keyed by mod folder, so that loading the mod list
+//! doesn't re-parse and re-walk every mod that hasn't changed.
+//!
+//! An entry is only used while the modification times of the mod folder and its `mod_info.json`
+//! both match what they were when it was cached, and its version files are all still there.
+//! Version files are always re-read, only where they are is cached.
+
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::Mutex,
+  time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use webview_shared::PROJECT;
+
+/// Modification times of a mod folder and its `mod_info.json`.
+pub type Stamp = (SystemTime, SystemTime);
+
+/// How old a stamp has to be to be trusted. Some file systems only keep modification times to
+/// the nearest couple of seconds, so a mod written just before or after its stamp was taken can
+/// have the same stamp either way.
+const SETTLED: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedModInfo {
+  stamp: Stamp,
+  /// `mod_info.json` converted to plain JSON.
+  pub mod_info: serde_json::Value,
+  pub size: u64,
+  pub version_files: Vec<PathBuf>,
+}
+
+#[derive(Default)]
+pub struct ParseCache {
+  previous: HashMap<PathBuf, CachedModInfo>,
+  current: Mutex<HashMap<PathBuf, CachedModInfo>>,
+}
+
+impl ParseCache {
+  fn path() -> PathBuf {
+    PROJECT.data_dir().join("mod_info_cache.json")
+  }
+
+  /// The cache as it was saved after the last scan, or an empty one if it can't be read.
+  pub fn load() -> Self {
+    let previous = std::fs::read(Self::path())
+      .ok()
+      .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+      .unwrap_or_default();
+
+    Self {
+      previous,
+      current: Default::default(),
+    }
+  }
+
+  /// What was cached for the mod in `path`, if it hasn't changed since.
+  pub fn get(&self, path: &Path, stamp: Option<Stamp>) -> Option<CachedModInfo> {
+    let cached = self.previous.get(path).filter(|cached| {
+      Some(cached.stamp) == stamp && cached.version_files.iter().all(|file| file.is_file())
+    })?;
+    self.keep(path, cached.clone());
+
+    Some(cached.clone())
+  }
+
+  /// Caches a freshly parsed mod. `stamp` should be taken before parsing, so a mod that changes
+  /// while it's being parsed is parsed again next time. A mod changed too recently for its stamp
+  /// to be trusted isn't cached.
+  pub fn insert(
+    &self,
+    path: &Path,
+    stamp: Option<Stamp>,
+    mod_info: serde_json::Value,
+    size: u64,
+    version_files: Vec<PathBuf>,
+  ) {
+    if let Some(stamp) = stamp
+      && settled(stamp)
+    {
+      self.keep(
+        path,
+        CachedModInfo {
+          stamp,
+          mod_info,
+          size,
+          version_files,
+        },
+      )
+    }
+  }
+
+  fn keep(&self, path: &Path, cached: CachedModInfo) {
+    if let Ok(mut current) = self.current.lock() {
+      current.insert(path.to_path_buf(), cached);
+    }
+  }
+
+  /// Writes the mods seen since loading, dropping any that weren't, as they've been removed.
+  pub fn save(self) {
+    let current = self
+      .current
+      .into_inner()
+      .unwrap_or_else(|err| err.into_inner());
+    let res = serde_json::to_vec(&current)
+      .map_err(std::io::Error::from)
+      .and_then(|json| {
+        std::fs::create_dir_all(PROJECT.data_dir())?;
+        std::fs::write(Self::path(), json)
+      });
+    if let Err(err) = res {
+      warn!("Failed to save the mod info cache {:?}", err)
+    }
+  }
+}
+
+pub fn stamp(path: &Path) -> Option<Stamp> {
+  let modified = |path: &Path| path.metadata().and_then(|meta| meta.modified()).ok();
+
+  Some((modified(path)?, modified(&path.join("mod_info.json"))?))
+}
+
+fn settled((folder, mod_info): Stamp) -> bool {
+  let settled = |time: SystemTime| time.elapsed().is_ok_and(|age| age >= SETTLED);
+
+  settled(folder) && settled(mod_info)
+}
+
+#[cfg(test)]
+mod test {
+  use std::time::{Duration, SystemTime};
+
+  use tempfile::tempdir;
+
+  use super::{stamp, ParseCache, SETTLED};
+
+  #[test]
+  fn misses_changed_mods() {
+    let dir = tempdir().expect("Create mod dir");
+    std::fs::write(dir.path().join("mod_info.json"), r#"{"id": "a"}"#)
+      .expect("Write mod_info.json");
+    let version_file = dir.path().join("a.version");
+    std::fs::write(&version_file, "{}").expect("Write version file");
+
+    let old = SystemTime::now() - SETTLED * 2;
+    let older = old - Duration::from_secs(60);
+
+    let mut cache = ParseCache::default();
+    let mod_info = serde_json::json!({"id": "a"});
+    cache.insert(
+      dir.path(),
+      Some((old, old)),
+      mod_info,
+      1,
+      vec![version_file.clone()],
+    );
+    cache.previous = cache.current.lock().expect("Lock cache").clone();
+    assert!(cache.get(dir.path(), Some((old, old))).is_some());
+    assert!(cache.get(dir.path(), Some((old, older))).is_none());
+
+    std::fs::remove_file(&version_file).expect("Remove version file");
+    assert!(cache.get(dir.path(), Some((old, old))).is_none());
+  }
+
+  #[test]
+  fn skips_recently_changed_mods() {
+    let dir = tempdir().expect("Create mod dir");
+    std::fs::write(dir.path().join("mod_info.json"), r#"{"id": "a"}"#)
+      .expect("Write mod_info.json");
+
+    let cache = ParseCache::default();
+    cache.insert(
+      dir.path(),
+      stamp(dir.path()),
+      serde_json::json!({"id": "a"}),
+      1,
+      vec![],
+    );
+    assert!(cache.current.lock().expect("Lock cache").is_empty());
+  }
+}
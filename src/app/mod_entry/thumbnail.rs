@@ -0,0 +1,332 @@
+//! Icons and banners shipped by mods, usually `icon.png` or `banner.png` in the mod folder or its
+//! `graphics` folder, shown next to the mod's name in the list and across the top of its details.
+//!
+//! Images are found, decoded and scaled down on a background thread and cached until the mod is
+//! reinstalled or the mod list refreshed, so scrolling through the list never waits on the disk.
+//! Until an image has loaded, or if the mod doesn't have one, a [`Thumbnail`] takes up no space.
+
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::{Arc, LazyLock, Mutex},
+};
+
+use druid::{
+  piet::InterpolationMode,
+  widget::{FillStrat, Image},
+  BoxConstraints, Env, Event, EventCtx, ExtEventSink, ImageBuf, LayoutCtx, LifeCycle, LifeCycleCtx,
+  PaintCtx, Point, Selector, Size, Target, UpdateCtx, Widget, WidgetId, WidgetPod,
+};
+use tracing::warn;
+
+use super::ModEntry;
+
+/// Folders looked in for images, relative to the mod folder.
+const DIRS: [&str; 2] = ["", "graphics"];
+/// Space between an icon and the mod's name.
+const ICON_GAP: f64 = 5.;
+
+const LOADED: Selector<(PathBuf, Kind)> = Selector::new("mod_entry.thumbnail.loaded");
+
+/// Decoded and scaled down images by mod folder, `None` where the mod doesn't have one or it
+/// couldn't be read.
+static CACHE: LazyLock<Mutex<HashMap<(PathBuf, Kind), Option<ImageBuf>>>> =
+  LazyLock::new(Default::default);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+  Icon,
+  Banner,
+}
+
+impl Kind {
+  /// Start of the file names looked for, most wanted first. Icons and banners stand in for each
+  /// other, since most mods only ship one of them.
+  fn prefixes(self) -> [&'static str; 2] {
+    match self {
+      Kind::Icon => ["icon", "banner"],
+      Kind::Banner => ["banner", "icon"],
+    }
+  }
+
+  /// The most pixels along its longest side an image is kept at, with room for high DPI screens.
+  /// Mods often ship far larger images, which would take up memory and be scaled down every time
+  /// they're drawn.
+  fn max_side(self) -> usize {
+    match self {
+      Kind::Icon => 64,
+      Kind::Banner => 1024,
+    }
+  }
+}
+
+/// Forgets every image loaded so far, so they're found and loaded again.
+pub fn clear_cache() {
+  CACHE.lock().unwrap().clear()
+}
+
+/// The image of `kind` in `mod_dir`, preferring the shortest name, so `icon.png` is picked over
+/// `icon_large.png`.
+pub fn find(mod_dir: &Path, kind: Kind) -> Option<PathBuf> {
+  let pngs: Vec<PathBuf> = DIRS
+    .iter()
+    .flat_map(|dir| std::fs::read_dir(mod_dir.join(dir)).into_iter().flatten())
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| {
+      path.is_file()
+        && path
+          .extension()
+          .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+    })
+    .collect();
+
+  IntoIterator::into_iter(kind.prefixes()).find_map(|prefix| {
+    pngs
+      .iter()
+      .filter(|path| {
+        path
+          .file_stem()
+          .and_then(|stem| stem.to_str())
+          .is_some_and(|stem| stem.to_lowercase().starts_with(prefix))
+      })
+      .min_by_key(|path| path.as_os_str().len())
+      .cloned()
+  })
+}
+
+fn decode(path: &Path) -> Option<ImageBuf> {
+  std::fs::read(path)
+    .map_err(|err| err.to_string())
+    .and_then(|bytes| ImageBuf::from_data(&bytes).map_err(|err| err.to_string()))
+    .inspect_err(|err| warn!("Failed to load mod image {:?}: {}", path, err))
+    .ok()
+}
+
+/// Shrinks `image` so its longest side is at most `max_side`, each new pixel the average of those
+/// it covers.
+fn downscale(image: ImageBuf, max_side: usize) -> ImageBuf {
+  let (width, height) = (image.width(), image.height());
+  let longest = width.max(height);
+  if longest <= max_side {
+    return image;
+  }
+
+  let new_width = (width * max_side / longest).max(1);
+  let new_height = (height * max_side / longest).max(1);
+  let channels = image.format().bytes_per_pixel();
+  let pixels = image.raw_pixels();
+  let mut scaled = Vec::with_capacity(new_width * new_height * channels);
+  let mut sums = vec![0usize; channels];
+  for y in 0..new_height {
+    let (top, bottom) = (y * height / new_height, (y + 1) * height / new_height);
+    for x in 0..new_width {
+      let (left, right) = (x * width / new_width, (x + 1) * width / new_width);
+      sums.iter_mut().for_each(|sum| *sum = 0);
+      for row in top..bottom {
+        let start = (row * width + left) * channels;
+        let end = (row * width + right) * channels;
+        for (i, value) in pixels[start..end].iter().enumerate() {
+          sums[i % channels] += *value as usize
+        }
+      }
+      let count = (bottom - top) * (right - left);
+      scaled.extend(sums.iter().map(|sum| (sum / count) as u8));
+    }
+  }
+
+  ImageBuf::from_raw(scaled, image.format(), new_width, new_height)
+}
+
+/// A mod's icon or banner.
+pub struct Thumbnail {
+  kind: Kind,
+  /// The icon's size, or the tallest the banner is allowed to be.
+  height: f64,
+  key: Option<(PathBuf, Kind)>,
+  /// The image and its aspect ratio.
+  image: Option<(WidgetPod<Arc<ModEntry>, Image>, f64)>,
+}
+
+impl Thumbnail {
+  /// A square icon for the mod list.
+  pub fn icon(size: f64) -> Self {
+    Self {
+      kind: Kind::Icon,
+      height: size,
+      key: None,
+      image: None,
+    }
+  }
+
+  /// A banner as wide as it's allowed to be, but no taller than `max_height`.
+  pub fn banner(max_height: f64) -> Self {
+    Self {
+      kind: Kind::Banner,
+      height: max_height,
+      key: None,
+      image: None,
+    }
+  }
+
+  /// Shows `entry`'s image, from the cache if it's already been loaded, otherwise once it has
+  /// been. If `reload` is set, the cached image is dropped and loaded again.
+  fn load(&mut self, entry: &ModEntry, ext_ctx: ExtEventSink, id: WidgetId, reload: bool) {
+    let key = (entry.path.clone(), self.kind);
+    if reload {
+      CACHE.lock().unwrap().remove(&key);
+    } else if self.key.as_ref() == Some(&key) {
+      return;
+    }
+    self.key = Some(key.clone());
+    self.image = None;
+
+    if let Some(cached) = CACHE.lock().unwrap().get(&key) {
+      self.set_image(cached.clone());
+      return;
+    }
+    rayon::spawn(move || {
+      let image = find(&key.0, key.1)
+        .and_then(|path| decode(&path))
+        .map(|image| downscale(image, key.1.max_side()));
+      CACHE.lock().unwrap().insert(key.clone(), image);
+      let _ = ext_ctx.submit_command(LOADED, key, Target::Widget(id));
+    });
+  }
+
+  fn set_image(&mut self, image: Option<ImageBuf>) {
+    self.image = image.filter(|image| image.height() > 0).map(|image| {
+      let aspect = image.width() as f64 / image.height() as f64;
+      let widget = Image::new(image)
+        .fill_mode(FillStrat::Contain)
+        .interpolation_mode(InterpolationMode::Bilinear);
+
+      (WidgetPod::new(widget), aspect)
+    })
+  }
+}
+
+impl Widget<Arc<ModEntry>> for Thumbnail {
+  fn event(&mut self, ctx: &mut EventCtx, event: &Event, _: &mut Arc<ModEntry>, _: &Env) {
+    if let Event::Command(cmd) = event
+      && let Some(key) = cmd.get(LOADED)
+    {
+      if self.key.as_ref() == Some(key) {
+        let image = CACHE.lock().unwrap().get(key).cloned().flatten();
+        self.set_image(image);
+        ctx.children_changed();
+      }
+      ctx.set_handled();
+    }
+  }
+
+  fn lifecycle(
+    &mut self,
+    ctx: &mut LifeCycleCtx,
+    event: &LifeCycle,
+    data: &Arc<ModEntry>,
+    env: &Env,
+  ) {
+    if let LifeCycle::WidgetAdded = event {
+      self.load(data, ctx.get_external_handle(), ctx.widget_id(), false);
+    }
+    if let Some((image, _)) = &mut self.image {
+      image.lifecycle(ctx, event, data, env)
+    }
+  }
+
+  fn update(&mut self, ctx: &mut UpdateCtx, old: &Arc<ModEntry>, data: &Arc<ModEntry>, env: &Env) {
+    // a reinstall or update may have brought new images
+    let reinstalled = old.manager_metadata.install_date != data.manager_metadata.install_date;
+    if old.path != data.path || reinstalled {
+      self.load(
+        data,
+        ctx.get_external_handle(),
+        ctx.widget_id(),
+        reinstalled,
+      );
+      ctx.children_changed();
+    } else if let Some((image, _)) = &mut self.image {
+      image.update(ctx, data, env)
+    }
+  }
+
+  fn layout(
+    &mut self,
+    ctx: &mut LayoutCtx,
+    bc: &BoxConstraints,
+    data: &Arc<ModEntry>,
+    env: &Env,
+  ) -> Size {
+    let Some((image, aspect)) = &mut self.image else {
+      return bc.min();
+    };
+
+    let (size, gap) = match self.kind {
+      Kind::Icon => (Size::new(self.height, self.height), ICON_GAP),
+      Kind::Banner => {
+        let width = if bc.is_width_bounded() {
+          bc.max().width
+        } else {
+          self.height * *aspect
+        };
+
+        (Size::new(width, (width / *aspect).min(self.height)), 0.)
+      }
+    };
+    image.layout(ctx, &BoxConstraints::tight(size), data, env);
+    image.set_origin(ctx, Point::ORIGIN);
+
+    bc.constrain(Size::new(size.width + gap, size.height))
+  }
+
+  fn paint(&mut self, ctx: &mut PaintCtx, data: &Arc<ModEntry>, env: &Env) {
+    if let Some((image, _)) = &mut self.image {
+      image.paint(ctx, data, env)
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::fs;
+
+  use druid::{piet::ImageFormat, ImageBuf};
+
+  use super::{downscale, find, Kind};
+
+  #[test]
+  fn finds_icons_and_banners() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("graphics")).unwrap();
+    for file in ["graphics/icon_large.png", "graphics/Icon.png", "banner.txt"] {
+      fs::write(dir.path().join(file), []).unwrap();
+    }
+
+    assert_eq!(
+      find(dir.path(), Kind::Icon),
+      Some(dir.path().join("graphics/Icon.png"))
+    );
+    // falls back to the icon when there's no banner
+    assert_eq!(
+      find(dir.path(), Kind::Banner),
+      Some(dir.path().join("graphics/Icon.png"))
+    );
+
+    fs::write(dir.path().join("banner.png"), []).unwrap();
+    assert_eq!(
+      find(dir.path(), Kind::Banner),
+      Some(dir.path().join("banner.png"))
+    );
+  }
+
+  #[test]
+  fn downscales_large_images() {
+    let pixels = vec![0u8, 2, 4, 6, 2, 4, 6, 8];
+    let image = ImageBuf::from_raw(pixels, ImageFormat::Grayscale, 4, 2);
+
+    let scaled = downscale(image, 2);
+    assert_eq!((scaled.width(), scaled.height()), (2, 1));
+    assert_eq!(scaled.raw_pixels(), &[2, 6]);
+  }
+}
@@ -1,10 +1,11 @@
 use std::{
-  collections::HashSet,
+  collections::{HashMap, HashSet},
   path::{Path, PathBuf},
   rc::Rc,
-  sync::Arc,
+  sync::{Arc, Mutex},
 };
 
+use chrono::Local;
 use druid::{
   im::Vector,
   lens, theme,
@@ -15,53 +16,310 @@ use druid::{
 use druid_widget_nursery::WidgetExt as WidgetExtNursery;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
 use sublime_fuzzy::best_match;
+use tracing::{error, warn};
+use webview_shared::PROJECT;
+use xxhash_rust::xxh3::xxh3_64;
 
 use crate::app::util::StarsectorVersionDiff;
 
 use super::{
-  installer::HybridPath,
-  mod_entry::{GameVersion, ModEntry, ModMetadata, UpdateStatus},
+  compat_db::CompatDb,
+  history,
+  i18n::tr,
+  installer::{HybridPath, OverwriteMode},
+  mod_entry::{
+    parse_cache::ParseCache, GameVersion, ModEntry, ModMetadata, ModVersionMeta, UpdateStatus,
+  },
+  progress::{self, Progress},
+  update_check,
   util::{self, xxHashMap, LoadBalancer, SaveError},
 };
 
 pub mod headings;
-use self::headings::{Header, Heading};
+pub mod library;
+pub mod link_farm;
+pub mod query;
+pub mod share;
+pub mod status_bar;
+use self::{
+  headings::{Header, Heading},
+  query::Query,
+};
 
 static UPDATE_BALANCER: LoadBalancer<Arc<ModEntry>, Vec<Arc<ModEntry>>, Vec<Arc<ModEntry>>> =
-  LoadBalancer::new(ModList::SUBMIT_ENTRY);
+  LoadBalancer::new("mod list", ModList::SUBMIT_ENTRY);
 
 #[derive(Clone, Data, Lens)]
 pub struct ModList {
-  pub mods: xxHashMap<String, Arc<ModEntry>>,
+  /// Every mod in the list, by path.
+  pub mods: xxHashMap<PathBuf, Arc<ModEntry>>,
+  /// The path of the mod used for each ID. Where several distinct mods share an ID, usually
+  /// forks, only this one can be enabled.
+  ids: xxHashMap<String, PathBuf>,
   pub header: Header,
   search_text: String,
   #[data(same_fn = "PartialEq::eq")]
   active_filters: HashSet<Filters>,
+  /// The version mods are checked against, the override from the settings if one is set.
   starsector_version: Option<GameVersion>,
+  /// The version read from the install, kept so clearing the override goes back to it.
+  detected_version: Option<GameVersion>,
+  pub query_text: String,
+  #[data(same_fn = "PartialEq::eq")]
+  query: Option<Query>,
+  pub query_error: Option<String>,
+  /// Saved queries toggled on as filters, keyed by their text.
+  #[data(same_fn = "PartialEq::eq")]
+  active_queries: HashMap<String, Query>,
+  /// Name typed in for saving the current filters as a [`FilterPreset`].
+  pub preset_name: String,
+  /// Known conflicts and abandoned mods, flagged on each entry as it's listed.
+  compat: Arc<CompatDb>,
 }
 
 impl ModList {
   pub const SUBMIT_ENTRY: Selector<Vec<Arc<ModEntry>>> = Selector::new("mod_list.submit_entry");
-  pub const OVERWRITE: Selector<(PathBuf, HybridPath, Arc<ModEntry>)> =
+  pub const OVERWRITE: Selector<(PathBuf, HybridPath, Arc<ModEntry>, OverwriteMode)> =
     Selector::new("mod_list.install.overwrite");
   pub const AUTO_UPDATE: Selector<Arc<ModEntry>> = Selector::new("mod_list.install.auto_update");
   pub const SEARCH_UPDATE: Selector<()> = Selector::new("mod_list.filter.search.update");
   pub const FILTER_UPDATE: Selector<(Filters, bool)> = Selector::new("mod_list.filter.update");
+  pub const QUERY_UPDATE: Selector<()> = Selector::new("mod_list.filter.query.update");
+  pub const SAVED_QUERY_UPDATE: Selector<(String, bool)> =
+    Selector::new("mod_list.filter.query.saved.update");
+  pub const APPLY_PRESET: Selector<FilterPreset> = Selector::new("mod_list.filter.preset.apply");
+  /// Shows only what the given filters leave, or everything if that's what's already shown.
+  pub const QUICK_FILTER: Selector<&'static [Filters]> = Selector::new("mod_list.filter.quick");
+  /// Sent as the mods folder starts being scanned, with how many of its folders have been loaded.
+  pub const SCAN_STARTED: Selector<Progress> = Selector::new("mod_list.scan.started");
+  /// Sent once every folder has been loaded.
+  pub const SCAN_FINISHED: Selector<()> = Selector::new("mod_list.scan.finished");
+  /// Mods found by the last scan that couldn't be loaded.
+  pub const PARSE_FAILURES: Selector<Vector<ParseFailure>> =
+    Selector::new("mod_list.scan.parse_failures");
+  /// `enabled_mods.json` in the given install dir exists but couldn't be read, so the mod list
+  /// wasn't loaded.
+  pub const ENABLED_MODS_UNREADABLE: Selector<PathBuf> =
+    Selector::new("mod_list.enabled_mods.unreadable");
   pub const DUPLICATE: Selector<(Arc<ModEntry>, Arc<ModEntry>)> =
     Selector::new("mod_list.submit_entry.duplicate");
 
-  pub fn new(headings: Vector<Heading>) -> Self {
+  pub fn new(
+    headings: Vector<Heading>,
+    sort: Vector<(Heading, bool)>,
+    columns: Option<Vector<f64>>,
+  ) -> Self {
     Self {
       mods: xxHashMap::new(),
-      header: Header::new(headings),
+      ids: xxHashMap::new(),
+      header: Header::new(headings, sort).with_ratios(columns),
       search_text: String::new(),
       active_filters: HashSet::new(),
       starsector_version: None,
+      detected_version: None,
+      query_text: String::new(),
+      query: None,
+      query_error: None,
+      active_queries: HashMap::new(),
+      preset_name: String::new(),
+      compat: Arc::default(),
+    }
+  }
+
+  /// Whether the saved query `text` is toggled on as a filter.
+  pub fn query_active(text: String) -> impl Lens<ModList, bool> {
+    lens::Map::new(
+      move |list: &ModList| list.active_queries.contains_key(&text),
+      |_, _| {},
+    )
+  }
+
+  pub fn set_compat(&mut self, compat: Arc<CompatDb>) {
+    self.compat = compat
+  }
+
+  pub fn compat(&self) -> &CompatDb {
+    &self.compat
+  }
+
+  pub fn set_detected_version(
+    &mut self,
+    version: Option<GameVersion>,
+    overridden: Option<GameVersion>,
+  ) {
+    self.detected_version = version;
+    self.set_version_override(overridden);
+  }
+
+  /// Checks mods against `overridden` rather than the detected version, or goes back to the
+  /// detected version if it's `None`.
+  pub fn set_version_override(&mut self, overridden: Option<GameVersion>) {
+    self.starsector_version = overridden.or_else(|| self.detected_version.clone())
+  }
+
+  /// The mod used for `id`, the one that can be enabled if several share it.
+  pub fn get(&self, id: &str) -> Option<&Arc<ModEntry>> {
+    self.ids.get(id).and_then(|path| self.mods.get(path))
+  }
+
+  /// Whether `entry` is the mod used for its ID. Others sharing its ID are always disabled.
+  pub fn can_enable(&self, entry: &ModEntry) -> bool {
+    self.ids.get(&entry.id) == Some(&entry.path)
+  }
+
+  /// The mod used for each ID, leaving out any others that share it.
+  pub fn used(&self) -> impl Iterator<Item = &Arc<ModEntry>> {
+    self.mods.values().filter(|entry| self.can_enable(entry))
+  }
+
+  /// Whether the same mod is used for each ID as in `other`.
+  pub fn uses_same(&self, other: &Self) -> bool {
+    self.ids.same(&other.ids)
+  }
+
+  /// Whether the list holds the same mods as `other`, enabled or not. A scan, refresh or install
+  /// adds or removes mods, where a user enabling or disabling them doesn't.
+  pub fn has_same_mods(&self, other: &Self) -> bool {
+    self.mods.same(&other.mods)
+      || (self.mods.len() == other.mods.len()
+        && self.mods.keys().all(|path| other.mods.contains_key(path)))
+  }
+
+  /// Adds a newly found mod. If a mod with the same ID is already in the list, one with a
+  /// different name is a separate mod, such as a fork, and is kept alongside it. Otherwise it's
+  /// another copy of the same mod and isn't added, the copy already in the list is returned.
+  ///
+  /// Of the mods sharing an ID, an enabled one is used over a disabled one, then the first by
+  /// path, so the same one is used however the mods folder was scanned. The others are disabled.
+  pub fn add(&mut self, mut entry: Arc<ModEntry>) -> Option<Arc<ModEntry>> {
+    let Some(used) = self.get(&entry.id).cloned() else {
+      self.ids.insert(entry.id.clone(), entry.path.clone());
+      self.mods.insert(entry.path.clone(), entry);
+      return None;
+    };
+    if let Some(copy) = self
+      .mods
+      .values()
+      .find(|other| other.id == entry.id && other.name == entry.name)
+    {
+      return Some(copy.clone());
+    }
+
+    let preferred = (!entry.enabled, &entry.path) < (!used.enabled, &used.path);
+    let mut unused = if preferred {
+      self.ids.insert(entry.id.clone(), entry.path.clone());
+      self.mods.insert(entry.path.clone(), entry);
+      used
+    } else {
+      entry
+    };
+    // enabled_mods.json only knows the ID, so both may have been loaded as enabled
+    if unused.enabled {
+      Arc::make_mut(&mut unused).enabled = false;
+    }
+    self.mods.insert(unused.path.clone(), unused);
+
+    None
+  }
+
+  /// Replaces the entry for the mod at `entry`'s path. Enabling a mod that shares its ID with the
+  /// one used for it swaps the two, disabling the other, so no more than one is ever enabled.
+  pub fn replace(&mut self, entry: Arc<ModEntry>) {
+    // the mod's ID may have been edited
+    if let Some(previous) = self.mods.get(&entry.path)
+      && previous.id != entry.id
+    {
+      let path = previous.path.clone();
+      self.remove(&path);
+    }
+
+    match self.ids.get(&entry.id).cloned() {
+      Some(used) if used != entry.path && entry.enabled => {
+        if let Some(mut used) = self.mods.get(&used).cloned() {
+          Arc::make_mut(&mut used).enabled = false;
+          self.mods.insert(used.path.clone(), used);
+        }
+        self.ids.insert(entry.id.clone(), entry.path.clone());
+      }
+      Some(_) => {}
+      None => {
+        self.ids.insert(entry.id.clone(), entry.path.clone());
+      }
+    }
+    self.mods.insert(entry.path.clone(), entry);
+  }
+
+  /// Removes the mod at `path`. If it was the one used for its ID, the first by path of any others
+  /// sharing the ID takes its place.
+  pub fn remove(&mut self, path: &Path) -> Option<Arc<ModEntry>> {
+    let removed = self.mods.remove(path)?;
+    if self.ids.get(&removed.id).map(PathBuf::as_path) == Some(path) {
+      self.ids.remove(&removed.id);
+      let next = self
+        .mods
+        .values()
+        .filter(|entry| entry.id == removed.id)
+        .min_by(|a, b| a.path.cmp(&b.path))
+        .map(|entry| entry.path.clone());
+      if let Some(next) = next {
+        self.ids.insert(removed.id.clone(), next);
+      }
+    }
+
+    Some(removed)
+  }
+
+  /// The current filters, saved queries, search and sort, saved as `name`.
+  pub fn preset(&self, name: impl Into<String>) -> FilterPreset {
+    let mut queries: Vector<String> = self.active_queries.keys().cloned().collect();
+    queries.sort();
+
+    FilterPreset {
+      name: name.into(),
+      filters: Filters::iter()
+        .filter(|filter| self.active_filters.contains(filter))
+        .collect(),
+      queries,
+      search: self.search_text.clone(),
+      sort: self.header.sort(),
+    }
+  }
+
+  /// Replaces the current filters, saved queries, search and sort with those saved in `preset`.
+  pub fn apply_preset(&mut self, preset: &FilterPreset) {
+    self.active_filters = preset.filters.iter().copied().collect();
+    self.active_queries = preset
+      .queries
+      .iter()
+      .filter_map(|text| Some((text.clone(), Query::parse(text).ok()?)))
+      .collect();
+    self.search_text = preset.search.clone();
+    self.header.then_by = preset.sort.clone();
+    self.header.sort_by = self
+      .header
+      .then_by
+      .pop_front()
+      .unwrap_or((Heading::Name, false));
+  }
+
+  /// Makes `filters` the only active filters, or clears them if they already are.
+  pub fn quick_filter(&mut self, filters: &[Filters]) {
+    let filters: HashSet<Filters> = filters.iter().copied().collect();
+    self.active_filters = if self.active_filters == filters {
+      HashSet::new()
+    } else {
+      filters
     }
   }
 
+  pub fn clear(&mut self) {
+    self.mods.clear();
+    self.ids.clear();
+  }
+
   pub fn ui_builder() -> impl Widget<Self> {
     Flex::column()
       .with_child(headings::Header::ui_builder().lens(ModList::header))
@@ -153,11 +411,12 @@ impl ModList {
             })
             .background(theme::BACKGROUND_LIGHT)
             .on_command(ModEntry::REPLACE, |ctx, payload, data: &mut ModList| {
-              data.mods.insert(payload.id.clone(), payload.clone());
+              data.replace(payload.clone());
               ctx.children_changed();
             })
             .on_command(ModList::SEARCH_UPDATE, |ctx, _, data| {
               data.header.sort_by = (Heading::Score, true);
+              data.header.then_by.clear();
               ctx.children_changed()
             })
             .on_command(ModList::FILTER_UPDATE, |ctx, (filter, insert), data| {
@@ -167,133 +426,132 @@ impl ModList {
                 data.active_filters.remove(filter)
               };
               ctx.children_changed()
+            })
+            .on_command(ModList::APPLY_PRESET, |ctx, preset, data| {
+              data.apply_preset(preset);
+              ctx.children_changed()
+            })
+            .on_command(ModList::QUICK_FILTER, |ctx, filters, data| {
+              data.quick_filter(filters);
+              ctx.children_changed()
+            })
+            .on_command(ModList::QUERY_UPDATE, |ctx, _, data| {
+              if data.query_text.trim().is_empty() {
+                data.query = None;
+                data.query_error = None;
+              } else {
+                match Query::parse(&data.query_text) {
+                  Ok(query) => {
+                    data.query = Some(query);
+                    data.query_error = None;
+                  }
+                  // keep filtering by the last valid query while the user is still typing
+                  Err(err) => data.query_error = Some(err),
+                }
+              }
+              ctx.children_changed()
+            })
+            .on_command(ModList::SAVED_QUERY_UPDATE, |ctx, (text, insert), data| {
+              if *insert && let Ok(query) = Query::parse(text) {
+                data.active_queries.insert(text.clone(), query);
+              } else {
+                data.active_queries.remove(text);
+              }
+              ctx.children_changed()
             }),
           )
           .vertical(),
-          Label::new("No mods")
+          Label::new(tr("mod-list-empty"))
             .expand()
             .background(theme::BACKGROUND_LIGHT),
         ),
         1.,
       )
+      .with_child(status_bar::ui_builder())
       .on_command(ModList::SUBMIT_ENTRY, |ctx, payload, data| {
         for entry in payload {
-          *data.mods = data.mods.alter(
-            |existing| {
-              if let Some(inner) = &existing {
-                ctx.submit_command(ModList::DUPLICATE.with((inner.clone(), entry.clone())));
-                existing
-              } else {
-                Some(entry.clone())
-              }
-            },
-            entry.id.clone(),
-          );
+          if let Some(existing) = data.add(entry.clone()) {
+            ctx.submit_command(ModList::DUPLICATE.with((existing, entry.clone())));
+          }
         }
       })
-      .on_command(Header::SORT_CHANGED, |ctx, payload, data| {
-        if data.header.sort_by.0 == *payload {
-          data.header.sort_by.1 = !data.header.sort_by.1;
-        } else {
-          data.header.sort_by = (*payload, false)
-        }
+      .on_command(Header::SORT_CHANGED, |ctx, (heading, shift), data| {
+        data.header.sort_clicked(*heading, *shift);
         ctx.children_changed()
       })
       .on_command(util::MASTER_VERSION_RECEIVED, |_ctx, payload, data| {
-        if let Some(mut entry) = data.mods.get(&payload.0).cloned() {
-          let remote = payload.1.as_ref().ok().cloned();
-          ModEntry::remote_version
-            .in_arc()
-            .put(&mut entry, remote.clone());
-          if let Some(version_checker) = &entry.version_checker {
-            let status = UpdateStatus::from((version_checker, &remote));
-            ModEntry::update_status
-              .in_arc()
-              .put(&mut entry, Some(status));
-          }
-          data.mods.insert(entry.id.clone(), entry);
-        };
+        data.apply_remote_version(&payload.0, &payload.1);
       })
       .on_command(
         ModMetadata::SUBMIT_MOD_METADATA,
-        |_ctx, (id, metadata), data| {
-          if let Some(mut entry) = data.mods.remove(id) {
+        |_ctx, (path, metadata), data| {
+          if let Some(mut entry) = data.mods.remove(path) {
             ModEntry::manager_metadata
               .in_arc()
               .put(&mut entry, metadata.clone());
 
-            data.mods.insert(id.clone(), entry);
+            data.mods.insert(path.clone(), entry);
           }
         },
       )
   }
 
-  pub async fn parse_mod_folder(event_sink: ExtEventSink, root_dir: Option<PathBuf>) {
-    let handle = tokio::runtime::Handle::current();
+  /// Records the result of checking a mod's remote version file. Returns whether the mod has an
+  /// update available that the user hasn't asked to ignore.
+  pub fn apply_remote_version(
+    &mut self,
+    id: &str,
+    result: &Result<ModVersionMeta, String>,
+  ) -> bool {
+    let Some(mut entry) = self.get(id).cloned() else {
+      return false;
+    };
 
-    if let Some(root_dir) = root_dir {
-      let mod_dir = root_dir.join("mods");
-      let enabled_mods_filename = mod_dir.join("enabled_mods.json");
+    let remote = result.as_ref().ok().cloned();
+    ModEntry::remote_version
+      .in_arc()
+      .put(&mut entry, remote.clone());
+    ModEntry::update_error
+      .in_arc()
+      .put(&mut entry, result.as_ref().err().cloned());
+    ModEntry::checking_update.in_arc().put(&mut entry, false);
+    let status = entry
+      .version_checker
+      .as_ref()
+      .map(|version_checker| UpdateStatus::from((version_checker, &remote)));
+    let update_available = matches!(
+      status,
+      Some(UpdateStatus::Major(_) | UpdateStatus::Minor(_) | UpdateStatus::Patch(_))
+    );
+    if status.is_some() {
+      ModEntry::update_status.in_arc().put(&mut entry, status);
+    }
+    let update_available = update_available && !entry.update_ignored();
+    self.mods.insert(entry.path.clone(), entry);
 
-      let enabled_mods = if !enabled_mods_filename.exists() {
-        vec![]
-      } else if let Ok(enabled_mods_text) = std::fs::read_to_string(enabled_mods_filename)
-        && let Ok(EnabledMods { enabled_mods }) = serde_json::from_str::<EnabledMods>(&enabled_mods_text)
-      {
-        enabled_mods
-      } else {
-        return
-      };
+    update_available
+  }
 
-      if let Ok(dir_iter) = std::fs::read_dir(mod_dir) {
-        let enabled_mods_iter = enabled_mods.par_iter();
+  /// Loads every mod in the install dir's mods folder, plus any in `external_dirs`. Links in the
+  /// mods folder to external mods are skipped, those mods are found in their external dir.
+  ///
+  /// Folders are parsed in parallel on a blocking thread, and each mod is streamed into the list
+  /// as soon as it's loaded, counted by the [`Progress`] sent in [`ModList::SCAN_STARTED`].
+  pub async fn parse_mod_folder(
+    event_sink: ExtEventSink,
+    root_dir: Option<PathBuf>,
+    external_dirs: Vec<PathBuf>,
+  ) {
+    let handle = tokio::runtime::Handle::current();
 
-        dir_iter
-          .par_bridge()
-          .filter_map(|entry| entry.ok())
-          .filter(|entry| {
-            if let Ok(file_type) = entry.file_type() {
-              file_type.is_dir()
-            } else {
-              false
-            }
-          })
-          .filter_map(|entry| {
-            if let Ok(mut mod_info) = ModEntry::from_file(&entry.path(), ModMetadata::default()) {
-              mod_info.set_enabled(
-                enabled_mods_iter
-                  .clone()
-                  .find_any(|id| mod_info.id.clone().eq(*id))
-                  .is_some(),
-              );
-              Some(Arc::new(mod_info))
-            } else {
-              dbg!(entry.path());
-              None
-            }
-          })
-          .for_each(|entry| {
-            let tx = {
-              let _guard = handle.enter();
-
-              UPDATE_BALANCER.sender(event_sink.clone())
-            };
-
-            if let Err(err) = tx.send(entry.clone()) {
-              eprintln!("Failed to submit found mod {}", err);
-            };
-            if let Some(version) = entry.version_checker.clone() {
-              handle.spawn(util::get_master_version(event_sink.clone(), version));
-            }
-            if ModMetadata::path(&entry.path).exists() {
-              handle.spawn(ModMetadata::parse_and_send(
-                entry.id.clone(),
-                entry.path.clone(),
-                event_sink.clone(),
-              ));
-            }
-          });
+    let scan_sink = event_sink.clone();
+    let scan = tokio::task::spawn_blocking(move || {
+      if let Some(root_dir) = root_dir {
+        Self::scan_mod_folder(handle, scan_sink, root_dir, external_dirs)
       }
+    });
+    if let Err(err) = scan.await {
+      error!("Mod folder scan failed {:?}", err)
     }
 
     if event_sink
@@ -306,80 +564,264 @@ impl ModList {
     };
   }
 
-  fn sorted_vals(&self) -> Vec<Arc<ModEntry>> {
-    let mut values: Vec<Arc<ModEntry>> = self
-      .mods
-      .iter()
-      .filter_map(|(_, entry)| {
-        let search = if let Heading::Score = self.header.sort_by.0 {
-          if !self.search_text.is_empty() {
-            let id_score = best_match(&self.search_text, &entry.id).map(|m| m.score());
-            let name_score = best_match(&self.search_text, &entry.name).map(|m| m.score());
-            let author_score = best_match(&self.search_text, &entry.author).map(|m| m.score());
-
-            id_score.is_some() || name_score.is_some() || author_score.is_some()
-          } else {
-            true
+  fn scan_mod_folder(
+    handle: tokio::runtime::Handle,
+    event_sink: ExtEventSink,
+    root_dir: PathBuf,
+    external_dirs: Vec<PathBuf>,
+  ) {
+    let mod_dir = util::mods_dir(&root_dir);
+
+    let Some(EnabledMods { enabled_mods }) = EnabledMods::load(&root_dir) else {
+      let _ =
+        event_sink.submit_command(ModList::ENABLED_MODS_UNREADABLE, root_dir, Target::Auto);
+      return;
+    };
+
+    let mod_dirs = std::fs::read_dir(mod_dir)
+      .into_iter()
+      .flatten()
+      .filter_map(|entry| entry.ok())
+      .filter(|entry| !link_farm::links_into(&entry.path(), &external_dirs))
+      .map(|entry| (entry, false))
+      .chain(external_dirs.iter().flat_map(|dir| {
+        std::fs::read_dir(dir)
+          .inspect_err(|err| warn!("Failed to read external mod folder {:?}: {}", dir, err))
+          .into_iter()
+          .flatten()
+          .filter_map(|entry| entry.ok())
+          .map(|entry| (entry, true))
+      }))
+      .filter(|(entry, _)| entry.file_name() != history::TRASH)
+      .collect::<Vec<_>>();
+
+    let enabled_mods: HashSet<String> = enabled_mods.into_iter().collect();
+    let cache = ParseCache::load();
+    let failures = Mutex::new(Vec::new());
+
+    let progress = Progress::new(progress::Unit::Mods);
+    progress.set_total(mod_dirs.len() as u64);
+    progress.set_stage(tr("tools-scan-stage"));
+    let _ = event_sink.submit_command(ModList::SCAN_STARTED, progress.clone(), Target::Auto);
+    let report_progress = || progress.advance(1);
+
+    let version_checkers = mod_dirs
+      .into_par_iter()
+      .filter(|(entry, _)| {
+        // follow links, an external mod may itself be a link to somewhere else
+        let is_dir = entry.path().is_dir();
+        if !is_dir {
+          report_progress()
+        }
+        is_dir
+      })
+      .filter_map(|(entry, external)| {
+        let loaded = match ModEntry::from_file_cached(&entry.path(), ModMetadata::default(), &cache)
+        {
+          Ok(mut mod_info) => {
+            mod_info.external = external;
+            mod_info.set_enabled(enabled_mods.contains(&mod_info.id));
+            Some(Arc::new(mod_info))
+          }
+          Err(err) => {
+            warn!("Failed to load mod at {:?}: {}", entry.path(), err);
+            // folders without a mod_info.json aren't mods, rather than broken ones
+            let file = entry.path().join("mod_info.json");
+            if file.exists()
+                && let Ok(mut failures) = failures.lock()
+              {
+                failures.push(ParseFailure {
+                  folder: entry.path(),
+                  file,
+                  error: err.to_string(),
+                })
+              }
+            None
           }
-        } else {
-          true
         };
-        let filters = self.active_filters.par_iter().all(|f| f.as_fn()(entry));
+        report_progress();
+        loaded
+      })
+      .filter_map(|entry| {
+        let tx = {
+          let _guard = handle.enter();
 
-        (search && filters).then(|| entry.clone())
+          UPDATE_BALANCER.sender(event_sink.clone())
+        };
+
+        if let Err(err) = tx.send_blocking(entry.clone()) {
+          error!("Failed to submit found mod {}", err);
+        };
+        if ModMetadata::path(&entry.path).exists() {
+          handle.spawn(ModMetadata::parse_and_send(
+            entry.path.clone(),
+            event_sink.clone(),
+          ));
+        }
+
+        entry.version_checker.clone()
       })
-      .collect();
+      .collect::<Vec<_>>();
 
-    values.par_sort_unstable_by(|a, b| {
-      let ord = match self.header.sort_by.0 {
-        Heading::ID => a.id.cmp(&b.id),
-        Heading::Name => a.name.cmp(&b.name),
-        Heading::Author => a.author.cmp(&b.author),
-        Heading::GameVersion => a.game_version.cmp(&b.game_version),
-        Heading::Enabled => a.enabled.cmp(&b.enabled),
-        Heading::Version => match (a.update_status.as_ref(), b.update_status.as_ref()) {
-          (None, None) => a.name.cmp(&b.name),
-          (_, _) if a.update_status.cmp(&b.update_status) == std::cmp::Ordering::Equal => {
-            a.name.cmp(&b.name)
-          }
-          (_, _) => a.update_status.cmp(&b.update_status),
-        },
-        Heading::Score => {
-          let scoring = |entry: &Arc<ModEntry>| -> Option<isize> {
-            let id_score = best_match(&self.search_text, &entry.id).map(|m| m.score());
-            let name_score = best_match(&self.search_text, &entry.name).map(|m| m.score());
-            let author_score = best_match(&self.search_text, &entry.author).map(|m| m.score());
+    cache.save();
 
-            std::cmp::max(std::cmp::max(id_score, name_score), author_score)
-          };
+    let mut failures = failures.into_inner().unwrap_or_else(|err| err.into_inner());
+    failures.sort_by(|a, b| a.folder.cmp(&b.folder));
+    let _ = event_sink.submit_command(ModList::PARSE_FAILURES, failures.into(), Target::Auto);
+    progress.finish();
+    let _ = event_sink.submit_command(ModList::SCAN_FINISHED, (), Target::Auto);
 
-          scoring(a).cmp(&scoring(b))
-        }
-        Heading::AutoUpdateSupport => a
-          .remote_version
-          .as_ref()
-          .and_then(|r| r.direct_download_url.as_ref())
-          .is_some()
-          .cmp(
-            &b.remote_version
-              .as_ref()
-              .and_then(|r| r.direct_download_url.as_ref())
-              .is_some(),
-          ),
-        Heading::InstallDate => a
-          .manager_metadata
-          .install_date
-          .cmp(&b.manager_metadata.install_date),
-      };
-
-      if self.header.sort_by.1 {
-        ord.reverse()
+    handle.spawn(update_check::sweep(event_sink.clone(), version_checkers));
+  }
+
+  /// Whether the search, filters or queries are hiding any part of the mod list.
+  pub fn is_filtered(&self) -> bool {
+    (matches!(self.header.sort_by.0, Heading::Score) && !self.search_text.is_empty())
+      || !self.active_filters.is_empty()
+      || self.query.is_some()
+      || !self.active_queries.is_empty()
+  }
+
+  /// Whether `entry` passes the search, filters and queries, and so is shown in the list.
+  pub fn is_shown(&self, entry: &Arc<ModEntry>) -> bool {
+    let search = if let Heading::Score = self.header.sort_by.0 {
+      if !self.search_text.is_empty() {
+        let id_score = best_match(&self.search_text, &entry.id).map(|m| m.score());
+        let name_score = best_match(&self.search_text, &entry.name).map(|m| m.score());
+        let author_score = best_match(&self.search_text, &entry.author).map(|m| m.score());
+
+        id_score.is_some() || name_score.is_some() || author_score.is_some()
       } else {
-        ord
+        true
       }
+    } else {
+      true
+    };
+    let filters = self.active_filters.par_iter().all(|f| f.as_fn()(entry));
+    let queries = self
+      .query
+      .as_ref()
+      .map_or(true, |query| query.matches(entry))
+      && self
+        .active_queries
+        .values()
+        .all(|query| query.matches(entry));
+
+    search && filters && queries
+  }
+
+  fn sorted_vals(&self) -> Vec<Arc<ModEntry>> {
+    let installed: HashMap<String, &str> = if self.compat.is_empty() {
+      HashMap::new()
+    } else {
+      self
+        .used()
+        .map(|entry| (entry.id.to_lowercase(), entry.name.as_str()))
+        .collect()
+    };
+    let shared_ids: HashSet<&str> = self
+      .mods
+      .values()
+      .filter(|entry| !self.can_enable(entry))
+      .map(|entry| entry.id.as_str())
+      .collect();
+    let mut values: Vec<Arc<ModEntry>> = self
+      .mods
+      .values()
+      .filter(|entry| self.is_shown(entry))
+      .cloned()
+      .map(|mut entry| {
+        let collides = shared_ids.contains(entry.id.as_str());
+        if entry.id_collision != collides {
+          Arc::make_mut(&mut entry).id_collision = collides;
+        }
+        let warning = self.compat.warning_text(&entry.id, |id| {
+          installed.get(id).map(|name| name.to_string())
+        });
+        if entry.compat_warning != warning {
+          Arc::make_mut(&mut entry).compat_warning = warning;
+        }
+        entry
+      })
+      .collect();
+
+    // the map iterates in no particular order, starting from IDs and paths and sorting stably
+    // means mods that tie on every key keep the same order between refreshes
+    values.sort_unstable_by(|a, b| a.id.cmp(&b.id).then_with(|| a.path.cmp(&b.path)));
+    let sort = self.header.sort();
+    values.par_sort_by(|a, b| {
+      sort
+        .iter()
+        .fold(std::cmp::Ordering::Equal, |ord, (heading, descending)| {
+          ord.then_with(|| {
+            let ord = self.compare(*heading, a, b);
+            if *descending {
+              ord.reverse()
+            } else {
+              ord
+            }
+          })
+        })
     });
     values
   }
+
+  /// How `a` and `b` compare on `heading`, ascending.
+  fn compare(&self, heading: Heading, a: &Arc<ModEntry>, b: &Arc<ModEntry>) -> std::cmp::Ordering {
+    match heading {
+      Heading::ID => a.id.cmp(&b.id),
+      Heading::Name => a.name.cmp(&b.name),
+      Heading::Author => a.author.cmp(&b.author),
+      Heading::GameVersion => a.game_version.cmp(&b.game_version),
+      Heading::Enabled => a.enabled.cmp(&b.enabled),
+      Heading::Version => match (a.update_status.as_ref(), b.update_status.as_ref()) {
+        (None, None) => a.name.cmp(&b.name),
+        (_, _) if a.update_status.cmp(&b.update_status) == std::cmp::Ordering::Equal => a
+          .version
+          .loose()
+          .cmp(&b.version.loose())
+          .then_with(|| a.name.cmp(&b.name)),
+        (_, _) => a.update_status.cmp(&b.update_status),
+      },
+      Heading::Score => {
+        let scoring = |entry: &Arc<ModEntry>| -> Option<isize> {
+          let id_score = best_match(&self.search_text, &entry.id).map(|m| m.score());
+          let name_score = best_match(&self.search_text, &entry.name).map(|m| m.score());
+          let author_score = best_match(&self.search_text, &entry.author).map(|m| m.score());
+
+          std::cmp::max(std::cmp::max(id_score, name_score), author_score)
+        };
+
+        scoring(a).cmp(&scoring(b))
+      }
+      Heading::AutoUpdateSupport => a
+        .remote_version
+        .as_ref()
+        .and_then(|r| r.direct_download_url.as_ref())
+        .is_some()
+        .cmp(
+          &b.remote_version
+            .as_ref()
+            .and_then(|r| r.direct_download_url.as_ref())
+            .is_some(),
+        ),
+      Heading::InstallDate => a
+        .manager_metadata
+        .install_date
+        .cmp(&b.manager_metadata.install_date),
+      Heading::LastUpdated => {
+        let updated = |entry: &Arc<ModEntry>| entry.repo_record.as_ref()?.updated;
+
+        updated(a).cmp(&updated(b))
+      }
+      Heading::Size => a.size.cmp(&b.size),
+      Heading::AuthorActivity => {
+        let activity = |entry: &Arc<ModEntry>| entry.repo_record.as_ref()?.author_activity;
+
+        activity(a).cmp(&activity(b))
+      }
+    }
+  }
 }
 
 type EntryAlias = (
@@ -447,18 +889,117 @@ impl EnabledMods {
     }
   }
 
+  /// Reads `enabled_mods.json` from the given install dir. A missing file counts as no mods being
+  /// enabled, `None` is only returned if the file exists but can't be read.
+  pub fn load(path: &Path) -> Option<Self> {
+    let enabled_mods_filename = util::mods_dir(path).join("enabled_mods.json");
+
+    if !enabled_mods_filename.exists() {
+      Some(Self::empty())
+    } else {
+      std::fs::read_to_string(enabled_mods_filename)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+    }
+  }
+
+  pub fn contains(&self, id: &str) -> bool {
+    self.enabled_mods.iter().any(|enabled| enabled == id)
+  }
+
+  pub fn ids(&self) -> &[String] {
+    &self.enabled_mods
+  }
+
+  /// Writes `enabled_mods.json` to a temporary file first and moves it into place, so the game
+  /// never sees a half written file, then keeps a copy in the backup dir unless it's empty or
+  /// the same as the newest copy.
   pub fn save(self, path: &Path) -> Result<(), SaveError> {
     use std::fs;
     use std::io::Write;
 
     let json = serde_json::to_string_pretty(&self).map_err(|_| SaveError::Format)?;
 
-    let mut file =
-      fs::File::create(path.join("mods").join("enabled_mods.json")).map_err(|_| SaveError::File)?;
+    let destination = util::mods_dir(path).join("enabled_mods.json");
+    let temp = destination.with_extension("json.tmp");
+    let mut file = fs::File::create(&temp).map_err(|_| SaveError::File)?;
 
     file
       .write_all(json.as_bytes())
-      .map_err(|_| SaveError::Write)
+      .and_then(|_| file.sync_all())
+      .map_err(|_| SaveError::Write)?;
+    drop(file);
+
+    fs::rename(&temp, &destination).map_err(|_| SaveError::File)?;
+
+    if let Err(err) = self.backup(path, &json) {
+      warn!("Failed to back up enabled_mods.json {:?}", err)
+    }
+
+    Ok(())
+  }
+
+  /// Where copies of the given install's `enabled_mods.json` are kept, so restoring never brings
+  /// back another install's mods.
+  pub fn backup_dir(install_dir: &Path) -> PathBuf {
+    PROJECT
+      .data_dir()
+      .join("enabled_mods_backups")
+      .join(format!(
+        "{:016x}",
+        xxh3_64(install_dir.to_string_lossy().as_bytes())
+      ))
+  }
+
+  /// Keeps a copy of a newly written file, dropping the oldest copies past [`BACKUP_LIMIT`].
+  fn backup(&self, install_dir: &Path, json: &str) -> std::io::Result<()> {
+    // an empty list isn't worth restoring, and a copy of the newest backup would only push out an
+    // older one that might be
+    let newest = Self::backups(install_dir)
+      .first()
+      .and_then(|newest| Self::load_backup(newest));
+    if self.enabled_mods.is_empty()
+      || newest.is_some_and(|newest| newest.enabled_mods == self.enabled_mods)
+    {
+      return Ok(());
+    }
+
+    std::fs::create_dir_all(Self::backup_dir(install_dir))?;
+    std::fs::write(
+      Self::backup_dir(install_dir).join(format!(
+        "enabled_mods-{}.json",
+        Local::now().format("%Y%m%d-%H%M%S%3f")
+      )),
+      json,
+    )?;
+
+    for old in Self::backups(install_dir).into_iter().skip(BACKUP_LIMIT) {
+      std::fs::remove_file(old)?;
+    }
+
+    Ok(())
+  }
+
+  /// Every backup of the given install's `enabled_mods.json`, newest first.
+  pub fn backups(install_dir: &Path) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = Self::backup_dir(install_dir)
+      .read_dir()
+      .into_iter()
+      .flatten()
+      .flatten()
+      .map(|entry| entry.path())
+      .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+      .collect();
+    // names start with when they were written
+    backups.sort_by(|a, b| b.cmp(a));
+
+    backups
+  }
+
+  pub fn load_backup(backup: &Path) -> Option<Self> {
+    std::fs::read_to_string(backup)
+      .ok()
+      .and_then(|text| serde_json::from_str(&text).ok())
   }
 }
 
@@ -476,7 +1017,20 @@ impl From<Vec<String>> for EnabledMods {
   }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash, Data, EnumIter, Display)]
+/// A mod folder whose `mod_info.json` couldn't be read or parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFailure {
+  pub folder: PathBuf,
+  pub file: PathBuf,
+  pub error: String,
+}
+
+/// How many copies of `enabled_mods.json` are kept in [`EnabledMods::backup_dir`].
+const BACKUP_LIMIT: usize = 10;
+
+#[derive(
+  Debug, Clone, Copy, Eq, PartialEq, Hash, Data, EnumIter, Display, Serialize, Deserialize,
+)]
 pub enum Filters {
   Enabled,
   Disabled,
@@ -488,13 +1042,69 @@ pub enum Filters {
   Patch,
   Minor,
   Major,
+  #[strum(to_string = "Not Checked Yet")]
+  Unchecked,
   #[strum(to_string = "Auto Update Available")]
   AutoUpdateAvailable,
   #[strum(to_string = "Auto Update Unsupported")]
   AutoUpdateUnsupported,
+  #[strum(to_string = "Installed This Week")]
+  InstalledWeek,
+  #[strum(to_string = "Installed This Month")]
+  InstalledMonth,
+  #[strum(to_string = "Updated This Week")]
+  UpdatedWeek,
+  #[strum(to_string = "Updated This Month")]
+  UpdatedMonth,
+  #[strum(to_string = "No Remote Version")]
+  Unmatched,
 }
 
 impl Filters {
+  pub fn label(&self) -> String {
+    tr(match self {
+      Filters::Enabled => "filter-enabled",
+      Filters::Disabled => "filter-disabled",
+      Filters::Unimplemented => "filter-unimplemented",
+      Filters::Error => "filter-error",
+      Filters::Discrepancy => "filter-discrepancy",
+      Filters::UpToDate => "filter-up-to-date",
+      Filters::Patch => "filter-patch",
+      Filters::Minor => "filter-minor",
+      Filters::Major => "filter-major",
+      Filters::Unchecked => "filter-unchecked",
+      Filters::AutoUpdateAvailable => "filter-auto-update-available",
+      Filters::AutoUpdateUnsupported => "filter-auto-update-unsupported",
+      Filters::InstalledWeek => "filter-installed-week",
+      Filters::InstalledMonth => "filter-installed-month",
+      Filters::UpdatedWeek => "filter-updated-week",
+      Filters::UpdatedMonth => "filter-updated-month",
+      Filters::Unmatched => "filter-unmatched",
+    })
+  }
+
+  /// Whether the filter's checkbox is ticked: smart filters are ticked while active, the others
+  /// while inactive.
+  pub fn ticked(self) -> impl Lens<ModList, bool> {
+    lens::Map::new(
+      move |list: &ModList| list.active_filters.contains(&self) == self.is_smart(),
+      |_, _| {},
+    )
+  }
+
+  /// Whether the filter shows only the mods it matches, rather than hiding them. These start off
+  /// and are turned on, where the others start on and are turned off.
+  pub fn is_smart(&self) -> bool {
+    matches!(
+      self,
+      Filters::InstalledWeek
+        | Filters::InstalledMonth
+        | Filters::UpdatedWeek
+        | Filters::UpdatedMonth
+        | Filters::Unmatched
+    )
+  }
+
   fn as_fn(&self) -> impl FnMut(&Arc<ModEntry>) -> bool {
     match self {
       Filters::Enabled => |entry: &Arc<ModEntry>| !entry.enabled,
@@ -516,6 +1126,10 @@ impl Filters {
       Filters::Major => {
         |entry: &Arc<ModEntry>| !matches!(entry.update_status, Some(UpdateStatus::Major(_)))
       }
+      // has a version file, but no status until it's been checked
+      Filters::Unchecked => {
+        |entry: &Arc<ModEntry>| entry.version_checker.is_none() || entry.update_status.is_some()
+      }
       Filters::AutoUpdateAvailable => |entry: &Arc<ModEntry>| {
         matches!(
           entry.update_status,
@@ -538,6 +1152,127 @@ impl Filters {
           .and_then(|r| r.direct_download_url.as_ref())
           .is_some()
       },
+      Filters::InstalledWeek => |entry: &Arc<ModEntry>| entry.manager_metadata.installed_within(7),
+      Filters::InstalledMonth => {
+        |entry: &Arc<ModEntry>| entry.manager_metadata.installed_within(30)
+      }
+      Filters::UpdatedWeek => |entry: &Arc<ModEntry>| entry.manager_metadata.updated_within(7),
+      Filters::UpdatedMonth => |entry: &Arc<ModEntry>| entry.manager_metadata.updated_within(30),
+      // there's no source to ask, or asking it failed. Mods that haven't been checked yet have
+      // neither a version nor an error, and aren't counted until they have one or the other
+      Filters::Unmatched => |entry: &Arc<ModEntry>| {
+        entry.remote_version.is_none()
+          && (entry.version_checker.is_none() || entry.update_error.is_some())
+      },
     }
   }
 }
+
+/// A named combination of filters, saved queries, search and sort, saved in the settings and
+/// picked from the filters panel.
+#[derive(Debug, Clone, Data, PartialEq, Serialize, Deserialize)]
+pub struct FilterPreset {
+  pub name: String,
+  #[data(same_fn = "PartialEq::eq")]
+  pub filters: Vector<Filters>,
+  /// The text of each saved query toggled on, presets saved before these were kept have none.
+  #[serde(default)]
+  #[data(same_fn = "PartialEq::eq")]
+  pub queries: Vector<String>,
+  pub search: String,
+  /// Sort keys, most significant first.
+  #[data(same_fn = "PartialEq::eq")]
+  pub sort: Vector<(Heading, bool)>,
+}
+
+#[cfg(test)]
+mod test {
+  use std::{path::PathBuf, sync::Arc};
+
+  use druid::im::Vector;
+
+  use super::{headings::Heading, query::Query, Filters, ModList};
+  use crate::app::mod_entry::ModEntry;
+
+  fn entry(name: &str, path: &str) -> Arc<ModEntry> {
+    Arc::new(ModEntry {
+      id: String::from("shared_id"),
+      name: String::from(name),
+      path: PathBuf::from(path),
+      enabled: true,
+      ..Default::default()
+    })
+  }
+
+  #[test]
+  fn only_one_fork_is_enabled() {
+    let mut list = ModList::new(Vector::new(), Vector::new(), None);
+    let original = entry("Original", "mods/a_original");
+    let fork = entry("Fork", "mods/b_fork");
+    let used = |list: &ModList| list.get("shared_id").expect("Get used mod").path.clone();
+
+    // whichever is found first, the first by path is used
+    assert!(list.add(fork.clone()).is_none());
+    assert!(list.add(original.clone()).is_none());
+    assert_eq!(used(&list), original.path);
+    // another copy of the same mod is a duplicate rather than a fork
+    assert!(list.add(entry("Original", "mods/copy")).is_some());
+    assert!(!list.mods[&fork.path].enabled);
+
+    list.replace(fork.clone());
+    assert_eq!(used(&list), fork.path);
+    assert!(!list.mods[&original.path].enabled);
+    assert_eq!(
+      list
+        .sorted_vals()
+        .iter()
+        .filter(|entry| entry.enabled)
+        .count(),
+      1
+    );
+    assert!(list.sorted_vals().iter().all(|entry| entry.id_collision));
+
+    list.remove(&fork.path);
+    assert_eq!(used(&list), original.path);
+    assert_eq!(list.mods.len(), 1);
+  }
+
+  #[test]
+  fn presets_restore_filters_and_sort() {
+    let mut list = ModList::new(Vector::new(), Vector::new(), None);
+    list.active_filters.insert(Filters::Disabled);
+    list.active_filters.insert(Filters::UpdatedWeek);
+    list.search_text = String::from("lazy");
+    list.header.sort_clicked(Heading::Author, false);
+    list.header.sort_clicked(Heading::Name, true);
+    let enabled = Query::parse("enabled").expect("Parse query");
+    list.active_queries.insert(String::from("enabled"), enabled);
+    let preset = list.preset("Recent");
+
+    let mut other = ModList::new(Vector::new(), Vector::new(), None);
+    let disabled = Query::parse("disabled").expect("Parse query");
+    other
+      .active_queries
+      .insert(String::from("disabled"), disabled);
+    other.apply_preset(&preset);
+    assert_eq!(other.active_filters, list.active_filters);
+    assert_eq!(other.active_queries, list.active_queries);
+    assert_eq!(other.search_text, "lazy");
+    assert_eq!(other.header.sort(), list.header.sort());
+    assert_eq!(other.preset("Recent"), preset);
+  }
+
+  #[test]
+  fn quick_filters_toggle() {
+    let mut list = ModList::new(Vector::new(), Vector::new(), None);
+    list.active_filters.insert(Filters::Major);
+
+    list.quick_filter(&[Filters::Disabled]);
+    assert_eq!(
+      list.active_filters,
+      IntoIterator::into_iter([Filters::Disabled]).collect()
+    );
+    list.quick_filter(&[Filters::Disabled]);
+    assert!(list.active_filters.is_empty());
+  }
+}
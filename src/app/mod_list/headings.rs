@@ -1,5 +1,9 @@
 use crate::{
-  app::{mod_entry::ModEntry, util::LabelExt},
+  app::{
+    i18n::tr,
+    mod_entry::ModEntry,
+    util::{LabelExt, WidgetExtEx},
+  },
   patch::split::{Split, DRAGGED},
 };
 use druid::{
@@ -26,6 +30,28 @@ pub enum Heading {
   Score,
   AutoUpdateSupport,
   InstallDate,
+  LastUpdated,
+  Size,
+  AuthorActivity,
+}
+
+impl Heading {
+  pub fn label(self) -> String {
+    tr(match self {
+      Heading::ID => "heading-id",
+      Heading::Name => "heading-name",
+      Heading::Author => "heading-author",
+      Heading::GameVersion => "heading-game-version",
+      Heading::Enabled => "heading-enabled",
+      Heading::Version => "heading-version",
+      Heading::Score => "heading-score",
+      Heading::AutoUpdateSupport => "heading-auto-update",
+      Heading::InstallDate => "heading-install-date",
+      Heading::LastUpdated => "heading-last-updated",
+      Heading::Size => "heading-size",
+      Heading::AuthorActivity => "heading-author-activity",
+    })
+  }
 }
 
 impl From<Heading> for &str {
@@ -40,6 +66,9 @@ impl From<Heading> for &str {
       Heading::Score => "score",
       Heading::AutoUpdateSupport => "Auto-Update Supported",
       Heading::InstallDate => "Install Date",
+      Heading::LastUpdated => "Last Updated",
+      Heading::Size => "Size",
+      Heading::AuthorActivity => "Author Last Active",
     }
   }
 }
@@ -51,10 +80,15 @@ pub struct Header {
   #[data(same_fn = "PartialEq::eq")]
   pub headings: Vector<Heading>,
   pub sort_by: (Heading, bool),
+  /// Keys that break ties in [`sort_by`](Self::sort_by), in order, added by shift-clicking
+  /// headings.
+  #[data(same_fn = "PartialEq::eq")]
+  pub then_by: Vector<(Heading, bool)>,
 }
 
 impl Header {
-  pub const SORT_CHANGED: Selector<Heading> = Selector::new("headings.sorting.changed");
+  /// A heading was clicked, and whether shift was held to add it as a secondary key.
+  pub const SORT_CHANGED: Selector<(Heading, bool)> = Selector::new("headings.sorting.changed");
   pub const SWAP_HEADINGS: Selector<(usize, usize)> = Selector::new("headings.order.changed");
   pub const ADD_HEADING: Selector<Heading> = Selector::new("headings.add");
   pub const REMOVE_HEADING: Selector<Heading> = Selector::new("headings.remove");
@@ -68,11 +102,63 @@ impl Header {
     Heading::GameVersion,
   ];
 
-  pub fn new(headings: Vector<Heading>) -> Self {
+  /// `sort` is the primary key followed by any secondary keys, as returned by
+  /// [`sort`](Self::sort).
+  pub fn new(headings: Vector<Heading>, mut sort: Vector<(Heading, bool)>) -> Self {
+    let sort_by = sort.pop_front().unwrap_or((Heading::Name, false));
+
     Self {
       ratios: Self::calculate_ratios(headings.len()),
       headings,
-      sort_by: (Heading::Name, false),
+      sort_by,
+      then_by: sort,
+    }
+  }
+
+  /// Restores column widths saved from an earlier session, if they were saved for the same number
+  /// of columns.
+  pub fn with_ratios(mut self, ratios: Option<Vector<f64>>) -> Self {
+    if let Some(ratios) = ratios
+      && ratios.len() == self.ratios.len()
+    {
+      self.ratios = ratios;
+    }
+
+    self
+  }
+
+  /// Where the divider with the given [`ResizeController`] id starts out, `default` unless the
+  /// columns have been resized.
+  fn split_point(&self, id: usize, default: f64) -> f64 {
+    if self.ratios == Self::calculate_ratios(self.headings.len()) {
+      default
+    } else {
+      self.ratios.get(id).copied().unwrap_or(default)
+    }
+  }
+
+  /// Every key the list is sorted by, most significant first.
+  pub fn sort(&self) -> Vector<(Heading, bool)> {
+    let mut sort = self.then_by.clone();
+    sort.push_front(self.sort_by);
+    sort
+  }
+
+  /// Clicking a heading makes it the only key, or flips its direction if it already is.
+  /// Shift-clicking adds it as a tie breaker, or flips its direction if it's already a key.
+  pub fn sort_clicked(&mut self, heading: Heading, shift: bool) {
+    if self.sort_by.0 == heading {
+      self.sort_by.1 = !self.sort_by.1;
+      if !shift {
+        self.then_by.clear()
+      }
+    } else if !shift || self.sort_by.0 == Heading::Score {
+      self.sort_by = (heading, false);
+      self.then_by.clear()
+    } else if let Some(key) = self.then_by.iter_mut().find(|(key, _)| *key == heading) {
+      key.1 = !key.1
+    } else {
+      self.then_by.push_back((heading, false))
     }
   }
 
@@ -86,12 +172,13 @@ impl Header {
   pub fn ui_builder() -> impl Widget<Header> {
     fn recursive_split(
       idx: usize,
-      titles: &Vector<Heading>,
+      header: &Header,
     ) -> ControllerHost<Split<Header>, ResizeController> {
+      let titles = &header.headings;
       if idx < titles.len() - 2 {
         Split::columns(
           heading_builder(titles[idx]),
-          recursive_split(idx + 1, titles),
+          recursive_split(idx + 1, header),
         )
       } else {
         Split::columns(
@@ -100,7 +187,7 @@ impl Header {
         )
       }
       .draggable(true)
-      .split_point(1. / (titles.len() - idx) as f64)
+      .split_point(header.split_point(idx + 1, 1. / (titles.len() - idx) as f64))
       .bar_size(2.)
       .solid_bar(true)
       .min_size(50., 50.)
@@ -113,12 +200,12 @@ impl Header {
         Split::columns(
           heading_builder(Heading::Enabled),
           if data.headings.len() > 1 {
-            recursive_split(0, &data.headings).boxed()
+            recursive_split(0, data).boxed()
           } else {
             heading_builder(data.headings[0]).boxed()
           },
         )
-        .split_point(ENABLED_RATIO)
+        .split_point(data.split_point(0, ENABLED_RATIO))
         .controller(ResizeController::new(0))
         .boxed()
       },
@@ -140,28 +227,41 @@ impl Header {
 fn heading_builder(title: Heading) -> impl Widget<Header> {
   Flex::row()
     .with_flex_child(
-      Label::wrapped(<&str>::from(title))
+      Label::wrapped(title.label())
         .with_text_alignment(druid::TextAlignment::Center)
         .expand_width(),
       1.,
     )
-    .with_child(
-      ViewSwitcher::new(
-        |data: &(Heading, bool), _| *data,
-        move |_, new, _| {
-          if new.0 == title {
-            if new.1 {
-              Box::new(Icon::new(ARROW_DROP_DOWN))
-            } else {
-              Box::new(Icon::new(ARROW_DROP_UP))
-            }
-          } else {
-            Box::new(Icon::new(UNFOLD_MORE))
-          }
-        },
-      )
-      .lens(Header::sort_by),
-    )
+    .with_child(ViewSwitcher::new(
+      move |data: &Header, _| {
+        let key = data
+          .sort()
+          .into_iter()
+          .enumerate()
+          .find_map(|(idx, (key, descending))| (key == title).then_some((idx, descending)));
+
+        (key, !data.then_by.is_empty())
+      },
+      |(key, numbered), _, _| {
+        let Some((position, descending)) = *key else {
+          return Box::new(Icon::new(UNFOLD_MORE));
+        };
+        let arrow = Icon::new(if descending {
+          ARROW_DROP_DOWN
+        } else {
+          ARROW_DROP_UP
+        });
+
+        if *numbered {
+          Flex::row()
+            .with_child(Label::new((position + 1).to_string()).with_text_size(11.))
+            .with_child(arrow)
+            .boxed()
+        } else {
+          arrow.boxed()
+        }
+      },
+    ))
     .fix_height(40.)
     .padding((0., 5., 0., 5.))
     .background(Painter::new(|ctx, _, env| {
@@ -170,7 +270,9 @@ fn heading_builder(title: Heading) -> impl Widget<Header> {
         ctx.stroke(border_rect, &env.get(druid::theme::BORDER_LIGHT), 3.)
       }
     }))
-    .on_click(move |ctx, _, _| ctx.submit_command(Header::SORT_CHANGED.with(title)))
+    .on_click2(move |ctx, mouse, _, _| {
+      ctx.submit_command(Header::SORT_CHANGED.with((title, mouse.mods.shift())))
+    })
 }
 
 struct ResizeController {
@@ -202,3 +304,32 @@ impl<W: Widget<Header>> Controller<Header, W> for ResizeController {
     child.event(ctx, event, data, env)
   }
 }
+
+#[cfg(test)]
+mod test {
+  use druid::im::vector;
+
+  use super::{Header, Heading};
+
+  #[test]
+  fn shift_click_adds_secondary_keys() {
+    let mut header = Header::new(Header::TITLES.to_vec().into(), vector![]);
+    assert_eq!(header.sort(), vector![(Heading::Name, false)]);
+
+    header.sort_clicked(Heading::Version, false);
+    header.sort_clicked(Heading::Name, true);
+    header.sort_clicked(Heading::Author, true);
+    header.sort_clicked(Heading::Name, true);
+    assert_eq!(
+      header.sort(),
+      vector![
+        (Heading::Version, false),
+        (Heading::Name, true),
+        (Heading::Author, false)
+      ]
+    );
+
+    header.sort_clicked(Heading::Version, false);
+    assert_eq!(header.sort(), vector![(Heading::Version, true)]);
+  }
+}
@@ -0,0 +1,142 @@
+//! Optional mod library, a MOSS-managed folder that mods are installed into instead of the game's
+//! mods folder.
+//!
+//! Library mods are handled like any other external mod (see [`link_farm`](super::link_farm)):
+//! enabling one links it into the mods folder and disabling it removes the link, so switching
+//! between sets of enabled mods never copies anything. Since the library lives in MOSS's own data
+//! folder it is shared by every install, a mod used by several installs is only stored once.
+//!
+//! Unlike other external mods, library mods belong to MOSS and can be updated and deleted from the
+//! mod list.
+
+use std::{
+  path::{Path, PathBuf},
+  sync::{Arc, LazyLock, RwLock},
+};
+
+use tracing::{info, warn};
+use webview_shared::PROJECT;
+
+use super::link_farm;
+use crate::app::{mod_entry::ModEntry, util::mods_dir};
+
+static LIBRARY: LazyLock<RwLock<Option<PathBuf>>> = LazyLock::new(Default::default);
+
+/// The default library location.
+pub fn default_dir() -> PathBuf {
+  PROJECT.data_dir().join("library")
+}
+
+/// Sets the library new installs go into, or `None` to install into the mods folder.
+pub fn set(dir: Option<PathBuf>) {
+  if let Some(dir) = &dir
+    && let Err(err) = std::fs::create_dir_all(dir)
+  {
+    warn!("Failed to create mod library {:?}: {}", dir, err)
+  }
+  if let Ok(mut library) = LIBRARY.write() {
+    *library = dir
+  }
+}
+
+pub fn get() -> Option<PathBuf> {
+  LIBRARY.read().ok().and_then(|library| library.clone())
+}
+
+/// Where a new mod should be installed - the library if one is in use, otherwise the mods folder.
+pub fn install_dir_for(install_dir: &Path) -> PathBuf {
+  get().unwrap_or_else(|| mods_dir(install_dir))
+}
+
+/// Whether `path` is inside the library.
+pub fn contains(path: &Path) -> bool {
+  get().is_some_and(|library| path.starts_with(library))
+}
+
+/// Moves every mod in the mods folder into `library`, linking the enabled ones back in. Mods
+/// already in the library, or that have a folder of the same name there, are left where they
+/// are. Returns how many mods were moved, or the first failure.
+pub fn move_into<'a>(
+  install_dir: &Path,
+  library: &Path,
+  mods: impl Iterator<Item = &'a Arc<ModEntry>>,
+) -> anyhow::Result<usize> {
+  std::fs::create_dir_all(library)?;
+
+  let mut moved = Vec::new();
+  let mut first_err = None;
+  for entry in mods.filter(|entry| !entry.external) {
+    let Some(name) = entry.path.file_name() else {
+      continue;
+    };
+    let destination = library.join(name);
+    if destination.exists() {
+      warn!(
+        "Not moving {} into the library, {:?} already exists",
+        entry.id, destination
+      );
+      continue;
+    }
+
+    // folders can't be hard linked and the library usually shares a drive with the game, so
+    // moves are renames and anything else is reported rather than copied
+    match std::fs::rename(&entry.path, &destination) {
+      Ok(()) => {
+        info!("Moved {} into the mod library", entry.id);
+        let mut entry = (**entry).clone();
+        entry.set_path(destination);
+        entry.external = true;
+        moved.push(Arc::new(entry));
+      }
+      Err(err) => {
+        first_err.get_or_insert(anyhow::anyhow!(
+          "Failed to move {} into the library: {}",
+          entry.name,
+          err
+        ));
+      }
+    }
+  }
+
+  if let Err(err) = link_farm::sync(install_dir, moved.iter()) {
+    first_err.get_or_insert(err);
+  }
+
+  first_err.map_or(Ok(moved.len()), Err)
+}
+
+#[cfg(all(test, unix))]
+mod test {
+  use std::sync::Arc;
+
+  use tempfile::tempdir;
+
+  use super::move_into;
+  use crate::app::{mod_entry::ModEntry, mod_list::link_farm::is_link};
+
+  #[test]
+  fn moves_mods_and_links_enabled_ones() {
+    let install = tempdir().expect("Create install dir");
+    let library = tempdir().expect("Create library");
+    let mods = install.path().join("mods");
+
+    let entries: Vec<Arc<ModEntry>> = vec![("enabled_mod", true), ("disabled_mod", false)]
+      .into_iter()
+      .map(|(id, enabled)| {
+        std::fs::create_dir_all(mods.join(id)).expect("Create mod dir");
+        let mut entry = ModEntry::default();
+        entry.id = String::from(id);
+        entry.path = mods.join(id);
+        entry.enabled = enabled;
+        Arc::new(entry)
+      })
+      .collect();
+
+    let moved = move_into(install.path(), library.path(), entries.iter()).expect("Move mods");
+    assert_eq!(moved, 2);
+    assert!(library.path().join("enabled_mod").is_dir());
+    assert!(library.path().join("disabled_mod").is_dir());
+    assert!(is_link(&mods.join("enabled_mod")));
+    assert!(!mods.join("disabled_mod").exists());
+  }
+}
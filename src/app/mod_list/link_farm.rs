@@ -0,0 +1,164 @@
+//! Mods kept outside the game's mods folder, eg: on a shared network drive.
+//!
+//! The game only loads mods from its own mods folder, so enabling an external mod creates a link
+//! to it there (a symlink, or a junction on Windows if symlinks aren't permitted) and disabling
+//! it removes the link again. The mod's files are never moved, and a real folder in the mods
+//! folder is never touched.
+
+use std::{
+  io,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use tracing::info;
+
+use crate::app::{mod_entry::ModEntry, util::mods_dir};
+
+/// Whether `path` is a link rather than a real folder.
+pub fn is_link(path: &Path) -> bool {
+  path
+    .symlink_metadata()
+    .is_ok_and(|meta| meta.file_type().is_symlink())
+}
+
+/// Whether `path` is a link into one of `external_dirs`.
+pub fn links_into(path: &Path, external_dirs: &[PathBuf]) -> bool {
+  is_link(path)
+    && std::fs::canonicalize(path).is_ok_and(|target| {
+      external_dirs
+        .iter()
+        .any(|dir| std::fs::canonicalize(dir).is_ok_and(|dir| target.starts_with(dir)))
+    })
+}
+
+/// Where the link for an external mod lives in the mods folder.
+pub fn link_path(install_dir: &Path, entry: &ModEntry) -> Option<PathBuf> {
+  Some(mods_dir(install_dir).join(entry.path.file_name()?))
+}
+
+/// Creates or removes links so that exactly the enabled external mods are linked into the mods
+/// folder. Keeps going past failures, returning the first.
+pub fn sync<'a>(
+  install_dir: &Path,
+  mods: impl Iterator<Item = &'a Arc<ModEntry>>,
+) -> anyhow::Result<()> {
+  let mut first_err = None;
+  for entry in mods.filter(|entry| entry.external) {
+    let Some(link) = link_path(install_dir, entry) else {
+      continue;
+    };
+
+    let res = if entry.enabled && !link.exists() && !is_link(&link) {
+      info!("Linking external mod {} into the mods folder", entry.id);
+      create_link(&entry.path, &link)
+    } else if !entry.enabled && is_link(&link) {
+      info!("Unlinking external mod {}", entry.id);
+      remove_link(&link)
+    } else {
+      Ok(())
+    };
+
+    if let Err(err) = res {
+      let err = anyhow::Error::new(err).context(format!(
+        "Failed to update the link for {} at {}",
+        entry.name,
+        link.to_string_lossy()
+      ));
+      first_err.get_or_insert(err);
+    }
+  }
+
+  first_err.map_or(Ok(()), Err)
+}
+
+#[cfg(unix)]
+fn create_link(target: &Path, link: &Path) -> io::Result<()> {
+  std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_link(target: &Path, link: &Path) -> io::Result<()> {
+  // directory symlinks need admin rights or developer mode, junctions don't
+  std::os::windows::fs::symlink_dir(target, link).or_else(|_| {
+    let status = std::process::Command::new("cmd")
+      .arg("/C")
+      .arg("mklink")
+      .arg("/J")
+      .arg(link)
+      .arg(target)
+      .status()?;
+
+    if status.success() {
+      Ok(())
+    } else {
+      Err(io::Error::new(
+        io::ErrorKind::Other,
+        format!("mklink exited with {}", status),
+      ))
+    }
+  })
+}
+
+#[cfg(unix)]
+fn remove_link(link: &Path) -> io::Result<()> {
+  std::fs::remove_file(link)
+}
+
+#[cfg(windows)]
+fn remove_link(link: &Path) -> io::Result<()> {
+  std::fs::remove_dir(link)
+}
+
+#[cfg(all(test, unix))]
+mod test {
+  use std::sync::Arc;
+
+  use tempfile::tempdir;
+
+  use super::{is_link, links_into, sync};
+  use crate::app::{mod_entry::ModEntry, util::mods_dir};
+
+  #[test]
+  fn links_enabled_external_mods() {
+    let install = tempdir().expect("Create install dir");
+    std::fs::create_dir(install.path().join("mods")).expect("Create mods dir");
+    let external = tempdir().expect("Create external dir");
+    let mod_dir = external.path().join("shared_mod");
+    std::fs::create_dir(&mod_dir).expect("Create mod dir");
+
+    let mut entry = ModEntry::default();
+    entry.id = String::from("shared_mod");
+    entry.path = mod_dir;
+    entry.enabled = true;
+    entry.external = true;
+    let link = install.path().join("mods").join("shared_mod");
+
+    sync(install.path(), [Arc::new(entry.clone())].iter()).expect("Link mod");
+    assert!(is_link(&link));
+    assert!(links_into(&link, &[external.path().to_path_buf()]));
+
+    entry.enabled = false;
+    sync(install.path(), [Arc::new(entry.clone())].iter()).expect("Unlink mod");
+    assert!(!link.exists());
+    assert!(entry.path.is_dir());
+  }
+
+  #[test]
+  fn leaves_real_folders_alone() {
+    let install = tempdir().expect("Create install dir");
+    let real = install.path().join("mods").join("shared_mod");
+    std::fs::create_dir_all(&real).expect("Create real mod dir");
+
+    let external = tempdir().expect("Create external dir");
+    let mut entry = ModEntry::default();
+    entry.id = String::from("shared_mod");
+    entry.path = external.path().join("shared_mod");
+    entry.enabled = true;
+    entry.external = true;
+
+    sync(install.path(), [Arc::new(entry)].iter()).expect("Skip real folder");
+    assert!(real.is_dir());
+    assert!(!is_link(&real));
+  }
+}
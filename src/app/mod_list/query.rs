@@ -0,0 +1,510 @@
+//! A small expression language for filtering the mod list, shared by the GUI and the CLI.
+//!
+//! Queries combine comparisons on mod fields with `&&`, `||`, `!` and parentheses, eg:
+//! `enabled && status == outdated && size > 500MB`. A bare field name tests a boolean field.
+
+use std::fmt::Display;
+
+use druid::Data;
+
+use crate::app::mod_entry::{ModEntry, UpdateStatus};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+  And(Box<Query>, Box<Query>),
+  Or(Box<Query>, Box<Query>),
+  Not(Box<Query>),
+  Flag(Flag),
+  Text(TextField, Op, String),
+  Status(Op, Status),
+  Size(Op, u64),
+}
+
+impl Data for Query {
+  fn same(&self, other: &Self) -> bool {
+    self == other
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+  Enabled,
+  Disabled,
+  VersionChecker,
+  AutoUpdate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+  Id,
+  Name,
+  Author,
+  Version,
+  GameVersion,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+  UpToDate,
+  Outdated,
+  Patch,
+  Minor,
+  Major,
+  Discrepancy,
+  Error,
+  Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+  /// Case insensitive substring match
+  Contains,
+}
+
+impl Display for Op {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let op = match self {
+      Op::Eq => "==",
+      Op::Ne => "!=",
+      Op::Lt => "<",
+      Op::Le => "<=",
+      Op::Gt => ">",
+      Op::Ge => ">=",
+      Op::Contains => "~",
+    };
+
+    write!(f, "{}", op)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  And,
+  Or,
+  Not,
+  Open,
+  Close,
+  Op(Op),
+  Word(String),
+}
+
+impl Query {
+  pub fn parse(input: &str) -> Result<Query, String> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+      return Err(String::from("Query is empty"));
+    }
+
+    let mut parser = Parser { tokens, pos: 0 };
+    let query = parser.or()?;
+    match parser.tokens.get(parser.pos) {
+      None => Ok(query),
+      Some(token) => Err(format!("Unexpected {} after end of query", describe(token))),
+    }
+  }
+
+  pub fn matches(&self, entry: &ModEntry) -> bool {
+    match self {
+      Query::And(left, right) => left.matches(entry) && right.matches(entry),
+      Query::Or(left, right) => left.matches(entry) || right.matches(entry),
+      Query::Not(inner) => !inner.matches(entry),
+      Query::Flag(flag) => match flag {
+        Flag::Enabled => entry.enabled,
+        Flag::Disabled => !entry.enabled,
+        Flag::VersionChecker => entry.version_checker.is_some(),
+        Flag::AutoUpdate => {
+          Status::Outdated.matches(&entry.update_status)
+            && entry
+              .remote_version
+              .as_ref()
+              .and_then(|remote| remote.direct_download_url.as_ref())
+              .is_some()
+        }
+      },
+      Query::Text(field, op, value) => {
+        let text = match field {
+          TextField::Id => entry.id.clone(),
+          TextField::Name => entry.name.clone(),
+          TextField::Author => entry.author.clone(),
+          TextField::Version => entry.version.to_string(),
+          TextField::GameVersion => entry.raw_game_version.clone(),
+        }
+        .to_lowercase();
+        let value = value.to_lowercase();
+
+        match op {
+          Op::Eq => text == value,
+          Op::Ne => text != value,
+          Op::Contains => text.contains(&value),
+          Op::Lt => text < value,
+          Op::Le => text <= value,
+          Op::Gt => text > value,
+          Op::Ge => text >= value,
+        }
+      }
+      Query::Status(op, status) => match op {
+        Op::Ne => !status.matches(&entry.update_status),
+        _ => status.matches(&entry.update_status),
+      },
+      Query::Size(op, size) => match op {
+        Op::Eq => entry.size == *size,
+        // only text can be searched, sizes with `~` are rejected when parsing
+        Op::Contains => false,
+        Op::Ne => entry.size != *size,
+        Op::Lt => entry.size < *size,
+        Op::Le => entry.size <= *size,
+        Op::Gt => entry.size > *size,
+        Op::Ge => entry.size >= *size,
+      },
+    }
+  }
+
+  /// Whether evaluating this query needs the result of a remote version check.
+  pub fn needs_remote(&self) -> bool {
+    match self {
+      Query::And(left, right) | Query::Or(left, right) => {
+        left.needs_remote() || right.needs_remote()
+      }
+      Query::Not(inner) => inner.needs_remote(),
+      Query::Flag(Flag::AutoUpdate) | Query::Status(..) => true,
+      _ => false,
+    }
+  }
+}
+
+impl Status {
+  fn parse(value: &str) -> Option<Self> {
+    let status = match value.to_lowercase().replace(['_', '-'], "").as_str() {
+      "uptodate" | "current" => Status::UpToDate,
+      "outdated" | "update" => Status::Outdated,
+      "patch" => Status::Patch,
+      "minor" => Status::Minor,
+      "major" => Status::Major,
+      "discrepancy" => Status::Discrepancy,
+      "error" => Status::Error,
+      "unknown" | "none" => Status::Unknown,
+      _ => return None,
+    };
+
+    Some(status)
+  }
+
+  fn matches(self, status: &Option<UpdateStatus>) -> bool {
+    match (self, status) {
+      (Status::Unknown, None) => true,
+      (Status::UpToDate, Some(UpdateStatus::UpToDate))
+      | (Status::Error, Some(UpdateStatus::Error))
+      | (Status::Discrepancy, Some(UpdateStatus::Discrepancy(_)))
+      | (Status::Patch, Some(UpdateStatus::Patch(_)))
+      | (Status::Minor, Some(UpdateStatus::Minor(_)))
+      | (Status::Major, Some(UpdateStatus::Major(_))) => true,
+      (Status::Outdated, Some(status)) => matches!(
+        status,
+        UpdateStatus::Patch(_) | UpdateStatus::Minor(_) | UpdateStatus::Major(_)
+      ),
+      _ => false,
+    }
+  }
+}
+
+struct Parser {
+  tokens: Vec<Token>,
+  pos: usize,
+}
+
+impl Parser {
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.pos).cloned();
+    self.pos += 1;
+    token
+  }
+
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos)
+  }
+
+  fn or(&mut self) -> Result<Query, String> {
+    let mut query = self.and()?;
+    while self.peek() == Some(&Token::Or) {
+      self.pos += 1;
+      query = Query::Or(Box::new(query), Box::new(self.and()?));
+    }
+
+    Ok(query)
+  }
+
+  fn and(&mut self) -> Result<Query, String> {
+    let mut query = self.unary()?;
+    while self.peek() == Some(&Token::And) {
+      self.pos += 1;
+      query = Query::And(Box::new(query), Box::new(self.unary()?));
+    }
+
+    Ok(query)
+  }
+
+  fn unary(&mut self) -> Result<Query, String> {
+    match self.next() {
+      Some(Token::Not) => Ok(Query::Not(Box::new(self.unary()?))),
+      Some(Token::Open) => {
+        let query = self.or()?;
+        match self.next() {
+          Some(Token::Close) => Ok(query),
+          _ => Err(String::from("Missing closing ')'")),
+        }
+      }
+      Some(Token::Word(field)) => self.comparison(&field),
+      Some(token) => Err(format!("Expected a field name, found {}", describe(&token))),
+      None => Err(String::from("Query ends unexpectedly")),
+    }
+  }
+
+  fn comparison(&mut self, field: &str) -> Result<Query, String> {
+    let field = field.to_lowercase().replace(['_', '-'], "");
+    let flag = match field.as_str() {
+      "enabled" => Some(Flag::Enabled),
+      "disabled" => Some(Flag::Disabled),
+      "versionchecker" => Some(Flag::VersionChecker),
+      "autoupdate" => Some(Flag::AutoUpdate),
+      _ => None,
+    };
+    if let Some(flag) = flag {
+      return Ok(Query::Flag(flag));
+    }
+
+    let Some(Token::Op(op)) = self.peek().cloned() else {
+      return Err(format!("Expected a comparison after '{}'", field));
+    };
+    self.pos += 1;
+    let Some(Token::Word(value)) = self.next() else {
+      return Err(format!("Expected a value after '{} {}'", field, op));
+    };
+
+    let text_field = match field.as_str() {
+      "id" => Some(TextField::Id),
+      "name" => Some(TextField::Name),
+      "author" => Some(TextField::Author),
+      "version" => Some(TextField::Version),
+      "gameversion" => Some(TextField::GameVersion),
+      _ => None,
+    };
+
+    if let Some(text_field) = text_field {
+      return Ok(Query::Text(text_field, op, value));
+    }
+
+    match field.as_str() {
+      "status" => {
+        if !matches!(op, Op::Eq | Op::Ne) {
+          return Err(format!(
+            "'status' can only be compared with == or !=, not {}",
+            op
+          ));
+        }
+        Status::parse(&value)
+          .map(|status| Query::Status(op, status))
+          .ok_or_else(|| format!("Unknown status '{}'", value))
+      }
+      "size" => {
+        if op == Op::Contains {
+          return Err(String::from("'size' can't be compared with ~"));
+        }
+        parse_size(&value)
+          .map(|size| Query::Size(op, size))
+          .ok_or_else(|| format!("Could not read '{}' as a size, eg: 500MB", value))
+      }
+      _ => Err(format!("Unknown field '{}'", field)),
+    }
+  }
+}
+
+fn describe(token: &Token) -> String {
+  match token {
+    Token::And => String::from("'&&'"),
+    Token::Or => String::from("'||'"),
+    Token::Not => String::from("'!'"),
+    Token::Open => String::from("'('"),
+    Token::Close => String::from("')'"),
+    Token::Op(op) => format!("'{}'", op),
+    Token::Word(word) => format!("'{}'", word),
+  }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+  let mut tokens = Vec::new();
+  let mut chars = input.chars().peekable();
+
+  while let Some(char) = chars.next() {
+    let token = match char {
+      _ if char.is_whitespace() => continue,
+      '(' => Token::Open,
+      ')' => Token::Close,
+      '~' => Token::Op(Op::Contains),
+      '&' | '|' => {
+        if chars.next_if_eq(&char).is_none() {
+          return Err(format!("Expected '{}{}'", char, char));
+        }
+        if char == '&' {
+          Token::And
+        } else {
+          Token::Or
+        }
+      }
+      '=' => {
+        chars.next_if_eq(&'=');
+        Token::Op(Op::Eq)
+      }
+      '!' => {
+        if chars.next_if_eq(&'=').is_some() {
+          Token::Op(Op::Ne)
+        } else {
+          Token::Not
+        }
+      }
+      '<' | '>' => {
+        let or_equal = chars.next_if_eq(&'=').is_some();
+        Token::Op(match (char, or_equal) {
+          ('<', false) => Op::Lt,
+          ('<', true) => Op::Le,
+          (_, false) => Op::Gt,
+          (_, true) => Op::Ge,
+        })
+      }
+      '"' | '\'' => {
+        let mut word = String::new();
+        loop {
+          match chars.next() {
+            Some(next) if next == char => break,
+            Some(next) => word.push(next),
+            None => return Err(String::from("Unterminated quote")),
+          }
+        }
+        Token::Word(word)
+      }
+      _ => {
+        let mut word = String::from(char);
+        while let Some(next) =
+          chars.next_if(|next| !next.is_whitespace() && !"()&|=!<>~\"'".contains(*next))
+        {
+          word.push(next)
+        }
+        Token::Word(word)
+      }
+    };
+    tokens.push(token)
+  }
+
+  Ok(tokens)
+}
+
+/// Parses sizes such as `500MB`, `1.5gb` or `2048` (bytes).
+fn parse_size(value: &str) -> Option<u64> {
+  let value = value.to_lowercase();
+  let split = value
+    .find(|char: char| !char.is_ascii_digit() && char != '.')
+    .unwrap_or(value.len());
+  let (number, unit) = value.split_at(split);
+  let multiplier: u64 = match unit.trim_end_matches("ib").trim_end_matches('b') {
+    "" => 1,
+    "k" => 1 << 10,
+    "m" => 1 << 20,
+    "g" => 1 << 30,
+    _ => return None,
+  };
+
+  number
+    .parse::<f64>()
+    .ok()
+    .map(|number| (number * multiplier as f64) as u64)
+}
+
+#[cfg(test)]
+mod test {
+  use crate::app::mod_entry::{ModEntry, UpdateStatus, Version};
+
+  use super::{parse_size, Flag, Op, Query, Status, TextField};
+
+  fn entry() -> ModEntry {
+    ModEntry {
+      id: String::from("lw_lazylib"),
+      name: String::from("LazyLib"),
+      enabled: true,
+      update_status: Some(UpdateStatus::Minor(Version {
+        major: 2,
+        minor: 8,
+        patch: String::new(),
+      })),
+      size: 600 << 20,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn parses_sizes() {
+    assert_eq!(parse_size("500MB"), Some(500 << 20));
+    assert_eq!(parse_size("1.5gb"), Some(3 << 29));
+    assert_eq!(parse_size("2048"), Some(2048));
+    assert_eq!(parse_size("12parsecs"), None);
+  }
+
+  #[test]
+  fn parses_precedence() {
+    let query = Query::parse("!enabled || name ~ lazy && status == outdated").unwrap();
+
+    assert_eq!(
+      query,
+      Query::Or(
+        Box::new(Query::Not(Box::new(Query::Flag(Flag::Enabled)))),
+        Box::new(Query::And(
+          Box::new(Query::Text(
+            TextField::Name,
+            Op::Contains,
+            String::from("lazy")
+          )),
+          Box::new(Query::Status(Op::Eq, Status::Outdated)),
+        )),
+      )
+    );
+  }
+
+  #[test]
+  fn matches_entry() {
+    let entry = entry();
+
+    assert!(
+      Query::parse("enabled && status == outdated && size > 500MB")
+        .unwrap()
+        .matches(&entry)
+    );
+    assert!(
+      Query::parse("(disabled || id == \"LW_LAZYLIB\") && status != major")
+        .unwrap()
+        .matches(&entry)
+    );
+    assert!(Query::parse("name ~ azyli && author ~ \"\"")
+      .unwrap()
+      .matches(&entry));
+    assert!(!Query::parse("name ~ magiclib").unwrap().matches(&entry));
+    assert!(!Query::parse("size < 1gb && !enabled")
+      .unwrap()
+      .matches(&entry));
+  }
+
+  #[test]
+  fn reports_errors() {
+    assert!(Query::parse("").is_err());
+    assert!(Query::parse("enabled &&").is_err());
+    assert!(Query::parse("(enabled").is_err());
+    assert!(Query::parse("colour == red").is_err());
+    assert!(Query::parse("status > outdated").is_err());
+    assert!(Query::parse("size > lots").is_err());
+    assert!(Query::parse("size ~ 500MB").is_err());
+    assert!(Query::parse("status ~ outdated").is_err());
+  }
+}
@@ -0,0 +1,123 @@
+//! The mod list written out for posting in a forum thread or on Discord, so a player asking for
+//! help can show what they're running in one paste. Shared by the GUI and the CLI.
+
+use clap::ArgEnum;
+
+use crate::app::{mod_description::ModDescription, mod_entry::ModEntry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum ShareFormat {
+  /// A `[list]` for the Fractal Softworks forum
+  #[clap(name = "bbcode")]
+  BBCode,
+  /// A table for GitHub, Discord and the like
+  Markdown,
+}
+
+/// Lists `mods` in `format`, each with its version and a link to its forum thread or Nexus Mods
+/// page when its version file has one.
+pub fn format<'a>(format: ShareFormat, mods: impl IntoIterator<Item = &'a ModEntry>) -> String {
+  let mut mods: Vec<&ModEntry> = mods.into_iter().collect();
+  mods.sort_by_key(|entry| entry.name.to_lowercase());
+
+  match format {
+    ShareFormat::BBCode => {
+      let mut text = String::from("[list]\n");
+      for entry in mods {
+        let name = format!("{} {}", entry.name, entry.version);
+        text.push_str(&match link(entry) {
+          Some(link) => format!("[*][url={}]{}[/url]\n", link, name),
+          None => format!("[*]{}\n", name),
+        });
+      }
+      text.push_str("[/list]");
+
+      text
+    }
+    ShareFormat::Markdown => {
+      let mut text = String::from("| Mod | Version | Link |\n| --- | --- | --- |");
+      for entry in mods {
+        text.push_str(&format!(
+          "\n| {} | {} | {} |",
+          escape_markdown(&entry.name),
+          escape_markdown(&entry.version.to_string()),
+          link(entry).map_or_else(String::new, |link| format!("<{}>", link))
+        ));
+      }
+
+      text
+    }
+  }
+}
+
+fn link(entry: &ModEntry) -> Option<String> {
+  let meta = entry.version_checker.as_ref()?;
+  if !meta.fractal_id.is_empty() {
+    Some(format!(
+      "{}{}",
+      ModDescription::FRACTAL_URL,
+      meta.fractal_id
+    ))
+  } else if !meta.nexus_id.is_empty() {
+    Some(format!("{}{}", ModDescription::NEXUS_URL, meta.nexus_id))
+  } else {
+    None
+  }
+}
+
+/// Keeps a `|` in a name from splitting the table cell.
+fn escape_markdown(text: &str) -> String {
+  text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod test {
+  use super::{format, ShareFormat};
+  use crate::app::mod_entry::{ModEntry, ModVersionMeta, VersionUnion};
+
+  fn entries() -> [ModEntry; 2] {
+    let lazylib = ModEntry {
+      id: String::from("lw_lazylib"),
+      name: String::from("LazyLib"),
+      version: VersionUnion::String(String::from("2.8")),
+      version_checker: Some(
+        ModVersionMeta::parse(
+          r#"{
+            "masterVersionFile": "https://example.com/lazylib.version",
+            "modName": "LazyLib",
+            "modThreadId": 5444,
+            "modVersion": { "majorVersion": 2, "minorVersion": 8 }
+          }"#,
+        )
+        .expect("Parse version file"),
+      ),
+      ..Default::default()
+    };
+    let pipes = ModEntry {
+      id: String::from("pipes"),
+      name: String::from("Pipes | Tubes"),
+      version: VersionUnion::String(String::from("0.1")),
+      ..Default::default()
+    };
+
+    [pipes, lazylib]
+  }
+
+  #[test]
+  fn formats_for_forums() {
+    assert_eq!(
+      format(ShareFormat::BBCode, &entries()),
+      "[list]\n\
+       [*][url=https://fractalsoftworks.com/forum/index.php?topic=5444]LazyLib 2.8[/url]\n\
+       [*]Pipes | Tubes 0.1\n\
+       [/list]"
+    );
+    assert_eq!(
+      format(ShareFormat::Markdown, &entries()),
+      "| Mod | Version | Link |\n\
+       | --- | --- | --- |\n\
+       | LazyLib | 2.8 | <https://fractalsoftworks.com/forum/index.php?topic=5444> |\n\
+       | Pipes \\| Tubes | 0.1 |  |"
+    );
+  }
+}
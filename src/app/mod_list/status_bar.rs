@@ -0,0 +1,108 @@
+//! The bar under the mod list counting how many mods are enabled, have an update or failed their
+//! version check, and how much space they all take up. Clicking a count filters the list down to
+//! those mods, and clicking it again shows everything.
+
+use druid::{
+  lens, theme,
+  widget::{Flex, Label},
+  Data, Widget, WidgetExt,
+};
+
+use super::{Filters, ModList};
+use crate::app::{
+  controllers::HoverController,
+  i18n::tr_args,
+  mod_entry::UpdateStatus,
+  util::{format_size, WidgetExtEx},
+};
+
+/// Leaves only enabled mods.
+const ENABLED: &[Filters] = &[Filters::Disabled];
+/// Leaves only mods with an update available.
+const UPDATES: &[Filters] = &[
+  Filters::Unimplemented,
+  Filters::Error,
+  Filters::Discrepancy,
+  Filters::UpToDate,
+  Filters::Unchecked,
+];
+/// Leaves only mods whose version check failed.
+const ERRORS: &[Filters] = &[
+  Filters::Unimplemented,
+  Filters::Discrepancy,
+  Filters::UpToDate,
+  Filters::Patch,
+  Filters::Minor,
+  Filters::Major,
+  Filters::Unchecked,
+];
+
+/// What the bar counts, over every mod including forks.
+#[derive(Debug, Default, Clone, Data, PartialEq, Eq)]
+pub struct Summary {
+  pub total: usize,
+  pub enabled: usize,
+  pub updates: usize,
+  pub errors: usize,
+  /// Bytes taken up by every mod folder.
+  pub size: u64,
+}
+
+impl Summary {
+  pub fn new(list: &ModList) -> Self {
+    let mut summary = Self::default();
+    for entry in list.mods.values() {
+      summary.total += 1;
+      summary.enabled += entry.enabled as usize;
+      summary.updates += matches!(
+        entry.update_status,
+        Some(UpdateStatus::Major(_) | UpdateStatus::Minor(_) | UpdateStatus::Patch(_))
+      ) as usize;
+      summary.errors += (entry.update_status == Some(UpdateStatus::Error)) as usize;
+      summary.size += entry.size;
+    }
+
+    summary
+  }
+}
+
+pub fn ui_builder() -> impl Widget<ModList> {
+  Flex::row()
+    .with_child(segment(&[], |summary| {
+      tr_args("status-bar-total", &[("count", &summary.total)])
+    }))
+    .with_default_spacer()
+    .with_child(segment(ENABLED, |summary| {
+      tr_args(
+        "status-bar-enabled",
+        &[("enabled", &summary.enabled), ("total", &summary.total)],
+      )
+    }))
+    .with_default_spacer()
+    .with_child(segment(UPDATES, |summary| {
+      tr_args("status-bar-updates", &[("count", &summary.updates)])
+    }))
+    .with_default_spacer()
+    .with_child(segment(ERRORS, |summary| {
+      tr_args("status-bar-errors", &[("count", &summary.errors)])
+    }))
+    .with_flex_spacer(1.)
+    .with_child(Label::dynamic(|summary: &Summary, _| {
+      tr_args("status-bar-size", &[("size", &format_size(summary.size))])
+    }))
+    .padding((8., 4.))
+    .expand_width()
+    .background(theme::BACKGROUND_DARK)
+    // counted once for the whole bar rather than by each segment
+    .lens(lens::Map::new(Summary::new, |_, _| {}))
+}
+
+/// A count that switches the list to `filters` when clicked.
+fn segment(
+  filters: &'static [Filters],
+  text: impl Fn(&Summary) -> String + 'static,
+) -> impl Widget<Summary> {
+  Label::dynamic(move |summary: &Summary, _| text(summary))
+    .controller(HoverController)
+    .on_activate(move |ctx, _, _| ctx.submit_command(ModList::QUICK_FILTER.with(filters)))
+}
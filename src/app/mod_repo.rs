@@ -1,4 +1,8 @@
-use std::fmt::Display;
+use std::{
+  fmt::Display,
+  path::PathBuf,
+  sync::{LazyLock, RwLock},
+};
 
 use chrono::{DateTime, Local, Utc};
 use deunicode::deunicode;
@@ -17,15 +21,28 @@ use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 use sublime_fuzzy::best_match;
 use tap::{Pipe, Tap};
+use tokio::sync::OnceCell;
+use tracing::warn;
+use webview_shared::{InstallType, PROJECT, SCROLL_TO_DOWNLOAD, WEBVIEW_INSTALL};
 
 use super::{
   controllers::HoverController,
+  i18n::{tr, tr_args},
   mod_description::OPEN_IN_BROWSER,
+  mod_entry::ModEntry,
   modal::Modal,
-  util::{default_true, hoverable_text, icons::*, Button2, CommandExt, LabelExt, WidgetExtEx},
+  net,
+  util::{
+    default_true, hoverable_text, icons::*, parse_game_version, Button2, CommandExt, LabelExt,
+    WidgetExtEx, GREEN_KEY, ON_GREEN_KEY, ON_YELLOW_KEY, YELLOW_KEY,
+  },
+  version_source::parse_version,
   App,
 };
 
+/// The latest index, for looking up installed mods outside the UI.
+static INDEX: LazyLock<RwLock<Option<ModRepo>>> = LazyLock::new(Default::default);
+
 #[derive(Deserialize, Data, Clone, Lens, Debug)]
 pub struct ModRepo {
   #[data(same_fn = "PartialEq::eq")]
@@ -40,8 +57,16 @@ pub struct ModRepo {
   #[serde(skip)]
   filters: Vector<ModSource>,
   #[serde(skip)]
+  category_filters: Vector<String>,
+  /// Major and minor game version, eg: `(0, 97)` for 0.97a
+  #[serde(skip)]
+  game_version_filter: Option<(u32, u32)>,
+  #[serde(skip)]
   #[serde(default = "ModRepo::default_sorting")]
   sort_by: Metadata,
+  /// Set when this is the cached copy of the index and the latest refresh failed
+  #[serde(skip)]
+  pub offline: bool,
 }
 
 impl ModRepo {
@@ -49,6 +74,7 @@ impl ModRepo {
     "https://raw.githubusercontent.com/davidwhitman/StarsectorModRepo/main/ModRepo.json";
 
   pub const OPEN_IN_DISCORD: Selector = Selector::new("mod_repo.open.discord");
+  pub const REFRESHED: Selector<Result<ModRepo, String>> = Selector::new("mod_repo.refreshed");
   const OPEN_CONFIRM: Selector<String> = Selector::new("mod_repo.open.discord.confirm");
   pub const CLEAR_MODAL: Selector = Selector::new("mod_repo.close.clear");
   const UPDATE_FILTERS: Selector<Filter> = Selector::new("mod_repo.filter.update");
@@ -57,11 +83,32 @@ impl ModRepo {
   const CARD_MAX_WIDTH: f64 = 475.0;
 
   pub fn ui_builder() -> impl Widget<ModRepo> {
-    Modal::new("Mod Repo")
+    Modal::new(&tr("repo-title"))
+      .with_content(
+        Either::new(
+          |data: &ModRepo, _| data.offline,
+          Label::wrapped_func(|data: &ModRepo, _| {
+            tr_args(
+              "repo-offline",
+              &[(
+                "date",
+                &DateTime::<Local>::from(data.last_updated).format("%v %I:%M%p"),
+              )],
+            )
+          })
+          .with_text_color(ON_YELLOW_KEY)
+          .padding(5.)
+          .background(YELLOW_KEY)
+          .rounded(3.)
+          .expand_width(),
+          SizedBox::empty(),
+        )
+        .boxed(),
+      )
       .with_content(
         Flex::row()
           .with_child(
-            Button2::from_label("Filters").on_click2(|ctx, mouse, _, _| {
+            Button2::from_label(tr("repo-filters")).on_click2(|ctx, mouse, _, _| {
               let lens = App::mod_repo.map(
                 |data| data.clone().unwrap(),
                 |orig, new| {
@@ -93,8 +140,70 @@ impl ModRepo {
             }),
           )
           .with_default_spacer()
+          .with_child(Button2::from_label(tr("repo-categories")).on_click2(
+            |ctx, mouse, data: &mut ModRepo, _| {
+              let lens = App::mod_repo.map(
+                |data| data.clone().unwrap(),
+                |orig, new| {
+                  orig.replace(new);
+                },
+              );
+
+              let menu = Menu::<App>::empty().pipe(|mut menu| {
+                for category in data.categories() {
+                  menu = menu.entry(
+                    MenuItem::new(category.clone())
+                      .selected_if({
+                        let category = category.clone();
+                        move |data: &ModRepo, _| data.category_filters.contains(&category)
+                      })
+                      .on_activate(move |ctx, _, _| {
+                        ctx.submit_command(
+                          Self::UPDATE_FILTERS.with(Filter::Category(category.clone())),
+                        )
+                      })
+                      .lens(lens.clone()),
+                  )
+                }
+
+                menu
+              });
+
+              ctx.show_context_menu(menu, ctx.to_window(mouse.pos))
+            },
+          ))
+          .with_default_spacer()
+          .with_child(Button2::from_label(tr("repo-game-version")).on_click2(
+            |ctx, mouse, data: &mut ModRepo, _| {
+              let lens = App::mod_repo.map(
+                |data| data.clone().unwrap(),
+                |orig, new| {
+                  orig.replace(new);
+                },
+              );
+
+              let menu = Menu::<App>::empty().pipe(|mut menu| {
+                let versions = data.game_versions().into_iter().map(Some);
+                for version in std::iter::once(None).chain(versions) {
+                  menu = menu.entry(
+                    MenuItem::new(Filter::GameVersion(version).to_string())
+                      .selected_if(move |data: &ModRepo, _| data.game_version_filter == version)
+                      .on_activate(move |ctx, _, _| {
+                        ctx.submit_command(Self::UPDATE_FILTERS.with(Filter::GameVersion(version)))
+                      })
+                      .lens(lens.clone()),
+                  )
+                }
+
+                menu
+              });
+
+              ctx.show_context_menu(menu, ctx.to_window(mouse.pos))
+            },
+          ))
+          .with_default_spacer()
           .with_child(
-            Button2::from_label("Sort by").on_click2(|ctx, mouse, _, _| {
+            Button2::from_label(tr("repo-sort-by")).on_click2(|ctx, mouse, _, _| {
               let lens = App::mod_repo.map(
                 |data| data.clone().unwrap(),
                 |orig, new| {
@@ -121,7 +230,7 @@ impl ModRepo {
             }),
           )
           .with_default_spacer()
-          .with_child(Label::new("Search:").with_text_size(18.))
+          .with_child(Label::new(tr("repo-search")).with_text_size(18.))
           .with_default_spacer()
           .with_child(
             TextBox::new()
@@ -137,7 +246,12 @@ impl ModRepo {
       .with_content(
         ViewSwitcher::new(
           |data: &(Vector<ModRepoItem>, Vector<ModSource>, Metadata), _| {
-            (data.0.len(), data.1.clone(), data.2)
+            (
+              data.0.len(),
+              data.1.clone(),
+              data.2,
+              data.0.iter().map(|item| item.display).collect::<Vec<_>>(),
+            )
           },
           |_, (items, _, _): &(Vector<ModRepoItem>, Vector<ModSource>, Metadata), _| {
             let mut wrap = Wrap::new()
@@ -200,6 +314,14 @@ impl ModRepo {
                 data.filters.push_back(*source)
               }
             }
+            Filter::Category(category) => {
+              if data.category_filters.contains(category) {
+                data.category_filters.retain(|val| val != category)
+              } else {
+                data.category_filters.push_back(category.clone())
+              }
+            }
+            Filter::GameVersion(version) => data.game_version_filter = *version,
             Filter::Search(search) => {
               if search.is_empty() {
                 ctx.submit_command(ModRepo::UPDATE_SORTING.with(Metadata::Name))
@@ -210,6 +332,8 @@ impl ModRepo {
           }
 
           let filters = &data.filters;
+          let category_filters = &data.category_filters;
+          let game_version_filter = data.game_version_filter;
           let search = &data.search;
           data.items.iter_mut().par_bridge().for_each(|item| {
             if let Filter::Search(search) = payload {
@@ -242,6 +366,14 @@ impl ModRepo {
                     .as_ref()
                     .is_some_and(|source| source.contains(filter))
                 }))
+              && (category_filters.is_empty()
+                || category_filters.iter().all(|filter| {
+                  item
+                    .categories
+                    .as_ref()
+                    .is_some_and(|categories| categories.contains(filter))
+                }))
+              && game_version_filter.map_or(true, |filter| item.game_version_key() == Some(filter))
           })
         })
         .on_command(ModRepo::UPDATE_SORTING, |_, sorting, data| {
@@ -253,14 +385,65 @@ impl ModRepo {
       .with_close()
       .build()
       .on_command(OPEN_IN_BROWSER, |ctx, _, _| ctx.set_disabled(true))
+      .on_command(App::OPEN_WEBVIEW, |ctx, _, _| ctx.set_disabled(true))
       .on_command(App::ENABLE, |ctx, _, _| ctx.set_disabled(false))
   }
 
+  /// Kept in the data dir, as the cache dir is cleared on exit.
+  fn cache_path() -> PathBuf {
+    PROJECT.data_dir().join("mod_repo.json")
+  }
+
+  /// Fetches the index, saving it to disk so it can be shown straight away next time, or while
+  /// offline.
   pub async fn get_mod_repo() -> anyhow::Result<Self> {
-    let mut repo = reqwest::get(Self::REPO_URL)
+    let text = net::api_client_builder()
+      .build()?
+      .get(Self::REPO_URL)
+      .send()
       .await?
-      .json::<ModRepo>()
+      .error_for_status()?
+      .text()
       .await?;
+    let repo = Self::parse(&text)?;
+
+    let path = Self::cache_path();
+    if let Err(err) = tokio::fs::create_dir_all(PROJECT.data_dir()).await {
+      warn!("Failed to create data dir for the mod repo: {}", err)
+    } else if let Err(err) = tokio::fs::write(&path, text).await {
+      warn!("Failed to cache the mod repo at {:?}: {}", path, err)
+    }
+
+    Ok(repo)
+  }
+
+  /// The index saved by the last successful fetch, if there is one.
+  pub async fn load_cache() -> Option<Self> {
+    let text = tokio::fs::read_to_string(Self::cache_path()).await.ok()?;
+
+    Self::parse(&text)
+      .inspect_err(|err| warn!("Failed to parse cached mod repo: {}", err))
+      .ok()
+  }
+
+  /// Swaps in a freshly fetched index, unless it's the same one already shown, or marks the shown
+  /// copy as offline if the refresh failed.
+  pub fn apply_refresh(current: &mut Option<Self>, refreshed: &Result<Self, String>) {
+    match (current.as_mut(), refreshed) {
+      (Some(current), Ok(repo)) if current.last_updated == repo.last_updated => {
+        current.offline = false
+      }
+      (_, Ok(repo)) => *current = Some(repo.clone()),
+      (Some(current), Err(err)) => {
+        warn!("Failed to refresh mod repo, showing cached copy: {}", err);
+        current.offline = true
+      }
+      (None, Err(err)) => warn!("Failed to fetch mod repo: {}", err),
+    }
+  }
+
+  fn parse(text: &str) -> anyhow::Result<Self> {
+    let mut repo = serde_json::from_str::<ModRepo>(text)?;
 
     repo.items.iter_mut().for_each(|item| {
       item.summary = item.summary.as_ref().map(|summary| deunicode(summary));
@@ -276,10 +459,173 @@ impl ModRepo {
     Ok(repo)
   }
 
+  /// The repo as first fetched this session, shared by anything that only needs to look mods up
+  /// rather than display the listing.
+  pub async fn cached() -> Option<&'static Self> {
+    static REPO: OnceCell<Option<ModRepo>> = OnceCell::const_new();
+
+    REPO
+      .get_or_init(|| async {
+        match Self::get_mod_repo().await {
+          Ok(repo) => Some(repo),
+          Err(_) => Self::load_cache().await,
+        }
+      })
+      .await
+      .as_ref()
+  }
+
+  /// Finds the listed version of a mod, matching on its forum thread or Nexus Mods ID.
+  pub fn find_version(&self, fractal_id: &str, nexus_id: &str) -> Option<String> {
+    self
+      .find_item(fractal_id, nexus_id)
+      .and_then(|item| item.mod_version.clone())
+  }
+
+  /// Finds somewhere else to get a mod from when `failed_url` is no longer reachable, preferring
+  /// a direct download over a download page.
+  pub fn find_alternate_download(
+    &self,
+    fractal_id: &str,
+    nexus_id: &str,
+    failed_url: &str,
+  ) -> Option<(UrlSource, String)> {
+    let urls = self.find_item(fractal_id, nexus_id)?.urls.as_ref()?;
+
+    IntoIterator::into_iter([UrlSource::DirectDownload, UrlSource::DownloadPage]).find_map(
+      |source| {
+        urls
+          .get(&source)
+          .filter(|url| url.trim_end_matches('/') != failed_url.trim_end_matches('/'))
+          .map(|url| (source, url.clone()))
+      },
+    )
+  }
+
+  fn find_item(&self, fractal_id: &str, nexus_id: &str) -> Option<&ModRepoItem> {
+    self
+      .position(fractal_id, nexus_id)
+      .map(|idx| &self.items[idx])
+  }
+
+  fn position(&self, fractal_id: &str, nexus_id: &str) -> Option<usize> {
+    let matches_forum = |url: &String| {
+      !fractal_id.is_empty()
+        && url.split(['?', '&', ';']).any(|param| {
+          param
+            .strip_prefix("topic=")
+            .is_some_and(|topic| topic.split('.').next() == Some(fractal_id))
+        })
+    };
+    let matches_nexus = |url: &String| {
+      !nexus_id.is_empty()
+        && url
+          .trim_end_matches('/')
+          .ends_with(&format!("/mods/{}", nexus_id))
+    };
+
+    self.items.iter().position(|item| {
+      item.urls.as_ref().is_some_and(|urls| {
+        urls.get(&UrlSource::Forum).is_some_and(matches_forum)
+          || urls.get(&UrlSource::NexusMods).is_some_and(matches_nexus)
+      })
+    })
+  }
+
+  /// Finds the repo's record of an installed mod, by the forum or Nexus ids in its version file,
+  /// or failing that by name.
+  fn position_of_installed(&self, entry: &ModEntry) -> Option<usize> {
+    let (fractal_id, nexus_id) = entry.version_checker.as_ref().map_or(("", ""), |meta| {
+      (meta.fractal_id.as_str(), meta.nexus_id.as_str())
+    });
+
+    self.position(fractal_id, nexus_id).or_else(|| {
+      let name = normalise_name(&entry.name);
+      self
+        .items
+        .iter()
+        .position(|item| normalise_name(&item.name) == name)
+    })
+  }
+
+  /// Makes this the index installed mods are looked up in.
+  pub fn set_index(&self) {
+    if let Ok(mut index) = INDEX.write() {
+      *index = Some(self.clone())
+    }
+  }
+
+  /// What the current index knows about an installed mod.
+  pub fn record_for(entry: &ModEntry) -> Option<RepoRecord> {
+    let index = INDEX.read().ok()?;
+    let repo = index.as_ref()?;
+
+    repo
+      .position_of_installed(entry)
+      .map(|idx| RepoRecord::from(&repo.items[idx]))
+  }
+
+  /// Marks the repo's entries for mods that are installed, and whether the repo lists a newer
+  /// version than the one installed.
+  pub fn mark_installed<'a>(&mut self, installed: impl Iterator<Item = &'a ModEntry>) {
+    let mut marks = std::collections::HashMap::new();
+    for entry in installed {
+      if let Some(idx) = self.position_of_installed(entry) {
+        let update_available = self.items[idx]
+          .mod_version
+          .as_deref()
+          .and_then(parse_version)
+          .zip(parse_version(&entry.version.to_string()))
+          .is_some_and(|(repo, installed)| repo > installed);
+
+        marks.insert(
+          idx,
+          if update_available {
+            InstallState::UpdateAvailable
+          } else {
+            InstallState::Installed
+          },
+        );
+      }
+    }
+
+    for (idx, item) in self.items.iter_mut().enumerate() {
+      let mark = marks.get(&idx).copied();
+      if item.installed != mark {
+        item.installed = mark
+      }
+    }
+  }
+
   pub fn modal_open(&self) -> bool {
     self.modal.is_some()
   }
 
+  /// Every category used in the index, in alphabetical order.
+  fn categories(&self) -> Vec<String> {
+    self
+      .items
+      .iter()
+      .filter_map(|item| item.categories.as_ref())
+      .flatten()
+      .cloned()
+      .collect::<std::collections::BTreeSet<_>>()
+      .into_iter()
+      .collect()
+  }
+
+  /// Every game version declared in the index, newest first.
+  fn game_versions(&self) -> Vec<(u32, u32)> {
+    self
+      .items
+      .iter()
+      .filter_map(ModRepoItem::game_version_key)
+      .collect::<std::collections::BTreeSet<_>>()
+      .into_iter()
+      .rev()
+      .collect()
+  }
+
   fn default_sorting() -> Metadata {
     Metadata::Name
   }
@@ -309,6 +655,9 @@ pub struct ModRepoItem {
   #[data(same_fn = "PartialEq::eq")]
   #[serde(alias = "dateTimeEdited")]
   edited: Option<DateTime<Utc>>,
+  #[data(same_fn = "PartialEq::eq")]
+  #[serde(alias = "forumPostDetails")]
+  forum: Option<ForumDetails>,
   #[serde(skip)]
   show_description: bool,
   #[serde(skip)]
@@ -316,6 +665,8 @@ pub struct ModRepoItem {
   display: bool,
   #[serde(skip)]
   score: Option<isize>,
+  #[serde(skip)]
+  installed: Option<InstallState>,
 }
 
 impl ModRepoItem {
@@ -323,18 +674,71 @@ impl ModRepoItem {
   const LABEL_FLEX: f64 = 1.0;
   const VALUE_FLEX: f64 = 3.0;
 
+  /// The major and minor game version this mod declares it's compatible with, eg: `(0, 97)` for
+  /// 0.97a-RC11.
+  fn game_version_key(&self) -> Option<(u32, u32)> {
+    let (major, minor, ..) = parse_game_version(self.game_version.as_deref()?);
+
+    Some((major?.parse().ok()?, minor?.parse().ok()?))
+  }
+
   fn ui_builder() -> impl Widget<ModRepoItem> {
     Flex::column()
       .with_child(
         Flex::row()
           .with_flex_child(
-            Label::new("Name:").align_right().expand_width(),
+            Label::new(tr("repo-name")).align_right().expand_width(),
             Self::LABEL_FLEX,
           )
           .with_flex_child(Label::wrapped_lens(ModRepoItem::name), Self::VALUE_FLEX)
           .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
           .expand_width(),
       )
+      .with_child(
+        Maybe::or_empty(|| {
+          Label::wrapped_func(|state: &InstallState, _| state.to_string())
+            .with_text_color(ON_GREEN_KEY)
+            .padding((5., 2.))
+            .background(GREEN_KEY)
+            .rounded(3.)
+            .align_right()
+            .expand_width()
+        })
+        .lens(ModRepoItem::installed),
+      )
+      .with_child(
+        Maybe::or_empty(|| {
+          Flex::row()
+            .with_child(Either::new(
+              |target: &InstallTarget, _| matches!(target, InstallTarget::Download(_)),
+              Button2::from_label(tr("repo-download-only"))
+                .on_click2(|ctx, _, target: &mut InstallTarget, _| {
+                  if let InstallTarget::Download(url) = target {
+                    ctx.submit_command_global(App::DOWNLOAD_ONLY.with(url.clone()))
+                  }
+                })
+                .padding((0., 0., 5., 0.)),
+              SizedBox::empty(),
+            ))
+            .with_child(Button2::from_label(tr("repo-install")).on_click2(
+              |ctx, _, target: &mut InstallTarget, _| match target {
+                InstallTarget::Download(url) => {
+                  ctx.submit_command_global(WEBVIEW_INSTALL.with(InstallType::Uri(url.clone())))
+                }
+                InstallTarget::Page(url) => {
+                  ctx.submit_command_global(App::OPEN_WEBVIEW.with(Some(url.clone())))
+                }
+              },
+            ))
+            .align_right()
+            .expand_width()
+            .padding((0., 5., 0., 0.))
+        })
+        .lens(ModRepoItem::urls.map(
+          |urls| urls.as_ref().and_then(InstallTarget::from_urls),
+          |_, _| {},
+        )),
+      )
       .with_child(
         Maybe::or_empty(|| Separator::new().with_width(0.5).padding(5.)).lens(ModRepoItem::summary),
       )
@@ -342,7 +746,7 @@ impl ModRepoItem {
         Maybe::or_empty(|| {
           Flex::row()
             .with_flex_child(
-              Label::new("Summary:").align_right().expand_width(),
+              Label::new(tr("repo-summary")).align_right().expand_width(),
               Self::LABEL_FLEX,
             )
             .with_flex_child(
@@ -369,7 +773,7 @@ impl ModRepoItem {
                   Icon::new(ARROW_DROP_DOWN),
                   Icon::new(ARROW_RIGHT),
                 ))
-                .with_child(Label::new("Description:"))
+                .with_child(Label::new(tr("repo-description")))
                 .main_axis_alignment(druid::widget::MainAxisAlignment::End)
                 .align_right()
                 .expand_width()
@@ -384,7 +788,7 @@ impl ModRepoItem {
               row.with_flex_child(Label::wrapped(description), Self::VALUE_FLEX)
             } else {
               row.with_flex_child(
-                Label::new("Click to expand...")
+                Label::new(tr("repo-expand"))
                   .controller(HoverController)
                   .on_click(|_, data: &mut bool, _| *data = !*data)
                   .lens(ModRepoItem::show_description),
@@ -411,7 +815,7 @@ impl ModRepoItem {
         Maybe::or_empty(|| {
           Flex::row()
             .with_flex_child(
-              Label::new("Authors:").align_right().expand_width(),
+              Label::new(tr("repo-authors")).align_right().expand_width(),
               Self::LABEL_FLEX,
             )
             .with_flex_child(
@@ -450,7 +854,7 @@ impl ModRepoItem {
         Maybe::or_empty(|| {
           Flex::row()
             .with_flex_child(
-              Label::new("Links:").align_right().expand_width(),
+              Label::new(tr("repo-links")).align_right().expand_width(),
               Self::LABEL_FLEX,
             )
             .with_flex_child(
@@ -521,7 +925,9 @@ impl ModRepoItem {
             .with_child(
               Flex::row()
                 .with_flex_child(
-                  Label::new("Updated at:").align_right().expand_width(),
+                  Label::new(tr("repo-updated-at"))
+                    .align_right()
+                    .expand_width(),
                   Self::LABEL_FLEX,
                 )
                 .with_flex_child(
@@ -550,7 +956,9 @@ impl ModRepoItem {
             .with_child(
               Flex::row()
                 .with_flex_child(
-                  Label::new("Created at:").align_right().expand_width(),
+                  Label::new(tr("repo-created-at"))
+                    .align_right()
+                    .expand_width(),
                   Self::LABEL_FLEX,
                 )
                 .with_flex_child(
@@ -610,6 +1018,96 @@ impl Display for ModSource {
   }
 }
 
+/// Whether a repo entry is for a mod that's already installed.
+#[derive(Clone, Copy, PartialEq, Eq, Data, Debug)]
+enum InstallState {
+  Installed,
+  UpdateAvailable,
+}
+
+impl Display for InstallState {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      InstallState::Installed => write!(f, "{}", tr("repo-installed")),
+      InstallState::UpdateAvailable => write!(f, "{}", tr("repo-update-available")),
+    }
+  }
+}
+
+/// What the Install button on a repo entry does.
+#[derive(Clone, PartialEq, Eq, Data, Debug)]
+enum InstallTarget {
+  /// Download and install the mod directly
+  Download(String),
+  /// Open the forum thread scrolled to its download link, or the download page, in the webview
+  Page(String),
+}
+
+impl InstallTarget {
+  fn from_urls(urls: &HashMap<UrlSource, String>) -> Option<Self> {
+    if let Some(url) = urls.get(&UrlSource::DirectDownload) {
+      Some(Self::Download(url.clone()))
+    } else if let Some(url) = urls.get(&UrlSource::Forum) {
+      let thread = url.split('#').next().unwrap_or(url);
+
+      Some(Self::Page(format!("{}#{}", thread, SCROLL_TO_DOWNLOAD)))
+    } else {
+      urls
+        .get(&UrlSource::DownloadPage)
+        .map(|url| Self::Page(url.clone()))
+    }
+  }
+}
+
+/// What the mod repo knows about an installed mod.
+#[derive(Clone, PartialEq, Eq, Data, Lens, Debug, Default)]
+pub struct RepoRecord {
+  /// The forum thread, or Discord post if there's no thread
+  pub discussion: Option<String>,
+  #[data(same_fn = "PartialEq::eq")]
+  pub categories: Vector<String>,
+  #[data(same_fn = "PartialEq::eq")]
+  pub updated: Option<DateTime<Utc>>,
+  /// When the author last edited the mod's forum thread, or the repo entry if there's no thread
+  #[data(same_fn = "PartialEq::eq")]
+  pub author_activity: Option<DateTime<Utc>>,
+}
+
+impl From<&ModRepoItem> for RepoRecord {
+  fn from(item: &ModRepoItem) -> Self {
+    Self {
+      discussion: item.urls.as_ref().and_then(|urls| {
+        urls
+          .get(&UrlSource::Forum)
+          .or_else(|| urls.get(&UrlSource::Discord))
+          .cloned()
+      }),
+      categories: item.categories.clone().unwrap_or_default(),
+      updated: item.edited.or(item.created),
+      author_activity: item
+        .forum
+        .as_ref()
+        .and_then(|forum| forum.last_edited)
+        .or(item.edited),
+    }
+  }
+}
+
+/// What the repo scraped from a mod's forum thread.
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+struct ForumDetails {
+  #[serde(alias = "lastEditedDate")]
+  last_edited: Option<DateTime<Utc>>,
+}
+
+fn normalise_name(name: &str) -> String {
+  deunicode(name)
+    .chars()
+    .filter(char::is_ascii_alphanumeric)
+    .map(|c| c.to_ascii_lowercase())
+    .collect()
+}
+
 #[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash, Data, Debug)]
 pub enum UrlSource {
   Forum,
@@ -621,22 +1119,21 @@ pub enum UrlSource {
 
 impl Display for UrlSource {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    f.write_fmt(format_args!(
-      "{}",
-      match self {
-        UrlSource::Forum => "Fractal Mod Forums",
-        UrlSource::Discord => "Discord",
-        UrlSource::NexusMods => "Nexus Mods",
-        UrlSource::DirectDownload => "Raw Url",
-        UrlSource::DownloadPage => "Other",
-      }
-    ))
+    match self {
+      UrlSource::Forum => f.write_str("Fractal Mod Forums"),
+      UrlSource::Discord => f.write_str("Discord"),
+      UrlSource::NexusMods => f.write_str("Nexus Mods"),
+      UrlSource::DirectDownload => f.write_str(&tr("repo-link-direct-download")),
+      UrlSource::DownloadPage => f.write_str(&tr("repo-link-download-page")),
+    }
   }
 }
 
 #[derive(Clone, PartialEq, Data)]
 enum Filter {
   Source(ModSource),
+  Category(String),
+  GameVersion(Option<(u32, u32)>),
   Search(String),
 }
 
@@ -644,7 +1141,12 @@ impl Display for Filter {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       Filter::Source(source) => source.fmt(f),
-      Filter::Search(_) => f.write_fmt(format_args!("Search")),
+      Filter::Category(category) => category.fmt(f),
+      Filter::GameVersion(Some((major, minor))) => {
+        f.write_fmt(format_args!("{}.{}a", major, minor))
+      }
+      Filter::GameVersion(None) => f.write_str(&tr("repo-filter-any-version")),
+      Filter::Search(_) => f.write_str(&tr("repo-filter-search")),
     }
   }
 }
@@ -675,15 +1177,12 @@ impl Metadata {
 
 impl Display for Metadata {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    f.write_fmt(format_args!(
-      "{}",
-      match self {
-        Self::Name => "Name",
-        Self::Created => "Created At",
-        Self::Updated => "Updated At",
-        Self::Authors => "Author(s)",
-        Self::Score => unimplemented!(),
-      }
-    ))
+    f.write_str(&tr(match self {
+      Self::Name => "repo-sort-name",
+      Self::Created => "repo-sort-created",
+      Self::Updated => "repo-sort-updated",
+      Self::Authors => "repo-sort-authors",
+      Self::Score => unimplemented!(),
+    }))
   }
 }
@@ -0,0 +1,277 @@
+//! Generates the folder of a new, empty mod, for mod authors starting from scratch.
+//!
+//! The mod gets a `mod_info.json`, an optional version file registered in `version_files.csv`,
+//! and an optional mod plugin. The plugin is a loose script in `data/scripts`, which the game
+//! compiles itself, so the new mod loads without needing a jar built first.
+
+use std::path::{Path, PathBuf};
+
+use druid::{
+  widget::{Button, Checkbox, Flex, Label, Maybe, TextBox},
+  Data, EventCtx, Lens, Widget, WidgetExt,
+};
+
+use super::{
+  i18n::{tr, tr_args},
+  modal::Modal,
+  util::{make_flex_description_row, LabelExt, WidgetExtEx},
+  version_source::parse_version,
+  App,
+};
+
+/// Folders every new mod starts with, so it's clear where things go.
+const FOLDERS: [&str; 6] = [
+  "data/config",
+  "data/hulls",
+  "data/scripts",
+  "data/weapons",
+  "graphics",
+  "sounds",
+];
+
+#[derive(Clone, Data, Lens)]
+pub struct SkeletonForm {
+  #[data(same_fn = "PartialEq::eq")]
+  mods_dir: PathBuf,
+  id: String,
+  name: String,
+  author: String,
+  version: String,
+  game_version: String,
+  description: String,
+  plugin: bool,
+  version_file: bool,
+  error: Option<String>,
+}
+
+impl SkeletonForm {
+  pub fn new(mods_dir: PathBuf, game_version: Option<String>) -> Self {
+    Self {
+      mods_dir,
+      id: String::new(),
+      name: String::new(),
+      author: String::new(),
+      version: String::from("0.1.0"),
+      game_version: game_version.unwrap_or_default(),
+      description: String::new(),
+      plugin: true,
+      version_file: true,
+      error: None,
+    }
+  }
+
+  /// The folder the mod will be created in, named after its ID.
+  fn folder(&self) -> PathBuf {
+    self.mods_dir.join(self.id.trim())
+  }
+
+  /// The plugin's class name, made from the mod's name: `My Cool Mod!` becomes
+  /// `MyCoolModModPlugin`.
+  fn plugin_class(&self) -> String {
+    let mut class: String = self
+      .name
+      .split(|c: char| !c.is_ascii_alphanumeric())
+      .filter(|word| !word.is_empty())
+      .map(|word| word[..1].to_uppercase() + &word[1..])
+      .collect();
+    if !class.starts_with(|c: char| c.is_ascii_alphabetic()) {
+      class.insert_str(0, "Mod");
+    }
+
+    class + "ModPlugin"
+  }
+
+  fn validate(&self) -> Result<(), String> {
+    let id = self.id.trim();
+    if id.is_empty()
+      || !id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+      return Err(tr("skeleton-id-invalid"));
+    }
+    if self.name.trim().is_empty() {
+      return Err(tr("skeleton-name-empty"));
+    }
+    if self.version_file && parse_version(&self.version).is_none() {
+      return Err(tr("skeleton-version-invalid"));
+    }
+    if self.folder().exists() {
+      return Err(tr_args(
+        "skeleton-folder-exists",
+        &[("path", &self.folder().to_string_lossy())],
+      ));
+    }
+
+    Ok(())
+  }
+
+  /// Writes the new mod, returning its folder. Nothing is left behind if any part fails.
+  pub fn create(&self) -> Result<PathBuf, String> {
+    self.validate()?;
+
+    let folder = self.folder();
+    let res = self.write(&folder).map_err(|err| err.to_string());
+    if res.is_err() {
+      let _ = std::fs::remove_dir_all(&folder);
+    }
+
+    res.map(|_| folder)
+  }
+
+  fn write(&self, folder: &Path) -> std::io::Result<()> {
+    for dir in FOLDERS {
+      std::fs::create_dir_all(folder.join(dir))?;
+    }
+
+    let quote = |text: &str| serde_json::to_string(text.trim()).unwrap_or_default();
+    let id = self.id.trim();
+    let mut mod_info = format!(
+      "{{\n  \"id\": {},\n  \"name\": {},\n  \"author\": {},\n  \"version\": {},\n  \
+       \"description\": {},\n  \"gameVersion\": {},\n",
+      quote(id),
+      quote(&self.name),
+      quote(&self.author),
+      quote(&self.version),
+      quote(&self.description),
+      quote(&self.game_version),
+    );
+
+    if self.plugin {
+      let class = self.plugin_class();
+      mod_info.push_str(&format!("  \"modPlugin\": \"data.scripts.{}\",\n", class));
+      std::fs::write(
+        folder.join("data/scripts").join(format!("{}.java", class)),
+        format!(
+          "package data.scripts;\n\nimport com.fs.starfarer.api.BaseModPlugin;\n\n\
+           public class {} extends BaseModPlugin {{\n  @Override\n  \
+           public void onApplicationLoad() throws Exception {{\n  }}\n\n  @Override\n  \
+           public void onGameLoad(boolean newGame) {{\n  }}\n}}\n",
+          class
+        ),
+      )?;
+    }
+    mod_info.push_str("}\n");
+    std::fs::write(folder.join("mod_info.json"), mod_info)?;
+
+    if self.version_file
+      && let Some(version) = parse_version(&self.version)
+    {
+      let version_dir = folder.join("data/config/version");
+      std::fs::create_dir_all(&version_dir)?;
+      std::fs::write(
+        version_dir.join("version_files.csv"),
+        format!("version file\ndata/config/version/{}.version\n", id),
+      )?;
+      std::fs::write(
+        version_dir.join(format!("{}.version", id)),
+        format!(
+          "{{\n  # a direct link to the raw text of this file once it's hosted, such as on \
+           GitHub\n  \"masterVersionFile\": \"\",\n  \"modName\": {},\n  \
+           # the number at the end of the mod's forum thread URL\n  \"modThreadId\": \"\",\n  \
+           \"modVersion\": {{ \"major\": {}, \"minor\": {}, \"patch\": {} }}\n}}\n",
+          quote(&self.name),
+          version.major,
+          version.minor,
+          quote(&version.patch),
+        ),
+      )?;
+    }
+
+    Ok(())
+  }
+
+  fn submit(&mut self, ctx: &mut EventCtx) {
+    match self.create() {
+      Ok(folder) => {
+        let _ = opener::open(&folder);
+        ctx.submit_command_global(App::REFRESH);
+        ctx.submit_command(druid::commands::CLOSE_WINDOW);
+      }
+      Err(err) => self.error = Some(err),
+    }
+  }
+
+  pub fn modal<'a>() -> Modal<'a, Self> {
+    fn row(
+      label: &str,
+      lens: impl Lens<SkeletonForm, String> + 'static,
+    ) -> Box<dyn Widget<SkeletonForm>> {
+      make_flex_description_row(
+        Label::wrapped(label),
+        TextBox::new().lens(lens).expand_width(),
+      )
+      .boxed()
+    }
+
+    Modal::new(&tr("skeleton-title"))
+      .with_content(tr("skeleton-hint"))
+      .with_content(row(&tr("mod-info-id"), SkeletonForm::id))
+      .with_content(row(&tr("mod-info-name"), SkeletonForm::name))
+      .with_content(row(&tr("mod-info-author"), SkeletonForm::author))
+      .with_content(row(&tr("mod-info-version"), SkeletonForm::version))
+      .with_content(row(
+        &tr("mod-info-game-version"),
+        SkeletonForm::game_version,
+      ))
+      .with_content(row(&tr("mod-info-description"), SkeletonForm::description))
+      .with_content(
+        Checkbox::new(tr("skeleton-plugin"))
+          .lens(SkeletonForm::plugin)
+          .boxed(),
+      )
+      .with_content(
+        Checkbox::new(tr("skeleton-version-file"))
+          .lens(SkeletonForm::version_file)
+          .boxed(),
+      )
+      .with_content(
+        Maybe::or_empty(|| Label::wrapped_func(|error: &String, _| error.clone()))
+          .lens(SkeletonForm::error)
+          .boxed(),
+      )
+      .with_content(
+        Flex::row()
+          .with_flex_spacer(1.)
+          .with_child(
+            Button::new(tr("skeleton-create"))
+              .on_activate(|ctx, form: &mut SkeletonForm, _| form.submit(ctx)),
+          )
+          .expand_width()
+          .boxed(),
+      )
+      .with_close_label(&tr("close"))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use tempfile::tempdir;
+
+  use super::SkeletonForm;
+  use crate::app::mod_entry::{ModEntry, ModMetadata};
+
+  #[test]
+  fn creates_loadable_mod() {
+    let mods = tempdir().expect("Create mods dir");
+    let mut form = SkeletonForm::new(mods.path().to_path_buf(), Some(String::from("0.97a-RC11")));
+    form.id = String::from("my_mod");
+    form.name = String::from("My \"Cool\" mod");
+
+    let folder = form.create().expect("Create mod");
+    let entry = ModEntry::from_file(&folder, ModMetadata::default()).expect("Load mod");
+    assert_eq!(entry.id, "my_mod");
+    assert_eq!(entry.name, "My \"Cool\" mod");
+    assert_eq!(
+      entry
+        .version_checker
+        .map(|checker| checker.version.to_string()),
+      Some(String::from("0.1.0"))
+    );
+    assert!(folder
+      .join("data/scripts/MyCoolModModPlugin.java")
+      .is_file());
+
+    assert!(form.create().is_err());
+  }
+}
@@ -10,7 +10,10 @@ use druid_widget_nursery::{AnyCtx, RequestCtx, Stack, StackChildPosition};
 use indexmap::IndexMap;
 use tap::Tap;
 
-use super::util::{h3, DragWindowController, LabelExt, WidgetExtEx};
+use super::{
+  i18n::tr,
+  util::{h3, DragWindowController, LabelExt, WidgetExtEx},
+};
 
 pub struct Modal<'a, T: Data> {
   title: String,
@@ -54,7 +57,7 @@ impl<'a, T: Data> Modal<'a, T> {
   }
 
   pub fn with_close(self) -> Self {
-    self.close("Close")
+    self.close(&tr("close"))
   }
 
   pub fn with_close_label(self, label: &str) -> Self {
@@ -174,7 +177,7 @@ impl<'a, T: Data> Modal<'a, T> {
           .with_flex_spacer(1.)
           .tap_mut(|flex| {
             for (label, commands) in self.buttons.drain(..) {
-              flex.add_child(Button::new(label).on_click({
+              flex.add_child(Button::new(label).on_activate({
                 move |ctx, data, _| {
                   for command in &commands {
                     match command {
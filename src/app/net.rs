@@ -0,0 +1,142 @@
+//! Network settings shared by every HTTP client the app creates.
+//!
+//! Clients are built through [`client_builder`] or [`api_client_builder`] so the user's proxy,
+//! extra CA certificate and timeouts apply everywhere - version checks, the mod repo, downloads
+//! (including those started from the webview), the JRE swapper and self updates.
+
+use std::{
+  sync::{LazyLock, RwLock},
+  time::Duration,
+};
+
+use druid::{Data, Lens};
+use reqwest::{Certificate, Client, ClientBuilder, Proxy};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+static CURRENT: LazyLock<RwLock<NetworkSettings>> = LazyLock::new(Default::default);
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Data, Lens, Debug)]
+#[serde(default)]
+pub struct NetworkSettings {
+  /// eg: `http://proxy.example.com:8080` or `socks5://127.0.0.1:1080`, empty for no proxy
+  pub proxy: String,
+  /// Path to a PEM certificate to trust on top of the built-in roots, empty for none
+  pub ca_certificate: String,
+  /// Seconds to wait for a connection, 0 for no limit
+  pub connect_timeout_secs: u32,
+  /// Seconds to wait for small requests such as version checks to finish, 0 for no limit.
+  /// Downloads are never cut off.
+  pub request_timeout_secs: u32,
+}
+
+impl Default for NetworkSettings {
+  fn default() -> Self {
+    Self {
+      proxy: String::new(),
+      ca_certificate: String::new(),
+      connect_timeout_secs: 30,
+      request_timeout_secs: 60,
+    }
+  }
+}
+
+impl NetworkSettings {
+  fn parse_proxy(&self) -> Result<Option<Proxy>, String> {
+    let proxy = self.proxy.trim();
+    if proxy.is_empty() {
+      return Ok(None);
+    }
+
+    Proxy::all(proxy)
+      .map(Some)
+      .map_err(|err| format!("Invalid proxy {}: {}", proxy, err))
+  }
+
+  fn certificate(&self) -> Result<Option<Certificate>, String> {
+    let path = self.ca_certificate.trim();
+    if path.is_empty() {
+      return Ok(None);
+    }
+
+    std::fs::read(path)
+      .map_err(|err| err.to_string())
+      .and_then(|pem| Certificate::from_pem(&pem).map_err(|err| err.to_string()))
+      .map(Some)
+      .map_err(|err| format!("Invalid CA certificate {}: {}", path, err))
+  }
+
+  /// What's wrong with these settings, if anything. Anything invalid is left out of clients.
+  pub fn problems(&self) -> Option<String> {
+    let problems: Vec<String> =
+      IntoIterator::into_iter([self.parse_proxy().err(), self.certificate().err()])
+        .flatten()
+        .collect();
+
+    (!problems.is_empty()).then(|| problems.join("\n"))
+  }
+}
+
+/// Applies `settings` to every client built from now on.
+pub fn configure(settings: &NetworkSettings) {
+  if let Some(problems) = settings.problems() {
+    warn!("{}", problems)
+  }
+  if let Ok(mut current) = CURRENT.write() {
+    *current = settings.clone()
+  }
+}
+
+/// A client builder with the user's proxy, CA certificate and connect timeout, for downloads.
+pub fn client_builder() -> ClientBuilder {
+  let settings = CURRENT.read().map(|s| s.clone()).unwrap_or_default();
+  let mut builder = Client::builder();
+
+  if let Ok(Some(proxy)) = settings.parse_proxy() {
+    builder = builder.proxy(proxy)
+  }
+  if let Ok(Some(certificate)) = settings.certificate() {
+    builder = builder.add_root_certificate(certificate)
+  }
+  if settings.connect_timeout_secs > 0 {
+    builder = builder.connect_timeout(Duration::from_secs(settings.connect_timeout_secs.into()))
+  }
+
+  builder
+}
+
+/// [`client_builder`] plus the user's request timeout, for requests that should finish quickly.
+pub fn api_client_builder() -> ClientBuilder {
+  let timeout = CURRENT.read().map_or(0, |s| s.request_timeout_secs);
+  let builder = client_builder();
+
+  if timeout > 0 {
+    builder.timeout(Duration::from_secs(timeout.into()))
+  } else {
+    builder
+  }
+}
+
+/// A client from [`api_client_builder`].
+pub fn api_client() -> Result<Client, String> {
+  api_client_builder().build().map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod test {
+  use super::NetworkSettings;
+
+  #[test]
+  fn reports_invalid_settings() {
+    assert!(NetworkSettings::default().problems().is_none());
+
+    let settings = NetworkSettings {
+      proxy: String::from("not a proxy"),
+      ca_certificate: String::from("/does/not/exist.pem"),
+      ..Default::default()
+    };
+    let problems = settings.problems().expect("Invalid settings");
+    assert!(problems.contains("Invalid proxy"));
+    assert!(problems.contains("Invalid CA certificate"));
+  }
+}
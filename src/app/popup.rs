@@ -0,0 +1,134 @@
+//! Popups raised from outside the widget tree, usually by background tasks that have no way of
+//! opening a window themselves. News that doesn't need the user to stop and look is shown as a
+//! [`Toast`](super::toast::Toast) instead.
+
+use std::path::PathBuf;
+
+use druid::{
+  commands,
+  widget::{Button, Flex},
+  Application, DelegateCtx, EventCtx, Selector, Target, WidgetExt, WindowDesc, WindowId,
+  WindowLevel,
+};
+use tap::Pipe;
+
+use super::{
+  controllers::HoverController,
+  i18n::tr,
+  modal::Modal,
+  progress::Progress,
+  settings::{Settings, SettingsCommand},
+  util::WidgetExtEx,
+  App,
+};
+
+/// Popups are blocking, each is its own window the user has to close. [`Popup::Critical`] is the
+/// one tier above the rest, every other window that opens raises it back to the front.
+/// Non-blocking news goes through [`Toast`](super::toast::Toast), which never opens a window, and
+/// none of these hide the webview.
+pub enum Popup {
+  /// A failure that would otherwise only end up in the log. The full error chain is shown and can
+  /// be copied, so it can be pasted into a bug report.
+  Error(anyhow::Error),
+  /// A failure to write something the game depends on, like `enabled_mods.json`, which leaves
+  /// MOSS and the game disagreeing until it's dealt with. Kept above every other popup and
+  /// subwindow until it's closed, so a later warning can't bury it.
+  Critical(anyhow::Error),
+  /// A long running task, with a button to cancel it. Closes itself once the task finishes, and
+  /// hiding it leaves the task running. Title, progress.
+  Progress(String, Progress),
+  /// Starsector installs found on first run, to pick from before falling back to a file picker.
+  SelectInstall(Vec<PathBuf>),
+}
+
+impl Popup {
+  pub const SHOW: Selector<Popup> = Selector::new("app.popup.show");
+
+  pub fn is_critical(&self) -> bool {
+    matches!(self, Popup::Critical(_))
+  }
+
+  /// Opens the popup, returning the id of its window.
+  pub fn show(&self, ctx: &mut DelegateCtx) -> WindowId {
+    let (modal, size) = match self {
+      Popup::Error(err) => (Self::error_modal(&tr("popup-error"), err), (500., 300.)),
+      Popup::Critical(err) => (Self::error_modal(&tr("popup-critical"), err), (550., 350.)),
+      Popup::Progress(title, progress) => {
+        let cancel = progress.clone();
+        let modal = Modal::<App>::new(title)
+          .with_content(progress.view().boxed())
+          .with_button(&tr("cancel"), move |ctx: &mut EventCtx, _: &mut App| {
+            if !cancel.cancel() {
+              ctx.submit_command(App::LOG_MESSAGE.with(tr("popup-progress-too-late")))
+            }
+          })
+          .with_close_label(&tr("popup-progress-hide"))
+          .build();
+
+        (modal.boxed(), (450., 180.))
+      }
+      Popup::SelectInstall(installs) => (Self::select_install_modal(installs), (500., 300.)),
+    };
+
+    let window = WindowDesc::new(modal)
+      .window_size(size)
+      .show_titlebar(false)
+      .set_level(WindowLevel::AppWindow);
+    let id = window.id;
+    ctx.new_window(window);
+
+    id
+  }
+
+  fn select_install_modal(installs: &[PathBuf]) -> Box<dyn druid::Widget<App>> {
+    let mut choices = Flex::column().cross_axis_alignment(druid::widget::CrossAxisAlignment::Start);
+    for install in installs {
+      let install = install.clone();
+      choices.add_child(
+        Button::new(install.to_string_lossy().to_string())
+          .controller(HoverController)
+          .on_activate(move |ctx, _, _| {
+            ctx.submit_command(
+              Settings::SELECTOR
+                .with(SettingsCommand::UpdateInstallDir(install.clone()))
+                .to(Target::Global),
+            );
+            ctx.submit_command(commands::CLOSE_WINDOW)
+          })
+          .padding((0., 2.)),
+      )
+    }
+
+    Modal::<App>::new(&tr("popup-select-install-title"))
+      .pipe(|modal| {
+        if installs.is_empty() {
+          modal.with_content(tr("popup-select-install-none"))
+        } else {
+          modal
+            .with_content(tr("popup-select-install-found"))
+            .with_content(choices.boxed())
+        }
+      })
+      .with_button(
+        &tr("popup-select-install-browse"),
+        Settings::SELECTOR.with(SettingsCommand::SelectInstallDir),
+      )
+      .with_close_label(&tr("popup-select-install-later"))
+      .build()
+      .boxed()
+  }
+
+  fn error_modal(title: &str, err: &anyhow::Error) -> Box<dyn druid::Widget<App>> {
+    let body = format!("{:?}", err);
+
+    Modal::<App>::new(title)
+      .with_content(body.clone())
+      .with_button(
+        &tr("popup-copy-error"),
+        move |_: &mut EventCtx, _: &mut App| Application::global().clipboard().put_string(&body),
+      )
+      .with_close()
+      .build()
+      .boxed()
+  }
+}
@@ -0,0 +1,275 @@
+//! A handle long running tasks report how far along they are through, shown in a
+//! [`Popup::Progress`](super::popup::Popup::Progress) with real byte or mod counts and a cancel
+//! button, rather than a spinner.
+//!
+//! Tasks check [`Progress::is_cancelled`] at points where stopping is safe, and
+//! [`Progress::lock`] it once they're past the last of them, after which it can't be cancelled.
+//! It's also how installs and downloads without a popup of their own are cancelled, from the
+//! install queue or the download list.
+//!
+//! Progress that belongs to something already on screen, like an entry in the install queue or
+//! the mod folder scan in the sidebar, is shown with [`Progress::inline_view`] instead.
+
+use std::{
+  sync::{
+    atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering},
+    Arc, Mutex,
+  },
+  time::Duration,
+};
+
+use druid::{
+  commands,
+  widget::{Flex, Label, ProgressBar},
+  BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, Lens, LifeCycle, LifeCycleCtx, PaintCtx,
+  Point, Size, TimerToken, UpdateCtx, Widget, WidgetExt, WidgetPod,
+};
+
+use super::util::{format_size, LabelExt};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const RUNNING: u8 = 0;
+const CANCELLED: u8 = 1;
+const LOCKED: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+  Bytes,
+  Mods,
+}
+
+#[derive(Debug, Clone)]
+pub struct Progress(Arc<Inner>);
+
+#[derive(Debug)]
+struct Inner {
+  unit: Unit,
+  done: AtomicU64,
+  /// Zero while the total isn't known.
+  total: AtomicU64,
+  stage: Mutex<String>,
+  state: AtomicU8,
+  finished: AtomicBool,
+}
+
+impl Progress {
+  pub fn new(unit: Unit) -> Self {
+    Self(Arc::new(Inner {
+      unit,
+      done: AtomicU64::new(0),
+      total: AtomicU64::new(0),
+      stage: Mutex::new(String::new()),
+      state: AtomicU8::new(RUNNING),
+      finished: AtomicBool::new(false),
+    }))
+  }
+
+  pub fn set_total(&self, total: u64) {
+    self.0.total.store(total, Ordering::Relaxed)
+  }
+
+  pub fn set_done(&self, done: u64) {
+    self.0.done.store(done, Ordering::Relaxed)
+  }
+
+  pub fn advance(&self, by: u64) {
+    self.0.done.fetch_add(by, Ordering::Relaxed);
+  }
+
+  /// What's being worked on, eg: which mod is being updated.
+  pub fn set_stage(&self, stage: impl Into<String>) {
+    *self.0.stage.lock().unwrap() = stage.into()
+  }
+
+  /// Asks the task to stop. Returns false if it's too late to.
+  pub fn cancel(&self) -> bool {
+    self
+      .0
+      .state
+      .compare_exchange(RUNNING, CANCELLED, Ordering::SeqCst, Ordering::SeqCst)
+      .is_ok()
+      || self.is_cancelled()
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.0.state.load(Ordering::SeqCst) == CANCELLED
+  }
+
+  /// Stops the task being cancelled from here on, once it's started something that can't be left
+  /// half done. Has no effect if it's already been cancelled.
+  pub fn lock(&self) {
+    let _ = self
+      .0
+      .state
+      .compare_exchange(RUNNING, LOCKED, Ordering::SeqCst, Ordering::SeqCst);
+  }
+
+  /// Resolves once the task has been cancelled, for racing against work that is safe to drop at
+  /// any await point until [`lock`](Self::lock)ed.
+  pub async fn cancelled(&self) {
+    while !self.is_cancelled() {
+      tokio::time::sleep(POLL_INTERVAL).await
+    }
+  }
+
+  /// Marks the task as done, which closes its popup.
+  pub fn finish(&self) {
+    self.0.finished.store(true, Ordering::Relaxed)
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.0.finished.load(Ordering::Relaxed)
+  }
+
+  fn status(&self) -> Status {
+    let done = self.0.done.load(Ordering::Relaxed);
+    let total = self.0.total.load(Ordering::Relaxed);
+    let count = |count: u64| match self.0.unit {
+      Unit::Bytes => format_size(count),
+      Unit::Mods => count.to_string(),
+    };
+    let unit = match self.0.unit {
+      Unit::Bytes => "",
+      Unit::Mods => " mods",
+    };
+
+    Status {
+      stage: self.0.stage.lock().unwrap().clone(),
+      fraction: if total > 0 {
+        done as f64 / total as f64
+      } else {
+        0.
+      },
+      counts: if total > 0 {
+        format!("{} of {}{}", count(done), count(total), unit)
+      } else {
+        format!("{}{}", count(done), unit)
+      },
+    }
+  }
+
+  /// Shows the progress of the task, closing the window it's in once the task finishes.
+  pub fn view<T: Data>(&self) -> impl Widget<T> {
+    self.build_view(true)
+  }
+
+  /// Shows the progress of the task in place, leaving it to whatever contains it to go away once
+  /// the task finishes.
+  pub fn inline_view<T: Data>(&self) -> impl Widget<T> {
+    self.build_view(false)
+  }
+
+  fn build_view(&self, close_when_finished: bool) -> ProgressView {
+    ProgressView {
+      progress: self.clone(),
+      close_when_finished,
+      status: self.status(),
+      inner: WidgetPod::new(
+        Flex::column()
+          .with_child(Label::wrapped_lens(Status::stage))
+          .with_spacer(5.)
+          .with_child(ProgressBar::new().lens(Status::fraction).expand_width())
+          .with_child(Label::dynamic(|status: &Status, _| status.counts.clone()))
+          .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+          .boxed(),
+      ),
+      timer: TimerToken::INVALID,
+    }
+  }
+}
+
+impl PartialEq for Progress {
+  fn eq(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.0, &other.0)
+  }
+}
+
+/// Two handles are the same if they report on the same task, [`ProgressView`] polls for the rest.
+impl Data for Progress {
+  fn same(&self, other: &Self) -> bool {
+    self == other
+  }
+}
+
+#[derive(Clone, Data, Lens, PartialEq)]
+struct Status {
+  stage: String,
+  fraction: f64,
+  counts: String,
+}
+
+/// Polls a [`Progress`], which isn't [`Data`], into a [`Status`] for its children to display.
+struct ProgressView {
+  progress: Progress,
+  close_when_finished: bool,
+  status: Status,
+  inner: WidgetPod<Status, Box<dyn Widget<Status>>>,
+  timer: TimerToken,
+}
+
+impl<T: Data> Widget<T> for ProgressView {
+  fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+    match event {
+      Event::WindowConnected => self.timer = ctx.request_timer(POLL_INTERVAL),
+      Event::Timer(token) if *token == self.timer => {
+        let status = self.progress.status();
+        if status != self.status {
+          self.status = status;
+          ctx.request_update();
+        }
+        if self.progress.is_finished() {
+          if self.close_when_finished {
+            ctx.submit_command(commands::CLOSE_WINDOW)
+          }
+        } else {
+          self.timer = ctx.request_timer(POLL_INTERVAL)
+        }
+      }
+      _ => {}
+    }
+
+    self.inner.event(ctx, event, &mut self.status, env)
+  }
+
+  fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, env: &Env) {
+    self.inner.lifecycle(ctx, event, &self.status, env)
+  }
+
+  fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, env: &Env) {
+    self.inner.update(ctx, &self.status, env)
+  }
+
+  fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+    let size = self.inner.layout(ctx, bc, &self.status, env);
+    self.inner.set_origin(ctx, Point::ORIGIN);
+
+    size
+  }
+
+  fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+    self.inner.paint(ctx, &self.status, env)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{Progress, Unit};
+
+  #[test]
+  fn cannot_cancel_once_locked() {
+    let progress = Progress::new(Unit::Mods);
+    progress.set_total(3);
+    progress.advance(1);
+    assert_eq!(progress.status().counts, "1 of 3 mods");
+
+    progress.lock();
+    assert!(!progress.cancel());
+    assert!(!progress.is_cancelled());
+
+    let progress = Progress::new(Unit::Bytes);
+    assert!(progress.cancel());
+    progress.lock();
+    assert!(progress.is_cancelled());
+  }
+}
@@ -0,0 +1,204 @@
+//! A rough estimate of the heap the game needs for the enabled mods, to compare against the
+//! `-Xmx` in vmparams.
+//!
+//! The game keeps every texture and sound of every enabled mod loaded, so the estimate is mostly
+//! the size of the mods' `graphics` and `sounds` folders. Code, factions and a handful of mods
+//! known to need more than their size suggests are added on top.
+
+use std::path::Path;
+
+use druid::{im::Vector, Data, Lens, Selector};
+
+use super::settings::vmparams::{Unit, VMParams, Value};
+
+pub const ESTIMATED: Selector<RamEstimate> = Selector::new("ram_estimate.estimated");
+/// Writes the last estimate's recommendation to vmparams.
+pub const APPLY: Selector = Selector::new("ram_estimate.apply");
+
+/// What the game itself needs with no mods enabled.
+const BASE_MB: u64 = 1536;
+/// Images are compressed on disk, and take a few times as much once loaded.
+const ASSET_FACTOR: u64 = 2;
+const JAR_FACTOR: u64 = 3;
+/// Each faction adds fleets, markets and doctrine data to the sector.
+const FACTION_MB: u64 = 32;
+/// Recommendations are rounded up to a multiple of this.
+const STEP_MB: u64 = 512;
+
+/// Mod IDs and the extra heap they need beyond what their size suggests.
+const HEAVY_MODS: [(&str, u64); 4] = [
+  // GraphicsLib generates lighting and normal maps for every ship in memory
+  ("shaderLib", 1024),
+  // Nexerelin's invasions and diplomacy keep a lot of extra state in the sector
+  ("nexerelin", 512),
+  ("IndEvo", 256),
+  // Unknown Skies adds many planet types with large textures
+  ("US", 256),
+];
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct RamEstimate {
+  pub recommended_mb: u64,
+  pub assets_mb: u64,
+  pub jars_mb: u64,
+  pub factions: usize,
+  #[data(same_fn = "PartialEq::eq")]
+  pub heavy_mods: Vector<String>,
+  /// `-Xmx` from vmparams when the estimate was made.
+  pub current_mb: Option<u64>,
+}
+
+impl RamEstimate {
+  /// Estimates the heap needed for mods given as ID and folder.
+  pub fn new<'a>(mods: impl IntoIterator<Item = (&'a str, &'a Path)>) -> Self {
+    let mut estimate = Self {
+      recommended_mb: 0,
+      assets_mb: 0,
+      jars_mb: 0,
+      factions: 0,
+      heavy_mods: Vector::new(),
+      current_mb: None,
+    };
+
+    let (mut asset_bytes, mut jar_bytes, mut heavy_mb) = (0, 0, 0);
+    for (id, path) in mods {
+      let sizes = Sizes::of(path);
+      asset_bytes += sizes.assets;
+      jar_bytes += sizes.jars;
+      estimate.factions += sizes.factions;
+      if let Some((heavy, extra)) = HEAVY_MODS.iter().find(|(heavy, _)| *heavy == id) {
+        estimate.heavy_mods.push_back(heavy.to_string());
+        heavy_mb += extra;
+      }
+    }
+    estimate.assets_mb = asset_bytes / 1024 / 1024;
+    estimate.jars_mb = jar_bytes / 1024 / 1024;
+
+    let needed = BASE_MB
+      + estimate.assets_mb * ASSET_FACTOR
+      + estimate.jars_mb * JAR_FACTOR
+      + estimate.factions as u64 * FACTION_MB
+      + heavy_mb;
+    estimate.recommended_mb = (needed + STEP_MB - 1) / STEP_MB * STEP_MB;
+
+    estimate
+  }
+
+  /// Whether `-Xmx` is well short of the recommendation, rather than just under it.
+  pub fn too_low(&self) -> bool {
+    self
+      .current_mb
+      .is_some_and(|current| current * 5 < self.recommended_mb * 4)
+  }
+
+  /// The recommendation as a vmparams value, in whole gigabytes where possible.
+  pub fn recommended_value(&self) -> Value {
+    if self.recommended_mb % 1024 == 0 {
+      Value {
+        amount: (self.recommended_mb / 1024) as i32,
+        unit: Unit::Giga,
+      }
+    } else {
+      Value {
+        amount: self.recommended_mb as i32,
+        unit: Unit::Mega,
+      }
+    }
+  }
+
+  /// Sets `-Xmx` to the recommendation, and `-Xms` too if it was the same as `-Xmx` before.
+  pub fn apply(&self, vmparams: &mut VMParams) {
+    if vmparams.heap_init.megabytes() == vmparams.heap_max.megabytes() {
+      vmparams.heap_init = self.recommended_value();
+    }
+    vmparams.heap_max = self.recommended_value();
+  }
+}
+
+/// Sizes of the parts of a mod folder that affect memory use.
+#[derive(Default)]
+struct Sizes {
+  assets: u64,
+  jars: u64,
+  factions: usize,
+}
+
+impl Sizes {
+  fn of(path: &Path) -> Self {
+    fn walk(dir: &Path, is_asset: bool, sizes: &mut Sizes) {
+      for entry in dir.read_dir().into_iter().flatten().flatten() {
+        let Ok(file_type) = entry.file_type() else {
+          continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+          walk(&path, is_asset, sizes)
+        } else if file_type.is_file() {
+          let len = entry.metadata().map_or(0, |metadata| metadata.len());
+          let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+          if extension.eq_ignore_ascii_case("jar") {
+            sizes.jars += len
+          } else if extension.eq_ignore_ascii_case("faction") {
+            sizes.factions += 1
+          } else if is_asset {
+            sizes.assets += len
+          }
+        }
+      }
+    }
+
+    let mut sizes = Sizes::default();
+    for entry in path.read_dir().into_iter().flatten().flatten() {
+      if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+        let name = entry.file_name();
+        let is_asset = name == "graphics" || name == "sounds";
+        walk(&entry.path(), is_asset, &mut sizes)
+      }
+    }
+
+    sizes
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use tempfile::tempdir;
+
+  use super::RamEstimate;
+  use crate::app::settings::vmparams::Unit;
+
+  #[test]
+  fn estimates_from_mod_contents() {
+    let dir = tempdir().expect("Create mod dir");
+    for (file, len) in [
+      ("graphics/ships/big.png", 300 * 1024 * 1024),
+      ("jars/mod.jar", 10 * 1024 * 1024),
+      ("data/world/factions/pirates.faction", 1024),
+      ("data/world/factions/new_faction.faction", 1024),
+      ("data/config/settings.json", 50 * 1024 * 1024),
+    ] {
+      let path = dir.path().join(file);
+      std::fs::create_dir_all(path.parent().expect("Parent dir")).expect("Create dir");
+      std::fs::File::create(&path)
+        .and_then(|file| file.set_len(len))
+        .expect("Write file");
+    }
+
+    let mut estimate = RamEstimate::new([("nexerelin", dir.path())]);
+    assert_eq!(estimate.assets_mb, 300);
+    assert_eq!(estimate.jars_mb, 10);
+    assert_eq!(estimate.factions, 2);
+    // 1536 + 600 + 30 + 64 + 512 = 2742, rounded up
+    assert_eq!(estimate.recommended_mb, 3072);
+    assert_eq!(estimate.recommended_value().unit, Unit::Giga);
+    assert_eq!(estimate.recommended_value().amount, 3);
+
+    estimate.current_mb = Some(1536);
+    assert!(estimate.too_low());
+    estimate.current_mb = Some(2560);
+    assert!(!estimate.too_low());
+  }
+}
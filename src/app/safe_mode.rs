@@ -0,0 +1,144 @@
+//! Recovery from the game crashing as it starts, which is almost always down to a mod.
+//!
+//! After a launch from MOSS that exits within about a minute, the part of `starsector.log`
+//! written since is checked for an error logged in the game's first minute. If there is one, safe mode offers to disable the most
+//! recently enabled or installed half of the enabled mods and launch again, so taking it after
+//! each crash halves the suspects until the broken mod is found.
+
+use std::{
+  collections::HashSet,
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::Duration,
+};
+
+use druid::Selector;
+
+use super::{mod_entry::ModEntry, util};
+
+/// The error line of a crash found in the log.
+pub const CRASHED: Selector<String> = Selector::new("safe_mode.crashed");
+/// IDs of the mods to disable before launching again.
+pub const ENTER: Selector<Vec<String>> = Selector::new("safe_mode.enter");
+
+/// Errors logged later than this many milliseconds after the game started don't count.
+const STARTUP_MS: u64 = 60_000;
+/// A game that ran for longer than this was played rather than crashing as it started, whatever
+/// its log says. A little longer than [`STARTUP_MS`], to allow for the launcher and JVM starting.
+const EXITED_WITHIN: Duration = Duration::from_secs(75);
+
+pub fn log_path(install_dir: &Path) -> Option<PathBuf> {
+  util::find_game_file(install_dir, "starsector.log")
+}
+
+/// How long the log is, taken before launching so only what the launch writes is checked.
+pub fn log_len(install_dir: &Path) -> u64 {
+  log_path(install_dir)
+    .and_then(|path| path.metadata().ok())
+    .map_or(0, |metadata| metadata.len())
+}
+
+/// The startup crash logged since the log was `offset` bytes long, if any, for a game that exited
+/// `ran_for` after being launched.
+pub fn check(install_dir: &Path, offset: u64, ran_for: Duration) -> Option<String> {
+  if ran_for > EXITED_WITHIN {
+    return None;
+  }
+  let bytes = std::fs::read(log_path(install_dir)?).ok()?;
+  // a shorter log has been replaced rather than appended to
+  let start = if bytes.len() as u64 >= offset {
+    offset as usize
+  } else {
+    0
+  };
+
+  startup_crash(&String::from_utf8_lossy(&bytes[start..]))
+}
+
+/// Finds an exception logged as an error within [`STARTUP_MS`] of the game starting. Each log
+/// line starts with the milliseconds since the game started, as in
+/// `5129 [main] ERROR com.fs.starfarer.combat.CombatMain  - java.lang.RuntimeException: ...`.
+fn startup_crash(log: &str) -> Option<String> {
+  log.lines().find_map(|line| {
+    let (elapsed, rest) = line.trim_start().split_once(' ')?;
+    let elapsed: u64 = elapsed.parse().ok()?;
+
+    (elapsed <= STARTUP_MS
+      && rest.contains("] ERROR ")
+      && (rest.contains("Exception") || rest.contains("Error")))
+    .then(|| line.trim().to_string())
+  })
+}
+
+/// The enabled mods safe mode disables: the most recent half, by when they were enabled this
+/// session then when they were installed, plus any mod that depends on one of them.
+pub fn suspects<'a>(
+  mods: impl IntoIterator<Item = &'a Arc<ModEntry>>,
+  recently_enabled: &[String],
+) -> Vec<Arc<ModEntry>> {
+  let mut enabled: Vec<Arc<ModEntry>> = mods
+    .into_iter()
+    .filter(|entry| entry.enabled)
+    .cloned()
+    .collect();
+  enabled.sort_by(|a, b| {
+    let recency = |entry: &ModEntry| {
+      recently_enabled
+        .iter()
+        .position(|id| *id == entry.id)
+        .unwrap_or(usize::MAX)
+    };
+    recency(a).cmp(&recency(b)).then_with(|| {
+      b.manager_metadata
+        .install_date
+        .cmp(&a.manager_metadata.install_date)
+    })
+  });
+
+  let (suspects, rest) = enabled.split_at((enabled.len() + 1) / 2);
+  let mut suspects = suspects.to_vec();
+  let mut ids: HashSet<String> = suspects.iter().map(|entry| entry.id.clone()).collect();
+  let mut rest = rest.to_vec();
+  while let Some(idx) = rest.iter().position(|entry| {
+    entry
+      .dependencies
+      .iter()
+      .any(|dependency| ids.contains(&dependency.id))
+  }) {
+    let dependent = rest.remove(idx);
+    ids.insert(dependent.id.clone());
+    suspects.push(dependent);
+  }
+
+  suspects
+}
+
+#[cfg(test)]
+mod test {
+  use super::startup_crash;
+
+  #[test]
+  fn finds_startup_crash() {
+    let log = "\
+0    [main] INFO  com.fs.starfarer.StarfarerLauncher  - Starting Starsector 0.97a-RC11 launcher
+4411 [Thread-3] ERROR com.fs.starfarer.combat.CombatMain  - java.lang.RuntimeException: Error \
+               loading [data.scripts.ExampleModPlugin]
+4412 [Thread-3] ERROR com.fs.starfarer.combat.CombatMain  - java.lang.RuntimeException: again
+";
+    assert_eq!(
+      startup_crash(log),
+      Some(String::from(
+        "4411 [Thread-3] ERROR com.fs.starfarer.combat.CombatMain  - java.lang.RuntimeException: \
+         Error loading [data.scripts.ExampleModPlugin]"
+      ))
+    );
+
+    let late = "912000 [Thread-3] ERROR com.fs.starfarer.combat.CombatMain  - \
+                java.lang.NullPointerException";
+    assert_eq!(startup_crash(late), None);
+    assert_eq!(
+      startup_crash("1200 [main] ERROR sound.Sound  - missing sound"),
+      None
+    );
+  }
+}
@@ -0,0 +1,115 @@
+//! Access tokens for private or protected download hosts, such as GitHub releases of private
+//! repos.
+//!
+//! Tokens are kept in the OS keychain, keyed by the domain they're used for - only the list of
+//! domains is saved in the config file. They're read once per session and held in memory, so if
+//! the keychain is unavailable tokens still work until MOSS is closed.
+
+use std::{
+  collections::HashMap,
+  sync::{LazyLock, RwLock},
+};
+
+use reqwest::Url;
+use tracing::warn;
+
+const SERVICE: &str = "org.laird.starsector-mod-manager";
+
+static TOKENS: LazyLock<RwLock<HashMap<String, String>>> = LazyLock::new(Default::default);
+
+/// Normalises user input such as `https://Example.com/` down to a bare domain.
+pub fn normalise_domain(input: &str) -> String {
+  let input = input.trim();
+  Url::parse(input)
+    .ok()
+    .and_then(|url| url.host_str().map(str::to_string))
+    .unwrap_or_else(|| input.trim_end_matches('/').to_string())
+    .to_lowercase()
+}
+
+/// Reads the tokens for `domains` from the keychain into this session.
+pub fn load<'a>(domains: impl IntoIterator<Item = &'a String>) {
+  let Ok(mut tokens) = TOKENS.write() else {
+    return;
+  };
+  for domain in domains {
+    match keyring::Entry::new(SERVICE, domain).and_then(|entry| entry.get_password()) {
+      Ok(token) => {
+        tokens.insert(domain.clone(), token);
+      }
+      Err(err) => warn!("Failed to read token for {} from keychain: {}", domain, err),
+    }
+  }
+}
+
+/// Stores `token` for `domain`. Returns false if the keychain couldn't be written to, in which
+/// case the token is only kept for the current session.
+pub fn set(domain: &str, token: &str) -> bool {
+  if let Ok(mut tokens) = TOKENS.write() {
+    tokens.insert(domain.to_string(), token.to_string());
+  }
+
+  match keyring::Entry::new(SERVICE, domain).and_then(|entry| entry.set_password(token)) {
+    Ok(()) => true,
+    Err(err) => {
+      warn!("Failed to save token for {} to keychain: {}", domain, err);
+      false
+    }
+  }
+}
+
+pub fn remove(domain: &str) {
+  if let Ok(mut tokens) = TOKENS.write() {
+    tokens.remove(domain);
+  }
+
+  match keyring::Entry::new(SERVICE, domain).and_then(|entry| entry.delete_password()) {
+    Ok(()) | Err(keyring::Error::NoEntry) => {}
+    Err(err) => warn!(
+      "Failed to remove token for {} from keychain: {}",
+      domain, err
+    ),
+  }
+}
+
+/// The token to send with a request to `url`, if one has been set for its domain or any parent
+/// domain.
+pub fn token_for(url: &str) -> Option<String> {
+  let url = Url::parse(url).ok()?;
+  let host = url.host_str()?.to_lowercase();
+  let tokens = TOKENS.read().ok()?;
+
+  tokens
+    .iter()
+    .filter(|(domain, _)| matches_domain(&host, domain))
+    .max_by_key(|(domain, _)| domain.len())
+    .map(|(_, token)| token.clone())
+}
+
+fn matches_domain(host: &str, domain: &str) -> bool {
+  host == domain
+    || host
+      .strip_suffix(domain)
+      .is_some_and(|subdomain| subdomain.ends_with('.'))
+}
+
+#[cfg(test)]
+mod test {
+  use super::{matches_domain, normalise_domain};
+
+  #[test]
+  fn normalises_domain() {
+    assert_eq!(
+      normalise_domain(" https://Example.com/path "),
+      "example.com"
+    );
+    assert_eq!(normalise_domain("github.com/"), "github.com");
+  }
+
+  #[test]
+  fn matches_subdomains() {
+    assert!(matches_domain("github.com", "github.com"));
+    assert!(matches_domain("objects.github.com", "github.com"));
+    assert!(!matches_domain("notgithub.com", "github.com"));
+  }
+}
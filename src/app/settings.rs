@@ -1,4 +1,9 @@
-use std::{path::PathBuf, rc::Rc};
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  rc::Rc,
+  sync::Arc,
+};
 
 use druid::{
   im::Vector,
@@ -6,36 +11,62 @@ use druid::{
   text::ParseFormatter,
   theme,
   widget::{
-    Axis, Button, Checkbox, Controller, Either, Flex, Label, Maybe, Painter, SizedBox, TextBox,
-    TextBoxEvent, ValidationDelegate, ViewSwitcher, WidgetExt,
+    Axis, Button, Checkbox, Controller, Either, Flex, Label, List, Maybe, Painter, RadioGroup,
+    SizedBox, TextBox, TextBoxEvent, ValidationDelegate, ViewSwitcher, WidgetExt,
   },
-  Data, Event, EventCtx, Lens, LensExt, Menu, MenuItem, RenderContext, Selector, Widget,
-  WindowConfig,
+  Data, Event, EventCtx, ExtEventSink, Lens, LensExt, Menu, MenuItem, RenderContext, Selector,
+  Target, Widget, WindowConfig,
+};
+use druid_widget_nursery::{
+  material_icons::Icon, DynLens, ProgressBar, WidgetExt as WidgetExtNursery,
 };
-use druid_widget_nursery::{material_icons::Icon, DynLens, WidgetExt as WidgetExtNursery};
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use tap::{Pipe, Tap};
+use tracing::error;
 
 use crate::{app::PROJECT, patch::click::Click};
 
 use self::{
-  jre::{revert, Flavour},
+  game_backup::Snapshot,
+  graphicslib::{GraphicsOptions, Preset, Toggle},
+  jre::{revert, Flavour, InstalledJre, Phase},
   vmparams::{Unit, VMParams, Value},
 };
 
 use super::{
+  compat_db,
   controllers::HoverController,
-  mod_list::headings::{Header, Heading},
+  file_association,
+  i18n::{self, tr, tr_args},
+  logging,
+  mod_entry::GameVersion,
+  mod_list::{
+    headings::{Header, Heading},
+    library, FilterPreset,
+  },
   modal::Modal,
+  net::{self, NetworkSettings},
+  popup::Popup,
+  theme::{Theme, UI_SCALES},
+  update_check,
+  updater::UpdateChannel,
+  url_scheme,
   util::{
-    bold_text, button_painter, default_true, h2, icons::*, make_column_pair, make_flex_pair,
-    make_flex_settings_row, Button2, Card, CommandExt, LabelExt, LoadError, SaveError,
+    self, bold_text, button_painter, default_true, h2, icons::*, make_column_pair, make_flex_pair,
+    make_flex_settings_row, Button2, Card, CommandExt, LabelExt, LoadError, SaveError, WidgetExtEx,
+    RED_KEY,
   },
+  version_source::NEXUS_API_KEY,
   App,
 };
 
+pub mod discovery;
+pub mod game_backup;
+pub mod graphicslib;
 pub mod jre;
+pub mod layout;
+pub mod mikohime;
 pub mod vmparams;
 
 const TRAILING_PADDING: (f64, f64, f64, f64) = (0., 0., 0., 5.);
@@ -60,23 +91,182 @@ pub struct Settings {
   pub hide_webview_on_conflict: bool,
   #[serde(default = "default_true")]
   pub open_forum_link_in_webview: bool,
+  /// Offer to open or download mod links copied to the clipboard
+  #[serde(default)]
+  pub watch_clipboard: bool,
+  /// Offer to place downloaded `.jar` and `.json` files into a mod's folder, see
+  /// [`loose_file`](super::installer::loose_file)
+  #[serde(default)]
+  pub place_loose_files: bool,
+  /// Open `moss://` links from mod sites with MOSS
+  #[serde(default)]
+  pub register_url_scheme: bool,
+  /// Offer MOSS for opening mod archives, so they install when double-clicked
+  #[serde(default)]
+  pub associate_archives: bool,
   #[serde(skip)]
   show_column_editor: bool,
   #[serde(default = "default_headers")]
   #[data(same_fn = "PartialEq::eq")]
   pub headings: Vector<Heading>,
+  /// The mod list's sort keys, most significant first.
+  #[serde(default = "default_sort")]
+  #[data(same_fn = "PartialEq::eq")]
+  pub sort: Vector<(Heading, bool)>,
+  #[serde(default)]
+  pub layout: layout::Layout,
   #[serde(skip)]
   show_jre_swapper: bool,
   #[serde(skip)]
   jre_swap_in_progress: bool,
+  #[serde(skip)]
+  #[data(same_fn = "PartialEq::eq")]
+  jre_swap_phase: Option<Phase>,
+  #[serde(skip)]
+  installed_jre: Option<InstalledJre>,
+  /// The Java versions the Mikohime kit has a release for on this platform, once fetched.
+  #[serde(skip)]
+  mikohime_kits: Option<Arc<[u32]>>,
+  #[serde(skip)]
+  show_graphicslib: bool,
+  #[serde(skip)]
+  graphics_options: Option<GraphicsOptions>,
   jre_managed_mode: bool,
   pub show_auto_update_for_discrepancy: bool,
+  #[serde(default)]
+  #[data(same_fn = "PartialEq::eq")]
+  #[lens(ignore)]
+  pub cache_dir: Option<PathBuf>,
+  /// Where archives are saved when downloading without installing.
+  #[serde(default)]
+  #[data(same_fn = "PartialEq::eq")]
+  #[lens(ignore)]
+  pub download_dir: Option<PathBuf>,
+  #[serde(default)]
+  pub nexus_api_key: String,
+  /// Where the list of abandoned and conflicting mods is fetched from, see [`compat_db`].
+  #[serde(default = "default_compatibility_db_url")]
+  pub compatibility_db_url: String,
+  #[serde(default)]
+  pub install_dir_confirmed: bool,
+  /// Used in place of the version read from the install, for layouts it can't be found in.
+  #[serde(default)]
+  pub game_version_override: String,
+  #[serde(default)]
+  #[data(same_fn = "PartialEq::eq")]
+  pub picker_history: HashMap<Picker, PathBuf>,
+  #[serde(default)]
+  pub bulk_confirmation: BulkConfirmation,
+  #[serde(default = "default_bulk_confirmation_threshold")]
+  pub bulk_confirmation_threshold: u32,
+  /// Hours between update checks while the app is open, 0 to only check on startup.
+  #[serde(default = "default_update_check_interval_hours")]
+  pub update_check_interval_hours: u32,
+  /// Whether MOSS offers its own beta releases as updates.
+  #[serde(default)]
+  pub update_channel: UpdateChannel,
+  #[serde(default)]
+  pub network: NetworkSettings,
+  /// Domains with an access token in the keychain. The tokens themselves are never saved here.
+  #[serde(default)]
+  pub token_domains: Vector<String>,
+  #[serde(default = "default_language")]
+  pub language: String,
+  #[serde(default)]
+  pub theme: Theme,
+  /// Hex colour replacing the theme's accent colour, empty to use the theme's own.
+  #[serde(default)]
+  pub accent_colour: String,
+  /// Multiplies the size of text and controls, on top of the OS's own scaling.
+  #[serde(default = "default_ui_scale")]
+  pub ui_scale: f64,
+  /// Mod list queries saved as custom filters.
+  #[serde(default)]
+  #[data(same_fn = "PartialEq::eq")]
+  pub saved_queries: Vector<String>,
+  /// Named combinations of filters, search and sort.
+  #[serde(default)]
+  #[data(same_fn = "PartialEq::eq")]
+  pub filter_presets: Vector<FilterPreset>,
+  /// Folders of mods kept outside the game's mods folder, linked in when enabled.
+  #[serde(default)]
+  #[data(same_fn = "PartialEq::eq")]
+  pub external_mod_dirs: Vector<PathBuf>,
+  /// Install mods into the mod library rather than the mods folder, see [`library`].
+  #[serde(default)]
+  pub use_mod_library: bool,
+  #[serde(skip)]
+  external_dir_buf: String,
+  #[serde(skip)]
+  token_domain_buf: String,
+  #[serde(skip)]
+  token_buf: String,
+}
+
+/// Extra confirmation asked for before enabling or disabling many mods at once.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Data, Debug, Default)]
+pub enum BulkConfirmation {
+  #[default]
+  Never,
+  Checkbox,
+  TypeWord,
+}
+
+impl BulkConfirmation {
+  pub const WORD: &'static str = "CONFIRM";
+
+  /// Whether an action changing `affected` mods needs confirming.
+  pub fn required(self, affected: usize, threshold: u32) -> bool {
+    self != BulkConfirmation::Never && affected > threshold as usize
+  }
+
+  pub fn satisfied(self, typed: &str, ticked: bool) -> bool {
+    match self {
+      BulkConfirmation::Never => true,
+      BulkConfirmation::Checkbox => ticked,
+      BulkConfirmation::TypeWord => typed.trim() == Self::WORD,
+    }
+  }
+}
+
+fn default_language() -> String {
+  i18n::DEFAULT_LANGUAGE.to_string()
+}
+
+fn default_compatibility_db_url() -> String {
+  compat_db::DEFAULT_URL.to_string()
+}
+
+fn default_ui_scale() -> f64 {
+  1.
+}
+
+fn default_bulk_confirmation_threshold() -> u32 {
+  10
+}
+
+fn default_update_check_interval_hours() -> u32 {
+  24 * 7
+}
+
+/// The different file and folder pickers, each of which remembers where it was last used.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum Picker {
+  Archive,
+  Folder,
+  InstallDir,
+  CacheDir,
+  DownloadDir,
 }
 
 fn default_headers() -> Vector<Heading> {
   Header::TITLES.to_vec().into()
 }
 
+fn default_sort() -> Vector<(Heading, bool)> {
+  Vector::unit((Heading::Name, false))
+}
+
 impl Settings {
   pub const SELECTOR: Selector<SettingsCommand> = Selector::new("SETTINGS");
 
@@ -85,54 +275,356 @@ impl Settings {
       hide_webview_on_conflict: true,
       open_forum_link_in_webview: true,
       headings: default_headers(),
+      sort: default_sort(),
+      bulk_confirmation_threshold: default_bulk_confirmation_threshold(),
+      update_check_interval_hours: default_update_check_interval_hours(),
+      language: default_language(),
+      ui_scale: default_ui_scale(),
+      compatibility_db_url: default_compatibility_db_url(),
       ..Default::default()
     }
   }
 
+  /// Where to fetch the compatibility list from, `None` if it's been turned off.
+  pub fn compat_db_url(&self) -> Option<String> {
+    let url = self.compatibility_db_url.trim();
+
+    (!url.is_empty()).then(|| url.to_string())
+  }
+
+  /// The game version to check mods against instead of the detected one, if one has been set.
+  pub fn version_override(&self) -> Option<GameVersion> {
+    let text = self.game_version_override.trim();
+
+    (!text.is_empty()).then(|| util::parse_game_version(text))
+  }
+
+  fn refresh_installed_jre(&mut self) {
+    self.installed_jre = self.install_dir.as_deref().and_then(InstalledJre::detect)
+  }
+
+  /// Starts installing `flavour` in the background, or reverting to the game's own JRE if it's
+  /// `None`.
+  pub fn start_jre_swap(&mut self, ext_ctx: ExtEventSink, flavour: Option<Flavour>) {
+    let Some(install_dir) = self.install_dir.clone() else {
+      return;
+    };
+
+    self.jre_swap_in_progress = true;
+    self.jre_swap_phase = None;
+    match flavour {
+      Some(flavour) => tokio::runtime::Handle::current().spawn(flavour.swap(
+        ext_ctx,
+        install_dir,
+        self.jre_managed_mode,
+      )),
+      None => tokio::runtime::Handle::current().spawn(revert(ext_ctx, install_dir)),
+    };
+  }
+
   pub fn ui_builder() -> impl Widget<Self> {
-    Modal::new("Settings")
+    Modal::new(&tr("settings-title"))
       .with_content(
         Flex::column()
           .with_child(Self::install_dir_browser_builder(Axis::Horizontal).padding(TRAILING_PADDING))
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-game-version"))
+                .stack_tooltip(tr("settings-game-version-tooltip"))
+                .with_crosshair(true),
+              1.,
+              TextBox::new()
+                .with_placeholder(tr("settings-game-version-placeholder"))
+                .lens(Settings::game_version_override)
+                .expand_width(),
+              1.5,
+              Axis::Horizontal,
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(Self::cache_dir_browser_builder().padding(TRAILING_PADDING))
+          .with_child(Self::download_dir_browser_builder().padding(TRAILING_PADDING))
+          .with_child(Self::external_dirs_builder().padding(TRAILING_PADDING))
+          .with_child(Self::mod_library_builder().padding(TRAILING_PADDING))
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-nexus-api-key"))
+                .stack_tooltip(tr("settings-nexus-api-key-tooltip"))
+                .with_crosshair(true),
+              1.,
+              TextBox::new()
+                .with_placeholder(tr("settings-nexus-api-key-placeholder"))
+                .lens(Settings::nexus_api_key)
+                .expand_width(),
+              1.5,
+              Axis::Horizontal,
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-compatibility-db"))
+                .stack_tooltip(tr("settings-compatibility-db-tooltip"))
+                .with_crosshair(true),
+              1.,
+              TextBox::new()
+                .with_placeholder(tr("settings-compatibility-db-placeholder"))
+                .lens(Settings::compatibility_db_url)
+                .expand_width(),
+              1.5,
+              Axis::Horizontal,
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(Self::token_editor_builder().padding(TRAILING_PADDING))
           .with_child(
             make_flex_settings_row(
               Checkbox::new("").lens(Settings::git_warn),
-              Label::wrapped("Warn when overwriting '.git' folders"),
+              Label::wrapped(tr("settings-git-warn")),
             )
             .padding(TRAILING_PADDING),
           )
           .with_child(
             make_flex_settings_row(
               Checkbox::new("").lens(Settings::hide_webview_on_conflict),
-              Label::wrapped("Minimize browser when installation encounters conflict"),
+              Label::wrapped(tr("settings-hide-webview-on-conflict")),
             )
             .padding(TRAILING_PADDING),
           )
           .with_child(
             make_flex_settings_row(
               Checkbox::new("").lens(Settings::open_forum_link_in_webview),
-              Label::wrapped("Use bundled browser when opening forum links")
-                .stack_tooltip("This allows installing mods directly from links in forum posts")
+              Label::wrapped(tr("settings-forum-links-in-webview"))
+                .stack_tooltip(tr("settings-forum-links-in-webview-tooltip"))
                 .with_crosshair(true),
             )
             .padding(TRAILING_PADDING),
           )
+          .with_child(
+            make_flex_settings_row(
+              Checkbox::new("").lens(Settings::watch_clipboard),
+              Label::wrapped(tr("settings-watch-clipboard"))
+                .stack_tooltip(tr("settings-watch-clipboard-tooltip"))
+                .with_crosshair(true),
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_settings_row(
+              Checkbox::new("").lens(Settings::place_loose_files),
+              Label::wrapped(tr("settings-place-loose-files"))
+                .stack_tooltip(tr("settings-place-loose-files-tooltip"))
+                .with_crosshair(true),
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_settings_row(
+              Checkbox::new("").lens(Settings::register_url_scheme),
+              Label::wrapped(tr("settings-url-scheme"))
+                .stack_tooltip(tr("settings-url-scheme-tooltip"))
+                .with_crosshair(true),
+            )
+            .on_change(|ctx, old, data, _| {
+              if old.register_url_scheme == data.register_url_scheme {
+                return;
+              }
+              let res = if data.register_url_scheme {
+                url_scheme::register()
+              } else {
+                url_scheme::unregister()
+              };
+              if let Err(err) = res {
+                data.register_url_scheme = false;
+                ctx.submit_command(
+                  Popup::SHOW.with(Popup::Error(err.context(tr("settings-url-scheme-failed")))),
+                );
+              }
+            })
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_settings_row(
+              Checkbox::new("").lens(Settings::associate_archives),
+              Label::wrapped(tr("settings-associate-archives"))
+                .stack_tooltip(tr("settings-associate-archives-tooltip"))
+                .with_crosshair(true),
+            )
+            .on_change(|ctx, old, data, _| {
+              if old.associate_archives == data.associate_archives {
+                return;
+              }
+              let res = if data.associate_archives {
+                file_association::register()
+              } else {
+                file_association::unregister()
+              };
+              if let Err(err) = res {
+                data.associate_archives = false;
+                ctx.submit_command(Popup::SHOW.with(Popup::Error(
+                  err.context(tr("settings-associate-archives-failed")),
+                )));
+              }
+            })
+            .padding(TRAILING_PADDING),
+          )
           .with_child(
             make_flex_settings_row(
               Checkbox::new("").lens(Settings::show_auto_update_for_discrepancy),
               Flex::column()
-                .with_child(Label::wrapped("Show automatic updates even for mods that have a version discrepancy"))
-                .with_child(Label::wrapped("(Recommended Off)"))
-                .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+                .with_child(Label::wrapped(tr("settings-discrepancy-auto-update")))
+                .with_child(Label::wrapped(tr("settings-discrepancy-auto-update-hint")))
+                .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start),
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-language"))
+                .stack_tooltip(tr_args(
+                  "settings-language-tooltip",
+                  &[("dir", &i18n::lang_dir().to_string_lossy())],
+                ))
+                .with_crosshair(true),
+              1.,
+              RadioGroup::row(
+                i18n::available()
+                  .into_iter()
+                  .map(|(code, name)| (name, code))
+                  .collect::<Vec<_>>(),
+              )
+              .lens(Settings::language),
+              1.5,
+              Axis::Horizontal,
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-theme")),
+              1.,
+              RadioGroup::row(
+                Theme::iter()
+                  .map(|theme| (theme.to_string(), theme))
+                  .collect::<Vec<_>>(),
+              )
+              .lens(Settings::theme),
+              1.5,
+              Axis::Horizontal,
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-ui-scale"))
+                .stack_tooltip(tr("settings-ui-scale-tooltip"))
+                .with_crosshair(true),
+              1.,
+              RadioGroup::row(
+                UI_SCALES
+                  .into_iter()
+                  .map(|scale| (format!("{}%", scale * 100.), scale))
+                  .collect::<Vec<_>>(),
+              )
+              .lens(Settings::ui_scale),
+              1.5,
+              Axis::Horizontal,
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-accent"))
+                .stack_tooltip(tr("settings-accent-tooltip"))
+                .with_crosshair(true),
+              1.,
+              TextBox::new()
+                .with_placeholder(tr("settings-accent-placeholder"))
+                .lens(Settings::accent_colour)
+                .expand_width(),
+              1.5,
+              Axis::Horizontal,
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-bulk-confirmation")),
+              1.,
+              RadioGroup::row(vec![
+                (
+                  tr("settings-bulk-confirmation-off"),
+                  BulkConfirmation::Never,
+                ),
+                (
+                  tr("settings-bulk-confirmation-checkbox"),
+                  BulkConfirmation::Checkbox,
+                ),
+                (
+                  tr("settings-bulk-confirmation-type-word"),
+                  BulkConfirmation::TypeWord,
+                ),
+              ])
+              .lens(Settings::bulk_confirmation),
+              1.5,
+              Axis::Horizontal,
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-bulk-confirmation-threshold")),
+              1.,
+              TextBox::new()
+                .with_formatter(ParseFormatter::new())
+                .update_data_while_editing(true)
+                .lens(Settings::bulk_confirmation_threshold)
+                .expand_width(),
+              1.5,
+              Axis::Horizontal,
+            )
+            .disabled_if(|data: &Settings, _| data.bulk_confirmation == BulkConfirmation::Never)
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-update-check-interval"))
+                .stack_tooltip(tr("settings-update-check-interval-tooltip"))
+                .with_crosshair(true),
+              1.,
+              TextBox::new()
+                .with_formatter(ParseFormatter::new())
+                .update_data_while_editing(true)
+                .lens(Settings::update_check_interval_hours)
+                .expand_width(),
+              1.5,
+              Axis::Horizontal,
             )
-            .padding(TRAILING_PADDING)
+            .padding(TRAILING_PADDING),
           )
+          .with_child(
+            make_flex_pair(
+              Label::wrapped(tr("settings-update-channel"))
+                .stack_tooltip(tr("settings-update-channel-tooltip"))
+                .with_crosshair(true),
+              1.,
+              RadioGroup::row(vec![
+                (tr("settings-update-channel-stable"), UpdateChannel::Stable),
+                (tr("settings-update-channel-beta"), UpdateChannel::Beta),
+              ])
+              .lens(Settings::update_channel),
+              1.5,
+              Axis::Horizontal,
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(Self::network_builder().lens(Settings::network))
           .with_child(
             make_flex_settings_row(
               SizedBox::empty(),
-              Button::from_label(Label::wrapped("Edit columns")).on_click(
+              Button::from_label(Label::wrapped(tr("settings-edit-columns"))).on_click(
                 |ctx, data: &mut Settings, env| {
-                  let modal = Modal::<Settings>::new("Column Editor")
+                  let modal = Modal::<Settings>::new(&tr("settings-column-editor-title"))
                     .with_content(
                       ViewSwitcher::new(
                         |headings: &Vector<Heading>, _| headings.clone(),
@@ -147,7 +639,7 @@ impl Settings {
                                       Icon::new(ARROW_LEFT)
                                         .background(button_painter())
                                         .controller(HoverController)
-                                        .on_click(move |ctx, data: &mut Vector<Heading>, _| {
+                                        .on_activate(move |ctx, data: &mut Vector<Heading>, _| {
                                           data.swap(idx - 1, idx);
                                           ctx.submit_command_global(
                                             Header::SWAP_HEADINGS.with((idx - 1, idx)),
@@ -162,7 +654,7 @@ impl Settings {
                                         }),
                                     )
                                     .with_flex_child(
-                                      Label::wrapped(<&str>::from(heading))
+                                      Label::wrapped(heading.label())
                                         .with_text_alignment(druid::TextAlignment::Center)
                                         .expand_width(),
                                       1.,
@@ -190,7 +682,7 @@ impl Settings {
                                       Icon::new(ARROW_RIGHT)
                                         .background(button_painter())
                                         .controller(HoverController)
-                                        .on_click(move |ctx, data: &mut Vector<Heading>, _| {
+                                        .on_activate(move |ctx, data: &mut Vector<Heading>, _| {
                                           data.swap(idx, idx + 1);
                                           ctx.submit_command_global(
                                             Header::SWAP_HEADINGS.with((idx, idx + 1)),
@@ -231,14 +723,14 @@ impl Settings {
                       Flex::row()
                         .with_flex_spacer(1.)
                         .with_flex_child(
-                          Button::new("Add new column")
+                          Button::new(tr("settings-add-column"))
                             .controller(Click::new(|ctx, mouse_event, data: &mut Settings, _| {
                               let mut menu: Menu<super::App> = Menu::empty();
                               for heading in Heading::iter().filter(|heading| {
                                 !matches!(heading, Heading::Score | Heading::Enabled)
                                   && !data.headings.contains(heading)
                               }) {
-                                menu = menu.entry(MenuItem::new(<&str>::from(heading)).on_activate(
+                                menu = menu.entry(MenuItem::new(heading.label()).on_activate(
                                   move |ctx, data: &mut App, _| {
                                     data.settings.headings.push_back(heading);
                                     ctx.submit_command(
@@ -275,6 +767,51 @@ impl Settings {
             )
             .padding(TRAILING_PADDING),
           )
+          .with_child(
+            make_flex_settings_row(
+              SizedBox::empty(),
+              Button::from_label(Label::wrapped(tr("settings-view-logs"))).on_click(
+                |ctx, data: &mut Settings, env| {
+                  let log = logging::tail(500).unwrap_or_else(|| tr("settings-logs-empty"));
+
+                  let balancers = util::balancer_report();
+
+                  Modal::<Settings>::new(&tr("settings-logs-title"))
+                    .pipe(|modal| {
+                      if balancers.is_empty() {
+                        modal
+                      } else {
+                        modal.with_content(tr_args(
+                          "settings-logs-balancers",
+                          &[("balancers", &balancers)],
+                        ))
+                      }
+                    })
+                    .with_content(log)
+                    .with_button(
+                      &tr("settings-open-log-folder"),
+                      |_: &mut EventCtx, _: &mut Settings| {
+                        let _ = opener::open(logging::log_dir());
+                      },
+                    )
+                    .with_close()
+                    .show_with_size(ctx, env, data, (900., 600.));
+                },
+              ),
+            )
+            .padding(TRAILING_PADDING),
+          )
+          .with_child(
+            make_flex_settings_row(
+              Label::wrapped(tr("settings-report-problem-hint")),
+              Button::from_label(Label::wrapped(tr("settings-report-problem"))).on_click(
+                |ctx, _, _| {
+                  ctx.submit_command(Settings::SELECTOR.with(SettingsCommand::ReportProblem))
+                },
+              ),
+            )
+            .padding(TRAILING_PADDING),
+          )
           .with_child(
             ViewSwitcher::new(
               |data: &Settings, _| data.show_column_editor,
@@ -293,7 +830,7 @@ impl Settings {
                                   Icon::new(ARROW_LEFT)
                                     .background(button_painter())
                                     .controller(HoverController)
-                                    .on_click(move |ctx, data: &mut Vector<Heading>, _| {
+                                    .on_activate(move |ctx, data: &mut Vector<Heading>, _| {
                                       data.swap(idx - 1, idx);
                                       ctx.submit_command_global(
                                         Header::SWAP_HEADINGS.with((idx - 1, idx)),
@@ -308,7 +845,7 @@ impl Settings {
                                     }),
                                 )
                                 .with_flex_child(
-                                  Label::wrapped(<&str>::from(*heading))
+                                  Label::wrapped(heading.label())
                                     .with_text_alignment(druid::TextAlignment::Center)
                                     .expand_width(),
                                   1.,
@@ -317,7 +854,7 @@ impl Settings {
                                   Icon::new(ARROW_RIGHT)
                                     .background(button_painter())
                                     .controller(HoverController)
-                                    .on_click(move |ctx, data: &mut Vector<Heading>, _| {
+                                    .on_activate(move |ctx, data: &mut Vector<Heading>, _| {
                                       data.swap(idx, idx + 1);
                                       ctx.submit_command_global(
                                         Header::SWAP_HEADINGS.with((idx, idx + 1)),
@@ -363,7 +900,7 @@ impl Settings {
           .with_child(
             make_flex_settings_row(
               Checkbox::new("").lens(Settings::vmparams_enabled),
-              Label::wrapped("Enable vmparams editing"),
+              Label::wrapped(tr("settings-vmparams")),
             )
             .on_change(|_, _old, data, _| {
               if data.vmparams_enabled && data.vmparams.is_none() {
@@ -385,7 +922,9 @@ impl Settings {
                   .with_child(
                     Flex::row()
                       .with_flex_child(
-                        Label::new("Minimum RAM:").align_right().expand_width(),
+                        Label::new(tr("settings-min-ram"))
+                          .align_right()
+                          .expand_width(),
                         3.25,
                       )
                       .with_spacer(5.)
@@ -408,7 +947,9 @@ impl Settings {
                   .with_child(
                     Flex::row()
                       .with_flex_child(
-                        Label::new("Maximum RAM:").align_right().expand_width(),
+                        Label::new(tr("settings-max-ram"))
+                          .align_right()
+                          .expand_width(),
                         3.25,
                       )
                       .with_spacer(5.)
@@ -430,18 +971,30 @@ impl Settings {
                   )
               })
               .lens(Settings::vmparams)
-              .on_change(|_, _, data, _| {
+              .on_change(|ctx, _, data, _| {
                 if let Some(install_dir) = data.install_dir.clone()
                   && let Some(vmparams) = data.vmparams.clone()
                   && let Err(err) = vmparams.save(install_dir)
                 {
-                  eprintln!("{:?}", err)
+                  ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::anyhow!(
+                    "Failed to save vmparams: {:?}",
+                    err
+                  ))))
                 }
               }),
               SizedBox::empty(),
             )
             .padding(TRAILING_PADDING),
           )
+          .with_child(
+            make_flex_settings_row(
+              Label::wrapped(tr("game-backup-hint")),
+              Button::new(tr("game-backup-open")).on_activate(|ctx, data: &mut Settings, env| {
+                Settings::game_backups_modal().show_with_size(ctx, env, data, (700., 500.));
+              }),
+            )
+            .padding(TRAILING_PADDING),
+          )
           .with_child(
             make_flex_settings_row(
               Either::new(
@@ -450,11 +1003,20 @@ impl Settings {
                 Icon::new(ARROW_RIGHT),
               )
               .padding((-5., 0., 0., 0.)),
-              Label::new("Open JRE Switcher"),
+              Label::new(tr("settings-jre-switcher")),
             )
             .controller(HoverController)
             .on_click(|_, data, _| *data = !*data)
             .lens(Settings::show_jre_swapper)
+            .on_change(|ctx, old, data, _| {
+              if !old.show_jre_swapper && data.show_jre_swapper {
+                data.refresh_installed_jre();
+                if data.mikohime_kits.is_none() {
+                  tokio::runtime::Handle::current()
+                    .spawn(mikohime::find_kits(ctx.get_external_handle()));
+                }
+              }
+            })
             .padding(TRAILING_PADDING.tap_mut(|padding| padding.2 = -5.)),
           )
           .with_child(
@@ -463,6 +1025,42 @@ impl Settings {
               make_flex_settings_row(
                 SizedBox::empty(),
                 Flex::column()
+                  .with_child(
+                    Flex::row()
+                      .with_flex_child(
+                        Label::wrapped_func(|data: &Settings, _| {
+                          data.installed_jre.as_ref().map_or_else(
+                            || tr("settings-current-jre-unknown"),
+                            |installed| {
+                              tr_args("settings-current-jre", &[("jre", &installed.describe())])
+                            },
+                          )
+                        }),
+                        1.,
+                      )
+                      .with_child(Either::new(
+                        |data: &Settings, _| {
+                          data
+                            .installed_jre
+                            .as_ref()
+                            .is_some_and(InstalledJre::adoptable)
+                        },
+                        Button2::new(Label::new(tr("jre-manage")).padding((10., 0.))).on_activate(
+                          |ctx, data: &mut Settings, _| {
+                            if let Some(install_dir) = &data.install_dir
+                              && let Some(installed) = &data.installed_jre
+                            {
+                              match installed.adopt(install_dir) {
+                                Ok(adopted) => data.installed_jre = Some(adopted),
+                                Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Error(err))),
+                              }
+                            }
+                          },
+                        ),
+                        SizedBox::empty(),
+                      ))
+                      .padding(TRAILING_PADDING),
+                  )
                   .with_child(
                     Flex::row()
                       .with_flex_child(
@@ -476,23 +1074,18 @@ impl Settings {
                               druid::theme::TEXT_COLOR,
                             ))
                             .with_child(bold_text(
-                              "(RECOMMENDED)",
+                              &tr("settings-jre-recommended"),
                               theme::TEXT_SIZE_NORMAL,
                               druid::FontWeight::MEDIUM,
                               druid::Color::GREEN,
                             ))
                             .with_spacer(5.)
                             .with_child(
-                              Button2::new(Label::new("Install").padding((10., 0.))).on_click(
-                                |ctx, data: &mut Settings, _| {
-                                  data.jre_swap_in_progress = true;
-                                  tokio::runtime::Handle::current().spawn(Flavour::Wisp.swap(
-                                    ctx.get_external_handle(),
-                                    data.install_dir.as_ref().unwrap().clone(),
-                                    data.jre_managed_mode
-                                  ));
-                                },
-                              ),
+                              Button2::new(Label::new(tr("jre-install")).padding((10., 0.)))
+                                .on_activate(|ctx, data: &mut Settings, _| {
+                                  data
+                                    .start_jre_swap(ctx.get_external_handle(), Some(Flavour::Wisp))
+                                }),
                             )
                             .main_axis_alignment(druid::widget::MainAxisAlignment::Center),
                         )
@@ -510,23 +1103,20 @@ impl Settings {
                               druid::theme::TEXT_COLOR,
                             ))
                             .with_child(bold_text(
-                              "(UNSUPPORTED)",
+                              &tr("settings-jre-unsupported"),
                               theme::TEXT_SIZE_NORMAL,
                               druid::FontWeight::MEDIUM,
                               druid::Color::MAROON,
                             ))
                             .with_spacer(5.)
                             .with_child(
-                              Button2::new(Label::new("Install").padding((10., 0.))).on_click(
-                                |ctx, data: &mut Settings, _| {
-                                  data.jre_swap_in_progress = true;
-                                  tokio::runtime::Handle::current().spawn(Flavour::Coretto.swap(
+                              Button2::new(Label::new(tr("jre-install")).padding((10., 0.)))
+                                .on_activate(|ctx, data: &mut Settings, _| {
+                                  data.start_jre_swap(
                                     ctx.get_external_handle(),
-                                    data.install_dir.as_ref().unwrap().clone(),
-                                    data.jre_managed_mode
-                                  ));
-                                },
-                              ),
+                                    Some(Flavour::Coretto),
+                                  )
+                                }),
                             )
                             .main_axis_alignment(druid::widget::MainAxisAlignment::Center),
                         )
@@ -544,23 +1134,20 @@ impl Settings {
                               druid::theme::TEXT_COLOR,
                             ))
                             .with_child(bold_text(
-                              "(UNSUPPORTED)",
+                              &tr("settings-jre-unsupported"),
                               theme::TEXT_SIZE_NORMAL,
                               druid::FontWeight::MEDIUM,
                               druid::Color::MAROON,
                             ))
                             .with_spacer(5.)
                             .with_child(
-                              Button2::new(Label::new("Install").padding((10., 0.))).on_click(
-                                |ctx, data: &mut Settings, _| {
-                                  data.jre_swap_in_progress = true;
-                                  tokio::runtime::Handle::current().spawn(Flavour::Hotspot.swap(
+                              Button2::new(Label::new(tr("jre-install")).padding((10., 0.)))
+                                .on_activate(|ctx, data: &mut Settings, _| {
+                                  data.start_jre_swap(
                                     ctx.get_external_handle(),
-                                    data.install_dir.as_ref().unwrap().clone(),
-                                    data.jre_managed_mode
-                                  ));
-                                },
-                              ),
+                                    Some(Flavour::Hotspot),
+                                  )
+                                }),
                             )
                             .main_axis_alignment(druid::widget::MainAxisAlignment::Center),
                         )
@@ -578,63 +1165,93 @@ impl Settings {
                               druid::theme::TEXT_COLOR,
                             ))
                             .with_child(bold_text(
-                              "(RESEARCH)",
+                              &tr("settings-jre-research"),
                               theme::TEXT_SIZE_NORMAL,
                               druid::FontWeight::MEDIUM,
                               druid::Color::rgb8(236, 188, 0),
                             ))
                             .with_spacer(5.)
                             .with_child(
-                              Button2::new(Label::new("Install").padding((10., 0.))).on_click(
-                                |ctx, data: &mut Settings, _| {
-                                  data.jre_swap_in_progress = true;
+                              Button2::new(Label::new(tr("jre-install")).padding((10., 0.)))
+                                .on_activate(|ctx, data: &mut Settings, _| {
                                   if let Some(vmparams) = data.vmparams.as_mut() {
                                     vmparams.verify_none = true;
-                                    let _ = vmparams.save(data.install_dir.as_ref().unwrap().clone());
+                                    if let Err(err) =
+                                      vmparams.save(data.install_dir.as_ref().unwrap().clone())
+                                    {
+                                      ctx.submit_command(Popup::SHOW.with(Popup::Error(
+                                        anyhow::anyhow!("Failed to save vmparams: {:?}", err),
+                                      )))
+                                    }
                                   }
-                                  tokio::runtime::Handle::current().spawn(Flavour::Azul.swap(
-                                    ctx.get_external_handle(),
-                                    data.install_dir.as_ref().unwrap().clone(),
-                                    data.jre_managed_mode
-                                  ));
-                                },
-                              ),
+                                  data
+                                    .start_jre_swap(ctx.get_external_handle(), Some(Flavour::Azul))
+                                }),
                             )
                             .main_axis_alignment(druid::widget::MainAxisAlignment::Center),
                         )
                         .expand_width(),
                         1.,
                       )
-                      .expand_width(),
+                      .expand_width()
+                      .disabled_if(|data: &Settings, _| data.jre_swap_in_progress),
                   )
+                  .with_child(Settings::temurin_builder())
                   .with_child(
-                    Button2::new(Label::new("Revert to Vanilla/Stock JRE 7").padding((10., 0.)))
-                      .on_click(|ctx, data: &mut Settings, _| {
-                        data.jre_swap_in_progress = true;
-                        tokio::runtime::Handle::current().spawn(revert(
-                          ctx.get_external_handle(),
-                          data.install_dir.as_ref().unwrap().clone(),
-                        ));
+                    Button2::new(Label::new(tr("jre-revert-stock")).padding((10., 0.)))
+                      .on_activate(|ctx, data: &mut Settings, _| {
+                        data.start_jre_swap(ctx.get_external_handle(), None)
                       })
+                      .disabled_if(|data: &Settings, _| data.jre_swap_in_progress)
                       .align_left()
                       .padding(TRAILING_PADDING)
                       .expand_width(),
                   )
+                  .with_child(Either::new(
+                    |data: &Settings, _| data.jre_swap_in_progress,
+                    Flex::column()
+                      .with_child(Label::wrapped_func(|data: &Option<Phase>, _| {
+                        data
+                          .as_ref()
+                          .map_or_else(|| tr("settings-jre-swap-starting"), Phase::describe)
+                      }))
+                      .with_child(
+                        Maybe::or_empty(|| {
+                          ProgressBar::new()
+                            .lens(lens::Map::new(
+                              |phase: &Phase| phase.fraction().unwrap_or_default(),
+                              |_, _| {},
+                            ))
+                            .expand_width()
+                        })
+                        .lens(lens::Map::new(
+                          |data: &Option<Phase>| {
+                            data.clone().filter(|phase| phase.fraction().is_some())
+                          },
+                          |_, _| {},
+                        )),
+                      )
+                      .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+                      .lens(Settings::jre_swap_phase)
+                      .padding(TRAILING_PADDING),
+                    SizedBox::empty(),
+                  ))
                   .with_child(make_flex_settings_row(
                     Checkbox::new("").lens(Settings::jre_managed_mode),
-                    Label::wrapped("Enable 'Managed' mode.")
+                    Label::wrapped(tr("settings-jre-managed-mode")),
                   ))
                   .with_child(make_flex_settings_row(
                     SizedBox::empty(),
-                    Label::wrapped("\
-                      'Managed' mode stores JRE updates in a MOSS managed data folder, \
-                      keeping your Starsector install folder clutter free.\n\
-                      Unfortunately, if you're on Windows, MOSS must be run with administrator privileges for this mode to work.\
-                    ")
+                    Label::wrapped(tr("settings-jre-managed-mode-hint")),
                   ))
                   .disabled_if(|data: &Settings, _| data.install_dir.is_none())
+                  .on_command(jre::PROGRESS, |_, phase, data| {
+                    data.jre_swap_phase = Some(phase.clone())
+                  })
                   .on_command(jre::SWAP_COMPLETE, |_, _, data| {
-                    data.jre_swap_in_progress = false
+                    data.jre_swap_in_progress = false;
+                    data.jre_swap_phase = None;
+                    data.refresh_installed_jre();
                   })
                   .expand_width(),
               ),
@@ -642,10 +1259,11 @@ impl Settings {
             )
             .padding(TRAILING_PADDING),
           )
+          .with_child(Settings::graphicslib_builder())
           .with_child(
             make_flex_settings_row(
               Checkbox::new("").lens(Settings::experimental_launch),
-              Label::wrapped("Enable experimental direct launch"),
+              Label::wrapped(tr("settings-experimental-launch")),
             )
             .padding(TRAILING_PADDING),
           )
@@ -661,7 +1279,7 @@ impl Settings {
                       .with_child(
                         Flex::row()
                           .with_flex_child(
-                            Label::new("Horizontal Resolution:")
+                            Label::new(tr("settings-horizontal-resolution"))
                               .align_right()
                               .expand_width(),
                             3.25,
@@ -679,7 +1297,7 @@ impl Settings {
                       .with_child(
                         Flex::row()
                           .with_flex_child(
-                            Label::new("Vertical Resolution:")
+                            Label::new(tr("settings-vertical-resolution"))
                               .align_right()
                               .expand_width(),
                             3.25,
@@ -703,14 +1321,30 @@ impl Settings {
           )
           .padding((10., 10.))
           .expand()
-          .on_change(|_, _old, data, _| {
+          .on_change(|ctx, old, data, _| {
+            if old.nexus_api_key != data.nexus_api_key {
+              data.sync_nexus_api_key()
+            }
+            if old.update_check_interval_hours != data.update_check_interval_hours {
+              update_check::set_interval(data.update_check_interval_hours)
+            }
+            if old.network != data.network {
+              net::configure(&data.network)
+            }
+            if old.update_channel != data.update_channel {
+              ctx.submit_command(App::CHECK_SELF_UPDATE)
+            }
+            if old.use_mod_library != data.use_mod_library {
+              library::set(data.mod_library());
+              ctx.submit_command(App::REFRESH)
+            }
             if let Err(err) = data.save() {
-              eprintln!("{:?}", err)
+              error!("{:?}", err)
             }
           })
           .on_command(Header::ADD_HEADING, |_, _heading, settings| {
             if let Err(err) = settings.save() {
-              eprintln!("{:?}", err)
+              error!("{:?}", err)
             }
           })
           .boxed(),
@@ -719,6 +1353,611 @@ impl Settings {
       .build()
   }
 
+  /// Lists the snapshots of game files, newest first, each with a button to put it back.
+  fn game_backups_modal<'a>() -> Modal<'a, Self> {
+    let snapshots = Snapshot::list();
+    let mut list = Flex::column();
+    for snapshot in snapshots.iter().cloned() {
+      let files = snapshot
+        .files
+        .iter()
+        .map(|file| file.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\n");
+      list.add_child(
+        Card::new(
+          Flex::row()
+            .with_flex_child(
+              Flex::column()
+                .with_child(bold_text(
+                  &format!("{} - {}", snapshot.taken.format("%F %R"), snapshot.reason),
+                  theme::TEXT_SIZE_NORMAL,
+                  druid::FontWeight::SEMI_BOLD,
+                  druid::theme::TEXT_COLOR,
+                ))
+                .with_child(Label::wrapped(files))
+                .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+                .expand_width(),
+              1.,
+            )
+            .with_child(
+              Button2::new(Label::new(tr("game-backup-restore")).padding((10., 0.))).on_activate(
+                move |ctx, data: &mut Settings, _| match snapshot.restore() {
+                  Ok(()) => {
+                    if data.vmparams.is_some() {
+                      data.vmparams = data
+                        .install_dir
+                        .clone()
+                        .ok_or(LoadError::NoSuchFile)
+                        .and_then(vmparams::VMParams::load)
+                        .ok()
+                    }
+                    ctx.submit_command(App::LOG_MESSAGE.with(tr_args(
+                      "game-backup-restored",
+                      &[("taken", &snapshot.taken.format("%F %R"))],
+                    )));
+                    ctx.submit_command(druid::commands::CLOSE_WINDOW)
+                  }
+                  Err(err) => {
+                    ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::anyhow!(tr_args(
+                      "game-backup-restore-failed",
+                      &[("err", &format!("{:?}", err))]
+                    )))))
+                  }
+                },
+              ),
+            ),
+        )
+        .expand_width(),
+      );
+    }
+
+    Modal::new(&tr("game-backup-title"))
+      .pipe(|modal| {
+        if snapshots.is_empty() {
+          modal.with_content(tr("game-backup-none"))
+        } else {
+          modal
+            .with_content(tr("game-backup-restore-hint"))
+            .with_content(list.scroll().vertical().boxed())
+        }
+      })
+      .with_button(
+        &tr("game-backup-open-folder"),
+        |_: &mut EventCtx, _: &mut Settings| {
+          let _ = opener::open(Snapshot::backup_dir());
+        },
+      )
+      .with_close()
+  }
+
+  /// The JREs that need the Mikohime kit, offered only once the kit's manifest lists a release
+  /// for them, as the swap can't be finished without one.
+  fn temurin_builder() -> impl Widget<Self> {
+    ViewSwitcher::new(
+      |data: &Settings, _| data.mikohime_kits.clone(),
+      |kits, _, _| {
+        let Some(kits) = kits.clone().filter(|kits| !kits.is_empty()) else {
+          return SizedBox::empty().boxed();
+        };
+
+        let mut row = Flex::row();
+        for flavour in [Flavour::Temurin17, Flavour::Temurin21] {
+          if !kits.contains(&flavour.java_version()) {
+            continue;
+          }
+          row.add_flex_child(
+            Card::new(
+              Flex::column()
+                .with_child(h2("Eclipse Temurin"))
+                .with_child(bold_text(
+                  &tr_args(
+                    "settings-jre-mikohime",
+                    &[("version", &flavour.java_version())],
+                  ),
+                  theme::TEXT_SIZE_NORMAL,
+                  druid::FontWeight::SEMI_BOLD,
+                  druid::theme::TEXT_COLOR,
+                ))
+                .with_child(bold_text(
+                  &tr("settings-jre-experimental"),
+                  theme::TEXT_SIZE_NORMAL,
+                  druid::FontWeight::MEDIUM,
+                  druid::Color::rgb8(236, 188, 0),
+                ))
+                .with_spacer(5.)
+                .with_child(
+                  Button2::new(Label::new(tr("jre-install")).padding((10., 0.))).on_activate(
+                    move |ctx, data: &mut Settings, _| {
+                      data.start_jre_swap(ctx.get_external_handle(), Some(flavour))
+                    },
+                  ),
+                )
+                .main_axis_alignment(druid::widget::MainAxisAlignment::Center),
+            )
+            .expand_width(),
+            1.,
+          )
+        }
+
+        Flex::column()
+          .with_child(
+            row
+              .expand_width()
+              .disabled_if(|data: &Settings, _| data.jre_swap_in_progress),
+          )
+          .with_child(Label::wrapped(tr("settings-jre-mikohime-hint")))
+          .boxed()
+      },
+    )
+    .on_command(mikohime::KITS, |_, kits, data| {
+      data.mikohime_kits = Some(kits.clone())
+    })
+  }
+
+  /// Presets for GraphicsLib's options, collapsed like the JRE switcher.
+  fn graphicslib_builder() -> impl Widget<Self> {
+    fn preset_card(preset: Preset) -> impl Widget<GraphicsOptions> {
+      Card::new(
+        Flex::column()
+          .with_child(h2(&preset.name()))
+          .with_child(bold_text(
+            &preset.description(),
+            theme::TEXT_SIZE_NORMAL,
+            druid::FontWeight::SEMI_BOLD,
+            druid::theme::TEXT_COLOR,
+          ))
+          .with_child(Either::new(
+            move |data: &GraphicsOptions, _| data.preset() == Some(preset),
+            bold_text(
+              &tr("graphicslib-current"),
+              theme::TEXT_SIZE_NORMAL,
+              druid::FontWeight::MEDIUM,
+              druid::Color::GREEN,
+            ),
+            SizedBox::empty(),
+          ))
+          .with_spacer(5.)
+          .with_child(
+            Button2::new(Label::new(tr("graphicslib-apply")).padding((10., 0.)))
+              .on_activate(move |_, data: &mut GraphicsOptions, _| data.apply(preset)),
+          )
+          .main_axis_alignment(druid::widget::MainAxisAlignment::Center),
+      )
+      .expand_width()
+    }
+
+    Flex::column()
+      .with_child(
+        make_flex_settings_row(
+          Either::new(
+            |data, _| *data,
+            Icon::new(ARROW_DROP_DOWN),
+            Icon::new(ARROW_RIGHT),
+          )
+          .padding((-5., 0., 0., 0.))
+          .lens(Settings::show_graphicslib),
+          Label::new(tr("graphicslib-open")),
+        )
+        .controller(HoverController)
+        .on_click(|_, data: &mut Settings, _| {
+          data.show_graphicslib = !data.show_graphicslib;
+          if data.show_graphicslib
+            && data.graphics_options.is_none()
+            && let Some(install_dir) = &data.install_dir
+          {
+            let mods_dir = util::mods_dir(install_dir);
+            data.graphics_options = GraphicsOptions::find(
+              std::iter::once(mods_dir.as_path())
+                .chain(data.external_mod_dirs.iter().map(PathBuf::as_path)),
+            )
+            .and_then(|path| GraphicsOptions::load(path).ok())
+          }
+        })
+        .padding(TRAILING_PADDING.tap_mut(|padding| padding.2 = -5.)),
+      )
+      .with_child(
+        Either::new(
+          |data: &Settings, _| data.show_graphicslib,
+          make_flex_settings_row(
+            SizedBox::empty(),
+            Maybe::new(
+              || {
+                let mut presets = Flex::row();
+                for preset in Preset::ALL {
+                  presets.add_flex_child(preset_card(preset), 1.)
+                }
+
+                Flex::column()
+                  .with_child(presets)
+                  .with_child(
+                    List::new(|| {
+                      make_flex_settings_row(
+                        Checkbox::new("").lens(Toggle::enabled),
+                        Label::wrapped_func(|data: &Toggle, _| tr(data.label)),
+                      )
+                    })
+                    .lens(GraphicsOptions::toggles),
+                  )
+                  .with_child(
+                    Button2::new(Label::new(tr("graphicslib-restore")).padding((10., 0.)))
+                      .on_activate(|ctx, data: &mut GraphicsOptions, _| {
+                        match data.restore_backup() {
+                          Ok(restored) => *data = restored,
+                          Err(err) => ctx.submit_command(Popup::SHOW.with(Popup::Error(
+                            anyhow::anyhow!(tr_args(
+                              "graphicslib-restore-failed",
+                              &[("err", &format!("{:?}", err))],
+                            )),
+                          ))),
+                        }
+                      })
+                      .disabled_if(|data: &GraphicsOptions, _| !data.has_backup())
+                      .align_left()
+                      .padding(TRAILING_PADDING)
+                      .expand_width(),
+                  )
+                  .with_child(Label::wrapped(tr("graphicslib-hint")))
+                  .on_change(|ctx, _, data, _| {
+                    if let Err(err) = Snapshot::take_once(
+                      &[data.path.clone()],
+                      &tr("game-backup-before-graphicslib"),
+                    ) {
+                      error!("Failed to back up GraphicsLib's options: {}", err)
+                    }
+                    if let Err(err) = data.save() {
+                      ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::anyhow!(tr_args(
+                        "graphicslib-save-failed",
+                        &[("err", &format!("{:?}", err))]
+                      )))))
+                    }
+                  })
+                  .expand_width()
+              },
+              || Label::wrapped(tr("graphicslib-not-found")),
+            )
+            .lens(Settings::graphics_options),
+          ),
+          SizedBox::empty(),
+        )
+        .padding(TRAILING_PADDING),
+      )
+  }
+
+  fn network_builder() -> impl Widget<NetworkSettings> {
+    fn text_row(
+      label: &str,
+      tooltip: &str,
+      placeholder: &str,
+      lens: impl Lens<NetworkSettings, String> + 'static,
+    ) -> impl Widget<NetworkSettings> {
+      make_flex_pair(
+        Label::wrapped(tr(label))
+          .stack_tooltip(tr(tooltip))
+          .with_crosshair(true),
+        1.,
+        TextBox::new()
+          .with_placeholder(placeholder.to_string())
+          .lens(lens)
+          .expand_width(),
+        1.5,
+        Axis::Horizontal,
+      )
+      .padding(TRAILING_PADDING)
+    }
+    fn timeout_row(
+      label: &str,
+      lens: impl Lens<NetworkSettings, u32> + 'static,
+    ) -> impl Widget<NetworkSettings> {
+      make_flex_pair(
+        Label::wrapped(tr(label)),
+        1.,
+        TextBox::new()
+          .with_formatter(ParseFormatter::new())
+          .update_data_while_editing(true)
+          .lens(lens)
+          .expand_width(),
+        1.5,
+        Axis::Horizontal,
+      )
+      .padding(TRAILING_PADDING)
+    }
+
+    Flex::column()
+      .with_child(text_row(
+        "settings-proxy",
+        "settings-proxy-tooltip",
+        "socks5://127.0.0.1:1080",
+        NetworkSettings::proxy,
+      ))
+      .with_child(text_row(
+        "settings-ca-certificate",
+        "settings-ca-certificate-tooltip",
+        "",
+        NetworkSettings::ca_certificate,
+      ))
+      .with_child(timeout_row(
+        "settings-connect-timeout",
+        NetworkSettings::connect_timeout_secs,
+      ))
+      .with_child(timeout_row(
+        "settings-request-timeout",
+        NetworkSettings::request_timeout_secs,
+      ))
+      .with_child(
+        Label::wrapped_func(|data: &NetworkSettings, _| data.problems().unwrap_or_default())
+          .with_text_color(RED_KEY)
+          .padding(TRAILING_PADDING),
+      )
+  }
+
+  fn token_editor_builder() -> impl Widget<Self> {
+    Flex::column()
+      .with_child(make_flex_pair(
+        Label::wrapped(tr("settings-access-tokens"))
+          .stack_tooltip(tr("settings-access-tokens-tooltip"))
+          .with_crosshair(true),
+        1.,
+        Flex::row()
+          .with_flex_child(
+            TextBox::new()
+              .with_placeholder("github.com")
+              .lens(Settings::token_domain_buf)
+              .expand_width(),
+            1.,
+          )
+          .with_default_spacer()
+          .with_flex_child(
+            TextBox::protected()
+              .with_placeholder(tr("settings-access-tokens-token"))
+              .lens(Settings::token_buf)
+              .expand_width(),
+            1.,
+          )
+          .with_default_spacer()
+          .with_child(
+            Button::new(tr("settings-add")).on_activate(|ctx, data: &mut Settings, _| {
+              ctx.submit_command_global(Settings::SELECTOR.with(SettingsCommand::AddToken(
+                data.token_domain_buf.clone(),
+                data.token_buf.clone(),
+              )));
+              data.token_domain_buf.clear();
+              data.token_buf.clear();
+            }),
+          )
+          .disabled_if(|data: &Settings, _| {
+            data.token_domain_buf.trim().is_empty() || data.token_buf.trim().is_empty()
+          }),
+        1.5,
+        Axis::Horizontal,
+      ))
+      .with_child(make_flex_pair(
+        SizedBox::empty(),
+        1.,
+        ViewSwitcher::new(
+          |domains: &Vector<String>, _| domains.clone(),
+          |_, domains, _| {
+            Flex::column()
+              .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+              .tap_mut(|column| {
+                for domain in domains.iter().cloned() {
+                  column.add_child(
+                    Flex::row()
+                      .with_flex_child(Label::wrapped(domain.clone()).expand_width(), 1.)
+                      .with_child(Icon::new(CLOSE).controller(HoverController).on_click(
+                        move |ctx, _, _| {
+                          ctx.submit_command_global(
+                            Settings::SELECTOR.with(SettingsCommand::RemoveToken(domain.clone())),
+                          )
+                        },
+                      )),
+                  )
+                }
+              })
+              .boxed()
+          },
+        )
+        .lens(Settings::token_domains),
+        1.5,
+        Axis::Horizontal,
+      ))
+  }
+
+  fn external_dirs_builder() -> impl Widget<Self> {
+    Flex::column()
+      .with_child(make_flex_pair(
+        Label::wrapped(tr("settings-external-dirs"))
+          .stack_tooltip(tr("settings-external-dirs-tooltip"))
+          .with_crosshair(true),
+        1.,
+        Flex::row()
+          .with_flex_child(
+            TextBox::new()
+              .with_placeholder(tr("settings-external-dirs-placeholder"))
+              .lens(Settings::external_dir_buf)
+              .expand_width(),
+            1.,
+          )
+          .with_default_spacer()
+          .with_child(
+            Button::new(tr("settings-add"))
+              .on_activate(|ctx, data: &mut Settings, _| {
+                ctx.submit_command_global(Settings::SELECTOR.with(
+                  SettingsCommand::AddExternalModDir(PathBuf::from(data.external_dir_buf.trim())),
+                ));
+                data.external_dir_buf.clear();
+              })
+              .disabled_if(|data: &Settings, _| !Path::new(data.external_dir_buf.trim()).is_dir()),
+          ),
+        1.5,
+        Axis::Horizontal,
+      ))
+      .with_child(make_flex_pair(
+        SizedBox::empty(),
+        1.,
+        ViewSwitcher::new(
+          |dirs: &Vector<PathBuf>, _| dirs.clone(),
+          |_, dirs, _| {
+            Flex::column()
+              .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+              .tap_mut(|column| {
+                for dir in dirs.iter().cloned() {
+                  column.add_child(
+                    Flex::row()
+                      .with_flex_child(
+                        Label::wrapped(dir.to_string_lossy().to_string()).expand_width(),
+                        1.,
+                      )
+                      .with_child(Icon::new(CLOSE).controller(HoverController).on_click(
+                        move |ctx, _, _| {
+                          ctx.submit_command_global(
+                            Settings::SELECTOR
+                              .with(SettingsCommand::RemoveExternalModDir(dir.clone())),
+                          )
+                        },
+                      )),
+                  )
+                }
+              })
+              .boxed()
+          },
+        )
+        .lens(Settings::external_mod_dirs),
+        1.5,
+        Axis::Horizontal,
+      ))
+  }
+
+  fn mod_library_builder() -> impl Widget<Self> {
+    Flex::column()
+      .with_child(make_flex_settings_row(
+        Checkbox::new("").lens(Settings::use_mod_library),
+        Label::wrapped(tr("settings-mod-library"))
+          .stack_tooltip(tr_args(
+            "settings-mod-library-tooltip",
+            &[("dir", &library::default_dir().to_string_lossy())],
+          ))
+          .with_crosshair(true),
+      ))
+      .with_child(
+        Button::new(tr("settings-mod-library-move"))
+          .on_activate(|ctx, _, _| {
+            ctx.submit_command_global(Settings::SELECTOR.with(SettingsCommand::MoveModsToLibrary))
+          })
+          .disabled_if(|data: &Settings, _| !data.use_mod_library || data.install_dir.is_none())
+          .align_right()
+          .expand_width(),
+      )
+  }
+
+  fn cache_dir_browser_builder() -> impl Widget<Self> {
+    make_flex_pair(
+      Label::wrapped(tr("settings-cache-dir"))
+        .stack_tooltip(tr("settings-cache-dir-tooltip"))
+        .with_crosshair(true),
+      1.,
+      Flex::row()
+        .with_flex_child(
+          Label::wrapped_func(|data: &Settings, _| data.cache_dir().to_string_lossy().to_string())
+            .expand_width(),
+          1.,
+        )
+        .with_child(
+          Button::new(tr("settings-browse"))
+            .controller(HoverController)
+            .on_activate(|ctx, _, _| {
+              ctx.submit_command_global(Settings::SELECTOR.with(SettingsCommand::SelectCacheDir))
+            }),
+        )
+        .with_child(
+          Button::new(tr("settings-reset"))
+            .controller(HoverController)
+            .on_activate(|ctx, _, _| {
+              ctx.submit_command_global(
+                Settings::SELECTOR.with(SettingsCommand::UpdateCacheDir(None)),
+              )
+            })
+            .disabled_if(|data: &Settings, _| data.cache_dir.is_none()),
+        ),
+      1.5,
+      Axis::Horizontal,
+    )
+  }
+
+  fn download_dir_browser_builder() -> impl Widget<Self> {
+    make_flex_pair(
+      Label::wrapped(tr("settings-download-dir"))
+        .stack_tooltip(tr("settings-download-dir-tooltip"))
+        .with_crosshair(true),
+      1.,
+      Flex::row()
+        .with_flex_child(
+          Label::wrapped_func(|data: &Settings, _| {
+            data.download_dir().map_or_else(
+              || tr("settings-download-dir-not-set"),
+              |dir| dir.to_string_lossy().to_string(),
+            )
+          })
+          .expand_width(),
+          1.,
+        )
+        .with_child(
+          Button::new(tr("settings-browse"))
+            .controller(HoverController)
+            .on_activate(|ctx, _, _| {
+              ctx.submit_command_global(Settings::SELECTOR.with(SettingsCommand::SelectDownloadDir))
+            }),
+        )
+        .with_child(
+          Button::new(tr("settings-reset"))
+            .controller(HoverController)
+            .on_activate(|ctx, _, _| {
+              ctx.submit_command_global(
+                Settings::SELECTOR.with(SettingsCommand::UpdateDownloadDir(None)),
+              )
+            })
+            .disabled_if(|data: &Settings, _| data.download_dir.is_none()),
+        ),
+      1.5,
+      Axis::Horizontal,
+    )
+  }
+
+  /// The folder chosen for downloads that aren't installed, or else the user's downloads folder.
+  pub fn download_dir(&self) -> Option<PathBuf> {
+    self.download_dir.clone().or_else(|| {
+      directories::UserDirs::new().and_then(|dirs| dirs.download_dir().map(Path::to_path_buf))
+    })
+  }
+
+  /// The mod library, if mods are installed into one.
+  pub fn mod_library(&self) -> Option<PathBuf> {
+    self.use_mod_library.then(library::default_dir)
+  }
+
+  /// Folders of mods that are linked into the mods folder - the external mod folders and the
+  /// mod library.
+  pub fn linked_mod_dirs(&self) -> Vec<PathBuf> {
+    self
+      .external_mod_dirs
+      .iter()
+      .cloned()
+      .chain(self.mod_library())
+      .collect()
+  }
+
+  /// The directory downloads, extracted archives and the webview cache are written to.
+  ///
+  /// Defaults to the platform cache directory. When the user has chosen a custom
+  /// location a `moss_cache` folder is created inside of it, so that clearing the
+  /// cache on exit never touches anything else the user keeps there.
+  pub fn cache_dir(&self) -> PathBuf {
+    self.cache_dir.as_ref().map_or_else(
+      || PROJECT.cache_dir().to_path_buf(),
+      |dir| dir.join("moss_cache"),
+    )
+  }
+
   pub fn install_dir_browser_builder(axis: Axis) -> Flex<Self> {
     let input = TextBox::multiline()
       .with_line_wrapping(true)
@@ -728,14 +1967,14 @@ impl Settings {
 
     match axis {
       Axis::Horizontal => make_flex_pair(
-        Label::wrapped("Starsector Install Directory:"),
+        Label::wrapped(tr("settings-install-dir")),
         1.,
         Flex::for_axis(axis)
           .with_flex_child(input.expand_width(), 1.)
           .with_child(
-            Button::new("Browse...")
+            Button::new(tr("settings-browse"))
               .controller(HoverController)
-              .on_click(|ctx, _, _| {
+              .on_activate(|ctx, _, _| {
                 ctx.submit_command_global(Selector::new("druid.builtin.textbox-cancel-editing"));
                 ctx
                   .submit_command_global(Settings::SELECTOR.with(SettingsCommand::SelectInstallDir))
@@ -745,13 +1984,13 @@ impl Settings {
         axis,
       ),
       Axis::Vertical => make_column_pair(
-        h2("Starsector Install Directory:"),
+        h2(&tr("settings-install-dir")),
         Flex::for_axis(axis)
           .with_child(input.expand_width())
           .with_child(
-            Button::new("Browse...")
+            Button::new(tr("settings-browse"))
               .controller(HoverController)
-              .on_click(|ctx, _, _| {
+              .on_activate(|ctx, _, _| {
                 ctx.submit_command_global(Selector::new("druid.builtin.textbox-cancel-editing"));
                 ctx
                   .submit_command_global(Settings::SELECTOR.with(SettingsCommand::SelectInstallDir))
@@ -762,6 +2001,64 @@ impl Settings {
     }
   }
 
+  /// Where a picker should open: wherever it was last used, otherwise a sensible default for what
+  /// it's picking.
+  pub fn picker_start(&self, picker: Picker) -> Option<PathBuf> {
+    self
+      .picker_history
+      .get(&picker)
+      .filter(|path| path.exists())
+      .cloned()
+      .or_else(|| match picker {
+        Picker::Archive => {
+          directories::UserDirs::new().and_then(|dirs| dirs.download_dir().map(Path::to_path_buf))
+        }
+        Picker::DownloadDir => self.download_dir(),
+        Picker::Folder => self.install_dir.clone(),
+        Picker::InstallDir | Picker::CacheDir => None,
+      })
+      .or_else(|| self.last_browsed.clone())
+  }
+
+  /// Records where a picker was last used. The folder containing whatever was picked is remembered,
+  /// so the picker reopens with the previous choice and its siblings visible.
+  pub fn record_pick(ext_ctx: &ExtEventSink, picker: Picker, picked: &Path) {
+    let dir = picked.parent().unwrap_or(picked);
+
+    let _ = ext_ctx.submit_command(
+      Settings::SELECTOR,
+      SettingsCommand::UpdatePickerHistory(picker, dir.to_path_buf()),
+      Target::Auto,
+    );
+  }
+
+  /// Checks for the game files we'd expect to find in a Starsector install, so that mods don't
+  /// get extracted into some arbitrary folder the user picked by mistake.
+  pub fn is_starsector_install(path: &Path) -> bool {
+    #[cfg(target_os = "linux")]
+    let core = path.join("starfarer_obf.jar");
+    #[cfg(target_os = "windows")]
+    let core = path.join("starsector-core");
+    #[cfg(target_os = "macos")]
+    let core = path.join("Contents/Resources/Java");
+
+    core.exists()
+  }
+
+  /// Installs are blocked until the install dir is either recognised or the user has confirmed it.
+  pub fn install_dir_usable(&self) -> bool {
+    self.install_dir.as_ref().is_some_and(|install_dir| {
+      self.install_dir_confirmed || Self::is_starsector_install(install_dir)
+    })
+  }
+
+  /// Makes the current Nexus Mods API key available to background version checks.
+  pub fn sync_nexus_api_key(&self) {
+    if let Ok(mut key) = NEXUS_API_KEY.write() {
+      *key = Some(self.nexus_api_key.trim().to_string()).filter(|key| !key.is_empty());
+    }
+  }
+
   pub fn path(try_make: bool) -> PathBuf {
     use std::fs;
 
@@ -808,6 +2105,21 @@ impl Settings {
 pub enum SettingsCommand {
   UpdateInstallDir(PathBuf),
   SelectInstallDir,
+  /// A new cache dir, or `None` to go back to the default one
+  UpdateCacheDir(Option<PathBuf>),
+  SelectCacheDir,
+  /// A new download only folder, or `None` to go back to the system's downloads folder
+  UpdateDownloadDir(Option<PathBuf>),
+  SelectDownloadDir,
+  ConfirmInstallDir,
+  UpdatePickerHistory(Picker, PathBuf),
+  /// Domain, token
+  AddToken(String, String),
+  RemoveToken(String),
+  AddExternalModDir(PathBuf),
+  RemoveExternalModDir(PathBuf),
+  MoveModsToLibrary,
+  ReportProblem,
 }
 
 struct InstallDirDelegate {}
@@ -866,14 +2178,17 @@ impl<W: Widget<VMParams>> Controller<VMParams, W> for UnitController<VMParams, U
                 MenuItem::new(unit.to_string())
                   .on_activate({
                     let lens = self.lens.clone();
-                    move |_, d: &mut super::App, _| {
+                    move |ctx, d: &mut super::App, _| {
                       if let Some(vmparams) = d.settings.vmparams.as_mut() {
                         lens.with_mut(vmparams, |data| *data = unit);
                         let vmparams = vmparams.clone();
                         if let Some(install_dir) = d.settings.install_dir.as_ref()
                           && let Err(err) = vmparams.save(install_dir)
                         {
-                          eprintln!("{:?}", err)
+                          ctx.submit_command(Popup::SHOW.with(Popup::Error(anyhow::anyhow!(
+                            "Failed to save vmparams: {:?}",
+                            err
+                          ))))
                         }
                       }
                     }
@@ -0,0 +1,172 @@
+//! Finding Starsector installs on first run, so the user can pick one instead of hunting for it in
+//! a file picker.
+//!
+//! Looks in the game's default install location, the folders GOG installs to, and every Steam
+//! library listed in Steam's `libraryfolders.vdf`, keeping only those that
+//! [look like an install](super::Settings::is_starsector_install).
+
+use std::path::{Path, PathBuf};
+
+use directories::BaseDirs;
+
+use super::Settings;
+
+/// Installs found in the usual places, in the order they were looked for.
+pub fn find_installs() -> Vec<PathBuf> {
+  let home = BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+
+  let mut found: Vec<PathBuf> = Vec::new();
+  for candidate in candidates(home.as_deref()) {
+    if !found.contains(&candidate) && Settings::is_starsector_install(&candidate) {
+      found.push(candidate)
+    }
+  }
+
+  found
+}
+
+fn candidates(home: Option<&Path>) -> Vec<PathBuf> {
+  let mut candidates = default_dirs(home);
+  for library in steam_dirs(home)
+    .iter()
+    .flat_map(|steam| read_library_folders(steam))
+  {
+    let game = library.join("steamapps/common/Starsector");
+    #[cfg(target_os = "macos")]
+    candidates.push(game.join("Starsector.app"));
+    candidates.push(game);
+  }
+
+  candidates
+}
+
+#[cfg(target_os = "windows")]
+fn default_dirs(_home: Option<&Path>) -> Vec<PathBuf> {
+  IntoIterator::into_iter([
+    r"C:\Program Files (x86)\Fractal Softworks\Starsector",
+    r"C:\Program Files\Fractal Softworks\Starsector",
+    r"C:\GOG Games\Starsector",
+    r"C:\Program Files (x86)\GOG Galaxy\Games\Starsector",
+  ])
+  .map(PathBuf::from)
+  .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn default_dirs(home: Option<&Path>) -> Vec<PathBuf> {
+  let mut dirs = vec![PathBuf::from("/opt/starsector")];
+  if let Some(home) = home {
+    // GOG keeps the game in a folder of its own, next to its installer scripts
+    for dir in [
+      "starsector",
+      "Games/starsector",
+      "GOG Games/Starsector/game",
+    ] {
+      dirs.push(home.join(dir))
+    }
+  }
+
+  dirs
+}
+
+#[cfg(target_os = "macos")]
+fn default_dirs(home: Option<&Path>) -> Vec<PathBuf> {
+  let mut dirs = vec![PathBuf::from("/Applications/Starsector.app")];
+  if let Some(home) = home {
+    dirs.push(home.join("Applications/Starsector.app"))
+  }
+
+  dirs
+}
+
+/// Where Steam itself may be installed, which always has a `libraryfolders.vdf` listing every
+/// library, including its own.
+#[cfg(target_os = "windows")]
+fn steam_dirs(_home: Option<&Path>) -> Vec<PathBuf> {
+  vec![
+    PathBuf::from(r"C:\Program Files (x86)\Steam"),
+    PathBuf::from(r"C:\Program Files\Steam"),
+  ]
+}
+
+#[cfg(target_os = "linux")]
+fn steam_dirs(home: Option<&Path>) -> Vec<PathBuf> {
+  let Some(home) = home else {
+    return Vec::new();
+  };
+
+  IntoIterator::into_iter([
+    ".steam/steam",
+    ".local/share/Steam",
+    // Flatpak
+    ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+  ])
+  .map(|dir| home.join(dir))
+  .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn steam_dirs(home: Option<&Path>) -> Vec<PathBuf> {
+  home
+    .map(|home| home.join("Library/Application Support/Steam"))
+    .into_iter()
+    .collect()
+}
+
+fn read_library_folders(steam: &Path) -> Vec<PathBuf> {
+  std::fs::read_to_string(steam.join("steamapps/libraryfolders.vdf"))
+    .map(|vdf| parse_library_folders(&vdf))
+    .unwrap_or_default()
+}
+
+/// The `"path"` of every library in a `libraryfolders.vdf`. The format is Valve's KeyValues, but
+/// only the path values are needed, and each is on a line of its own.
+fn parse_library_folders(vdf: &str) -> Vec<PathBuf> {
+  vdf
+    .lines()
+    .filter_map(|line| {
+      let mut quoted = line.split('"').skip(1).step_by(2);
+      let (key, value) = (quoted.next()?, quoted.next()?);
+
+      (key == "path").then_some(value)
+    })
+    .map(|path| PathBuf::from(path.replace(r"\\", r"\")))
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use super::parse_library_folders;
+
+  #[test]
+  fn reads_library_paths() {
+    let vdf = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"apps"
+		{
+			"228980"		"435183158"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary"
+	}
+}
+"#;
+
+    assert_eq!(
+      parse_library_folders(vdf),
+      vec![
+        PathBuf::from(r"C:\Program Files (x86)\Steam"),
+        PathBuf::from(r"D:\SteamLibrary")
+      ]
+    );
+  }
+}
@@ -0,0 +1,227 @@
+//! Snapshots of game files taken before MOSS changes them, so a bad edit to vmparams or
+//! GraphicsLib's options, or a JRE swap gone wrong, can be undone from the settings.
+//!
+//! Each snapshot is a folder in MOSS's data folder, named after when it was taken, holding copies
+//! of the files and a manifest of where they came from. A file is only snapshotted before the
+//! first change MOSS makes to it each run, so editing vmparams a keystroke at a time keeps the
+//! file as it was before editing started rather than filling the folder.
+
+use std::{
+  collections::HashSet,
+  io,
+  path::{Path, PathBuf},
+  sync::{LazyLock, Mutex},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use webview_shared::PROJECT;
+
+use crate::app::i18n::tr_args;
+
+/// How many snapshots are kept.
+const LIMIT: usize = 20;
+const MANIFEST: &str = "manifest.json";
+
+/// Files already snapshotted this run.
+static SNAPSHOTTED: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(Default::default);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+  #[serde(skip)]
+  pub dir: PathBuf,
+  pub reason: String,
+  pub taken: DateTime<Local>,
+  /// Where each copied file came from, in the order they were copied.
+  pub files: Vec<PathBuf>,
+}
+
+impl Snapshot {
+  pub fn backup_dir() -> PathBuf {
+    PROJECT.data_dir().join("game_backups")
+  }
+
+  /// Copies whichever of `files` exist into a new snapshot. Returns `None` if there was nothing
+  /// to copy.
+  pub fn take(files: &[PathBuf], reason: &str) -> io::Result<Option<Self>> {
+    let snapshot = Self::take_into(&Self::backup_dir(), files, reason)?;
+    Self::prune(&Self::backup_dir())?;
+
+    Ok(snapshot)
+  }
+
+  fn take_into(backup_dir: &Path, files: &[PathBuf], reason: &str) -> io::Result<Option<Self>> {
+    let files: Vec<PathBuf> = files
+      .iter()
+      .filter(|file| file.is_file())
+      .cloned()
+      .collect();
+    if files.is_empty() {
+      return Ok(None);
+    }
+
+    let taken = Local::now();
+    let name = taken.format("%Y%m%d-%H%M%S%3f").to_string();
+    let mut dir = backup_dir.join(&name);
+    let mut count = 0;
+    while dir.exists() {
+      count += 1;
+      dir = backup_dir.join(format!("{}-{}", name, count));
+    }
+    std::fs::create_dir_all(&dir)?;
+    for (idx, file) in files.iter().enumerate() {
+      std::fs::copy(file, dir.join(Self::copy_name(idx, file)))?;
+    }
+
+    let snapshot = Self {
+      dir,
+      reason: reason.to_string(),
+      taken,
+      files,
+    };
+    std::fs::write(
+      snapshot.dir.join(MANIFEST),
+      serde_json::to_vec_pretty(&snapshot)?,
+    )?;
+
+    Ok(Some(snapshot))
+  }
+
+  /// Deletes the oldest snapshots past [`LIMIT`].
+  fn prune(backup_dir: &Path) -> io::Result<()> {
+    for old in Self::list_in(backup_dir).into_iter().skip(LIMIT) {
+      remove_dir_all::remove_dir_all(old.dir)?;
+    }
+
+    Ok(())
+  }
+
+  /// [`Snapshot::take`], but only of files that haven't been snapshotted yet this run. Files only
+  /// count as snapshotted once the snapshot has been taken, so a failed one is tried again.
+  pub fn take_once(files: &[PathBuf], reason: &str) -> io::Result<Option<Self>> {
+    let mut snapshotted = SNAPSHOTTED.lock().unwrap_or_else(|err| err.into_inner());
+    let files: Vec<PathBuf> = files
+      .iter()
+      .filter(|file| !snapshotted.contains(*file))
+      .cloned()
+      .collect();
+
+    let snapshot = Self::take(&files, reason)?;
+    snapshotted.extend(files);
+
+    Ok(snapshot)
+  }
+
+  fn copy_name(idx: usize, file: &Path) -> String {
+    format!(
+      "{}-{}",
+      idx,
+      file.file_name().unwrap_or_default().to_string_lossy()
+    )
+  }
+
+  /// Every snapshot, newest first.
+  pub fn list() -> Vec<Self> {
+    Self::list_in(&Self::backup_dir())
+  }
+
+  fn list_in(backup_dir: &Path) -> Vec<Self> {
+    let mut snapshots: Vec<Self> = backup_dir
+      .read_dir()
+      .into_iter()
+      .flatten()
+      .flatten()
+      .filter_map(|entry| {
+        let text = std::fs::read_to_string(entry.path().join(MANIFEST)).ok()?;
+        let mut snapshot: Self = serde_json::from_str(&text).ok()?;
+        snapshot.dir = entry.path();
+
+        Some(snapshot)
+      })
+      .collect();
+    snapshots.sort_by(|a, b| b.taken.cmp(&a.taken).then_with(|| b.dir.cmp(&a.dir)));
+
+    snapshots
+  }
+
+  /// Copies the files back where they came from, snapshotting what's there now first so the
+  /// restore can itself be undone. Old snapshots are only pruned afterwards, as this may be one.
+  pub fn restore(&self) -> io::Result<()> {
+    let backup_dir = self.dir.parent().unwrap_or(&self.dir);
+    Self::take_into(
+      backup_dir,
+      &self.files,
+      &tr_args("game-backup-before-restoring", &[("reason", &self.reason)]),
+    )?;
+    for (idx, file) in self.files.iter().enumerate() {
+      std::fs::copy(self.dir.join(Self::copy_name(idx, file)), file)?;
+    }
+
+    Self::prune(backup_dir)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use tempfile::tempdir;
+
+  use super::{Snapshot, LIMIT};
+
+  #[test]
+  fn restores_snapshot() {
+    let game = tempdir().expect("Create game dir");
+    let backups = tempdir().expect("Create backup dir");
+    let vmparams = game.path().join("vmparams");
+    std::fs::write(&vmparams, "java.exe -Xms1536m -Xmx1536m").expect("Write vmparams");
+
+    let snapshot = Snapshot::take_into(
+      backups.path(),
+      &[vmparams.clone(), game.path().join("missing")],
+      "Before editing vmparams",
+    )
+    .expect("Take snapshot")
+    .expect("Snapshot vmparams");
+    assert_eq!(snapshot.files, [vmparams.clone()]);
+
+    std::fs::write(&vmparams, "java.exe -Xms4g -Xmx4g").expect("Edit vmparams");
+    let listed = Snapshot::list_in(backups.path());
+    assert_eq!(listed.len(), 1);
+    listed[0].restore().expect("Restore snapshot");
+    assert_eq!(
+      std::fs::read_to_string(&vmparams).expect("Read vmparams"),
+      "java.exe -Xms1536m -Xmx1536m"
+    );
+
+    // the edit that was restored over is kept too
+    let listed = Snapshot::list_in(backups.path());
+    assert_eq!(listed.len(), 2);
+    assert!(listed[0].reason.starts_with("Before restoring"));
+  }
+
+  #[test]
+  fn restores_oldest_snapshot() {
+    let game = tempdir().expect("Create game dir");
+    let backups = tempdir().expect("Create backup dir");
+    let vmparams = game.path().join("vmparams");
+    for i in 0..LIMIT {
+      std::fs::write(&vmparams, i.to_string()).expect("Write vmparams");
+      Snapshot::take_into(
+        backups.path(),
+        &[vmparams.clone()],
+        "Before editing vmparams",
+      )
+      .expect("Take snapshot");
+    }
+
+    let listed = Snapshot::list_in(backups.path());
+    assert_eq!(listed.len(), LIMIT);
+    listed[LIMIT - 1]
+      .restore()
+      .expect("Restore oldest snapshot");
+    assert_eq!(
+      std::fs::read_to_string(&vmparams).expect("Read vmparams"),
+      "0"
+    );
+    assert_eq!(Snapshot::list_in(backups.path()).len(), LIMIT);
+  }
+}
@@ -0,0 +1,218 @@
+//! Presets and toggles for GraphicsLib's `GRAPHICS_OPTIONS.ini`, the usual cause of poor
+//! performance with GraphicsLib installed.
+//!
+//! The file is edited in place, keeping its comments. The first edit copies the original to
+//! `GRAPHICS_OPTIONS.ini.bak`, which can be restored from the settings.
+
+use std::path::{Path, PathBuf};
+
+use druid::{im::Vector, Data, Lens};
+
+use crate::app::{
+  i18n::tr,
+  mod_entry::{config_text, mod_info_edit},
+  util::{LoadError, SaveError},
+};
+
+pub const FILE_NAME: &str = "GRAPHICS_OPTIONS.ini";
+
+/// Option keys and the keys of their translated labels, for the options that cost the most
+/// performance.
+const TOGGLES: [(&str, &str); 9] = [
+  ("enableShaders", "graphicslib-shaders"),
+  ("enableLights", "graphicslib-lights"),
+  ("enableBloom", "graphicslib-bloom"),
+  ("enableDistortion", "graphicslib-distortion"),
+  ("enableShieldRipples", "graphicslib-shield-ripples"),
+  ("enableNormal", "graphicslib-normal-maps"),
+  ("loadMaterial", "graphicslib-material-maps"),
+  ("loadSurface", "graphicslib-surface-maps"),
+  ("use64BitBuffer", "graphicslib-64-bit-buffer"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+  Low,
+  Medium,
+  High,
+}
+
+impl Preset {
+  pub const ALL: [Preset; 3] = [Preset::Low, Preset::Medium, Preset::High];
+
+  pub fn name(&self) -> String {
+    tr(match self {
+      Preset::Low => "graphicslib-low",
+      Preset::Medium => "graphicslib-medium",
+      Preset::High => "graphicslib-high",
+    })
+  }
+
+  pub fn description(&self) -> String {
+    tr(match self {
+      Preset::Low => "graphicslib-low-description",
+      Preset::Medium => "graphicslib-medium-description",
+      Preset::High => "graphicslib-high-description",
+    })
+  }
+
+  /// Whether the option with `key` is on in this preset.
+  fn enables(&self, key: &str) -> bool {
+    match self {
+      Preset::Low => matches!(key, "enableShaders" | "enableLights"),
+      Preset::Medium => !matches!(key, "loadMaterial" | "loadSurface" | "use64BitBuffer"),
+      Preset::High => key != "use64BitBuffer",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Toggle {
+  #[data(ignore)]
+  pub key: &'static str,
+  /// The key of the option's translated label.
+  #[data(ignore)]
+  pub label: &'static str,
+  pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct GraphicsOptions {
+  #[data(same_fn = "PartialEq::eq")]
+  pub path: PathBuf,
+  /// The options from [`TOGGLES`] that are in the file.
+  pub toggles: Vector<Toggle>,
+}
+
+impl GraphicsOptions {
+  /// Finds GraphicsLib's options in the mods folder or the external mod folders. GraphicsLib's
+  /// folder is usually named after its version, so every mod folder is checked, and the last by
+  /// name is taken if there's more than one.
+  pub fn find<'a>(dirs: impl IntoIterator<Item = &'a Path>) -> Option<PathBuf> {
+    dirs.into_iter().find_map(|dir| {
+      let mut candidates: Vec<PathBuf> = dir
+        .read_dir()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path().join(FILE_NAME))
+        .filter(|path| path.is_file())
+        .collect();
+      candidates.sort();
+
+      candidates.pop()
+    })
+  }
+
+  pub fn load(path: PathBuf) -> Result<Self, LoadError> {
+    let text = config_text::read(&path).map_err(|_| LoadError::ReadError)?;
+    let toggles: Vector<Toggle> = TOGGLES
+      .iter()
+      .filter_map(|&(key, label)| {
+        let enabled = match mod_info_edit::get_raw(&text, key)? {
+          "true" => true,
+          "false" => false,
+          _ => return None,
+        };
+
+        Some(Toggle {
+          key,
+          label,
+          enabled,
+        })
+      })
+      .collect();
+    if toggles.is_empty() {
+      return Err(LoadError::FormatError);
+    }
+
+    Ok(Self { path, toggles })
+  }
+
+  pub fn apply(&mut self, preset: Preset) {
+    for toggle in self.toggles.iter_mut() {
+      toggle.enabled = preset.enables(toggle.key)
+    }
+  }
+
+  /// The preset matching the current toggles, if any.
+  pub fn preset(&self) -> Option<Preset> {
+    Preset::ALL.iter().copied().find(|preset| {
+      self
+        .toggles
+        .iter()
+        .all(|toggle| toggle.enabled == preset.enables(toggle.key))
+    })
+  }
+
+  fn backup_path(&self) -> PathBuf {
+    self.path.with_extension("ini.bak")
+  }
+
+  pub fn has_backup(&self) -> bool {
+    self.backup_path().is_file()
+  }
+
+  /// Writes the toggles into the file if they've changed, backing up the original first if it
+  /// hasn't been already.
+  pub fn save(&self) -> Result<(), SaveError> {
+    let text = config_text::read(&self.path).map_err(|_| SaveError::File)?;
+    let edited = self
+      .toggles
+      .iter()
+      .try_fold(text.clone(), |text, toggle| {
+        mod_info_edit::set_raw(&text, toggle.key, &toggle.enabled.to_string())
+      })
+      .ok_or(SaveError::Format)?;
+    if edited == text {
+      return Ok(());
+    }
+
+    if !self.has_backup() {
+      std::fs::copy(&self.path, self.backup_path()).map_err(|_| SaveError::Write)?;
+    }
+    std::fs::write(&self.path, edited).map_err(|_| SaveError::Write)
+  }
+
+  /// Puts the original file back, returning its options.
+  pub fn restore_backup(&self) -> Result<Self, LoadError> {
+    std::fs::rename(self.backup_path(), &self.path).map_err(|_| LoadError::NoSuchFile)?;
+
+    Self::load(self.path.clone())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use tempfile::tempdir;
+
+  use super::{GraphicsOptions, Preset, FILE_NAME};
+
+  const OPTIONS: &str = "{\n\t# Set to false to disable all shaders\n\t\"enableShaders\":true,\n\
+                         \t\"enableBloom\":true,\n\t\"loadMaterial\":true,\n\
+                         \t\"maximumLights\":100,\n}";
+
+  #[test]
+  fn applies_presets_with_backup() {
+    let mods = tempdir().expect("Create mods dir");
+    let folder = mods.path().join("GraphicsLib-1.9.0");
+    std::fs::create_dir(&folder).expect("Create GraphicsLib folder");
+    std::fs::write(folder.join(FILE_NAME), OPTIONS).expect("Write options");
+
+    let path = GraphicsOptions::find([mods.path()]).expect("Find options");
+    let mut options = GraphicsOptions::load(path.clone()).expect("Load options");
+    assert_eq!(options.toggles.len(), 3);
+    assert_eq!(options.preset(), Some(Preset::High));
+
+    options.apply(Preset::Low);
+    options.save().expect("Save options");
+    let text = std::fs::read_to_string(&path).expect("Read options");
+    assert!(text.contains("\"enableBloom\":false,"));
+    assert!(text.contains("\"maximumLights\":100,"));
+    assert!(text.contains("# Set to false"));
+
+    let restored = options.restore_backup().expect("Restore backup");
+    assert_eq!(restored.preset(), Some(Preset::High));
+    assert!(!restored.has_backup());
+  }
+}
@@ -5,8 +5,7 @@ use std::{
 };
 
 use anyhow::Context;
-use compress_tools::uncompress_archive;
-use druid::{ExtEventSink, Selector, Target};
+use druid::{Data, ExtEventSink, Selector, Target};
 use flate2::read::GzDecoder;
 use rand::random;
 use serde::{Deserialize, Serialize};
@@ -15,17 +14,128 @@ use tap::Pipe;
 use tar::Archive;
 use tempfile::TempDir;
 use tokio::runtime::Handle;
+use webview_shared::EventSender;
 
-use crate::app::App;
+use super::{
+  game_backup::Snapshot,
+  mikohime::Manifest,
+  vmparams::{VMParamsPath, VMParamsPathDefault},
+};
+use crate::app::{
+  i18n::{tr, tr_args},
+  net,
+  popup::Popup,
+  progress::{Progress, Unit},
+  util::format_size,
+  App,
+};
 
 pub const SWAP_COMPLETE: Selector = Selector::new("settings.jre.swap_complete");
+pub const PROGRESS: Selector<Phase> = Selector::new("settings.jre.progress");
+/// The flavour that failed to install, or `None` if reverting failed, and the error.
+pub const SWAP_FAILED: Selector<(Option<Flavour>, String)> =
+  Selector::new("settings.jre.swap_failed");
+
+/// Context on an error from a swap that failed part way, after the JRE it replaced was put back.
+#[derive(Debug)]
+pub struct RolledBack;
+
+impl std::fmt::Display for RolledBack {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("The previous JRE was put back")
+  }
+}
 
-#[derive(Copy, Clone, Display, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Display, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Flavour {
   Coretto,
   Hotspot,
   Wisp,
   Azul,
+  Temurin17,
+  Temurin21,
+}
+
+/// What a swap did with the JRE it replaced, so it can be undone.
+pub struct Swapped {
+  stock_jre: PathBuf,
+  /// Where the new JRE was moved or linked from.
+  installed_from: PathBuf,
+  linked: bool,
+  /// Where the replaced JRE was moved to, if it was a folder.
+  backup: Option<PathBuf>,
+  /// Where the replaced JRE linked to, if it was a link.
+  previous_link: Option<PathBuf>,
+}
+
+impl Swapped {
+  /// Puts the replaced JRE back, once the new one is out of the way.
+  fn restore_previous(&self) -> std::io::Result<()> {
+    if let Some(backup) = &self.backup {
+      std::fs::rename(backup, &self.stock_jre)
+    } else if let Some(previous_link) = &self.previous_link {
+      symlink(previous_link, &self.stock_jre)
+    } else {
+      Ok(())
+    }
+  }
+
+  /// Moves the new JRE back where it came from and puts the replaced one back.
+  fn undo(&self) -> std::io::Result<()> {
+    if self.linked {
+      remove_symlink(&self.stock_jre)?;
+    } else {
+      std::fs::rename(&self.stock_jre, &self.installed_from)?;
+    }
+
+    self.restore_previous()
+  }
+}
+
+/// How far along a swap is.
+#[derive(Debug, Clone, Data, PartialEq)]
+pub enum Phase {
+  Downloading {
+    received: u64,
+    total: Option<u64>,
+  },
+  Extracting,
+  /// Moving the new JRE into the install, and the old one out of the way.
+  Installing,
+}
+
+impl Phase {
+  pub fn describe(&self) -> String {
+    match self {
+      Phase::Downloading {
+        received,
+        total: Some(total),
+      } => tr_args(
+        "jre-phase-downloading-of",
+        &[
+          ("received", &format_size(*received)),
+          ("total", &format_size(*total)),
+        ],
+      ),
+      Phase::Downloading { received, .. } => tr_args(
+        "jre-phase-downloading",
+        &[("received", &format_size(*received))],
+      ),
+      Phase::Extracting => tr("jre-phase-extracting"),
+      Phase::Installing => tr("jre-phase-installing"),
+    }
+  }
+
+  /// How much of the download is done, when its size is known.
+  pub fn fraction(&self) -> Option<f64> {
+    match self {
+      Phase::Downloading {
+        received,
+        total: Some(total),
+      } if *total > 0 => Some(*received as f64 / *total as f64),
+      _ => None,
+    }
+  }
 }
 
 const ORIGINAL_JRE_BACKUP: &str = "jre7";
@@ -33,27 +143,117 @@ const JRE_BACKUP: &str = "jre.bak";
 
 impl Flavour {
   pub async fn swap(&self, ext_ctx: ExtEventSink, root: PathBuf, managed: bool) {
-    ext_ctx
-      .submit_command(
-        App::LOG_MESSAGE,
-        format!(
-          "Beginning JRE upgrade - installing {}. This may take a while...",
-          self
+    let events = EventSender::new(ext_ctx);
+    if !events.submit(
+      App::LOG_MESSAGE,
+      format!(
+        "Beginning JRE upgrade - installing {}. This may take a while...",
+        self
+      ),
+      Target::Auto,
+    ) {
+      return;
+    }
+
+    let job = Progress::new(Unit::Bytes);
+    job.set_stage(format!("Fetching JRE {}", self));
+    events.submit(
+      Popup::SHOW,
+      Popup::Progress(format!("Installing JRE {}", self), job.clone()),
+      Target::Auto,
+    );
+    let progress = |phase: Phase| {
+      match &phase {
+        Phase::Downloading { received, total } => {
+          job.set_stage(format!("Downloading JRE {}", self));
+          job.set_total(total.unwrap_or(0));
+          job.set_done(*received);
+        }
+        // the old JRE is about to be moved out of the way
+        Phase::Extracting | Phase::Installing => {
+          job.lock();
+          job.set_stage(phase.describe());
+        }
+      }
+      events.submit(PROGRESS, phase, Target::Auto);
+    };
+    let work = async {
+      backup_vmparams(&root, &tr_args("game-backup-before-jre", &[("jre", &self)]))?;
+      // find the kit first, so the JRE is left alone if there isn't one
+      let kit = match self.java_version() {
+        8 => None,
+        java => Some(
+          Manifest::fetch()
+            .await?
+            .kit(java)
+            .cloned()
+            .with_context(|| format!("No Mikohime kit is listed for Java {} yet", java))?,
         ),
-        Target::Auto,
-      )
-      .expect("Send message");
+      };
+
+      let swapped = self
+        .swap_jre(
+          &root,
+          managed,
+          webview_shared::PROJECT.data_dir(),
+          &progress,
+        )
+        .await?;
+      if let Some(kit) = kit
+        && let Err(err) = kit.install(&root, &progress).await
+      {
+        // the new JRE can't run the game without the kit
+        if let Some(Err(undo_err)) = swapped.as_ref().map(Swapped::undo) {
+          return Err(err.context(format!("Failed to put the previous JRE back: {}", undo_err)));
+        }
+        return Err(err.context(RolledBack));
+      }
 
-    let res = self
-      .swap_jre(&root, managed, webview_shared::PROJECT.data_dir())
-      .await;
+      Ok::<_, anyhow::Error>(swapped.is_none())
+    };
+    // cancelling is checked first, so the swap is dropped at its next await rather than carrying
+    // on past the point it was cancelled at
+    let res = tokio::select! {
+      biased;
+      () = job.cancelled() => None,
+      res = work => Some(res),
+    };
+    job.finish();
+    let Some(res) = res else {
+      events
+        .submit_with_retry(
+          App::LOG_MESSAGE,
+          String::from("JRE upgrade cancelled."),
+          Target::Auto,
+        )
+        .await;
+      events
+        .submit_with_retry(SWAP_COMPLETE, (), Target::Auto)
+        .await;
+      return;
+    };
 
-    match res {
-      Ok(true) => ext_ctx.submit_command(App::LOG_MESSAGE, format!("JRE {} already installed!", self), Target::Auto).expect("Send message"),
-      Ok(false) => ext_ctx.submit_command(App::LOG_MESSAGE, String::from("JRE upgrade complete!"), Target::Auto).expect("Send message"),
-      Err(err) => ext_ctx.submit_command(App::LOG_MESSAGE, format!("ERROR: Failed to upgrade JRE. Your Starsector installation may be corrupted.\nError: {:?}", err), Target::Auto).expect("Send message")
+    let message = match &res {
+      Ok(true) => tr_args("jre-already-installed", &[("jre", &self)]),
+      Ok(false) => tr("jre-upgrade-complete"),
+      Err(err) if err.downcast_ref::<RolledBack>().is_some() => tr("jre-upgrade-rolled-back"),
+      Err(_) => tr("jre-upgrade-failed"),
+    };
+    events
+      .submit_with_retry(App::LOG_MESSAGE, message, Target::Auto)
+      .await;
+    events
+      .submit_with_retry(SWAP_COMPLETE, (), Target::Auto)
+      .await;
+    if let Err(err) = res {
+      events
+        .submit_with_retry(
+          SWAP_FAILED,
+          (Some(*self), format!("{:?}", err)),
+          Target::Auto,
+        )
+        .await;
     }
-    let _ = ext_ctx.submit_command(SWAP_COMPLETE, (), Target::Auto);
   }
 
   async fn swap_jre(
@@ -61,7 +261,8 @@ impl Flavour {
     root: &Path,
     managed: bool,
     project_data: &Path,
-  ) -> anyhow::Result<bool> {
+    progress: &(dyn Fn(Phase) + Sync),
+  ) -> anyhow::Result<Option<Swapped>> {
     let cached_jre = if managed { project_data } else { root }.join(format!("jre_{}", self));
     let stock_jre = root.join(consts::JRE_PATH);
 
@@ -79,14 +280,17 @@ impl Flavour {
         Ok(false)
       });
     if let Ok(true) = already_installed {
-      return already_installed;
+      return Ok(None);
     }
 
     let tempdir: TempDir;
     let jre_8 = if !cached_jre.exists() {
-      tempdir = self
-        .unpack(if managed { project_data } else { root })
-        .await?;
+      tempdir = download(
+        self.get_url(),
+        if managed { project_data } else { root },
+        progress,
+      )
+      .await?;
 
       let search_stratgey = self.get_search_strategy();
       let jre_8 = Self::find_jre(tempdir.path(), search_stratgey).await?;
@@ -106,30 +310,58 @@ impl Flavour {
       cached_jre
     };
 
-    if !managed {
+    progress(Phase::Installing);
+    let mut swapped = Swapped {
+      stock_jre: stock_jre.clone(),
+      installed_from: jre_8.clone(),
+      linked: managed,
+      backup: None,
+      previous_link: None,
+    };
+    let installed = if !managed {
       if stock_jre.exists() {
-        std::fs::rename(&stock_jre, get_backup_path(&stock_jre)?)?;
+        let path = get_backup_path(&stock_jre)?;
+        std::fs::rename(&stock_jre, &path)?;
+        swapped.backup = Some(path);
       }
-      std::fs::rename(jre_8, &stock_jre)?;
+      std::fs::rename(jre_8, &stock_jre)
     } else {
       if stock_jre.exists() {
         if !std::fs::symlink_metadata(&stock_jre)?.is_symlink() {
-          std::fs::rename(&stock_jre, get_backup_path(&stock_jre)?)?;
+          let path = get_backup_path(&stock_jre)?;
+          std::fs::rename(&stock_jre, &path)?;
+          swapped.backup = Some(path);
         } else {
-          #[cfg(target_os = "windows")]
-          std::fs::remove_dir(&stock_jre)?;
-          #[cfg(target_family = "unix")]
-          std::fs::remove_file(&stock_jre)?;
+          swapped.previous_link = Some(std::fs::read_link(&stock_jre)?);
+          remove_symlink(&stock_jre)?;
         }
       }
 
-      #[cfg(target_os = "windows")]
-      std::os::windows::fs::symlink_dir(jre_8, &stock_jre)?;
-      #[cfg(target_family = "unix")]
-      std::os::unix::fs::symlink(jre_8, &stock_jre)?;
+      symlink(&jre_8, &stock_jre)
+    };
+
+    // put the old JRE back rather than leave the install without one
+    if let Err(err) = installed {
+      swapped
+        .restore_previous()
+        .context("Restore the previous JRE")?;
+      return Err(
+        anyhow::Error::new(err)
+          .context("Move the new JRE into place")
+          .context(RolledBack),
+      );
     }
 
-    Ok(false)
+    Ok(Some(swapped))
+  }
+
+  /// The Java major version. The game needs the Mikohime kit to run on anything newer than 8.
+  pub fn java_version(&self) -> u32 {
+    match self {
+      Flavour::Temurin17 => 17,
+      Flavour::Temurin21 => 21,
+      _ => 8,
+    }
   }
 
   fn as_const(&self) -> (&'static str, FindBy) {
@@ -138,6 +370,8 @@ impl Flavour {
       Flavour::Hotspot => consts::HOTSPOT,
       Flavour::Wisp => consts::WISP,
       Flavour::Azul => consts::AZUL,
+      Flavour::Temurin17 => consts::TEMURIN_17,
+      Flavour::Temurin21 => consts::TEMURIN_21,
     }
   }
 
@@ -149,38 +383,6 @@ impl Flavour {
     self.as_const().1
   }
 
-  async fn unpack(&self, root: &Path) -> anyhow::Result<TempDir> {
-    let url = Self::get_url(self);
-
-    let tempdir = TempDir::new_in(root).context("Create tempdir")?;
-
-    let mut res = reqwest::get(url).await?;
-
-    let mut buf = Vec::new();
-    while let Some(bytes) = res.chunk().await? {
-      buf.append(&mut bytes.to_vec())
-    }
-
-    let path = root.join(tempdir.path());
-    Handle::current()
-      .spawn_blocking(move || -> anyhow::Result<()> {
-        if infer::archive::is_gz(&buf) {
-          let tar = GzDecoder::new(Cursor::new(buf));
-          let mut archive = Archive::new(tar);
-          archive.unpack(&path).context("Unpack tarball")
-        } else if infer::archive::is_zip(&buf) {
-          let mut zip = zip::ZipArchive::new(Cursor::new(buf))?;
-          zip.extract(&path).context("Unpack zip")
-        } else {
-          uncompress_archive(Cursor::new(buf), &path, compress_tools::Ownership::Ignore)
-            .context("Failed to unpack")
-        }
-      })
-      .await??;
-
-    Ok(tempdir)
-  }
-
   async fn find_jre(root: &Path, search_strategy: FindBy) -> anyhow::Result<PathBuf> {
     let mut visit = VecDeque::new();
     visit.push_back(root.to_path_buf());
@@ -214,6 +416,83 @@ impl Flavour {
   }
 }
 
+/// Downloads the archive at `url` and unpacks it into a temporary folder in `root`.
+pub async fn download(
+  url: &str,
+  root: &Path,
+  progress: &(dyn Fn(Phase) + Sync),
+) -> anyhow::Result<TempDir> {
+  let tempdir = TempDir::new_in(root).context("Create tempdir")?;
+
+  let mut res = net::client_builder()
+    .build()?
+    .get(url)
+    .send()
+    .await
+    .and_then(|res| res.error_for_status())
+    .with_context(|| format!("Download {}", url))?;
+
+  let total = res.content_length();
+  let mut buf = Vec::new();
+  let mut reported = None;
+  progress(Phase::Downloading { received: 0, total });
+  while let Some(bytes) = res
+    .chunk()
+    .await
+    .with_context(|| format!("Download {}", url))?
+  {
+    buf.append(&mut bytes.to_vec());
+    // once a percent, or once a megabyte when the size isn't known
+    let step = total.map_or(buf.len() as u64 / 1024 / 1024, |total| {
+      buf.len() as u64 * 100 / total.max(1)
+    });
+    if reported != Some(step) {
+      reported = Some(step);
+      progress(Phase::Downloading {
+        received: buf.len() as u64,
+        total,
+      });
+    }
+  }
+
+  progress(Phase::Extracting);
+
+  let path = root.join(tempdir.path());
+  Handle::current()
+    .spawn_blocking(move || -> anyhow::Result<()> {
+      if infer::archive::is_gz(&buf) {
+        let tar = GzDecoder::new(Cursor::new(buf));
+        let mut archive = Archive::new(tar);
+        archive.unpack(&path).context("Unpack tarball")
+      } else if infer::archive::is_zip(&buf) {
+        let mut zip = zip::ZipArchive::new(Cursor::new(buf))?;
+        zip.extract(&path).context("Unpack zip")
+      } else {
+        #[cfg(feature = "libarchive")]
+        return compress_tools::uncompress_archive(
+          Cursor::new(buf),
+          &path,
+          compress_tools::Ownership::Ignore,
+        )
+        .context("Failed to unpack");
+        #[cfg(not(feature = "libarchive"))]
+        anyhow::bail!("Unsupported archive format")
+      }
+    })
+    .await??;
+
+  Ok(tempdir)
+}
+
+/// Snapshots vmparams before the JRE changes, as a different JRE usually means different
+/// arguments.
+fn backup_vmparams(root: &Path, reason: &str) -> anyhow::Result<()> {
+  Snapshot::take(&[root.join(VMParamsPathDefault::path())], reason)
+    .context("Failed to back up vmparams")?;
+
+  Ok(())
+}
+
 fn get_backup_path(stock_jre: &Path) -> Result<PathBuf, anyhow::Error> {
   let is_original = std::fs::read_to_string(stock_jre.join("release")).is_ok_and(|release| {
     release
@@ -239,22 +518,52 @@ fn get_backup_path(stock_jre: &Path) -> Result<PathBuf, anyhow::Error> {
 }
 
 pub async fn revert(ext_ctx: ExtEventSink, root: PathBuf) {
-  ext_ctx
-    .submit_command(
-      App::LOG_MESSAGE,
-      String::from("Attempting to revert to JRE 7"),
-      Target::Auto,
-    )
-    .expect("Send message");
+  let events = EventSender::new(ext_ctx);
+  if !events.submit(
+    App::LOG_MESSAGE,
+    String::from("Attempting to revert to JRE 7"),
+    Target::Auto,
+  ) {
+    return;
+  }
 
-  let res = revert_jre(&root).await;
+  let res = match backup_vmparams(&root, &tr("game-backup-before-jre-7")) {
+    Ok(()) => revert_jre(&root).await,
+    Err(err) => Err(err),
+  };
 
-  match res {
-    Ok(true) => ext_ctx.submit_command(App::LOG_MESSAGE, String::from("Succesfully reverted to JRE 7"), Target::Auto).expect("Send message"),
-    Ok(false) => ext_ctx.submit_command(App::LOG_MESSAGE, String::from("ERROR: Could not revert to JRE 7 - no JRE 7 backup found"), Target::Auto).expect("Send message"),
-    Err(err) => ext_ctx.submit_command(App::LOG_MESSAGE, format!("ERROR: Failed to revert JRE. Your Starsector installation may be corrupted.\nError: {:?}", err), Target::Auto).expect("Send message")
+  let message = match &res {
+    Ok(true) => String::from("Succesfully reverted to JRE 7"),
+    Ok(false) => String::from("ERROR: Could not revert to JRE 7 - no JRE 7 backup found"),
+    Err(_) => {
+      String::from("ERROR: Failed to revert JRE. Your Starsector installation may be corrupted.")
+    }
+  };
+  events
+    .submit_with_retry(App::LOG_MESSAGE, message, Target::Auto)
+    .await;
+  events
+    .submit_with_retry(SWAP_COMPLETE, (), Target::Auto)
+    .await;
+  if let Err(err) = res {
+    events
+      .submit_with_retry(SWAP_FAILED, (None, format!("{:?}", err)), Target::Auto)
+      .await;
   }
-  let _ = ext_ctx.submit_command(SWAP_COMPLETE, (), Target::Auto);
+}
+
+fn symlink(original: &Path, link: &Path) -> std::io::Result<()> {
+  #[cfg(target_os = "windows")]
+  return std::os::windows::fs::symlink_dir(original, link);
+  #[cfg(target_family = "unix")]
+  return std::os::unix::fs::symlink(original, link);
+}
+
+fn remove_symlink(link: &Path) -> std::io::Result<()> {
+  #[cfg(target_os = "windows")]
+  return std::fs::remove_dir(link);
+  #[cfg(target_family = "unix")]
+  return std::fs::remove_file(link);
 }
 
 async fn revert_jre(root: &Path) -> anyhow::Result<bool> {
@@ -266,10 +575,7 @@ async fn revert_jre(root: &Path) -> anyhow::Result<bool> {
       if !std::fs::symlink_metadata(&current_jre)?.is_symlink() {
         std::fs::rename(&current_jre, get_backup_path(&current_jre)?)?;
       } else {
-        #[cfg(target_os = "windows")]
-        std::fs::remove_dir(&current_jre)?;
-        #[cfg(target_family = "unix")]
-        std::fs::remove_file(&current_jre)?;
+        remove_symlink(&current_jre)?;
       }
     }
 
@@ -281,6 +587,128 @@ async fn revert_jre(root: &Path) -> anyhow::Result<bool> {
   }
 }
 
+/// The JRE the game will run on, whether MOSS installed it or not.
+#[derive(Debug, Clone, Data, PartialEq)]
+pub struct InstalledJre {
+  /// `JAVA_VERSION` from the JRE's `release` file, eg: `1.8.0_271`.
+  pub version: String,
+  /// `IMPLEMENTOR` from the `release` file, which older builds leave out.
+  pub vendor: Option<String>,
+  /// The flavour MOSS installed, from its `.moss` marker.
+  #[data(same_fn = "PartialEq::eq")]
+  pub managed: Option<Flavour>,
+  /// Whether the Mikohime kit is in the game folder.
+  pub mikohime: bool,
+}
+
+impl InstalledJre {
+  pub fn detect(root: &Path) -> Option<Self> {
+    let jre = root.join(consts::JRE_PATH);
+    let release = std::fs::read_to_string(jre.join("release")).ok()?;
+    let field = |key: &str| {
+      release.lines().find_map(|line| {
+        let (line_key, value) = line.split_once('=')?;
+        (line_key.trim() == key).then(|| value.trim().trim_matches('"').to_string())
+      })
+    };
+    let managed = std::fs::read_to_string(jre.join(".moss"))
+      .ok()
+      .and_then(|marker| serde_json::from_str(&marker).ok());
+    // the kit's launch scripts and readme are all named after it
+    let mikohime = root
+      .read_dir()
+      .into_iter()
+      .flatten()
+      .flatten()
+      .any(|entry| {
+        entry
+          .file_name()
+          .to_string_lossy()
+          .to_ascii_lowercase()
+          .starts_with("miko")
+      });
+
+    Some(Self {
+      version: field("JAVA_VERSION")?,
+      vendor: field("IMPLEMENTOR"),
+      managed,
+      mikohime,
+    })
+  }
+
+  pub fn is_original(&self) -> bool {
+    self.version.starts_with("1.7")
+  }
+
+  /// The flavour this most likely is, going by its version and vendor.
+  pub fn flavour(&self) -> Option<Flavour> {
+    if self.managed.is_some() {
+      return self.managed;
+    }
+
+    let vendor = self.vendor.as_deref().unwrap_or_default();
+    let major = if let Some(legacy) = self.version.strip_prefix("1.") {
+      legacy.split('.').next()
+    } else {
+      self.version.split('.').next()
+    };
+    match (major?, vendor) {
+      ("8", vendor) if vendor.starts_with("Amazon") => Some(Flavour::Coretto),
+      ("8", vendor) if vendor.starts_with("AdoptOpenJDK") => Some(Flavour::Hotspot),
+      ("8", vendor) if vendor.starts_with("Azul") => Some(Flavour::Azul),
+      ("8", _) if self.version == "1.8.0_271" => Some(Flavour::Wisp),
+      ("17", vendor) if vendor.starts_with("Eclipse") => Some(Flavour::Temurin17),
+      ("21", vendor) if vendor.starts_with("Eclipse") => Some(Flavour::Temurin21),
+      _ => None,
+    }
+  }
+
+  pub fn describe(&self) -> String {
+    let mut description = tr_args("jre-describe-java", &[("version", &self.version)]);
+    if let Some(vendor) = &self.vendor {
+      description = tr_args(
+        "jre-describe-vendor",
+        &[("jre", &description), ("vendor", vendor)],
+      )
+    }
+    description = if self.is_original() {
+      tr_args("jre-describe-original", &[("jre", &description)])
+    } else if let Some(flavour) = self.managed {
+      tr_args(
+        "jre-describe-managed",
+        &[("jre", &description), ("flavour", &flavour)],
+      )
+    } else {
+      tr_args("jre-describe-by-hand", &[("jre", &description)])
+    };
+    if self.mikohime {
+      description = tr_args("jre-describe-mikohime", &[("jre", &description)])
+    }
+
+    description
+  }
+
+  /// Whether MOSS can take over a JRE that was installed by hand.
+  pub fn adoptable(&self) -> bool {
+    self.managed.is_none() && !self.is_original() && self.flavour().is_some()
+  }
+
+  /// Writes the `.moss` marker, so MOSS treats the JRE as one it installed - it won't reinstall
+  /// the same flavour over it, and backs it up under its flavour's name when swapping.
+  pub fn adopt(&self, root: &Path) -> anyhow::Result<Self> {
+    let flavour = self.flavour().context("Couldn't tell which JRE this is")?;
+    std::fs::write(
+      root.join(consts::JRE_PATH).join(".moss"),
+      serde_json::to_string_pretty(&flavour)?,
+    )?;
+
+    Ok(Self {
+      managed: Some(flavour),
+      ..self.clone()
+    })
+  }
+}
+
 #[allow(dead_code)]
 #[derive(Clone, Copy, PartialEq)]
 pub enum FindBy {
@@ -302,6 +730,14 @@ mod consts {
     "https://cdn.azul.com/zulu/bin/zulu8.68.0.21-ca-jre8.0.362-win_x64.zip",
     FindBy::Bin,
   );
+  pub const TEMURIN_17: (&str, FindBy) = (
+    "https://api.adoptium.net/v3/binary/latest/17/ga/windows/x64/jre/hotspot/normal/eclipse",
+    FindBy::Bin,
+  );
+  pub const TEMURIN_21: (&str, FindBy) = (
+    "https://api.adoptium.net/v3/binary/latest/21/ga/windows/x64/jre/hotspot/normal/eclipse",
+    FindBy::Bin,
+  );
 
   pub const JRE_PATH: &str = "jre";
 }
@@ -319,6 +755,14 @@ mod consts {
     "https://cdn.azul.com/zulu/bin/zulu8.68.0.21-ca-jre8.0.362-linux_x64.zip",
     FindBy::Bin,
   );
+  pub const TEMURIN_17: (&str, FindBy) = (
+    "https://api.adoptium.net/v3/binary/latest/17/ga/linux/x64/jre/hotspot/normal/eclipse",
+    FindBy::Bin,
+  );
+  pub const TEMURIN_21: (&str, FindBy) = (
+    "https://api.adoptium.net/v3/binary/latest/21/ga/linux/x64/jre/hotspot/normal/eclipse",
+    FindBy::Bin,
+  );
 
   pub const JRE_PATH: &str = "jre_linux";
 }
@@ -336,6 +780,14 @@ mod consts {
     "https://cdn.azul.com/zulu/bin/zulu8.68.0.21-ca-jre8.0.362-macosx_x64.zip",
     FindBy::Bin,
   );
+  pub const TEMURIN_17: (&str, FindBy) = (
+    "https://api.adoptium.net/v3/binary/latest/17/ga/mac/x64/jre/hotspot/normal/eclipse",
+    FindBy::Bin,
+  );
+  pub const TEMURIN_21: (&str, FindBy) = (
+    "https://api.adoptium.net/v3/binary/latest/21/ga/mac/x64/jre/hotspot/normal/eclipse",
+    FindBy::Bin,
+  );
 
   pub const JRE_PATH: &str = "Contents/Home";
 }
@@ -344,7 +796,9 @@ mod consts {
 mod test {
   use tempfile::TempDir;
 
-  use super::{consts, revert_jre, Flavour, JRE_BACKUP, ORIGINAL_JRE_BACKUP};
+  use super::{
+    consts, revert_jre, Flavour, InstalledJre, Swapped, JRE_BACKUP, ORIGINAL_JRE_BACKUP,
+  };
 
   fn base_test(
     flavour: Flavour,
@@ -382,11 +836,11 @@ mod test {
       }
 
       let res = flavour
-        .swap_jre(test_dir.path(), managed, project_test_dir.path())
+        .swap_jre(test_dir.path(), managed, project_test_dir.path(), &|_| {})
         .await
         .expect("Swap JRE");
 
-      assert_eq!(res, expected);
+      assert_eq!(res.is_none(), expected);
 
       if let Some(mock_original) = mock_original {
         if mock_original {
@@ -630,4 +1084,58 @@ mod test {
 
     base_test(flavour, None, test_dir, None, false, true);
   }
+
+  #[test]
+  fn adopts_manual_install() {
+    let test_dir = TempDir::new().expect("Create tempdir");
+    let jre = test_dir.path().join(consts::JRE_PATH);
+    std::fs::create_dir_all(&jre).expect("Create mock JRE folder");
+    std::fs::write(
+      jre.join("release"),
+      "JAVA_VERSION=\"1.8.0_362\"\nIMPLEMENTOR=\"Azul Systems, Inc.\"\n",
+    )
+    .expect("Write test release");
+    std::fs::write(test_dir.path().join("Miko_R3.txt"), "").expect("Write kit readme");
+
+    let installed = InstalledJre::detect(test_dir.path()).expect("Detect JRE");
+    assert_eq!(installed.vendor.as_deref(), Some("Azul Systems, Inc."));
+    assert!(installed.mikohime);
+    assert!(installed.adoptable());
+
+    let adopted = installed.adopt(test_dir.path()).expect("Adopt JRE");
+    assert_eq!(adopted.managed, Some(Flavour::Azul));
+    assert_eq!(
+      InstalledJre::detect(test_dir.path()).expect("Detect JRE"),
+      adopted
+    );
+  }
+
+  #[test]
+  fn undoes_swap() {
+    let test_dir = TempDir::new().expect("Create tempdir");
+    let stock_jre = test_dir.path().join(consts::JRE_PATH);
+    let backup = stock_jre.with_file_name(JRE_BACKUP);
+    let cached = test_dir.path().join("jre_Coretto");
+    std::fs::create_dir_all(&backup).expect("Create mock old JRE");
+    std::fs::write(backup.join("release"), "old").expect("Write old release");
+    std::fs::create_dir_all(&stock_jre).expect("Create mock new JRE");
+    std::fs::write(stock_jre.join("release"), "new").expect("Write new release");
+
+    Swapped {
+      stock_jre: stock_jre.clone(),
+      installed_from: cached.clone(),
+      linked: false,
+      backup: Some(backup.clone()),
+      previous_link: None,
+    }
+    .undo()
+    .expect("Undo swap");
+
+    assert_eq!(
+      std::fs::read_to_string(stock_jre.join("release")).expect("Read release"),
+      "old"
+    );
+    assert!(cached.join("release").exists());
+    assert!(!backup.exists());
+  }
 }
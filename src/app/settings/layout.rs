@@ -0,0 +1,64 @@
+use druid::{im::Vector, Data, Lens, Point, Size, WindowDesc, WindowState};
+use serde::{Deserialize, Serialize};
+
+/// How the main window was laid out when it was last closed, restored on the next launch.
+#[derive(Debug, Clone, Data, Lens, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Layout {
+  /// Size of the window while it isn't maximized.
+  pub size: (f64, f64),
+  /// Position of the window while it isn't maximized, `None` leaves placing it to the OS.
+  pub position: Option<(f64, f64)>,
+  pub maximized: bool,
+  /// Which of the launch and tools tabs was open.
+  pub side_panel_tab: usize,
+  /// Where the divider between the mod list and the side panel was.
+  pub side_panel_split: f64,
+  /// The mod list's column dividers, `None` until they've been moved.
+  #[data(same_fn = "PartialEq::eq")]
+  pub columns: Option<Vector<f64>>,
+}
+
+impl Default for Layout {
+  fn default() -> Self {
+    Self {
+      size: (1280., 1024.),
+      position: None,
+      maximized: false,
+      side_panel_tab: 0,
+      side_panel_split: 0.8,
+      columns: None,
+    }
+  }
+}
+
+impl Layout {
+  /// Opens `window` at the size and position it was closed at.
+  pub fn apply<T>(&self, window: WindowDesc<T>) -> WindowDesc<T> {
+    let window = window.window_size(self.size);
+    let window = match self.position {
+      Some((x, y)) => window.set_position(Point::new(x, y)),
+      None => window,
+    };
+
+    if self.maximized {
+      window.set_window_state(WindowState::Maximized)
+    } else {
+      window
+    }
+  }
+
+  /// Records the window's geometry, keeping the size and position it had before being maximized
+  /// or minimized so it goes back to them on being restored.
+  pub fn record_window(&mut self, size: Size, position: Point, state: WindowState) {
+    match state {
+      WindowState::Maximized => self.maximized = true,
+      WindowState::Restored => {
+        self.maximized = false;
+        self.size = (size.width, size.height);
+        self.position = Some((position.x, position.y));
+      }
+      WindowState::Minimized => {}
+    }
+  }
+}
@@ -0,0 +1,182 @@
+//! The Mikohime kit, which the game needs to run on Java 17 or newer.
+//!
+//! The kit replaces the game's launch scripts, vmparams and some of its libraries, and most game
+//! updates need a new release of it. Which releases exist is read from a manifest in MOSS's
+//! repository rather than built in, so a new release only needs the manifest updating.
+
+use std::{
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use anyhow::Context;
+use druid::{ExtEventSink, Selector, Target};
+use serde::Deserialize;
+use tokio::runtime::Handle;
+use tracing::warn;
+
+use super::{
+  game_backup::Snapshot,
+  jre::{self, Phase},
+};
+use crate::app::{i18n::tr_args, installer::copy_dir_recursive, net};
+
+pub const MANIFEST_URL: &str =
+  "https://raw.githubusercontent.com/atlanticaccent/starsector-mod-manager-rust/main/mikohime.json";
+
+/// The Java versions the manifest has a kit for on this platform.
+pub const KITS: Selector<Arc<[u32]>> = Selector::new("settings.mikohime.kits");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Manifest {
+  /// Oldest first.
+  pub kits: Vec<Kit>,
+}
+
+impl Manifest {
+  pub async fn fetch() -> anyhow::Result<Self> {
+    net::api_client_builder()
+      .build()?
+      .get(MANIFEST_URL)
+      .send()
+      .await
+      .and_then(|res| res.error_for_status())?
+      .json()
+      .await
+      .context("Fetch the Mikohime kit manifest")
+  }
+
+  /// The newest kit for Java `java` with a download for this platform.
+  pub fn kit(&self, java: u32) -> Option<&Kit> {
+    self
+      .kits
+      .iter()
+      .rev()
+      .find(|kit| kit.java == java && kit.url().is_some())
+  }
+}
+
+/// Fetches the manifest and submits [`KITS`], with none if it couldn't be fetched.
+pub async fn find_kits(ext_ctx: ExtEventSink) {
+  let kits: Arc<[u32]> = match Manifest::fetch().await {
+    Ok(manifest) => IntoIterator::into_iter([17, 21])
+      .filter(|java| manifest.kit(*java).is_some())
+      .collect(),
+    Err(err) => {
+      warn!("Failed to fetch the Mikohime kit manifest: {:?}", err);
+      Arc::new([])
+    }
+  };
+
+  let _ = ext_ctx.submit_command(KITS, kits, Target::Auto);
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Kit {
+  /// The Java major version the kit is for.
+  pub java: u32,
+  /// Release name, eg: `R3`.
+  pub version: String,
+  pub windows: Option<String>,
+  pub linux: Option<String>,
+  pub macos: Option<String>,
+  /// The folder in the archive holding what goes in the game folder, if it isn't the top level.
+  #[serde(default)]
+  pub folder: Option<String>,
+}
+
+impl Kit {
+  pub fn url(&self) -> Option<&str> {
+    #[cfg(target_os = "windows")]
+    return self.windows.as_deref();
+    #[cfg(target_os = "linux")]
+    return self.linux.as_deref();
+    #[cfg(target_os = "macos")]
+    return self.macos.as_deref();
+  }
+
+  /// Unpacks the kit over the game folder, snapshotting the files it replaces first and putting
+  /// them back if it fails.
+  pub async fn install(
+    &self,
+    root: &Path,
+    progress: &(dyn Fn(Phase) + Sync),
+  ) -> anyhow::Result<()> {
+    let url = self.url().with_context(|| {
+      format!(
+        "Mikohime {} has no download for this platform",
+        self.version
+      )
+    })?;
+    let tempdir = jre::download(url, root, progress).await?;
+    let contents = self.folder.as_ref().map_or_else(
+      || tempdir.path().to_path_buf(),
+      |folder| tempdir.path().join(folder),
+    );
+
+    progress(Phase::Installing);
+    let root = root.to_path_buf();
+    let reason = tr_args("game-backup-before-mikohime", &[("version", &self.version)]);
+    Handle::current()
+      .spawn_blocking(move || -> anyhow::Result<()> {
+        let replaced: Vec<PathBuf> = files(&contents)
+          .iter()
+          .filter_map(|file| file.strip_prefix(&contents).ok())
+          .map(|file| root.join(file))
+          .collect();
+        let snapshot =
+          Snapshot::take(&replaced, &reason).context("Back up the files the kit replaces")?;
+
+        let copied =
+          copy_dir_recursive(&root, &contents).context("Copy the kit into the game folder");
+        // don't leave the game with half the kit's scripts
+        if copied.is_err()
+          && let Some(snapshot) = snapshot
+          && let Err(err) = snapshot.restore()
+        {
+          warn!("Failed to restore the files a failed Mikohime install replaced: {}", err)
+        }
+
+        copied
+      })
+      .await?
+  }
+}
+
+fn files(dir: &Path) -> Vec<PathBuf> {
+  let mut found = Vec::new();
+  for entry in dir.read_dir().into_iter().flatten().flatten() {
+    match entry.file_type() {
+      Ok(file_type) if file_type.is_dir() => found.extend(files(&entry.path())),
+      Ok(file_type) if file_type.is_file() => found.push(entry.path()),
+      _ => {}
+    }
+  }
+
+  found
+}
+
+#[cfg(test)]
+mod test {
+  use super::Manifest;
+
+  #[test]
+  fn picks_newest_kit() {
+    let manifest: Manifest = serde_json::from_str(
+      r#"{ "kits": [
+        { "java": 17, "version": "R1", "windows": "a", "linux": "a", "macos": "a" },
+        { "java": 21, "version": "R2", "windows": "b", "linux": "b", "macos": "b" },
+        { "java": 17, "version": "R3", "windows": "c", "linux": "c", "macos": "c" },
+        { "java": 17, "version": "R4" }
+      ] }"#,
+    )
+    .expect("Parse manifest");
+    assert_eq!(manifest.kit(17).map(|kit| kit.version.as_str()), Some("R3"));
+    assert_eq!(manifest.kit(21).map(|kit| kit.version.as_str()), Some("R2"));
+    assert!(manifest.kit(8).is_none());
+
+    let published: Manifest =
+      serde_json::from_str(include_str!("../../../mikohime.json")).expect("Parse mikohime.json");
+    assert!(published.kits.iter().all(|kit| kit.java >= 17));
+  }
+}
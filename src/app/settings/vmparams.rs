@@ -8,7 +8,11 @@ use std::sync::LazyLock;
 use std::{fmt::Display, path::Path};
 use strum_macros::EnumIter;
 
-use crate::app::util::{LoadError, SaveError};
+use super::game_backup::Snapshot;
+use crate::app::{
+  i18n::tr,
+  util::{LoadError, SaveError},
+};
 
 #[derive(Debug, Clone, Data, Lens)]
 pub struct VMParams<T: VMParamsPath = VMParamsPathDefault> {
@@ -25,6 +29,17 @@ pub struct Value {
   pub unit: Unit,
 }
 
+impl Value {
+  pub fn megabytes(&self) -> u64 {
+    let amount = self.amount.max(0) as u64;
+    match self.unit {
+      Unit::Giga => amount * 1024,
+      Unit::Mega => amount,
+      Unit::Kilo => amount / 1024,
+    }
+  }
+}
+
 impl Display for Value {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.write_fmt(format_args!("{}{}", self.amount, self.unit))
@@ -62,6 +77,11 @@ pub trait VMParamsPath {
     #[cfg(target_os = "linux")]
     return PathBuf::from("./starsector.sh");
   }
+
+  /// Whether [`VMParams::save`] snapshots the file before its first change each session.
+  fn backed_up() -> bool {
+    true
+  }
 }
 
 #[derive(Debug, Clone, Data)]
@@ -217,6 +237,14 @@ impl<T: VMParamsPath> VMParams<T> {
       }
     }
 
+    if T::backed_up() {
+      Snapshot::take_once(
+        &[install_dir.as_ref().join(T::path())],
+        &tr("game-backup-before-vmparams"),
+      )
+      .map_err(|_| SaveError::File)?;
+    }
+
     let mut file =
       fs::File::create(install_dir.as_ref().join(T::path())).map_err(|_| SaveError::File)?;
 
@@ -273,6 +301,10 @@ mod test {
     fn path() -> PathBuf {
       TEST_FILE.path().to_path_buf()
     }
+
+    fn backed_up() -> bool {
+      false
+    }
   }
 
   fn test_func<T: VMParamsPath>(verify_none: bool) {
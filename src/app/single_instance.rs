@@ -0,0 +1,84 @@
+//! Ensures only one instance of MOSS runs at a time.
+//!
+//! The first instance binds a local socket (a named pipe on Windows). Any instance launched after
+//! that connects to the socket, hands over its command line arguments and exits, leaving the
+//! running instance to act on them and bring itself to the front.
+
+use std::io::{ErrorKind, Write};
+
+use druid::{ExtEventSink, Target};
+use interprocess::local_socket::{LocalSocketListener, LocalSocketStream, NameTypeSupport};
+use tracing::{error, warn};
+use webview_shared::EventSender;
+
+use super::App;
+
+const SOCKET_NAME: &str = "moss-single-instance.sock";
+
+pub enum Instance {
+  /// No other instance is running. Holds `None` if the lock couldn't be created, in which case
+  /// we carry on without one.
+  Primary(Option<LocalSocketListener>),
+  /// Another instance is already running and has been sent our arguments.
+  Secondary,
+}
+
+fn socket_name() -> String {
+  match NameTypeSupport::query() {
+    NameTypeSupport::OnlyPaths => std::env::temp_dir()
+      .join(SOCKET_NAME)
+      .to_string_lossy()
+      .into_owned(),
+    NameTypeSupport::OnlyNamespaced | NameTypeSupport::Both => format!("@{}", SOCKET_NAME),
+  }
+}
+
+/// Attempts to become the only running instance, forwarding `args` to the existing instance if
+/// there already is one.
+pub fn acquire(args: &[String]) -> Instance {
+  let name = socket_name();
+
+  if let Ok(mut stream) = LocalSocketStream::connect(name.as_str()) {
+    if let Err(err) = bincode::serialize_into(&mut stream, args) {
+      error!("Failed to forward arguments to running instance: {:?}", err)
+    }
+    let _ = stream.flush();
+
+    return Instance::Secondary;
+  }
+
+  let listener = match LocalSocketListener::bind(name.as_str()) {
+    // Nobody answered but the socket file still exists - left behind by an instance that crashed
+    Err(err) if err.kind() == ErrorKind::AddrInUse && !name.starts_with('@') => {
+      let _ = std::fs::remove_file(&name);
+      LocalSocketListener::bind(name.as_str())
+    }
+    res => res,
+  };
+
+  match listener {
+    Ok(listener) => Instance::Primary(Some(listener)),
+    Err(err) => {
+      warn!("Failed to acquire single instance lock: {:?}", err);
+      Instance::Primary(None)
+    }
+  }
+}
+
+/// Listens for arguments forwarded by later instances, passing them on to the app as
+/// [`App::ACTIVATE`] commands.
+pub fn listen(listener: LocalSocketListener, ext_ctx: ExtEventSink) {
+  let events = EventSender::new(ext_ctx);
+  std::thread::spawn(move || {
+    for mut conn in listener.incoming().filter_map(Result::ok) {
+      match bincode::deserialize_from::<_, Vec<String>>(&mut conn) {
+        Ok(args) => {
+          if !events.submit(App::ACTIVATE, args, Target::Auto) {
+            break;
+          }
+        }
+        Err(err) => warn!("Received malformed activation message: {:?}", err),
+      }
+    }
+  });
+}
@@ -0,0 +1,203 @@
+//! Built-in colour palettes, the user's accent colour override and the UI scale.
+//!
+//! The env druid starts with is saved before any palette is applied, so switching palettes can
+//! rebuild the env from scratch - keys a palette doesn't set fall back to their original values
+//! rather than whatever the previous palette left behind.
+
+use std::cell::RefCell;
+
+use druid::{theme, Color, Env, Key};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+use super::util::{
+  BLUE_KEY, GREEN_KEY, ON_BLUE_KEY, ON_GREEN_KEY, ON_ORANGE_KEY, ON_RED_KEY, ON_YELLOW_KEY,
+  ORANGE_KEY, RED_KEY, YELLOW_KEY,
+};
+
+/// Text size of the buttons along the top of the main window, scaled with the rest of the UI.
+pub const NAV_TEXT_SIZE: Key<f64> = Key::new("moss.theme.nav_text_size");
+
+/// UI scales offered in the settings.
+pub const UI_SCALES: [f64; 6] = [0.75, 1., 1.25, 1.5, 1.75, 2.];
+
+thread_local! {
+  static ORIGINAL_ENV: RefCell<Option<Env>> = RefCell::new(None);
+}
+
+#[derive(
+  Clone, Copy, PartialEq, Eq, Serialize, Deserialize, druid::Data, Debug, Default, EnumIter, Display,
+)]
+pub enum Theme {
+  #[default]
+  Dark,
+  Light,
+  #[strum(to_string = "OLED Black")]
+  OledBlack,
+  #[strum(to_string = "High Contrast")]
+  HighContrast,
+}
+
+/// Colours set by a palette. Anything left as `None` keeps druid's default.
+struct Palette {
+  background_dark: &'static str,
+  background_light: &'static str,
+  border_dark: &'static str,
+  border_light: &'static str,
+  button: Option<&'static str>,
+  text: Option<&'static str>,
+  placeholder: Option<&'static str>,
+  selection: Option<&'static str>,
+  cursor: Option<&'static str>,
+  /// Pairs of status colours and the text drawn over them
+  status: [(Key<Color>, &'static str, Key<Color>, &'static str); 5],
+}
+
+impl Theme {
+  fn palette(self) -> Palette {
+    match self {
+      Theme::Dark => Palette {
+        background_dark: "1f1a1b",
+        background_light: "292425",
+        border_dark: "48454f",
+        border_light: "c9c4cf",
+        button: None,
+        text: None,
+        placeholder: None,
+        selection: None,
+        cursor: None,
+        status: [
+          (GREEN_KEY, "135200", ON_GREEN_KEY, "adf68a"),
+          (RED_KEY, "930006", ON_RED_KEY, "ffdad4"),
+          (YELLOW_KEY, "574500", ON_YELLOW_KEY, "ffe174"),
+          (BLUE_KEY, "004d66", ON_BLUE_KEY, "bbe9ff"),
+          (ORANGE_KEY, "7f2c00", ON_ORANGE_KEY, "ffdbcc"),
+        ],
+      },
+      Theme::Light => Palette {
+        background_dark: "f4eff0",
+        background_light: "fffbff",
+        border_dark: "c9c4cf",
+        border_light: "48454f",
+        button: Some("e6e0e9"),
+        text: Some("1d1b1e"),
+        placeholder: Some("79747e"),
+        selection: Some("cbbfe8"),
+        cursor: Some("1d1b1e"),
+        status: [
+          (GREEN_KEY, "adf68a", ON_GREEN_KEY, "0b3900"),
+          (RED_KEY, "ffdad4", ON_RED_KEY, "410001"),
+          (YELLOW_KEY, "ffe174", ON_YELLOW_KEY, "241a00"),
+          (BLUE_KEY, "bbe9ff", ON_BLUE_KEY, "001f2a"),
+          (ORANGE_KEY, "ffdbcc", ON_ORANGE_KEY, "351000"),
+        ],
+      },
+      Theme::OledBlack => Palette {
+        background_dark: "000000",
+        background_light: "0e0e0e",
+        border_dark: "2e2e2e",
+        border_light: "c9c4cf",
+        button: Some("121212"),
+        text: Some("e6e1e5"),
+        ..Theme::Dark.palette()
+      },
+      Theme::HighContrast => Palette {
+        background_dark: "000000",
+        background_light: "000000",
+        border_dark: "ffffff",
+        border_light: "ffff00",
+        button: Some("000000"),
+        text: Some("ffffff"),
+        placeholder: Some("c0c0c0"),
+        selection: Some("0000c0"),
+        cursor: Some("ffffff"),
+        status: [
+          (GREEN_KEY, "005000", ON_GREEN_KEY, "ffffff"),
+          (RED_KEY, "900000", ON_RED_KEY, "ffffff"),
+          (YELLOW_KEY, "505000", ON_YELLOW_KEY, "ffffff"),
+          (BLUE_KEY, "000090", ON_BLUE_KEY, "ffffff"),
+          (ORANGE_KEY, "803000", ON_ORANGE_KEY, "ffffff"),
+        ],
+      },
+    }
+  }
+}
+
+/// Keeps a copy of the env druid was launched with, then applies `selected`.
+pub fn save_original_env(env: &mut Env, selected: Theme, accent: &str, scale: f64) {
+  env.set(theme::BUTTON_BORDER_RADIUS, 2.);
+  env.set(theme::BUTTON_BORDER_WIDTH, 2.);
+  ORIGINAL_ENV.with(|original| *original.borrow_mut() = Some(env.clone()));
+
+  apply(env, selected, accent, scale)
+}
+
+/// Rebuilds the colours in `env` for `selected`, with `accent` (a hex colour, eg: `ff9800`)
+/// replacing the palette's highlight colour if it's valid.
+///
+/// Text and the theme's widget sizes are multiplied by `scale`, on top of the scaling the OS
+/// applies, for monitors where that comes out too small or too large.
+pub fn apply(env: &mut Env, selected: Theme, accent: &str, scale: f64) {
+  let palette = selected.palette();
+  let hex = |hex: &str| Color::from_hex_str(hex).unwrap();
+
+  ORIGINAL_ENV.with(|original| {
+    if let Some(original) = original.borrow().as_ref() {
+      for key in [
+        theme::BUTTON_DARK,
+        theme::TEXT_COLOR,
+        theme::PLACEHOLDER_COLOR,
+        theme::SELECTED_TEXT_BACKGROUND_COLOR,
+        theme::CURSOR_COLOR,
+        theme::PRIMARY_LIGHT,
+        theme::PRIMARY_DARK,
+      ] {
+        env.set(&key, original.get(&key))
+      }
+      for key in [
+        theme::TEXT_SIZE_NORMAL,
+        theme::TEXT_SIZE_LARGE,
+        theme::BASIC_WIDGET_HEIGHT,
+        theme::WIDE_WIDGET_WIDTH,
+        theme::BORDERED_WIDGET_HEIGHT,
+        theme::SCROLLBAR_WIDTH,
+      ] {
+        env.set(&key, original.get(&key) * scale)
+      }
+      for key in [theme::UI_FONT, theme::UI_FONT_BOLD, theme::UI_FONT_ITALIC] {
+        let font = original.get(&key);
+        env.set(&key, font.clone().with_size(font.size * scale))
+      }
+    }
+  });
+  env.set(NAV_TEXT_SIZE, 18. * scale);
+
+  env.set(theme::BACKGROUND_DARK, hex(palette.background_dark));
+  env.set(theme::BACKGROUND_LIGHT, hex(palette.background_light));
+  env.set(theme::WINDOW_BACKGROUND_COLOR, hex(palette.background_dark));
+  env.set(theme::BORDER_DARK, hex(palette.border_dark));
+  env.set(theme::BORDER_LIGHT, hex(palette.border_light));
+  for (key, value) in [
+    (theme::BUTTON_DARK, palette.button),
+    (theme::TEXT_COLOR, palette.text),
+    (theme::PLACEHOLDER_COLOR, palette.placeholder),
+    (theme::SELECTED_TEXT_BACKGROUND_COLOR, palette.selection),
+    (theme::CURSOR_COLOR, palette.cursor),
+  ] {
+    if let Some(value) = value {
+      env.set(key, hex(value))
+    }
+  }
+  // buttons are flat
+  env.set(theme::BUTTON_LIGHT, env.get(theme::BUTTON_DARK));
+  for (key, value, on_key, on_value) in palette.status {
+    env.set(key, hex(value));
+    env.set(on_key, hex(on_value));
+  }
+
+  if let Ok(accent) = Color::from_hex_str(accent.trim().trim_start_matches('#')) {
+    env.set(theme::BORDER_LIGHT, accent.clone());
+    env.set(theme::PRIMARY_LIGHT, accent.clone());
+    env.set(theme::PRIMARY_DARK, accent);
+  }
+}
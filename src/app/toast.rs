@@ -0,0 +1,181 @@
+//! Short notices stacked in the corner of the main window that go away on their own, for news
+//! that shouldn't stop whatever the user is doing the way a [`Popup`] does - an install
+//! finishing, an update check completing, a background task failing.
+//!
+//! Clicking a toast runs its action, if it has one, which is usually opening the full details.
+
+use std::{
+  sync::atomic::{AtomicU64, Ordering},
+  time::{Duration, Instant},
+};
+
+use druid::{
+  im::Vector,
+  widget::{Controller, CrossAxisAlignment, Flex, Label, List},
+  Command, Data, Env, Event, EventCtx, ExtEventSink, FontDescriptor, FontWeight, Selector, Target,
+  TimerToken, Widget, WidgetExt,
+};
+use druid_widget_nursery::material_icons::Icon;
+use tracing::error;
+
+use super::{
+  controllers::HoverController,
+  i18n::tr,
+  popup::Popup,
+  util::{icons::CLOSE, Card, WidgetExtEx, RED_KEY},
+};
+
+/// How many toasts can be shown at once, the oldest are dropped to make room.
+const MAX_SHOWN: usize = 5;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Data)]
+pub struct Toast {
+  id: u64,
+  error: bool,
+  title: String,
+  message: String,
+  #[data(ignore)]
+  action: Option<Command>,
+  #[data(ignore)]
+  expires: Instant,
+}
+
+impl Toast {
+  pub const SHOW: Selector<Toast> = Selector::new("app.toast.show");
+  const DISMISS: Selector<u64> = Selector::new("app.toast.dismiss");
+
+  pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+    Self {
+      id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+      error: false,
+      title: title.into(),
+      message: message.into(),
+      action: None,
+      expires: Instant::now() + Duration::from_secs(6),
+    }
+  }
+
+  /// A failure, which opens the full error chain when clicked. Stays up for longer than other
+  /// toasts so there's time to notice it.
+  pub fn error(err: anyhow::Error) -> Self {
+    Self {
+      error: true,
+      expires: Instant::now() + Duration::from_secs(15),
+      ..Self::new(tr("toast-error"), err.to_string())
+    }
+    .with_action(Popup::SHOW.with(Popup::Error(err)))
+  }
+
+  /// Runs `action` when the toast is clicked.
+  pub fn with_action(mut self, action: Command) -> Self {
+    self.action = Some(action);
+    self
+  }
+
+  /// Logs `err` and shows it as a toast. Does nothing beyond logging if the app has already shut
+  /// down.
+  pub fn submit_error(ext_ctx: &ExtEventSink, err: impl Into<anyhow::Error>) {
+    let err = err.into();
+    error!("{:?}", err);
+    let _ = ext_ctx.submit_command(Self::SHOW, Self::error(err), Target::Auto);
+  }
+
+  /// Adds `toast` to those shown.
+  pub fn push(toasts: &mut Vector<Toast>, toast: Toast) {
+    toasts.push_back(toast);
+    while toasts.len() > MAX_SHOWN {
+      toasts.pop_front();
+    }
+  }
+
+  pub fn stack() -> impl Widget<Vector<Toast>> {
+    List::new(Self::ui_builder)
+      .with_spacing(5.)
+      .controller(ToastStack::default())
+  }
+
+  fn ui_builder() -> impl Widget<Toast> {
+    Card::new(
+      Flex::row()
+        .with_flex_child(
+          Flex::column()
+            .with_child(
+              Label::dynamic(|toast: &Toast, _| toast.title.clone())
+                .with_font(FontDescriptor::default().with_weight(FontWeight::SEMI_BOLD))
+                .env_scope(|env, toast: &Toast| {
+                  if toast.error {
+                    env.set(druid::theme::TEXT_COLOR, env.get(RED_KEY))
+                  }
+                }),
+            )
+            .with_child(Label::wrapped_func(|toast: &Toast, _| {
+              toast.message.clone()
+            }))
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .expand_width()
+            .controller(HoverController)
+            .on_activate(|ctx, toast: &mut Toast, _| {
+              if let Some(action) = toast.action.clone() {
+                ctx.submit_command(action)
+              }
+              ctx.submit_command(Self::DISMISS.with(toast.id))
+            }),
+          1.,
+        )
+        .with_child(
+          Icon::new(CLOSE)
+            .controller(HoverController)
+            .on_activate(|ctx, toast: &mut Toast, _| {
+              ctx.submit_command(Self::DISMISS.with(toast.id))
+            })
+            .labelled(tr("toast-dismiss")),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start),
+    )
+    .fix_width(320.)
+  }
+}
+
+/// Dismisses toasts once they expire or are closed.
+#[derive(Default)]
+struct ToastStack {
+  timer: TimerToken,
+}
+
+impl<W: Widget<Vector<Toast>>> Controller<Vector<Toast>, W> for ToastStack {
+  fn event(
+    &mut self,
+    child: &mut W,
+    ctx: &mut EventCtx,
+    event: &Event,
+    data: &mut Vector<Toast>,
+    env: &Env,
+  ) {
+    match event {
+      Event::WindowConnected => self.timer = ctx.request_timer(POLL_INTERVAL),
+      Event::Timer(token) if *token == self.timer => {
+        self.timer = ctx.request_timer(POLL_INTERVAL);
+        // hovering a toast keeps it up, so it can be read and clicked
+        if !ctx.is_hot() {
+          let now = Instant::now();
+          data.retain(|toast| toast.expires > now);
+        }
+
+        return;
+      }
+      Event::Command(cmd) if cmd.is(Toast::DISMISS) => {
+        let id = *cmd.get_unchecked(Toast::DISMISS);
+        data.retain(|toast| toast.id != id);
+        ctx.set_handled();
+
+        return;
+      }
+      _ => {}
+    }
+
+    child.event(ctx, event, data, env)
+  }
+}
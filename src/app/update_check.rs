@@ -0,0 +1,149 @@
+//! Version checker sweeps.
+//!
+//! Every installed mod with a version file is checked on startup, and again every
+//! [`Settings::update_check_interval_hours`](super::settings::Settings) while the app is open.
+//! Results are batched back to the UI through a [`LoadBalancer`], and once a sweep has finished a
+//! single summary is shown if any mods have updates. Mods the user has opted in to updating
+//! automatically are then downloaded and installed, with a report of how that went.
+
+use std::{
+  collections::HashSet,
+  sync::atomic::{AtomicU32, Ordering},
+  time::Duration,
+};
+
+use druid::{ExtEventSink, Selector, Target};
+use tokio::{sync::mpsc::error::TrySendError, task::JoinSet};
+use tracing::{error, info};
+
+use super::{
+  installer::InstallOutcome,
+  mod_entry::ModVersionMeta,
+  util::{check_remote_version, LoadBalancer},
+};
+
+pub type SweepResult = (String, Result<ModVersionMeta, String>);
+
+/// Number of mods about to be checked.
+pub const SWEEP_STARTED: Selector<usize> = Selector::new("update_check.sweep.started");
+pub const SWEEP_RESULTS: Selector<Vec<SweepResult>> = Selector::new("update_check.sweep.results");
+/// Sent when a scheduled sweep is due, so the UI can start one with its current mod list.
+pub const SWEEP_DUE: Selector = Selector::new("update_check.sweep.due");
+/// How each automatic update after a sweep went.
+pub const AUTO_UPDATED: Selector<Vec<InstallOutcome>> = Selector::new("update_check.auto_updated");
+
+static INTERVAL_HOURS: AtomicU32 = AtomicU32::new(0);
+
+pub fn set_interval(hours: u32) {
+  INTERVAL_HOURS.store(hours, Ordering::Relaxed)
+}
+
+/// Checks every mod in `metas`, reporting the results as they arrive.
+pub async fn sweep(ext_ctx: ExtEventSink, metas: Vec<ModVersionMeta>) {
+  static BALANCER: LoadBalancer<SweepResult, Vec<SweepResult>, Vec<SweepResult>> =
+    LoadBalancer::new("update check", SWEEP_RESULTS).with_interval(Duration::from_millis(250));
+
+  if ext_ctx
+    .submit_command(SWEEP_STARTED, metas.len(), Target::Auto)
+    .is_err()
+  {
+    return;
+  }
+
+  let tx = BALANCER.sender(ext_ctx.clone());
+  let mut checks = JoinSet::new();
+  for meta in metas {
+    checks.spawn(async move { (meta.id.clone(), check_remote_version(&meta).await) });
+  }
+
+  while let Some(res) = checks.join_next().await {
+    let Ok(result) = res else {
+      continue;
+    };
+
+    // the sweep only finishes once every result is in, so never drop one
+    if let Err(TrySendError::Full(result) | TrySendError::Closed(result)) = tx.try_send(result)
+      && let Err(err) = ext_ctx.submit_command(SWEEP_RESULTS, vec![result], Target::Auto)
+    {
+      error!("Failed to submit update check result {}", err)
+    }
+  }
+}
+
+/// Asks the UI for a sweep every [`set_interval`] hours. An interval of 0 turns scheduled sweeps
+/// off, a change of interval takes effect once the current wait is over.
+pub async fn schedule(ext_ctx: ExtEventSink) {
+  const DISABLED_POLL: Duration = Duration::from_secs(60 * 60);
+
+  loop {
+    let hours = INTERVAL_HOURS.load(Ordering::Relaxed);
+    if hours == 0 {
+      tokio::time::sleep(DISABLED_POLL).await;
+      continue;
+    }
+
+    tokio::time::sleep(Duration::from_secs(u64::from(hours) * 60 * 60)).await;
+    if INTERVAL_HOURS.load(Ordering::Relaxed) == 0 {
+      continue;
+    }
+
+    info!("Running scheduled update check");
+    if ext_ctx.submit_command(SWEEP_DUE, (), Target::Auto).is_err() {
+      break;
+    }
+  }
+}
+
+/// Progress of the sweep currently running, if any.
+#[derive(Default)]
+pub struct Sweep {
+  total: usize,
+  remaining: usize,
+  updates: HashSet<String>,
+}
+
+impl Sweep {
+  pub fn new(count: usize) -> Option<Self> {
+    (count > 0).then(|| Self {
+      total: count,
+      remaining: count,
+      ..Default::default()
+    })
+  }
+
+  /// Records that `checked` mods have been checked, and which of them have updates. Returns the
+  /// number of mods with updates once every mod has been checked.
+  pub fn record(
+    &mut self,
+    checked: usize,
+    with_updates: impl Iterator<Item = String>,
+  ) -> Option<usize> {
+    self.updates.extend(with_updates);
+    self.remaining = self.remaining.saturating_sub(checked);
+
+    (self.remaining == 0).then(|| self.updates.len())
+  }
+
+  /// Mods checked so far, out of how many.
+  pub fn progress(&self) -> (usize, usize) {
+    (self.total - self.remaining, self.total)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::Sweep;
+
+  #[test]
+  fn counts_updates_once_finished() {
+    assert!(Sweep::new(0).is_none());
+
+    let mut sweep = Sweep::new(3).unwrap();
+    assert_eq!(sweep.record(2, std::iter::once(String::from("a"))), None);
+    assert_eq!(sweep.progress(), (2, 3));
+    assert_eq!(
+      sweep.record(1, vec![String::from("a"), String::from("b")].into_iter()),
+      Some(2)
+    );
+  }
+}
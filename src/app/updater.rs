@@ -1,4 +1,29 @@
-use self_update::errors::Error as SelfUpdateError;
+use std::path::{Path, PathBuf};
+
+use druid::{Data, ExtEventSink};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{error, warn};
+
+use super::{installer, net};
+use crate::util::{Asset, Release};
+
+/// Which MOSS releases to offer as updates.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Data, Debug, Default)]
+pub enum UpdateChannel {
+  /// Full releases only.
+  #[default]
+  Stable,
+  /// Pre-releases as well as full releases.
+  Beta,
+}
+
+impl UpdateChannel {
+  /// Whether `release` should be offered on this channel. Drafts never are.
+  pub fn accepts(self, release: &Release) -> bool {
+    !release.draft && (self == UpdateChannel::Beta || !release.prerelease)
+  }
+}
 
 pub fn support_self_update() -> bool {
   #[cfg(target_os = "macos")]
@@ -7,48 +32,174 @@ pub fn support_self_update() -> bool {
   true
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn self_update() -> Result<(), SelfUpdateError> {
-  use self_update::{backends::github::Update, cargo_crate_version};
-
-  Update::configure()
-    .repo_owner("atlanticaccent")
-    .repo_name("starsector-mod-manager-rust")
-    .current_version(cargo_crate_version!())
-    .target({
-      #[cfg(target_os = "windows")]
-      let bin = "starsector_mod_manager.exe";
-      #[cfg(all(target_os = "linux", target_feature = "crt-static"))]
-      let bin = "starsector_mod_manager_linux_dynamic";
-      #[cfg(all(target_os = "linux", not(target_feature = "crt-static")))]
-      let bin = "starsector_mod_manager_linux_static";
-
-      bin
-    })
-    .bin_name({
-      #[cfg(target_os = "windows")]
-      let bin = "tmp_starsector_mod_manager.exe";
-      #[cfg(all(target_os = "linux", target_feature = "crt-static"))]
-      let bin = "tmp_starsector_mod_manager_linux_dynamic";
-      #[cfg(all(target_os = "linux", not(target_feature = "crt-static")))]
-      let bin = "tmp_starsector_mod_manager_linux_static";
-
-      bin
-    })
-    .no_confirm(true)
-    .build()?
-    .update()?;
+/// The release asset containing this platform's executable.
+fn executable_asset_name() -> &'static str {
+  #[cfg(target_os = "windows")]
+  let bin = "starsector_mod_manager.exe";
+  #[cfg(all(target_os = "linux", target_feature = "crt-static"))]
+  let bin = "starsector_mod_manager_linux_dynamic";
+  #[cfg(all(target_os = "linux", not(target_feature = "crt-static")))]
+  let bin = "starsector_mod_manager_linux_static";
+  #[cfg(target_os = "macos")]
+  let bin = "starsector_mod_manager_macos";
+
+  bin
+}
+
+/// What was downloaded for an update, and so how it needs to be applied.
+enum UpdateKind {
+  /// A bare executable that replaces the running one.
+  Executable,
+  /// An installer that has to be run, and which takes care of replacing the installed copy itself.
+  Installer,
+}
+
+/// The outcome of a successfully applied update.
+pub enum Applied {
+  /// The executable was replaced - the new version runs after a restart.
+  Replaced(PathBuf),
+  /// The installer was launched and needs MOSS to exit so it can overwrite it.
+  InstallerLaunched,
+}
+
+/// Picks the asset to update from, preferring to swap the executable in place and falling back
+/// to an installer if the release doesn't include a bare executable for this platform.
+fn find_asset(release: &Release) -> Option<(&Asset, UpdateKind)> {
+  let executable = release
+    .assets
+    .iter()
+    .find(|asset| asset.name == executable_asset_name())
+    .map(|asset| (asset, UpdateKind::Executable));
+
+  executable.or_else(|| {
+    #[cfg(target_os = "windows")]
+    let installer = release
+      .assets
+      .iter()
+      .find(|asset| asset.name.to_lowercase().ends_with(".msi"))
+      .map(|asset| (asset, UpdateKind::Installer));
+    #[cfg(not(target_os = "windows"))]
+    let installer = None;
+
+    installer
+  })
+}
+
+/// The published SHA-256 of an asset - either from the digest GitHub records for every upload,
+/// or from a `<asset>.sha256` file uploaded alongside it.
+async fn expected_digest(release: &Release, asset: &Asset) -> Option<String> {
+  if let Some(digest) = asset
+    .digest
+    .as_ref()
+    .and_then(|digest| digest.strip_prefix("sha256:"))
+  {
+    return Some(digest.to_lowercase());
+  }
+
+  let checksum_name = format!("{}.sha256", asset.name);
+  let checksum = release
+    .assets
+    .iter()
+    .find(|candidate| candidate.name == checksum_name)?;
+  let text = net::api_client()
+    .ok()?
+    .get(&checksum.browser_download_url)
+    .send()
+    .await
+    .ok()?
+    .error_for_status()
+    .ok()?
+    .text()
+    .await
+    .ok()?;
+
+  text
+    .split_whitespace()
+    .next()
+    .map(|digest| digest.to_lowercase())
+}
+
+fn verify(path: &Path, asset: &Asset, expected: Option<&str>) -> Result<(), String> {
+  let bytes = std::fs::read(path).map_err(|err| format!("Failed to read download: {}", err))?;
+
+  if asset.size.is_some_and(|size| size != bytes.len() as u64) {
+    return Err(String::from("Download is incomplete"));
+  }
+  match expected {
+    Some(expected) => {
+      let actual = format!("{:x}", Sha256::digest(&bytes));
+      if actual != expected {
+        return Err(format!(
+          "Checksum mismatch - expected {}, got {}",
+          expected, actual
+        ));
+      }
+    }
+    None => warn!(
+      "No checksum published for {}, only the size was verified",
+      asset.name
+    ),
+  }
 
   Ok(())
 }
-#[cfg(target_os = "macos")]
-pub fn self_update() -> Result<(), SelfUpdateError> {
-  Err(SelfUpdateError::Io(std::io::ErrorKind::Other.into()))
+
+/// Downloads the update for this platform from `release`, verifies it, and applies it.
+///
+/// Download progress is shown in the downloads window, the same as mod downloads.
+pub async fn self_update(
+  release: Release,
+  ext_ctx: ExtEventSink,
+  cache_dir: PathBuf,
+) -> Result<Applied, String> {
+  let (asset, kind) = find_asset(&release)
+    .ok_or_else(|| String::from("The latest release has no download for this platform"))?;
+  let expected = expected_digest(&release, asset).await;
+
+  let file = installer::download(asset.browser_download_url.clone(), ext_ctx, &cache_dir)
+    .await
+    .map_err(|err| format!("Failed to download update: {}", err))?;
+  verify(file.path(), asset, expected.as_deref())?;
+
+  match kind {
+    UpdateKind::Executable => {
+      let current_exe = std::env::current_exe().map_err(|err| err.to_string())?;
+      // both executables have to be on the same filesystem as the current one to be swapped by
+      // renaming, which the cache may not be
+      let swap = tempfile::tempdir_in(current_exe.parent().unwrap_or(&cache_dir))
+        .map_err(|err| err.to_string())?;
+      let new_exe = swap.path().join("new_starsector_mod_manager");
+      installer::persist(file, &new_exe)
+        .map_err(|err| format!("Failed to move update next to the executable: {}", err))?;
+      #[cfg(unix)]
+      {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(&new_exe, std::fs::Permissions::from_mode(0o755))
+          .map_err(|err| format!("Failed to mark update as executable: {}", err))?;
+      }
+
+      self_update::Move::from_source(&new_exe)
+        .replace_using_temp(&swap.path().join("old_starsector_mod_manager"))
+        .to_dest(&current_exe)
+        .map_err(|err| format!("Failed to replace executable: {}", err))?;
+
+      Ok(Applied::Replaced(current_exe))
+    }
+    UpdateKind::Installer => {
+      let installer = cache_dir.join(&asset.name);
+      installer::persist(file, &installer)
+        .map_err(|err| format!("Failed to save installer: {}", err))?;
+      opener::open(&installer).map_err(|err| format!("Failed to launch installer: {}", err))?;
+
+      Ok(Applied::InstallerLaunched)
+    }
+  }
 }
 
 pub fn open_in_browser() {
   if opener::open("https://github.com/atlanticaccent/starsector-mod-manager-rust/releases").is_err()
   {
-    eprintln!("Failed to open GitHub");
+    error!("Failed to open GitHub");
   }
 }
@@ -0,0 +1,199 @@
+//! `moss://` links, so mod sites can offer "Install with MOSS" buttons.
+//!
+//! `moss://install?url=<download>` asks to download and install a mod, going through the same
+//! confirmation as a download started in the mod browser, since any web page can open one.
+//! `moss://open?url=<page>` opens a page in the mod browser. The OS starts MOSS with the link as
+//! an argument, and if MOSS is already running the single instance lock hands the link over.
+//!
+//! The scheme is registered for the current user only, when turned on in the settings. On macOS
+//! links arrive as Apple events rather than arguments, which the windowing library doesn't pass
+//! on, so registering isn't supported there.
+
+use reqwest::Url;
+
+pub const SCHEME: &str = "moss";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+  Install(String),
+  Open(String),
+}
+
+/// Whether a command line argument is a `moss:` link, rather than a file that happens to start
+/// with `moss`, such as `mossy_mod.zip`.
+pub fn is_link(arg: &str) -> bool {
+  arg
+    .trim()
+    .split_once(':')
+    .is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))
+}
+
+/// The action `link` asks for, if it's a valid `moss://` link.
+pub fn parse(link: &str) -> Option<DeepLink> {
+  let link = Url::parse(link.trim()).ok()?;
+  if link.scheme() != SCHEME {
+    return None;
+  }
+  let target = link
+    .query_pairs()
+    .find_map(|(key, value)| (key == "url").then(|| value.into_owned()))
+    .filter(|target| {
+      Url::parse(target).is_ok_and(|target| matches!(target.scheme(), "http" | "https"))
+    })?;
+
+  // `moss://install?..` puts the action in the host, `moss:install?..` in the path
+  match link
+    .host_str()
+    .unwrap_or_else(|| link.path())
+    .trim_matches('/')
+  {
+    "install" => Some(DeepLink::Install(target)),
+    "open" => Some(DeepLink::Open(target)),
+    _ => None,
+  }
+}
+
+#[cfg(target_os = "windows")]
+const KEY: &str = r"HKCU\Software\Classes\moss";
+
+/// Makes `moss://` links open this executable.
+#[cfg(target_os = "windows")]
+pub fn register() -> anyhow::Result<()> {
+  let exe = std::env::current_exe()?;
+  let command = format!("\"{}\" \"%1\"", exe.to_string_lossy());
+
+  reg(&["add", KEY, "/ve", "/d", "URL:MOSS", "/f"])?;
+  reg(&["add", KEY, "/v", "URL Protocol", "/d", "", "/f"])?;
+  reg(&[
+    "add",
+    &format!(r"{}\shell\open\command", KEY),
+    "/ve",
+    "/d",
+    &command,
+    "/f",
+  ])
+}
+
+#[cfg(target_os = "windows")]
+pub fn unregister() -> anyhow::Result<()> {
+  reg(&["delete", KEY, "/f"])
+}
+
+#[cfg(target_os = "windows")]
+pub(super) fn reg(args: &[&str]) -> anyhow::Result<()> {
+  use std::os::windows::process::CommandExt;
+
+  // keeps a console window from flashing up
+  const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+  let output = std::process::Command::new("reg")
+    .args(args)
+    .creation_flags(CREATE_NO_WINDOW)
+    .output()?;
+  if !output.status.success() {
+    anyhow::bail!(
+      "reg {} failed: {}",
+      args[0],
+      String::from_utf8_lossy(&output.stderr).trim()
+    )
+  }
+
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "moss-url-handler.desktop";
+
+#[cfg(target_os = "linux")]
+fn desktop_file() -> anyhow::Result<std::path::PathBuf> {
+  let dirs = directories::BaseDirs::new().ok_or_else(|| anyhow::anyhow!("No home folder"))?;
+
+  Ok(dirs.data_dir().join("applications").join(DESKTOP_FILE))
+}
+
+/// Makes `moss://` links open this executable.
+#[cfg(target_os = "linux")]
+pub fn register() -> anyhow::Result<()> {
+  let exe = std::env::current_exe()?;
+  let path = desktop_file()?;
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  std::fs::write(
+    &path,
+    format!(
+      "[Desktop Entry]\n\
+       Type=Application\n\
+       Name=MOSS\n\
+       Exec=\"{}\" %u\n\
+       NoDisplay=true\n\
+       MimeType=x-scheme-handler/{};\n",
+      exe.to_string_lossy(),
+      SCHEME
+    ),
+  )?;
+
+  let status = std::process::Command::new("xdg-mime")
+    .args([
+      "default",
+      DESKTOP_FILE,
+      &format!("x-scheme-handler/{}", SCHEME),
+    ])
+    .status()?;
+  if !status.success() {
+    anyhow::bail!("xdg-mime exited with {}", status)
+  }
+
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn unregister() -> anyhow::Result<()> {
+  match std::fs::remove_file(desktop_file()?) {
+    Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+    _ => Ok(()),
+  }
+}
+
+#[cfg(target_os = "macos")]
+pub fn register() -> anyhow::Result<()> {
+  anyhow::bail!("moss:// links aren't supported on macOS yet")
+}
+
+#[cfg(target_os = "macos")]
+pub fn unregister() -> anyhow::Result<()> {
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::{is_link, parse, DeepLink};
+
+  #[test]
+  fn parses_links() {
+    assert_eq!(
+      parse("moss://install?url=https%3A%2F%2Fexample.com%2FMod.zip"),
+      Some(DeepLink::Install(String::from(
+        "https://example.com/Mod.zip"
+      )))
+    );
+    assert_eq!(
+      parse("moss:open?url=https://fractalsoftworks.com/forum/index.php%3Ftopic%3D5444"),
+      Some(DeepLink::Open(String::from(
+        "https://fractalsoftworks.com/forum/index.php?topic=5444"
+      )))
+    );
+    assert_eq!(parse("moss://install?url=file:///etc/passwd"), None);
+    assert_eq!(parse("moss://uninstall?url=https://example.com"), None);
+    assert_eq!(parse("https://example.com"), None);
+  }
+
+  #[test]
+  fn tells_links_from_files() {
+    assert!(is_link("moss://install?url=https://example.com/Mod.zip"));
+    assert!(is_link("MOSS:open?url=https://example.com"));
+    assert!(!is_link("mossy_mod.zip"));
+    assert!(!is_link("moss.zip"));
+    assert!(!is_link(r"C:\Mods\moss.zip"));
+  }
+}
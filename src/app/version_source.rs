@@ -0,0 +1,567 @@
+use std::{
+  collections::HashMap,
+  future::Future,
+  path::PathBuf,
+  pin::Pin,
+  sync::{LazyLock, Mutex, RwLock},
+  time::Duration,
+};
+
+use regex::Regex;
+use reqwest::{
+  header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+  RequestBuilder, Response, StatusCode, Url,
+};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+use webview_shared::PROJECT;
+use xxhash_rust::xxh3::xxh3_64;
+
+use super::{
+  mod_entry::{ModVersionMeta, Version},
+  mod_repo::ModRepo,
+  net,
+};
+
+pub type SourceFuture<'a> =
+  Pin<Box<dyn Future<Output = Result<ModVersionMeta, String>> + Send + 'a>>;
+
+/// A place the latest version of a mod can be looked up from.
+///
+/// Sources are tried in priority order by [`check`] - the first source that
+/// supports a given mod and returns a version wins, any errors are only
+/// reported if every source failed.
+pub trait VersionSource: Send + Sync {
+  fn name(&self) -> &'static str;
+
+  fn supports(&self, local: &ModVersionMeta) -> bool;
+
+  fn fetch<'a>(&'a self, local: &'a ModVersionMeta) -> SourceFuture<'a>;
+}
+
+/// The API key used by [`NexusMods`]. Mirrors the key in the user's settings.
+pub static NEXUS_API_KEY: LazyLock<RwLock<Option<String>>> = LazyLock::new(Default::default);
+
+static SOURCES: LazyLock<Vec<Box<dyn VersionSource>>> = LazyLock::new(|| {
+  vec![
+    Box::new(MasterVersionFile),
+    Box::new(GithubReleases),
+    Box::new(NexusMods),
+    Box::new(ModRepoIndex),
+  ]
+});
+
+/// Minimum time between requests to the same host.
+const HOST_DELAY: Duration = Duration::from_millis(250);
+/// Retries after the first attempt for requests that fail in a way that may not happen again.
+const MAX_RETRIES: u32 = 3;
+
+/// When each host may next be sent a request.
+static NEXT_REQUEST: LazyLock<Mutex<HashMap<String, Instant>>> = LazyLock::new(Default::default);
+
+/// Phrases mod authors commonly use to say an update will break existing saves.
+static SAVE_BREAKING: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(
+    r"(?i)(requires?|needs?|start) an? (new|fresh) (game|save|campaign)|not save[ -]?compatible|save[ -]?(game )?(incompatible|breaking)|breaks? (existing |old |current )?saves|incompatible with (existing |old |previous |current )?saves",
+  )
+  .unwrap()
+});
+
+pub async fn check(local: &ModVersionMeta) -> Result<ModVersionMeta, String> {
+  let mut errors = Vec::new();
+  for source in SOURCES.iter().filter(|source| source.supports(local)) {
+    match source.fetch(local).await {
+      Ok(mut remote) => {
+        if remote.save_warning.is_none()
+          && remote.version > local.version
+          && let Some(url) = remote.changelog_url.clone()
+          && let Ok(changelog) = send_request(url).await
+        {
+          remote.save_warning = save_warning(&changelog, &local.version);
+        }
+        return Ok(remote);
+      }
+      Err(err) => errors.push(format!("{}: {}", source.name(), err)),
+    }
+  }
+
+  if errors.is_empty() {
+    Err(String::from("No version source available for this mod"))
+  } else {
+    Err(errors.join("\n"))
+  }
+}
+
+/// The "classic" Version Checker format - a JSON file hosted somewhere, usually
+/// a Github repo or a forum attachment.
+pub struct MasterVersionFile;
+
+impl VersionSource for MasterVersionFile {
+  fn name(&self) -> &'static str {
+    "Version file"
+  }
+
+  fn supports(&self, local: &ModVersionMeta) -> bool {
+    !local.remote_url.is_empty()
+  }
+
+  fn fetch<'a>(&'a self, local: &'a ModVersionMeta) -> SourceFuture<'a> {
+    Box::pin(async move {
+      let remote = send_request(local.remote_url.clone()).await?;
+
+      ModVersionMeta::parse(&remote).map_err(|_| format!("Parse error. Payload:\n{}", remote))
+    })
+  }
+}
+
+/// Uses the latest release of the Github repository the mod's version file or
+/// direct download is hosted in.
+pub struct GithubReleases;
+
+impl GithubReleases {
+  fn repo(local: &ModVersionMeta) -> Option<(String, String)> {
+    std::iter::once(&local.remote_url)
+      .chain(local.direct_download_url.as_ref())
+      .find_map(|url| {
+        let url = Url::parse(url).ok()?;
+        match url.host_str()? {
+          "raw.githubusercontent.com" | "github.com" => {
+            let mut segments = url.path_segments()?;
+            let owner = segments.next().filter(|s| !s.is_empty())?;
+            let repo = segments.next().filter(|s| !s.is_empty())?;
+
+            Some((owner.to_string(), repo.to_string()))
+          }
+          _ => None,
+        }
+      })
+  }
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+  tag_name: String,
+  #[serde(default)]
+  body: Option<String>,
+  #[serde(default)]
+  assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+  name: String,
+  browser_download_url: String,
+}
+
+impl VersionSource for GithubReleases {
+  fn name(&self) -> &'static str {
+    "Github releases"
+  }
+
+  fn supports(&self, local: &ModVersionMeta) -> bool {
+    Self::repo(local).is_some()
+  }
+
+  fn fetch<'a>(&'a self, local: &'a ModVersionMeta) -> SourceFuture<'a> {
+    Box::pin(async move {
+      let (owner, repo) = Self::repo(local).ok_or("Not a Github hosted mod")?;
+
+      let client = client()?;
+      let url = format!(
+        "https://api.github.com/repos/{}/{}/releases/latest",
+        owner, repo
+      );
+      let release = send_politely(&url, || client.get(&url))
+        .await?
+        .json::<GithubRelease>()
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+
+      let version = parse_version(&release.tag_name)
+        .ok_or_else(|| format!("Could not parse release tag {}", release.tag_name))?;
+      let download = release
+        .assets
+        .iter()
+        .find(|asset| {
+          let name = asset.name.to_lowercase();
+          [".zip", ".7z", ".rar"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+        })
+        .map(|asset| asset.browser_download_url.clone());
+
+      Ok(ModVersionMeta {
+        save_warning: release
+          .body
+          .as_deref()
+          .and_then(|body| save_warning(body, &local.version)),
+        version,
+        direct_download_url: download.or_else(|| local.direct_download_url.clone()),
+        ..local.clone()
+      })
+    })
+  }
+}
+
+/// Queries the Nexus Mods API. Requires the user to have provided an API key.
+pub struct NexusMods;
+
+#[derive(Deserialize)]
+struct NexusMod {
+  version: String,
+}
+
+impl VersionSource for NexusMods {
+  fn name(&self) -> &'static str {
+    "Nexus Mods"
+  }
+
+  fn supports(&self, local: &ModVersionMeta) -> bool {
+    !local.nexus_id.is_empty()
+      && NEXUS_API_KEY
+        .read()
+        .is_ok_and(|key| key.as_ref().is_some_and(|key| !key.is_empty()))
+  }
+
+  fn fetch<'a>(&'a self, local: &'a ModVersionMeta) -> SourceFuture<'a> {
+    Box::pin(async move {
+      let key = NEXUS_API_KEY
+        .read()
+        .ok()
+        .and_then(|key| key.clone())
+        .ok_or("No Nexus Mods API key")?;
+
+      let client = client()?;
+      let url = format!(
+        "https://api.nexusmods.com/v1/games/starsector/mods/{}.json",
+        local.nexus_id
+      );
+      let nexus_mod = send_politely(&url, || client.get(&url).header("apikey", &key))
+        .await?
+        .json::<NexusMod>()
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+
+      let version = parse_version(&nexus_mod.version)
+        .ok_or_else(|| format!("Could not parse version {}", nexus_mod.version))?;
+
+      Ok(ModVersionMeta {
+        version,
+        ..local.clone()
+      })
+    })
+  }
+}
+
+/// Falls back to the unofficial mod repo, matching on forum thread or Nexus ID.
+pub struct ModRepoIndex;
+
+impl VersionSource for ModRepoIndex {
+  fn name(&self) -> &'static str {
+    "Mod repo"
+  }
+
+  fn supports(&self, local: &ModVersionMeta) -> bool {
+    !local.fractal_id.is_empty() || !local.nexus_id.is_empty()
+  }
+
+  fn fetch<'a>(&'a self, local: &'a ModVersionMeta) -> SourceFuture<'a> {
+    Box::pin(async move {
+      let repo = ModRepo::cached().await.ok_or("Mod repo unavailable")?;
+
+      let mod_version = repo
+        .find_version(&local.fractal_id, &local.nexus_id)
+        .ok_or("Mod not found in mod repo")?;
+      let version = parse_version(&mod_version)
+        .ok_or_else(|| format!("Could not parse version {}", mod_version))?;
+
+      Ok(ModVersionMeta {
+        version,
+        ..local.clone()
+      })
+    })
+  }
+}
+
+/// Looks for a note that an update isn't save compatible in a changelog, returning the line it
+/// was found on. Changelogs are assumed to be newest first, so anything from the entry for the
+/// `installed` version onwards is ignored.
+pub fn save_warning(changelog: &str, installed: &Version) -> Option<String> {
+  let installed = installed.to_string();
+
+  changelog
+    .lines()
+    .take_while(|line| {
+      !line
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+        .any(|word| word.trim_start_matches(['v', 'V']) == installed)
+    })
+    .find(|line| SAVE_BREAKING.is_match(line))
+    .map(|line| line.trim().to_string())
+}
+
+/// Loosely parses a freeform version string such as `v1.2.3b` into a [`Version`].
+pub fn parse_version(text: &str) -> Option<Version> {
+  let text = text.trim().trim_start_matches(['v', 'V']);
+  let mut parts = text.splitn(3, '.');
+
+  let major = parts.next()?.trim().parse().ok()?;
+  let minor = parts.next().map_or(Some(0), |minor| {
+    let digits: String = minor.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+  })?;
+  let patch = parts.next().unwrap_or_default().trim().to_string();
+
+  Some(Version {
+    major,
+    minor,
+    patch,
+  })
+}
+
+fn client() -> Result<reqwest::Client, String> {
+  net::api_client_builder()
+    .user_agent("StarsectorModManager")
+    .build()
+    .map_err(|e| e.to_string())
+}
+
+/// Fetches `url` as text. Responses with an `ETag` or `Last-Modified` header are cached on disk
+/// and revalidated next time, so files that haven't changed aren't downloaded again.
+async fn send_request(url: String) -> Result<String, String> {
+  let client = net::api_client()?;
+  let cached = CachedResponse::load(&url).await;
+
+  let res = send_politely(&url, || {
+    let mut request = client.get(&url);
+    if let Some(cached) = &cached {
+      if let Some(etag) = &cached.etag {
+        request = request.header(IF_NONE_MATCH, etag)
+      }
+      if let Some(last_modified) = &cached.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, last_modified)
+      }
+    }
+
+    request
+  })
+  .await?;
+
+  if res.status() == StatusCode::NOT_MODIFIED
+    && let Some(cached) = cached
+  {
+    return Ok(cached.body);
+  }
+
+  let header = |name: HeaderName| {
+    res
+      .headers()
+      .get(name)
+      .and_then(|value| value.to_str().ok())
+      .map(str::to_string)
+  };
+  let etag = header(ETAG);
+  let last_modified = header(LAST_MODIFIED);
+  let body = res.text().await.map_err(|e| format!("{:?}", e))?;
+
+  if etag.is_some() || last_modified.is_some() {
+    CachedResponse {
+      url,
+      etag,
+      last_modified,
+      body: body.clone(),
+    }
+    .save()
+    .await;
+  }
+
+  Ok(body)
+}
+
+/// A response kept with its validators, one file per URL in the cache dir.
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+  url: String,
+  etag: Option<String>,
+  last_modified: Option<String>,
+  body: String,
+}
+
+impl CachedResponse {
+  /// Kept in the data dir, as the cache dir is cleared on exit.
+  fn path(url: &str) -> PathBuf {
+    PROJECT
+      .data_dir()
+      .join("http")
+      .join(format!("{:016x}.json", xxh3_64(url.as_bytes())))
+  }
+
+  async fn load(url: &str) -> Option<Self> {
+    let json = tokio::fs::read(Self::path(url)).await.ok()?;
+
+    serde_json::from_slice::<Self>(&json)
+      .ok()
+      .filter(|cached| cached.url == url)
+  }
+
+  async fn save(&self) {
+    let path = Self::path(&self.url);
+    let res = async {
+      if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+      }
+      tokio::fs::write(&path, serde_json::to_vec(self)?).await
+    };
+
+    if let Err(err) = res.await {
+      warn!("Failed to cache response for {}: {}", self.url, err)
+    }
+  }
+}
+
+/// Sends the request made by `build`, after waiting for the host to be free, retrying with
+/// exponential backoff if it times out, can't connect, is rate limited or hits a server error.
+async fn send_politely(url: &str, build: impl Fn() -> RequestBuilder) -> Result<Response, String> {
+  let mut attempt = 0;
+  loop {
+    wait_for_host(url).await;
+
+    match build().send().await.and_then(Response::error_for_status) {
+      Err(err) if attempt < MAX_RETRIES && is_transient(&err) => {
+        debug!("Retrying {} after {:?}", url, err);
+        tokio::time::sleep(backoff(attempt)).await;
+        attempt += 1;
+      }
+      res => return res.map_err(|e| format!("{:?}", e)),
+    }
+  }
+}
+
+/// Waits until `url`'s host is next free, so many mods hosted in the same place don't all hit it
+/// at once.
+async fn wait_for_host(url: &str) {
+  let Some(host) = Url::parse(url)
+    .ok()
+    .and_then(|url| url.host_str().map(str::to_string))
+  else {
+    return;
+  };
+
+  let slot = {
+    let Ok(mut next) = NEXT_REQUEST.lock() else {
+      return;
+    };
+    let now = Instant::now();
+    let slot = next
+      .get(&host)
+      .copied()
+      .filter(|slot| *slot > now)
+      .unwrap_or(now);
+    next.insert(host, slot + HOST_DELAY);
+
+    slot
+  };
+
+  tokio::time::sleep_until(slot).await
+}
+
+fn is_transient(err: &reqwest::Error) -> bool {
+  err.is_timeout()
+    || err.is_connect()
+    || err
+      .status()
+      .is_some_and(|status| status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error())
+}
+
+/// How long to wait before retry number `attempt`, starting from 0.
+fn backoff(attempt: u32) -> Duration {
+  Duration::from_secs(1) * 2u32.pow(attempt)
+}
+
+#[cfg(test)]
+mod test {
+  use std::time::Duration;
+
+  use super::{backoff, parse_version, save_warning, GithubReleases};
+  use crate::app::mod_entry::ModVersionMeta;
+
+  #[test]
+  fn parses_loose_versions() {
+    let version = parse_version("v1.2.3").expect("Parse version");
+    assert_eq!(
+      (version.major, version.minor, version.patch.as_str()),
+      (1, 2, "3")
+    );
+
+    let version = parse_version("2.10a").expect("Parse version");
+    assert_eq!(
+      (version.major, version.minor, version.patch.as_str()),
+      (2, 10, "")
+    );
+
+    let version = parse_version("3").expect("Parse version");
+    assert_eq!((version.major, version.minor), (3, 0));
+
+    assert!(parse_version("latest").is_none());
+  }
+
+  #[test]
+  fn finds_github_repo() {
+    let local = ModVersionMeta {
+      remote_url: String::from(
+        "https://raw.githubusercontent.com/someone/some-mod/master/mod.version",
+      ),
+      direct_download_url: None,
+      id: String::from("some_mod"),
+      fractal_id: String::new(),
+      nexus_id: String::new(),
+      version: parse_version("1.0.0").unwrap(),
+      changelog_url: None,
+      save_warning: None,
+    };
+
+    assert_eq!(
+      GithubReleases::repo(&local),
+      Some((String::from("someone"), String::from("some-mod")))
+    );
+
+    let local = ModVersionMeta {
+      remote_url: String::from("https://bitbucket.org/someone/some-mod/mod.version"),
+      ..local
+    };
+
+    assert!(GithubReleases::repo(&local).is_none());
+  }
+
+  #[test]
+  fn finds_save_warnings() {
+    let changelog = "\
+Version 1.2.0
+- Reworked the faction, requires a new game
+- Fixed a crash
+
+Version 1.1.0
+- Added ships
+Version 1.0.0
+- Now save compatible again";
+    let installed = parse_version("1.1.0").unwrap();
+
+    assert_eq!(
+      save_warning(changelog, &installed).as_deref(),
+      Some("- Reworked the faction, requires a new game")
+    );
+    assert!(save_warning(
+      "v1.1.1\n- Fixed typos\nv1.1.0\n- NOT save compatible",
+      &installed
+    )
+    .is_none());
+    assert!(save_warning("Save-breaking: new sector generation", &installed).is_some());
+  }
+
+  #[test]
+  fn backs_off_exponentially() {
+    assert_eq!(backoff(0), Duration::from_secs(1));
+    assert_eq!(backoff(2), Duration::from_secs(4));
+  }
+}
@@ -1,5 +1,8 @@
 pub use druid_widget_nursery::material_icons::normal::{
-  action::{EXTENSION, HELP, INSTALL_DESKTOP, OPEN_IN_BROWSER as OPEN_BROWSER, SETTINGS, VERIFIED},
+  action::{
+    EXTENSION, HELP, INFO, INSTALL_DESKTOP, OPEN_IN_BROWSER as OPEN_BROWSER, SETTINGS, VERIFIED,
+  },
+  alert::WARNING,
   av::{NEW_RELEASES, PLAY_ARROW},
   content::REPORT,
   image::NAVIGATE_NEXT,
@@ -23,7 +23,7 @@ extern crate webview_subsystem;
 
 use clap::Parser;
 use const_format::concatcp;
-use druid::{theme, AppLauncher, Color, WindowDesc};
+use druid::{AppLauncher, Target, WindowDesc};
 use tokio::runtime::Builder;
 use webview_shared::PROJECT;
 
@@ -32,84 +32,74 @@ mod app;
 mod patch;
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
+#[clap(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
-  #[clap(long)]
+  #[clap(subcommand)]
+  command: Option<app::cli::Command>,
+  /// Starsector install directory to use instead of the one in settings (command line only)
+  #[clap(long, global = true)]
+  install_dir: Option<std::path::PathBuf>,
+  /// Deprecated: the webview always runs inside the main window
+  #[cfg(feature = "legacy-webview-socket")]
+  #[clap(long, hide = true)]
   webview: bool,
-  url: Option<String>,
+  /// Archives or folders to install, or a link to open in the mod browser
+  targets: Vec<String>,
 }
 
 fn main() {
+  let _log_guard = app::logging::init();
+  let args = Args::parse();
+  #[cfg(feature = "legacy-webview-socket")]
+  if args.webview {
+    tracing::warn!("The standalone webview process is deprecated, opening the webview in-process");
+  }
+  if let Some(command) = args.command {
+    let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
+    std::process::exit(runtime.block_on(app::cli::run(command, args.install_dir)));
+  }
+  let targets: Vec<String> = args
+    .targets
+    .into_iter()
+    .map(|target| {
+      std::fs::canonicalize(&target).map_or(target, |path| path.to_string_lossy().into_owned())
+    })
+    .collect();
+
+  let listener = match app::single_instance::acquire(&targets) {
+    app::single_instance::Instance::Primary(listener) => listener,
+    app::single_instance::Instance::Secondary => return,
+  };
+
   std::fs::create_dir_all(PROJECT.cache_dir()).expect("Create cache dir");
   std::fs::create_dir_all(PROJECT.data_dir()).expect("Create cache dir");
 
-  let main_window = WindowDesc::new(app::App::ui_builder())
-    .title(concatcp!(
-      "MOSS | Mod Organizer for StarSector v",
-      env!("CARGO_PKG_VERSION")
-    ))
-    .window_size((1280., 1024.));
-
   let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
 
   // create the initial app state
   let initial_state = app::App::new(runtime.handle().clone());
 
+  let main_window = WindowDesc::new(app::App::ui_builder()).title(concatcp!(
+    "MOSS | Mod Organizer for StarSector v",
+    env!("CARGO_PKG_VERSION")
+  ));
+  let main_window = initial_state.restore_layout(main_window);
+
   let _guard = runtime.enter();
 
+  let launcher = AppLauncher::with_window(main_window);
+
+  let ext_ctx = launcher.get_external_handle();
+  if let Some(listener) = listener {
+    app::single_instance::listen(listener, ext_ctx.clone());
+  }
+  if !targets.is_empty() {
+    let _ = ext_ctx.submit_command(app::App::ACTIVATE, targets, Target::Auto);
+  }
+
   // start the application
-  AppLauncher::with_window(main_window)
-    .configure_env(|env, _| {
-      env.set(theme::BUTTON_BORDER_RADIUS, 2.);
-      env.set(theme::BUTTON_BORDER_WIDTH, 2.);
-      env.set(theme::BUTTON_LIGHT, env.get(theme::BUTTON_DARK));
-      env.set(
-        theme::BACKGROUND_DARK,
-        Color::from_hex_str("1f1a1b").unwrap(),
-      );
-      env.set(
-        theme::BACKGROUND_LIGHT,
-        Color::from_hex_str("292425").unwrap(),
-      );
-      env.set(
-        theme::WINDOW_BACKGROUND_COLOR,
-        env.get(theme::BACKGROUND_DARK),
-      );
-      env.set(theme::BORDER_DARK, Color::from_hex_str("48454f").unwrap());
-      env.set(theme::BORDER_LIGHT, Color::from_hex_str("c9c4cf").unwrap());
-      env.set(theme::BORDER_LIGHT, Color::from_hex_str("c9c4cf").unwrap());
-      env.set(app::util::GREEN_KEY, Color::from_hex_str("135200").unwrap());
-      env.set(app::util::RED_KEY, Color::from_hex_str("930006").unwrap());
-      env.set(
-        app::util::YELLOW_KEY,
-        Color::from_hex_str("574500").unwrap(),
-      );
-      env.set(
-        app::util::ON_GREEN_KEY,
-        Color::from_hex_str("adf68a").unwrap(),
-      );
-      env.set(
-        app::util::ON_RED_KEY,
-        Color::from_hex_str("ffdad4").unwrap(),
-      );
-      env.set(
-        app::util::ON_YELLOW_KEY,
-        Color::from_hex_str("ffe174").unwrap(),
-      );
-      env.set(app::util::BLUE_KEY, Color::from_hex_str("004d66").unwrap());
-      env.set(
-        app::util::ON_BLUE_KEY,
-        Color::from_hex_str("bbe9ff").unwrap(),
-      );
-      env.set(
-        app::util::ORANGE_KEY,
-        Color::from_hex_str("7f2c00").unwrap(),
-      );
-      env.set(
-        app::util::ON_ORANGE_KEY,
-        Color::from_hex_str("ffdbcc").unwrap(),
-      );
-    })
+  launcher
+    .configure_env(|env, data| data.save_original_env(env))
     .delegate(app::AppDelegate::default())
     .launch(initial_state)
     .expect("Failed to launch application");
@@ -271,6 +271,11 @@ impl<T> Split<T> {
   pub fn set_split_point_chosen(&mut self, split_point_chosen: f64) {
     self.split_point_chosen = split_point_chosen;
   }
+
+  /// Get the split's split point chosen.
+  pub fn split_point_chosen(&self) -> f64 {
+    self.split_point_chosen
+  }
 }
 
 impl<T: Data> Widget<T> for Split<T> {
@@ -348,12 +353,12 @@ impl<T: Data> Widget<T> for Split<T> {
     match self.split_axis {
       Axis::Horizontal => {
         if !bc.is_width_bounded() {
-          eprintln!("A Split widget was given an unbounded width to split.")
+          tracing::warn!("A Split widget was given an unbounded width to split.")
         }
       }
       Axis::Vertical => {
         if !bc.is_height_bounded() {
-          eprintln!("A Split widget was given an unbounded height to split.")
+          tracing::warn!("A Split widget was given an unbounded height to split.")
         }
       }
     }
@@ -4,11 +4,25 @@ use std::hash::Hash;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
-use std::sync::{Mutex, Weak};
-use std::{collections::VecDeque, io::Read, path::PathBuf, sync::Arc};
+use std::sync::{
+  atomic::{AtomicBool, AtomicU64, Ordering},
+  LazyLock, Mutex, Weak,
+};
+use std::time::Duration;
+use std::{
+  io::Read,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
 
+use chrono::{DateTime, Utc};
 use druid::lens::Then;
 use druid::widget::{ControllerHost, Either, LabelText, SizedBox};
+use druid::{
+  commands::CLOSE_WINDOW, BoxConstraints, Env, KbKey, LayoutCtx, LensExt as _, LifeCycle,
+  LifeCycleCtx, MouseButton, MouseButtons, MouseEvent, PaintCtx, Size, TimerToken, UpdateCtx, Vec2,
+  WidgetPod, WindowConfig, WindowId, WindowLevel, WindowSizePolicy,
+};
 use druid::{
   lens,
   text::{Attribute, AttributeSpans, RichText},
@@ -17,21 +31,19 @@ use druid::{
   Color, Command, Data, Event, EventCtx, ExtEventSink, FontWeight, Key, KeyOrValue, Lens, Point,
   RenderContext, Selector, Target, UnitPoint, Widget, WidgetExt,
 };
-use druid::{Env, MouseEvent, LensExt as _};
 use druid_widget_nursery::CommandCtx;
-use json_comments::strip_comments;
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::Deserialize;
 use tap::Tap;
 use tokio::select;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Semaphore};
+use tracing::{error, warn};
 use xxhash_rust::xxh3::Xxh3Builder;
 
-use crate::patch::click::Click;
-
 use super::controllers::{HoverController, OnEvent, OnNotif};
-use super::mod_entry::{GameVersion, ModEntry, ModVersionMeta};
+use super::mod_entry::{GameVersion, ModVersionMeta};
+use super::{net, updater::UpdateChannel, version_source};
 
 pub(crate) mod icons;
 
@@ -179,46 +191,27 @@ pub fn make_column_pair<T: Data>(
 pub const MASTER_VERSION_RECEIVED: Selector<(String, Result<ModVersionMeta, String>)> =
   Selector::new("remote_version_received");
 
+/// Most version checks run at the same time, see [`check_remote_version`].
+const MAX_CONCURRENT_CHECKS: usize = 8;
+
+static CHECK_PERMITS: LazyLock<Semaphore> = LazyLock::new(|| Semaphore::new(MAX_CONCURRENT_CHECKS));
+
+/// Checks for a newer version of a mod, waiting for one of a limited number of slots so checking
+/// every installed mod doesn't fire hundreds of requests at once.
+pub async fn check_remote_version(local: &ModVersionMeta) -> Result<ModVersionMeta, String> {
+  let _permit = CHECK_PERMITS.acquire().await;
+
+  version_source::check(local).await
+}
+
 pub async fn get_master_version(ext_sink: ExtEventSink, local: ModVersionMeta) {
-  let res = send_request(local.remote_url.clone()).await;
-
-  let payload = match res {
-    Err(err) => (local.id.clone(), Err(err)),
-    Ok(remote) => {
-      let mut stripped = String::new();
-      if strip_comments(remote.as_bytes()).read_to_string(&mut stripped).is_ok()
-        && let Ok(normalized) = handwritten_json::normalize(&stripped)
-        && let Ok(remote) = json5::from_str::<ModVersionMeta>(&normalized)
-      {
-        (
-          local.id.clone(),
-          Ok(remote)
-        )
-      } else {
-        (
-          local.id.clone(),
-          Err(format!("Parse error. Payload:\n{}", remote))
-        )
-      }
-    }
-  };
+  let payload = (local.id.clone(), check_remote_version(&local).await);
 
   if let Err(err) = ext_sink.submit_command(MASTER_VERSION_RECEIVED, payload, Target::Auto) {
-    eprintln!("Failed to submit remote version data {}", err)
+    error!("Failed to submit remote version data {}", err)
   };
 }
 
-async fn send_request(url: String) -> Result<String, String> {
-  reqwest::get(url)
-    .await
-    .map_err(|e| format!("{:?}", e))?
-    .error_for_status()
-    .map_err(|e| format!("{:?}", e))?
-    .text()
-    .await
-    .map_err(|e| format!("{:?}", e))
-}
-
 pub fn bold_text<T: Data>(
   text: &str,
   size: impl Into<KeyOrValue<f64>>,
@@ -249,25 +242,72 @@ pub fn h3<T: Data>(text: &str) -> impl Widget<T> {
   bold_text(text, 18., FontWeight::MEDIUM, theme::TEXT_COLOR)
 }
 
-pub const GET_INSTALLED_STARSECTOR: Selector<Result<GameVersion, LoadError>> =
+/// Where the installed game's version was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSource {
+  /// The game's own jar, which always matches what's installed.
+  Jar,
+  /// The launcher line in `starsector.log`, which is only as recent as the last launch.
+  Log,
+}
+
+pub const GET_INSTALLED_STARSECTOR: Selector<Result<(GameVersion, VersionSource), LoadError>> =
   Selector::new("util.starsector_version.get");
 
+/// Folders the game's own files may be in: where the official installers put them first, then
+/// the layouts of other installers - such as GOG's, which nests the game in a `game` folder - and
+/// finally the install folder itself, in case the core folder was picked instead.
+pub fn game_core_dirs(install_dir: &Path) -> Vec<PathBuf> {
+  #[cfg(target_os = "linux")]
+  let candidates = ["", "game", "data/noarch/game", "starsector"];
+  #[cfg(target_os = "windows")]
+  let candidates = ["starsector-core", "game/starsector-core", ""];
+  #[cfg(target_os = "macos")]
+  let candidates = ["Contents/Resources/Java", ""];
+
+  IntoIterator::into_iter(candidates)
+    .map(|dir| install_dir.join(dir))
+    .collect()
+}
+
+/// The first of `name` found in the game's [core folders](game_core_dirs).
+pub fn find_game_file(install_dir: &Path, name: &str) -> Option<PathBuf> {
+  game_core_dirs(install_dir)
+    .into_iter()
+    .map(|dir| dir.join(name))
+    .find(|path| path.is_file())
+}
+
+/// The install's mods folder. On case-sensitive filesystems a mods folder copied over from
+/// Windows, or made by hand, may be `Mods` rather than `mods`, so whichever casing exists is used,
+/// falling back to `mods` if there isn't one yet.
+pub fn mods_dir(install_dir: &Path) -> PathBuf {
+  let mods = install_dir.join("mods");
+  if mods.is_dir() {
+    return mods;
+  }
+
+  std::fs::read_dir(install_dir)
+    .ok()
+    .and_then(|entries| {
+      entries
+        .flatten()
+        .find(|entry| entry.file_name().eq_ignore_ascii_case("mods") && entry.path().is_dir())
+    })
+    .map_or(mods, |entry| entry.path())
+}
+
 pub async fn get_starsector_version(ext_ctx: ExtEventSink, install_dir: PathBuf) {
   use classfile_parser::class_parser;
   use regex::bytes::Regex;
   use tokio::{fs, task};
 
-  #[cfg(target_os = "linux")]
-  let obf_jar = install_dir.join("starfarer_obf.jar");
-  #[cfg(target_os = "windows")]
-  let obf_jar = install_dir.join("starsector-core/starfarer_obf.jar");
-  #[cfg(target_os = "macos")]
-  let obf_jar = install_dir.join("Contents/Resources/Java/starfarer_obf.jar");
-
-  let mut res = task::spawn_blocking(move || {
-    let mut zip = zip::ZipArchive::new(std::fs::File::open(obf_jar).unwrap()).unwrap();
+  let obf_jar = find_game_file(&install_dir, "starfarer_obf.jar");
 
-    // println!("{:?}", zip.file_names().collect::<Vec<&str>>());
+  let res = task::spawn_blocking(move || {
+    let obf_jar = obf_jar.ok_or(LoadError::NoSuchFile)?;
+    let file = std::fs::File::open(obf_jar).map_err(|_| LoadError::ReadError)?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|_| LoadError::FormatError)?;
 
     let mut version_class = zip.by_name("com/fs/starfarer/Version.class").map_err(|_| LoadError::NoSuchFile)?;
 
@@ -292,32 +332,45 @@ pub async fn get_starsector_version(ext_ctx: ExtEventSink, install_dir: PathBuf)
       })
   }).await
   .map_err(|_| LoadError::ReadError)
-  .flatten();
-
-  if res.is_err() {
-    lazy_static! {
-      static ref RE: Regex = Regex::new(r"Starting Starsector (.*) launcher").unwrap();
-    }
-    res = fs::read(install_dir.join("starsector-core").join("starsector.log"))
-      .await
-      .map_err(|_| LoadError::ReadError)
+  .flatten()
+  .map(|text| (text, VersionSource::Jar));
+
+  let res = match res {
+    Ok(res) => Ok(res),
+    Err(err) => {
+      warn!(
+        "Couldn't read the game version from starfarer_obf.jar: {:?}",
+        err
+      );
+      lazy_static! {
+        static ref RE: Regex = Regex::new(r"Starting Starsector (.*) launcher").unwrap();
+      }
+      let log = find_game_file(&install_dir, "starsector.log").ok_or(LoadError::NoSuchFile);
+      match log {
+        Ok(log) => fs::read(log).await.map_err(|_| LoadError::ReadError),
+        Err(err) => Err(err),
+      }
       .and_then(|file| {
-        RE.captures(&file)
+        // the log is appended to, so the last launch is the most recent version
+        RE.captures_iter(&file)
+          .last()
           .and_then(|captures| captures.get(1))
           .ok_or(LoadError::FormatError)
           .and_then(|m| {
             String::from_utf8(m.as_bytes().to_vec()).map_err(|_| LoadError::FormatError)
           })
       })
+      .map(|text| (text, VersionSource::Log))
+    }
   };
 
-  let parsed = res.map(|text| parse_game_version(&text));
+  let parsed = res.map(|(text, source)| (parse_game_version(&text), source));
 
   if ext_ctx
     .submit_command(GET_INSTALLED_STARSECTOR, parsed, Target::Auto)
     .is_err()
   {
-    eprintln!("Failed to submit starsector version back to main thread")
+    error!("Failed to submit starsector version back to main thread")
   };
 }
 
@@ -329,7 +382,7 @@ pub async fn get_starsector_version(ext_ctx: ExtEventSink, install_dir: PathBuf)
 * - If there are three components it is either the major, minor and patch OR major, minor and RC OR minor, patch and RC
 * - If there are four components then the first components MUST be 0 and MUST be the major component, and the following components
      are the minor, patch and RC components
- */
+*/
 pub fn parse_game_version(
   text: &str,
 ) -> (
@@ -406,7 +459,7 @@ pub fn parse_game_version(
       )
     }
     _ => {
-      dbg!("Failed to normalise mod's quoted game version");
+      warn!("Failed to normalise mod's quoted game version");
       (None, None, None, None)
     }
   }
@@ -493,40 +546,115 @@ pub struct Release {
   pub name: String,
   pub tag_name: String,
   pub assets: Vec<Asset>,
+  /// Release notes, as markdown.
+  #[serde(default)]
+  pub body: Option<String>,
+  #[serde(default)]
+  pub prerelease: bool,
+  #[serde(default)]
+  pub draft: bool,
 }
 
 #[derive(Deserialize, Clone)]
 pub struct Asset {
   pub name: String,
   pub browser_download_url: String,
+  #[serde(default)]
+  pub size: Option<u64>,
+  #[serde(default)]
+  pub digest: Option<String>,
 }
 
-pub async fn get_latest_manager() -> Result<Release, String> {
-  let client = reqwest::Client::builder()
+/// The newest MOSS release on `channel`.
+pub async fn get_latest_manager(channel: UpdateChannel) -> Result<Release, String> {
+  let client = net::api_client_builder()
     .user_agent("StarsectorModManager")
     .build()
     .map_err(|e| e.to_string())?;
 
-  let mut res = client
+  let res = client
     .get("https://api.github.com/repos/atlanticaccent/starsector-mod-manager-rust/releases")
     .send()
     .await
     .map_err(|e| e.to_string())?
-    .json::<VecDeque<Release>>()
+    .json::<Vec<Release>>()
     .await
     .map_err(|e| e.to_string())?;
 
-  if let Some(release) = res.pop_front() {
-    Ok(release)
-  } else {
-    Err(String::from("Could not find any releases."))
-  }
+  // releases are listed newest first
+  res
+    .into_iter()
+    .find(|release| channel.accepts(release))
+    .ok_or_else(|| String::from("Could not find any releases."))
 }
 
 pub fn default_true() -> bool {
   true
 }
 
+/// Total size in bytes of every file under `path`, skipping anything that can't be read.
+pub fn dir_size(path: &std::path::Path) -> u64 {
+  std::fs::read_dir(path)
+    .map(|entries| {
+      entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+          let file_type = entry.file_type().ok()?;
+          if file_type.is_dir() {
+            Some(dir_size(&entry.path()))
+          } else {
+            entry.metadata().ok().map(|meta| meta.len())
+          }
+        })
+        .sum()
+    })
+    .unwrap_or(0)
+}
+
+/// Formats a size in bytes with binary units, eg: `1.5 MB`.
+pub fn format_size(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+  if bytes < 1024 {
+    return format!("{} B", bytes);
+  }
+
+  let mut size = bytes as f64 / 1024.;
+  let mut unit = UNITS[0];
+  for next in &UNITS[1..] {
+    if size < 1024. {
+      break;
+    }
+    size /= 1024.;
+    unit = next;
+  }
+
+  format!("{:.1} {}", size, unit)
+}
+
+/// How long ago `date` was, in the largest whole unit, eg: `3 days ago`.
+pub fn time_since(date: DateTime<Utc>) -> String {
+  let elapsed = Utc::now().signed_duration_since(date);
+  let (count, unit) = if elapsed.num_days() >= 365 {
+    (elapsed.num_days() / 365, "year")
+  } else if elapsed.num_days() >= 30 {
+    (elapsed.num_days() / 30, "month")
+  } else if elapsed.num_days() >= 1 {
+    (elapsed.num_days(), "day")
+  } else if elapsed.num_hours() >= 1 {
+    (elapsed.num_hours(), "hour")
+  } else {
+    return String::from("Just now");
+  };
+
+  format!(
+    "{} {}{} ago",
+    count,
+    unit,
+    if count == 1 { "" } else { "s" }
+  )
+}
+
 #[derive(Clone, Data, Lens)]
 pub struct IndyToggleState {
   state: bool,
@@ -694,11 +822,19 @@ pub trait WidgetExtEx<T: Data>: Widget<T> + Sized + 'static {
     self.controller(OnNotif::new(selector, handler))
   }
 
+  /// Like [`on_click`](WidgetExt::on_click), but the widget can also be reached with Tab and
+  /// activated with Enter or Space. Keyboard activation is passed a mouse event at the middle of
+  /// the widget, holding the modifier keys pressed at the time.
   fn on_click2(
     self,
     f: impl Fn(&mut EventCtx, &MouseEvent, &mut T, &Env) + 'static,
-  ) -> ControllerHost<Self, Click<T>> {
-    ControllerHost::new(self, Click::new(f))
+  ) -> Focusable<T> {
+    Focusable::new(self, f)
+  }
+
+  /// A keyboard reachable [`on_click`](WidgetExt::on_click), see [`on_click2`](Self::on_click2).
+  fn on_activate(self, f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Focusable<T> {
+    Focusable::new(self, move |ctx, _, data, env| f(ctx, data, env))
   }
 
   fn on_event(
@@ -730,6 +866,164 @@ impl Button2 {
   }
 }
 
+/// A clickable widget that takes part in Tab focus traversal, activated by Enter or Space while
+/// focused and drawing a ring around itself so it's clear where focus is.
+///
+/// Druid has no accessibility tree for a screen reader to read, so a [label](Self::labelled) is
+/// shown just below the widget while it has focus instead, which matters for icon-only buttons.
+pub struct Focusable<T> {
+  child: WidgetPod<T, Box<dyn Widget<T>>>,
+  action: Box<dyn Fn(&mut EventCtx, &MouseEvent, &mut T, &Env)>,
+  label: Option<String>,
+  label_window: Option<WindowId>,
+  label_timer: TimerToken,
+}
+
+impl<T: Data> Focusable<T> {
+  pub fn new(
+    child: impl Widget<T> + 'static,
+    action: impl Fn(&mut EventCtx, &MouseEvent, &mut T, &Env) + 'static,
+  ) -> Self {
+    Self {
+      child: WidgetPod::new(child.boxed()),
+      action: Box::new(action),
+      label: None,
+      label_window: None,
+      label_timer: TimerToken::INVALID,
+    }
+  }
+
+  /// Describes what the widget does, for when that isn't clear from what it shows.
+  pub fn labelled(mut self, label: impl Into<String>) -> Self {
+    self.label = Some(label.into());
+    self
+  }
+
+  fn show_label(&mut self, ctx: &mut EventCtx, env: &Env) {
+    let Some(label) = self.label.clone() else {
+      return;
+    };
+    let below = ctx.window_origin() + Vec2::new(0., ctx.size().height + 4.);
+    self.label_window = Some(
+      ctx.new_sub_window(
+        WindowConfig::default()
+          .show_titlebar(false)
+          .window_size_policy(WindowSizePolicy::Content)
+          .set_level(WindowLevel::Tooltip(ctx.window().clone()))
+          .set_position(below),
+        Label::new(label)
+          .with_text_size(14.)
+          .padding((6., 3.))
+          .background(theme::BACKGROUND_LIGHT)
+          .border(theme::BORDER_DARK, 1.),
+        (),
+        env.clone(),
+      ),
+    );
+  }
+}
+
+fn activates(key: &KbKey) -> bool {
+  match key {
+    KbKey::Enter => true,
+    KbKey::Character(character) => character == " ",
+    _ => false,
+  }
+}
+
+impl<T: Data> Widget<T> for Focusable<T> {
+  fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+    match event {
+      Event::MouseDown(mouse) if mouse.button == MouseButton::Left && !ctx.is_disabled() => {
+        ctx.set_active(true);
+        ctx.request_paint();
+      }
+      Event::MouseUp(mouse) if ctx.is_active() && mouse.button == MouseButton::Left => {
+        ctx.set_active(false);
+        if ctx.is_hot() && !ctx.is_disabled() {
+          (self.action)(ctx, mouse, data, env);
+        }
+        ctx.request_paint();
+      }
+      Event::KeyDown(key) if ctx.has_focus() && key.key == KbKey::Tab => {
+        if key.mods.shift() {
+          ctx.focus_prev()
+        } else {
+          ctx.focus_next()
+        }
+        ctx.set_handled();
+      }
+      Event::KeyDown(key) if ctx.has_focus() && !ctx.is_disabled() && activates(&key.key) => {
+        let centre = ctx.size().to_rect().center();
+        let mouse = MouseEvent {
+          pos: centre,
+          window_pos: ctx.window_origin() + centre.to_vec2(),
+          buttons: MouseButtons::new(),
+          mods: key.mods,
+          count: 1,
+          focus: false,
+          button: MouseButton::Left,
+          wheel_delta: Vec2::ZERO,
+        };
+        (self.action)(ctx, &mouse, data, env);
+        ctx.set_handled();
+      }
+      // sub windows can only be opened while handling an event
+      Event::Timer(token) if *token == self.label_timer => {
+        if ctx.has_focus() && self.label_window.is_none() {
+          self.show_label(ctx, env)
+        }
+        ctx.set_handled();
+      }
+      _ => {}
+    }
+
+    self.child.event(ctx, event, data, env)
+  }
+
+  fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+    match event {
+      LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+      LifeCycle::FocusChanged(focused) => {
+        if *focused && self.label.is_some() {
+          self.label_timer = ctx.request_timer(Duration::ZERO);
+        } else if let Some(window) = self.label_window.take() {
+          ctx.submit_command(CLOSE_WINDOW.to(window))
+        }
+        ctx.request_paint()
+      }
+      LifeCycle::HotChanged(_) => ctx.request_paint(),
+      _ => {}
+    }
+
+    self.child.lifecycle(ctx, event, data, env)
+  }
+
+  fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+    self.child.update(ctx, data, env)
+  }
+
+  fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+    let size = self.child.layout(ctx, bc, data, env);
+    self.child.set_origin(ctx, Point::ORIGIN);
+    ctx.set_baseline_offset(self.child.baseline_offset());
+
+    size
+  }
+
+  fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+    self.child.paint(ctx, data, env);
+    if ctx.has_focus() {
+      let ring = ctx
+        .size()
+        .to_rect()
+        .inset(-1.)
+        .to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+      ctx.stroke(ring, &env.get(theme::PRIMARY_LIGHT), 2.);
+    }
+  }
+}
+
 /// A bad trait
 pub trait Collection<T, U> {
   fn insert(&mut self, item: T);
@@ -757,8 +1051,8 @@ impl<A: Clone + Hash + Eq, B, C> Collection<(A, B, C), Vec<(A, B, C)>> for HashM
   }
 }
 
-impl Collection<Arc<ModEntry>, Vec<Arc<ModEntry>>> for Vec<Arc<ModEntry>> {
-  fn insert(&mut self, item: Arc<ModEntry>) {
+impl<T> Collection<T, Vec<T>> for Vec<T> {
+  fn insert(&mut self, item: T) {
     self.push(item);
   }
 
@@ -766,61 +1060,139 @@ impl Collection<Arc<ModEntry>, Vec<Arc<ModEntry>>> for Vec<Arc<ModEntry>> {
     self.len()
   }
 
-  fn drain(&mut self) -> Vec<Arc<ModEntry>> {
+  fn drain(&mut self) -> Vec<T> {
     self.split_off(0)
   }
 }
 
+/// Counters for a [`LoadBalancer`], shown alongside the logs in settings.
+///
+/// Items replaced by a newer item with the same key before being flushed count as queued but
+/// never as flushed or dropped.
+pub struct BalancerStats {
+  name: &'static str,
+  registered: AtomicBool,
+  queued: AtomicU64,
+  flushed: AtomicU64,
+  dropped: AtomicU64,
+}
+
+impl BalancerStats {
+  const fn new(name: &'static str) -> Self {
+    Self {
+      name,
+      registered: AtomicBool::new(false),
+      queued: AtomicU64::new(0),
+      flushed: AtomicU64::new(0),
+      dropped: AtomicU64::new(0),
+    }
+  }
+}
+
+static BALANCER_STATS: Mutex<Vec<&'static BalancerStats>> = Mutex::new(Vec::new());
+
+/// One line per load balancer that has been used this session, eg:
+/// `mod list: 120 queued, 120 flushed, 0 dropped`.
+pub fn balancer_report() -> String {
+  BALANCER_STATS
+    .lock()
+    .map(|stats| {
+      stats
+        .iter()
+        .map(|stats| {
+          format!(
+            "{}: {} queued, {} flushed, {} dropped",
+            stats.name,
+            stats.queued.load(Ordering::Relaxed),
+            stats.flushed.load(Ordering::Relaxed),
+            stats.dropped.load(Ordering::Relaxed),
+          )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+    })
+    .unwrap_or_default()
+}
+
+/// Batches items sent from background tasks into a single command every `interval`, so the UI
+/// isn't flooded with one command per item.
+///
+/// At most `capacity` items are held per batch. Once a batch is full and the channel behind it
+/// has filled up too, senders are pushed back on until the next flush.
 pub struct LoadBalancer<T: Any + Send, DRAIN: Any + Send, SINK: Default + Collection<T, DRAIN>> {
-  tx: std::sync::LazyLock<Mutex<Weak<mpsc::UnboundedSender<T>>>>,
+  tx: std::sync::LazyLock<Mutex<Weak<BalancerSender<T>>>>,
   sink: PhantomData<SINK>,
   selector: Selector<DRAIN>,
+  interval: Duration,
+  capacity: usize,
+  stats: BalancerStats,
 }
 
 impl<T: Any + Send, U: Any + Send, SINK: Default + Collection<T, U> + Send>
   LoadBalancer<T, U, SINK>
 {
-  pub const fn new(selector: Selector<U>) -> Self {
+  pub const fn new(name: &'static str, selector: Selector<U>) -> Self {
     Self {
       tx: std::sync::LazyLock::new(Default::default),
       sink: PhantomData,
       selector,
+      interval: Duration::from_millis(50),
+      capacity: 1024,
+      stats: BalancerStats::new(name),
     }
   }
 
-  pub fn sender(&self, ext_ctx: ExtEventSink) -> Arc<mpsc::UnboundedSender<T>> {
+  pub const fn with_interval(mut self, interval: Duration) -> Self {
+    self.interval = interval;
+    self
+  }
+
+  pub const fn with_capacity(mut self, capacity: usize) -> Self {
+    self.capacity = capacity;
+    self
+  }
+
+  pub fn sender(&'static self, ext_ctx: ExtEventSink) -> Arc<BalancerSender<T>> {
     let mut sender = self.tx.lock().unwrap();
     if let Some(tx) = sender.upgrade() {
       tx
     } else {
-      let (tx, mut rx) = mpsc::unbounded_channel::<T>();
-      let tx = Arc::new(tx);
+      if !self.stats.registered.swap(true, Ordering::Relaxed) {
+        if let Ok(mut stats) = BALANCER_STATS.lock() {
+          stats.push(&self.stats)
+        }
+      }
+
+      let (tx, mut rx) = mpsc::channel::<T>(self.capacity);
+      let tx = Arc::new(BalancerSender {
+        tx,
+        stats: &self.stats,
+      });
       let selector = self.selector;
+      let interval = self.interval;
+      let capacity = self.capacity;
+      let stats = &self.stats;
       tokio::task::spawn(async move {
-        let sleep = tokio::time::sleep(std::time::Duration::from_millis(50));
+        let sleep = tokio::time::sleep(interval);
         tokio::pin!(sleep);
 
         let mut sink = SINK::default();
         loop {
           select! {
-            message = rx.recv() => {
+            message = rx.recv(), if sink.len() < capacity => {
               match message {
                 Some(message) => {
                   sink.insert(message);
                 },
                 None => {
-                  if !sink.is_empty() {
-                    let vals = sink.drain();
-                    let _ = ext_ctx.submit_command(selector, vals, Target::Auto);
-                  }
+                  Self::flush(&ext_ctx, selector, stats, &mut sink);
                   break
                 },
               }
             },
             _ = &mut sleep => {
-              let vals = sink.drain();
-              let _ = ext_ctx.submit_command(selector, vals, Target::Auto);
-              sleep.as_mut().reset(tokio::time::Instant::now() + std::time::Duration::from_millis(50));
+              Self::flush(&ext_ctx, selector, stats, &mut sink);
+              sleep.as_mut().reset(tokio::time::Instant::now() + interval);
             }
           }
         }
@@ -831,6 +1203,54 @@ impl<T: Any + Send, U: Any + Send, SINK: Default + Collection<T, U> + Send>
       tx
     }
   }
+
+  fn flush(ext_ctx: &ExtEventSink, selector: Selector<U>, stats: &BalancerStats, sink: &mut SINK) {
+    if sink.is_empty() {
+      return;
+    }
+
+    let len = sink.len() as u64;
+    if let Err(err) = ext_ctx.submit_command(selector, sink.drain(), Target::Auto) {
+      stats.dropped.fetch_add(len, Ordering::Relaxed);
+      warn!(
+        "{} load balancer dropped {} items: {:?}",
+        stats.name, len, err
+      );
+    } else {
+      stats.flushed.fetch_add(len, Ordering::Relaxed);
+    }
+  }
+}
+
+pub struct BalancerSender<T> {
+  tx: mpsc::Sender<T>,
+  stats: &'static BalancerStats,
+}
+
+impl<T> BalancerSender<T> {
+  /// Queues `item`, handing it back if the balancer is full because the UI can't keep up.
+  pub fn try_send(&self, item: T) -> Result<(), mpsc::error::TrySendError<T>> {
+    self.tx.try_send(item)?;
+    self.stats.queued.fetch_add(1, Ordering::Relaxed);
+
+    Ok(())
+  }
+
+  /// Queues `item`, blocking the current thread until the balancer has room for it.
+  pub fn send_blocking(&self, mut item: T) -> Result<(), mpsc::error::SendError<T>> {
+    loop {
+      match self.try_send(item) {
+        Ok(()) => return Ok(()),
+        Err(mpsc::error::TrySendError::Full(returned)) => {
+          item = returned;
+          std::thread::sleep(Duration::from_millis(5));
+        }
+        Err(mpsc::error::TrySendError::Closed(returned)) => {
+          return Err(mpsc::error::SendError(returned))
+        }
+      }
+    }
+  }
 }
 
 #[allow(non_camel_case_types)]
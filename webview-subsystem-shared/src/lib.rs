@@ -1,9 +1,17 @@
-use std::{path::PathBuf, any::Any};
+use std::{
+  any::Any,
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::Duration,
+};
 
 use directories::ProjectDirs;
 use druid::{Selector, Target, ExtEventError, ExtEventSink};
 use lazy_static::lazy_static;
-
+#[cfg(feature = "legacy-socket")]
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone)]
@@ -12,6 +20,9 @@ pub enum InstallType {
   Path(PathBuf),
 }
 
+/// Messages exchanged with the standalone webview process.
+#[cfg(feature = "legacy-socket")]
+#[deprecated = "The webview runs in-process as a child of the main window"]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
 pub enum WebviewMessage {
   Navigation(String),
@@ -30,7 +41,13 @@ pub enum UserEvent {
   Download(String),
   CancelDownload,
   BlobReceived(String),
+  /// The size in bytes of the blob about to be sent
+  BlobSize(u64),
   BlobChunk(Option<String>),
+  /// The page's scripts failed to read the blob
+  BlobError(String),
+  /// A message from the page's scripts that couldn't be understood
+  IpcError(String),
 }
 
 lazy_static! {
@@ -42,6 +59,10 @@ pub const FRACTAL_INDEX: &str = "https://fractalsoftworks.com/forum/index.php?to
 pub const FRACTAL_MODS_FORUM: &str = "https://fractalsoftworks.com/forum/index.php?board=8.0";
 pub const FRACTAL_MODDING_SUBFORUM: &str = "https://fractalsoftworks.com/forum/index.php?board=3.0";
 
+/// URL fragment that has the webview scroll to the first download link in a forum thread, see
+/// `init.js`
+pub const SCROLL_TO_DOWNLOAD: &str = "moss-download";
+
 pub const WEBVIEW_EVENT: Selector<UserEvent> = Selector::new("webview.event");
 pub const WEBVIEW_INSTALL: Selector<InstallType> = Selector::new("webview.install");
 
@@ -64,3 +85,101 @@ impl ExtEventSinkExt for ExtEventSink {
       self.submit_command(selector, payload, Target::Global)
     }
 }
+
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+/// Marks the app as shutting down - every [`EventSender`] stops delivering from this point on.
+pub fn shutdown() {
+  SHUTDOWN.store(true, Ordering::SeqCst)
+}
+
+/// Wraps an [`ExtEventSink`] for use in background tasks.
+///
+/// Failed submissions are logged rather than discarded, and once a submission has failed or the
+/// app has begun shutting down the sender is considered closed - tasks should check
+/// [`EventSender::is_closed`] and stop instead of posting commands nobody will receive.
+#[derive(Clone)]
+pub struct EventSender {
+  sink: ExtEventSink,
+  closed: Arc<AtomicBool>,
+}
+
+impl EventSender {
+  const RETRIES: u32 = 3;
+
+  pub fn new(sink: ExtEventSink) -> Self {
+    Self {
+      sink,
+      closed: Arc::new(AtomicBool::new(false)),
+    }
+  }
+
+  pub fn sink(&self) -> &ExtEventSink {
+    &self.sink
+  }
+
+  pub fn is_closed(&self) -> bool {
+    SHUTDOWN.load(Ordering::SeqCst) || self.closed.load(Ordering::SeqCst)
+  }
+
+  /// Submits a command, returning whether it was delivered.
+  pub fn submit<T: Any + Send>(
+    &self,
+    selector: Selector<T>,
+    payload: impl Into<Box<T>>,
+    target: impl Into<Target>,
+  ) -> bool {
+    if self.is_closed() {
+      return false;
+    }
+
+    match self.sink.submit_command(selector, payload, target) {
+      Ok(()) => true,
+      Err(err) => {
+        tracing::error!("Failed to submit {:?}: {}", selector, err);
+        self.closed.store(true, Ordering::SeqCst);
+        false
+      }
+    }
+  }
+
+  pub fn submit_global<T: Any + Send>(
+    &self,
+    selector: Selector<T>,
+    payload: impl Into<Box<T>>,
+  ) -> bool {
+    self.submit(selector, payload, Target::Global)
+  }
+
+  /// Like [`EventSender::submit`], but retries a few times before giving up. Only for commands
+  /// that are safe to deliver late.
+  pub async fn submit_with_retry<T: Any + Send + Clone>(
+    &self,
+    selector: Selector<T>,
+    payload: T,
+    target: impl Into<Target>,
+  ) -> bool {
+    let target = target.into();
+    for attempt in 0..Self::RETRIES {
+      if self.is_closed() {
+        return false;
+      }
+      match self.sink.submit_command(selector, payload.clone(), target) {
+        Ok(()) => return true,
+        Err(err) => {
+          tracing::warn!(
+            "Failed to submit {:?} (attempt {}/{}): {}",
+            selector,
+            attempt + 1,
+            Self::RETRIES,
+            err
+          );
+          tokio::time::sleep(Duration::from_millis(50 << attempt)).await;
+        }
+      }
+    }
+    self.closed.store(true, Ordering::SeqCst);
+
+    false
+  }
+}
@@ -118,6 +118,19 @@ document.addEventListener('DOMContentLoaded', _ => {
   `;
 });
 
+// Threads opened from the mod repo's Install button scroll to the first download link in the post
+document.addEventListener('DOMContentLoaded', _ => {
+  if (location.hash !== '#moss-download') return;
+
+  const download = /download|releases|mediafire|drive\.google|dropbox|mega\.nz|\.(zip|7z|rar)(\?|$)/i;
+  const links = [...document.querySelectorAll('.post a[href]'), ...document.querySelectorAll('a[href]')];
+  const link = links.find(a => download.test(a.href));
+  if (link) {
+    link.scrollIntoView({ block: 'center' });
+    link.style.outline = '3px solid #ff9800';
+  }
+});
+
 // Adds an URL.getFromObjectURL( <blob:// URI> ) method
 // returns the original object (<Blob> or <MediaSource>) the URI points to or null
 (() => {
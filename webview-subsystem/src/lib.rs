@@ -1,64 +1,85 @@
+use std::path::PathBuf;
+
 use base64::decode;
 use druid::{ExtEventSink, WindowHandle};
 use url::Url;
-use webview_shared::{ExtEventSinkExt, UserEvent, WEBVIEW_EVENT, WEBVIEW_OFFSET, FRACTAL_INDEX};
+use webview_shared::{EventSender, UserEvent, FRACTAL_INDEX, WEBVIEW_EVENT, WEBVIEW_OFFSET};
 use wry::{WebContext, WebView, WebViewBuilder};
 
+/// Where the webview sits in `window`: filling it below the nav bar, which grows with
+/// `ui_scale`.
+///
+/// Druid measures the window in display points, which GTK and WebKit on macOS also position child
+/// views in, but WebView2 takes physical pixels, so on Windows the window's current scale - which
+/// changes as it moves between monitors - is applied as well.
+pub fn webview_bounds(window: &WindowHandle, ui_scale: f64) -> wry::Rect {
+  let size = window.get_size();
+  let offset = f64::from(WEBVIEW_OFFSET) * ui_scale;
+  let rect = druid::Rect::new(0., offset, size.width, size.height.max(offset));
+  #[cfg(target_os = "windows")]
+  let rect = window.get_scale().map_or(rect, |scale| scale.to_px(&rect));
+
+  wry::Rect {
+    x: rect.x0 as i32,
+    y: rect.y0 as i32,
+    width: rect.width() as u32,
+    height: rect.height() as u32,
+  }
+}
+
 pub fn init_webview(
   url: Option<String>,
   window: &WindowHandle,
   ext_ctx: ExtEventSink,
+  data_directory: PathBuf,
+  ui_scale: f64,
 ) -> wry::Result<WebView> {
-  let mut webcontext = WebContext::default();
+  let mut webcontext = WebContext::new(Some(data_directory));
   webcontext.set_allows_automation(true);
 
   let init_script = include_str!("init.js");
+  let events = EventSender::new(ext_ctx);
 
   let webview = WebViewBuilder::new_as_child(window)
-    .with_bounds(wry::Rect {
-      x: 0,
-      y: WEBVIEW_OFFSET.into(),
-      width: window.get_size().width as u32,
-      height: (window.get_size().height as u32).saturating_sub(WEBVIEW_OFFSET as u32),
-    })
+    .with_bounds(webview_bounds(window, ui_scale))
     .with_url(url.as_deref().unwrap_or(FRACTAL_INDEX))?
     .with_initialization_script(init_script)
+    .with_web_context(&mut webcontext)
     .with_ipc_handler({
-      let ext_ctx = ext_ctx.clone();
-      move |string| match dbg!(string.as_str()) {
+      let events = events.clone();
+      move |string| match string.as_str() {
         _ if string.starts_with("data:") => {
-          let _ = ext_ctx.submit_command_global(WEBVIEW_EVENT, UserEvent::BlobChunk(Some(string)));
+          events.submit_global(WEBVIEW_EVENT, UserEvent::BlobChunk(Some(string)));
         }
         "#EOF" => {
-          let _ = ext_ctx.submit_command_global(WEBVIEW_EVENT, UserEvent::BlobChunk(None));
+          events.submit_global(WEBVIEW_EVENT, UserEvent::BlobChunk(None));
+        }
+        _ if string.starts_with("#SIZE:") => {
+          let event = match string["#SIZE:".len()..].parse() {
+            Ok(size) => UserEvent::BlobSize(size),
+            Err(err) => UserEvent::IpcError(format!("{} in {:?}", err, string)),
+          };
+          events.submit_global(WEBVIEW_EVENT, event);
+        }
+        _ if string.starts_with("#ERROR:") => {
+          events.submit_global(
+            WEBVIEW_EVENT,
+            UserEvent::BlobError(string["#ERROR:".len()..].to_string()),
+          );
         }
         _ if string.starts_with("confirm_download") => {
-          let mut parts = string.split(',');
-          let confirm = parts
-            .next()
-            .expect("split ipc")
-            .split(':')
-            .nth(1)
-            .expect("split ipc");
-          if confirm == "true" {
-            let base = parts
-              .next()
-              .expect("split ipc")
-              .split(':')
-              .nth(1)
-              .expect("split ipc");
-            let decoded = decode(base).expect("decode uri");
-            let uri = String::from_utf8(decoded).expect("decode");
-            let _ = ext_ctx.submit_command_global(WEBVIEW_EVENT, UserEvent::Download(uri));
-          } else {
-            let _ = ext_ctx.submit_command_global(WEBVIEW_EVENT, UserEvent::CancelDownload);
-          }
+          let event = match parse_confirm_download(&string) {
+            Ok(Some(uri)) => UserEvent::Download(uri),
+            Ok(None) => UserEvent::CancelDownload,
+            Err(err) => UserEvent::IpcError(format!("{} in {:?}", err, string)),
+          };
+          events.submit_global(WEBVIEW_EVENT, event);
         }
         _ => {}
       }
     })
     .with_navigation_handler({
-      let ext_ctx = ext_ctx.clone();
+      let events = events.clone();
       move |uri: String| {
         if &uri == "about:blank" {
           return false;
@@ -68,38 +89,31 @@ pub fn init_webview(
           if url.host_str() == Some("drive.google.com")
             && url.query().map_or(false, |q| q.contains("export=download"))
           {
-            let _ = ext_ctx
-              .submit_command_global(WEBVIEW_EVENT, UserEvent::AskDownload(uri + "&confirm=t"));
+            events.submit_global(WEBVIEW_EVENT, UserEvent::AskDownload(uri + "&confirm=t"));
             return false;
           }
         }
 
-        ext_ctx
-          .submit_command_global(WEBVIEW_EVENT, UserEvent::Navigation(uri))
-          .is_ok()
+        events.submit_global(WEBVIEW_EVENT, UserEvent::Navigation(uri))
       }
     })
     .with_new_window_req_handler({
-      let ext_ctx = ext_ctx.clone();
+      let events = events.clone();
       move |uri: String| {
-        ext_ctx
-          .submit_command_global(WEBVIEW_EVENT, UserEvent::NewWindow(uri))
-          .expect("Send event");
+        events.submit_global(WEBVIEW_EVENT, UserEvent::NewWindow(uri));
 
         false
       }
     })
     .with_download_started_handler({
-      let ext_ctx = ext_ctx;
+      let events = events;
       move |uri, _| {
         if uri.starts_with("blob:https://mega.nz") {
-          let _ = ext_ctx.submit_command_global(WEBVIEW_EVENT, UserEvent::BlobReceived(uri));
+          events.submit_global(WEBVIEW_EVENT, UserEvent::BlobReceived(uri));
           return false;
         }
 
-        ext_ctx
-          .submit_command_global(WEBVIEW_EVENT, UserEvent::AskDownload(uri))
-          .expect("Send event");
+        events.submit_global(WEBVIEW_EVENT, UserEvent::AskDownload(uri));
 
         false
       }
@@ -111,3 +125,23 @@ pub fn init_webview(
 
   Ok(webview)
 }
+
+/// Parses a `confirm_download:<bool>,uri:<base64>` message, returning the URI to download if the
+/// user confirmed.
+fn parse_confirm_download(message: &str) -> Result<Option<String>, String> {
+  let mut parts = message
+    .split(',')
+    .map(|part| part.split_once(':').map(|(_, value)| value));
+
+  let confirm = parts.next().flatten().ok_or("missing confirmation")?;
+  if confirm != "true" {
+    return Ok(None);
+  }
+
+  let base = parts.next().flatten().ok_or("missing uri")?;
+  let decoded = decode(base).map_err(|err| format!("invalid uri encoding: {}", err))?;
+
+  String::from_utf8(decoded)
+    .map(Some)
+    .map_err(|err| format!("invalid uri: {}", err))
+}